@@ -0,0 +1,262 @@
+//! Photoshop `.abr` brush import (sampled tips only).
+//!
+//! Supports the legacy v1/v2 layout (a flat list of brush records) and the
+//! v6/v7/v10 layout (an `8BIM` `samp` section). Computed (round/elliptic)
+//! brushes and all brush settings are ignored — only the sampled tip bitmaps
+//! are extracted, as grayscale masks with 255 = full paint.
+
+use std::path::Path;
+
+use crate::psd::Reader;
+
+/// Tips larger than this (longest edge) are downscaled on import so picker
+/// icons and cached masks stay reasonably sized.
+pub const ABR_MAX_TIP_EDGE: u32 = 256;
+
+/// Guard against absurd dimensions in corrupt files.
+const MAX_SAMPLE_EDGE: u32 = 16384;
+
+/// A sampled brush tip extracted from an `.abr` file.
+#[derive(Clone, Debug)]
+pub struct AbrTip {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    /// Row-major 8-bit mask (255 = full paint).
+    pub mask: Vec<u8>,
+}
+
+impl AbrTip {
+    /// Encode the mask as a grayscale PNG, square-padded and centered, and
+    /// downscaled to at most [`ABR_MAX_TIP_EDGE`] — the format
+    /// `Assets::load_brush_tip` and the custom tip settings expect.
+    pub fn to_png(&self) -> Result<Vec<u8>, String> {
+        let edge = self.width.max(self.height);
+        let mut square = image::GrayImage::new(edge, edge);
+        let ox = (edge - self.width) / 2;
+        let oy = (edge - self.height) / 2;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let v = self.mask[(y * self.width + x) as usize];
+                square.put_pixel(ox + x, oy + y, image::Luma([v]));
+            }
+        }
+        if edge > ABR_MAX_TIP_EDGE {
+            square = image::imageops::resize(
+                &square,
+                ABR_MAX_TIP_EDGE,
+                ABR_MAX_TIP_EDGE,
+                image::imageops::FilterType::Triangle,
+            );
+        }
+        let mut out = Vec::new();
+        image::DynamicImage::ImageLuma8(square)
+            .write_to(&mut std::io::Cursor::new(&mut out), image::ImageFormat::Png)
+            .map_err(|e| e.to_string())?;
+        Ok(out)
+    }
+}
+
+/// Read an `.abr` file from disk. Tips without a name of their own are named
+/// after the file stem.
+pub fn load_abr(path: &Path) -> Result<Vec<AbrTip>, String> {
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    let base_name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "ABR".to_string());
+    parse_abr(&data, &base_name)
+}
+
+/// Parse `.abr` bytes into sampled tips, naming unnamed tips `"<base_name> N"`.
+pub fn parse_abr(data: &[u8], base_name: &str) -> Result<Vec<AbrTip>, String> {
+    let mut r = Reader::new(data);
+    let version = r.u16()?;
+    let tips = match version {
+        1 | 2 => {
+            let count = r.u16()?;
+            parse_v12(&mut r, version, count, base_name)?
+        }
+        6 | 7 | 10 => {
+            let subversion = r.u16()?;
+            if subversion != 1 && subversion != 2 {
+                return Err(format!(
+                    "Unsupported ABR subversion {} (version {})",
+                    subversion, version
+                ));
+            }
+            parse_v6(&mut r, subversion, base_name)?
+        }
+        _ => return Err(format!("Unsupported ABR version {}", version)),
+    };
+    if tips.is_empty() {
+        return Err("No sampled brush tips found in ABR file".to_string());
+    }
+    Ok(tips)
+}
+
+fn parse_v12(
+    r: &mut Reader,
+    version: u16,
+    count: u16,
+    base_name: &str,
+) -> Result<Vec<AbrTip>, String> {
+    let mut tips = Vec::new();
+    for _ in 0..count {
+        let brush_type = r.u16()?;
+        let brush_size = r.u32()? as usize;
+        let next = r
+            .pos()
+            .checked_add(brush_size)
+            .ok_or("ABR brush record too large")?;
+        if brush_type == 2 {
+            let _misc = r.u32()?;
+            let _spacing = r.u16()?;
+            let name = if version == 2 {
+                r.ucs2_string()?
+            } else {
+                String::new()
+            };
+            let _antialias = r.u8()?;
+            r.skip(8)?; // short bounds
+            let name = if name.trim().is_empty() {
+                format!("{} {}", base_name, tips.len() + 1)
+            } else {
+                name
+            };
+            if let Some(tip) = read_sample(r, name)? {
+                tips.push(tip);
+            }
+        }
+        // Computed brushes (type 1) carry no bitmap — skip.
+        r.seek(next);
+    }
+    Ok(tips)
+}
+
+fn parse_v6(r: &mut Reader, subversion: u16, base_name: &str) -> Result<Vec<AbrTip>, String> {
+    let section_len = find_8bim_section(r, b"samp")? as usize;
+    let section_end = r.pos().saturating_add(section_len.min(r.remaining()));
+    let mut tips = Vec::new();
+    while r.pos() + 4 <= section_end {
+        let brush_len = r.u32()? as usize;
+        // Records are padded to a multiple of 4 bytes.
+        let next = brush_len
+            .div_ceil(4)
+            .checked_mul(4)
+            .and_then(|len| r.pos().checked_add(len))
+            .ok_or("ABR brush record too large")?;
+        // Subversion 1: ID string + short bounds + unknown; 2: ID + unknown block.
+        let skip = if subversion == 1 { 47 } else { 301 };
+        if r.skip(skip).is_ok() {
+            let name = format!("{} {}", base_name, tips.len() + 1);
+            if let Some(tip) = read_sample(r, name)? {
+                tips.push(tip);
+            }
+        }
+        r.seek(next);
+    }
+    Ok(tips)
+}
+
+/// Scan `8BIM` resource blocks until `key`, returning its payload length.
+fn find_8bim_section(r: &mut Reader, key: &[u8; 4]) -> Result<u32, String> {
+    while r.remaining() >= 12 {
+        let sig = r.bytes(4)?;
+        if sig != b"8BIM" {
+            return Err("Malformed ABR section header".to_string());
+        }
+        let tag = r.bytes(4)?;
+        let len = r.u32()?;
+        if tag == key {
+            return Ok(len);
+        }
+        r.skip(len as usize)?;
+    }
+    Err("ABR file has no sampled brush section".to_string())
+}
+
+/// Bounds + depth + compression header followed by the bitmap.
+/// Returns `None` for empty, oversized or unsupported samples, which the
+/// callers skip by seeking to the next record.
+fn read_sample(r: &mut Reader, name: String) -> Result<Option<AbrTip>, String> {
+    let top = r.i32()?;
+    let left = r.i32()?;
+    let bottom = r.i32()?;
+    let right = r.i32()?;
+    let depth = r.u16()?;
+    let compression = r.u8()?;
+
+    let width = (i64::from(right) - i64::from(left)).max(0);
+    let height = (i64::from(bottom) - i64::from(top)).max(0);
+    let max_edge = i64::from(MAX_SAMPLE_EDGE);
+    if width == 0 || height == 0 || width > max_edge || height > max_edge {
+        return Ok(None);
+    }
+    let (width, height) = (width as u32, height as u32);
+    let bytes_per_px = match depth {
+        8 => 1usize,
+        16 => 2usize,
+        _ => return Ok(None),
+    };
+    let row_len = width as usize * bytes_per_px;
+    let sample_len = row_len * height as usize;
+    // A PackBits run turns 2 bytes into at most 128, so a sample can never
+    // decode to more than 64x the bytes left; larger claims are corrupt and
+    // must not drive the allocation below.
+    let max_expansion = if compression == 0 { 1 } else { 64 };
+    if sample_len > r.remaining().saturating_mul(max_expansion) {
+        return Ok(None);
+    }
+
+    let raw = if compression == 0 {
+        r.bytes(sample_len)?.to_vec()
+    } else {
+        // PackBits: per-row byte counts, then the packed rows.
+        let mut counts = Vec::with_capacity(height as usize);
+        for _ in 0..height {
+            counts.push(r.u16()? as usize);
+        }
+        let mut out = Vec::with_capacity(sample_len);
+        for count in counts {
+            let packed = r.bytes(count)?;
+            unpack_bits(packed, row_len, &mut out);
+        }
+        out
+    };
+
+    // 16-bit samples are big-endian: keep the high byte.
+    let mask = if bytes_per_px == 2 {
+        raw.chunks_exact(2).map(|c| c[0]).collect()
+    } else {
+        raw
+    };
+    Ok(Some(AbrTip {
+        name,
+        width,
+        height,
+        mask,
+    }))
+}
+
+/// Decode one PackBits row into exactly `row_len` bytes appended to `out`.
+fn unpack_bits(packed: &[u8], row_len: usize, out: &mut Vec<u8>) {
+    let start = out.len();
+    let mut i = 0;
+    while i < packed.len() && out.len() - start < row_len {
+        let n = packed[i] as i8;
+        i += 1;
+        if n >= 0 {
+            let run = (n as usize + 1).min(packed.len() - i);
+            out.extend_from_slice(&packed[i..i + run]);
+            i += run;
+        } else if n != -128 {
+            let run = (1 - n as isize) as usize;
+            if let Some(&v) = packed.get(i) {
+                out.extend(std::iter::repeat_n(v, run));
+            }
+            i += 1;
+        }
+    }
+    out.resize(start + row_len, 0);
+}
@@ -2,7 +2,9 @@ use crate::assets::{
     AppSettings, Assets, BindableAction, Icon, KeyCombo, PixelGridMode, SettingsWindow,
 };
 use crate::canvas::{BlendMode, Canvas, CanvasState, Layer, TiledImage};
-use crate::components::dialogs::{NewFileDialog, SaveFileDialog, SaveFormat, TiffCompression};
+use crate::components::dialogs::{
+    NewFileDialog, RecoveryDialog, SaveFileDialog, SaveFormat, TiffCompression,
+};
 use crate::components::history::{
     CanvasSnapshot, SelectionCommand, SingleLayerSnapshotCommand, SnapshotCommand,
 };
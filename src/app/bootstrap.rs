@@ -132,6 +132,8 @@ impl PaintFEApp {
         // input-driven update cycle).
         #[cfg(target_arch = "wasm32")]
         crate::web_bridge::set_egui_context(cc.egui_ctx.clone());
+        // Pen tilt arrives outside egui's input (see `pen_probe`).
+        crate::pen_probe::install();
         // Listen for the browser's native paste event so Ctrl+V can pull an
         // image from the OS clipboard (there's no synchronous clipboard-read
         // API available to egui's input handling on web).
@@ -191,9 +193,11 @@ impl PaintFEApp {
             layers_panel: layers::LayersPanel::default(),
             colors_panel: colors::ColorsPanel::default(),
             palette_panel: palette::PalettePanel::default(),
+            layer_comps_panel: layer_comps::LayerCompsPanel::default(),
             history_panel: history::HistoryPanel::default(),
             new_file_dialog: NewFileDialog::default(),
             save_file_dialog: SaveFileDialog::default(),
+            recovery_dialog: RecoveryDialog::default(),
             settings_window: SettingsWindow::default(),
             #[cfg(target_arch = "wasm32")]
             show_welcome_popup: !crate::web_storage::has_seen_welcome(),
@@ -225,6 +229,7 @@ impl PaintFEApp {
             history_panel_size: None,
             colors_panel_left_offset: None,
             palette_panel_pos: None,
+            layer_comps_panel_pos: None,
             tools_panel_pos: None,
             last_screen_size: (0.0, 0.0),
             ui_cursor_blocking_rects: Vec::new(),
@@ -245,6 +250,7 @@ impl PaintFEApp {
             io_receiver,
             pending_io_ops: 0,
             io_ops_start_time: None,
+            toasts: Vec::new(),
             onnx_available,
             onnx_last_probed_paths,
             script_editor: {
@@ -263,6 +269,8 @@ impl PaintFEApp {
             exit_save_queue: Vec::new(),
             exit_save_active: false,
             last_autosave: crate::time_compat::Instant::now(),
+            autosave_session: crate::io::autosave_dir()
+                .and_then(|dir| crate::io::AutosaveSession::start(&dir).ok()),
             first_frame: true,
             ipc_receiver,
             close_initial_blank: !startup_files.is_empty() && create_canvas_on_startup,
@@ -345,6 +353,9 @@ impl PaintFEApp {
                 }
             }
         }
+        app.tools_panel.brush_presets = app.settings.brush_presets.clone();
+        app.tools_panel.gradient_library = app.settings.gradient_library.clone();
+        app.layers_panel.gradient_library = app.settings.gradient_library.clone();
         app.apply_persisted_tool_settings();
         app.last_tool_settings_fingerprint = app.compute_tool_settings_fingerprint();
         if let Some((project_id, undo_count)) = app
@@ -501,6 +512,7 @@ impl PaintFEApp {
             return;
         }
 
+        self.discard_project_autosave(index);
         self.persist_active_project_view();
         self.projects.remove(index);
 
@@ -525,6 +537,7 @@ impl PaintFEApp {
         if index == self.active_project_index && self.paste_overlay.is_some() {
             self.commit_paste_overlay();
         }
+        self.discard_project_autosave(index);
         self.persist_active_project_view();
         self.projects.remove(index);
         if self.projects.is_empty() {
@@ -761,6 +774,7 @@ impl PaintFEApp {
             tools::Tool::Zoom => "zoom",
             tools::Tool::Pan => "pan",
             tools::Tool::Shapes => "shapes",
+            tools::Tool::Slice => "slice",
         }
     }
 
@@ -789,6 +803,7 @@ impl PaintFEApp {
             "zoom" => tools::Tool::Zoom,
             "pan" => tools::Tool::Pan,
             "shapes" => tools::Tool::Shapes,
+            "slice" => tools::Tool::Slice,
             _ => tools::Tool::Brush,
         }
     }
@@ -821,8 +836,45 @@ impl PaintFEApp {
             "dodge" => tools::BrushMode::Dodge,
             "burn" => tools::BrushMode::Burn,
             "sponge" => tools::BrushMode::Sponge,
+            "mixer" => tools::BrushMode::Mixer,
             _ => tools::BrushMode::Normal,
         };
+        let mixer = &mut self.tools_panel.properties.mixer;
+        mixer.wetness = self.settings.persisted_mixer_wetness.clamp(0.0, 1.0);
+        mixer.load = self.settings.persisted_mixer_load.clamp(0.0, 1.0);
+        mixer.mix = self.settings.persisted_mixer_mix.clamp(0.0, 1.0);
+        mixer.sample_all_layers = self.settings.persisted_mixer_sample_all_layers;
+
+        let dynamics = &mut self.tools_panel.properties.dynamics;
+        dynamics.pressure_curve = match self.settings.persisted_dyn_pressure_curve.as_str() {
+            "soft" => tools::ResponseCurve::Soft,
+            "firm" => tools::ResponseCurve::Firm,
+            "s_curve" => tools::ResponseCurve::SCurve,
+            _ => tools::ResponseCurve::Linear,
+        };
+        dynamics.tilt_size = self.settings.persisted_dyn_tilt_size.clamp(0.0, 1.0);
+        dynamics.tilt_opacity = self.settings.persisted_dyn_tilt_opacity.clamp(0.0, 1.0);
+        dynamics.tilt_scatter = self.settings.persisted_dyn_tilt_scatter.clamp(0.0, 1.0);
+        dynamics.tilt_angle = self.settings.persisted_dyn_tilt_angle;
+        dynamics.velocity_curve = match self.settings.persisted_dyn_velocity_curve.as_str() {
+            "soft" => tools::ResponseCurve::Soft,
+            "firm" => tools::ResponseCurve::Firm,
+            "s_curve" => tools::ResponseCurve::SCurve,
+            _ => tools::ResponseCurve::Linear,
+        };
+        dynamics.velocity_size = self.settings.persisted_dyn_velocity_size.clamp(0.0, 1.0);
+        dynamics.velocity_opacity = self.settings.persisted_dyn_velocity_opacity.clamp(0.0, 1.0);
+        dynamics.velocity_scatter = self.settings.persisted_dyn_velocity_scatter.clamp(0.0, 1.0);
+        dynamics.direction_angle = self.settings.persisted_dyn_direction_angle;
+        dynamics.taper_start = self.settings.persisted_dyn_taper_start.clamp(0.0, 500.0);
+        dynamics.taper_end = self.settings.persisted_dyn_taper_end.clamp(0.0, 500.0);
+        dynamics.stabilizer = match self.settings.persisted_dyn_stabilizer.as_str() {
+            "lazy_mouse" => tools::StabilizerMode::LazyMouse,
+            "pulled_string" => tools::StabilizerMode::PulledString,
+            _ => tools::StabilizerMode::Off,
+        };
+        dynamics.stabilizer_strength = self.settings.persisted_dyn_stabilizer_strength.clamp(0.0, 1.0);
+        dynamics.stabilizer_radius = self.settings.persisted_dyn_stabilizer_radius.clamp(1.0, 200.0);
 
         self.tools_panel.properties.brush_tip = if self.settings.persisted_brush_tip.is_empty() {
             tools::BrushTip::Circle
@@ -932,12 +984,15 @@ impl PaintFEApp {
             tools::BrushMode::Dodge => 1u8,
             tools::BrushMode::Burn => 2u8,
             tools::BrushMode::Sponge => 3u8,
+            tools::BrushMode::Mixer => 4u8,
         }
         .hash(&mut hasher);
+        format!("{:?}", self.tools_panel.properties.mixer).hash(&mut hasher);
         match &self.tools_panel.properties.brush_tip {
             tools::BrushTip::Circle => "".hash(&mut hasher),
             tools::BrushTip::Image(name) => name.hash(&mut hasher),
         }
+        format!("{:?}", self.tools_panel.properties.dynamics).hash(&mut hasher);
 
         self.tools_panel
             .fill_state
@@ -1136,12 +1191,51 @@ impl PaintFEApp {
             tools::BrushMode::Dodge => "dodge",
             tools::BrushMode::Burn => "burn",
             tools::BrushMode::Sponge => "sponge",
+            tools::BrushMode::Mixer => "mixer",
         }
         .to_string();
+        let mixer = &self.tools_panel.properties.mixer;
+        self.settings.persisted_mixer_wetness = mixer.wetness;
+        self.settings.persisted_mixer_load = mixer.load;
+        self.settings.persisted_mixer_mix = mixer.mix;
+        self.settings.persisted_mixer_sample_all_layers = mixer.sample_all_layers;
         self.settings.persisted_brush_tip = match &self.tools_panel.properties.brush_tip {
             tools::BrushTip::Circle => String::new(),
             tools::BrushTip::Image(name) => name.clone(),
         };
+        let dynamics = &self.tools_panel.properties.dynamics;
+        self.settings.persisted_dyn_pressure_curve = match dynamics.pressure_curve {
+            tools::ResponseCurve::Linear => "linear",
+            tools::ResponseCurve::Soft => "soft",
+            tools::ResponseCurve::Firm => "firm",
+            tools::ResponseCurve::SCurve => "s_curve",
+        }
+        .to_string();
+        self.settings.persisted_dyn_tilt_size = dynamics.tilt_size;
+        self.settings.persisted_dyn_tilt_opacity = dynamics.tilt_opacity;
+        self.settings.persisted_dyn_tilt_scatter = dynamics.tilt_scatter;
+        self.settings.persisted_dyn_tilt_angle = dynamics.tilt_angle;
+        self.settings.persisted_dyn_velocity_curve = match dynamics.velocity_curve {
+            tools::ResponseCurve::Linear => "linear",
+            tools::ResponseCurve::Soft => "soft",
+            tools::ResponseCurve::Firm => "firm",
+            tools::ResponseCurve::SCurve => "s_curve",
+        }
+        .to_string();
+        self.settings.persisted_dyn_velocity_size = dynamics.velocity_size;
+        self.settings.persisted_dyn_velocity_opacity = dynamics.velocity_opacity;
+        self.settings.persisted_dyn_velocity_scatter = dynamics.velocity_scatter;
+        self.settings.persisted_dyn_direction_angle = dynamics.direction_angle;
+        self.settings.persisted_dyn_taper_start = dynamics.taper_start;
+        self.settings.persisted_dyn_taper_end = dynamics.taper_end;
+        self.settings.persisted_dyn_stabilizer = match dynamics.stabilizer {
+            tools::StabilizerMode::Off => "off",
+            tools::StabilizerMode::LazyMouse => "lazy_mouse",
+            tools::StabilizerMode::PulledString => "pulled_string",
+        }
+        .to_string();
+        self.settings.persisted_dyn_stabilizer_strength = dynamics.stabilizer_strength;
+        self.settings.persisted_dyn_stabilizer_radius = dynamics.stabilizer_radius;
 
         self.settings.persisted_fill_tolerance = self.tools_panel.fill_state.tolerance;
         self.settings.persisted_fill_anti_aliased = self.tools_panel.fill_state.anti_aliased;
@@ -7,6 +7,25 @@ impl PaintFEApp {
             log_info!("Dialog: open ({})", dialog.name());
         }
 
+        // Locked layers: refuse dialogs that would edit or move them.
+        if dialog.edits_active_layer()
+            && let Some(layer) = self
+                .active_project()
+                .and_then(|p| p.canvas_state.layers.get(p.canvas_state.active_layer_index))
+            && (layer.locks.pixels_locked()
+                || (dialog.moves_active_layer() && layer.locks.position_locked()))
+        {
+            let (name, locks) = (layer.name.clone(), layer.locks.describe());
+            log_info!(
+                "Dialog: {} blocked, layer \"{}\" is locked ({})",
+                dialog.name(),
+                name,
+                locks
+            );
+            self.push_toast(t!("toast.layer_locked", layer = name, locks = locks), true);
+            return;
+        }
+
         if self.process_brush_tip_dialog(ctx, &mut dialog) {
             return;
         }
@@ -57,6 +76,7 @@ impl PaintFEApp {
 }
 
 include!("dialogs/brush_tip.rs");
+include!("dialogs/gradients.rs");
 include!("dialogs/canvas_and_transform.rs");
 include!("dialogs/adjustments.rs");
 include!("dialogs/blur.rs");
@@ -77,3 +77,219 @@ impl PaintFEApp {
         }
     }
 }
+
+const BRUSH_PACK_PICKER_TAG: &str = "brush_pack";
+const ABR_PICKER_TAG: &str = "abr";
+
+impl PaintFEApp {
+    /// Persist preset edits from the picker and run its pending file action.
+    fn process_brush_preset_actions(&mut self, ctx: &egui::Context) {
+        use crate::components::tools::BrushPresetAction;
+        use crate::config::brush_presets::{BRUSH_PRESET_PACK_EXTENSION, BrushPresetPack};
+
+        if self.tools_panel.brush_presets_changed {
+            self.tools_panel.brush_presets_changed = false;
+            self.settings.brush_presets = self.tools_panel.brush_presets.clone();
+            self.settings.save();
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            for (_, bytes) in crate::web_bridge::drain_pending(BRUSH_PACK_PICKER_TAG) {
+                let result = String::from_utf8(bytes)
+                    .map_err(|e| e.to_string())
+                    .and_then(|text| BrushPresetPack::from_json(&text));
+                self.import_brush_preset_pack(ctx, result);
+            }
+            for (name, bytes) in crate::web_bridge::drain_pending(ABR_PICKER_TAG) {
+                let stem = std::path::Path::new(&name)
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "ABR".to_string());
+                let result = crate::abr::parse_abr(&bytes, &stem);
+                self.import_abr_tips(ctx, &stem, result);
+            }
+        }
+
+        let Some(action) = self.tools_panel.pending_brush_preset_action.take() else {
+            return;
+        };
+        match action {
+            BrushPresetAction::ImportPack => {
+                #[cfg(target_arch = "wasm32")]
+                crate::web_bridge::open_picker(BRUSH_PACK_PICKER_TAG, ".pfebrushes", false);
+                #[cfg(not(target_arch = "wasm32"))]
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Brush Presets", &[BRUSH_PRESET_PACK_EXTENSION])
+                    .pick_file()
+                {
+                    self.import_brush_preset_pack(ctx, BrushPresetPack::load(&path));
+                }
+            }
+            BrushPresetAction::ExportPack => {
+                let pack = self.build_brush_preset_pack();
+                #[cfg(target_arch = "wasm32")]
+                match pack.to_json() {
+                    Ok(json) => crate::web_fs::trigger_download(
+                        &format!("paintfe.{}", BRUSH_PRESET_PACK_EXTENSION),
+                        json.as_bytes(),
+                    ),
+                    Err(e) => {
+                        crate::log_warn!("Brush presets: export failed — {}", e);
+                    }
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Brush Presets", &[BRUSH_PRESET_PACK_EXTENSION])
+                    .set_file_name(format!("paintfe.{}", BRUSH_PRESET_PACK_EXTENSION))
+                    .save_file()
+                {
+                    match pack.save(&path) {
+                        Ok(()) => {
+                            log_info!(
+                                "Brush presets: exported {} preset(s) to {:?}",
+                                pack.presets.len(),
+                                path
+                            );
+                        }
+                        Err(e) => {
+                            crate::log_warn!("Brush presets: export failed — {}", e);
+                        }
+                    }
+                }
+            }
+            BrushPresetAction::ImportAbr => {
+                #[cfg(target_arch = "wasm32")]
+                crate::web_bridge::open_picker(ABR_PICKER_TAG, ".abr", false);
+                #[cfg(not(target_arch = "wasm32"))]
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Photoshop Brushes", &["abr"])
+                    .pick_file()
+                {
+                    let stem = path
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .unwrap_or_else(|| "ABR".to_string());
+                    self.import_abr_tips(ctx, &stem, crate::abr::load_abr(&path));
+                }
+            }
+        }
+    }
+
+    /// All presets plus the custom tips they reference.
+    fn build_brush_preset_pack(&self) -> crate::config::brush_presets::BrushPresetPack {
+        use crate::config::brush_presets::{BrushPresetPack, PackedBrushTip};
+
+        let presets = self.tools_panel.brush_presets.clone();
+        let tips = self
+            .settings
+            .custom_brush_tips
+            .iter()
+            .filter(|(name, _, _)| presets.iter().any(|p| &p.tip == name))
+            .map(|(name, category, b64)| PackedBrushTip {
+                name: name.clone(),
+                category: category.clone(),
+                png_base64: b64.clone(),
+            })
+            .collect();
+        BrushPresetPack::new(presets, tips)
+    }
+
+    fn import_brush_preset_pack(
+        &mut self,
+        ctx: &egui::Context,
+        pack: Result<crate::config::brush_presets::BrushPresetPack, String>,
+    ) {
+        let pack = match pack {
+            Ok(pack) => pack,
+            Err(e) => {
+                crate::log_warn!("Brush presets: import failed — {}", e);
+                return;
+            }
+        };
+        for tip in &pack.tips {
+            // Tips already in the library (built-in or same custom tip) are reused.
+            if self.assets.get_brush_tip_data(&tip.name).is_some() {
+                continue;
+            }
+            use base64::Engine;
+            if let Ok(png_data) = base64::engine::general_purpose::STANDARD.decode(&tip.png_base64)
+            {
+                self.assets
+                    .load_brush_tip(ctx, &tip.name, &tip.category, &png_data);
+                self.settings.custom_brush_tips.push((
+                    tip.name.clone(),
+                    tip.category.clone(),
+                    tip.png_base64.clone(),
+                ));
+            }
+        }
+        let count = pack.presets.len();
+        for preset in pack.presets {
+            crate::config::brush_presets::upsert_brush_preset(
+                &mut self.tools_panel.brush_presets,
+                preset,
+            );
+        }
+        self.settings.brush_presets = self.tools_panel.brush_presets.clone();
+        self.settings.save();
+        log_info!("Brush presets: imported {} preset(s)", count);
+    }
+
+    /// Add sampled `.abr` tips to the brush tip library under an "ABR: <file>" category.
+    fn import_abr_tips(
+        &mut self,
+        ctx: &egui::Context,
+        file_stem: &str,
+        tips: Result<Vec<crate::abr::AbrTip>, String>,
+    ) {
+        let tips = match tips {
+            Ok(tips) => tips,
+            Err(e) => {
+                crate::log_warn!("ABR import: {}", e);
+                return;
+            }
+        };
+        // Settings store tips as comma-separated lines, so keep commas out.
+        let category = format!("ABR: {}", file_stem.replace(',', " "));
+        let mut first = None;
+        let mut imported = 0usize;
+        for tip in &tips {
+            let png_data = match tip.to_png() {
+                Ok(data) => data,
+                Err(e) => {
+                    crate::log_warn!("ABR import: skipping '{}' — {}", tip.name, e);
+                    continue;
+                }
+            };
+            let base = tip.name.replace(',', " ");
+            let mut name = base.clone();
+            let mut n = 2;
+            while self.assets.get_brush_tip_data(&name).is_some() {
+                name = format!("{} ({})", base, n);
+                n += 1;
+            }
+            self.assets.load_brush_tip(ctx, &name, &category, &png_data);
+            use base64::Engine;
+            self.settings.custom_brush_tips.push((
+                name.clone(),
+                category.clone(),
+                base64::engine::general_purpose::STANDARD.encode(&png_data),
+            ));
+            first.get_or_insert(name);
+            imported += 1;
+        }
+        if imported == 0 {
+            return;
+        }
+        self.settings.save();
+        if let Some(name) = first {
+            self.tools_panel.properties.brush_tip = crate::components::tools::BrushTip::Image(name);
+        }
+        log_info!(
+            "ABR import: added {} tip(s) to category '{}'",
+            imported,
+            category
+        );
+    }
+}
@@ -1,6 +1,6 @@
 impl PaintFEApp {
     fn process_canvas_and_transform_dialog(&mut self, ctx: &egui::Context, dialog: &mut ActiveDialog) -> bool {
-        let matched = matches!(dialog, ActiveDialog::None | ActiveDialog::ResizeImage(_) | ActiveDialog::ResizeCanvas(_) | ActiveDialog::AlignLayer(_) | ActiveDialog::GaussianBlur(_) | ActiveDialog::LayerTransform(_));
+        let matched = matches!(dialog, ActiveDialog::None | ActiveDialog::ResizeImage(_) | ActiveDialog::ResizeCanvas(_) | ActiveDialog::Trim(_) | ActiveDialog::AlignLayer(_) | ActiveDialog::GaussianBlur(_) | ActiveDialog::LayerTransform(_) | ActiveDialog::SvgImport(_) | ActiveDialog::PageSetup(_) | ActiveDialog::ExportAs(_) | ActiveDialog::ExportLayers(_) | ActiveDialog::ExportSlices(_) | ActiveDialog::ImportSpriteSheet(_));
         if !matched {
             return false;
         }
@@ -22,12 +22,25 @@ impl PaintFEApp {
                             }
                         }
                         let before = CanvasSnapshot::capture(&project.canvas_state);
+                        let old_size = (project.canvas_state.width, project.canvas_state.height);
+                        let linear = project.canvas_state.linear_blending;
                         let flat_layers: Vec<RgbaImage> = project
                             .canvas_state
                             .layers
                             .iter()
                             .map(|l| l.pixels.to_rgba_image())
                             .collect();
+                        let off_canvas: Vec<_> = project
+                            .canvas_state
+                            .layers
+                            .iter()
+                            .map(|l| l.off_canvas.clone())
+                            .collect();
+                        let document_bounds = crate::ops::transform::scale_document_bounds(
+                            project.canvas_state.document_bounds,
+                            old_size,
+                            (w, h),
+                        );
                         let sender = self.canvas_op_sender.clone();
                         let project_index = self.active_project_index;
                         let current_time = ctx.input(|i| i.time);
@@ -37,12 +50,34 @@ impl PaintFEApp {
                         self.filter_status_description = "Resize Image".to_string();
                         self.pending_filter_jobs += 1;
                         crate::par_compat::spawn(move || {
-                            let result_layers =
-                                crate::ops::transform::resize_layers(flat_layers, w, h, interp);
+                            let resized = crate::ops::transform::resize_layers(
+                                flat_layers,
+                                w,
+                                h,
+                                interp,
+                                linear,
+                            );
+                            let result_layers = resized
+                                .into_iter()
+                                .zip(off_canvas)
+                                .map(|(tiled, off)| {
+                                    let off = off.and_then(|off| {
+                                        crate::ops::transform::scale_off_canvas(
+                                            &off,
+                                            old_size,
+                                            (w, h),
+                                            interp,
+                                            linear,
+                                        )
+                                    });
+                                    (tiled, off)
+                                })
+                                .collect();
                             let _ = sender.send(CanvasOpResult {
                                 project_index,
                                 before,
                                 result_layers,
+                                document_bounds,
                                 new_width: w,
                                 new_height: h,
                                 description: "Resize Image".to_string(),
@@ -81,12 +116,21 @@ impl PaintFEApp {
                             let before = CanvasSnapshot::capture(&project.canvas_state);
                             let old_w = project.canvas_state.width;
                             let old_h = project.canvas_state.height;
-                            let flat_layers: Vec<RgbaImage> = project
+                            let extents: Vec<((i32, i32), RgbaImage)> = project
                                 .canvas_state
                                 .layers
                                 .iter()
-                                .map(|l| l.pixels.to_rgba_image())
+                                .map(|l| l.extent_rgba())
                                 .collect();
+                            let document_bounds = crate::ops::transform::offset_document_bounds(
+                                project.canvas_state.document_bounds,
+                                crate::ops::transform::canvas_anchor_offset(
+                                    (old_w, old_h),
+                                    (w, h),
+                                    anchor,
+                                ),
+                                (w, h),
+                            );
                             let sender = self.canvas_op_sender.clone();
                             let project_index = self.active_project_index;
                             let current_time = ctx.input(|i| i.time);
@@ -97,7 +141,7 @@ impl PaintFEApp {
                             self.pending_filter_jobs += 1;
                             crate::par_compat::spawn(move || {
                                 let result_layers = crate::ops::transform::resize_canvas_layers(
-                                    flat_layers,
+                                    extents,
                                     old_w,
                                     old_h,
                                     w,
@@ -109,6 +153,7 @@ impl PaintFEApp {
                                     project_index,
                                     before,
                                     result_layers,
+                                    document_bounds,
                                     new_width: w,
                                     new_height: h,
                                     description: "Resize Canvas".to_string(),
@@ -200,6 +245,9 @@ impl PaintFEApp {
                             let sel_mask = self
                                 .active_project()
                                 .and_then(|p| p.canvas_state.selection_mask.clone());
+                            let linear = self
+                                .active_project()
+                                .is_some_and(|p| p.canvas_state.linear_blending);
                             self.spawn_preview_job(
                                 ctx.input(|i| i.time),
                                 "Gaussian Blur".to_string(),
@@ -211,6 +259,7 @@ impl PaintFEApp {
                                         img,
                                         sigma,
                                         sel_mask.as_ref(),
+                                        linear,
                                     )
                                 },
                             );
@@ -239,6 +288,9 @@ impl PaintFEApp {
                             let sel_mask = self
                                 .active_project()
                                 .and_then(|p| p.canvas_state.selection_mask.clone());
+                            let linear = self
+                                .active_project()
+                                .is_some_and(|p| p.canvas_state.linear_blending);
                             self.spawn_filter_job(
                                 ctx.input(|i| i.time),
                                 "Gaussian Blur".to_string(),
@@ -250,6 +302,7 @@ impl PaintFEApp {
                                         flat,
                                         sigma,
                                         sel_mask.as_ref(),
+                                        linear,
                                     )
                                 },
                             );
@@ -288,7 +341,7 @@ impl PaintFEApp {
                         let scale = dlg.scale_percent / 100.0;
                         let offset = (dlg.offset_x, dlg.offset_y);
                         let idx = dlg.layer_idx;
-                        if let Some(flat) = &dlg.original_flat
+                        if let Some((origin, flat)) = &dlg.original_flat
                             && let Some(project) = self.active_project_mut()
                         {
                             crate::ops::transform::affine_transform_layer_from_flat(
@@ -299,6 +352,7 @@ impl PaintFEApp {
                                 ry,
                                 scale,
                                 offset,
+                                *origin,
                                 flat,
                             );
                         }
@@ -311,14 +365,20 @@ impl PaintFEApp {
                             if let Some(original) = &dlg.original_pixels
                                 && idx < project.canvas_state.layers.len()
                             {
-                                let transformed = project.canvas_state.layers[idx].pixels.clone();
-                                project.canvas_state.layers[idx].pixels = original.clone();
+                                let layer = &mut project.canvas_state.layers[idx];
+                                let transformed = std::mem::replace(&mut layer.pixels, original.clone());
+                                let transformed_off = std::mem::replace(
+                                    &mut layer.off_canvas,
+                                    dlg.original_off_canvas.clone(),
+                                );
                                 let mut cmd = SingleLayerSnapshotCommand::new_for_layer(
                                     "Layer Transform".to_string(),
                                     &project.canvas_state,
                                     idx,
                                 );
-                                project.canvas_state.layers[idx].pixels = transformed;
+                                let layer = &mut project.canvas_state.layers[idx];
+                                layer.pixels = transformed;
+                                layer.off_canvas = transformed_off;
                                 cmd.set_after(&project.canvas_state);
                                 project.history.push(Box::new(cmd));
                             }
@@ -336,6 +396,8 @@ impl PaintFEApp {
                         {
                             if let Some(layer) = project.canvas_state.layers.get_mut(idx) {
                                 layer.pixels = original.clone();
+                                layer.off_canvas = dlg.original_off_canvas.clone();
+                                layer.invalidate_lod();
                             }
                             project.canvas_state.mark_dirty(None);
                         }
@@ -350,6 +412,238 @@ impl PaintFEApp {
             // BRIGHTNESS / CONTRAST
             // ================================================================
 
+            ActiveDialog::SvgImport(dlg) => match dlg.show(ctx) {
+                DialogResult::Ok(options) => {
+                    self.active_dialog = ActiveDialog::None;
+                    let document = dlg.document.clone();
+                    let path = dlg.path.clone();
+                    match dlg.mode {
+                        crate::ops::dialogs::SvgImportMode::NewDocument => {
+                            if !self
+                                .pending_open_paths
+                                .insert(Self::normalize_open_path(&path))
+                            {
+                                return true;
+                            }
+                            let sender = self.io_sender.clone();
+                            if self.pending_io_ops == 0 {
+                                self.io_ops_start_time = Some(ctx.input(|i| i.time));
+                            }
+                            self.pending_io_ops += 1;
+                            crate::par_compat::spawn(move || {
+                                let name = path
+                                    .file_stem()
+                                    .map(|s| s.to_string_lossy().to_string())
+                                    .unwrap_or_else(|| "Background".to_string());
+                                match document.to_canvas(&options, &name) {
+                                    Ok(canvas_state) => {
+                                        let _ = sender
+                                            .send(IoResult::LayeredLoaded { canvas_state, path });
+                                    }
+                                    Err(e) => {
+                                        let _ = sender.send(IoResult::LoadFailed {
+                                            path: Some(path),
+                                            error: format!("Failed to open SVG: {}", e),
+                                        });
+                                    }
+                                }
+                            });
+                        }
+                        crate::ops::dialogs::SvgImportMode::Layers => {
+                            let name = path
+                                .file_stem()
+                                .map(|s| s.to_string_lossy().to_string())
+                                .unwrap_or_else(|| "Imported".to_string());
+                            match document.render_layers(&options) {
+                                Ok(layers) => {
+                                    self.do_snapshot_op("Import SVG", |s| {
+                                        crate::ops::canvas_ops::import_svg_layers(s, layers, &name);
+                                    });
+                                }
+                                Err(e) => {
+                                    log_info!("FileIO: SVG import failed — {}", e);
+                                    eprintln!("Failed to import SVG: {}", e);
+                                    self.push_toast(
+                                        t!(
+                                            "toast.open_failed",
+                                            error = format!("Failed to import SVG: {}", e)
+                                        ),
+                                        true,
+                                    );
+                                }
+                            }
+                        }
+                    }
+                    return true;
+                }
+                DialogResult::Cancel => {
+                    self.active_dialog = ActiveDialog::None;
+                    return true;
+                }
+                _ => {}
+            },
+
+            ActiveDialog::PageSetup(dlg) => match dlg.show(ctx) {
+                DialogResult::Ok(action) => {
+                    self.settings.persist_page_setup = dlg.setup.clone();
+                    self.active_dialog = ActiveDialog::None;
+                    let setup = dlg.setup.clone();
+                    let image = dlg.image.clone();
+                    match action {
+                        crate::ops::dialogs::PageSetupAction::Print => {
+                            if let Err(e) = crate::ops::print::print_image(&image, &setup) {
+                                eprintln!("Print error: {}", e);
+                            }
+                        }
+                        crate::ops::dialogs::PageSetupAction::ExportPdf => {
+                            let stem = self
+                                .active_project()
+                                .map(|p| p.name.clone())
+                                .unwrap_or_else(|| "Untitled".to_string());
+                            #[cfg(not(target_arch = "wasm32"))]
+                            if let Some(path) = rfd::FileDialog::new()
+                                .add_filter("PDF", &["pdf"])
+                                .set_file_name(format!("{}.pdf", stem))
+                                .save_file()
+                            {
+                                self.spawn_export(ctx.input(|i| i.time), move || {
+                                    crate::ops::page_setup::write_pdf(&image, &setup, &path)
+                                        .map(|()| vec![path])
+                                });
+                            }
+                            #[cfg(target_arch = "wasm32")]
+                            match crate::ops::page_setup::export_pdf(&image, &setup) {
+                                Ok(bytes) => crate::web_fs::trigger_download(
+                                    &format!("{}.pdf", stem),
+                                    &bytes,
+                                ),
+                                Err(e) => {
+                                    eprintln!("PDF export error: {}", e);
+                                    self.push_toast(t!("toast.export_failed", error = e), true);
+                                }
+                            }
+                        }
+                    }
+                    return true;
+                }
+                DialogResult::Cancel => {
+                    self.settings.persist_page_setup = dlg.setup.clone();
+                    self.active_dialog = ActiveDialog::None;
+                    return true;
+                }
+                _ => {}
+            },
+
+            ActiveDialog::ExportAs(dlg) => match dlg.show(ctx) {
+                DialogResult::Ok(()) => {
+                    if dlg.presets_changed
+                        && let Err(e) =
+                            crate::config::export_presets::save_export_presets(&dlg.presets)
+                    {
+                        eprintln!("Could not save export presets: {}", e);
+                    }
+                    let presets = dlg.chosen();
+                    self.settings.persist_export_directory = dlg.directory.clone();
+                    self.settings.persist_export_presets =
+                        presets.iter().map(|p| p.name.clone()).collect();
+                    let dir = PathBuf::from(&dlg.directory);
+                    let base_name = dlg.base_name.trim().to_string();
+                    self.active_dialog = ActiveDialog::None;
+                    self.export_with_presets(ctx.input(|i| i.time), &presets, dir, &base_name);
+                    return true;
+                }
+                DialogResult::Cancel => {
+                    self.active_dialog = ActiveDialog::None;
+                    return true;
+                }
+                _ => {}
+            },
+
+            ActiveDialog::ExportLayers(dlg) => match dlg.show(ctx) {
+                DialogResult::Ok(()) => {
+                    self.settings.persist_export_directory = dlg.directory.clone();
+                    let dir = PathBuf::from(&dlg.directory);
+                    let doc_name = dlg.doc_name.trim().to_string();
+                    let options = dlg.options.clone();
+                    self.active_dialog = ActiveDialog::None;
+                    self.export_layers(ctx.input(|i| i.time), dir, doc_name, options);
+                    return true;
+                }
+                DialogResult::Cancel => {
+                    self.active_dialog = ActiveDialog::None;
+                    return true;
+                }
+                _ => {}
+            },
+
+            ActiveDialog::ExportSlices(dlg) => match dlg.show(ctx) {
+                DialogResult::Ok(()) => {
+                    self.settings.persist_export_directory = dlg.directory.clone();
+                    let dir = PathBuf::from(&dlg.directory);
+                    let current_time = ctx.input(|i| i.time);
+                    match dlg.mode {
+                        crate::ops::dialogs::SliceExportMode::Files => {
+                            let options = dlg.files.clone();
+                            self.export_slices(current_time, dir, options);
+                        }
+                        crate::ops::dialogs::SliceExportMode::SpriteSheet => {
+                            let name = dlg.sheet_name.trim().to_string();
+                            let options = dlg.sheet.clone();
+                            self.export_sprite_sheet(current_time, dir, name, options);
+                        }
+                    }
+                    self.active_dialog = ActiveDialog::None;
+                    return true;
+                }
+                DialogResult::Cancel => {
+                    self.active_dialog = ActiveDialog::None;
+                    return true;
+                }
+                _ => {}
+            },
+
+            ActiveDialog::Trim(dlg) => match dlg.show(ctx) {
+                DialogResult::Ok(options) => {
+                    self.active_dialog = ActiveDialog::None;
+                    self.do_snapshot_op_if_changed("Trim", |s| {
+                        crate::ops::trim::trim_canvas(s, &options)
+                    });
+                    return true;
+                }
+                DialogResult::Cancel => {
+                    self.active_dialog = ActiveDialog::None;
+                    return true;
+                }
+                _ => {}
+            },
+
+            ActiveDialog::ImportSpriteSheet(dlg) => match dlg.show(ctx) {
+                DialogResult::Ok(()) => {
+                    match crate::ops::slices::sprite_sheet_document(
+                        &dlg.sheet,
+                        &dlg.grid,
+                        dlg.skip_empty,
+                    ) {
+                        Ok(canvas_state) => {
+                            let name = dlg.name.clone();
+                            self.active_dialog = ActiveDialog::None;
+                            self.open_canvas_as_new_project(canvas_state, &name);
+                        }
+                        Err(e) => {
+                            self.active_dialog = ActiveDialog::None;
+                            eprintln!("Sprite sheet import failed: {}", e);
+                            self.push_toast(t!("toast.sprite_sheet_import_failed", error = e), true);
+                        }
+                    }
+                    return true;
+                }
+                DialogResult::Cancel => {
+                    self.active_dialog = ActiveDialog::None;
+                    return true;
+                }
+                _ => {}
+            },
+
             _ => unreachable!(),
         }
 
@@ -0,0 +1,69 @@
+const GRADIENT_PICKER_TAG: &str = "gradient";
+
+impl PaintFEApp {
+    /// Persist gradient library edits and run a pending `.grd`/`.ggr` import.
+    fn process_gradient_library_actions(&mut self) {
+        if self.tools_panel.gradient_library_changed {
+            self.tools_panel.gradient_library_changed = false;
+            self.settings.gradient_library = self.tools_panel.gradient_library.clone();
+            self.layers_panel.gradient_library = self.tools_panel.gradient_library.clone();
+            self.settings.save();
+        }
+
+        let foreground = self
+            .colors_panel
+            .get_primary_color()
+            .to_srgba_unmultiplied();
+        let background = self
+            .colors_panel
+            .get_secondary_color()
+            .to_srgba_unmultiplied();
+
+        #[cfg(target_arch = "wasm32")]
+        for (name, bytes) in crate::web_bridge::drain_pending(GRADIENT_PICKER_TAG) {
+            let stem = std::path::Path::new(&name)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| "Gradient".to_string());
+            let result = crate::gradient::parse_gradients(&bytes, &stem, foreground, background);
+            self.import_gradients(result);
+        }
+
+        if !std::mem::take(&mut self.tools_panel.pending_gradient_import) {
+            return;
+        }
+        #[cfg(target_arch = "wasm32")]
+        crate::web_bridge::open_picker(GRADIENT_PICKER_TAG, ".grd,.ggr", false);
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Gradients", crate::gradient::GRADIENT_IMPORT_EXTENSIONS)
+            .pick_file()
+        {
+            self.import_gradients(crate::gradient::load_gradients(
+                &path, foreground, background,
+            ));
+        }
+    }
+
+    /// Add imported gradients to the library and load the first into the gradient tool.
+    fn import_gradients(&mut self, gradients: Result<Vec<crate::gradient::Gradient>, String>) {
+        let gradients = match gradients {
+            Ok(gradients) => gradients,
+            Err(e) => {
+                crate::log_warn!("Gradient import: {}", e);
+                return;
+            }
+        };
+        if let Some(first) = gradients.first() {
+            self.tools_panel.gradient_state.load_gradient(first);
+        }
+        let count = gradients.len();
+        for gradient in gradients {
+            crate::gradient::upsert_gradient(&mut self.tools_panel.gradient_library, gradient);
+        }
+        self.settings.gradient_library = self.tools_panel.gradient_library.clone();
+        self.layers_panel.gradient_library = self.tools_panel.gradient_library.clone();
+        self.settings.save();
+        log_info!("Gradient import: added {} gradient(s)", count);
+    }
+}
@@ -165,6 +165,81 @@ impl PaintFEApp {
         }
     }
 
+    /// Like [`Self::do_snapshot_op`], for operations that may find nothing
+    /// to do. The closure returns whether it changed the canvas; when it did
+    /// not, text layers keep their editable content and no undo step is
+    /// recorded.
+    fn do_snapshot_op_if_changed(
+        &mut self,
+        description: &str,
+        op: impl FnOnce(&mut CanvasState) -> bool,
+    ) {
+        if let Some(project) = self.active_project_mut() {
+            project.canvas_state.ensure_all_text_layers_rasterized();
+            let mut text_contents = Vec::new();
+            for (index, layer) in project.canvas_state.layers.iter_mut().enumerate() {
+                if layer.is_text_layer() {
+                    let content =
+                        std::mem::replace(&mut layer.content, crate::canvas::LayerContent::Raster);
+                    text_contents.push((index, content));
+                }
+            }
+            let mut cmd = SnapshotCommand::new(description.to_string(), &project.canvas_state);
+            if !op(&mut project.canvas_state) {
+                for (index, content) in text_contents {
+                    project.canvas_state.layers[index].content = content;
+                }
+                return;
+            }
+            cmd.set_after(&project.canvas_state);
+            project.history.push(Box::new(cmd));
+            project.mark_dirty();
+        }
+    }
+
+    /// Queue a toast; errors are drawn in the theme's error colour.
+    fn push_toast(&mut self, text: String, is_error: bool) {
+        self.toasts.push(Toast {
+            text,
+            is_error,
+            shown_at: None,
+        });
+    }
+
+    /// Draw queued toasts stacked up from the bottom-right corner, newest at
+    /// the bottom, and drop the ones that have been up long enough.
+    fn show_toasts(&mut self, ctx: &egui::Context) {
+        let now = ctx.input(|i| i.time);
+        self.toasts
+            .retain(|toast| toast.shown_at.is_none_or(|t| now - t < TOAST_SECONDS));
+        if self.toasts.is_empty() {
+            return;
+        }
+        let screen_rect = ctx.content_rect();
+        let mut bottom = screen_rect.max.y - 36.0;
+        for (i, toast) in self.toasts.iter_mut().enumerate().rev() {
+            toast.shown_at.get_or_insert(now);
+            let area = egui::Area::new(egui::Id::new(("io_toast", i)))
+                .order(egui::Order::Foreground)
+                .pivot(egui::Align2::RIGHT_BOTTOM)
+                .fixed_pos(egui::pos2(screen_rect.max.x - 12.0, bottom))
+                .interactable(false)
+                .show(ctx, |ui| {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_max_width(360.0);
+                        let color = if toast.is_error {
+                            ui.visuals().error_fg_color
+                        } else {
+                            ui.visuals().text_color()
+                        };
+                        ui.label(egui::RichText::new(&toast.text).color(color));
+                    });
+                });
+            bottom = area.response.rect.min.y - 6.0;
+        }
+        ctx.request_repaint_after(std::time::Duration::from_millis(250));
+    }
+
     fn cut_selection_with_history(&mut self, transparent_cutout: bool) -> bool {
         let Some(project) = self.active_project_mut() else {
             return false;
@@ -218,6 +293,7 @@ impl PaintFEApp {
                 });
                 project.canvas_state.clear_preview_state();
                 overlay.commit(&mut project.canvas_state);
+                crate::ops::transform::extend_infinite_canvas(&mut project.canvas_state, None);
                 let after =
                     crate::components::history::CanvasSnapshot::capture(&project.canvas_state);
                 project.history.push(Box::new(
@@ -267,11 +343,56 @@ impl PaintFEApp {
             }
         }
 
-        if let Some(stroke_event) = self.tools_panel.take_stroke_event()
-            && let Some(project) = self.active_project_mut()
-        {
+        // Infinite canvas: a stroke that grew the canvas shares one history
+        // entry with the growth, so a single undo restores both.
+        let stroke_event = self.tools_panel.take_stroke_event();
+        let growth_before = if stroke_event.is_some() || !self.tools_panel.is_stroke_active() {
+            self.tools_panel.canvas_growth_before.take()
+        } else {
+            None
+        };
+        let Some(stroke_event) = stroke_event else {
+            if let Some(before) = growth_before
+                && let Some(project) = self.active_project_mut()
+            {
+                push_canvas_growth(project, "Extend Canvas".to_string(), before);
+            }
+            return;
+        };
+        if let Some(project) = self.active_project_mut() {
             match stroke_event.target {
                 crate::components::tools::StrokeTarget::LayerPixels => {
+                    // Locked layers: put back what the lock protects before
+                    // the "after" state is captured.
+                    if let Some(before_patch) = stroke_event.before_snapshot.as_ref()
+                        && let Some(layer) =
+                            project.canvas_state.layers.get_mut(stroke_event.layer_index)
+                        && (layer.locks.pixels_locked() || layer.locks.transparency_locked())
+                    {
+                        let editable = layer.enforce_locks_in_rect(
+                            before_patch.rect.min.x as u32,
+                            before_patch.rect.min.y as u32,
+                            before_patch.width,
+                            before_patch.height,
+                            &before_patch.pixels,
+                        );
+                        layer.gpu_generation += 1;
+                        project.canvas_state.mark_dirty(Some(before_patch.rect));
+                        if !editable {
+                            crate::log_info!(
+                                "{} discarded: layer pixels are locked",
+                                stroke_event.description
+                            );
+                            if let Some(before) = growth_before {
+                                push_canvas_growth(project, "Extend Canvas".to_string(), before);
+                            }
+                            return;
+                        }
+                    }
+                    if let Some(before) = growth_before {
+                        push_canvas_growth(project, stroke_event.description, before);
+                        return;
+                    }
                     let after_patch = history::PixelPatch::capture(
                         &project.canvas_state,
                         stroke_event.layer_index,
@@ -289,7 +410,9 @@ impl PaintFEApp {
                     }
                 }
                 crate::components::tools::StrokeTarget::LayerMask => {
-                    if let Some(layer) = project.canvas_state.layers.get(stroke_event.layer_index) {
+                    if let Some(before) = growth_before {
+                        push_canvas_growth(project, stroke_event.description, before);
+                    } else if let Some(layer) = project.canvas_state.layers.get(stroke_event.layer_index) {
                         let command = history::LayerMaskCommand::new(
                             stroke_event.description,
                             stroke_event.layer_index,
@@ -485,7 +608,7 @@ impl PaintFEApp {
     ) where
         F: FnOnce(&RgbaImage) -> RgbaImage,
     {
-        if layer_idx >= state.layers.len() {
+        if layer_idx >= state.layers.len() || state.layers[layer_idx].locks.pixels_locked() {
             return;
         }
         // Convert text layer to raster so the effect isn't overwritten by re-rasterization
@@ -493,7 +616,21 @@ impl PaintFEApp {
             state.layers[layer_idx].content = crate::canvas::LayerContent::Raster;
         }
         let result = effect_fn(original_flat);
-        state.layers[layer_idx].pixels = TiledImage::from_rgba_image(&result);
+        state.layers[layer_idx].set_pixels_with_locks(&result);
         state.mark_dirty(None);
     }
 }
+
+/// Record an infinite-canvas growth, and the stroke painted after it, as one
+/// snapshot entry running from `before` to the current canvas.
+fn push_canvas_growth(
+    project: &mut Project,
+    description: String,
+    before: crate::components::history::CanvasSnapshot,
+) {
+    let after = crate::components::history::CanvasSnapshot::capture(&project.canvas_state);
+    project.history.push(Box::new(
+        crate::components::history::SnapshotCommand::from_snapshots(description, before, after),
+    ));
+    project.mark_dirty();
+}
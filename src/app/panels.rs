@@ -290,20 +290,42 @@ impl PaintFEApp {
                                 "Image",
                                 &[
                                     "png", "jpg", "jpeg", "bmp", "gif", "webp", "tiff", "tif",
-                                    "tga", "ico",
+                                    "tga", "ico", "qoi", "pgm", "ppm", "pbm", "pnm", "pam", "svg",
                                 ],
                             )
                             .pick_file()
-                            && let Ok(img) = image::open(&path)
                         {
-                            let rgba = img.to_rgba8();
-                            let name = path
-                                .file_stem()
-                                .map(|s| s.to_string_lossy().to_string())
-                                .unwrap_or_else(|| "Imported".to_string());
-                            self.do_snapshot_op("Import Layer", |s| {
-                                crate::ops::adjustments::import_layer_from_image(s, &rgba, &name);
-                            });
+                            if path
+                                .extension()
+                                .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"))
+                            {
+                                match std::fs::read(&path)
+                                    .map_err(|e| e.to_string())
+                                    .and_then(|data| crate::svg_io::SvgDocument::parse(&data))
+                                {
+                                    Ok(document) => {
+                                        self.active_dialog = ActiveDialog::SvgImport(
+                                            crate::ops::dialogs::SvgImportDialog::new(
+                                                document,
+                                                path,
+                                                crate::ops::dialogs::SvgImportMode::Layers,
+                                            ),
+                                        );
+                                    }
+                                    Err(e) => eprintln!("Failed to import SVG: {}", e),
+                                }
+                            } else if let Ok(img) = image::open(&path) {
+                                let rgba = img.to_rgba8();
+                                let name = path
+                                    .file_stem()
+                                    .map(|s| s.to_string_lossy().to_string())
+                                    .unwrap_or_else(|| "Imported".to_string());
+                                self.do_snapshot_op("Import Layer", |s| {
+                                    crate::ops::adjustments::import_layer_from_image(
+                                        s, &rgba, &name,
+                                    );
+                                });
+                            }
                         }
                     }
                     crate::components::layers::LayerAppAction::FlipHorizontal => {
@@ -595,6 +617,62 @@ impl PaintFEApp {
         self.window_visibility.colors = show;
     }
 
+    /// Apply a slice edit requested from the Slice tool's context bar.
+    fn handle_slice_action(&mut self, action: crate::components::tools::SliceToolAction) {
+        use crate::components::tools::SliceToolAction;
+        let Some(project) = self.active_project_mut() else {
+            return;
+        };
+        let state = &mut project.canvas_state;
+        let before = state.slices.clone();
+        let description = match action {
+            SliceToolAction::Rename(idx, name) => {
+                if state.slices.get(idx).is_none_or(|s| s.name == name) {
+                    return;
+                }
+                state.slices[idx].name = state.unique_slice_name(&name);
+                "Rename Slice"
+            }
+            SliceToolAction::Delete(idx) => {
+                if idx >= state.slices.len() {
+                    return;
+                }
+                state.slices.remove(idx);
+                "Delete Slice"
+            }
+            SliceToolAction::Grid { columns, rows } => {
+                state.slices.clear();
+                let grid =
+                    crate::ops::slices::grid_slices(state.width, state.height, columns, rows);
+                state.add_slices(grid);
+                "Slice Grid"
+            }
+            SliceToolAction::FromLayers => {
+                let slices = crate::ops::slices::layer_slices(state);
+                if state.add_slices(slices) == 0 {
+                    return;
+                }
+                "Slices from Layers"
+            }
+            SliceToolAction::Clear => {
+                if state.slices.is_empty() {
+                    return;
+                }
+                state.slices.clear();
+                "Clear Slices"
+            }
+        };
+        let after = state.slices.clone();
+        project
+            .history
+            .push(Box::new(crate::components::history::SlicesCommand::new(
+                description,
+                before,
+                after,
+            )));
+        project.mark_dirty();
+    }
+
     /// Show the floating Script Editor panel
     fn show_floating_palette_panel(&mut self, ctx: &egui::Context, screen_size_changed: bool) {
         let mut show = self.window_visibility.palette;
@@ -702,6 +780,150 @@ impl PaintFEApp {
         self.window_visibility.palette = show;
     }
 
+    /// Show the floating Layer Comps panel
+    fn show_floating_layer_comps_panel(&mut self, ctx: &egui::Context) {
+        let mut show = self.window_visibility.layer_comps;
+        if !show {
+            return;
+        }
+        let mut close_clicked = false;
+        let screen_rect = ctx.content_rect();
+        let panel_size = egui::vec2(240.0, 220.0);
+        let (pos_x, pos_y) = self.layer_comps_panel_pos.unwrap_or((
+            screen_rect.max.x - panel_size.x - 220.0,
+            screen_rect.center().y - panel_size.y * 0.5,
+        ));
+
+        let hover_id = egui::Id::new("LayerComps_hover");
+        let hover_t = ctx.animate_bool(hover_id, false);
+        let mut window = egui::Window::new("Layer Comps")
+            .open(&mut show)
+            .resizable(true)
+            .collapsible(false)
+            .min_width(200.0)
+            .min_height(140.0)
+            .default_size(panel_size)
+            .title_bar(false)
+            .frame(self.theme.floating_window_frame_animated(hover_t));
+        if self.layer_comps_panel_pos.is_none() {
+            window = window.current_pos(Self::clamp_floating_pos(
+                pos_x,
+                pos_y,
+                panel_size,
+                screen_rect,
+            ));
+        }
+
+        let mut action = None;
+        let resp = window.show(ctx, |ui| {
+            if signal_widgets::panel_header(
+                ui,
+                &self.theme,
+                "Layer Comps",
+                Some(("COMPS", self.theme.accent4)),
+                0.0,
+            ) {
+                close_clicked = true;
+            }
+            ui.style_mut().override_text_style = Some(egui::TextStyle::Small);
+            if let Some(project) = self.projects.get(self.active_project_index) {
+                action = self.layer_comps_panel.show(ui, &project.canvas_state);
+            }
+        });
+
+        if let Some(inner_resp) = resp {
+            let win_rect = inner_resp.response.rect;
+            self.remember_ui_cursor_rect(win_rect);
+            self.layer_comps_panel_pos = Some((win_rect.min.x, win_rect.min.y));
+            let hovered =
+                ctx.input(|i| i.pointer.hover_pos().is_some_and(|p| win_rect.contains(p)));
+            ctx.animate_bool(hover_id, hovered);
+        }
+
+        if let Some(action) = action {
+            self.handle_layer_comp_action(action);
+        }
+        if close_clicked {
+            show = false;
+        }
+        self.window_visibility.layer_comps = show;
+    }
+
+    fn handle_layer_comp_action(&mut self, action: layer_comps::LayerCompAction) {
+        use layer_comps::LayerCompAction;
+        if let LayerCompAction::Apply(idx) = action {
+            self.do_snapshot_op("Apply Layer Comp", |s| {
+                s.apply_layer_comp(idx);
+            });
+            self.canvas.gpu_clear_layers();
+            return;
+        }
+        let Some(project) = self.active_project_mut() else {
+            return;
+        };
+        let state = &mut project.canvas_state;
+        match action {
+            LayerCompAction::Apply(_) => {}
+            LayerCompAction::Create(name) => {
+                let comp = crate::canvas::LayerComp::capture(name, state);
+                state.layer_comps.push(comp);
+            }
+            LayerCompAction::Update(idx) => {
+                if let Some(name) = state.layer_comps.get(idx).map(|c| c.name.clone()) {
+                    state.layer_comps[idx] = crate::canvas::LayerComp::capture(name, state);
+                }
+            }
+            LayerCompAction::Rename(idx, name) => {
+                if let Some(comp) = state.layer_comps.get_mut(idx) {
+                    comp.name = name;
+                }
+            }
+            LayerCompAction::Delete(idx) => {
+                if idx < state.layer_comps.len() {
+                    state.layer_comps.remove(idx);
+                }
+            }
+            LayerCompAction::ExportAll => {
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    let stem = project
+                        .path
+                        .as_ref()
+                        .and_then(|p| p.file_stem())
+                        .map(|s| s.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| project.name.clone());
+                    let mut picker = rfd::FileDialog::new();
+                    if let Some(dir) = project.path.as_ref().and_then(|p| p.parent()) {
+                        picker = picker.set_directory(dir);
+                    }
+                    if let Some(dir) = picker.pick_folder() {
+                        match crate::ops::layer_comps::export_all_comps(
+                            &mut project.canvas_state,
+                            &dir,
+                            &stem,
+                            crate::components::dialogs::SaveFormat::Png,
+                            100,
+                        ) {
+                            Ok(paths) => {
+                                log_info!(
+                                    "Exported {} layer comps to {}",
+                                    paths.len(),
+                                    dir.display()
+                                );
+                            }
+                            Err(e) => {
+                                log_info!("Layer comp export failed: {}", e);
+                            }
+                        }
+                        project.canvas_state.mark_dirty(None);
+                    }
+                }
+                return;
+            }
+        }
+        project.mark_dirty();
+    }
+
     /// Show the floating Script Editor panel
     fn show_floating_script_editor(&mut self, ctx: &egui::Context, screen_size_changed: bool) {
         let mut show = self.window_visibility.script_editor;
@@ -803,6 +1025,13 @@ impl PaintFEApp {
         }
 
         let layer = &state.layers[layer_idx];
+        if layer.locks.pixels_locked() {
+            self.script_editor.add_console_line(
+                format!("Layer \"{}\" is locked ({})", layer.name, layer.locks.describe()),
+                crate::components::script_editor::ConsoleLineKind::Error,
+            );
+            return;
+        }
         let w = state.width;
         let h = state.height;
 
@@ -863,6 +1092,14 @@ impl PaintFEApp {
         }
 
         let layer = &state.layers[layer_idx];
+        if layer.locks.pixels_locked() {
+            log_info!(
+                "Custom effect {} blocked, layer \"{}\" is locked",
+                name,
+                layer.name
+            );
+            return;
+        }
         let w = state.width;
         let h = state.height;
 
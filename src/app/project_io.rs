@@ -207,7 +207,27 @@ impl PaintFEApp {
     fn poll_web_open_picker(&mut self) {
         for (name, bytes) in crate::web_bridge::drain_pending_opens() {
             let is_pfe = name.to_lowercase().ends_with(".pfe");
-            if is_pfe {
+            let ext = std::path::Path::new(&name)
+                .extension()
+                .map(|e| e.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if let Some(result) = crate::io::parse_layered_document(&ext, &bytes) {
+                let path = std::path::PathBuf::from(&name);
+                match result {
+                    Ok(canvas_state) => {
+                        let _ = self
+                            .io_sender
+                            .send(IoResult::LayeredLoaded { canvas_state, path });
+                    }
+                    Err(e) => {
+                        let _ = self.io_sender.send(IoResult::LoadFailed {
+                            path: Some(path),
+                            error: format!("Failed to open layered document: {}", e),
+                        });
+                    }
+                }
+                self.pending_io_ops += 1;
+            } else if is_pfe {
                 let path = std::path::PathBuf::from(&name);
                 match crate::io::load_pfe_from_bytes(&bytes) {
                     Ok(canvas_state) => {
@@ -245,6 +265,37 @@ impl PaintFEApp {
             return;
         }
 
+        // SVG asks for a raster size first; the dialog starts the load.
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"))
+        {
+            if self.pending_open_paths.contains(&normalized_path) {
+                return;
+            }
+            match std::fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|data| crate::svg_io::SvgDocument::parse(&data))
+            {
+                Ok(document) => {
+                    self.active_dialog =
+                        ActiveDialog::SvgImport(crate::ops::dialogs::SvgImportDialog::new(
+                            document,
+                            path,
+                            crate::ops::dialogs::SvgImportMode::NewDocument,
+                        ));
+                }
+                Err(e) => {
+                    self.pending_io_ops += 1;
+                    let _ = self.io_sender.send(IoResult::LoadFailed {
+                        path: Some(path),
+                        error: format!("Failed to open SVG: {}", e),
+                    });
+                }
+            }
+            return;
+        }
+
         if !self.pending_open_paths.insert(normalized_path) {
             return;
         }
@@ -256,6 +307,26 @@ impl PaintFEApp {
         let is_pdn = path
             .extension()
             .is_some_and(|ext| ext.eq_ignore_ascii_case("pdn"));
+        // Layered and float documents go through load_image_sync so layers
+        // and deep pixels survive.
+        let is_layered = path.extension().is_some_and(|ext| {
+            matches!(
+                ext.to_string_lossy().to_lowercase().as_str(),
+                "psd"
+                    | "psb"
+                    | "ora"
+                    | "exr"
+                    | "hdr"
+                    | "ico"
+                    | "tif"
+                    | "tiff"
+                    | "dds"
+                    | "pgm"
+                    | "ppm"
+                    | "pam"
+                    | "pnm"
+            )
+        });
 
         if is_pfe {
             // Open .pfe project file in background (preserves layers)
@@ -275,6 +346,25 @@ impl PaintFEApp {
                     });
                 }
             });
+        } else if is_layered {
+            // Open PSD/OpenRaster/EXR/HDR/ICO/TIFF/DDS/Netpbm in background (preserves layers
+            // and float or 16-bit data)
+            let sender = self.io_sender.clone();
+            if self.pending_io_ops == 0 {
+                self.io_ops_start_time = Some(current_time);
+            }
+            self.pending_io_ops += 1;
+            crate::par_compat::spawn(move || match crate::io::load_image_sync(&path) {
+                Ok(canvas_state) => {
+                    let _ = sender.send(IoResult::LayeredLoaded { canvas_state, path });
+                }
+                Err(e) => {
+                    let _ = sender.send(IoResult::LoadFailed {
+                        path: Some(path),
+                        error: format!("Failed to open layered document: {}", e),
+                    });
+                }
+            });
         } else if is_pdn {
             #[cfg(not(target_arch = "wasm32"))]
             {
@@ -462,6 +552,7 @@ impl PaintFEApp {
                                 "ico" => SaveFormat::Ico,
                                 "tiff" | "tif" => SaveFormat::Tiff,
                                 "gif" => SaveFormat::Gif,
+                                "qoi" => SaveFormat::Qoi,
                                 _ => SaveFormat::Png,
                             })
                             .unwrap_or(SaveFormat::Png);
@@ -499,6 +590,9 @@ impl PaintFEApp {
         if has_path {
             let project = &mut self.projects[idx];
             let is_pfe = project.file_handler.last_format == SaveFormat::Pfe;
+            let format = project.file_handler.last_format;
+            let layered_options = project.file_handler.layered_options();
+            let is_layered = crate::io::LayeredExport::handles(format, &layered_options);
             let is_animated = project.file_handler.last_animated
                 && project.file_handler.last_format.supports_animation();
 
@@ -532,6 +626,43 @@ impl PaintFEApp {
                         }
                     });
                 }
+            } else if is_layered {
+                // Layered interchange and icon save — snapshot layers, encode in background
+                project.canvas_state.ensure_all_text_layers_rasterized();
+                let export = crate::io::LayeredExport::build(
+                    format,
+                    &project.canvas_state,
+                    &layered_options,
+                );
+                let quality = layered_options.quality;
+                let tiff_compression = layered_options.tiff_compression;
+                let path = project.file_handler.current_path.clone();
+                if let (Some(path), Some(export)) = (path, export) {
+                    let sender = self.io_sender.clone();
+                    if self.pending_io_ops == 0 {
+                        self.io_ops_start_time = Some(current_time);
+                    }
+                    self.pending_io_ops += 1;
+                    crate::par_compat::spawn(move || match export.write(&path) {
+                        Ok(()) => {
+                            let _ = sender.send(IoResult::SaveComplete {
+                                project_index: idx,
+                                path,
+                                format,
+                                quality,
+                                webp_lossless: true,
+                                tiff_compression,
+                                update_project_path: false,
+                            });
+                        }
+                        Err(e) => {
+                            let _ = sender.send(IoResult::SaveFailed {
+                                project_index: idx,
+                                error: format!("{}", e),
+                            });
+                        }
+                    });
+                }
             } else if is_animated {
                 // Quick-save animated format (include all layers, even hidden)
                 project.canvas_state.ensure_all_text_layers_rasterized();
@@ -678,6 +809,277 @@ impl PaintFEApp {
         self.save_file_dialog.open = true;
     }
 
+    /// Open File > Export As for the active project. The destination starts at
+    /// the last export folder, falling back to the project's own folder.
+    fn open_export_as_dialog(&mut self) {
+        let Some(project) = self.active_project() else {
+            return;
+        };
+        let base_name = match &project.path {
+            Some(path) => path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| project.name.clone()),
+            None => project.name.clone(),
+        };
+        let directory = self.default_export_directory(project);
+        self.active_dialog = ActiveDialog::ExportAs(crate::ops::dialogs::ExportAsDialog::new(
+            crate::config::export_presets::load_export_presets(),
+            &self.settings.persist_export_presets,
+            directory,
+            base_name,
+        ));
+    }
+
+    /// Run an export on a background thread as a pending IO op. `job`
+    /// returns the files it wrote; the outcome comes back as an
+    /// `IoResult::ExportComplete`/`ExportFailed` toast.
+    fn spawn_export(
+        &mut self,
+        current_time: f64,
+        job: impl FnOnce() -> Result<Vec<PathBuf>, String> + Send + 'static,
+    ) {
+        let sender = self.io_sender.clone();
+        if self.pending_io_ops == 0 {
+            self.io_ops_start_time = Some(current_time);
+        }
+        self.pending_io_ops += 1;
+        crate::par_compat::spawn(move || {
+            let _ = sender.send(match job() {
+                Ok(paths) => IoResult::ExportComplete { paths },
+                Err(error) => IoResult::ExportFailed { error },
+            });
+        });
+    }
+
+    /// Write the active project once per preset into `dir` on a background thread.
+    fn export_with_presets(
+        &mut self,
+        current_time: f64,
+        presets: &[crate::config::export_presets::ExportPreset],
+        dir: PathBuf,
+        base_name: &str,
+    ) {
+        let Some(project) = self.active_project_mut() else {
+            return;
+        };
+        project.canvas_state.ensure_all_text_layers_rasterized();
+        let mut jobs = Vec::new();
+        let mut errors = Vec::new();
+        for preset in presets {
+            match crate::io::PresetExport::build(&project.canvas_state, preset) {
+                Ok(export) => jobs.push((export, preset.output_path(&dir, base_name))),
+                Err(e) => errors.push(format!("{}: {}", preset.name, e)),
+            }
+        }
+        self.spawn_export(current_time, move || {
+            let mut written = Vec::new();
+            for (export, path) in jobs {
+                match export.write(&path) {
+                    Ok(()) => written.push(path),
+                    Err(e) => errors.push(format!("{}: {}", export.preset().name, e)),
+                }
+            }
+            if errors.is_empty() {
+                Ok(written)
+            } else {
+                Err(errors.join("; "))
+            }
+        });
+    }
+
+    fn open_export_layers_dialog(&mut self) {
+        let Some(project) = self.active_project() else {
+            return;
+        };
+        let doc_name = match &project.path {
+            Some(path) => path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| project.name.clone()),
+            None => project.name.clone(),
+        };
+        let directory = self.default_export_directory(project);
+        let sample_layer = project
+            .canvas_state
+            .layers
+            .last()
+            .map(|l| l.name.clone())
+            .unwrap_or_default();
+        self.active_dialog =
+            ActiveDialog::ExportLayers(crate::ops::dialogs::ExportLayersDialog::new(
+                crate::config::export_presets::load_export_presets(),
+                directory,
+                doc_name,
+                sample_layer,
+            ));
+    }
+
+    /// Snapshot the active project for an export that renders off the UI
+    /// thread, so the live document is never touched by it.
+    fn export_snapshot(&mut self) -> Option<crate::components::history::CanvasSnapshot> {
+        let project = self.active_project_mut()?;
+        project.canvas_state.ensure_all_text_layers_rasterized();
+        Some(crate::components::history::CanvasSnapshot::capture(
+            &project.canvas_state,
+        ))
+    }
+
+    /// Write each layer or top-level group of the active project into `dir`
+    /// on a background thread.
+    fn export_layers(
+        &mut self,
+        current_time: f64,
+        dir: PathBuf,
+        doc_name: String,
+        options: crate::ops::layer_export::LayerExportOptions,
+    ) {
+        let Some(snapshot) = self.export_snapshot() else {
+            return;
+        };
+        self.spawn_export(current_time, move || {
+            crate::ops::layer_export::export_layers(
+                &mut snapshot.to_canvas_state(),
+                &dir,
+                &doc_name,
+                &options,
+            )
+        });
+    }
+
+    /// Destination folder for exports that write several files: the last
+    /// one used, else the project's folder.
+    fn default_export_directory(&self, project: &Project) -> String {
+        if self.settings.persist_export_directory.is_empty() {
+            project
+                .path
+                .as_ref()
+                .and_then(|p| p.parent())
+                .map(|d| d.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        } else {
+            self.settings.persist_export_directory.clone()
+        }
+    }
+
+    fn open_export_slices_dialog(&mut self) {
+        let Some(project) = self.active_project() else {
+            return;
+        };
+        let doc_name = match &project.path {
+            Some(path) => path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| project.name.clone()),
+            None => project.name.clone(),
+        };
+        let directory = self.default_export_directory(project);
+        let slice_count = project.canvas_state.slices.len();
+        self.active_dialog =
+            ActiveDialog::ExportSlices(crate::ops::dialogs::ExportSlicesDialog::new(
+                crate::config::export_presets::load_export_presets(),
+                directory,
+                doc_name,
+                slice_count,
+            ));
+    }
+
+    /// Write each slice of the active project into `dir` on a background
+    /// thread.
+    fn export_slices(
+        &mut self,
+        current_time: f64,
+        dir: PathBuf,
+        options: crate::ops::slices::SliceExportOptions,
+    ) {
+        let Some(snapshot) = self.export_snapshot() else {
+            return;
+        };
+        self.spawn_export(current_time, move || {
+            crate::ops::slices::export_slices(&mut snapshot.to_canvas_state(), &dir, &options)
+        });
+    }
+
+    /// Pack the active project's slices or layers into `<name>.png` plus a
+    /// JSON atlas in `dir`, on a background thread.
+    fn export_sprite_sheet(
+        &mut self,
+        current_time: f64,
+        dir: PathBuf,
+        name: String,
+        options: crate::ops::slices::SpriteSheetOptions,
+    ) {
+        let Some(snapshot) = self.export_snapshot() else {
+            return;
+        };
+        self.spawn_export(current_time, move || {
+            crate::ops::slices::export_sprite_sheet(
+                &mut snapshot.to_canvas_state(),
+                &dir,
+                &name,
+                &options,
+            )
+        });
+    }
+
+    /// Pick an image and open the sprite sheet import dialog for it.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_import_sprite_sheet_dialog(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Images", &["png", "gif", "webp", "bmp", "tga", "qoi"])
+            .pick_file()
+        else {
+            return;
+        };
+        let sheet = image::open(&path)
+            .map(|image| image.to_rgba8())
+            .map_err(|e| e.to_string())
+            .and_then(|sheet| {
+                crate::io::validate_open_dimensions(sheet.width(), sheet.height())?;
+                Ok(sheet)
+            });
+        let sheet = match sheet {
+            Ok(sheet) => sheet,
+            Err(e) => {
+                eprintln!("Could not open sprite sheet '{}': {}", path.display(), e);
+                self.push_toast(
+                    t!("toast.open_failed", error = format!("{}: {}", path.display(), e)),
+                    true,
+                );
+                return;
+            }
+        };
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "Sprite Sheet".to_string());
+        self.active_dialog = ActiveDialog::ImportSpriteSheet(
+            crate::ops::dialogs::ImportSpriteSheetDialog::new(sheet, name),
+        );
+    }
+
+    /// Open `canvas_state` in a new untitled tab called `name`.
+    fn open_canvas_as_new_project(&mut self, canvas_state: CanvasState, name: &str) {
+        self.persist_active_project_view();
+        self.untitled_counter += 1;
+        let mut project = Project::new_untitled(
+            self.untitled_counter,
+            canvas_state.width,
+            canvas_state.height,
+        );
+        project.canvas_state = canvas_state;
+        project.canvas_state.composite_cache = None;
+        project.canvas_state.mark_dirty(None);
+        if !name.trim().is_empty() {
+            project.name = name.trim().to_string();
+        }
+        self.projects.push(project);
+        self.active_project_index = self.projects.len() - 1;
+        self.restore_active_project_view();
+        self.canvas.gpu_clear_layers();
+        self.maybe_close_initial_blank();
+    }
+
     /// Save a specific project by index (only if it has a path — skips dialog).
     /// Returns true if a background save was launched.
     fn handle_save_project(&mut self, idx: usize, current_time: f64) -> bool {
@@ -693,6 +1095,9 @@ impl PaintFEApp {
 
         let project = &mut self.projects[idx];
         let is_pfe = project.file_handler.last_format == SaveFormat::Pfe;
+        let format = project.file_handler.last_format;
+        let layered_options = project.file_handler.layered_options();
+        let is_layered = crate::io::LayeredExport::handles(format, &layered_options);
         let is_animated = project.file_handler.last_animated
             && project.file_handler.last_format.supports_animation();
 
@@ -725,6 +1130,40 @@ impl PaintFEApp {
                     }
                 });
             }
+        } else if is_layered {
+            // Layered interchange and icon save — snapshot layers, encode in background
+            project.canvas_state.ensure_all_text_layers_rasterized();
+            let export =
+                crate::io::LayeredExport::build(format, &project.canvas_state, &layered_options);
+            let quality = layered_options.quality;
+            let tiff_compression = layered_options.tiff_compression;
+            let path = project.file_handler.current_path.clone();
+            if let (Some(path), Some(export)) = (path, export) {
+                let sender = self.io_sender.clone();
+                if self.pending_io_ops == 0 {
+                    self.io_ops_start_time = Some(current_time);
+                }
+                self.pending_io_ops += 1;
+                crate::par_compat::spawn(move || match export.write(&path) {
+                    Ok(()) => {
+                        let _ = sender.send(IoResult::SaveComplete {
+                            project_index: idx,
+                            path,
+                            format,
+                            quality,
+                            webp_lossless: true,
+                            tiff_compression,
+                            update_project_path: false,
+                        });
+                    }
+                    Err(e) => {
+                        let _ = sender.send(IoResult::SaveFailed {
+                            project_index: idx,
+                            error: format!("{}", e),
+                        });
+                    }
+                });
+            }
         } else if is_animated {
             project.canvas_state.ensure_all_text_layers_rasterized();
             let frames: Vec<image::RgbaImage> = project
@@ -839,6 +1278,91 @@ impl PaintFEApp {
             self.handle_save_project(i, current_time);
         }
     }
+
+    /// Reopen the autosaves chosen in the recovery dialog and delete the rest.
+    ///
+    /// Restored projects keep their autosave's project id so later auto-save
+    /// ticks overwrite the same entry and a clean close removes it.
+    fn apply_recovery_decision(&mut self, decision: crate::components::dialogs::RecoveryDecision) {
+        let Some(dir) = crate::io::autosave_dir() else {
+            return;
+        };
+        for entry in &decision.discard {
+            crate::io::remove_autosave(&dir, &entry.manifest.project_id);
+        }
+        let mut restored = 0;
+        for entry in decision.restore {
+            let manifest = entry.manifest;
+            let mut canvas_state = match crate::io::load_pfe(&entry.pfe_path) {
+                Ok(state) => state,
+                Err(e) => {
+                    crate::log_err!(
+                        "Recovery: failed to load autosave {:?}: {}",
+                        entry.pfe_path,
+                        e
+                    );
+                    continue;
+                }
+            };
+            canvas_state.composite_cache = None;
+            canvas_state.mark_dirty(None);
+
+            let mut project = match manifest.original_path.clone() {
+                Some(path) => {
+                    let file_handler = FileHandler {
+                        last_format: path
+                            .extension()
+                            .and_then(|e| e.to_str())
+                            .and_then(SaveFormat::from_extension)
+                            .unwrap_or(SaveFormat::Png),
+                        current_path: Some(path.clone()),
+                        ..FileHandler::new()
+                    };
+                    Project::from_file(path, canvas_state, file_handler)
+                }
+                None => {
+                    self.untitled_counter += 1;
+                    let mut project = Project::new_untitled(
+                        self.untitled_counter,
+                        canvas_state.width,
+                        canvas_state.height,
+                    );
+                    project.canvas_state = canvas_state;
+                    project
+                }
+            };
+            project.name = manifest.name.clone();
+            project.is_dirty = manifest.is_dirty;
+            if let Ok(id) = uuid::Uuid::parse_str(&manifest.project_id) {
+                project.id = id;
+            }
+            log_info!(
+                "Recovery: restored \"{}\" from {:?}",
+                manifest.name,
+                entry.pfe_path
+            );
+
+            self.persist_active_project_view();
+            self.projects.push(project);
+            self.active_project_index = self.projects.len() - 1;
+            self.restore_active_project_view();
+            self.canvas.gpu_clear_layers();
+            restored += 1;
+        }
+        // A restored session replaces the blank startup canvas, same as
+        // opening files from the command line.
+        if restored > 0 {
+            self.close_initial_blank = true;
+            self.maybe_close_initial_blank();
+        }
+    }
+
+    /// Remove the autosave of a project that is being closed cleanly.
+    fn discard_project_autosave(&self, index: usize) {
+        if let (Some(project), Some(dir)) = (self.projects.get(index), crate::io::autosave_dir()) {
+            crate::io::remove_autosave(&dir, &project.id.to_string());
+        }
+    }
 }
 
 // --- Operation Helpers (snapshot undo for menus) ---
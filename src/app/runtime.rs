@@ -16,6 +16,16 @@ impl eframe::App for PaintFEApp {
         self.show_runtime_dialogs_menu(&ctx, ui);
         self.show_runtime_canvas_tail(&ctx, ui, modal_open);
     }
+
+    /// The app is closing normally, so its autosaves are no longer needed for
+    /// crash recovery.
+    fn on_exit(&mut self) {
+        if let Some(dir) = crate::io::autosave_dir() {
+            for project in &self.projects {
+                crate::io::remove_autosave(&dir, &project.id.to_string());
+            }
+        }
+    }
 }
 
 include!("runtime/update/lifecycle_async.rs");
@@ -191,6 +191,9 @@ impl PaintFEApp {
             self.settings.save();
         }
 
+        self.process_brush_preset_actions(ctx);
+        self.process_gradient_library_actions();
+
         if self.tools_panel.pending_open_add_shape {
             self.tools_panel.pending_open_add_shape = false;
             let cats: Vec<String> = self.assets.custom_shape_categories()
@@ -231,6 +234,10 @@ impl PaintFEApp {
             }
         }
 
+        if let Some(action) = self.tools_panel.slice_state.pending_action.take() {
+            self.handle_slice_action(action);
+        }
+
         // --- Full-Screen Canvas (CentralPanel fills remaining space) ---
         let canvas_bg_top = self.theme.canvas_bg_top;
         let canvas_bg_bottom = self.theme.canvas_bg_bottom;
@@ -480,6 +487,7 @@ impl PaintFEApp {
         self.show_floating_history_panel(ctx, screen_size_changed);
         self.show_floating_colors_panel(ctx, screen_size_changed);
         self.show_floating_palette_panel(ctx, screen_size_changed);
+        self.show_floating_layer_comps_panel(ctx);
         self.show_floating_script_editor(ctx, screen_size_changed);
         self.publish_ui_cursor_blocking_rects();
         if self.palette_reposition_settle_frames > 0 {
@@ -524,6 +532,9 @@ impl PaintFEApp {
             }
         }
 
+        // --- Toasts (bottom-right outcome messages for background IO) ---
+        self.show_toasts(ctx);
+
         // Update last_screen_size AFTER all panels have used the flag
         self.last_screen_size = (screen_w, screen_h);
 
@@ -46,6 +46,19 @@ impl PaintFEApp {
                             self.handle_open_file(ctx.input(|i| i.time));
                             ui.close();
                         }
+                        #[cfg(not(target_arch = "wasm32"))]
+                        if self
+                            .assets
+                            .menu_item(
+                                ui,
+                                Icon::MenuFileOpen,
+                                &t!("menu.file.import_sprite_sheet"),
+                            )
+                            .clicked()
+                        {
+                            self.open_import_sprite_sheet_dialog();
+                            ui.close();
+                        }
                         ui.separator();
                         if self
                             .assets
@@ -143,6 +156,48 @@ impl PaintFEApp {
                             self.save_file_dialog.open = true;
                             ui.close();
                         }
+                        if self
+                            .assets
+                            .menu_item_enabled(
+                                ui,
+                                Icon::MenuFileSaveAs,
+                                &t!("menu.file.export_as"),
+                                has_project,
+                            )
+                            .clicked()
+                        {
+                            self.open_export_as_dialog();
+                            ui.close();
+                        }
+                        // Writes a folder of files, which the web build can't.
+                        #[cfg(not(target_arch = "wasm32"))]
+                        if self
+                            .assets
+                            .menu_item_enabled(
+                                ui,
+                                Icon::MenuFileSaveAs,
+                                &t!("menu.file.export_layers"),
+                                has_project,
+                            )
+                            .clicked()
+                        {
+                            self.open_export_layers_dialog();
+                            ui.close();
+                        }
+                        #[cfg(not(target_arch = "wasm32"))]
+                        if self
+                            .assets
+                            .menu_item_enabled(
+                                ui,
+                                Icon::MenuFileSaveAs,
+                                &t!("menu.file.export_slices"),
+                                has_project,
+                            )
+                            .clicked()
+                        {
+                            self.open_export_slices_dialog();
+                            ui.close();
+                        }
                         ui.separator();
                         if self
                             .assets
@@ -157,9 +212,12 @@ impl PaintFEApp {
                             if let Some(project) = self.active_project_mut() {
                                 project.canvas_state.ensure_all_text_layers_rasterized();
                                 let composite = project.canvas_state.composite();
-                                if let Err(e) = crate::ops::print::print_image(&composite) {
-                                    eprintln!("Print error: {}", e);
-                                }
+                                self.active_dialog = ActiveDialog::PageSetup(
+                                    crate::ops::dialogs::PageSetupDialog::new(
+                                        self.settings.persist_page_setup.clone(),
+                                        composite,
+                                    ),
+                                );
                             }
                             ui.close();
                         }
@@ -483,6 +541,108 @@ impl PaintFEApp {
                             });
                             ui.close();
                         }
+                        if self
+                            .assets
+                            .menu_item_enabled(
+                                ui,
+                                Icon::MenuCanvasCrop,
+                                &t!("menu.canvas.trim"),
+                                no_dialog,
+                            )
+                            .clicked()
+                        {
+                            if let Some(project) = self.active_project() {
+                                self.active_dialog = ActiveDialog::Trim(
+                                    crate::ops::dialogs::TrimDialog::new(&project.canvas_state),
+                                );
+                            }
+                            ui.close();
+                        }
+                        if self
+                            .assets
+                            .menu_item(
+                                ui,
+                                Icon::MenuCanvasCrop,
+                                &t!("menu.canvas.crop_to_content"),
+                            )
+                            .clicked()
+                        {
+                            self.do_snapshot_op_if_changed("Crop to Layer Content", |s| {
+                                let index = s.active_layer_index;
+                                crate::ops::trim::crop_to_layer_content(s, index)
+                            });
+                            ui.close();
+                        }
+                        if self
+                            .assets
+                            .menu_item(
+                                ui,
+                                Icon::MenuCanvasCrop,
+                                &t!("menu.canvas.auto_straighten"),
+                            )
+                            .clicked()
+                        {
+                            self.do_snapshot_op_if_changed("Auto Straighten", |s| {
+                                crate::ops::trim::auto_straighten_and_crop(
+                                    s,
+                                    crate::ops::trim::DEFAULT_SCAN_TOLERANCE,
+                                )
+                                .is_some()
+                            });
+                            ui.close();
+                        }
+                        let has_off_canvas = self.active_project().is_some_and(|p| {
+                            p.canvas_state.layers.iter().any(|l| l.off_canvas.is_some())
+                        });
+                        if self
+                            .assets
+                            .menu_item_enabled(
+                                ui,
+                                Icon::MenuCanvasResize,
+                                &t!("menu.canvas.reveal_all"),
+                                has_off_canvas,
+                            )
+                            .clicked()
+                        {
+                            self.do_snapshot_op("Reveal All", |s| {
+                                crate::ops::transform::reveal_all(s);
+                            });
+                            ui.close();
+                        }
+                        let infinite_on = self
+                            .active_project()
+                            .is_some_and(|p| p.canvas_state.document_bounds.is_some());
+                        let mut infinite_checked = infinite_on;
+                        if ui
+                            .checkbox(&mut infinite_checked, t!("menu.canvas.infinite_canvas"))
+                            .on_hover_text(t!("menu.canvas.infinite_canvas.tooltip"))
+                            .changed()
+                        {
+                            self.do_snapshot_op("Infinite Canvas", |s| {
+                                crate::ops::transform::set_infinite_canvas(s, infinite_checked);
+                            });
+                        }
+                        let mut linear_checked = self
+                            .active_project()
+                            .is_some_and(|p| p.canvas_state.linear_blending);
+                        if ui
+                            .checkbox(&mut linear_checked, t!("menu.canvas.linear_blending"))
+                            .on_hover_text(t!("menu.canvas.linear_blending.tooltip"))
+                            .changed()
+                            && let Some(project) = self.active_project_mut()
+                        {
+                            // Only a document flag: no need to rasterize text
+                            // layers the way `do_snapshot_op` does.
+                            let mut cmd = crate::components::history::SnapshotCommand::new(
+                                "Linear Blending".to_string(),
+                                &project.canvas_state,
+                            );
+                            project.canvas_state.linear_blending = linear_checked;
+                            project.canvas_state.mark_dirty(None);
+                            cmd.set_after(&project.canvas_state);
+                            project.history.push(Box::new(cmd));
+                            project.mark_dirty();
+                        }
                         ui.separator();
                         if self
                             .assets
@@ -931,7 +1091,8 @@ impl PaintFEApp {
                                 self.active_dialog = ActiveDialog::GradientMap(
                                     crate::ops::dialogs::GradientMapDialog::new(
                                         &project.canvas_state,
-                                    ),
+                                    )
+                                    .with_library(self.tools_panel.gradient_library.clone()),
                                 );
                             }
                             ui.close();
@@ -1676,6 +1837,10 @@ impl PaintFEApp {
                             t!("menu.view.colors_panel"),
                         );
                         ui.checkbox(&mut self.window_visibility.palette, "Palette Panel");
+                        ui.checkbox(
+                            &mut self.window_visibility.layer_comps,
+                            t!("menu.view.layer_comps_panel"),
+                        );
                         // Script Editor needs a native file picker to load .rhai
                         // scripts from disk — not available on web, so don't
                         // offer the menu entry at all there.
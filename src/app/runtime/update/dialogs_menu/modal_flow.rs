@@ -24,6 +24,10 @@ impl PaintFEApp {
 
         self.process_active_dialog(ctx);
 
+        if let Some(decision) = self.recovery_dialog.show(ctx) {
+            self.apply_recovery_decision(decision);
+        }
+
         // Paste size confirmation (when clipboard image exceeds current canvas bounds)
         if let Some(req) = self.pending_paste_request.as_ref() {
             let mut do_resize = false;
@@ -293,6 +297,17 @@ impl PaintFEApp {
             save_dialog_confirmed = true;
             let project_index = self.active_project_index;
             if project_index < self.projects.len() {
+                let layered_options = crate::io::LayeredExportOptions {
+                    icon: action.icon.clone(),
+                    tiff_pages: action.tiff_pages,
+                    tiff_compression: action.tiff_compression,
+                    quality: action.quality,
+                    dds: action.dds.clone(),
+                    jpeg: action.jpeg.clone(),
+                };
+                self.projects[project_index].file_handler.last_tiff_pages = action.tiff_pages;
+                self.projects[project_index].file_handler.last_dds = action.dds.clone();
+                self.projects[project_index].file_handler.last_jpeg = action.jpeg.clone();
                 if action.format == SaveFormat::Pfe {
                     let project = &mut self.projects[project_index];
                     project.canvas_state.ensure_all_text_layers_rasterized();
@@ -324,6 +339,51 @@ impl PaintFEApp {
                             });
                         }
                     });
+                } else if crate::io::LayeredExport::handles(action.format, &layered_options) {
+                    let project = &mut self.projects[project_index];
+                    project.canvas_state.ensure_all_text_layers_rasterized();
+                    let export = crate::io::LayeredExport::build(
+                        action.format,
+                        &project.canvas_state,
+                        &layered_options,
+                    );
+                    project.file_handler.last_icon = action.icon.clone();
+                    let format = action.format;
+                    let quality = action.quality;
+                    let tiff_compression = action.tiff_compression;
+                    let path = action.path.clone();
+
+                    let sender = self.io_sender.clone();
+                    if self.pending_io_ops == 0 {
+                        self.io_ops_start_time = Some(ctx.input(|i| i.time));
+                    }
+                    self.pending_io_ops += 1;
+
+                    crate::par_compat::spawn(move || match export.map(|e| e.write(&path)) {
+                        Some(Ok(())) => {
+                            let _ = sender.send(IoResult::SaveComplete {
+                                project_index,
+                                path,
+                                format,
+                                quality,
+                                webp_lossless: true,
+                                tiff_compression,
+                                update_project_path: true,
+                            });
+                        }
+                        Some(Err(e)) => {
+                            let _ = sender.send(IoResult::SaveFailed {
+                                project_index,
+                                error: format!("{}", e),
+                            });
+                        }
+                        None => {
+                            let _ = sender.send(IoResult::SaveFailed {
+                                project_index,
+                                error: format!("{} has no layered export", format.extension()),
+                            });
+                        }
+                    });
                 } else if action.animated && action.format.supports_animation() {
                     let project = &mut self.projects[project_index];
                     project.canvas_state.ensure_all_text_layers_rasterized();
@@ -1287,7 +1287,8 @@ impl PaintFEApp {
                     && let Some(project) = self.active_project()
                 {
                     self.active_dialog = ActiveDialog::GradientMap(
-                        crate::ops::dialogs::GradientMapDialog::new(&project.canvas_state),
+                        crate::ops::dialogs::GradientMapDialog::new(&project.canvas_state)
+                            .with_library(self.tools_panel.gradient_library.clone()),
                     );
                 }
                 if kb.is_pressed(ctx, BindableAction::ColorBlackAndWhite)
@@ -47,6 +47,14 @@ impl PaintFEApp {
                 ctx.send_viewport_cmd(egui::ViewportCommand::Maximized(true));
             }
 
+            // Autosaves are removed on clean close, so anything left over
+            // that no running instance owns belongs to a session that
+            // crashed — offer to restore it.
+            if let Some(dir) = crate::io::autosave_dir() {
+                self.recovery_dialog
+                    .open_with(crate::io::list_autosaves(&dir));
+            }
+
             // Open files passed as positional arguments (e.g. right-click → "Open with PaintFE")
             let files = std::mem::take(&mut self.pending_startup_files);
             let current_time = ctx.input(|i| i.time);
@@ -77,46 +85,60 @@ impl PaintFEApp {
         }
 
         // --- Auto-save tick ---
-        // Saves every open project as a .autosave.pfe in the platform data dir.
+        // Saves every open project as <project id>.autosave.pfe (plus a JSON
+        // manifest and thumbnail for crash recovery) in the platform data dir.
         // Controlled by settings.auto_save_minutes (0 = disabled).
         {
             let interval_secs = (self.settings.auto_save_minutes as u64) * 60;
             if interval_secs > 0 && self.last_autosave.elapsed().as_secs() >= interval_secs {
                 self.last_autosave = crate::time_compat::Instant::now();
                 if let Some(dir) = crate::io::autosave_dir() {
-                    let _ = std::fs::create_dir_all(&dir);
+                    let saved_at = crate::io::unix_time_now();
                     for project in &self.projects {
-                        // Sanitize project name into a safe filename component
-                        let safe_name: String = project
-                            .name
-                            .chars()
-                            .map(|c| {
-                                if c.is_alphanumeric() || c == '-' || c == '_' {
-                                    c
-                                } else {
-                                    '_'
-                                }
-                            })
-                            .collect();
-                        let path = dir.join(format!("{}.autosave.pfe", safe_name));
+                        let manifest = crate::io::AutosaveManifest {
+                            project_id: project.id.to_string(),
+                            session: self
+                                .autosave_session
+                                .as_ref()
+                                .map(|s| s.id().to_string()),
+                            name: project.name.clone(),
+                            original_path: project.path.clone(),
+                            is_dirty: project.is_dirty,
+                            saved_at,
+                            width: project.canvas_state.width,
+                            height: project.canvas_state.height,
+                        };
                         let pfe_data = crate::io::build_pfe(&project.canvas_state);
-                        let proj_name = project.name.clone();
+                        let thumbnail = crate::io::autosave_thumbnail(&project.canvas_state);
+                        let dir = dir.clone();
                         crate::par_compat::spawn(move || {
-                            match crate::io::write_pfe(&pfe_data, &path) {
+                            match crate::io::write_autosave(
+                                &dir,
+                                &manifest,
+                                &pfe_data,
+                                Some(&thumbnail),
+                            ) {
                                 Ok(()) => {
                                     crate::logger::write(
                                         "INFO",
                                         &format!(
                                             "Auto-save OK  \"{}\"  →  {}",
-                                            proj_name,
-                                            path.display()
+                                            manifest.name,
+                                            crate::io::autosave_pfe_path(
+                                                &dir,
+                                                &manifest.project_id
+                                            )
+                                            .display()
                                         ),
                                     );
                                 }
                                 Err(e) => {
                                     crate::logger::write(
                                         "ERROR",
-                                        &format!("Auto-save FAILED for \"{}\": {}", proj_name, e),
+                                        &format!(
+                                            "Auto-save FAILED for \"{}\": {}",
+                                            manifest.name, e
+                                        ),
                                     );
                                 }
                             }
@@ -178,15 +200,17 @@ impl PaintFEApp {
                 result.before.restore_into(state);
                 let mut cmd = SnapshotCommand::new(result.description, state);
                 // Apply result layers
-                for (i, tiled) in result.result_layers.into_iter().enumerate() {
+                for (i, (tiled, off_canvas)) in result.result_layers.into_iter().enumerate() {
                     if i < state.layers.len() {
                         state.layers[i].pixels = tiled;
+                        state.layers[i].off_canvas = off_canvas;
                         state.layers[i].invalidate_lod();
                         state.layers[i].gpu_generation += 1;
                     }
                 }
                 state.width = result.new_width;
                 state.height = result.new_height;
+                state.document_bounds = result.document_bounds;
                 state.composite_cache = None;
                 state.clear_preview_state();
                 state.mark_dirty(None);
@@ -240,17 +264,31 @@ impl PaintFEApp {
                     {
                         // Restore original pixels so snapshot captures the true before-state
                         project.canvas_state.layers[layer_idx].pixels = original_pixels;
+                        let locks = project.canvas_state.layers[layer_idx].locks;
 
-                        if canvas_ops.is_empty() {
+                        if !canvas_ops.is_empty() && locks.position_locked() {
+                            self.script_editor.add_console_line(
+                                "Script result discarded: layer position is locked".to_string(),
+                                crate::components::script_editor::ConsoleLineKind::Error,
+                            );
+                            project.canvas_state.mark_dirty(None);
+                        } else if canvas_ops.is_empty() {
                             // Layer-only script: lightweight single-layer snapshot
                             let mut cmd = SingleLayerSnapshotCommand::new_for_layer(
                                 "Script".to_string(),
                                 &project.canvas_state,
                                 layer_idx,
                             );
-                            let result_tiled =
-                                TiledImage::from_raw_rgba(width, height, &result_pixels);
-                            project.canvas_state.layers[layer_idx].pixels = result_tiled;
+                            let layer = &mut project.canvas_state.layers[layer_idx];
+                            if locks.transparency_locked()
+                                && let Some(result) =
+                                    image::RgbaImage::from_raw(width, height, result_pixels.clone())
+                            {
+                                layer.set_pixels_with_locks(&result);
+                            } else {
+                                layer.pixels =
+                                    TiledImage::from_raw_rgba(width, height, &result_pixels);
+                            }
                             cmd.set_after(&project.canvas_state);
                             project.history.push(Box::new(cmd));
                         } else {
@@ -387,6 +425,10 @@ impl PaintFEApp {
                         last_animation_fps: 10.0,
                         last_gif_colors: 256,
                         last_gif_dither: true,
+                        last_icon: crate::icon_io::IconOptions::default(),
+                        last_tiff_pages: false,
+                        last_dds: crate::dds_io::DdsOptions::default(),
+                        last_jpeg: crate::jpeg_io::JpegOptions::default(),
                     };
                     let project = Project::from_file(path, canvas_state, file_handler);
                     self.projects.push(project);
@@ -404,6 +446,7 @@ impl PaintFEApp {
                     }
                     log_info!("FileIO: load failed — {}", error);
                     eprintln!("Failed to open image: {}", error);
+                    self.push_toast(t!("toast.open_failed", error = error), true);
                 }
                 IoResult::SaveComplete {
                     project_index,
@@ -439,6 +482,7 @@ impl PaintFEApp {
                 } => {
                     log_info!("FileIO: save failed — {}", error);
                     eprintln!("Failed to save: {}", error);
+                    self.push_toast(t!("toast.save_failed", error = error), true);
                 }
                 IoResult::AnimatedLoaded {
                     tiled,
@@ -475,6 +519,10 @@ impl PaintFEApp {
                         last_animation_fps: fps,
                         last_gif_colors: 256,
                         last_gif_dither: true,
+                        last_icon: crate::icon_io::IconOptions::default(),
+                        last_tiff_pages: false,
+                        last_dds: crate::dds_io::DdsOptions::default(),
+                        last_jpeg: crate::jpeg_io::JpegOptions::default(),
                     };
                     let mut project = Project::from_file(path, canvas_state, file_handler);
                     project.was_animated = true;
@@ -512,6 +560,10 @@ impl PaintFEApp {
                                 webp_frame_compression:
                                     crate::canvas::WebpFrameCompression::default(),
                                 deep_pixels: None,
+                                locks: Default::default(),
+                                off_canvas: None,
+                                blend_if: crate::canvas::BlendIf::default(),
+                                move_offset: (0, 0),
                             };
                             project.canvas_state.layers.push(layer);
                         }
@@ -540,6 +592,52 @@ impl PaintFEApp {
                         last_animation_fps: 10.0,
                         last_gif_colors: 256,
                         last_gif_dither: true,
+                        last_icon: crate::icon_io::IconOptions::default(),
+                        last_tiff_pages: false,
+                        last_dds: crate::dds_io::DdsOptions::default(),
+                        last_jpeg: crate::jpeg_io::JpegOptions::default(),
+                    };
+                    let project = Project::from_file(path, canvas_state, file_handler);
+                    self.projects.push(project);
+                    self.persist_active_project_view();
+                    self.active_project_index = self.projects.len() - 1;
+                    self.restore_active_project_view();
+                    self.canvas.gpu_clear_layers();
+                    self.maybe_close_initial_blank();
+                }
+                IoResult::LayeredLoaded {
+                    mut canvas_state,
+                    path,
+                } => {
+                    self.pending_open_paths
+                        .remove(&Self::normalize_open_path(&path));
+                    log_info!("FileIO: layered document loaded — path={:?}", path);
+                    canvas_state.composite_cache = None;
+                    canvas_state.mark_dirty(None);
+
+                    // Formats PaintFE can write back (PSD, ORA, EXR, HDR, ICO, TIFF) keep their save target;
+                    // PSB opens like PDN, with Save going to Save As.
+                    let format = path
+                        .extension()
+                        .and_then(|e| SaveFormat::from_extension(&e.to_string_lossy()));
+                    let file_handler = match format {
+                        Some(format) => FileHandler {
+                            current_path: Some(path.clone()),
+                            last_format: format,
+                            last_quality: 90,
+                            last_tiff_compression: TiffCompression::None,
+                            last_webp_lossless: true,
+                            last_animated: false,
+                            last_animation_fps: 10.0,
+                            last_gif_colors: 256,
+                            last_gif_dither: true,
+                            last_icon: crate::icon_io::IconOptions::default(),
+                            // A multi-page TIFF saves back as pages.
+                            last_tiff_pages: canvas_state.layers.len() > 1,
+                            last_dds: crate::dds_io::DdsOptions::default(),
+                            last_jpeg: crate::jpeg_io::JpegOptions::default(),
+                        },
+                        None => FileHandler::new(),
                     };
                     let project = Project::from_file(path, canvas_state, file_handler);
                     self.projects.push(project);
@@ -570,6 +668,27 @@ impl PaintFEApp {
                     self.canvas.gpu_clear_layers();
                     self.maybe_close_initial_blank();
                 }
+                IoResult::ExportComplete { paths } => {
+                    log_info!("FileIO: exported {} files", paths.len());
+                    let text = match paths.as_slice() {
+                        [path] => t!("toast.exported_file", path = path.display()),
+                        _ => t!(
+                            "toast.exported_files",
+                            count = paths.len(),
+                            dir = paths
+                                .first()
+                                .and_then(|p| p.parent())
+                                .map(|d| d.display().to_string())
+                                .unwrap_or_default()
+                        ),
+                    };
+                    self.push_toast(text, false);
+                }
+                IoResult::ExportFailed { error } => {
+                    log_info!("FileIO: export failed — {}", error);
+                    eprintln!("Export failed: {}", error);
+                    self.push_toast(t!("toast.export_failed", error = error), true);
+                }
             }
         }
         if self.pending_io_ops > 0 {
@@ -19,8 +19,11 @@ pub struct CanvasOpResult {
     pub project_index: usize,
     /// Snapshot of the canvas state before the operation (for undo).
     pub before: crate::components::history::CanvasSnapshot,
-    /// The processed layers (one `TiledImage` per layer, in order).
-    pub result_layers: Vec<TiledImage>,
+    /// The processed layers (canvas pixels plus any off-canvas content per
+    /// layer, in order).
+    pub result_layers: Vec<(TiledImage, Option<crate::canvas::OffCanvasPixels>)>,
+    /// Document rectangle after the operation (infinite canvas mode).
+    pub document_bounds: Option<(u32, u32, u32, u32)>,
     /// New canvas dimensions after the operation.
     pub new_width: u32,
     pub new_height: u32,
@@ -109,11 +112,33 @@ pub enum IoResult {
         canvas_state: CanvasState,
         path: std::path::PathBuf,
     },
+    /// A PSD, OpenRaster, EXR or HDR document was loaded in background — layers,
+    /// folders and float pixels preserved.
+    LayeredLoaded {
+        canvas_state: CanvasState,
+        path: std::path::PathBuf,
+    },
     /// A read-only Paint.NET project import. Saving must use a PaintFE-supported format.
     PdnLoaded {
         canvas_state: CanvasState,
         path: std::path::PathBuf,
     },
+    /// A background export (Export As, layers, slices, comps) wrote `paths`.
+    ExportComplete { paths: Vec<std::path::PathBuf> },
+    /// A background export failed.
+    ExportFailed { error: String },
+}
+
+/// How long a toast stays up, in seconds.
+const TOAST_SECONDS: f64 = 4.0;
+
+/// A short message in the bottom-right corner reporting work that finished
+/// off the UI thread.
+struct Toast {
+    text: String,
+    is_error: bool,
+    /// Time the toast was first drawn; it expires [`TOAST_SECONDS`] later.
+    shown_at: Option<f64>,
 }
 
 pub struct PaintFEApp {
@@ -133,11 +158,14 @@ pub struct PaintFEApp {
     layers_panel: layers::LayersPanel,
     colors_panel: colors::ColorsPanel,
     palette_panel: palette::PalettePanel,
+    layer_comps_panel: layer_comps::LayerCompsPanel,
     history_panel: history::HistoryPanel,
 
     // Dialogs
     new_file_dialog: NewFileDialog,
     save_file_dialog: SaveFileDialog,
+    /// Startup crash-recovery prompt listing orphaned autosaves.
+    recovery_dialog: RecoveryDialog,
     settings_window: SettingsWindow,
     /// Web only: first-run welcome/beta-disclaimer popup, shown once per
     /// browser (dismissal persisted to localStorage).
@@ -185,6 +213,7 @@ pub struct PaintFEApp {
     history_panel_size: Option<(f32, f32)>,
     colors_panel_left_offset: Option<(f32, f32)>, // (x, offset_from_bottom)
     palette_panel_pos: Option<(f32, f32)>,        // (x, y)
+    layer_comps_panel_pos: Option<(f32, f32)>,    // (x, y)
     tools_panel_pos: Option<(f32, f32)>,          // (x, y) absolute
     last_screen_size: (f32, f32),
     ui_cursor_blocking_rects: Vec<egui::Rect>,
@@ -221,6 +250,8 @@ pub struct PaintFEApp {
     pending_io_ops: usize,
     /// Time when IO operations started (for elapsed time display)
     io_ops_start_time: Option<f64>,
+    /// Outcome messages of background IO, newest last.
+    toasts: Vec<Toast>,
     /// Whether ONNX Runtime is available (both DLL and model configured + DLL probed OK)
     onnx_available: bool,
     /// Cached ONNX paths used for last probe (re-probe only when changed)
@@ -256,6 +287,9 @@ pub struct PaintFEApp {
 
     /// Instant of the last auto-save tick (used to measure the interval).
     last_autosave: crate::time_compat::Instant,
+    /// Lock marking this instance's autosaves as owned; `None` when the
+    /// autosave directory is unavailable.
+    autosave_session: Option<crate::io::AutosaveSession>,
 
     /// True only on the very first update() call — used to send a reliable Maximized command.
     first_frame: bool,
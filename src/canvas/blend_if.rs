@@ -0,0 +1,194 @@
+// ============================================================================
+// BLEND IF — per-layer blending ranges
+// ============================================================================
+//
+// Each layer carries a pair of ranges per channel (gray, red, green, blue):
+// one tested against the layer's own pixel ("This layer") and one against the
+// composite beneath it ("Underlying layer"). A pixel contributes fully while
+// every tested value sits between the inner handles and fades out linearly
+// towards the outer ones. The result scales the layer pixel's alpha before
+// the blend mode runs, so every blend mode honours it. The GPU compositor
+// evaluates the same ranges in `UBER_COMPOSITE_SHADER`.
+
+/// One blending range on the 0–255 scale. `black` and `white` are the two
+/// halves of the split black and white sliders: values below `black[0]` or
+/// above `white[1]` are excluded, values between `black[1]` and `white[0]`
+/// pass, and values in between fade.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlendRange {
+    pub black: [u8; 2],
+    pub white: [u8; 2],
+}
+
+impl Default for BlendRange {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+impl BlendRange {
+    /// The range that lets every value through.
+    pub const FULL: Self = Self {
+        black: [0, 0],
+        white: [255, 255],
+    };
+
+    pub fn is_full(self) -> bool {
+        self == Self::FULL
+    }
+
+    /// Put the four handles back in order after one was dragged past another.
+    pub fn normalized(self) -> Self {
+        let b0 = self.black[0];
+        let b1 = self.black[1].max(b0);
+        let w0 = self.white[0].max(b1);
+        let w1 = self.white[1].max(w0);
+        Self {
+            black: [b0, b1],
+            white: [w0, w1],
+        }
+    }
+
+    /// How much a value of `v` (0–255) passes, from 0 to 1.
+    pub fn coverage(self, v: f32) -> f32 {
+        let [b0, b1] = self.black.map(f32::from);
+        let [w0, w1] = self.white.map(f32::from);
+        if v < b0 || v > w1 {
+            0.0
+        } else if v < b1 {
+            (v - b0) / (b1 - b0)
+        } else if v > w0 {
+            (w1 - v) / (w1 - w0)
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Channel a blending range is tested against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum BlendIfChannel {
+    #[default]
+    Gray,
+    Red,
+    Green,
+    Blue,
+}
+
+impl BlendIfChannel {
+    pub const ALL: [Self; 4] = [Self::Gray, Self::Red, Self::Green, Self::Blue];
+
+    pub fn label(self) -> String {
+        match self {
+            Self::Gray => t!("blend_if.gray"),
+            Self::Red => t!("blend_if.red"),
+            Self::Green => t!("blend_if.green"),
+            Self::Blue => t!("blend_if.blue"),
+        }
+    }
+
+    /// The channel's value for `px` on the 0–255 scale. Gray is Rec. 601 luma.
+    pub fn value(self, px: Rgba<u8>) -> f32 {
+        match self {
+            Self::Gray => 0.299 * px[0] as f32 + 0.587 * px[1] as f32 + 0.114 * px[2] as f32,
+            Self::Red => px[0] as f32,
+            Self::Green => px[1] as f32,
+            Self::Blue => px[2] as f32,
+        }
+    }
+}
+
+/// A layer's blending ranges, indexed by [`BlendIfChannel`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct BlendIf {
+    pub this_layer: [BlendRange; 4],
+    pub underlying: [BlendRange; 4],
+}
+
+impl BlendIf {
+    /// True when every range is full, i.e. the layer blends as usual.
+    pub fn is_default(&self) -> bool {
+        self.this_layer
+            .iter()
+            .chain(self.underlying.iter())
+            .all(|r| r.is_full())
+    }
+
+    /// How much of `top` survives over `base`, from 0 to 1. Underlying ranges
+    /// are skipped where the backdrop is fully transparent, since there is
+    /// nothing beneath to test.
+    pub fn coverage(&self, top: Rgba<u8>, base: Rgba<u8>) -> f32 {
+        let mut cov = 1.0;
+        for ch in BlendIfChannel::ALL {
+            let range = self.this_layer[ch as usize];
+            if !range.is_full() {
+                cov *= range.coverage(ch.value(top));
+            }
+            let range = self.underlying[ch as usize];
+            if base[3] > 0 && !range.is_full() {
+                cov *= range.coverage(ch.value(base));
+            }
+        }
+        cov
+    }
+
+    /// `top` with its alpha scaled by [`Self::coverage`].
+    pub fn apply(&self, top: Rgba<u8>, base: Rgba<u8>) -> Rgba<u8> {
+        if top[3] == 0 || self.is_default() {
+            return top;
+        }
+        let cov = self.coverage(top, base);
+        Rgba([top[0], top[1], top[2], (top[3] as f32 * cov + 0.5) as u8])
+    }
+
+    /// Pack into 32 bytes for project files: this-layer ranges, then
+    /// underlying, each as black low/high and white low/high.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (i, r) in self
+            .this_layer
+            .iter()
+            .chain(self.underlying.iter())
+            .enumerate()
+        {
+            out[i * 4..i * 4 + 4]
+                .copy_from_slice(&[r.black[0], r.black[1], r.white[0], r.white[1]]);
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8; 32]) -> Self {
+        let range = |i: usize| {
+            BlendRange {
+                black: [bytes[i * 4], bytes[i * 4 + 1]],
+                white: [bytes[i * 4 + 2], bytes[i * 4 + 3]],
+            }
+            .normalized()
+        };
+        Self {
+            this_layer: [range(0), range(1), range(2), range(3)],
+            underlying: [range(4), range(5), range(6), range(7)],
+        }
+    }
+
+    /// Ranges as the blend shader expects them: eight `vec4`s (this layer,
+    /// then underlying) on the 0–1 scale. `None` when no range is set.
+    pub fn gpu_ranges(&self) -> Option<[[f32; 4]; 8]> {
+        if self.is_default() {
+            return None;
+        }
+        let mut out = [[0.0; 4]; 8];
+        for (slot, r) in out
+            .iter_mut()
+            .zip(self.this_layer.iter().chain(self.underlying.iter()))
+        {
+            *slot = [
+                r.black[0] as f32 / 255.0,
+                r.black[1] as f32 / 255.0,
+                r.white[0] as f32 / 255.0,
+                r.white[1] as f32 / 255.0,
+            ];
+        }
+        Some(out)
+    }
+}
@@ -136,6 +136,17 @@ pub struct CanvasState {
     /// Widget ID of the canvas painter (set by Canvas::show_with_state).
     /// Used by tools to detect when a non-canvas widget has keyboard focus.
     pub canvas_widget_id: Option<egui::Id>,
+    /// Infinite canvas mode: the document rectangle `(x, y, w, h)` in canvas
+    /// pixels. The canvas grows around the content while sketching and flat
+    /// exports are cropped to this rectangle. `None` = normal canvas.
+    pub document_bounds: Option<(u32, u32, u32, u32)>,
+    /// Named layer comps, in panel order.
+    pub layer_comps: Vec<LayerComp>,
+    /// Named export rectangles, in the order they were added.
+    pub slices: Vec<Slice>,
+    /// Blend, blur, resample and stamp colors in linear light instead of
+    /// directly on the stored sRGB values.
+    pub linear_blending: bool,
 }
 
 impl CanvasState {
@@ -198,6 +209,10 @@ impl CanvasState {
             text_glyph_cache: Default::default(),
             text_editing_layer: None,
             canvas_widget_id: None,
+            document_bounds: None,
+            layer_comps: Vec::new(),
+            slices: Vec::new(),
+            linear_blending: false,
         }
     }
 
@@ -222,8 +237,7 @@ impl CanvasState {
         }
         layer
             .folder_id
-            .and_then(|id| self.layer_folder(id))
-            .is_none_or(|folder| folder.visible)
+            .is_none_or(|id| self.folder_effectively_visible(id))
     }
 
     /// Reset all preview-related state (call whenever preview_layer is cleared).
@@ -438,6 +452,20 @@ impl CanvasState {
         self.text_editing_layer = saved;
     }
 
+    /// Apply an HDR exposure/tone-map view to every float layer and
+    /// re-render their previews.
+    pub fn set_hdr_view(&mut self, view: crate::experimental::HdrView) {
+        for layer in &mut self.layers {
+            let current = layer.hdr_metadata.view;
+            if layer.has_float_pixels()
+                && (current.exposure, current.tone_map) != (view.exposure, view.tone_map)
+            {
+                layer.set_hdr_view(view);
+            }
+        }
+        self.mark_dirty(None);
+    }
+
     /// Force-rasterize a specific text layer by index, even if it is the
     /// currently-editing layer.  Used to show live effects/warp changes
     /// during text editing.
@@ -552,6 +580,14 @@ impl CanvasState {
         let layer_visibility: Vec<bool> = (0..self.layers.len())
             .map(|idx| self.layer_effectively_visible(idx))
             .collect();
+        let plan = GroupPlan::new(
+            &self.layers,
+            &self.layer_folders,
+            &layer_visibility,
+            0..self.layers.len(),
+            self.linear_blending,
+        );
+        let linear = self.linear_blending;
         let layers = &self.layers;
         let preview = &self.preview_layer;
         let preview_blend = self.preview_blend_mode;
@@ -571,8 +607,10 @@ impl CanvasState {
                 let ch = CHUNK_SIZE.min(img_h.saturating_sub(base_y));
 
                 let mut pixels = vec![Rgba([0u8, 0, 0, 0]); (cw * ch) as usize];
+                let mut group_stack = Vec::new();
 
                 for (li, layer) in layers.iter().enumerate() {
+                    plan.step_tile(li, &mut pixels, &mut group_stack, (base_x, base_y), cw);
                     if !layer_visibility.get(li).copied().unwrap_or(false) {
                         continue;
                     }
@@ -652,7 +690,15 @@ impl CanvasState {
                                             (top[3] as f32 * inv + ow[3] as f32 * cov + 0.5) as u8,
                                         ]);
                                     } else {
-                                        top = Self::blend_pixel_static(top, pp, preview_blend, 1.0);
+                                        top = Self::blend_pixel_at(
+                                            top,
+                                            pp,
+                                            preview_blend,
+                                            1.0,
+                                            base_x + lx,
+                                            base_y + ly,
+                                            linear,
+                                        );
                                     }
                                 }
                             }
@@ -664,19 +710,30 @@ impl CanvasState {
                                 }
                             }
 
+                            top = layer.blend_if.apply(top, pixels[idx]);
                             if opaque_overwrite && top[3] == 255 {
                                 pixels[idx] = top;
                             } else {
-                                pixels[idx] = Self::blend_pixel_static(
+                                pixels[idx] = Self::blend_pixel_at(
                                     pixels[idx],
                                     top,
                                     layer.blend_mode,
                                     layer.opacity,
+                                    base_x + lx,
+                                    base_y + ly,
+                                    linear,
                                 );
                             }
                         }
                     }
                 }
+                plan.step_tile(
+                    layers.len(),
+                    &mut pixels,
+                    &mut group_stack,
+                    (base_x, base_y),
+                    cw,
+                );
 
                 (cx, cy, cw, ch, pixels)
             })
@@ -726,6 +783,14 @@ impl CanvasState {
         let preview_targets_mask = self.preview_targets_mask;
         let preview_mask_reveal = self.preview_mask_reveal;
         let active_layer_index = self.active_layer_index;
+        let plan = GroupPlan::new(
+            &self.layers,
+            &self.layer_folders,
+            &layer_visibility,
+            0..self.layers.len(),
+            self.linear_blending,
+        );
+        let linear = self.linear_blending;
 
         let row_len = target_w as usize;
         let mut pixels = vec![Color32::TRANSPARENT; (target_w * target_h) as usize];
@@ -742,7 +807,7 @@ impl CanvasState {
                     // Opaque-base optimisation: find the deepest fully-opaque
                     // normal-blend pixel to skip layers beneath it
                     let mut start_layer_idx = 0;
-                    if !preview_targets_mask {
+                    if !preview_targets_mask && plan.is_empty() {
                         for (idx, layer) in layers.iter().enumerate().rev() {
                             if !layer_visibility.get(idx).copied().unwrap_or(false) {
                                 continue;
@@ -753,7 +818,10 @@ impl CanvasState {
                             {
                                 continue;
                             }
-                            if layer.blend_mode == BlendMode::Normal && layer.opacity >= 1.0 {
+                            if layer.blend_mode == BlendMode::Normal
+                                && layer.opacity >= 1.0
+                                && layer.blend_if.is_default()
+                            {
                                 let mut effective_a = layer.pixels.get_pixel(x, y)[3];
                                 if effective_a == 255
                                     && layer.mask_enabled
@@ -775,8 +843,10 @@ impl CanvasState {
                     }
 
                     let mut base = Rgba([0, 0, 0, 0]);
+                    let mut group_stack = Vec::new();
 
                     for (li, layer) in layers.iter().enumerate().skip(start_layer_idx) {
+                        base = plan.step_pixel(li, base, &mut group_stack, x, y);
                         if !layer_visibility.get(li).copied().unwrap_or(false) {
                             continue;
                         }
@@ -832,8 +902,15 @@ impl CanvasState {
                                         (top[3] as f32 * inv + ow[3] as f32 * cov + 0.5) as u8,
                                     ]);
                                 } else {
-                                    top =
-                                        Self::blend_pixel_static(top, pp, preview_blend_mode, 1.0);
+                                    top = Self::blend_pixel_at(
+                                        top,
+                                        pp,
+                                        preview_blend_mode,
+                                        1.0,
+                                        x,
+                                        y,
+                                        linear,
+                                    );
                                 }
                             }
                         }
@@ -849,8 +926,18 @@ impl CanvasState {
                             top[3] = ((top[3] as u32 * (255 - conceal as u32)) / 255) as u8;
                         }
 
-                        base = Self::blend_pixel_static(base, top, layer.blend_mode, layer.opacity);
+                        let top = layer.blend_if.apply(top, base);
+                        base = Self::blend_pixel_at(
+                            base,
+                            top,
+                            layer.blend_mode,
+                            layer.opacity,
+                            x,
+                            y,
+                            linear,
+                        );
                     }
+                    base = plan.step_pixel(layers.len(), base, &mut group_stack, x, y);
 
                     let a = base[3];
                     if a == 255 || a == 0 {
@@ -898,6 +985,14 @@ impl CanvasState {
         let preview_targets_mask = self.preview_targets_mask;
         let preview_mask_reveal = self.preview_mask_reveal;
         let active_layer_index = self.active_layer_index;
+        let plan = GroupPlan::new(
+            &self.layers,
+            &self.layer_folders,
+            &layer_visibility,
+            0..self.layers.len(),
+            self.linear_blending,
+        );
+        let linear = self.linear_blending;
 
         // 2. Pre-allocate output and parallelise row-by-row in-place
         let row_len = width as usize;
@@ -918,6 +1013,7 @@ impl CanvasState {
 
                 // Per-row buffer for chunk raw pointers (reused across chunk columns)
                 let mut chunk_raws: Vec<Option<&[u8]>> = Vec::with_capacity(n_layers);
+                let mut group_stack = Vec::new();
 
                 for cx in first_cx..=last_cx {
                     let span_start = (cx * CHUNK_SIZE).max(min_x);
@@ -948,7 +1044,7 @@ impl CanvasState {
 
                         // Opaque-base optimisation: find deepest opaque Normal pixel
                         let mut start_layer_idx = 0;
-                        if !preview_targets_mask {
+                        if !preview_targets_mask && plan.is_empty() {
                             for (idx, layer) in layers.iter().enumerate().rev() {
                                 if !layer_visibility.get(idx).copied().unwrap_or(false) {
                                     continue;
@@ -961,6 +1057,7 @@ impl CanvasState {
                                 }
                                 if layer.blend_mode == BlendMode::Normal
                                     && layer.opacity >= 1.0
+                                    && layer.blend_if.is_default()
                                     && let Some(raw) = chunk_raws[idx]
                                 {
                                     let mut effective_a = raw[px_off + 3];
@@ -985,8 +1082,10 @@ impl CanvasState {
                         }
 
                         let mut base = Rgba([0, 0, 0, 0]);
+                        group_stack.clear();
 
                         for (li, layer) in layers.iter().enumerate().skip(start_layer_idx) {
+                            base = plan.step_pixel(li, base, &mut group_stack, x, y);
                             if !layer_visibility.get(li).copied().unwrap_or(false) {
                                 continue;
                             }
@@ -1040,11 +1139,14 @@ impl CanvasState {
                                         let new_a = (current_a * (1.0 - mask_strength)).max(0.0);
                                         top[3] = (new_a * 255.0) as u8;
                                     } else {
-                                        top = Self::blend_pixel_static(
+                                        top = Self::blend_pixel_at(
                                             top,
                                             pp,
                                             preview_blend_mode,
                                             1.0,
+                                            x,
+                                            y,
+                                            linear,
                                         );
                                     }
                                 }
@@ -1061,13 +1163,18 @@ impl CanvasState {
                                 top[3] = ((top[3] as u32 * (255 - conceal as u32)) / 255) as u8;
                             }
 
-                            base = Self::blend_pixel_static(
+                            let top = layer.blend_if.apply(top, base);
+                            base = Self::blend_pixel_at(
                                 base,
                                 top,
                                 layer.blend_mode,
                                 layer.opacity,
+                                x,
+                                y,
+                                linear,
                             );
                         }
+                        base = plan.step_pixel(layers.len(), base, &mut group_stack, x, y);
 
                         let a = base[3];
                         if a == 255 || a == 0 {
@@ -1106,6 +1213,15 @@ impl CanvasState {
         if !has_any {
             return None;
         }
+        let plan = GroupPlan::new(
+            &self.layers,
+            &self.layer_folders,
+            &layer_visibility,
+            above_start..self.layers.len(),
+            self.linear_blending,
+        );
+        let linear = self.linear_blending;
+        let layer_count = self.layers.len();
 
         let w = self.width as usize;
         let h = self.height as usize;
@@ -1121,7 +1237,9 @@ impl CanvasState {
             .for_each(|(y, row)| {
                 for (x, pixel) in row.iter_mut().enumerate() {
                     let mut base = Rgba([0u8, 0, 0, 0]);
+                    let mut group_stack = Vec::new();
                     for (idx, layer) in self.layers.iter().enumerate().skip(above_start) {
+                        base = plan.step_pixel(idx, base, &mut group_stack, x as u32, y as u32);
                         if !layer_visibility.get(idx).copied().unwrap_or(false) {
                             continue;
                         }
@@ -1138,8 +1256,18 @@ impl CanvasState {
                                 top[3] = ((top[3] as u32 * (255 - conceal as u32)) / 255) as u8;
                             }
                         }
-                        base = Self::blend_pixel_static(base, top, layer.blend_mode, layer.opacity);
+                        let top = layer.blend_if.apply(top, base);
+                        base = Self::blend_pixel_at(
+                            base,
+                            top,
+                            layer.blend_mode,
+                            layer.opacity,
+                            x as u32,
+                            y as u32,
+                            linear,
+                        );
                     }
+                    base = plan.step_pixel(layer_count, base, &mut group_stack, x as u32, y as u32);
                     let a = base[3];
                     if a == 0 {
                         *pixel = Color32::TRANSPARENT;
@@ -1176,6 +1304,15 @@ impl CanvasState {
         if !has_any {
             return None;
         }
+        let below_end = self.active_layer_index;
+        let plan = GroupPlan::new(
+            &self.layers,
+            &self.layer_folders,
+            &layer_visibility,
+            0..below_end,
+            self.linear_blending,
+        );
+        let linear = self.linear_blending;
 
         let w = self.width as usize;
         let h = self.height as usize;
@@ -1190,8 +1327,9 @@ impl CanvasState {
             .for_each(|(y, row)| {
                 for (x, pixel) in row.iter_mut().enumerate() {
                     let mut base = Rgba([0u8, 0, 0, 0]);
-                    for (idx, layer) in self.layers.iter().enumerate().take(self.active_layer_index)
-                    {
+                    let mut group_stack = Vec::new();
+                    for (idx, layer) in self.layers.iter().enumerate().take(below_end) {
+                        base = plan.step_pixel(idx, base, &mut group_stack, x as u32, y as u32);
                         if !layer_visibility.get(idx).copied().unwrap_or(false) {
                             continue;
                         }
@@ -1208,8 +1346,18 @@ impl CanvasState {
                                 top[3] = ((top[3] as u32 * (255 - conceal as u32)) / 255) as u8;
                             }
                         }
-                        base = Self::blend_pixel_static(base, top, layer.blend_mode, layer.opacity);
+                        let top = layer.blend_if.apply(top, base);
+                        base = Self::blend_pixel_at(
+                            base,
+                            top,
+                            layer.blend_mode,
+                            layer.opacity,
+                            x as u32,
+                            y as u32,
+                            linear,
+                        );
                     }
+                    base = plan.step_pixel(below_end, base, &mut group_stack, x as u32, y as u32);
                     let a = base[3];
                     if a == 0 {
                         *pixel = Color32::TRANSPARENT;
@@ -1239,7 +1387,7 @@ impl CanvasState {
         mode: BlendMode,
         opacity: f32,
     ) -> Rgba<u8> {
-        Self::blend_pixel_static(base, top, mode, opacity)
+        Self::blend_pixel_space(base, top, mode, opacity, self.linear_blending)
     }
 
     /// Static version of blend_pixel for use when self is borrowed mutably elsewhere
@@ -1259,31 +1407,68 @@ impl CanvasState {
             return top;
         }
 
-        let opacity = opacity.clamp(0.0, 1.0);
+        let unit = |p: Rgba<u8>| p.0.map(|c| c as f32 / 255.0);
+        let [r, g, b, a] = Self::blend_unit(unit(base), unit(top), mode, opacity);
+        Rgba([
+            (r * 255.0).clamp(0.0, 255.0) as u8,
+            (g * 255.0).clamp(0.0, 255.0) as u8,
+            (b * 255.0).clamp(0.0, 255.0) as u8,
+            (a * 255.0).clamp(0.0, 255.0) as u8,
+        ])
+    }
 
-        let base_r = base[0] as f32 / 255.0;
-        let base_g = base[1] as f32 / 255.0;
-        let base_b = base[2] as f32 / 255.0;
-        let base_a = base[3] as f32 / 255.0;
+    /// `blend_pixel_static`, or its linear-light counterpart when `linear`:
+    /// color channels are decoded from sRGB before the blend and encoded
+    /// again after it.
+    pub fn blend_pixel_space(
+        base: Rgba<u8>,
+        top: Rgba<u8>,
+        mode: BlendMode,
+        opacity: f32,
+        linear: bool,
+    ) -> Rgba<u8> {
+        if !linear {
+            return Self::blend_pixel_static(base, top, mode, opacity);
+        }
+        if top[3] == 0 {
+            return base;
+        }
+        if matches!(mode, BlendMode::Normal) && opacity >= 1.0 && top[3] == 255 {
+            return top;
+        }
+        let decode = |p: Rgba<u8>| {
+            [
+                srgb8_to_linear(p[0]),
+                srgb8_to_linear(p[1]),
+                srgb8_to_linear(p[2]),
+                p[3] as f32 / 255.0,
+            ]
+        };
+        let [r, g, b, a] = Self::blend_unit(decode(base), decode(top), mode, opacity);
+        Rgba([
+            linear_to_srgb8(r),
+            linear_to_srgb8(g),
+            linear_to_srgb8(b),
+            (a * 255.0).clamp(0.0, 255.0) as u8,
+        ])
+    }
+
+    /// Blend straight-alpha `top` over `base`, channels in `0..=1`.
+    fn blend_unit(base: [f32; 4], top: [f32; 4], mode: BlendMode, opacity: f32) -> [f32; 4] {
+        let opacity = opacity.clamp(0.0, 1.0);
 
-        let top_r = top[0] as f32 / 255.0;
-        let top_g = top[1] as f32 / 255.0;
-        let top_b = top[2] as f32 / 255.0;
-        let top_a = (top[3] as f32 / 255.0) * opacity;
+        let [base_r, base_g, base_b, base_a] = base;
+        let [top_r, top_g, top_b, top_a] = top;
+        let top_a = top_a * opacity;
 
         match mode {
             BlendMode::Overwrite => {
-                return Rgba([
-                    (top_r * 255.0) as u8,
-                    (top_g * 255.0) as u8,
-                    (top_b * 255.0) as u8,
-                    (top_a * 255.0) as u8,
-                ]);
+                return [top_r, top_g, top_b, top_a];
             }
             BlendMode::Xor => {
                 let xor_a = base_a * (1.0 - top_a) + top_a * (1.0 - base_a);
                 if xor_a == 0.0 {
-                    return Rgba([0, 0, 0, 0]);
+                    return [0.0; 4];
                 }
                 let xor_r =
                     (base_r * base_a * (1.0 - top_a) + top_r * top_a * (1.0 - base_a)) / xor_a;
@@ -1291,18 +1476,16 @@ impl CanvasState {
                     (base_g * base_a * (1.0 - top_a) + top_g * top_a * (1.0 - base_a)) / xor_a;
                 let xor_b =
                     (base_b * base_a * (1.0 - top_a) + top_b * top_a * (1.0 - base_a)) / xor_a;
-                return Rgba([
-                    (xor_r * 255.0).clamp(0.0, 255.0) as u8,
-                    (xor_g * 255.0).clamp(0.0, 255.0) as u8,
-                    (xor_b * 255.0).clamp(0.0, 255.0) as u8,
-                    (xor_a * 255.0).clamp(0.0, 255.0) as u8,
-                ]);
+                return [xor_r, xor_g, xor_b, xor_a];
             }
             _ => {}
         }
 
+        let base_rgb = [base_r, base_g, base_b];
+        let top_rgb = [top_r, top_g, top_b];
         let (r, g, b) = match mode {
-            BlendMode::Normal => (top_r, top_g, top_b),
+            // Dissolve needs the pixel position; see `blend_pixel_at`.
+            BlendMode::Normal | BlendMode::Dissolve => (top_r, top_g, top_b),
             BlendMode::Multiply => (base_r * top_r, base_g * top_g, base_b * top_b),
             BlendMode::Screen => (
                 1.0 - (1.0 - base_r) * (1.0 - top_r),
@@ -1401,24 +1584,126 @@ impl CanvasState {
                 if base_g + top_g >= 1.0 { 1.0 } else { 0.0 },
                 if base_b + top_b >= 1.0 { 1.0 } else { 0.0 },
             ),
+            BlendMode::Hue => Self::rgb_tuple(Self::set_lum(
+                Self::set_sat(top_rgb, Self::sat(base_rgb)),
+                Self::lum(base_rgb),
+            )),
+            BlendMode::Saturation => Self::rgb_tuple(Self::set_lum(
+                Self::set_sat(base_rgb, Self::sat(top_rgb)),
+                Self::lum(base_rgb),
+            )),
+            BlendMode::Color => Self::rgb_tuple(Self::set_lum(top_rgb, Self::lum(base_rgb))),
+            BlendMode::Luminosity => Self::rgb_tuple(Self::set_lum(base_rgb, Self::lum(top_rgb))),
+            BlendMode::DarkerColor => {
+                if Self::lum(top_rgb) < Self::lum(base_rgb) {
+                    (top_r, top_g, top_b)
+                } else {
+                    (base_r, base_g, base_b)
+                }
+            }
+            BlendMode::LighterColor => {
+                if Self::lum(top_rgb) > Self::lum(base_rgb) {
+                    (top_r, top_g, top_b)
+                } else {
+                    (base_r, base_g, base_b)
+                }
+            }
             BlendMode::Xor | BlendMode::Overwrite => unreachable!(),
         };
+        // Where the backdrop is transparent the blend has nothing to act on,
+        // so the source shows through unchanged (matters for isolated groups).
+        let r = top_r + (r - top_r) * base_a;
+        let g = top_g + (g - top_g) * base_a;
+        let b = top_b + (b - top_b) * base_a;
 
         let out_a = top_a + base_a * (1.0 - top_a);
         if out_a == 0.0 {
-            return Rgba([0, 0, 0, 0]);
+            return [0.0; 4];
         }
 
         let out_r = (r * top_a + base_r * base_a * (1.0 - top_a)) / out_a;
         let out_g = (g * top_a + base_g * base_a * (1.0 - top_a)) / out_a;
         let out_b = (b * top_a + base_b * base_a * (1.0 - top_a)) / out_a;
 
-        Rgba([
-            (out_r * 255.0).clamp(0.0, 255.0) as u8,
-            (out_g * 255.0).clamp(0.0, 255.0) as u8,
-            (out_b * 255.0).clamp(0.0, 255.0) as u8,
-            (out_a * 255.0).clamp(0.0, 255.0) as u8,
-        ])
+        [out_r, out_g, out_b, out_a]
+    }
+
+    /// Like `blend_pixel_space`, for a pixel at canvas position `(x, y)`.
+    /// Dissolve shows the top pixel fully opaque where a per-position noise
+    /// value falls below its alpha and not at all elsewhere; every other mode
+    /// is position independent.
+    pub fn blend_pixel_at(
+        base: Rgba<u8>,
+        top: Rgba<u8>,
+        mode: BlendMode,
+        opacity: f32,
+        x: u32,
+        y: u32,
+        linear: bool,
+    ) -> Rgba<u8> {
+        if mode != BlendMode::Dissolve {
+            return Self::blend_pixel_space(base, top, mode, opacity, linear);
+        }
+        let alpha = (top[3] as f32 * opacity.clamp(0.0, 1.0)).round() as u32;
+        if Self::dissolve_threshold(x, y) < alpha {
+            Rgba([top[0], top[1], top[2], 255])
+        } else {
+            base
+        }
+    }
+
+    /// Per-pixel noise in `0..255` for Dissolve. Mirrored by
+    /// `dissolve_threshold` in `UBER_COMPOSITE_SHADER`.
+    pub fn dissolve_threshold(x: u32, y: u32) -> u32 {
+        let mut h = x.wrapping_mul(0x8da6_b343) ^ y.wrapping_mul(0xd816_3841);
+        h ^= h >> 16;
+        h = h.wrapping_mul(0x7feb_352d);
+        h ^= h >> 15;
+        h = h.wrapping_mul(0x846c_a68b);
+        h ^= h >> 16;
+        h % 255
+    }
+
+    // Non-separable (HSL) blend helpers, per the W3C compositing spec.
+    fn lum(c: [f32; 3]) -> f32 {
+        0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2]
+    }
+
+    fn clip_color(c: [f32; 3]) -> [f32; 3] {
+        let l = Self::lum(c);
+        let n = c[0].min(c[1]).min(c[2]);
+        let x = c[0].max(c[1]).max(c[2]);
+        let mut c = c;
+        if n < 0.0 {
+            c = c.map(|v| l + (v - l) * l / (l - n));
+        }
+        if x > 1.0 {
+            c = c.map(|v| l + (v - l) * (1.0 - l) / (x - l));
+        }
+        c
+    }
+
+    fn set_lum(c: [f32; 3], l: f32) -> [f32; 3] {
+        let d = l - Self::lum(c);
+        Self::clip_color(c.map(|v| v + d))
+    }
+
+    fn sat(c: [f32; 3]) -> f32 {
+        c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+    }
+
+    fn set_sat(c: [f32; 3], s: f32) -> [f32; 3] {
+        let mn = c[0].min(c[1]).min(c[2]);
+        let mx = c[0].max(c[1]).max(c[2]);
+        if mx > mn {
+            c.map(|v| (v - mn) * s / (mx - mn))
+        } else {
+            [0.0; 3]
+        }
+    }
+
+    fn rgb_tuple(c: [f32; 3]) -> (f32, f32, f32) {
+        (c[0], c[1], c[2])
     }
 
     // Blend mode helper functions
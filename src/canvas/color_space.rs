@@ -0,0 +1,69 @@
+// ============================================================================
+// LINEAR-LIGHT BLENDING
+// ============================================================================
+//
+// Pixels are stored as 8-bit sRGB. With `CanvasState::linear_blending` on,
+// compositing, blurs, resampling and brush stamps decode color channels to
+// linear light, mix there and encode back, so a 50% mix of red and green is
+// a bright yellow instead of a muddy one. Alpha is always linear.
+
+/// Entries in the linear → sRGB encode table. Fine enough that every 8-bit
+/// output is reachable and the steepest part of the curve stays exact.
+const LINEAR_ENCODE_STEPS: usize = 65536;
+
+fn srgb_decode_table() -> &'static [f32; 256] {
+    static TABLE: std::sync::OnceLock<[f32; 256]> = std::sync::OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = [0.0f32; 256];
+        for (i, entry) in table.iter_mut().enumerate() {
+            let v = i as f64 / 255.0;
+            *entry = if v <= 0.04045 {
+                v / 12.92
+            } else {
+                ((v + 0.055) / 1.055).powf(2.4)
+            } as f32;
+        }
+        table
+    })
+}
+
+fn srgb_encode_table() -> &'static [u8] {
+    static TABLE: std::sync::OnceLock<Vec<u8>> = std::sync::OnceLock::new();
+    TABLE.get_or_init(|| {
+        (0..LINEAR_ENCODE_STEPS)
+            .map(|i| {
+                let v = i as f64 / (LINEAR_ENCODE_STEPS - 1) as f64;
+                let s = if v <= 0.003_130_8 {
+                    v * 12.92
+                } else {
+                    1.055 * v.powf(1.0 / 2.4) - 0.055
+                };
+                (s * 255.0).round().clamp(0.0, 255.0) as u8
+            })
+            .collect()
+    })
+}
+
+/// Linear-light value `0..=1` of an 8-bit sRGB channel.
+#[inline]
+pub fn srgb8_to_linear(v: u8) -> f32 {
+    srgb_decode_table()[v as usize]
+}
+
+/// Linear-light value of an sRGB channel in `0..=1`, for samples deeper
+/// than 8 bits.
+pub fn srgb_to_linear(v: f32) -> f32 {
+    let v = v.clamp(0.0, 1.0);
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// 8-bit sRGB channel for a linear-light value, clamped to `0..=1`.
+#[inline]
+pub fn linear_to_srgb8(v: f32) -> u8 {
+    let idx = (v.clamp(0.0, 1.0) * (LINEAR_ENCODE_STEPS - 1) as f32).round() as usize;
+    srgb_encode_table()[idx]
+}
@@ -0,0 +1,164 @@
+// ============================================================================
+// LAYER COMPS
+// ============================================================================
+//
+// A comp records, per layer, visibility, opacity, blend mode and the layer's
+// move offset. Layers are matched by name (the n-th layer called "Logo" in the
+// comp applies to the n-th layer called "Logo" in the stack), so comps survive
+// reordering and layers added after the comp was captured keep their current
+// state. Applying a comp undoes whatever translation the layer has had since
+// capture, off-canvas pixels included, so painting after capture does not
+// shift the layer and moving content back and forth is lossless.
+
+/// One layer's recorded state inside a [`LayerComp`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerCompEntry {
+    pub layer_name: String,
+    pub visible: bool,
+    pub opacity: f32,
+    pub blend_mode: BlendMode,
+    /// [`Layer::move_offset`] at capture. `None` for non-raster layers, whose
+    /// position is left alone.
+    pub offset: Option<(i32, i32)>,
+}
+
+/// A named document state that can be re-applied to the layer stack.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerComp {
+    pub name: String,
+    pub entries: Vec<LayerCompEntry>,
+}
+
+impl LayerComp {
+    /// Record the current state of every layer.
+    pub fn capture(name: impl Into<String>, state: &CanvasState) -> Self {
+        let entries = state
+            .layers
+            .iter()
+            .map(|layer| LayerCompEntry {
+                layer_name: layer.name.clone(),
+                visible: layer.visible,
+                opacity: layer.opacity,
+                blend_mode: layer.blend_mode,
+                offset: matches!(layer.content, LayerContent::Raster)
+                    .then_some(layer.move_offset),
+            })
+            .collect();
+        Self {
+            name: name.into(),
+            entries,
+        }
+    }
+
+    /// The entry recorded for `state.layers[layer_idx]`, matched by name and
+    /// occurrence.
+    fn entry_for(&self, state: &CanvasState, layer_idx: usize) -> Option<&LayerCompEntry> {
+        let name = &state.layers[layer_idx].name;
+        let occurrence = state.layers[..layer_idx]
+            .iter()
+            .filter(|l| &l.name == name)
+            .count();
+        self.entries
+            .iter()
+            .filter(|e| &e.layer_name == name)
+            .nth(occurrence)
+    }
+}
+
+impl Layer {
+    /// Move the layer's content by `(dx, dy)`, keeping whatever leaves the
+    /// canvas as off-canvas pixels, and add it to the move offset. The
+    /// off-canvas store moves as a whole; only its tiles that reach the
+    /// canvas and the canvas tiles themselves are rewritten.
+    pub fn translate_content(&mut self, dx: i32, dy: i32, canvas_w: u32, canvas_h: u32) {
+        if dx == 0 && dy == 0 {
+            return;
+        }
+        let (cw, ch) = (canvas_w as i32, canvas_h as i32);
+        let mut canvas = TiledImage::new(canvas_w, canvas_h);
+        let mut escaped = Vec::new();
+        for (cx, cy) in self.pixels.chunk_keys() {
+            let Some(chunk) = self.pixels.get_chunk(cx, cy) else {
+                continue;
+            };
+            let (base_x, base_y) = ((cx * CHUNK_SIZE) as i32, (cy * CHUNK_SIZE) as i32);
+            for (x, y, px) in chunk.enumerate_pixels() {
+                let (sx, sy) = (base_x + x as i32, base_y + y as i32);
+                if px[3] == 0 || sx >= cw || sy >= ch {
+                    continue;
+                }
+                let (tx, ty) = (sx + dx, sy + dy);
+                if tx >= 0 && ty >= 0 && tx < cw && ty < ch {
+                    canvas.put_pixel(tx as u32, ty as u32, *px);
+                } else {
+                    escaped.push((tx, ty, *px));
+                }
+            }
+        }
+        let off = self.off_canvas.take().and_then(|mut off| {
+            off.origin = (off.origin.0 + dx, off.origin.1 + dy);
+            off.move_onto_canvas(&mut canvas)
+        });
+        self.off_canvas =
+            OffCanvasPixels::with_patches(off, &escaped, (canvas_w, canvas_h), |_, px| px);
+        self.pixels = canvas;
+        self.invalidate_lod();
+        self.move_offset = (self.move_offset.0 + dx, self.move_offset.1 + dy);
+        self.gpu_generation += 1;
+    }
+}
+
+impl CanvasState {
+    /// Index of the comp called `name`.
+    pub fn layer_comp_index(&self, name: &str) -> Option<usize> {
+        self.layer_comps.iter().position(|c| c.name == name)
+    }
+
+    /// Apply the comp at `comp_idx` to the layer stack. Layers the comp does
+    /// not mention are left unchanged, and position-locked layers keep their
+    /// position. Returns `false` for an unknown index.
+    pub fn apply_layer_comp(&mut self, comp_idx: usize) -> bool {
+        let Some(comp) = self.layer_comps.get(comp_idx).cloned() else {
+            return false;
+        };
+        let (w, h) = (self.width, self.height);
+        for idx in 0..self.layers.len() {
+            let Some(entry) = comp.entry_for(self, idx).cloned() else {
+                continue;
+            };
+            let layer = &mut self.layers[idx];
+            layer.visible = entry.visible;
+            layer.opacity = entry.opacity;
+            layer.blend_mode = entry.blend_mode;
+            if let Some((x, y)) = entry.offset
+                && matches!(layer.content, LayerContent::Raster)
+                && !layer.locks.position_locked()
+            {
+                let (mx, my) = layer.move_offset;
+                layer.translate_content(x - mx, y - my, w, h);
+            }
+        }
+        self.composite_cache = None;
+        self.clear_preview_state();
+        self.mark_dirty(None);
+        true
+    }
+
+    /// Apply the comp called `name`.
+    pub fn apply_layer_comp_named(&mut self, name: &str) -> Result<(), String> {
+        let idx = self.layer_comp_index(name).ok_or_else(|| {
+            let names: Vec<&str> = self.layer_comps.iter().map(|c| c.name.as_str()).collect();
+            if names.is_empty() {
+                format!("unknown layer comp '{}' (the document has none)", name)
+            } else {
+                format!(
+                    "unknown layer comp '{}' (available: {})",
+                    name,
+                    names.join(", ")
+                )
+            }
+        })?;
+        self.apply_layer_comp(idx);
+        Ok(())
+    }
+}
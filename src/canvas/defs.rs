@@ -14,3 +14,8 @@ include!("tiled_image.rs");
 include!("layers.rs");
 include!("mirror.rs");
 include!("canvas_state.rs");
+include!("groups.rs");
+include!("comps.rs");
+include!("slices.rs");
+include!("blend_if.rs");
+include!("color_space.rs");
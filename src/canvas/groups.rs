@@ -0,0 +1,474 @@
+// ============================================================================
+// NESTED LAYER GROUPS
+// ============================================================================
+//
+// Folders nest through `LayerFolder::parent_id`; a layer's `folder_id` names
+// its innermost folder. Groups that only organise layers (pass through, full
+// opacity, no mask) composite exactly like the flat stack and are ignored
+// here. Every other group is composited with a per-pixel stack: entering the
+// group saves the backdrop, leaving it combines the group result with it.
+
+/// Walk `folder_id` and its ancestors, innermost first. Stops on a missing
+/// folder or a parent cycle.
+fn folder_chain(folders: &[LayerFolder], folder_id: Option<u64>) -> Vec<usize> {
+    let mut chain = Vec::new();
+    let mut next = folder_id;
+    while let Some(id) = next {
+        let Some(idx) = folders.iter().position(|f| f.id == id) else {
+            break;
+        };
+        if chain.contains(&idx) || chain.len() > folders.len() {
+            break;
+        }
+        chain.push(idx);
+        next = folders[idx].parent_id;
+    }
+    chain
+}
+
+/// Premultiplied lerp from `a` to `b`, returned straight. With `linear` the
+/// color channels are mixed in linear light.
+fn lerp_rgba(a: Rgba<u8>, b: Rgba<u8>, t: f32, linear: bool) -> Rgba<u8> {
+    if t >= 1.0 {
+        return b;
+    }
+    if t <= 0.0 {
+        return a;
+    }
+    let aa = a[3] as f32 / 255.0;
+    let ba = b[3] as f32 / 255.0;
+    let out_a = aa + (ba - aa) * t;
+    if out_a <= 0.0 {
+        return Rgba([0, 0, 0, 0]);
+    }
+    let ch = |i: usize| {
+        if linear {
+            let pa = srgb8_to_linear(a[i]) * aa;
+            let pb = srgb8_to_linear(b[i]) * ba;
+            return linear_to_srgb8((pa + (pb - pa) * t) / out_a);
+        }
+        let pa = a[i] as f32 * aa;
+        let pb = b[i] as f32 * ba;
+        ((pa + (pb - pa) * t) / out_a).round().clamp(0.0, 255.0) as u8
+    };
+    Rgba([ch(0), ch(1), ch(2), (out_a * 255.0).round() as u8])
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum GroupTransition {
+    /// Leave the group at this folder index.
+    Close(usize),
+    /// Enter the group at this folder index.
+    Open(usize),
+}
+
+/// A layer span composited as one isolated group, for the GPU compositor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CompositeGroup {
+    /// First layer index of the span.
+    pub start: usize,
+    /// One past the last layer index.
+    pub end: usize,
+    pub opacity: f32,
+    pub blend_mode: BlendMode,
+}
+
+/// Where groups open and close across a layer range. Built once per
+/// composite; empty when no group changes the result.
+pub struct GroupPlan<'a> {
+    folders: &'a [LayerFolder],
+    start: usize,
+    /// `transitions[i - start]` runs before layer `i`; the extra last slot
+    /// runs after the range.
+    transitions: Vec<Vec<GroupTransition>>,
+    empty: bool,
+    /// Combine groups with their backdrop in linear light.
+    linear: bool,
+}
+
+impl<'a> GroupPlan<'a> {
+    /// Plan the groups over `range`. `visibility` is the effective per-layer
+    /// visibility (hidden groups hide their layers already). Groups that
+    /// start before or end after the range are clipped to it. `linear`
+    /// follows `CanvasState::linear_blending`.
+    pub(crate) fn new(
+        layers: &[Layer],
+        folders: &'a [LayerFolder],
+        visibility: &[bool],
+        range: std::ops::Range<usize>,
+        linear: bool,
+    ) -> Self {
+        let start = range.start;
+        let len = range.end.saturating_sub(range.start);
+        let mut plan = Self {
+            folders,
+            start,
+            transitions: Vec::new(),
+            empty: true,
+            linear,
+        };
+        if !folders.iter().any(LayerFolder::composites_as_group) || len == 0 {
+            return plan;
+        }
+
+        // (folder index, depth, first, last) per grouping folder.
+        let mut spans: Vec<(usize, usize, usize, usize)> = Vec::new();
+        for li in range.clone() {
+            if !visibility.get(li).copied().unwrap_or(false) {
+                continue;
+            }
+            let chain = folder_chain(folders, layers[li].folder_id);
+            let depth_of = |pos: usize| chain.len() - 1 - pos;
+            for (pos, &fi) in chain.iter().enumerate() {
+                if !folders[fi].composites_as_group() {
+                    continue;
+                }
+                match spans.iter_mut().find(|s| s.0 == fi) {
+                    Some(span) => span.3 = li,
+                    None => spans.push((fi, depth_of(pos), li, li)),
+                }
+            }
+        }
+        if spans.is_empty() {
+            return plan;
+        }
+
+        plan.transitions = vec![Vec::new(); len + 1];
+        // Innermost groups close first; outermost groups open first.
+        spans.sort_by_key(|span| std::cmp::Reverse(span.1));
+        for &(fi, _, _, last) in &spans {
+            plan.transitions[last + 1 - start].push(GroupTransition::Close(fi));
+        }
+        spans.sort_by_key(|span| span.1);
+        for &(fi, _, first, _) in &spans {
+            plan.transitions[first - start].push(GroupTransition::Open(fi));
+        }
+        for slot in &mut plan.transitions {
+            // Closes before opens within a slot; the sorts above keep the
+            // nesting order inside each kind.
+            slot.sort_by_key(|t| matches!(t, GroupTransition::Open(_)));
+        }
+        plan.empty = false;
+        plan
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.empty
+    }
+
+    fn slot(&self, layer_idx: usize) -> &[GroupTransition] {
+        if self.empty {
+            return &[];
+        }
+        layer_idx
+            .checked_sub(self.start)
+            .and_then(|i| self.transitions.get(i))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Combine a finished group with its backdrop at canvas position `(x, y)`.
+    fn finish(
+        &self,
+        folder: &LayerFolder,
+        backdrop: Rgba<u8>,
+        mut group: Rgba<u8>,
+        x: u32,
+        y: u32,
+    ) -> Rgba<u8> {
+        let conceal = folder.mask_conceal_at(x, y);
+        match folder.blend_mode {
+            Some(mode) => {
+                if conceal > 0 {
+                    group[3] = ((group[3] as u32 * (255 - conceal as u32)) / 255) as u8;
+                }
+                CanvasState::blend_pixel_at(
+                    backdrop,
+                    group,
+                    mode,
+                    folder.opacity,
+                    x,
+                    y,
+                    self.linear,
+                )
+            }
+            None => {
+                let t = folder.opacity * (1.0 - conceal as f32 / 255.0);
+                lerp_rgba(backdrop, group, t, self.linear)
+            }
+        }
+    }
+
+    /// Apply the group transitions due before `layer_idx` (or after the
+    /// range, for `layer_idx == range.end`) to one pixel at `(x, y)`.
+    pub(crate) fn step_pixel(
+        &self,
+        layer_idx: usize,
+        mut base: Rgba<u8>,
+        stack: &mut Vec<Rgba<u8>>,
+        x: u32,
+        y: u32,
+    ) -> Rgba<u8> {
+        for t in self.slot(layer_idx) {
+            match *t {
+                GroupTransition::Open(fi) => {
+                    stack.push(base);
+                    if self.folders[fi].blend_mode.is_some() {
+                        base = Rgba([0, 0, 0, 0]);
+                    }
+                }
+                GroupTransition::Close(fi) => {
+                    let folder = &self.folders[fi];
+                    let backdrop = stack.pop().unwrap_or(Rgba([0, 0, 0, 0]));
+                    base = self.finish(folder, backdrop, base, x, y);
+                }
+            }
+        }
+        base
+    }
+
+    /// Chunk-buffer variant of [`Self::step_pixel`] for `composite_viewport`:
+    /// `pixels` is a `cw`-wide tile whose top-left canvas pixel is `origin`.
+    pub(crate) fn step_tile(
+        &self,
+        layer_idx: usize,
+        pixels: &mut Vec<Rgba<u8>>,
+        stack: &mut Vec<Vec<Rgba<u8>>>,
+        origin: (u32, u32),
+        cw: u32,
+    ) {
+        for t in self.slot(layer_idx) {
+            match *t {
+                GroupTransition::Open(fi) => {
+                    let isolated = self.folders[fi].blend_mode.is_some();
+                    let copy = if isolated {
+                        std::mem::replace(pixels, vec![Rgba([0, 0, 0, 0]); pixels.len()])
+                    } else {
+                        pixels.clone()
+                    };
+                    stack.push(copy);
+                }
+                GroupTransition::Close(fi) => {
+                    let folder = &self.folders[fi];
+                    let Some(backdrop) = stack.pop() else {
+                        continue;
+                    };
+                    for (i, (px, back)) in pixels.iter_mut().zip(backdrop).enumerate() {
+                        let x = origin.0 + i as u32 % cw;
+                        let y = origin.1 + i as u32 / cw;
+                        *px = self.finish(folder, back, *px, x, y);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl CanvasState {
+    /// Ancestors of `folder_id` (itself first) as folder ids.
+    pub fn folder_ancestors(&self, folder_id: u64) -> Vec<u64> {
+        folder_chain(&self.layer_folders, Some(folder_id))
+            .into_iter()
+            .map(|idx| self.layer_folders[idx].id)
+            .collect()
+    }
+
+    /// Nesting depth of a folder; top-level folders are 0.
+    pub fn folder_depth(&self, folder_id: u64) -> usize {
+        self.folder_ancestors(folder_id).len().saturating_sub(1)
+    }
+
+    /// Whether the folder and all of its ancestors are visible.
+    pub fn folder_effectively_visible(&self, folder_id: u64) -> bool {
+        folder_chain(&self.layer_folders, Some(folder_id))
+            .into_iter()
+            .all(|idx| self.layer_folders[idx].visible)
+    }
+
+    /// Whether `folder_id` is `ancestor_id` or nested anywhere inside it.
+    pub fn folder_is_within(&self, folder_id: u64, ancestor_id: u64) -> bool {
+        self.folder_ancestors(folder_id).contains(&ancestor_id)
+    }
+
+    /// Whether the layer belongs to the folder directly or through a nested
+    /// folder.
+    pub fn layer_in_folder(&self, layer_idx: usize, folder_id: u64) -> bool {
+        self.layers
+            .get(layer_idx)
+            .and_then(|layer| layer.folder_id)
+            .is_some_and(|id| self.folder_is_within(id, folder_id))
+    }
+
+    /// Move folders whose parent no longer exists (or that form a cycle) to
+    /// the top level.
+    pub fn repair_folder_parents(&mut self) {
+        for idx in 0..self.layer_folders.len() {
+            let Some(parent) = self.layer_folders[idx].parent_id else {
+                continue;
+            };
+            let own = self.layer_folders[idx].id;
+            if self.layer_folder(parent).is_none() || self.folder_is_within(parent, own) {
+                self.layer_folders[idx].parent_id = None;
+            }
+        }
+    }
+
+    fn layer_visibility(&self) -> Vec<bool> {
+        (0..self.layers.len())
+            .map(|idx| self.layer_effectively_visible(idx))
+            .collect()
+    }
+
+    /// Whether any visible group needs group compositing.
+    pub fn has_group_compositing(&self) -> bool {
+        let visibility = self.layer_visibility();
+        !GroupPlan::new(
+            &self.layers,
+            &self.layer_folders,
+            &visibility,
+            0..self.layers.len(),
+            self.linear_blending,
+        )
+        .is_empty()
+    }
+
+    /// Isolated group spans for the GPU compositor, innermost first, or
+    /// `None` when a group needs something only the CPU path does (pass
+    /// through with reduced opacity, or a group mask).
+    pub fn gpu_composite_groups(&self) -> Option<Vec<CompositeGroup>> {
+        let visibility = self.layer_visibility();
+        let mut groups: Vec<(usize, CompositeGroup)> = Vec::new();
+        for folder in &self.layer_folders {
+            if !folder.composites_as_group() || !self.folder_effectively_visible(folder.id) {
+                continue;
+            }
+            let members: Vec<usize> = (0..self.layers.len())
+                .filter(|&idx| visibility[idx] && self.layer_in_folder(idx, folder.id))
+                .collect();
+            let (Some(&first), Some(&last)) = (members.first(), members.last()) else {
+                continue;
+            };
+            let blend_mode = folder.blend_mode?;
+            if folder.has_live_mask() {
+                return None;
+            }
+            groups.push((
+                self.folder_depth(folder.id),
+                CompositeGroup {
+                    start: first,
+                    end: last + 1,
+                    opacity: folder.opacity,
+                    blend_mode,
+                },
+            ));
+        }
+        groups.sort_by(|(da, a), (db, b)| {
+            (a.end - a.start)
+                .cmp(&(b.end - b.start))
+                .then(db.cmp(da))
+        });
+        Some(groups.into_iter().map(|(_, g)| g).collect())
+    }
+}
+
+/// Layer stack as a tree, bottom to top, for formats that store groups as
+/// nested containers (PSD, OpenRaster).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LayerTreeNode {
+    Layer(usize),
+    /// Folder id and its children, bottom to top.
+    Folder(u64, Vec<LayerTreeNode>),
+}
+
+impl CanvasState {
+    /// Build the group tree. Folders without layers are placed where
+    /// `insert_above_layer` anchors them, inside their parent when it is open
+    /// there, and on top otherwise.
+    pub fn layer_tree(&self) -> Vec<LayerTreeNode> {
+        fn push_into(level: &mut Vec<LayerTreeNode>, path: &[u64], node: LayerTreeNode) {
+            if let Some((id, rest)) = path.split_first()
+                && let Some(pos) = level
+                    .iter()
+                    .rposition(|n| matches!(n, LayerTreeNode::Folder(fid, _) if fid == id))
+                && let LayerTreeNode::Folder(_, children) = &mut level[pos]
+            {
+                push_into(children, rest, node);
+                return;
+            }
+            level.push(node);
+        }
+
+        let chains: Vec<Vec<u64>> = (0..self.layers.len())
+            .map(|idx| {
+                let mut chain = self.layers[idx]
+                    .folder_id
+                    .map(|id| self.folder_ancestors(id))
+                    .unwrap_or_default();
+                chain.reverse();
+                chain
+            })
+            .collect();
+        let used: std::collections::HashSet<u64> = chains.iter().flatten().copied().collect();
+        let empty: Vec<&LayerFolder> = self
+            .layer_folders
+            .iter()
+            .filter(|f| !used.contains(&f.id))
+            .collect();
+        // Empty folders nested in another empty folder are emitted with it.
+        let empty_node = |folder: &LayerFolder| {
+            fn build(id: u64, empty: &[&LayerFolder], depth: usize) -> LayerTreeNode {
+                let children = if depth > empty.len() {
+                    Vec::new()
+                } else {
+                    empty
+                        .iter()
+                        .filter(|f| f.parent_id == Some(id))
+                        .map(|f| build(f.id, empty, depth + 1))
+                        .collect()
+                };
+                LayerTreeNode::Folder(id, children)
+            }
+            build(folder.id, &empty, 0)
+        };
+        let is_top_empty =
+            |f: &LayerFolder| f.parent_id.is_none_or(|p| !empty.iter().any(|e| e.id == p));
+        let anchor = |folder: &LayerFolder| {
+            folder
+                .insert_above_layer
+                .filter(|&i| i < self.layers.len())
+        };
+
+        let mut root = Vec::new();
+        let mut path: Vec<u64> = Vec::new();
+        for (index, chain) in chains.iter().enumerate() {
+            let common = path
+                .iter()
+                .zip(chain)
+                .take_while(|(a, b)| a == b)
+                .count();
+            path.truncate(common);
+            for &id in &chain[common..] {
+                push_into(&mut root, &path, LayerTreeNode::Folder(id, Vec::new()));
+                path.push(id);
+            }
+            push_into(&mut root, &path, LayerTreeNode::Layer(index));
+            for folder in empty
+                .iter()
+                .filter(|f| anchor(f) == Some(index) && is_top_empty(f))
+            {
+                let depth = folder
+                    .parent_id
+                    .and_then(|p| path.iter().position(|&id| id == p))
+                    .map_or(0, |pos| pos + 1);
+                path.truncate(depth);
+                push_into(&mut root, &path, empty_node(folder));
+            }
+        }
+        for folder in empty
+            .iter()
+            .filter(|f| anchor(f).is_none() && is_top_empty(f))
+        {
+            root.push(empty_node(folder));
+        }
+        root
+    }
+}
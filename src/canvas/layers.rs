@@ -26,6 +26,13 @@ pub enum BlendMode {
     LinearLight,
     PinLight,
     HardMix,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+    DarkerColor,
+    LighterColor,
+    Dissolve,
 }
 
 impl BlendMode {
@@ -33,6 +40,7 @@ impl BlendMode {
     pub fn all() -> &'static [BlendMode] {
         &[
             BlendMode::Normal,
+            BlendMode::Dissolve,
             BlendMode::Multiply,
             BlendMode::Screen,
             BlendMode::Additive,
@@ -55,6 +63,12 @@ impl BlendMode {
             BlendMode::LinearLight,
             BlendMode::PinLight,
             BlendMode::HardMix,
+            BlendMode::DarkerColor,
+            BlendMode::LighterColor,
+            BlendMode::Hue,
+            BlendMode::Saturation,
+            BlendMode::Color,
+            BlendMode::Luminosity,
             BlendMode::Xor,
             BlendMode::Overwrite,
         ]
@@ -87,6 +101,13 @@ impl BlendMode {
             BlendMode::LinearLight => "Linear Light",
             BlendMode::PinLight => "Pin Light",
             BlendMode::HardMix => "Hard Mix",
+            BlendMode::Hue => "Hue",
+            BlendMode::Saturation => "Saturation",
+            BlendMode::Color => "Color",
+            BlendMode::Luminosity => "Luminosity",
+            BlendMode::DarkerColor => "Darker Color",
+            BlendMode::LighterColor => "Lighter Color",
+            BlendMode::Dissolve => "Dissolve",
         }
     }
 
@@ -118,6 +139,13 @@ impl BlendMode {
             BlendMode::LinearLight => t!("blend.linear_light"),
             BlendMode::PinLight => t!("blend.pin_light"),
             BlendMode::HardMix => t!("blend.hard_mix"),
+            BlendMode::Hue => t!("blend.hue"),
+            BlendMode::Saturation => t!("blend.saturation"),
+            BlendMode::Color => t!("blend.color"),
+            BlendMode::Luminosity => t!("blend.luminosity"),
+            BlendMode::DarkerColor => t!("blend.darker_color"),
+            BlendMode::LighterColor => t!("blend.lighter_color"),
+            BlendMode::Dissolve => t!("blend.dissolve"),
         }
     }
 
@@ -149,6 +177,13 @@ impl BlendMode {
             BlendMode::LinearLight => 22,
             BlendMode::PinLight => 23,
             BlendMode::HardMix => 24,
+            BlendMode::Hue => 25,
+            BlendMode::Saturation => 26,
+            BlendMode::Color => 27,
+            BlendMode::Luminosity => 28,
+            BlendMode::DarkerColor => 29,
+            BlendMode::LighterColor => 30,
+            BlendMode::Dissolve => 31,
         }
     }
 
@@ -180,6 +215,13 @@ impl BlendMode {
             22 => BlendMode::LinearLight,
             23 => BlendMode::PinLight,
             24 => BlendMode::HardMix,
+            25 => BlendMode::Hue,
+            26 => BlendMode::Saturation,
+            27 => BlendMode::Color,
+            28 => BlendMode::Luminosity,
+            29 => BlendMode::DarkerColor,
+            30 => BlendMode::LighterColor,
+            31 => BlendMode::Dissolve,
             _ => BlendMode::Normal,
         }
     }
@@ -216,6 +258,36 @@ pub struct HdrMetadata {
     pub max_luminance_nits: Option<f32>,
     pub reference_white_nits: Option<f32>,
     pub transfer_function: Option<String>,
+    /// Exposure and tone map used to render float `deep_pixels` into the
+    /// 8-bit preview. A view setting, so not saved with the project; its
+    /// `transfer` is ignored in favour of [`Self::transfer`].
+    #[serde(skip)]
+    pub view: crate::experimental::HdrView,
+}
+
+impl HdrMetadata {
+    /// Transfer tag for scene-linear float payloads (OpenEXR and Radiance
+    /// HDR imports). Any other tag, including the `linear-*` tags older
+    /// projects carry, is display-referred.
+    pub const SCENE_LINEAR: &'static str = "scene-linear";
+
+    pub fn transfer(&self) -> crate::experimental::HdrTransfer {
+        match &self.transfer_function {
+            Some(tag) if tag.starts_with(Self::SCENE_LINEAR) => {
+                crate::experimental::HdrTransfer::SceneLinear
+            }
+            _ => crate::experimental::HdrTransfer::Display,
+        }
+    }
+
+    /// [`Self::view`] with this payload's transfer, for mapping between the
+    /// float payload and the 8-bit preview.
+    pub fn payload_view(&self) -> crate::experimental::HdrView {
+        crate::experimental::HdrView {
+            transfer: self.transfer(),
+            ..self.view
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
@@ -243,6 +315,92 @@ impl WebpFrameCompression {
     }
 }
 
+/// Per-layer edit locks, following the usual editor model: `transparency`
+/// keeps alpha as it is (paint only recolours existing pixels), `pixels`
+/// blocks every pixel edit, `position` blocks moves and transforms, and
+/// `all` combines them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct LayerLocks {
+    pub transparency: bool,
+    pub pixels: bool,
+    pub position: bool,
+    pub all: bool,
+}
+
+impl LayerLocks {
+    pub fn any(self) -> bool {
+        self.transparency || self.pixels || self.position || self.all
+    }
+
+    pub fn pixels_locked(self) -> bool {
+        self.all || self.pixels
+    }
+
+    pub fn position_locked(self) -> bool {
+        self.all || self.position
+    }
+
+    /// Locked for anything that moves or resamples the layer's pixels.
+    pub fn blocks_transform(self) -> bool {
+        self.pixels_locked() || self.position_locked()
+    }
+
+    /// Whether the layer's alpha must stay as it is.
+    pub fn transparency_locked(self) -> bool {
+        self.all || self.transparency
+    }
+
+    /// Resolve one edited pixel against its previous value.
+    pub fn filter_pixel(self, before: Rgba<u8>, after: Rgba<u8>) -> Rgba<u8> {
+        if self.pixels_locked() {
+            before
+        } else if self.transparency_locked() {
+            if before[3] == 0 {
+                before
+            } else {
+                Rgba([after[0], after[1], after[2], before[3]])
+            }
+        } else {
+            after
+        }
+    }
+
+    /// Pack into a bit set for project files.
+    pub fn to_bits(self) -> u8 {
+        (self.transparency as u8)
+            | (self.pixels as u8) << 1
+            | (self.position as u8) << 2
+            | (self.all as u8) << 3
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            transparency: bits & 1 != 0,
+            pixels: bits & 2 != 0,
+            position: bits & 4 != 0,
+            all: bits & 8 != 0,
+        }
+    }
+
+    /// Human-readable list of the active locks, e.g. "Transparency, Position".
+    pub fn describe(self) -> String {
+        let mut parts = Vec::new();
+        if self.all {
+            parts.push(t!("layer.lock.all"));
+        }
+        if self.transparency {
+            parts.push(t!("layer.lock.transparency"));
+        }
+        if self.pixels {
+            parts.push(t!("layer.lock.pixels"));
+        }
+        if self.position {
+            parts.push(t!("layer.lock.position"));
+        }
+        parts.join(", ")
+    }
+}
+
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub enum AdjustmentKind {
     Exposure {
@@ -374,16 +532,304 @@ pub enum LayerContent {
     Adjustment(AdjustmentLayerData),
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+/// A layer group. Layers join a folder through `Layer::folder_id`; folders
+/// nest through `parent_id`, so a layer belongs to its folder and to every
+/// ancestor of it.
+#[derive(Clone)]
 pub struct LayerFolder {
     pub id: u64,
     pub name: String,
     pub visible: bool,
     pub collapsed: bool,
-    #[serde(default)]
     pub insert_above_layer: Option<usize>,
-    #[serde(default)]
     pub color_index: Option<u8>,
+    /// Enclosing folder, `None` at the top level.
+    pub parent_id: Option<u64>,
+    /// Group opacity (0.0–1.0).
+    pub opacity: f32,
+    /// `None` is pass through: the contents blend straight into the layers
+    /// below. `Some(mode)` composites the contents on their own first
+    /// (isolated) and blends the result with `mode`.
+    pub blend_mode: Option<BlendMode>,
+    /// Group mask, same encoding as `Layer::mask` (alpha = concealment).
+    pub mask: Option<TiledImage>,
+    pub mask_enabled: bool,
+}
+
+impl Default for LayerFolder {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            name: String::new(),
+            visible: true,
+            collapsed: false,
+            insert_above_layer: None,
+            color_index: None,
+            parent_id: None,
+            opacity: 1.0,
+            blend_mode: None,
+            mask: None,
+            mask_enabled: true,
+        }
+    }
+}
+
+impl std::fmt::Debug for LayerFolder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LayerFolder")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("visible", &self.visible)
+            .field("parent_id", &self.parent_id)
+            .field("opacity", &self.opacity)
+            .field("blend_mode", &self.blend_mode)
+            .field("has_mask", &self.mask.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl LayerFolder {
+    pub fn has_live_mask(&self) -> bool {
+        self.mask_enabled && self.mask.is_some()
+    }
+
+    /// Whether the group changes the result compared to compositing its
+    /// layers flat: an isolated blend mode, reduced opacity or a live mask.
+    /// Plain pass-through groups are skipped by the compositors.
+    pub fn composites_as_group(&self) -> bool {
+        self.blend_mode.is_some() || self.opacity < 1.0 || self.has_live_mask()
+    }
+
+    /// Concealment (0 = revealed, 255 = hidden) of the group mask at a pixel.
+    pub fn mask_conceal_at(&self, x: u32, y: u32) -> u8 {
+        match &self.mask {
+            Some(mask) if self.mask_enabled => mask.get_pixel(x, y)[3],
+            _ => 0,
+        }
+    }
+}
+
+/// Layer content that lies outside the canvas, kept so moving pixels or
+/// resizing the canvas does not crop it. `pixels` has its top-left corner at
+/// `origin` (canvas coordinates); the part that overlaps the canvas is always
+/// empty because `Layer::pixels` holds that area.
+#[derive(Clone)]
+pub struct OffCanvasPixels {
+    pub origin: (i32, i32),
+    pub pixels: TiledImage,
+}
+
+impl OffCanvasPixels {
+    /// `(min_x, min_y, max_x, max_y)` in canvas coordinates, max exclusive.
+    pub fn bounds(&self) -> (i32, i32, i32, i32) {
+        (
+            self.origin.0,
+            self.origin.1,
+            self.origin.0 + self.pixels.width() as i32,
+            self.origin.1 + self.pixels.height() as i32,
+        )
+    }
+
+    /// Collect the non-transparent pixels of `image` (top-left at `origin`)
+    /// that fall outside a `canvas_w` x `canvas_h` canvas, cropped tight.
+    /// Returns `None` when there are none.
+    pub fn from_outside(
+        origin: (i32, i32),
+        image: &RgbaImage,
+        canvas_w: u32,
+        canvas_h: u32,
+    ) -> Option<Self> {
+        let (cw, ch) = (canvas_w as i32, canvas_h as i32);
+        let outside =
+            |x: u32, y: u32| -> bool {
+                let (cx, cy) = (origin.0 + x as i32, origin.1 + y as i32);
+                cx < 0 || cy < 0 || cx >= cw || cy >= ch
+            };
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+        for (x, y, px) in image.enumerate_pixels() {
+            if px[3] > 0 && outside(x, y) {
+                bounds = Some(match bounds {
+                    Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                    None => (x, y, x, y),
+                });
+            }
+        }
+        let (x0, y0, x1, y1) = bounds?;
+        let mut cropped = RgbaImage::new(x1 - x0 + 1, y1 - y0 + 1);
+        for (x, y, px) in cropped.enumerate_pixels_mut() {
+            if outside(x + x0, y + y0) {
+                *px = *image.get_pixel(x + x0, y + y0);
+            }
+        }
+        Some(Self {
+            origin: (origin.0 + x0 as i32, origin.1 + y0 as i32),
+            pixels: TiledImage::from_rgba_image(&cropped),
+        })
+    }
+
+    /// Bounds of the stored pixels with alpha > 0, found by scanning the
+    /// populated tiles only. `None` when nothing is stored.
+    fn content_bounds(&self) -> Option<(i32, i32, i32, i32)> {
+        let (w, h) = (self.pixels.width(), self.pixels.height());
+        let mut bounds: Option<(i32, i32, i32, i32)> = None;
+        for (cx, cy) in self.pixels.chunk_keys() {
+            let Some(chunk) = self.pixels.get_chunk(cx, cy) else {
+                continue;
+            };
+            let (base_x, base_y) = (cx * CHUNK_SIZE, cy * CHUNK_SIZE);
+            for (x, y, px) in chunk.enumerate_pixels() {
+                let (sx, sy) = (base_x + x, base_y + y);
+                if px[3] == 0 || sx >= w || sy >= h {
+                    continue;
+                }
+                let (px_x, px_y) = (self.origin.0 + sx as i32, self.origin.1 + sy as i32);
+                bounds = Some(match bounds {
+                    Some((x0, y0, x1, y1)) => {
+                        (x0.min(px_x), y0.min(px_y), x1.max(px_x + 1), y1.max(px_y + 1))
+                    }
+                    None => (px_x, px_y, px_x + 1, px_y + 1),
+                });
+            }
+        }
+        bounds
+    }
+
+    /// The same pixels in a store covering `bounds` (canvas coordinates, max
+    /// exclusive). Only populated tiles are visited.
+    fn retiled(&self, bounds: (i32, i32, i32, i32)) -> Self {
+        let mut out = Self {
+            origin: (bounds.0, bounds.1),
+            pixels: TiledImage::new((bounds.2 - bounds.0) as u32, (bounds.3 - bounds.1) as u32),
+        };
+        let (w, h) = (self.pixels.width(), self.pixels.height());
+        for (cx, cy) in self.pixels.chunk_keys() {
+            let Some(chunk) = self.pixels.get_chunk(cx, cy) else {
+                continue;
+            };
+            let (base_x, base_y) = (cx * CHUNK_SIZE, cy * CHUNK_SIZE);
+            for (x, y, px) in chunk.enumerate_pixels() {
+                let (sx, sy) = (base_x + x, base_y + y);
+                if px[3] > 0 && sx < w && sy < h {
+                    out.put(self.origin.0 + sx as i32, self.origin.1 + sy as i32, *px);
+                }
+            }
+        }
+        out
+    }
+
+    /// Write `px` at canvas position `(x, y)`, which must lie in `bounds()`.
+    fn put(&mut self, x: i32, y: i32, px: Rgba<u8>) {
+        self.pixels.put_pixel(
+            (x - self.origin.0) as u32,
+            (y - self.origin.1) as u32,
+            px,
+        );
+    }
+
+    /// Shrink the store to the pixels it still holds; `None` when empty.
+    fn tightened(self) -> Option<Self> {
+        let bounds = self.content_bounds()?;
+        Some(if bounds == self.bounds() {
+            self
+        } else {
+            self.retiled(bounds)
+        })
+    }
+
+    /// Write `patches` (canvas coordinates) that fall outside a `canvas` sized
+    /// canvas into `store`, growing it as needed. `combine(existing, new)`
+    /// decides each stored value. Only populated tiles are copied when the
+    /// store grows, so cost follows the painted area, not the bounding box.
+    pub fn with_patches(
+        store: Option<Self>,
+        patches: &[(i32, i32, Rgba<u8>)],
+        canvas: (u32, u32),
+        combine: impl Fn(Rgba<u8>, Rgba<u8>) -> Rgba<u8>,
+    ) -> Option<Self> {
+        let (cw, ch) = (canvas.0 as i32, canvas.1 as i32);
+        let outside = |&&(x, y, _): &&(i32, i32, Rgba<u8>)| x < 0 || y < 0 || x >= cw || y >= ch;
+        let mut bounds = store.as_ref().map(|off| off.bounds());
+        for &(x, y, _) in patches.iter().filter(outside) {
+            bounds = Some(match bounds {
+                Some(b) => (b.0.min(x), b.1.min(y), b.2.max(x + 1), b.3.max(y + 1)),
+                None => (x, y, x + 1, y + 1),
+            });
+        }
+        let bounds = bounds?;
+        let mut store = match store {
+            Some(off) if off.bounds() == bounds => off,
+            Some(off) => off.retiled(bounds),
+            None => Self {
+                origin: (bounds.0, bounds.1),
+                pixels: TiledImage::new((bounds.2 - bounds.0) as u32, (bounds.3 - bounds.1) as u32),
+            },
+        };
+        for &(x, y, px) in patches.iter().filter(outside) {
+            let (ix, iy) = ((x - store.origin.0) as u32, (y - store.origin.1) as u32);
+            let existing = *store.pixels.get_pixel(ix, iy);
+            let value = combine(existing, px);
+            if value != existing {
+                store.pixels.put_pixel(ix, iy, value);
+            }
+        }
+        store.tightened()
+    }
+
+    /// Move the stored pixels that now lie on `canvas` into it, touching only
+    /// the tiles that overlap the canvas. Returns what is left, if anything.
+    pub fn move_onto_canvas(mut self, canvas: &mut TiledImage) -> Option<Self> {
+        let (cw, ch) = (canvas.width() as i32, canvas.height() as i32);
+        let (ox, oy) = self.origin;
+        let size = CHUNK_SIZE as i32;
+        let overlapping: Vec<(u32, u32)> = self
+            .pixels
+            .chunk_keys()
+            .filter(|&(cx, cy)| {
+                let (bx, by) = (ox + (cx * CHUNK_SIZE) as i32, oy + (cy * CHUNK_SIZE) as i32);
+                bx < cw && by < ch && bx + size > 0 && by + size > 0
+            })
+            .collect();
+        if overlapping.is_empty() {
+            return Some(self);
+        }
+        for (cx, cy) in overlapping {
+            let (bx, by) = (ox + (cx * CHUNK_SIZE) as i32, oy + (cy * CHUNK_SIZE) as i32);
+            let Some(chunk) = self.pixels.get_chunk_mut(cx, cy) else {
+                continue;
+            };
+            for (x, y, px) in chunk.enumerate_pixels_mut() {
+                let (tx, ty) = (bx + x as i32, by + y as i32);
+                if px[3] > 0 && tx >= 0 && ty >= 0 && tx < cw && ty < ch {
+                    canvas.put_pixel(tx as u32, ty as u32, *px);
+                    *px = Rgba([0, 0, 0, 0]);
+                }
+            }
+        }
+        self.tightened()
+    }
+
+    /// Copy the stored pixels into `target`, whose top-left is at
+    /// `target_origin` in canvas coordinates.
+    pub fn copy_into(&self, target: &mut RgbaImage, target_origin: (i32, i32)) {
+        for (cx, cy) in self.pixels.chunk_keys() {
+            let Some(chunk) = self.pixels.get_chunk(cx, cy) else {
+                continue;
+            };
+            let base_x = self.origin.0 + (cx * CHUNK_SIZE) as i32 - target_origin.0;
+            let base_y = self.origin.1 + (cy * CHUNK_SIZE) as i32 - target_origin.1;
+            for (x, y, px) in chunk.enumerate_pixels() {
+                let (tx, ty) = (base_x + x as i32, base_y + y as i32);
+                if px[3] > 0
+                    && tx >= 0
+                    && ty >= 0
+                    && (tx as u32) < target.width()
+                    && (ty as u32) < target.height()
+                {
+                    target.put_pixel(tx as u32, ty as u32, *px);
+                }
+            }
+        }
+    }
 }
 
 pub struct Layer {
@@ -418,6 +864,15 @@ pub struct Layer {
     pub webp_frame_compression: WebpFrameCompression,
     /// Original high-depth pixel payload for project round-trips.
     pub deep_pixels: Option<crate::experimental::DeepRgbaBuffer>,
+    /// Edit locks (transparency, pixels, position, all).
+    pub locks: LayerLocks,
+    /// Content beyond the canvas edges, if any.
+    pub off_canvas: Option<OffCanvasPixels>,
+    /// Blending ranges ("blend if") limiting where the layer shows.
+    pub blend_if: BlendIf,
+    /// Net translation applied to the whole layer by moves. Layer comps
+    /// record and restore it.
+    pub move_offset: (i32, i32),
 }
 
 fn sync_deep_region<T: Copy, F: Fn(u32, u32, Rgba<u8>) -> [T; 4]>(
@@ -463,6 +918,10 @@ impl Layer {
             source_metadata: ImageMetadata::default(),
             webp_frame_compression: WebpFrameCompression::default(),
             deep_pixels: None,
+            locks: LayerLocks::default(),
+            off_canvas: None,
+            blend_if: BlendIf::default(),
+            move_offset: (0, 0),
         }
     }
 
@@ -485,6 +944,10 @@ impl Layer {
             source_metadata: ImageMetadata::default(),
             webp_frame_compression: WebpFrameCompression::default(),
             deep_pixels: None,
+            locks: LayerLocks::default(),
+            off_canvas: None,
+            blend_if: BlendIf::default(),
+            move_offset: (0, 0),
         }
     }
 
@@ -542,6 +1005,7 @@ impl Layer {
                 );
             }
             crate::experimental::DeepRgbaBuffer::F16(values) => {
+                let view = self.hdr_metadata.payload_view();
                 sync_deep_region(
                     values,
                     width,
@@ -549,18 +1013,12 @@ impl Layer {
                     y0,
                     x1,
                     y1,
-                    |_, _, p| {
-                        [
-                            crate::experimental::f32_to_f16_bits(p[0] as f32 / 255.0),
-                            crate::experimental::f32_to_f16_bits(p[1] as f32 / 255.0),
-                            crate::experimental::f32_to_f16_bits(p[2] as f32 / 255.0),
-                            crate::experimental::f32_to_f16_bits(p[3] as f32 / 255.0),
-                        ]
-                    },
+                    |_, _, p| view.unmap(p).map(crate::experimental::f32_to_f16_bits),
                     &self.pixels,
                 );
             }
             crate::experimental::DeepRgbaBuffer::F32(values) => {
+                let view = self.hdr_metadata.payload_view();
                 sync_deep_region(
                     values,
                     width,
@@ -568,14 +1026,7 @@ impl Layer {
                     y0,
                     x1,
                     y1,
-                    |_, _, p| {
-                        [
-                            p[0] as f32 / 255.0,
-                            p[1] as f32 / 255.0,
-                            p[2] as f32 / 255.0,
-                            p[3] as f32 / 255.0,
-                        ]
-                    },
+                    |_, _, p| view.unmap(p),
                     &self.pixels,
                 );
             }
@@ -586,6 +1037,164 @@ impl Layer {
         self.sync_deep_pixels_from_preview_region(0, 0, self.pixels.width(), self.pixels.height());
     }
 
+    /// Whether this layer carries a float (`F16`/`F32`) payload.
+    pub fn has_float_pixels(&self) -> bool {
+        matches!(
+            self.deep_pixels,
+            Some(crate::experimental::DeepRgbaBuffer::F16(_))
+                | Some(crate::experimental::DeepRgbaBuffer::F32(_))
+        )
+    }
+
+    /// Switch the HDR view and re-render the 8-bit preview from the float
+    /// payload. If the preview was edited without syncing the payload, the
+    /// payload is brought up to date through the old view first.
+    pub fn set_hdr_view(&mut self, view: crate::experimental::HdrView) {
+        if !matches!(self.content, LayerContent::Raster) || !self.has_float_pixels() {
+            self.hdr_metadata.view = view;
+            return;
+        }
+        let (width, height) = (self.pixels.width(), self.pixels.height());
+        let render = |layer: &Self| {
+            layer
+                .deep_pixels
+                .as_ref()
+                .and_then(|deep| layer.hdr_metadata.payload_view().preview(deep, width, height))
+        };
+        if render(self).is_none_or(|preview| preview != self.pixels.to_rgba_image()) {
+            self.sync_all_deep_pixels_from_preview();
+        }
+        self.hdr_metadata.view = view;
+        if let Some(preview) = render(self) {
+            self.pixels = TiledImage::from_rgba_image(&preview);
+            self.invalidate_lod();
+        }
+    }
+
+    /// Re-apply the transparency/pixel locks to an edited rectangle, using
+    /// `before` (same size as the rectangle, row-major) as the previous
+    /// contents. Returns false when nothing may change.
+    pub fn enforce_locks_in_rect(
+        &mut self,
+        x0: u32,
+        y0: u32,
+        width: u32,
+        height: u32,
+        before: &[Rgba<u8>],
+    ) -> bool {
+        let locks = self.locks;
+        if !locks.pixels_locked() && !locks.transparency_locked() {
+            return true;
+        }
+        let (lw, lh) = (self.pixels.width(), self.pixels.height());
+        for dy in 0..height {
+            for dx in 0..width {
+                let (x, y) = (x0 + dx, y0 + dy);
+                let Some(&prev) = before.get((dy * width + dx) as usize) else {
+                    continue;
+                };
+                if x >= lw || y >= lh {
+                    continue;
+                }
+                let cur = *self.pixels.get_pixel(x, y);
+                let next = locks.filter_pixel(prev, cur);
+                if next != cur {
+                    self.pixels.put_pixel(x, y, next);
+                }
+            }
+        }
+        self.invalidate_lod();
+        !locks.pixels_locked()
+    }
+
+    /// Replace the layer's pixels with an edited full-layer image, honouring
+    /// the transparency and pixel locks. Returns false when the layer is
+    /// pixel-locked and nothing was written.
+    pub fn set_pixels_with_locks(&mut self, result: &RgbaImage) -> bool {
+        if self.locks.pixels_locked() {
+            return false;
+        }
+        if self.locks.transparency_locked() {
+            let mut out = result.clone();
+            for (x, y, px) in out.enumerate_pixels_mut() {
+                if x < self.pixels.width() && y < self.pixels.height() {
+                    *px = self.locks.filter_pixel(*self.pixels.get_pixel(x, y), *px);
+                }
+            }
+            self.pixels = TiledImage::from_rgba_image(&out);
+        } else {
+            self.pixels = TiledImage::from_rgba_image(result);
+        }
+        true
+    }
+
+    /// `(min_x, min_y, max_x, max_y)` covering the canvas area and any
+    /// off-canvas content, in canvas coordinates (max exclusive).
+    pub fn extent(&self) -> (i32, i32, i32, i32) {
+        let canvas = (0, 0, self.pixels.width() as i32, self.pixels.height() as i32);
+        match &self.off_canvas {
+            Some(off) => {
+                let b = off.bounds();
+                (
+                    canvas.0.min(b.0),
+                    canvas.1.min(b.1),
+                    canvas.2.max(b.2),
+                    canvas.3.max(b.3),
+                )
+            }
+            None => canvas,
+        }
+    }
+
+    /// The whole layer, off-canvas content included, as one image plus the
+    /// canvas position of its top-left corner.
+    pub fn extent_rgba(&self) -> ((i32, i32), RgbaImage) {
+        let (x0, y0, x1, y1) = self.extent();
+        let mut image = RgbaImage::new((x1 - x0) as u32, (y1 - y0) as u32);
+        if let Some(off) = &self.off_canvas {
+            off.copy_into(&mut image, (x0, y0));
+        }
+        image::imageops::replace(
+            &mut image,
+            &self.pixels.to_rgba_image(),
+            -x0 as i64,
+            -y0 as i64,
+        );
+        ((x0, y0), image)
+    }
+
+    /// Replace the layer from a full-extent image (see `extent_rgba`): the
+    /// part over a `canvas_w` x `canvas_h` canvas becomes `pixels`, the rest
+    /// is kept as off-canvas content.
+    pub fn set_extent_rgba(
+        &mut self,
+        origin: (i32, i32),
+        image: &RgbaImage,
+        canvas_w: u32,
+        canvas_h: u32,
+    ) {
+        let mut canvas = RgbaImage::new(canvas_w, canvas_h);
+        image::imageops::replace(&mut canvas, image, origin.0 as i64, origin.1 as i64);
+        self.pixels = TiledImage::from_rgba_image(&canvas);
+        self.off_canvas = OffCanvasPixels::from_outside(origin, image, canvas_w, canvas_h);
+        self.invalidate_lod();
+    }
+
+    /// Write pixels that lie outside the canvas into the off-canvas store.
+    /// `combine(existing, new)` decides each stored value.
+    pub fn apply_off_canvas_pixels(
+        &mut self,
+        patches: &[(i32, i32, Rgba<u8>)],
+        combine: impl Fn(Rgba<u8>, Rgba<u8>) -> Rgba<u8>,
+    ) {
+        if patches.is_empty() {
+            return;
+        }
+        let canvas = (self.pixels.width(), self.pixels.height());
+        self.off_canvas =
+            OffCanvasPixels::with_patches(self.off_canvas.take(), patches, canvas, combine);
+    }
+
     /// Invalidate the LOD cache (call after any pixel modification).
     pub fn invalidate_lod(&mut self) {
         self.lod_cache = None;
@@ -0,0 +1,92 @@
+// ============================================================================
+// SLICES
+// ============================================================================
+//
+// A slice is a named rectangle of the canvas exported as its own image or
+// packed as one frame of a sprite sheet. Rectangles are in canvas pixels and
+// are kept inside the canvas; slices are drawn over the canvas by the Slice
+// tool and saved in PFE files.
+
+/// A named export rectangle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Slice {
+    pub name: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Slice {
+    pub fn new(name: impl Into<String>, x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            name: name.into(),
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// `(x, y, w, h)`, the form used for document bounds.
+    pub fn rect(&self) -> (u32, u32, u32, u32) {
+        (self.x, self.y, self.width, self.height)
+    }
+
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && y >= self.y && x - self.x < self.width && y - self.y < self.height
+    }
+
+    /// This slice cut to a `width`×`height` canvas, or `None` when nothing
+    /// of it is left.
+    pub fn clamped(&self, width: u32, height: u32) -> Option<Slice> {
+        let x1 = self.x.saturating_add(self.width).min(width);
+        let y1 = self.y.saturating_add(self.height).min(height);
+        (x1 > self.x && y1 > self.y).then(|| Slice {
+            name: self.name.clone(),
+            x: self.x,
+            y: self.y,
+            width: x1 - self.x,
+            height: y1 - self.y,
+        })
+    }
+}
+
+impl CanvasState {
+    /// Index of the topmost (last added) slice containing the pixel.
+    pub fn slice_at(&self, x: u32, y: u32) -> Option<usize> {
+        self.slices.iter().rposition(|s| s.contains(x, y))
+    }
+
+    /// `base`, or `base 2`, `base 3`, … when a slice is already called that.
+    pub fn unique_slice_name(&self, base: &str) -> String {
+        let taken = |name: &str| self.slices.iter().any(|s| s.name == name);
+        if !taken(base) {
+            return base.to_string();
+        }
+        let mut n = 2;
+        while taken(&format!("{} {}", base, n)) {
+            n += 1;
+        }
+        format!("{} {}", base, n)
+    }
+
+    /// Add slices after the existing ones, clamped to the canvas and renamed
+    /// where their names are taken. Returns how many were added.
+    pub fn add_slices(&mut self, slices: impl IntoIterator<Item = Slice>) -> usize {
+        let mut added = 0;
+        for slice in slices {
+            let Some(mut slice) = slice.clamped(self.width, self.height) else {
+                continue;
+            };
+            let base = match slice.name.trim() {
+                "" => "Slice".to_string(),
+                name => name.to_string(),
+            };
+            slice.name = self.unique_slice_name(&base);
+            self.slices.push(slice);
+            added += 1;
+        }
+        added
+    }
+}
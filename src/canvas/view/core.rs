@@ -232,7 +232,7 @@ impl Canvas {
         &mut self,
         ui: &mut egui::Ui,
         state: &mut CanvasState,
-        tools: Option<&mut crate::components::tools::ToolsPanel>,
+        mut tools: Option<&mut crate::components::tools::ToolsPanel>,
         primary_color_f32: [f32; 4],
         secondary_color_f32: [f32; 4],
         bg_color: Color32,
@@ -302,6 +302,15 @@ impl Canvas {
             self.pan_offset += response.drag_delta();
         }
 
+        if let Some(t) = tools.as_deref_mut()
+            && !modal_open
+            && !pointer_over_blocking_ui
+            && !ui_blocks_canvas_input
+            && !egui::Popup::is_any_open(ui.ctx())
+        {
+            self.grow_infinite_canvas_for_stroke(ui, state, t, canvas_rect);
+        }
+
         // Determine correct texture filter based on zoom level and user settings.
         // User can choose between Linear (smooth) and Nearest (sharp) for zoomed-out views.
         let use_linear_filter = match debug_settings.zoom_filter_mode {
@@ -346,6 +355,8 @@ impl Canvas {
                 state.layer_effectively_visible(idx)
                     && matches!(l.content, LayerContent::Adjustment(_))
             });
+            // Group masks and faded pass-through groups composite on the CPU.
+            let gpu_groups = state.gpu_composite_groups();
 
             // Plan A: filter_changed (zoom crosses 2.0├ù threshold) doesn't
             // require GPU recomposite ÔÇö the pixels haven't changed, only the
@@ -353,7 +364,9 @@ impl Canvas {
             let needs_reupload_only =
                 filter_changed && !pixels_dirty && !state.composite_cpu_buffer.is_empty();
 
-            if pixels_dirty && (has_visible_adjustment || state.cmyk_preview) {
+            if pixels_dirty
+                && (has_visible_adjustment || gpu_groups.is_none() || state.cmyk_preview)
+            {
                 if let (Some(rs), Some(id)) =
                     (self.egui_render_state.as_ref(), self.native_composite_texture.take())
                 {
@@ -482,8 +495,9 @@ impl Canvas {
 
                 let preview_gpu_idx = usize::MAX;
                 gpu.remove_layer(preview_gpu_idx);
+                gpu.set_linear_blending(state.linear_blending);
 
-                let mut layer_info: Vec<(usize, f32, bool, u8)> =
+                let mut layer_info: Vec<(usize, f32, bool, u8, _)> =
                     Vec::with_capacity(state.layers.len());
                 for (i, l) in state.layers.iter().enumerate() {
                     layer_info.push((
@@ -491,8 +505,14 @@ impl Canvas {
                         l.opacity,
                         state.layer_effectively_visible(i),
                         l.blend_mode.to_u8(),
+                        l.blend_if.gpu_ranges(),
                     ));
                 }
+                let group_info: Vec<(usize, usize, f32, u8)> = gpu_groups
+                    .iter()
+                    .flatten()
+                    .map(|g| (g.start, g.end, g.opacity, g.blend_mode.to_u8()))
+                    .collect();
 
                 // Preferred path: compositor output stays on eframe's device and
                 // is registered directly as an egui texture. No readback or
@@ -502,6 +522,7 @@ impl Canvas {
                         state.width,
                         state.height,
                         &layer_info,
+                        &group_info,
                         use_linear_filter,
                     ) {
                         let filter = if use_linear_filter {
@@ -555,6 +576,7 @@ impl Canvas {
                     state.width,
                     state.height,
                     &layer_info,
+                    &group_info,
                     dr_for_readback,
                 );
 
@@ -999,7 +1021,8 @@ impl Canvas {
                 // (e.g. fill tool with semi-transparent colours needs proper
                 // layer-stack compositing to preview accurately).
                 // Also force blend-aware path when there are visible layers above
-                // the active layer, so they get composited correctly.
+                // the active layer, so they get composited correctly, and in
+                // linear-light documents, which the painter cannot reproduce.
                 let active_layer_normal = state
                     .layers
                     .get(state.active_layer_index)
@@ -1020,7 +1043,8 @@ impl Canvas {
                     && !state.preview_targets_mask
                     && !state.preview_force_composite
                     && !has_layers_above
-                    && !state.preview_is_eraser;
+                    && !state.preview_is_eraser
+                    && !state.linear_blending;
 
                 if use_fast_path {
                     if state.preview_flat_ready {
@@ -1331,6 +1355,18 @@ impl Canvas {
             self.draw_pixel_grid(&painter, image_rect, state, canvas_rect, debug_settings);
         }
 
+        if let Some(bounds) = state.document_bounds {
+            self.draw_document_bounds(&painter, image_rect, canvas_rect, bounds);
+        }
+
+        if let Some(selected) = tools
+            .as_ref()
+            .filter(|t| t.active_tool == crate::components::tools::Tool::Slice)
+            .map(|t| t.slice_state.selected)
+        {
+            self.draw_slices_overlay(&painter, image_rect, canvas_rect, &state.slices, selected);
+        }
+
         // Draw mirror axis overlay
         if state.mirror_mode.is_active() {
             self.draw_mirror_overlay(&painter, image_rect, state, canvas_rect);
@@ -2146,6 +2182,7 @@ impl Canvas {
             // AND paste overlay didn't consume the input
             let tool_drag_active = tools.selection_state.dragging
                 || tools.lasso_state.dragging
+                || tools.slice_state.dragging
                 || tools.gradient_state.dragging
                 || tools.text_state.text_box_drag.is_some()
                 || tools.text_state.dragging_handle
@@ -2401,7 +2438,8 @@ impl Canvas {
                         | Tool::PerspectiveCrop
                         | Tool::MagicWand
                         | Tool::ColorRemover
-                        | Tool::Gradient => egui::CursorIcon::Crosshair,
+                        | Tool::Gradient
+                        | Tool::Slice => egui::CursorIcon::Crosshair,
                         Tool::Shapes => {
                             if is_dragging {
                                 egui::CursorIcon::Grabbing
@@ -3234,6 +3272,71 @@ impl Canvas {
     pub fn pan_by(&mut self, delta: Vec2) {
         self.pan_offset += delta;
     }
+
+    /// Infinite canvas mode: when a freehand stroke starts, grow the canvas
+    /// before anything is rasterized so the brush has room around the press
+    /// point. The canvas as it was is left in `tools.canvas_growth_before`,
+    /// so undoing the stroke also undoes the growth.
+    fn grow_infinite_canvas_for_stroke(
+        &mut self,
+        ui: &egui::Ui,
+        state: &mut CanvasState,
+        tools: &mut crate::components::tools::ToolsPanel,
+        canvas_rect: Rect,
+    ) {
+        use crate::components::tools::Tool;
+        if state.document_bounds.is_none()
+            || tools.canvas_growth_before.is_some()
+            || tools.is_stroke_active()
+            || !matches!(
+                tools.active_tool,
+                Tool::Brush | Tool::Pencil | Tool::Eraser | Tool::Smudge | Tool::CloneStamp
+            )
+        {
+            return;
+        }
+        let press = ui.input(|i| {
+            i.events.iter().find_map(|e| match e {
+                egui::Event::PointerButton {
+                    pressed: true,
+                    pos,
+                    button: egui::PointerButton::Primary | egui::PointerButton::Secondary,
+                    ..
+                } => Some(*pos),
+                egui::Event::Touch {
+                    phase: egui::TouchPhase::Start,
+                    pos,
+                    ..
+                } => Some(*pos),
+                _ => None,
+            })
+        });
+        let Some(press) = press.filter(|pos| canvas_rect.contains(*pos)) else {
+            return;
+        };
+        let (x, y) = self.screen_to_canvas_unclamped(press, canvas_rect, state);
+        let r = tools.properties.size * 0.5 + 1.0;
+        let touched = Some((
+            (x - r).floor() as i32,
+            (y - r).floor() as i32,
+            (x + r).ceil() as i32,
+            (y + r).ceil() as i32,
+        ));
+        if !crate::ops::transform::infinite_canvas_needs_growth(state, touched) {
+            return;
+        }
+        let before = crate::components::history::CanvasSnapshot::capture(state);
+        let (old_w, old_h) = (state.width, state.height);
+        let Some((dx, dy)) = crate::ops::transform::grow_infinite_canvas(state, touched) else {
+            return;
+        };
+        // Keep the existing content where it was on screen.
+        let grow_w = (state.width - old_w) as f32;
+        let grow_h = (state.height - old_h) as f32;
+        self.pan_offset -=
+            Vec2::new(dx as f32 - grow_w * 0.5, dy as f32 - grow_h * 0.5) * self.zoom;
+        tools.canvas_growth_before = Some(before);
+    }
 }
 
 fn selection_mask_bounds(mask: &image::GrayImage) -> Option<(u32, u32, u32, u32)> {
@@ -379,6 +379,119 @@ impl Canvas {
         }
     }
 
+    // ========================================================================
+    // DOCUMENT BOUNDS  – export rectangle in infinite canvas mode
+    // ========================================================================
+
+    fn draw_document_bounds(
+        &self,
+        painter: &egui::Painter,
+        image_rect: Rect,
+        viewport: Rect,
+        bounds: (u32, u32, u32, u32),
+    ) {
+        let (x, y, w, h) = bounds;
+        let doc_rect = Rect::from_min_size(
+            Pos2::new(
+                image_rect.min.x + x as f32 * self.zoom,
+                image_rect.min.y + y as f32 * self.zoom,
+            ),
+            Vec2::new(w as f32 * self.zoom, h as f32 * self.zoom),
+        );
+        let clipped_painter = painter.with_clip_rect(viewport);
+        clipped_painter.rect_stroke(
+            doc_rect,
+            0.0,
+            egui::Stroke::new(2.0, Color32::from_black_alpha(140)),
+            egui::StrokeKind::Outside,
+        );
+        clipped_painter.rect_stroke(
+            doc_rect,
+            0.0,
+            egui::Stroke::new(1.0, Color32::from_white_alpha(200)),
+            egui::StrokeKind::Outside,
+        );
+    }
+
+    // ========================================================================
+    // SLICES OVERLAY  – Slice tool rectangles and names
+    // ========================================================================
+
+    fn draw_slices_overlay(
+        &self,
+        painter: &egui::Painter,
+        image_rect: Rect,
+        viewport: Rect,
+        slices: &[crate::canvas::Slice],
+        selected: Option<usize>,
+    ) {
+        let clipped_painter = painter.with_clip_rect(viewport);
+        let accent = self.selection_stroke;
+        for (i, slice) in slices.iter().enumerate() {
+            let rect = Rect::from_min_size(
+                Pos2::new(
+                    image_rect.min.x + slice.x as f32 * self.zoom,
+                    image_rect.min.y + slice.y as f32 * self.zoom,
+                ),
+                Vec2::new(
+                    slice.width as f32 * self.zoom,
+                    slice.height as f32 * self.zoom,
+                ),
+            );
+            if !rect.intersects(viewport) {
+                continue;
+            }
+            let is_selected = selected == Some(i);
+            if is_selected {
+                let [r, g, b, _] = accent.to_array();
+                clipped_painter.rect_filled(
+                    rect,
+                    0.0,
+                    Color32::from_rgba_unmultiplied(r, g, b, 30),
+                );
+            }
+            clipped_painter.rect_stroke(
+                rect,
+                0.0,
+                egui::Stroke::new(2.0, Color32::from_black_alpha(140)),
+                egui::StrokeKind::Inside,
+            );
+            clipped_painter.rect_stroke(
+                rect,
+                0.0,
+                egui::Stroke::new(1.0, if is_selected { accent } else { Color32::WHITE }),
+                egui::StrokeKind::Inside,
+            );
+
+            // Name tag in the top-left corner, when the slice is big enough.
+            if rect.width() < 24.0 || rect.height() < 14.0 {
+                continue;
+            }
+            let galley = clipped_painter.layout_no_wrap(
+                slice.name.clone(),
+                egui::FontId::proportional(11.0),
+                Color32::WHITE,
+            );
+            let tag = Rect::from_min_size(
+                rect.min + Vec2::splat(2.0),
+                galley.size() + Vec2::new(6.0, 2.0),
+            )
+            .intersect(rect);
+            clipped_painter.rect_filled(
+                tag,
+                2.0,
+                if is_selected {
+                    accent
+                } else {
+                    Color32::from_black_alpha(160)
+                },
+            );
+            clipped_painter
+                .with_clip_rect(tag.intersect(viewport))
+                .galley(tag.min + Vec2::new(3.0, 1.0), galley, Color32::WHITE);
+        }
+    }
+
     // ========================================================================
     // MIRROR AXIS OVERLAY  – dashed symmetry lines
     // ========================================================================
@@ -8,6 +8,10 @@
 //   paintfe -i *.jpg --script invert.rhai --output-dir processed/ --format png
 //   paintfe -i project.pfe --output flat.jpg --quality 85
 //   paintfe -i a.png b.png c.png --output-dir out/
+//   paintfe -i mockup.pfe --comp Light --comp Dark --output-dir out/ --format png
+//   paintfe -i ui.pfe --export-layers --trim --output-dir sprites/
+//   paintfe -i icons.pfe --export-slices --output-dir icons/ --format webp
+//   paintfe -i walk.pfe --sprite-sheet layers --sheet-trim -o walk.png
 //
 // No GUI is opened in CLI mode. All processing runs synchronously on the
 // current thread (no rayon, no wgpu) using CPU-only paths.
@@ -19,8 +23,20 @@ use std::time::Instant;
 use clap::Parser;
 
 use crate::components::dialogs::{SaveFormat, TiffCompression};
-use crate::io::{encode_and_write, load_image_sync, save_pfe};
+use crate::config::export_presets::{ExportPreset, ExportPresetFile, load_export_presets};
+use crate::dds_io::{DdsCompression, DdsOptions};
+use crate::io::{
+    LayeredExport, LayeredExportOptions, PresetExport, encode_and_write,
+    encode_canvas_state_and_write, load_image_sync, save_pfe,
+};
+use crate::jpeg_io::{ChromaSubsampling, JpegOptions};
+use crate::ops::layer_comps::comp_file_stem;
+use crate::ops::layer_export::{DEFAULT_LAYER_NAME_TEMPLATE, LayerExportOptions, export_layers};
 use crate::ops::scripting::{apply_canvas_ops, execute_script_sync};
+use crate::ops::slices::{
+    SliceExportOptions, SpriteSheetOptions, SpriteSource, export_slices, export_sprite_sheet,
+};
+use crate::svg_io::SvgOptions;
 
 // ============================================================================
 // CLI argument definition (clap Derive)
@@ -61,7 +77,8 @@ pub struct CliArgs {
     #[arg(long, value_name = "DIR")]
     pub output_dir: Option<PathBuf>,
 
-    /// Output format: png, jpeg, webp, bmp, tga, ico, tiff, gif, pfe.
+    /// Output format: png, jpeg, webp, bmp, tga, qoi, pgm, ppm, pam, dds, ico, icns, tiff, gif,
+    /// psd, ora, exr, hdr, pfe.
     /// When omitted, the format is inferred from --output's extension, defaulting to png.
     #[arg(short, long, value_name = "FORMAT")]
     pub format: Option<String>,
@@ -74,10 +91,133 @@ pub struct CliArgs {
     #[arg(long)]
     pub webp_lossy: bool,
 
-    /// TIFF compression mode: none, lzw, deflate (default: none).
+    /// TIFF compression mode: none, lzw, deflate, packbits, jpeg (default: none).
+    /// JPEG uses --quality and drops transparency.
     #[arg(long, default_value = "none", value_name = "MODE")]
     pub tiff_compression: String,
 
+    /// Write each layer of a TIFF output as its own named page.
+    #[arg(long)]
+    pub tiff_pages: bool,
+
+    /// DDS pixel encoding: none, bc1, bc3, bc7 (default: none).
+    #[arg(long, default_value = "none", value_name = "MODE")]
+    pub dds_compression: String,
+
+    /// Append a full mip chain to DDS output.
+    #[arg(long)]
+    pub mipmaps: bool,
+
+    /// Write progressive JPEG output instead of baseline.
+    #[arg(long)]
+    pub progressive: bool,
+
+    /// JPEG chroma subsampling: 444, 422, 420 (default: 444).
+    #[arg(long, default_value = "444", value_name = "MODE")]
+    pub chroma_subsampling: String,
+
+    /// Build per-image optimized JPEG Huffman tables instead of the standard ones.
+    #[arg(long)]
+    pub optimize_huffman: bool,
+
+    /// Pick the highest JPEG quality whose file fits in this many KB,
+    /// overriding --quality.
+    #[arg(long, value_name = "KB")]
+    pub target_size: Option<u32>,
+
+    /// Resolution SVG input is rasterized at (default 96, the SVG's own size).
+    #[arg(long, default_value_t = crate::svg_io::SVG_BASE_DPI, value_name = "DPI")]
+    pub svg_dpi: f32,
+
+    /// Rasterize SVG input at this width in pixels, overriding --svg-dpi.
+    #[arg(long, value_name = "PX")]
+    pub svg_width: Option<u32>,
+
+    /// Rasterize SVG input at this height in pixels, overriding --svg-dpi.
+    #[arg(long, value_name = "PX")]
+    pub svg_height: Option<u32>,
+
+    /// Render SVG input to one layer instead of one layer per top-level group.
+    #[arg(long)]
+    pub svg_flatten: bool,
+
+    /// Export through a named preset from the export preset file instead of
+    /// --format; repeat for several outputs (e.g. --preset "PNG @1x" --preset WebP).
+    /// Files are named <stem><suffix>.<ext> in --output-dir, --output's folder
+    /// or the input's folder.
+    #[arg(long = "preset", value_name = "NAME")]
+    pub presets: Vec<String>,
+
+    /// Preset file for --preset (default: export_presets.json beside the
+    /// settings file, as saved by File > Export As).
+    #[arg(long, value_name = "FILE")]
+    pub presets_file: Option<PathBuf>,
+
+    /// Apply a layer comp saved in a .pfe input before exporting. Repeat to
+    /// export several comps; each is then written as <stem>-<comp>.<ext>.
+    #[arg(long = "comp", value_name = "NAME")]
+    pub comps: Vec<String>,
+
+    /// Write each layer to its own file instead of one flattened image,
+    /// named by --layer-name in --output-dir, --output's folder or the
+    /// input's folder. Combines with --preset and --format.
+    #[arg(long, conflicts_with = "comps")]
+    pub export_layers: bool,
+
+    /// File name template for --export-layers: {doc}, {layer} and {index}
+    /// are replaced by the document name, layer name and position from the top.
+    #[arg(
+        long,
+        default_value = DEFAULT_LAYER_NAME_TEMPLATE,
+        value_name = "TEMPLATE",
+        requires = "export_layers"
+    )]
+    pub layer_name: String,
+
+    /// With --export-layers, export each top-level group as one file.
+    #[arg(long, requires = "export_layers")]
+    pub export_groups: bool,
+
+    /// With --export-layers, crop each file to its non-transparent pixels.
+    #[arg(long, requires = "export_layers")]
+    pub trim: bool,
+
+    /// With --export-layers, also export hidden layers and groups.
+    #[arg(long, requires = "export_layers")]
+    pub include_hidden: bool,
+
+    /// Write each slice of a .pfe input to its own file, named after the
+    /// slice, in --output-dir, --output's folder or the input's folder.
+    /// Combines with --preset and --format.
+    #[arg(long, conflicts_with_all = ["comps", "export_layers"])]
+    pub export_slices: bool,
+
+    /// Pack the input's slices or visible layers into one PNG sprite sheet
+    /// plus a TexturePacker-style JSON atlas, named by --output or the input.
+    #[arg(
+        long,
+        value_name = "slices|layers",
+        conflicts_with_all = ["comps", "export_layers", "export_slices", "presets"]
+    )]
+    pub sprite_sheet: Option<String>,
+
+    /// Frames per row of the sprite sheet (0 = roughly square).
+    #[arg(long, default_value_t = 0, value_name = "N", requires = "sprite_sheet")]
+    pub sheet_columns: u32,
+
+    /// Transparent pixels around and between sprite sheet frames.
+    #[arg(
+        long,
+        default_value_t = 2,
+        value_name = "PX",
+        requires = "sprite_sheet"
+    )]
+    pub sheet_padding: u32,
+
+    /// Crop each sprite sheet frame to its non-transparent pixels.
+    #[arg(long, requires = "sprite_sheet")]
+    pub sheet_trim: bool,
+
     /// Flatten all visible layers before saving.
     /// Always true for raster formats; PFE output preserves layers regardless.
     #[arg(long, default_value_t = true)]
@@ -125,8 +265,96 @@ pub fn run(args: CliArgs) -> ExitCode {
     let tiff_compression = match args.tiff_compression.to_lowercase().as_str() {
         "lzw" => TiffCompression::Lzw,
         "deflate" => TiffCompression::Deflate,
+        "packbits" => TiffCompression::PackBits,
+        "jpeg" | "jpg" => TiffCompression::Jpeg,
         _ => TiffCompression::None,
     };
+    let dds = DdsOptions {
+        compression: match args.dds_compression.to_lowercase().as_str() {
+            "bc1" | "dxt1" => DdsCompression::Bc1,
+            "bc3" | "dxt5" => DdsCompression::Bc3,
+            "bc7" => DdsCompression::Bc7,
+            _ => DdsCompression::None,
+        },
+        mipmaps: args.mipmaps,
+    };
+    let Some(subsampling) = ChromaSubsampling::parse(&args.chroma_subsampling) else {
+        eprintln!(
+            "error: unknown chroma subsampling '{}' (expected 444, 422 or 420).",
+            args.chroma_subsampling
+        );
+        return ExitCode::FAILURE;
+    };
+    let jpeg = JpegOptions {
+        progressive: args.progressive,
+        subsampling,
+        optimize_huffman: args.optimize_huffman,
+        target_size: args.target_size.map(|kb| kb as usize * 1024),
+    };
+    let svg = SvgOptions {
+        dpi: args.svg_dpi,
+        width: args.svg_width,
+        height: args.svg_height,
+        split_groups: !args.svg_flatten,
+    };
+
+    let presets = if args.presets.is_empty() {
+        Vec::new()
+    } else {
+        match resolve_presets(&args.presets, args.presets_file.as_deref()) {
+            Ok(presets) => presets,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    };
+
+    let layer_export = LayerExportOptions {
+        template: args.layer_name.clone(),
+        groups: args.export_groups,
+        trim: args.trim,
+        include_hidden: args.include_hidden,
+        format: save_format,
+        quality: args.quality,
+        preset: None,
+    };
+    if args.export_layers
+        && let Err(e) = layer_export.validate()
+    {
+        eprintln!("error: {}", e);
+        return ExitCode::FAILURE;
+    }
+
+    let slice_export = SliceExportOptions {
+        format: save_format,
+        quality: args.quality,
+        preset: None,
+    };
+    if args.export_slices
+        && let Err(e) = slice_export.validate()
+    {
+        eprintln!("error: {}", e);
+        return ExitCode::FAILURE;
+    }
+    let sprite_sheet = match args.sprite_sheet.as_deref() {
+        None => None,
+        Some(source) => match SpriteSource::parse(source) {
+            Some(source) => Some(SpriteSheetOptions {
+                source,
+                columns: args.sheet_columns,
+                padding: args.sheet_padding,
+                trim: args.sheet_trim,
+            }),
+            None => {
+                eprintln!(
+                    "error: unknown sprite sheet source '{}' (expected slices or layers).",
+                    source
+                );
+                return ExitCode::FAILURE;
+            }
+        },
+    };
 
     // Load script source if provided
     let script_source: Option<String> = match &args.script {
@@ -163,6 +391,99 @@ pub fn run(args: CliArgs) -> ExitCode {
 
         let file_start = Instant::now();
 
+        if args.export_layers {
+            match run_export_layers(
+                input_path,
+                args.output.as_deref(),
+                args.output_dir.as_deref(),
+                script_source.as_deref(),
+                &presets,
+                &layer_export,
+                &svg,
+                args.verbose,
+            ) {
+                Ok(paths) => {
+                    if args.verbose || multi {
+                        for path in paths {
+                            println!("  → {}", path.display());
+                        }
+                        println!("  ({:.0}ms)", file_start.elapsed().as_secs_f64() * 1000.0);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("  error: {}", e);
+                    any_failure = true;
+                }
+            }
+            continue;
+        }
+
+        if args.export_slices || sprite_sheet.is_some() {
+            let outcome = match &sprite_sheet {
+                Some(options) => run_sprite_sheet(
+                    input_path,
+                    args.output.as_deref(),
+                    args.output_dir.as_deref(),
+                    script_source.as_deref(),
+                    options,
+                    &svg,
+                    args.verbose,
+                ),
+                None => run_export_slices(
+                    input_path,
+                    args.output.as_deref(),
+                    args.output_dir.as_deref(),
+                    script_source.as_deref(),
+                    &presets,
+                    &slice_export,
+                    &svg,
+                    args.verbose,
+                ),
+            };
+            match outcome {
+                Ok(paths) => {
+                    if args.verbose || multi {
+                        for path in paths {
+                            println!("  → {}", path.display());
+                        }
+                        println!("  ({:.0}ms)", file_start.elapsed().as_secs_f64() * 1000.0);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("  error: {}", e);
+                    any_failure = true;
+                }
+            }
+            continue;
+        }
+
+        if !presets.is_empty() {
+            match run_presets(
+                input_path,
+                args.output.as_deref(),
+                args.output_dir.as_deref(),
+                script_source.as_deref(),
+                &presets,
+                &args.comps,
+                &svg,
+                args.verbose,
+            ) {
+                Ok(paths) => {
+                    if args.verbose || multi {
+                        for path in paths {
+                            println!("  → {}", path.display());
+                        }
+                        println!("  ({:.0}ms)", file_start.elapsed().as_secs_f64() * 1000.0);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("  error: {}", e);
+                    any_failure = true;
+                }
+            }
+            continue;
+        }
+
         // Determine output path
         let output_path = match build_output_path(
             input_path,
@@ -185,20 +506,24 @@ pub fn run(args: CliArgs) -> ExitCode {
             input_path,
             &output_path,
             script_source.as_deref(),
+            &args.comps,
             save_format,
             args.quality,
             !args.webp_lossy,
             tiff_compression,
+            args.tiff_pages,
+            &dds,
+            &jpeg,
+            &svg,
             args.flatten,
             args.verbose,
         ) {
-            Ok(()) => {
+            Ok(paths) => {
                 if args.verbose || multi {
-                    println!(
-                        "  → {} ({:.0}ms)",
-                        output_path.display(),
-                        file_start.elapsed().as_secs_f64() * 1000.0
-                    );
+                    for path in paths {
+                        println!("  → {}", path.display());
+                    }
+                    println!("  ({:.0}ms)", file_start.elapsed().as_secs_f64() * 1000.0);
                 }
             }
             Err(e) => {
@@ -219,19 +544,153 @@ pub fn run(args: CliArgs) -> ExitCode {
 // Per-file processing pipeline
 // ============================================================================
 
+/// Load, script and save one input. With `--comp`, saves once per comp.
+/// Returns the paths written.
 fn run_one(
     input: &Path,
     output: &Path,
     script: Option<&str>,
+    comps: &[String],
     format: SaveFormat,
     quality: u8,
     webp_lossless: bool,
     tiff_compression: TiffCompression,
+    tiff_pages: bool,
+    dds: &DdsOptions,
+    jpeg: &JpegOptions,
+    svg: &SvgOptions,
     flatten: bool,
     verbose: bool,
+) -> Result<Vec<PathBuf>, String> {
+    let mut state = load_and_run_script(input, script, svg, verbose)?;
+
+    // -- Step 3: Save ----------------------------------------------------
+    // Ensure text layers are rasterized before compositing/saving
+    state.ensure_all_text_layers_rasterized();
+
+    let save = |state: &crate::canvas::CanvasState, output: &Path| {
+        save_state(
+            state,
+            output,
+            format,
+            quality,
+            webp_lossless,
+            tiff_compression,
+            tiff_pages,
+            dds,
+            jpeg,
+            flatten,
+        )
+    };
+    if comps.is_empty() {
+        save(&state, output)?;
+        return Ok(vec![output.to_path_buf()]);
+    }
+    let mut written = Vec::with_capacity(comps.len());
+    for comp in comps {
+        state.apply_layer_comp_named(comp)?;
+        let path = if comps.len() > 1 {
+            comp_output_path(output, comp)
+        } else {
+            output.to_path_buf()
+        };
+        save(&state, &path)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Write an already loaded document to `output` in `format`.
+fn save_state(
+    state: &crate::canvas::CanvasState,
+    output: &Path,
+    format: SaveFormat,
+    quality: u8,
+    webp_lossless: bool,
+    tiff_compression: TiffCompression,
+    tiff_pages: bool,
+    dds: &DdsOptions,
+    jpeg: &JpegOptions,
+    flatten: bool,
 ) -> Result<(), String> {
+    let layered_options = LayeredExportOptions {
+        tiff_pages,
+        tiff_compression,
+        quality,
+        dds: dds.clone(),
+        jpeg: jpeg.clone(),
+        ..Default::default()
+    };
+    match format {
+        SaveFormat::Pfe => {
+            save_pfe(state, output).map_err(|e| format!("PFE save failed: {:?}", e))?;
+        }
+        _ if LayeredExport::handles(format, &layered_options) => {
+            if let Some(export) = LayeredExport::build(format, state, &layered_options) {
+                export.write(output).map_err(|e| {
+                    format!("{} save failed: {}", format.extension().to_uppercase(), e)
+                })?;
+            }
+        }
+        SaveFormat::Hdr => {
+            // Float target: write the linear samples, not the 8-bit composite
+            encode_canvas_state_and_write(
+                state,
+                output,
+                format,
+                quality,
+                tiff_compression,
+                webp_lossless,
+            )
+            .map_err(|e| format!("HDR save failed: {}", e))?;
+        }
+        _ => {
+            let flat_img = if flatten && state.layers.len() > 1 {
+                // Composite all visible layers
+                state.composite()
+            } else {
+                // Use the active layer directly (single-layer or flatten disabled)
+                let layer = &state.layers[state.active_layer_index];
+                let raw = layer
+                    .pixels
+                    .extract_region_rgba(0, 0, state.width, state.height);
+                image::RgbaImage::from_raw(state.width, state.height, raw)
+                    .unwrap_or_else(|| image::RgbaImage::new(state.width, state.height))
+            };
+
+            encode_and_write(
+                &flat_img,
+                output,
+                format,
+                quality,
+                tiff_compression,
+                webp_lossless,
+            )
+            .map_err(|e| format!("save failed: {}", e))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Steps 1 and 2 shared by format and preset output: load `input` and run
+/// the script on its active layer.
+fn load_and_run_script(
+    input: &Path,
+    script: Option<&str>,
+    svg: &SvgOptions,
+    verbose: bool,
+) -> Result<crate::canvas::CanvasState, String> {
     // -- Step 1: Load ----------------------------------------------------
-    let mut state = load_image_sync(input).map_err(|e| format!("load failed: {}", e))?;
+    let is_svg = input
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"));
+    let mut state = if is_svg {
+        crate::svg_io::load_svg(input, svg)
+    } else {
+        load_image_sync(input)
+    }
+    .map_err(|e| format!("load failed: {}", e))?;
 
     // -- Step 2: Apply script (optional) ---------------------------------
     if let Some(src) = script {
@@ -270,41 +729,167 @@ fn run_one(
         }
     }
 
-    // -- Step 3: Save ----------------------------------------------------
-    // Ensure text layers are rasterized before compositing/saving
+    Ok(state)
+}
+
+/// Write `input` once per preset. Returns the paths written.
+fn run_presets(
+    input: &Path,
+    output: Option<&Path>,
+    output_dir: Option<&Path>,
+    script: Option<&str>,
+    presets: &[ExportPreset],
+    comps: &[String],
+    svg: &SvgOptions,
+    verbose: bool,
+) -> Result<Vec<PathBuf>, String> {
+    let mut state = load_and_run_script(input, script, svg, verbose)?;
     state.ensure_all_text_layers_rasterized();
 
-    match format {
-        SaveFormat::Pfe => {
-            save_pfe(&state, output).map_err(|e| format!("PFE save failed: {:?}", e))?;
-        }
-        _ => {
-            let flat_img = if flatten && state.layers.len() > 1 {
-                // Composite all visible layers
-                state.composite()
-            } else {
-                // Use the active layer directly (single-layer or flatten disabled)
-                let layer = &state.layers[state.active_layer_index];
-                let raw = layer
-                    .pixels
-                    .extract_region_rgba(0, 0, state.width, state.height);
-                image::RgbaImage::from_raw(state.width, state.height, raw)
-                    .unwrap_or_else(|| image::RgbaImage::new(state.width, state.height))
-            };
+    // --output names the stem and folder; the preset adds suffix and extension.
+    let named = output.unwrap_or(input);
+    let stem = named
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .ok_or_else(|| format!("cannot determine output name for '{}'", input.display()))?;
+    let dir = output_dir
+        .or_else(|| named.parent())
+        .unwrap_or_else(|| Path::new(""));
 
-            encode_and_write(
-                &flat_img,
-                output,
-                format,
-                quality,
-                tiff_compression,
-                webp_lossless,
-            )
-            .map_err(|e| format!("save failed: {}", e))?;
+    let mut written = Vec::with_capacity(presets.len() * comps.len().max(1));
+    let mut write_presets = |state: &crate::canvas::CanvasState, stem: &str| {
+        for preset in presets {
+            let path = preset.output_path(dir, stem);
+            PresetExport::build(state, preset)
+                .and_then(|export| export.write(&path))
+                .map_err(|e| format!("preset '{}' failed: {}", preset.name, e))?;
+            written.push(path);
         }
+        Ok::<(), String>(())
+    };
+    if comps.is_empty() {
+        write_presets(&state, &stem)?;
     }
+    for comp in comps {
+        state.apply_layer_comp_named(comp)?;
+        if comps.len() > 1 {
+            write_presets(&state, &comp_file_stem(&stem, comp))?;
+        } else {
+            write_presets(&state, &stem)?;
+        }
+    }
+    Ok(written)
+}
 
-    Ok(())
+/// Write each layer or top-level group of `input` to its own file, once per
+/// preset or, without presets, in the options' format. Returns the paths
+/// written.
+fn run_export_layers(
+    input: &Path,
+    output: Option<&Path>,
+    output_dir: Option<&Path>,
+    script: Option<&str>,
+    presets: &[ExportPreset],
+    options: &LayerExportOptions,
+    svg: &SvgOptions,
+    verbose: bool,
+) -> Result<Vec<PathBuf>, String> {
+    let mut state = load_and_run_script(input, script, svg, verbose)?;
+
+    // --output names {doc} and the folder, as with presets.
+    let named = output.unwrap_or(input);
+    let doc = named
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .ok_or_else(|| format!("cannot determine output name for '{}'", input.display()))?;
+    let dir = output_dir
+        .or_else(|| named.parent())
+        .unwrap_or_else(|| Path::new(""));
+
+    if presets.is_empty() {
+        return export_layers(&mut state, dir, &doc, options);
+    }
+    let mut written = Vec::new();
+    for preset in presets {
+        let options = LayerExportOptions {
+            preset: Some(preset.clone()),
+            ..options.clone()
+        };
+        written.extend(export_layers(&mut state, dir, &doc, &options)?);
+    }
+    Ok(written)
+}
+
+/// Write each slice of `input` to its own file, once per preset or, without
+/// presets, in the options' format. Returns the paths written.
+fn run_export_slices(
+    input: &Path,
+    output: Option<&Path>,
+    output_dir: Option<&Path>,
+    script: Option<&str>,
+    presets: &[ExportPreset],
+    options: &SliceExportOptions,
+    svg: &SvgOptions,
+    verbose: bool,
+) -> Result<Vec<PathBuf>, String> {
+    let mut state = load_and_run_script(input, script, svg, verbose)?;
+    let named = output.unwrap_or(input);
+    let dir = output_dir
+        .or_else(|| named.parent())
+        .unwrap_or_else(|| Path::new(""));
+
+    if presets.is_empty() {
+        return export_slices(&mut state, dir, options);
+    }
+    let mut written = Vec::new();
+    for preset in presets {
+        let options = SliceExportOptions {
+            preset: Some(preset.clone()),
+            ..options.clone()
+        };
+        written.extend(export_slices(&mut state, dir, &options)?);
+    }
+    Ok(written)
+}
+
+/// Pack `input` into a sprite sheet and its JSON atlas. Returns the paths
+/// written.
+fn run_sprite_sheet(
+    input: &Path,
+    output: Option<&Path>,
+    output_dir: Option<&Path>,
+    script: Option<&str>,
+    options: &SpriteSheetOptions,
+    svg: &SvgOptions,
+    verbose: bool,
+) -> Result<Vec<PathBuf>, String> {
+    let mut state = load_and_run_script(input, script, svg, verbose)?;
+
+    // --output names the sheet and folder; the extensions are always
+    // .png and .json.
+    let named = output.unwrap_or(input);
+    let stem = named
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .ok_or_else(|| format!("cannot determine output name for '{}'", input.display()))?;
+    let dir = output_dir
+        .or_else(|| named.parent())
+        .unwrap_or_else(|| Path::new(""));
+    export_sprite_sheet(&mut state, dir, &stem, options)
+}
+
+/// `output` with the comp name appended to its stem, for multi-comp exports.
+fn comp_output_path(output: &Path, comp: &str) -> PathBuf {
+    let stem = output
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut name = comp_file_stem(&stem, comp);
+    if let Some(ext) = output.extension() {
+        name.push('.');
+        name.push_str(&ext.to_string_lossy());
+    }
+    output.with_file_name(name)
 }
 
 // ============================================================================
@@ -349,6 +934,34 @@ fn resolve_inputs(patterns: &[String]) -> Vec<PathBuf> {
     result
 }
 
+/// Look up `names` in the preset file, in the order given.
+fn resolve_presets(names: &[String], file: Option<&Path>) -> Result<Vec<ExportPreset>, String> {
+    let available = match file {
+        Some(path) => ExportPresetFile::load(path)
+            .map(|f| f.presets)
+            .map_err(|e| format!("could not read presets '{}': {}", path.display(), e))?,
+        None => load_export_presets()?,
+    };
+    names
+        .iter()
+        .map(|name| {
+            let preset = available
+                .iter()
+                .find(|p| p.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| {
+                    let known: Vec<&str> = available.iter().map(|p| p.name.as_str()).collect();
+                    format!(
+                        "unknown preset '{}' (available: {})",
+                        name,
+                        known.join(", ")
+                    )
+                })?;
+            preset.validate()?;
+            Ok(preset.clone())
+        })
+        .collect()
+}
+
 /// Choose the [`SaveFormat`] from the `--format` string or infer it from the
 /// output file extension. Defaults to PNG when neither is known.
 fn parse_format(format_arg: Option<&str>, output: Option<&Path>) -> SaveFormat {
@@ -358,9 +971,19 @@ fn parse_format(format_arg: Option<&str>, output: Option<&Path>) -> SaveFormat {
             "webp" => SaveFormat::Webp,
             "bmp" => SaveFormat::Bmp,
             "tga" => SaveFormat::Tga,
+            "qoi" => SaveFormat::Qoi,
+            "pgm" => SaveFormat::Pgm,
+            "ppm" => SaveFormat::Ppm,
+            "pam" => SaveFormat::Pam,
+            "dds" => SaveFormat::Dds,
             "ico" => SaveFormat::Ico,
+            "icns" => SaveFormat::Icns,
             "tiff" | "tif" => SaveFormat::Tiff,
             "gif" => SaveFormat::Gif,
+            "psd" => SaveFormat::Psd,
+            "ora" => SaveFormat::Ora,
+            "exr" => SaveFormat::Exr,
+            "hdr" => SaveFormat::Hdr,
             "pfe" => SaveFormat::Pfe,
             _ => SaveFormat::Png,
         };
@@ -378,9 +1001,19 @@ fn parse_format(format_arg: Option<&str>, output: Option<&Path>) -> SaveFormat {
             "webp" => SaveFormat::Webp,
             "bmp" => SaveFormat::Bmp,
             "tga" => SaveFormat::Tga,
+            "qoi" => SaveFormat::Qoi,
+            "pgm" => SaveFormat::Pgm,
+            "ppm" => SaveFormat::Ppm,
+            "pam" => SaveFormat::Pam,
+            "dds" => SaveFormat::Dds,
             "ico" => SaveFormat::Ico,
+            "icns" => SaveFormat::Icns,
             "tiff" | "tif" => SaveFormat::Tiff,
             "gif" => SaveFormat::Gif,
+            "psd" => SaveFormat::Psd,
+            "ora" => SaveFormat::Ora,
+            "exr" => SaveFormat::Exr,
+            "hdr" => SaveFormat::Hdr,
             "pfe" => SaveFormat::Pfe,
             _ => SaveFormat::Png,
         };
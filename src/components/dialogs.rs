@@ -1,12 +1,16 @@
 use eframe::egui;
 use egui::{Color32, ColorImage, TextureHandle, TextureOptions};
 use image::ImageEncoder;
+use image::RgbaImage;
 use image::codecs::bmp::BmpEncoder;
-use image::codecs::jpeg::JpegEncoder;
 use image::codecs::png::PngEncoder;
+use image::codecs::qoi::QoiEncoder;
 use image::codecs::tiff::TiffEncoder;
 use image::imageops::FilterType;
-use image::{DynamicImage, RgbaImage};
+
+use crate::dds_io::{DdsCompression, DdsOptions};
+use crate::icon_io::IconOptions;
+use crate::jpeg_io::{ChromaSubsampling, JpegOptions};
 use std::io::Cursor;
 use std::path::PathBuf;
 use std::sync::mpsc;
@@ -824,7 +828,8 @@ fn evaluate_dimension_expression(input: &str) -> Option<f32> {
 // IMAGE FORMAT ENUM
 // ============================================================================
 
-#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SaveFormat {
     #[default]
     Png,
@@ -832,9 +837,19 @@ pub enum SaveFormat {
     Webp,
     Bmp,
     Tga,
+    Qoi,
+    Pgm,
+    Ppm,
+    Pam,
+    Dds,
     Ico,
+    Icns,
     Tiff,
     Gif,
+    Psd,
+    Ora,
+    Exr,
+    Hdr,
     Pfe,
 }
 
@@ -845,6 +860,8 @@ pub enum TiffCompression {
     None,
     Lzw,
     Deflate,
+    PackBits,
+    Jpeg,
 }
 
 impl SaveFormat {
@@ -855,9 +872,19 @@ impl SaveFormat {
             SaveFormat::Webp => "webp",
             SaveFormat::Bmp => "bmp",
             SaveFormat::Tga => "tga",
+            SaveFormat::Qoi => "qoi",
+            SaveFormat::Pgm => "pgm",
+            SaveFormat::Ppm => "ppm",
+            SaveFormat::Pam => "pam",
+            SaveFormat::Dds => "dds",
             SaveFormat::Ico => "ico",
+            SaveFormat::Icns => "icns",
             SaveFormat::Tiff => "tiff",
             SaveFormat::Gif => "gif",
+            SaveFormat::Psd => "psd",
+            SaveFormat::Ora => "ora",
+            SaveFormat::Exr => "exr",
+            SaveFormat::Hdr => "hdr",
             SaveFormat::Pfe => "pfe",
         }
     }
@@ -869,32 +896,92 @@ impl SaveFormat {
             SaveFormat::Webp => t!("format.webp"),
             SaveFormat::Bmp => t!("format.bmp"),
             SaveFormat::Tga => t!("format.tga"),
+            SaveFormat::Qoi => t!("format.qoi"),
+            SaveFormat::Pgm => t!("format.pgm"),
+            SaveFormat::Ppm => t!("format.ppm"),
+            SaveFormat::Pam => t!("format.pam"),
+            SaveFormat::Dds => t!("format.dds"),
             SaveFormat::Ico => t!("format.ico"),
+            SaveFormat::Icns => t!("format.icns"),
             SaveFormat::Tiff => t!("format.tiff"),
             SaveFormat::Gif => t!("format.gif"),
+            SaveFormat::Psd => t!("format.psd"),
+            SaveFormat::Ora => t!("format.ora"),
+            SaveFormat::Exr => t!("format.exr"),
+            SaveFormat::Hdr => t!("format.hdr"),
             SaveFormat::Pfe => t!("format.pfe"),
         }
     }
 
+    /// Format matching a file extension (case-insensitive), if PaintFE can save it.
+    pub fn from_extension(ext: &str) -> Option<SaveFormat> {
+        match ext.to_ascii_lowercase().as_str() {
+            "png" => Some(SaveFormat::Png),
+            "jpg" | "jpeg" => Some(SaveFormat::Jpeg),
+            "webp" => Some(SaveFormat::Webp),
+            "bmp" => Some(SaveFormat::Bmp),
+            "tga" => Some(SaveFormat::Tga),
+            "qoi" => Some(SaveFormat::Qoi),
+            "pgm" => Some(SaveFormat::Pgm),
+            "ppm" => Some(SaveFormat::Ppm),
+            "pam" => Some(SaveFormat::Pam),
+            "dds" => Some(SaveFormat::Dds),
+            "ico" => Some(SaveFormat::Ico),
+            "icns" => Some(SaveFormat::Icns),
+            "tiff" | "tif" => Some(SaveFormat::Tiff),
+            "gif" => Some(SaveFormat::Gif),
+            "psd" => Some(SaveFormat::Psd),
+            "ora" => Some(SaveFormat::Ora),
+            "exr" => Some(SaveFormat::Exr),
+            "hdr" => Some(SaveFormat::Hdr),
+            "pfe" => Some(SaveFormat::Pfe),
+            _ => None,
+        }
+    }
+
     pub fn supports_quality(&self) -> bool {
         matches!(self, SaveFormat::Jpeg | SaveFormat::Webp)
     }
 
+    /// Returns true if saving keeps layers (project and interchange formats).
+    pub fn supports_layers(&self) -> bool {
+        matches!(
+            self,
+            SaveFormat::Pfe | SaveFormat::Psd | SaveFormat::Ora | SaveFormat::Exr
+        )
+    }
+
+    /// Returns true for the multi-size icon formats, which render their
+    /// entries from the layer stack (see [`crate::icon_io`]).
+    pub fn is_icon(&self) -> bool {
+        matches!(self, SaveFormat::Ico | SaveFormat::Icns)
+    }
+
     /// Returns true if this format supports animated (multi-frame) output.
     pub fn supports_animation(&self) -> bool {
         matches!(self, SaveFormat::Png | SaveFormat::Gif | SaveFormat::Webp)
     }
 
-    fn all() -> &'static [SaveFormat] {
+    pub(crate) fn all() -> &'static [SaveFormat] {
         &[
             SaveFormat::Pfe,
+            SaveFormat::Psd,
+            SaveFormat::Ora,
             SaveFormat::Png,
             SaveFormat::Jpeg,
             SaveFormat::Webp,
             SaveFormat::Bmp,
             SaveFormat::Tga,
+            SaveFormat::Qoi,
+            SaveFormat::Pgm,
+            SaveFormat::Ppm,
+            SaveFormat::Pam,
+            SaveFormat::Dds,
             SaveFormat::Ico,
+            SaveFormat::Icns,
             SaveFormat::Tiff,
+            SaveFormat::Exr,
+            SaveFormat::Hdr,
             SaveFormat::Gif,
         ]
     }
@@ -942,27 +1029,7 @@ pub fn generate_preview(
             })
         }
         SaveFormat::Jpeg => {
-            // JPEG is lossy - encode then decode to show artifacts
-            let rgb_image = DynamicImage::ImageRgba8(image.clone()).to_rgb8();
-            let mut cursor = Cursor::new(&mut buffer);
-            let mut encoder = JpegEncoder::new_with_quality(&mut cursor, quality);
-            encoder
-                .encode(
-                    rgb_image.as_raw(),
-                    rgb_image.width(),
-                    rgb_image.height(),
-                    image::ColorType::Rgb8.into(),
-                )
-                .ok()?;
-
-            // Decode back to show compression artifacts
-            let decoded = image::load_from_memory(&buffer).ok()?;
-            let preview = decoded.to_rgba8();
-
-            Some(PreviewResult {
-                file_size: buffer.len(),
-                preview_image: preview,
-            })
+            jpeg_preview(image, quality, &JpegOptions::default()).map(|(preview, _)| preview)
         }
         SaveFormat::Webp => {
             #[cfg(not(target_arch = "wasm32"))]
@@ -1030,8 +1097,43 @@ pub fn generate_preview(
                 preview_image: image.clone(),
             })
         }
-        SaveFormat::Ico => {
-            // ICO is lossless (PNG-encoded inside)
+        SaveFormat::Qoi => {
+            // QOI is lossless
+            let mut cursor = Cursor::new(&mut buffer);
+            QoiEncoder::new(&mut cursor)
+                .write_image(
+                    image.as_raw(),
+                    image.width(),
+                    image.height(),
+                    image::ExtendedColorType::Rgba8,
+                )
+                .ok()?;
+
+            Some(PreviewResult {
+                file_size: buffer.len(),
+                preview_image: image.clone(),
+            })
+        }
+        SaveFormat::Pgm | SaveFormat::Ppm | SaveFormat::Pam => {
+            // Lossless, but PGM drops colour and PGM/PPM drop alpha — decode
+            // back so the preview shows it.
+            let buffer = crate::io::encode_netpbm(
+                format,
+                image.width(),
+                image.height(),
+                image.as_raw(),
+                255,
+            );
+            let preview = image::load_from_memory(&buffer).ok()?.to_rgba8();
+
+            Some(PreviewResult {
+                file_size: buffer.len(),
+                preview_image: preview,
+            })
+        }
+        SaveFormat::Dds => dds_preview(image, &DdsOptions::default()),
+        SaveFormat::Ico | SaveFormat::Icns => {
+            // Icons are lossless (PNG-encoded inside)
             // Estimate size based on the image data
             let mut buffer = Vec::new();
             let mut cursor = Cursor::new(&mut buffer);
@@ -1072,8 +1174,18 @@ pub fn generate_preview(
                 preview_image: image.clone(),
             })
         }
-        SaveFormat::Pfe => {
-            // PFE is a project format — no single-image preview needed
+        SaveFormat::Exr | SaveFormat::Hdr => {
+            // Float formats — the preview shows the 8-bit rendering; estimate
+            // uncompressed half-float RGBA as an upper bound.
+            let estimated_size = (image.width() * image.height() * 4) as usize * 2 + 512;
+
+            Some(PreviewResult {
+                file_size: estimated_size,
+                preview_image: image.clone(),
+            })
+        }
+        SaveFormat::Psd | SaveFormat::Ora | SaveFormat::Pfe => {
+            // Layered project formats — no single-image preview needed
             // Estimate a rough size (header + raw pixels)
             let estimated_size = (image.width() * image.height() * 4) as usize + 64;
 
@@ -1095,6 +1207,42 @@ pub fn generate_preview(
     }
 }
 
+/// Encode `image` as a DDS texture with `options` and decode the top level
+/// back, so block-compression artifacts show in the preview.
+pub fn dds_preview(image: &RgbaImage, options: &DdsOptions) -> Option<PreviewResult> {
+    let buffer = crate::dds_io::encode_dds(image, options);
+    let preview = crate::dds_io::decode_dds(&buffer).ok()?;
+    Some(PreviewResult {
+        file_size: buffer.len(),
+        preview_image: preview,
+    })
+}
+
+/// Encode `image` as a JPEG with `options` and decode it back so the
+/// preview shows compression artifacts. Also returns the quality used, which
+/// differs from `quality` in target size mode.
+pub fn jpeg_preview(
+    image: &RgbaImage,
+    quality: u8,
+    options: &JpegOptions,
+) -> Option<(PreviewResult, u8)> {
+    let (quality, buffer) = match options.target_size {
+        Some(target) => crate::jpeg_io::fit_quality(image, target, options).ok()?,
+        None => (
+            quality,
+            crate::jpeg_io::encode_at_quality(image, quality, options).ok()?,
+        ),
+    };
+    let preview = image::load_from_memory(&buffer).ok()?.to_rgba8();
+    Some((
+        PreviewResult {
+            file_size: buffer.len(),
+            preview_image: preview,
+        },
+        quality,
+    ))
+}
+
 /// Create a thumbnail of an image for preview display
 pub fn create_thumbnail(image: &RgbaImage, max_size: u32) -> RgbaImage {
     let (width, height) = image.dimensions();
@@ -1143,6 +1291,14 @@ pub struct SaveAction {
     pub gif_colors: u16,
     /// GIF dithering enabled
     pub gif_dither: bool,
+    /// Entry sizes and sources for ICO / ICNS output
+    pub icon: IconOptions,
+    /// Write each layer as a TIFF page instead of flattening
+    pub tiff_pages: bool,
+    /// Block compression and mip chain for DDS output
+    pub dds: DdsOptions,
+    /// Progressive, subsampling and target size for JPEG output
+    pub jpeg: JpegOptions,
 }
 
 // ============================================================================
@@ -1193,6 +1349,24 @@ pub struct SaveFileDialog {
     layer_count: usize, // number of layers/frames available
     was_animated: bool, // whether source was animated on import
 
+    // Icon options (ICO / ICNS)
+    icon_options: IconOptions,
+    /// TIFF: write each layer as its own page
+    tiff_pages: bool,
+    /// DDS: block compression and mipmaps
+    dds_options: DdsOptions,
+    last_preview_dds: DdsOptions,
+    /// JPEG: encoder options
+    jpeg_options: JpegOptions,
+    last_preview_jpeg: JpegOptions,
+    /// JPEG target size in KB, kept while target mode is off
+    jpeg_target_kb: u32,
+    /// Quality the target size search settled on for the preview
+    jpeg_fitted_quality: Option<u8>,
+    /// Show the unencoded image instead of the encoded preview
+    show_original: bool,
+    original_texture: Option<TextureHandle>,
+
     // Animation preview playback
     anim_playing: bool,                         // play/pause state
     anim_current_frame: usize,                  // which frame is displayed (0-based)
@@ -1231,6 +1405,16 @@ impl Default for SaveFileDialog {
             gif_dither: true,
             layer_count: 1,
             was_animated: false,
+            icon_options: IconOptions::default(),
+            tiff_pages: false,
+            dds_options: DdsOptions::default(),
+            last_preview_dds: DdsOptions::default(),
+            jpeg_options: JpegOptions::default(),
+            last_preview_jpeg: JpegOptions::default(),
+            jpeg_target_kb: 200,
+            jpeg_fitted_quality: None,
+            show_original: false,
+            original_texture: None,
             anim_playing: false,
             anim_current_frame: 0,
             anim_last_frame_time: 0.0,
@@ -1248,6 +1432,8 @@ impl SaveFileDialog {
         self.source_thumbnail = None;
         self.source_dimensions = (0, 0);
         self.preview_texture = None;
+        self.original_texture = None;
+        self.show_original = false;
         self.preview_file_size = 0;
         self.estimated_full_size = 0;
         self.needs_preview_update = true;
@@ -1275,6 +1461,7 @@ impl SaveFileDialog {
     pub fn set_source_image(&mut self, image: &RgbaImage) {
         self.source_dimensions = (image.width(), image.height());
         self.source_thumbnail = Some(create_thumbnail(image, PREVIEW_MAX_SIZE));
+        self.original_texture = None;
         self.needs_preview_update = true;
         // Reset pan/zoom so auto-fit recalculates for the new image.
         self.preview_zoom = 0.0;
@@ -1322,6 +1509,7 @@ impl SaveFileDialog {
                 "bmp" => SaveFormat::Bmp,
                 "tga" => SaveFormat::Tga,
                 "ico" => SaveFormat::Ico,
+                "icns" => SaveFormat::Icns,
                 "tiff" | "tif" => SaveFormat::Tiff,
                 "gif" => SaveFormat::Gif,
                 _ => SaveFormat::Png,
@@ -1338,17 +1526,48 @@ impl SaveFileDialog {
             || (self.format.supports_quality() && self.quality != self.last_preview_quality);
         let webp_settings_changed = self.format == SaveFormat::Webp
             && self.webp_lossless != self.last_preview_webp_lossless;
-
-        if !self.needs_preview_update && !settings_changed && !webp_settings_changed {
+        let dds_settings_changed =
+            self.format == SaveFormat::Dds && self.dds_options != self.last_preview_dds;
+        let jpeg_settings_changed =
+            self.format == SaveFormat::Jpeg && self.jpeg_options != self.last_preview_jpeg;
+
+        if !self.needs_preview_update
+            && !settings_changed
+            && !webp_settings_changed
+            && !dds_settings_changed
+            && !jpeg_settings_changed
+        {
             return;
         }
 
+        self.jpeg_fitted_quality = None;
         if let Some(thumbnail) = &self.source_thumbnail {
             let thumb_w = thumbnail.width();
             let thumb_h = thumbnail.height();
-            if let Some(preview_result) =
+            let preview = if self.format == SaveFormat::Dds {
+                dds_preview(thumbnail, &self.dds_options)
+            } else if self.format == SaveFormat::Jpeg {
+                // The thumbnail gets the share of the target its pixel count
+                // is of the full image, so the fitted quality carries over.
+                let (fw, fh) = self.source_dimensions;
+                let share = (thumb_w as f64 * thumb_h as f64) / (fw as f64 * fh as f64).max(1.0);
+                let options = JpegOptions {
+                    target_size: self
+                        .jpeg_options
+                        .target_size
+                        .map(|t| ((t as f64 * share) as usize).max(1)),
+                    ..self.jpeg_options.clone()
+                };
+                jpeg_preview(thumbnail, self.quality, &options).map(|(preview, quality)| {
+                    if options.target_size.is_some() {
+                        self.jpeg_fitted_quality = Some(quality);
+                    }
+                    preview
+                })
+            } else {
                 generate_preview(thumbnail, self.format, self.quality, self.webp_lossless)
-            {
+            };
+            if let Some(preview_result) = preview {
                 // Update texture
                 let color_image = rgba_to_color_image(&preview_result.preview_image);
                 self.preview_texture =
@@ -1371,6 +1590,8 @@ impl SaveFileDialog {
         self.last_preview_format = self.format;
         self.last_preview_quality = self.quality;
         self.last_preview_webp_lossless = self.webp_lossless;
+        self.last_preview_dds = self.dds_options.clone();
+        self.last_preview_jpeg = self.jpeg_options.clone();
         self.needs_preview_update = false;
     }
 
@@ -1450,6 +1671,7 @@ impl SaveFileDialog {
                             // Reload textures if filter changed
                             if use_nearest != self.preview_texture_is_nearest {
                                 self.preview_texture = None;
+                                self.original_texture = None;
                                 self.frame_textures.iter_mut().for_each(|t| *t = None);
                                 self.preview_texture_is_nearest = use_nearest;
                             }
@@ -1467,6 +1689,18 @@ impl SaveFileDialog {
                                 ));
                             }
 
+                            let compare = self.show_original && self.format.supports_quality();
+                            if compare
+                                && self.original_texture.is_none()
+                                && let Some(thumb) = &self.source_thumbnail
+                            {
+                                self.original_texture = Some(ctx.load_texture(
+                                    "save_preview_original",
+                                    rgba_to_color_image(thumb),
+                                    tex_opts,
+                                ));
+                            }
+
                             // Determine which texture to show
                             let display_texture = if show_anim_controls {
                                 let idx = self.anim_current_frame.min(self.frame_thumbnails.len().saturating_sub(1));
@@ -1479,6 +1713,8 @@ impl SaveFileDialog {
                                     ));
                                 }
                                 self.frame_textures[idx].as_ref()
+                            } else if compare {
+                                self.original_texture.as_ref()
                             } else {
                                 self.preview_texture.as_ref()
                             };
@@ -1625,6 +1861,12 @@ impl SaveFileDialog {
                                     self.preview_zoom = 0.0;
                                     self.preview_pan = egui::Vec2::ZERO;
                                 }
+                                // Before / after: the unencoded image vs. the encoded preview
+                                if self.format.supports_quality() && !show_anim_controls {
+                                    ui.separator();
+                                    ui.selectable_value(&mut self.show_original, true, t!("dialog.save.before"));
+                                    ui.selectable_value(&mut self.show_original, false, t!("dialog.save.after"));
+                                }
                             });
 
                             // Animation playback controls
@@ -1661,7 +1903,13 @@ impl SaveFileDialog {
                                 });
                                 ui.small(format!("Frame {} / {}", self.anim_current_frame + 1, self.frame_thumbnails.len()));
                             } else {
-                                let size_text = format!("Est. {}", Self::format_file_size(self.estimated_full_size));
+                                let mut size_text = format!("Est. {}", Self::format_file_size(self.estimated_full_size));
+                                if self.format == SaveFormat::Jpeg
+                                    && let Some(quality) = self.jpeg_fitted_quality
+                                {
+                                    // Fitted on the thumbnail; the save fits the full image.
+                                    size_text.push_str(&format!(" {}", t!("dialog.save.fitted_quality", quality = quality)));
+                                }
                                 ui.label(egui::RichText::new(size_text).size(11.0).color(colors.text_muted));
                             }
 
@@ -1700,7 +1948,9 @@ impl SaveFileDialog {
                                 }
                                 if self.format != SaveFormat::Webp || !self.webp_lossless {
                                     section_label(ui, &colors, "QUALITY");
-                                    if ui.add(egui::Slider::new(&mut self.quality, 1..=100).suffix("%")).changed() {
+                                    let fixed_quality = self.format != SaveFormat::Jpeg
+                                        || self.jpeg_options.target_size.is_none();
+                                    if ui.add_enabled(fixed_quality, egui::Slider::new(&mut self.quality, 1..=100).suffix("%")).changed() {
                                         ctx.request_repaint();
                                     }
                                 }
@@ -1722,6 +1972,41 @@ impl SaveFileDialog {
                                 }
                             }
 
+                            // ── ENCODING (JPEG) ───────────────────────────────
+                            if self.format == SaveFormat::Jpeg {
+                                accent_separator(ui, &colors);
+                                section_label(ui, &colors, &t!("dialog.save.encoding"));
+                                ui.checkbox(&mut self.jpeg_options.progressive, t!("dialog.save.progressive"));
+                                ui.checkbox(&mut self.jpeg_options.optimize_huffman, t!("dialog.save.optimize_huffman"));
+                                ui.horizontal(|ui| {
+                                    ui.label(t!("dialog.save.chroma"));
+                                    egui::ComboBox::from_id_salt("jpeg_subsampling_combo")
+                                        .width(150.0)
+                                        .selected_text(self.jpeg_options.subsampling.label())
+                                        .show_ui(ui, |ui| {
+                                            for subsampling in ChromaSubsampling::ALL {
+                                                ui.selectable_value(&mut self.jpeg_options.subsampling, subsampling, subsampling.label());
+                                            }
+                                        });
+                                });
+                                ui.horizontal(|ui| {
+                                    let mut use_target = self.jpeg_options.target_size.is_some();
+                                    ui.checkbox(&mut use_target, t!("dialog.save.target_size"));
+                                    ui.add_enabled(
+                                        use_target,
+                                        egui::DragValue::new(&mut self.jpeg_target_kb)
+                                            .range(1..=100_000)
+                                            .speed(5.0)
+                                            .suffix(" KB"),
+                                    );
+                                    self.jpeg_options.target_size =
+                                        use_target.then_some(self.jpeg_target_kb as usize * 1024);
+                                });
+                                if self.jpeg_options.target_size.is_some() {
+                                    ui.label(egui::RichText::new(t!("dialog.save.target_size_hint")).size(11.0).color(colors.text_muted));
+                                }
+                            }
+
                             // ── COMPRESSION (TIFF) ────────────────────────────
                             if self.format == SaveFormat::Tiff {
                                 accent_separator(ui, &colors);
@@ -1729,29 +2014,81 @@ impl SaveFileDialog {
                                 egui::ComboBox::from_id_salt("tiff_compression_combo")
                                     .width(160.0)
                                     .selected_text(match self.tiff_compression {
-                                        TiffCompression::None    => "None",
-                                        TiffCompression::Lzw     => "LZW",
-                                        TiffCompression::Deflate => "Deflate",
+                                        TiffCompression::None     => "None",
+                                        TiffCompression::Lzw      => "LZW",
+                                        TiffCompression::Deflate  => "Deflate",
+                                        TiffCompression::PackBits => "PackBits",
+                                        TiffCompression::Jpeg     => "JPEG",
                                     })
                                     .show_ui(ui, |ui| {
-                                        ui.selectable_value(&mut self.tiff_compression, TiffCompression::None,    "None");
-                                        ui.selectable_value(&mut self.tiff_compression, TiffCompression::Lzw,     "LZW");
-                                        ui.selectable_value(&mut self.tiff_compression, TiffCompression::Deflate, "Deflate");
+                                        ui.selectable_value(&mut self.tiff_compression, TiffCompression::None,     "None");
+                                        ui.selectable_value(&mut self.tiff_compression, TiffCompression::Lzw,      "LZW");
+                                        ui.selectable_value(&mut self.tiff_compression, TiffCompression::Deflate,  "Deflate");
+                                        ui.selectable_value(&mut self.tiff_compression, TiffCompression::PackBits, "PackBits");
+                                        ui.selectable_value(&mut self.tiff_compression, TiffCompression::Jpeg,     "JPEG");
                                     });
+                                if self.tiff_compression == TiffCompression::Jpeg {
+                                    section_label(ui, &colors, "QUALITY");
+                                    ui.add(egui::Slider::new(&mut self.quality, 1..=100).suffix("%"));
+                                    ui.label(egui::RichText::new("JPEG pages drop transparency and store 8-bit RGB.").size(11.0).color(colors.text_muted));
+                                }
+                                if self.layer_count > 1 {
+                                    ui.checkbox(&mut self.tiff_pages, "Layers as pages");
+                                }
                             }
 
-                            // ── ICO warning ───────────────────────────────────
-                            if self.format == SaveFormat::Ico {
+                            // ── TEXTURE (DDS) ─────────────────────────────────
+                            if self.format == SaveFormat::Dds {
                                 accent_separator(ui, &colors);
-                                ui.add_space(4.0);
-                                let (w, h) = self.source_dimensions;
-                                if w > 256 || h > 256 {
-                                    ui.colored_label(
-                                        Color32::from_rgb(255, 180, 50),
-                                        format!("\u{26A0} {}×{} exceeds 256×256 and will be scaled down.", w, h),
-                                    );
+                                section_label(ui, &colors, "TEXTURE");
+                                egui::ComboBox::from_id_salt("dds_compression_combo")
+                                    .width(160.0)
+                                    .selected_text(self.dds_options.compression.label())
+                                    .show_ui(ui, |ui| {
+                                        for compression in DdsCompression::ALL {
+                                            ui.selectable_value(&mut self.dds_options.compression, compression, compression.label());
+                                        }
+                                    });
+                                ui.checkbox(&mut self.dds_options.mipmaps, "Generate mipmaps");
+                                if self.dds_options.compression == DdsCompression::Bc1 {
+                                    ui.label(egui::RichText::new("BC1 keeps only on/off transparency.").size(11.0).color(colors.text_muted));
+                                }
+                            }
+
+                            // ── ICON SIZES (ICO / ICNS) ───────────────────────
+                            if self.format.is_icon() {
+                                accent_separator(ui, &colors);
+                                section_label(ui, &colors, "ICON SIZES");
+                                let sizes: &[u32] = if self.format == SaveFormat::Icns {
+                                    &crate::icon_io::ICNS_SIZES
                                 } else {
-                                    ui.label(egui::RichText::new("ICO supports up to 256×256 per entry.").size(11.0).color(colors.text_muted));
+                                    &crate::icon_io::ICO_SIZES
+                                };
+                                ui.horizontal_wrapped(|ui| {
+                                    for &size in sizes {
+                                        let mut on = self.icon_options.sizes.contains(&size);
+                                        if ui.checkbox(&mut on, format!("{}", size)).changed() {
+                                            if on {
+                                                self.icon_options.sizes.push(size);
+                                                self.icon_options.sizes.sort_unstable();
+                                            } else {
+                                                self.icon_options.sizes.retain(|&s| s != size);
+                                            }
+                                        }
+                                    }
+                                });
+                                ui.checkbox(&mut self.icon_options.use_size_layers, "Use size layers (e.g. 32x32)");
+                                if self.format == SaveFormat::Ico {
+                                    ui.checkbox(
+                                        &mut self.icon_options.png_large,
+                                        format!("PNG-compress {}px and up", crate::icon_io::PNG_ENTRY_MIN),
+                                    );
+                                }
+                                let longest = self.source_dimensions.0.max(self.source_dimensions.1);
+                                if sizes.iter().any(|s| *s > longest && self.icon_options.sizes.contains(s)) {
+                                    ui.label(egui::RichText::new(
+                                        format!("Sizes above {}px are skipped unless a matching layer exists.", longest)
+                                    ).size(11.0).color(colors.text_muted));
                                 }
                             }
 
@@ -1845,6 +2182,10 @@ impl SaveFileDialog {
                                         animation_fps: self.animation_fps,
                                         gif_colors: self.gif_colors,
                                         gif_dither: self.gif_dither,
+                                        icon: self.icon_options.clone(),
+                                        tiff_pages: self.tiff_pages && self.layer_count > 1,
+                                        dds: self.dds_options.clone(),
+                                        jpeg: self.jpeg_options.clone(),
                                     });
                                     should_close = true;
                                 }
@@ -1858,9 +2199,212 @@ impl SaveFileDialog {
             self.open = false;
             self.anim_playing = false;
             self.preview_texture = None;
+            self.original_texture = None;
             self.frame_textures.clear();
         }
 
         result
     }
 }
+
+// ============================================================================
+// CRASH RECOVERY DIALOG
+// ============================================================================
+
+/// Thumbnail edge used for each autosave row in the recovery dialog.
+const RECOVERY_THUMB_SIZE: f32 = 64.0;
+
+struct RecoveryItem {
+    entry: crate::io::AutosaveEntry,
+    selected: bool,
+    thumbnail: Option<RgbaImage>,
+    texture: Option<TextureHandle>,
+}
+
+/// What the user decided in the recovery dialog.
+/// Entries in neither list were left for the next launch ("Decide Later").
+pub struct RecoveryDecision {
+    pub restore: Vec<crate::io::AutosaveEntry>,
+    pub discard: Vec<crate::io::AutosaveEntry>,
+}
+
+/// Startup dialog listing autosaves left behind by a session that did not
+/// close cleanly, with a thumbnail, original location and age for each.
+#[derive(Default)]
+pub struct RecoveryDialog {
+    pub open: bool,
+    items: Vec<RecoveryItem>,
+    now: u64,
+}
+
+impl RecoveryDialog {
+    /// Open the dialog for the given orphaned autosaves (no-op when empty).
+    pub fn open_with(&mut self, entries: Vec<crate::io::AutosaveEntry>) {
+        if entries.is_empty() {
+            return;
+        }
+        self.now = crate::io::unix_time_now();
+        self.items = entries
+            .into_iter()
+            .map(|entry| {
+                let thumbnail = entry
+                    .thumbnail_path
+                    .as_ref()
+                    .and_then(|p| image::open(p).ok())
+                    .map(|img| img.to_rgba8());
+                RecoveryItem {
+                    entry,
+                    selected: true,
+                    thumbnail,
+                    texture: None,
+                }
+            })
+            .collect();
+        self.open = true;
+    }
+
+    /// Show the dialog and return the user's decision once a button is clicked.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<RecoveryDecision> {
+        use crate::ops::dialogs::{
+            DialogColors, accent_separator, contrast_text_color, paint_dialog_header,
+        };
+
+        if !self.open {
+            return None;
+        }
+
+        let mut restore_clicked = false;
+        let mut discard_clicked = false;
+        let mut later_clicked = false;
+        let now = self.now;
+
+        egui::Window::new("recovery_dialog_internal")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                let colors = DialogColors::from_ctx(ctx);
+                ui.set_min_width(480.0);
+
+                if paint_dialog_header(ui, &colors, "\u{1F504}", &t!("dialog.recovery.title")) {
+                    later_clicked = true;
+                }
+                ui.add_space(6.0);
+                ui.label(t!("dialog.recovery.message"));
+                ui.add_space(6.0);
+
+                egui::ScrollArea::vertical()
+                    .max_height(360.0)
+                    .show(ui, |ui| {
+                        for (idx, item) in self.items.iter_mut().enumerate() {
+                            if item.texture.is_none()
+                                && let Some(thumb) = &item.thumbnail
+                            {
+                                item.texture = Some(ctx.load_texture(
+                                    format!("recovery_thumb_{}", idx),
+                                    rgba_to_color_image(thumb),
+                                    TextureOptions::LINEAR,
+                                ));
+                            }
+                            let manifest = &item.entry.manifest;
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut item.selected, "");
+                                let thumb_size =
+                                    egui::vec2(RECOVERY_THUMB_SIZE, RECOVERY_THUMB_SIZE);
+                                let (rect, _) =
+                                    ui.allocate_exact_size(thumb_size, egui::Sense::hover());
+                                ui.painter().rect_filled(rect, 2.0, colors.accent_faint);
+                                if let Some(tex) = &item.texture {
+                                    let [tw, th] = tex.size();
+                                    let scale =
+                                        (RECOVERY_THUMB_SIZE / tw.max(th).max(1) as f32).min(1.0);
+                                    let size = egui::vec2(tw as f32 * scale, th as f32 * scale);
+                                    let img_rect =
+                                        egui::Rect::from_center_size(rect.center(), size);
+                                    ui.painter().image(
+                                        tex.id(),
+                                        img_rect,
+                                        egui::Rect::from_min_max(
+                                            egui::pos2(0.0, 0.0),
+                                            egui::pos2(1.0, 1.0),
+                                        ),
+                                        Color32::WHITE,
+                                    );
+                                }
+                                ui.vertical(|ui| {
+                                    let title = if manifest.is_dirty {
+                                        format!("{}*", manifest.name)
+                                    } else {
+                                        manifest.name.clone()
+                                    };
+                                    ui.label(egui::RichText::new(title).strong());
+                                    let location = match &manifest.original_path {
+                                        Some(path) => path.display().to_string(),
+                                        None => t!("dialog.recovery.untitled"),
+                                    };
+                                    ui.label(egui::RichText::new(location).weak());
+                                    let mut details =
+                                        crate::io::format_autosave_age(manifest.saved_at, now);
+                                    if manifest.width > 0 && manifest.height > 0 {
+                                        details = format!(
+                                            "{}  \u{2022}  {}\u{00D7}{}",
+                                            details, manifest.width, manifest.height
+                                        );
+                                    }
+                                    ui.label(egui::RichText::new(details).weak().small());
+                                });
+                            });
+                            ui.add_space(4.0);
+                        }
+                    });
+
+                accent_separator(ui, &colors);
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    if ui.button(t!("dialog.recovery.discard_all")).clicked() {
+                        discard_clicked = true;
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let any_selected = self.items.iter().any(|i| i.selected);
+                        let restore_btn = egui::Button::new(
+                            egui::RichText::new(t!("dialog.recovery.restore"))
+                                .color(contrast_text_color(colors.accent))
+                                .strong(),
+                        )
+                        .fill(colors.accent);
+                        if ui.add_enabled(any_selected, restore_btn).clicked() {
+                            restore_clicked = true;
+                        }
+                        if ui.button(t!("dialog.recovery.later")).clicked() {
+                            later_clicked = true;
+                        }
+                    });
+                });
+            });
+
+        if !(restore_clicked || discard_clicked || later_clicked) {
+            return None;
+        }
+
+        self.open = false;
+        let items = std::mem::take(&mut self.items);
+        let mut decision = RecoveryDecision {
+            restore: Vec::new(),
+            discard: Vec::new(),
+        };
+        if later_clicked {
+            return Some(decision);
+        }
+        for item in items {
+            // Restoring selected entries implicitly discards the rest, so the
+            // dialog doesn't come back for projects the user chose to drop.
+            if restore_clicked && item.selected {
+                decision.restore.push(item.entry);
+            } else {
+                decision.discard.push(item.entry);
+            }
+        }
+        Some(decision)
+    }
+}
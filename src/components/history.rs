@@ -328,6 +328,10 @@ pub enum LayerOperation {
         source_metadata: crate::canvas::ImageMetadata,
         webp_frame_compression: crate::canvas::WebpFrameCompression,
         deep_pixels: Option<crate::experimental::DeepRgbaBuffer>,
+        locks: crate::canvas::LayerLocks,
+        off_canvas: Option<crate::canvas::OffCanvasPixels>,
+        blend_if: crate::canvas::BlendIf,
+        move_offset: (i32, i32),
     },
     /// Layer was moved from one index to another
     Move { from_index: usize, to_index: usize },
@@ -345,6 +349,12 @@ pub enum LayerOperation {
         old_name: String,
         new_name: String,
     },
+    /// Layer locks were changed
+    Locks {
+        index: usize,
+        old_locks: crate::canvas::LayerLocks,
+        new_locks: crate::canvas::LayerLocks,
+    },
     /// Layer was duplicated (stores the new layer's data for undo)
     Duplicate {
         source_index: usize,
@@ -362,6 +372,10 @@ pub enum LayerOperation {
         source_metadata: crate::canvas::ImageMetadata,
         webp_frame_compression: crate::canvas::WebpFrameCompression,
         deep_pixels: Option<crate::experimental::DeepRgbaBuffer>,
+        locks: crate::canvas::LayerLocks,
+        off_canvas: Option<crate::canvas::OffCanvasPixels>,
+        blend_if: crate::canvas::BlendIf,
+        move_offset: (i32, i32),
     },
 }
 
@@ -407,6 +421,10 @@ impl Command for LayerOpCommand {
                 webp_frame_compression,
                 deep_pixels,
                 folder_id,
+                locks,
+                off_canvas,
+                blend_if,
+                move_offset,
             } => {
                 // Undo delete = restore the layer
                 let mut layer = Layer::new(
@@ -427,6 +445,10 @@ impl Command for LayerOpCommand {
                 layer.source_metadata = source_metadata.clone();
                 layer.webp_frame_compression = *webp_frame_compression;
                 layer.deep_pixels = deep_pixels.clone();
+                layer.locks = *locks;
+                layer.off_canvas = off_canvas.clone();
+                layer.blend_if = *blend_if;
+                layer.move_offset = *move_offset;
 
                 let insert_idx = (*index).min(canvas.layers.len());
                 canvas.layers.insert(insert_idx, layer);
@@ -461,6 +483,13 @@ impl Command for LayerOpCommand {
                     canvas.layers[*index].name = old_name.clone();
                 }
             }
+            LayerOperation::Locks {
+                index, old_locks, ..
+            } => {
+                if *index < canvas.layers.len() {
+                    canvas.layers[*index].locks = *old_locks;
+                }
+            }
             LayerOperation::Duplicate { new_index, .. } => {
                 // Undo duplicate = remove the duplicated layer
                 if *new_index < canvas.layers.len() {
@@ -535,6 +564,13 @@ impl Command for LayerOpCommand {
                     canvas.layers[*index].name = new_name.clone();
                 }
             }
+            LayerOperation::Locks {
+                index, new_locks, ..
+            } => {
+                if *index < canvas.layers.len() {
+                    canvas.layers[*index].locks = *new_locks;
+                }
+            }
             LayerOperation::Duplicate {
                 new_index,
                 pixels,
@@ -550,6 +586,10 @@ impl Command for LayerOpCommand {
                 webp_frame_compression,
                 deep_pixels,
                 folder_id,
+                locks,
+                off_canvas,
+                blend_if,
+                move_offset,
                 ..
             } => {
                 // Redo duplicate = restore the duplicated layer
@@ -571,6 +611,10 @@ impl Command for LayerOpCommand {
                 layer.source_metadata = source_metadata.clone();
                 layer.webp_frame_compression = *webp_frame_compression;
                 layer.deep_pixels = deep_pixels.clone();
+                layer.locks = *locks;
+                layer.off_canvas = off_canvas.clone();
+                layer.blend_if = *blend_if;
+                layer.move_offset = *move_offset;
                 let insert_idx = (*new_index).min(canvas.layers.len());
                 canvas.layers.insert(insert_idx, layer);
                 canvas.active_layer_index = insert_idx;
@@ -607,6 +651,15 @@ impl Command for LayerOpCommand {
             } => {
                 format!("Rename: {} → {}", old_name, new_name)
             }
+            LayerOperation::Locks {
+                index, new_locks, ..
+            } => {
+                if new_locks.any() {
+                    format!("Lock Layer {}: {}", index, new_locks.describe())
+                } else {
+                    format!("Unlock Layer {}", index)
+                }
+            }
             LayerOperation::Duplicate { name, .. } => {
                 format!("Duplicate: {}", name)
             }
@@ -796,6 +849,9 @@ pub struct CanvasSnapshot {
     pub active_layer_index: usize,
     pub selection_mask: Option<image::GrayImage>,
     pub selection_all: bool,
+    pub document_bounds: Option<(u32, u32, u32, u32)>,
+    pub linear_blending: bool,
+    pub slices: Vec<crate::canvas::Slice>,
 }
 
 #[derive(Clone)]
@@ -814,6 +870,10 @@ pub struct LayerSnapshot {
     pub source_metadata: crate::canvas::ImageMetadata,
     pub webp_frame_compression: crate::canvas::WebpFrameCompression,
     pub deep_pixels: Option<crate::experimental::DeepRgbaBuffer>,
+    pub locks: crate::canvas::LayerLocks,
+    pub off_canvas: Option<crate::canvas::OffCanvasPixels>,
+    pub blend_if: crate::canvas::BlendIf,
+    pub move_offset: (i32, i32),
 }
 
 impl CanvasSnapshot {
@@ -824,6 +884,9 @@ impl CanvasSnapshot {
             active_layer_index: state.active_layer_index,
             selection_mask: state.selection_mask.clone(),
             selection_all: state.selection_all,
+            document_bounds: state.document_bounds,
+            linear_blending: state.linear_blending,
+            slices: state.slices.clone(),
             layer_folders: state.layer_folders.clone(),
             next_layer_folder_id: state.next_layer_folder_id,
             layers: state
@@ -844,6 +907,10 @@ impl CanvasSnapshot {
                     source_metadata: l.source_metadata.clone(),
                     webp_frame_compression: l.webp_frame_compression,
                     deep_pixels: l.deep_pixels.clone(),
+                    locks: l.locks,
+                    off_canvas: l.off_canvas.clone(),
+                    blend_if: l.blend_if,
+                    move_offset: l.move_offset,
                 })
                 .collect(),
         }
@@ -876,10 +943,17 @@ impl CanvasSnapshot {
             layer.source_metadata = snap.source_metadata.clone();
             layer.webp_frame_compression = snap.webp_frame_compression;
             layer.deep_pixels = snap.deep_pixels.clone();
+            layer.locks = snap.locks;
+            layer.off_canvas = snap.off_canvas.clone();
+            layer.blend_if = snap.blend_if;
+            layer.move_offset = snap.move_offset;
             state.layers.push(layer);
         }
         state.selection_mask = self.selection_mask.clone();
         state.selection_all = self.selection_all;
+        state.document_bounds = self.document_bounds;
+        state.linear_blending = self.linear_blending;
+        state.slices = self.slices.clone();
         state.composite_cache = None;
         state.clear_preview_state();
         state.invalidate_selection_overlay();
@@ -887,10 +961,22 @@ impl CanvasSnapshot {
         state.mark_dirty(None);
     }
 
+    /// A new document holding the captured state, for work such as exports
+    /// that runs off the UI thread without touching the live canvas.
+    pub fn to_canvas_state(&self) -> CanvasState {
+        let mut state = CanvasState::new(self.width, self.height);
+        self.restore_into(&mut state);
+        state
+    }
+
     fn memory_bytes(&self) -> usize {
         self.layers
             .iter()
-            .map(|l| l.pixels.memory_bytes() + l.name.len())
+            .map(|l| {
+                l.pixels.memory_bytes()
+                    + l.off_canvas.as_ref().map_or(0, |o| o.pixels.memory_bytes())
+                    + l.name.len()
+            })
             .sum()
     }
 }
@@ -975,6 +1061,8 @@ pub struct SingleLayerSnapshotCommand {
     after_webp_frame_compression: crate::canvas::WebpFrameCompression,
     before_deep_pixels: Option<crate::experimental::DeepRgbaBuffer>,
     after_deep_pixels: Option<crate::experimental::DeepRgbaBuffer>,
+    before_off_canvas: Option<crate::canvas::OffCanvasPixels>,
+    after_off_canvas: Option<crate::canvas::OffCanvasPixels>,
 }
 
 impl SingleLayerSnapshotCommand {
@@ -1003,6 +1091,7 @@ impl SingleLayerSnapshotCommand {
             before_source_metadata,
             before_webp_frame_compression,
             before_deep_pixels,
+            before_off_canvas,
         ) = if let Some(layer) = state.layers.get(safe_idx) {
             (
                 layer.pixels.clone(),
@@ -1016,6 +1105,7 @@ impl SingleLayerSnapshotCommand {
                 layer.source_metadata.clone(),
                 layer.webp_frame_compression,
                 layer.deep_pixels.clone(),
+                layer.off_canvas.clone(),
             )
         } else {
             (
@@ -1030,6 +1120,7 @@ impl SingleLayerSnapshotCommand {
                 crate::canvas::ImageMetadata::default(),
                 crate::canvas::WebpFrameCompression::default(),
                 None,
+                None,
             )
         };
         Self {
@@ -1057,6 +1148,8 @@ impl SingleLayerSnapshotCommand {
             after_webp_frame_compression: before_webp_frame_compression,
             before_deep_pixels: before_deep_pixels.clone(),
             after_deep_pixels: before_deep_pixels,
+            before_off_canvas: before_off_canvas.clone(),
+            after_off_canvas: before_off_canvas,
         }
     }
 
@@ -1074,6 +1167,7 @@ impl SingleLayerSnapshotCommand {
             self.after_source_metadata = layer.source_metadata.clone();
             self.after_webp_frame_compression = layer.webp_frame_compression;
             self.after_deep_pixels = layer.deep_pixels.clone();
+            self.after_off_canvas = layer.off_canvas.clone();
         }
     }
 }
@@ -1092,6 +1186,7 @@ impl Command for SingleLayerSnapshotCommand {
             layer.source_metadata = self.before_source_metadata.clone();
             layer.webp_frame_compression = self.before_webp_frame_compression;
             layer.deep_pixels = self.before_deep_pixels.clone();
+            layer.off_canvas = self.before_off_canvas.clone();
         }
         canvas.mark_dirty(None);
     }
@@ -1111,6 +1206,7 @@ impl Command for SingleLayerSnapshotCommand {
             layer.source_metadata = self.after_source_metadata.clone();
             layer.webp_frame_compression = self.after_webp_frame_compression;
             layer.deep_pixels = self.after_deep_pixels.clone();
+            layer.off_canvas = self.after_off_canvas.clone();
         }
         canvas.mark_dirty(None);
     }
@@ -1124,6 +1220,14 @@ impl Command for SingleLayerSnapshotCommand {
             + self.before_mask.as_ref().map_or(0, |m| m.memory_bytes())
             + self.after_pixels.as_ref().map_or(0, |p| p.memory_bytes())
             + self.after_mask.as_ref().map_or(0, |m| m.memory_bytes())
+            + self
+                .before_off_canvas
+                .as_ref()
+                .map_or(0, |o| o.pixels.memory_bytes())
+            + self
+                .after_off_canvas
+                .as_ref()
+                .map_or(0, |o| o.pixels.memory_bytes())
     }
 }
 
@@ -1316,6 +1420,55 @@ impl Command for SelectionCommand {
     }
 }
 
+// ============================================================================
+// SLICES COMMAND - Undo/redo for slice edits
+// ============================================================================
+
+/// Command that stores the document's slices before and after an edit.
+pub struct SlicesCommand {
+    description: String,
+    before: Vec<crate::canvas::Slice>,
+    after: Vec<crate::canvas::Slice>,
+}
+
+impl SlicesCommand {
+    pub fn new(
+        description: impl Into<String>,
+        before: Vec<crate::canvas::Slice>,
+        after: Vec<crate::canvas::Slice>,
+    ) -> Self {
+        Self {
+            description: description.into(),
+            before,
+            after,
+        }
+    }
+}
+
+impl Command for SlicesCommand {
+    fn undo(&self, canvas: &mut CanvasState) {
+        canvas.slices = self.before.clone();
+    }
+
+    fn redo(&self, canvas: &mut CanvasState) {
+        canvas.slices = self.after.clone();
+    }
+
+    fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn memory_size(&self) -> usize {
+        let size = |slices: &[crate::canvas::Slice]| -> usize {
+            slices
+                .iter()
+                .map(|s| std::mem::size_of_val(s) + s.name.len())
+                .sum()
+        };
+        std::mem::size_of::<Self>() + size(&self.before) + size(&self.after)
+    }
+}
+
 // ============================================================================
 // CUT SELECTION COMMAND - one layer plus semantic selection state
 // ============================================================================
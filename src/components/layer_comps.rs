@@ -0,0 +1,144 @@
+use crate::canvas::CanvasState;
+use eframe::egui;
+
+/// What the user asked the Layer Comps panel to do. The app applies it so
+/// the change goes through history and marks the project dirty.
+pub enum LayerCompAction {
+    /// Apply the comp at this index to the layer stack.
+    Apply(usize),
+    /// Capture the current layer state as a new comp with this name.
+    Create(String),
+    /// Re-capture the comp at this index from the current layer state.
+    Update(usize),
+    Rename(usize, String),
+    Delete(usize),
+    /// Export every comp to its own file.
+    ExportAll,
+}
+
+#[derive(Default)]
+pub struct LayerCompsPanel {
+    new_name: String,
+    /// Comp last applied or selected, highlighted in the list.
+    selected: Option<usize>,
+    renaming: Option<(usize, String)>,
+}
+
+impl LayerCompsPanel {
+    pub fn show(&mut self, ui: &mut egui::Ui, state: &CanvasState) -> Option<LayerCompAction> {
+        let mut action = None;
+        let comps = &state.layer_comps;
+        if self.selected.is_some_and(|i| i >= comps.len()) {
+            self.selected = None;
+        }
+
+        ui.horizontal(|ui| {
+            let edit = ui.add(
+                egui::TextEdit::singleline(&mut self.new_name)
+                    .hint_text(t!("layer_comps.new_name"))
+                    .desired_width(ui.available_width() - 56.0),
+            );
+            let submit = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if (ui.button(t!("layer_comps.add")).clicked() || submit)
+                && !self.new_name.trim().is_empty()
+            {
+                action = Some(LayerCompAction::Create(self.new_name.trim().to_string()));
+                self.selected = Some(comps.len());
+                self.new_name.clear();
+            }
+        });
+        ui.separator();
+
+        if comps.is_empty() {
+            ui.weak(t!("layer_comps.empty"));
+            return action;
+        }
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, true])
+            .max_height(ui.available_height() - 28.0)
+            .show(ui, |ui| {
+                for (idx, comp) in comps.iter().enumerate() {
+                    if let Some((rename_idx, text)) = self.renaming.as_mut()
+                        && *rename_idx == idx
+                    {
+                        let resp = ui.text_edit_singleline(text);
+                        if resp.lost_focus() {
+                            let name = text.trim().to_string();
+                            if !name.is_empty() && name != comp.name {
+                                action = Some(LayerCompAction::Rename(idx, name));
+                            }
+                            self.renaming = None;
+                        } else {
+                            resp.request_focus();
+                        }
+                        continue;
+                    }
+                    let row = ui.selectable_label(self.selected == Some(idx), &comp.name);
+                    if row.clicked() {
+                        self.selected = Some(idx);
+                        action = Some(LayerCompAction::Apply(idx));
+                    }
+                    if row.double_clicked() {
+                        self.renaming = Some((idx, comp.name.clone()));
+                    }
+                    row.on_hover_text(t!(
+                        "layer_comps.layers_recorded",
+                        count = comp.entries.len()
+                    ))
+                    .context_menu(|ui| {
+                        if ui.button(t!("common.apply")).clicked() {
+                            self.selected = Some(idx);
+                            action = Some(LayerCompAction::Apply(idx));
+                            ui.close();
+                        }
+                        if ui.button(t!("layer_comps.update_from_layers")).clicked() {
+                            action = Some(LayerCompAction::Update(idx));
+                            ui.close();
+                        }
+                        if ui.button(t!("layer_comps.rename")).clicked() {
+                            self.renaming = Some((idx, comp.name.clone()));
+                            ui.close();
+                        }
+                        if ui.button(t!("common.delete")).clicked() {
+                            action = Some(LayerCompAction::Delete(idx));
+                            ui.close();
+                        }
+                    });
+                }
+            });
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            let selected = self.selected;
+            if ui
+                .add_enabled(
+                    selected.is_some(),
+                    egui::Button::new(t!("layer_comps.update")),
+                )
+                .on_hover_text(t!("layer_comps.update_tooltip"))
+                .clicked()
+                && let Some(idx) = selected
+            {
+                action = Some(LayerCompAction::Update(idx));
+            }
+            if ui
+                .add_enabled(selected.is_some(), egui::Button::new(t!("common.delete")))
+                .clicked()
+                && let Some(idx) = selected
+            {
+                action = Some(LayerCompAction::Delete(idx));
+                self.selected = None;
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            if ui
+                .button(t!("layer_comps.export_all"))
+                .on_hover_text(t!("layer_comps.export_all_tooltip"))
+                .clicked()
+            {
+                action = Some(LayerCompAction::ExportAll);
+            }
+        });
+        action
+    }
+}
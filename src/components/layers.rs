@@ -1,5 +1,7 @@
 use crate::assets::{AppSettings, Assets, Icon};
-use crate::canvas::{BlendMode, CanvasState, Layer, LayerContent, TiledImage};
+use crate::canvas::{
+    BlendIfChannel, BlendMode, BlendRange, CanvasState, Layer, LayerContent, LayerLocks, TiledImage,
+};
 use crate::components::history::{HistoryManager, LayerOpCommand, LayerOperation, SnapshotCommand};
 use crate::ops::canvas_ops::ImageChannel;
 use crate::ops::dialogs::DialogColors;
@@ -39,6 +41,8 @@ pub struct LayerSettingsState {
     pub editing_name: String,
     pub editing_opacity: f32,
     pub editing_blend_mode: BlendMode,
+    /// Channel shown in the "Blend If" section.
+    pub blend_if_channel: BlendIfChannel,
     /// Active tab for the settings window.
     pub tab: LayerSettingsTab,
     /// Cloned text effects for editing (applied on "Apply").
@@ -133,6 +137,8 @@ pub struct LayersPanel {
     pub pending_gpu_clear: bool,
     pub pending_app_action: Option<LayerAppAction>,
     search_query: String,
+    /// Saved gradients offered for text gradient fills (mirrors `AppSettings::gradient_library`).
+    pub gradient_library: Vec<crate::gradient::Gradient>,
 }
 
 include!("layers/list.rs");
@@ -185,6 +191,7 @@ enum ContextAction {
     TextLayerWarp,
     MoveToFolder(u64),
     RemoveFromFolder,
+    SetLocks(LayerLocks),
     ExtractChannel(ImageChannel),
     ReplaceAlphaFromBelowLuminance,
 }
@@ -201,8 +208,16 @@ enum FolderAction {
     AddLayer(u64),
     AddTextLayer(u64),
     AddFolderAbove(u64),
+    AddFolderInside(u64),
     SelectContents(u64),
     SetColor(u64, Option<u8>),
+    SetOpacity(u64, f32),
+    SetBlendMode(u64, Option<BlendMode>),
+    AddMask(u64, bool),
+    ToggleMask(u64),
+    InvertMask(u64),
+    DeleteMask(u64),
+    MoveOut(u64),
 }
 
 #[derive(Clone, Copy)]
@@ -123,11 +123,31 @@ impl LayersPanel {
 
         let mut shown_folders = HashSet::new();
         let mut display_rows = Vec::new();
+        // Emit a folder row preceded by any ancestor rows not yet shown, so
+        // nested folders always appear under their parents.
+        let push_folder_rows =
+            |folder_id: u64, shown: &mut HashSet<u64>, rows: &mut Vec<LayerListRow>| {
+                let chain = canvas_state.folder_ancestors(folder_id);
+                for (depth, id) in chain.iter().rev().enumerate() {
+                    if !shown.insert(*id) {
+                        continue;
+                    }
+                    let hidden_by_parent = !is_filtering
+                        && chain.iter().rev().take(depth).any(|ancestor| {
+                            canvas_state
+                                .layer_folder(*ancestor)
+                                .is_some_and(|folder| folder.collapsed)
+                        });
+                    if !hidden_by_parent {
+                        rows.push(LayerListRow::Folder(*id));
+                    }
+                }
+            };
         for display_idx in 0..layer_count {
             let layer_idx = layer_count - 1 - display_idx;
             for folder in &canvas_state.layer_folders {
-                if folder.insert_above_layer == Some(layer_idx) && shown_folders.insert(folder.id) {
-                    display_rows.push(LayerListRow::Folder(folder.id));
+                if folder.insert_above_layer == Some(layer_idx) {
+                    push_folder_rows(folder.id, &mut shown_folders, &mut display_rows);
                 }
             }
             let layer_matches = !is_filtering
@@ -135,21 +155,22 @@ impl LayersPanel {
                     .name
                     .to_lowercase()
                     .contains(&query_lower);
-            let folder_id = canvas_state.layers[layer_idx].folder_id;
-            if let Some(folder_id) = folder_id
-                && shown_folders.insert(folder_id)
-                && canvas_state.layer_folder(folder_id).is_some()
-            {
-                display_rows.push(LayerListRow::Folder(folder_id));
+            let folder_id = canvas_state.layers[layer_idx]
+                .folder_id
+                .filter(|id| canvas_state.layer_folder(*id).is_some());
+            if let Some(folder_id) = folder_id {
+                push_folder_rows(folder_id, &mut shown_folders, &mut display_rows);
             }
             if !layer_matches {
                 continue;
             }
             if !is_filtering
                 && let Some(folder_id) = folder_id
-                && canvas_state
-                    .layer_folder(folder_id)
-                    .is_some_and(|folder| folder.collapsed)
+                && canvas_state.folder_ancestors(folder_id).iter().any(|id| {
+                    canvas_state
+                        .layer_folder(*id)
+                        .is_some_and(|folder| folder.collapsed)
+                })
             {
                 continue;
             }
@@ -159,9 +180,7 @@ impl LayersPanel {
             });
         }
         for folder in &canvas_state.layer_folders {
-            if !shown_folders.contains(&folder.id) {
-                display_rows.push(LayerListRow::Folder(folder.id));
-            }
+            push_folder_rows(folder.id, &mut shown_folders, &mut display_rows);
         }
 
         if display_rows.is_empty() {
@@ -304,7 +323,9 @@ impl LayersPanel {
                                         {
                                             Some(id)
                                         }
-                                        LayerListRow::Folder(_) => None,
+                                        LayerListRow::Folder(id) => canvas_state
+                                            .layer_folder(id)
+                                            .and_then(|folder| folder.parent_id),
                                         LayerListRow::Layer { layer_idx, .. } => {
                                             canvas_state.layers[layer_idx].folder_id
                                         }
@@ -315,27 +336,25 @@ impl LayersPanel {
                                                 FolderDropZone::Above => {
                                                     self.folder_top_insert_index(canvas_state, id)
                                                 }
-                                                FolderDropZone::Inside => canvas_state
-                                                    .layers
-                                                    .iter()
-                                                    .rposition(|layer| layer.folder_id == Some(id))
-                                                    .map(|idx| idx + 1)
+                                                FolderDropZone::Inside => {
+                                                    Self::folder_top_layer(canvas_state, id)
+                                                        .map(|idx| idx + 1)
                                                     .unwrap_or_else(|| {
                                                         (canvas_state.active_layer_index + 1)
                                                             .min(canvas_state.layers.len())
-                                                    }),
-                                                FolderDropZone::Below => canvas_state
-                                                    .layers
-                                                    .iter()
-                                                    .position(|layer| layer.folder_id == Some(id))
-                                                    .unwrap_or_else(|| {
+                                                    })
+                                                }
+                                                FolderDropZone::Below => {
+                                                    Self::folder_bottom_layer(canvas_state, id)
+                                                        .unwrap_or_else(|| {
                                                         canvas_state
                                                             .layer_folder(id)
                                                             .and_then(|folder| {
                                                                 folder.insert_above_layer
                                                             })
                                                             .unwrap_or(0)
-                                                    }),
+                                                    })
+                                                }
                                             }
                                         }
                                         LayerListRow::Layer { layer_idx, .. } => {
@@ -415,43 +434,59 @@ impl LayersPanel {
                     } else {
                         let target = hovered_row
                             .and_then(|(target_row, rel)| match target_row {
-                                LayerListRow::Folder(id) if id != folder_id => {
+                                LayerListRow::Folder(id)
+                                    if !canvas_state.folder_is_within(id, folder_id) =>
+                                {
+                                    let sibling_parent = canvas_state
+                                        .layer_folder(id)
+                                        .and_then(|folder| folder.parent_id);
                                     Some(match Self::folder_drop_zone(rel) {
-                                        FolderDropZone::Above | FolderDropZone::Inside => {
-                                            self.folder_top_insert_index(canvas_state, id)
-                                        }
-                                        FolderDropZone::Below => canvas_state
-                                            .layers
-                                            .iter()
-                                            .position(|layer| layer.folder_id == Some(id))
-                                            .unwrap_or_else(|| {
-                                                canvas_state
-                                                    .layer_folder(id)
-                                                    .and_then(|folder| folder.insert_above_layer)
-                                                    .unwrap_or(0)
-                                            }),
+                                        FolderDropZone::Above => (
+                                            self.folder_top_insert_index(canvas_state, id),
+                                            sibling_parent,
+                                        ),
+                                        FolderDropZone::Inside => (
+                                            self.folder_top_insert_index(canvas_state, id),
+                                            Some(id),
+                                        ),
+                                        FolderDropZone::Below => (
+                                            Self::folder_bottom_layer(canvas_state, id)
+                                                .unwrap_or_else(|| {
+                                                    canvas_state
+                                                        .layer_folder(id)
+                                                        .and_then(|folder| {
+                                                            folder.insert_above_layer
+                                                        })
+                                                        .unwrap_or(0)
+                                                }),
+                                            sibling_parent,
+                                        ),
                                     })
                                 }
                                 LayerListRow::Layer { layer_idx, .. }
-                                    if canvas_state.layers[layer_idx].folder_id.is_none() =>
+                                    if !canvas_state.layer_in_folder(layer_idx, folder_id) =>
                                 {
-                                    Some(if rel < 0.5 { layer_idx + 1 } else { layer_idx })
+                                    Some((
+                                        if rel < 0.5 { layer_idx + 1 } else { layer_idx },
+                                        canvas_state.layers[layer_idx].folder_id,
+                                    ))
                                 }
                                 _ => None,
                             })
                             .or_else(|| {
                                 pointer_pos.map(|p| {
                                     if p.y < total_rect.center().y {
-                                        canvas_state.layers.len()
+                                        (canvas_state.layers.len(), None)
                                     } else {
-                                        0
+                                        (0, None)
                                     }
                                 })
                             });
-                        if let Some(insert_before_idx) = target {
+                        if let Some((insert_before_idx, parent_id)) = target {
                             self.move_folder_block(
                                 folder_id,
                                 insert_before_idx,
+                                parent_id,
                                 canvas_state,
                                 history,
                             );
@@ -639,16 +674,68 @@ impl LayersPanel {
                                 FolderAction::AddFolderAbove(id) => {
                                     self.add_layer_folder_above(id, canvas_state, history);
                                 }
+                                FolderAction::AddFolderInside(id) => {
+                                    self.add_layer_folder_inside(id, canvas_state, history);
+                                }
+                                FolderAction::SetOpacity(id, opacity) => {
+                                    self.set_layer_folder_opacity(
+                                        id,
+                                        opacity,
+                                        canvas_state,
+                                        history,
+                                    );
+                                }
+                                FolderAction::SetBlendMode(id, blend_mode) => {
+                                    self.set_layer_folder_blend_mode(
+                                        id,
+                                        blend_mode,
+                                        canvas_state,
+                                        history,
+                                    );
+                                }
+                                FolderAction::AddMask(id, from_selection) => {
+                                    self.add_layer_folder_mask(
+                                        id,
+                                        from_selection,
+                                        canvas_state,
+                                        history,
+                                    );
+                                }
+                                FolderAction::ToggleMask(id) => {
+                                    self.toggle_layer_folder_mask(id, canvas_state, history);
+                                }
+                                FolderAction::InvertMask(id) => {
+                                    self.invert_layer_folder_mask(id, canvas_state, history);
+                                }
+                                FolderAction::DeleteMask(id) => {
+                                    self.delete_layer_folder_mask(id, canvas_state, history);
+                                }
+                                FolderAction::MoveOut(id) => {
+                                    let parent = canvas_state
+                                        .layer_folder(id)
+                                        .and_then(|folder| folder.parent_id);
+                                    if let Some(parent) = parent {
+                                        let grandparent = canvas_state
+                                            .layer_folder(parent)
+                                            .and_then(|folder| folder.parent_id);
+                                        let insert_before_idx =
+                                            self.folder_top_insert_index(canvas_state, parent);
+                                        self.move_folder_block(
+                                            id,
+                                            insert_before_idx,
+                                            grandparent,
+                                            canvas_state,
+                                            history,
+                                        );
+                                    }
+                                }
                                 FolderAction::SelectContents(id) => {
                                     self.selected_folder = None;
                                     self.selected_layers.clear();
-                                    let mut members: Vec<usize> = canvas_state
+                                    let mut members: Vec<usize> = (0..canvas_state
                                         .layers
-                                        .iter()
-                                        .enumerate()
-                                        .filter_map(|(idx, layer)| {
-                                            (layer.folder_id == Some(id)).then_some(idx)
-                                        })
+                                        .len())
+                                        .filter(|idx| canvas_state.layer_in_folder(*idx, id))
                                         .collect();
                                     if let Some(primary) = members.iter().copied().max() {
                                         canvas_state.active_layer_index = primary;
@@ -934,6 +1021,19 @@ impl LayersPanel {
                             ContextAction::RemoveFromFolder => {
                                 layer_to_folder = Some((layer_idx, None));
                             }
+                            ContextAction::SetLocks(new_locks) => {
+                                let old_locks = canvas_state.layers[layer_idx].locks;
+                                if old_locks != new_locks {
+                                    canvas_state.layers[layer_idx].locks = new_locks;
+                                    history.push(Box::new(LayerOpCommand::new(
+                                        LayerOperation::Locks {
+                                            index: layer_idx,
+                                            old_locks,
+                                            new_locks,
+                                        },
+                                    )));
+                                }
+                            }
                             ContextAction::ExtractChannel(channel) => {
                                 let mut snap = SnapshotCommand::new(
                                     format!("Extract {:?} Channel", channel),
@@ -1088,7 +1188,7 @@ impl LayersPanel {
         }
 
         let center_y = row_rect.center().y;
-        let mut x = row_rect.left() + 6.0;
+        let mut x = row_rect.left() + 6.0 + canvas_state.folder_depth(folder_id) as f32 * 14.0;
         let arrow_rect = Rect::from_center_size(Pos2::new(x + 7.0, center_y), Vec2::splat(16.0));
         let icon_dark = Self::icon_dark_for_text(text_color);
         let arrow_icon = if folder.collapsed {
@@ -1201,6 +1301,119 @@ impl LayersPanel {
                 action = Some(FolderAction::AddFolderAbove(folder_id));
                 ui.close();
             }
+            if assets
+                .menu_item(ui, Icon::MenuFileOpen, &t!("layer.folder.add_inside"))
+                .clicked()
+            {
+                action = Some(FolderAction::AddFolderInside(folder_id));
+                ui.close();
+            }
+            if folder.parent_id.is_some()
+                && assets
+                    .menu_item(ui, Icon::MoveUp, &t!("layer.folder.move_out"))
+                    .clicked()
+            {
+                action = Some(FolderAction::MoveOut(folder_id));
+                ui.close();
+            }
+            ui.separator();
+            ui.label(egui::RichText::new(t!("layer.group")).strong());
+            // Keep the in-progress slider value between frames so the
+            // opacity is committed once, as a single undo step.
+            let opacity_id = Id::new("layer_folder_opacity").with(folder_id);
+            let mut opacity_pct = ui
+                .data(|d| d.get_temp::<f32>(opacity_id))
+                .unwrap_or(folder.opacity * 100.0);
+            let opacity_response = ui.add(
+                egui::Slider::new(&mut opacity_pct, 0.0..=100.0)
+                    .suffix("%")
+                    .text(t!("layer.group.opacity")),
+            );
+            if opacity_response.changed() {
+                ui.data_mut(|d| d.insert_temp(opacity_id, opacity_pct));
+            }
+            if opacity_response.drag_stopped()
+                || (opacity_response.changed() && !opacity_response.dragged())
+            {
+                ui.data_mut(|d| d.remove::<f32>(opacity_id));
+                action = Some(FolderAction::SetOpacity(folder_id, opacity_pct / 100.0));
+            }
+            let blend_label = folder
+                .blend_mode
+                .map(|mode| mode.display_name())
+                .unwrap_or_else(|| t!("layer.group.pass_through"));
+            egui::ComboBox::from_id_salt(Id::new("layer_folder_blend").with(folder_id))
+                .selected_text(blend_label)
+                .show_ui(ui, |ui| {
+                    if ui
+                        .selectable_label(
+                            folder.blend_mode.is_none(),
+                            t!("layer.group.pass_through"),
+                        )
+                        .clicked()
+                    {
+                        action = Some(FolderAction::SetBlendMode(folder_id, None));
+                    }
+                    for &mode in BlendMode::all() {
+                        if ui
+                            .selectable_label(folder.blend_mode == Some(mode), mode.display_name())
+                            .clicked()
+                        {
+                            action = Some(FolderAction::SetBlendMode(folder_id, Some(mode)));
+                        }
+                    }
+                });
+            if folder.mask.is_none() {
+                if assets
+                    .menu_item(ui, Icon::AddLayerMaskRevealAll, &t!("layer.group.add_mask"))
+                    .clicked()
+                {
+                    action = Some(FolderAction::AddMask(folder_id, false));
+                    ui.close();
+                }
+                if canvas_state.selection_mask.is_some()
+                    && assets
+                        .menu_item(
+                            ui,
+                            Icon::AddLayerMaskFromSelection,
+                            &t!("layer.group.mask_from_selection"),
+                        )
+                        .clicked()
+                {
+                    action = Some(FolderAction::AddMask(folder_id, true));
+                    ui.close();
+                }
+            } else {
+                if assets
+                    .menu_item(
+                        ui,
+                        Icon::ToggleLayerMask,
+                        &if folder.mask_enabled {
+                            t!("layer.group.disable_mask")
+                        } else {
+                            t!("layer.group.enable_mask")
+                        },
+                    )
+                    .clicked()
+                {
+                    action = Some(FolderAction::ToggleMask(folder_id));
+                    ui.close();
+                }
+                if assets
+                    .menu_item(ui, Icon::InvertLayerMask, &t!("layer.group.invert_mask"))
+                    .clicked()
+                {
+                    action = Some(FolderAction::InvertMask(folder_id));
+                    ui.close();
+                }
+                if assets
+                    .menu_item(ui, Icon::DeleteLayerMask, &t!("layer.group.delete_mask"))
+                    .clicked()
+                {
+                    action = Some(FolderAction::DeleteMask(folder_id));
+                    ui.close();
+                }
+            }
             ui.separator();
             ui.label(egui::RichText::new("Folder color").strong());
             ui.horizontal_wrapped(|ui| {
@@ -1369,8 +1582,8 @@ impl LayersPanel {
             // Layout: [Eye] [Thumbnail] [Name]
             let mut x = row_rect.left() + 4.0;
             let center_y = row_rect.center().y;
-            if canvas_state.layers[layer_idx].folder_id.is_some() {
-                x += 14.0;
+            if let Some(folder_id) = canvas_state.layers[layer_idx].folder_id {
+                x += 14.0 * (canvas_state.folder_depth(folder_id) + 1) as f32;
             }
 
             // Pre-calculate all rects
@@ -1394,11 +1607,45 @@ impl LayersPanel {
                 LayerContent::Raster => None,
             };
             let gear_width = if is_text_layer { 20.0 } else { 0.0 };
+            let locks = canvas_state.layers[layer_idx].locks;
+            let lock_width = if locks.any() { 18.0 } else { 0.0 };
             let name_rect = Rect::from_min_max(
                 Pos2::new(x, row_rect.top() + 4.0),
-                Pos2::new(row_rect.right() - 6.0 - gear_width, row_rect.bottom() - 4.0),
+                Pos2::new(
+                    row_rect.right() - 6.0 - gear_width - lock_width,
+                    row_rect.bottom() - 4.0,
+                ),
             );
 
+            // Padlock for locked layers (left of the gear)
+            if locks.any() {
+                let lock_rect = Rect::from_center_size(
+                    Pos2::new(
+                        row_rect.right() - 6.0 - gear_width - lock_width / 2.0,
+                        center_y,
+                    ),
+                    Vec2::splat(16.0),
+                );
+                let lock_color = if locks.all || locks.pixels {
+                    ui.visuals().strong_text_color()
+                } else {
+                    ui.visuals().text_color()
+                };
+                ui.painter().text(
+                    lock_rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    "\u{1F512}",
+                    egui::FontId::proportional(12.0),
+                    lock_color,
+                );
+                ui.interact(
+                    lock_rect,
+                    Id::new(("layer_lock", layer_idx)),
+                    Sense::hover(),
+                )
+                .on_hover_text(t!("layer.lock.locked", locks = locks.describe()));
+            }
+
             // Small gear icon for text layer settings (right side of row)
             if is_text_layer {
                 let gear_rect = Rect::from_center_size(
@@ -1674,6 +1921,19 @@ impl LayersPanel {
                 );
                 ui.separator();
             }
+            let current_locks = canvas_state.layers[layer_idx].locks;
+            ui.menu_button(format!("\u{1F512} {}", t!("layer.lock")), |ui| {
+                let mut locks = current_locks;
+                ui.checkbox(&mut locks.transparency, t!("layer.lock.transparency"));
+                ui.checkbox(&mut locks.pixels, t!("layer.lock.pixels"));
+                ui.checkbox(&mut locks.position, t!("layer.lock.position"));
+                ui.separator();
+                ui.checkbox(&mut locks.all, t!("layer.lock.all"));
+                if locks != current_locks {
+                    context_action = Some(ContextAction::SetLocks(locks));
+                }
+            });
+            ui.separator();
             ui.menu_button(
                 format!("{} Extract Channel", Icon::MenuColorLevels.emoji()),
                 |ui| {
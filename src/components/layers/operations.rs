@@ -1,9 +1,6 @@
 impl LayersPanel {
     fn folder_insert_index(&self, canvas_state: &CanvasState, folder_id: u64) -> usize {
-        canvas_state
-            .layers
-            .iter()
-            .rposition(|layer| layer.folder_id == Some(folder_id))
+        Self::folder_top_layer(canvas_state, folder_id)
             .map(|idx| idx + 1)
             .or_else(|| {
                 canvas_state
@@ -137,34 +134,76 @@ impl LayersPanel {
         let id = canvas_state.next_layer_folder_id.max(1);
         canvas_state.next_layer_folder_id = id + 1;
         let name = format!("Folder {}", canvas_state.layer_folders.len() + 1);
-        let insert_above_layer = canvas_state
+        let active_folder = canvas_state
             .layers
             .get(canvas_state.active_layer_index)
-            .and_then(|layer| layer.folder_id)
+            .and_then(|layer| layer.folder_id);
+        let parent_id = active_folder
+            .and_then(|id| canvas_state.layer_folder(id))
+            .and_then(|folder| folder.parent_id);
+        let insert_above_layer = active_folder
             .and_then(|id| {
-                canvas_state
-                    .layers
-                    .iter()
-                    .rposition(|layer| layer.folder_id == Some(id))
+                (0..canvas_state.layers.len())
+                    .rev()
+                    .find(|idx| canvas_state.layer_in_folder(*idx, id))
             })
             .or_else(|| (!canvas_state.layers.is_empty()).then_some(canvas_state.active_layer_index));
         canvas_state.layer_folders.push(crate::canvas::LayerFolder {
             id,
             name,
-            visible: true,
-            collapsed: false,
             insert_above_layer,
-            color_index: None,
+            parent_id,
+            ..Default::default()
         });
         snap.set_after(canvas_state);
         history.push(Box::new(snap));
     }
 
+    /// Add an empty folder nested inside `parent_folder_id`, above its contents.
+    fn add_layer_folder_inside(
+        &mut self,
+        parent_folder_id: u64,
+        canvas_state: &mut CanvasState,
+        history: &mut HistoryManager,
+    ) {
+        if canvas_state.layer_folder(parent_folder_id).is_none() {
+            return;
+        }
+        let insert_above_layer = self
+            .folder_top_insert_index(canvas_state, parent_folder_id)
+            .checked_sub(1);
+        let mut snap = SnapshotCommand::new("Add Layer Folder".to_string(), canvas_state);
+        let id = canvas_state.next_layer_folder_id.max(1);
+        canvas_state.next_layer_folder_id = id + 1;
+        let name = format!("Folder {}", canvas_state.layer_folders.len() + 1);
+        canvas_state.layer_folders.push(crate::canvas::LayerFolder {
+            id,
+            name,
+            insert_above_layer,
+            parent_id: Some(parent_folder_id),
+            ..Default::default()
+        });
+        if let Some(parent) = canvas_state.layer_folder_mut(parent_folder_id) {
+            parent.collapsed = false;
+        }
+        snap.set_after(canvas_state);
+        history.push(Box::new(snap));
+    }
+
+    /// Index of the first layer in the folder (transitively), if any.
+    fn folder_bottom_layer(canvas_state: &CanvasState, folder_id: u64) -> Option<usize> {
+        (0..canvas_state.layers.len()).find(|idx| canvas_state.layer_in_folder(*idx, folder_id))
+    }
+
+    /// Index of the last layer in the folder (transitively), if any.
+    fn folder_top_layer(canvas_state: &CanvasState, folder_id: u64) -> Option<usize> {
+        (0..canvas_state.layers.len())
+            .rev()
+            .find(|idx| canvas_state.layer_in_folder(*idx, folder_id))
+    }
+
     fn folder_top_insert_index(&self, canvas_state: &CanvasState, folder_id: u64) -> usize {
-        canvas_state
-            .layers
-            .iter()
-            .rposition(|layer| layer.folder_id == Some(folder_id))
+        Self::folder_top_layer(canvas_state, folder_id)
             .map(|idx| idx + 1)
             .or_else(|| {
                 canvas_state
@@ -179,19 +218,26 @@ impl LayersPanel {
         &mut self,
         folder_id: u64,
         insert_before_idx: usize,
+        parent_id: Option<u64>,
         canvas_state: &mut CanvasState,
         history: &mut HistoryManager,
     ) {
         if canvas_state.layer_folder(folder_id).is_none() {
             return;
         }
-        let member_indices: Vec<usize> = canvas_state
-            .layers
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, layer)| (layer.folder_id == Some(folder_id)).then_some(idx))
+        if let Some(parent) = parent_id
+            && (canvas_state.layer_folder(parent).is_none()
+                || canvas_state.folder_is_within(parent, folder_id))
+        {
+            return;
+        }
+        let member_indices: Vec<usize> = (0..canvas_state.layers.len())
+            .filter(|idx| canvas_state.layer_in_folder(*idx, folder_id))
             .collect();
         let mut snap = SnapshotCommand::new("Move Folder".to_string(), canvas_state);
+        if let Some(folder) = canvas_state.layer_folder_mut(folder_id) {
+            folder.parent_id = parent_id;
+        }
         if member_indices.is_empty() {
             let adjusted = insert_before_idx.saturating_sub(1).min(canvas_state.layers.len());
             if let Some(folder) = canvas_state.layer_folder_mut(folder_id) {
@@ -234,6 +280,7 @@ impl LayersPanel {
             return;
         };
         let insert_above_layer = anchor.insert_above_layer;
+        let parent_id = anchor.parent_id;
         let mut snap = SnapshotCommand::new("Add Layer Folder".to_string(), canvas_state);
         let id = canvas_state.next_layer_folder_id.max(1);
         canvas_state.next_layer_folder_id = id + 1;
@@ -241,10 +288,9 @@ impl LayersPanel {
         canvas_state.layer_folders.push(crate::canvas::LayerFolder {
             id,
             name,
-            visible: true,
-            collapsed: false,
             insert_above_layer,
-            color_index: None,
+            parent_id,
+            ..Default::default()
         });
         snap.set_after(canvas_state);
         history.push(Box::new(snap));
@@ -333,11 +379,20 @@ impl LayersPanel {
         if canvas_state.layer_folder(folder_id).is_none() {
             return;
         }
+        let parent_id = canvas_state
+            .layer_folder(folder_id)
+            .and_then(|folder| folder.parent_id);
         let mut snap = SnapshotCommand::new("Delete Layer Folder".to_string(), canvas_state);
         canvas_state.layer_folders.retain(|folder| folder.id != folder_id);
+        // Contents move up one level rather than being flattened to the top.
+        for folder in &mut canvas_state.layer_folders {
+            if folder.parent_id == Some(folder_id) {
+                folder.parent_id = parent_id;
+            }
+        }
         for layer in &mut canvas_state.layers {
             if layer.folder_id == Some(folder_id) {
-                layer.folder_id = None;
+                layer.folder_id = parent_id;
             }
         }
         snap.set_after(canvas_state);
@@ -345,6 +400,169 @@ impl LayersPanel {
         self.mark_full_dirty(canvas_state);
     }
 
+    /// Apply an edit to a folder's group properties as one undoable step.
+    fn edit_layer_folder(
+        &mut self,
+        folder_id: u64,
+        label: &str,
+        canvas_state: &mut CanvasState,
+        history: &mut HistoryManager,
+        edit: impl FnOnce(&mut crate::canvas::LayerFolder, u32, u32, Option<&image::GrayImage>),
+    ) {
+        if canvas_state.layer_folder(folder_id).is_none() {
+            return;
+        }
+        let mut snap = SnapshotCommand::new(label.to_string(), canvas_state);
+        let (width, height) = (canvas_state.width, canvas_state.height);
+        let selection = canvas_state.selection_mask.clone();
+        if let Some(folder) = canvas_state.layer_folder_mut(folder_id) {
+            edit(folder, width, height, selection.as_ref());
+        }
+        snap.set_after(canvas_state);
+        history.push(Box::new(snap));
+        self.pending_gpu_clear = true;
+        self.mark_full_dirty(canvas_state);
+    }
+
+    fn set_layer_folder_opacity(
+        &mut self,
+        folder_id: u64,
+        opacity: f32,
+        canvas_state: &mut CanvasState,
+        history: &mut HistoryManager,
+    ) {
+        if canvas_state
+            .layer_folder(folder_id)
+            .is_none_or(|folder| (folder.opacity - opacity).abs() < f32::EPSILON)
+        {
+            return;
+        }
+        self.edit_layer_folder(
+            folder_id,
+            "Folder Opacity",
+            canvas_state,
+            history,
+            |folder, _, _, _| folder.opacity = opacity.clamp(0.0, 1.0),
+        );
+    }
+
+    fn set_layer_folder_blend_mode(
+        &mut self,
+        folder_id: u64,
+        blend_mode: Option<BlendMode>,
+        canvas_state: &mut CanvasState,
+        history: &mut HistoryManager,
+    ) {
+        if canvas_state
+            .layer_folder(folder_id)
+            .is_none_or(|folder| folder.blend_mode == blend_mode)
+        {
+            return;
+        }
+        self.edit_layer_folder(
+            folder_id,
+            "Folder Blend Mode",
+            canvas_state,
+            history,
+            |folder, _, _, _| folder.blend_mode = blend_mode,
+        );
+    }
+
+    /// Add a group mask that reveals everything, or only the current
+    /// selection when `from_selection` is set and a selection exists.
+    fn add_layer_folder_mask(
+        &mut self,
+        folder_id: u64,
+        from_selection: bool,
+        canvas_state: &mut CanvasState,
+        history: &mut HistoryManager,
+    ) {
+        let label = if from_selection {
+            "Group Mask From Selection"
+        } else {
+            "Add Group Mask"
+        };
+        self.edit_layer_folder(
+            folder_id,
+            label,
+            canvas_state,
+            history,
+            |folder, width, height, selection| {
+                let mut mask = TiledImage::new(width, height);
+                if from_selection && let Some(sel) = selection {
+                    for y in 0..height.min(sel.height()) {
+                        for x in 0..width.min(sel.width()) {
+                            let conceal = 255u8.saturating_sub(sel.get_pixel(x, y)[0]);
+                            if conceal > 0 {
+                                mask.put_pixel(x, y, Rgba([0, 0, 0, conceal]));
+                            }
+                        }
+                    }
+                }
+                folder.mask = Some(mask);
+                folder.mask_enabled = true;
+            },
+        );
+    }
+
+    fn toggle_layer_folder_mask(
+        &mut self,
+        folder_id: u64,
+        canvas_state: &mut CanvasState,
+        history: &mut HistoryManager,
+    ) {
+        self.edit_layer_folder(
+            folder_id,
+            "Toggle Group Mask",
+            canvas_state,
+            history,
+            |folder, _, _, _| folder.mask_enabled = !folder.mask_enabled,
+        );
+    }
+
+    fn invert_layer_folder_mask(
+        &mut self,
+        folder_id: u64,
+        canvas_state: &mut CanvasState,
+        history: &mut HistoryManager,
+    ) {
+        self.edit_layer_folder(
+            folder_id,
+            "Invert Group Mask",
+            canvas_state,
+            history,
+            |folder, _, _, _| {
+                if let Some(mask) = folder.mask.as_mut() {
+                    for y in 0..mask.height() {
+                        for x in 0..mask.width() {
+                            let conceal = 255 - mask.get_pixel(x, y)[3];
+                            mask.put_pixel(x, y, Rgba([0, 0, 0, conceal]));
+                        }
+                    }
+                }
+                folder.mask_enabled = true;
+            },
+        );
+    }
+
+    fn delete_layer_folder_mask(
+        &mut self,
+        folder_id: u64,
+        canvas_state: &mut CanvasState,
+        history: &mut HistoryManager,
+    ) {
+        self.edit_layer_folder(
+            folder_id,
+            "Delete Group Mask",
+            canvas_state,
+            history,
+            |folder, _, _, _| {
+                folder.mask = None;
+                folder.mask_enabled = true;
+            },
+        );
+    }
+
     /// Rasterize a text layer — converts it to a regular raster layer,
     /// losing editability but preserving the current pixel appearance.
     fn rasterize_text_layer(
@@ -445,6 +663,10 @@ impl LayersPanel {
         let source_metadata = layer.source_metadata.clone();
         let webp_frame_compression = layer.webp_frame_compression;
         let deep_pixels = layer.deep_pixels.clone();
+        let locks = layer.locks;
+        let off_canvas = layer.off_canvas.clone();
+        let blend_if = layer.blend_if;
+        let move_offset = layer.move_offset;
         let clear_selection =
             canvas_state.active_layer_index == layer_idx && canvas_state.selection_mask.is_some();
         let snapshot_cmd = clear_selection
@@ -498,6 +720,10 @@ impl LayersPanel {
                 source_metadata,
                 webp_frame_compression,
                 deep_pixels,
+                locks,
+                off_canvas,
+                blend_if,
+                move_offset,
             })));
         }
 
@@ -536,6 +762,10 @@ impl LayersPanel {
         new_layer.source_metadata = source.source_metadata.clone();
         new_layer.webp_frame_compression = source.webp_frame_compression;
         new_layer.deep_pixels = source.deep_pixels.clone();
+        new_layer.locks = source.locks;
+        new_layer.off_canvas = source.off_canvas.clone();
+        new_layer.blend_if = source.blend_if;
+        new_layer.move_offset = source.move_offset;
 
         let new_index = layer_idx + 1;
 
@@ -552,6 +782,10 @@ impl LayersPanel {
         let source_metadata = new_layer.source_metadata.clone();
         let webp_frame_compression = new_layer.webp_frame_compression;
         let deep_pixels = new_layer.deep_pixels.clone();
+        let locks = new_layer.locks;
+        let off_canvas = new_layer.off_canvas.clone();
+        let blend_if = new_layer.blend_if;
+        let move_offset = new_layer.move_offset;
 
         // Insert above the duplicated layer
         canvas_state.layers.insert(new_index, new_layer);
@@ -574,6 +808,10 @@ impl LayersPanel {
             source_metadata,
             webp_frame_compression,
             deep_pixels,
+            locks,
+            off_canvas,
+            blend_if,
+            move_offset,
         })));
 
         self.thumbnail_cache.clear();
@@ -800,59 +1038,7 @@ impl LayersPanel {
         // Snapshot before merge for undo (multi-layer op requires full snapshot)
         let mut snap_cmd = SnapshotCommand::new("Merge Down".to_string(), canvas_state);
 
-        // Auto-rasterize text layers before merge (pixels must be up-to-date)
-        for idx in [layer_idx, layer_idx - 1] {
-            if canvas_state.layers[idx].is_text_layer() {
-                canvas_state.ensure_all_text_layers_rasterized();
-                canvas_state.layers[idx].content = LayerContent::Raster;
-            }
-        }
-
-        let width = canvas_state.width;
-        let height = canvas_state.height;
-
-        let top_blend_mode = canvas_state.layers[layer_idx].blend_mode;
-        let top_opacity = canvas_state.layers[layer_idx].opacity;
-        let top_visible = canvas_state.layers[layer_idx].visible;
-
-        if !top_visible {
-            canvas_state.layers.remove(layer_idx);
-            if canvas_state.active_layer_index >= layer_idx && canvas_state.active_layer_index > 0 {
-                canvas_state.active_layer_index -= 1;
-            }
-            self.thumbnail_cache.clear();
-            self.pending_gpu_clear = true;
-            return;
-        }
-
-        let top_pixels: Vec<Rgba<u8>> = {
-            let top_layer = &canvas_state.layers[layer_idx];
-            (0..height)
-                .flat_map(|y| (0..width).map(move |x| *top_layer.pixels.get_pixel(x, y)))
-                .collect()
-        };
-
-        let bottom_layer = &mut canvas_state.layers[layer_idx - 1];
-        for y in 0..height {
-            for x in 0..width {
-                let idx = (y * width + x) as usize;
-                let top_pixel = top_pixels[idx];
-                let base_pixel = *bottom_layer.pixels.get_pixel(x, y);
-
-                let blended = CanvasState::blend_pixel_static(
-                    base_pixel,
-                    top_pixel,
-                    top_blend_mode,
-                    top_opacity,
-                );
-                bottom_layer.pixels.put_pixel(x, y, blended);
-            }
-        }
-
-        canvas_state.layers.remove(layer_idx);
-        if canvas_state.active_layer_index >= layer_idx && canvas_state.active_layer_index > 0 {
-            canvas_state.active_layer_index -= 1;
-        }
+        crate::ops::canvas_ops::merge_down(canvas_state, layer_idx);
 
         self.thumbnail_cache.clear();
         self.pending_gpu_clear = true;
@@ -246,6 +246,8 @@ impl LayersPanel {
                 });
         });
 
+        self.show_blend_if_settings(ui, layer_idx, canvas_state);
+
         if matches!(
             canvas_state.layers[layer_idx].content,
             LayerContent::Adjustment(_)
@@ -332,6 +334,41 @@ impl LayersPanel {
                 }
             });
         }
+        if canvas_state.layers[layer_idx].has_float_pixels() {
+            // Display/export view for float data; shared by every float layer.
+            let mut view = canvas_state.layers[layer_idx].hdr_metadata.view;
+            ui.horizontal(|ui| {
+                ui.label(t!("hdr.exposure"));
+                ui.add(
+                    egui::DragValue::new(&mut view.exposure)
+                        .range(-10.0..=10.0)
+                        .speed(0.05)
+                        .suffix(" EV"),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label(t!("hdr.tone_map"));
+                egui::ComboBox::from_id_salt(("hdr_tone_map_ls", layer_idx))
+                    .selected_text(view.tone_map.label())
+                    .width(120.0)
+                    .show_ui(ui, |ui| {
+                        for next in [
+                            crate::experimental::HdrToneMap::Clip,
+                            crate::experimental::HdrToneMap::Reinhard,
+                        ] {
+                            ui.selectable_value(&mut view.tone_map, next, next.label());
+                        }
+                    });
+            });
+            if view != canvas_state.layers[layer_idx].hdr_metadata.view {
+                canvas_state.set_hdr_view(view);
+            }
+            ui.label(
+                egui::RichText::new(t!("hdr.view_hint"))
+                    .size(11.0)
+                    .color(ui.visuals().weak_text_color()),
+            );
+        }
         let meta = &canvas_state.layers[layer_idx].source_metadata;
         if meta.source_format.is_some()
             || !meta.png_text_chunks.is_empty()
@@ -348,6 +385,57 @@ impl LayersPanel {
         }
     }
 
+    /// "Blend If" section: this-layer and underlying-layer ranges for the
+    /// chosen channel. Alt-drag a handle to split it for a soft falloff.
+    fn show_blend_if_settings(
+        &mut self,
+        ui: &mut egui::Ui,
+        layer_idx: usize,
+        canvas_state: &mut CanvasState,
+    ) {
+        ui.add_space(8.0);
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new(t!("blend_if.title"))
+                    .strong()
+                    .size(12.0),
+            );
+            let channel = &mut self.settings_state.blend_if_channel;
+            egui::ComboBox::from_id_salt(("blend_if_channel_ls", layer_idx))
+                .selected_text(channel.label())
+                .width(80.0)
+                .show_ui(ui, |ui| {
+                    for next in BlendIfChannel::ALL {
+                        ui.selectable_value(channel, next, next.label());
+                    }
+                });
+        });
+
+        let ch = self.settings_state.blend_if_channel;
+        let mut blend_if = canvas_state.layers[layer_idx].blend_if;
+        ui.label(t!("blend_if.this_layer"));
+        blend_range_slider(
+            ui,
+            ("blend_if_this", layer_idx),
+            &mut blend_if.this_layer[ch as usize],
+            ch,
+        );
+        ui.label(t!("blend_if.underlying_layer"));
+        blend_range_slider(
+            ui,
+            ("blend_if_under", layer_idx),
+            &mut blend_if.underlying[ch as usize],
+            ch,
+        );
+        if !blend_if.is_default() && ui.small_button(t!("common.reset")).clicked() {
+            blend_if = Default::default();
+        }
+        if blend_if != canvas_state.layers[layer_idx].blend_if {
+            canvas_state.layers[layer_idx].blend_if = blend_if;
+            self.mark_full_dirty(canvas_state);
+        }
+    }
+
     fn show_adjustment_settings(
         &mut self,
         ui: &mut egui::Ui,
@@ -745,33 +833,68 @@ impl LayersPanel {
                 changed = true;
             }
 
+            let library = &self.gradient_library;
             if let Some(ref mut gradient) = self.settings_state.text_effects.gradient_fill {
                 ui.indent("ls_gradient_fill", |ui| {
-                    ui.horizontal(|ui| {
-                        ui.label(t!("ctx.text.effects.gradient.start"));
-                        let mut c = Color32::from_rgba_unmultiplied(
-                            gradient.start_color[0],
-                            gradient.start_color[1],
-                            gradient.start_color[2],
-                            gradient.start_color[3],
-                        );
-                        if ui.color_edit_button_srgba(&mut c).changed() {
-                            gradient.start_color = [c.r(), c.g(), c.b(), c.a()];
-                            changed = true;
-                        }
-                    });
-                    ui.horizontal(|ui| {
-                        ui.label(t!("ctx.text.effects.gradient.end"));
-                        let mut c = Color32::from_rgba_unmultiplied(
-                            gradient.end_color[0],
-                            gradient.end_color[1],
-                            gradient.end_color[2],
-                            gradient.end_color[3],
-                        );
-                        if ui.color_edit_button_srgba(&mut c).changed() {
-                            gradient.end_color = [c.r(), c.g(), c.b(), c.a()];
-                            changed = true;
-                        }
+                    if !library.is_empty() || gradient.gradient.is_some() {
+                        ui.horizontal(|ui| {
+                            ui.label(t!("ctx.gradient.library"));
+                            egui::ComboBox::from_id_salt("ls_gradient_fill_library")
+                                .selected_text(
+                                    gradient
+                                        .gradient
+                                        .as_ref()
+                                        .map(|g| g.name.clone())
+                                        .unwrap_or_else(|| t!("ctx.gradient.start_to_end")),
+                                )
+                                .show_ui(ui, |ui| {
+                                    if ui
+                                        .selectable_label(
+                                            gradient.gradient.is_none(),
+                                            t!("ctx.gradient.start_to_end"),
+                                        )
+                                        .clicked()
+                                    {
+                                        gradient.gradient = None;
+                                        changed = true;
+                                    }
+                                    for g in library {
+                                        let selected = gradient.gradient.as_ref() == Some(g);
+                                        if ui.selectable_label(selected, &g.name).clicked() {
+                                            gradient.gradient = Some(g.clone());
+                                            changed = true;
+                                        }
+                                    }
+                                });
+                        });
+                    }
+                    ui.add_enabled_ui(gradient.gradient.is_none(), |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(t!("ctx.text.effects.gradient.start"));
+                            let mut c = Color32::from_rgba_unmultiplied(
+                                gradient.start_color[0],
+                                gradient.start_color[1],
+                                gradient.start_color[2],
+                                gradient.start_color[3],
+                            );
+                            if ui.color_edit_button_srgba(&mut c).changed() {
+                                gradient.start_color = [c.r(), c.g(), c.b(), c.a()];
+                                changed = true;
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(t!("ctx.text.effects.gradient.end"));
+                            let mut c = Color32::from_rgba_unmultiplied(
+                                gradient.end_color[0],
+                                gradient.end_color[1],
+                                gradient.end_color[2],
+                                gradient.end_color[3],
+                            );
+                            if ui.color_edit_button_srgba(&mut c).changed() {
+                                gradient.end_color = [c.r(), c.g(), c.b(), c.a()];
+                                changed = true;
+                            }
+                        });
                     });
                     ui.horizontal(|ui| {
                         ui.label(t!("ctx.text.effects.gradient.angle"));
@@ -1160,3 +1283,130 @@ impl LayersPanel {
 
     // === Layer Operations ===
 }
+
+/// A 0–255 gradient bar with split black and white handles. Dragging a
+/// handle moves both of its halves; holding Alt drags the nearest half on its
+/// own.
+fn blend_range_slider(
+    ui: &mut egui::Ui,
+    id_salt: impl std::hash::Hash + std::fmt::Debug,
+    range: &mut BlendRange,
+    channel: BlendIfChannel,
+) {
+    let width = ui.available_width().min(240.0);
+    let (rect, response) =
+        ui.allocate_exact_size(Vec2::new(width, 26.0), Sense::click_and_drag());
+    let bar = Rect::from_min_max(rect.min, Pos2::new(rect.max.x, rect.min.y + 12.0));
+    let to_x = |v: u8| bar.left() + bar.width() * v as f32 / 255.0;
+    let to_v = |x: f32| (((x - bar.left()) / bar.width()).clamp(0.0, 1.0) * 255.0).round() as u8;
+
+    // Handle being dragged, remembered across frames: 0/1 = black halves,
+    // 2/3 = white halves, +4 = both halves together.
+    let drag_id = ui.make_persistent_id(id_salt);
+    if let Some(pos) = response.interact_pointer_pos() {
+        let handle = if response.drag_started() || response.clicked() {
+            let alt = ui.input(|i| i.modifiers.alt);
+            let handles = [range.black[0], range.black[1], range.white[0], range.white[1]];
+            let nearest = (0..4)
+                .min_by(|&a, &b| {
+                    let da = (to_x(handles[a]) - pos.x).abs();
+                    let db = (to_x(handles[b]) - pos.x).abs();
+                    da.total_cmp(&db)
+                })
+                .unwrap_or(0);
+            let handle = if alt { nearest } else { nearest / 2 * 2 + 4 };
+            ui.data_mut(|d| d.insert_temp(drag_id, handle));
+            handle
+        } else {
+            ui.data(|d| d.get_temp::<usize>(drag_id)).unwrap_or(4)
+        };
+        let v = to_v(pos.x);
+        match handle {
+            0 => range.black[0] = v.min(range.black[1]),
+            1 => range.black[1] = v.clamp(range.black[0], range.white[0]),
+            2 => range.white[0] = v.clamp(range.black[1], range.white[1]),
+            3 => range.white[1] = v.max(range.white[0]),
+            4 => {
+                // Keep the split width while moving the pair.
+                let span = range.black[1] - range.black[0];
+                let b0 = v.min(range.white[0].saturating_sub(span));
+                range.black = [b0, b0 + span];
+            }
+            _ => {
+                let span = range.white[1] - range.white[0];
+                let w0 = v.max(range.black[1]).min(255 - span);
+                range.white = [w0, w0 + span];
+            }
+        }
+        *range = range.normalized();
+    }
+
+    if ui.is_rect_visible(rect) {
+        let painter = ui.painter();
+        let steps = 32;
+        for i in 0..steps {
+            let t0 = i as f32 / steps as f32;
+            let t1 = (i + 1) as f32 / steps as f32;
+            let v = ((t0 + t1) * 0.5 * 255.0) as u8;
+            let color = match channel {
+                BlendIfChannel::Gray => Color32::from_gray(v),
+                BlendIfChannel::Red => Color32::from_rgb(v, 0, 0),
+                BlendIfChannel::Green => Color32::from_rgb(0, v, 0),
+                BlendIfChannel::Blue => Color32::from_rgb(0, 0, v),
+            };
+            painter.rect_filled(
+                Rect::from_min_max(
+                    Pos2::new(bar.left() + bar.width() * t0, bar.top()),
+                    Pos2::new(bar.left() + bar.width() * t1 + 0.5, bar.bottom()),
+                ),
+                egui::CornerRadius::ZERO,
+                color,
+            );
+        }
+        painter.rect_stroke(
+            bar,
+            egui::CornerRadius::ZERO,
+            egui::Stroke::new(1.0, ui.visuals().widgets.noninteractive.bg_stroke.color),
+            egui::StrokeKind::Inside,
+        );
+        let stroke = egui::Stroke::new(1.0, ui.visuals().text_color());
+        let draw_handle = |v: u8, fill: Color32| {
+            let x = to_x(v);
+            let y = bar.bottom() + 1.0;
+            painter.add(egui::Shape::convex_polygon(
+                vec![
+                    Pos2::new(x, y),
+                    Pos2::new(x + 5.0, y + 8.0),
+                    Pos2::new(x - 5.0, y + 8.0),
+                ],
+                fill,
+                stroke,
+            ));
+        };
+        draw_handle(range.black[0], Color32::BLACK);
+        if range.black[1] != range.black[0] {
+            draw_handle(range.black[1], Color32::BLACK);
+        }
+        draw_handle(range.white[0], Color32::WHITE);
+        if range.white[1] != range.white[0] {
+            draw_handle(range.white[1], Color32::WHITE);
+        }
+    }
+
+    let label = if range.black[0] == range.black[1] {
+        range.black[0].to_string()
+    } else {
+        format!("{}/{}", range.black[0], range.black[1])
+    };
+    let white_label = if range.white[0] == range.white[1] {
+        range.white[0].to_string()
+    } else {
+        format!("{}/{}", range.white[0], range.white[1])
+    };
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new(label).size(11.0));
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.label(egui::RichText::new(white_label).size(11.0));
+        });
+    });
+}
@@ -2,6 +2,7 @@ pub mod adjustments;
 pub mod colors;
 pub mod dialogs;
 pub mod history;
+pub mod layer_comps;
 pub mod layers;
 pub mod palette;
 pub mod script_editor;
@@ -0,0 +1,172 @@
+//! Named brush presets and brush preset pack files (`.pfebrushes`).
+//!
+//! A preset captures everything that shapes a stroke — tip, size, hardness,
+//! spacing, flow, scatter, jitters, mode, mixer and dynamics — but not the paint
+//! color or layer blend mode. Packs are JSON and may embed the custom tip
+//! bitmaps their presets reference so they can be shared between machines.
+
+use crate::components::tools::{BrushDynamics, BrushMode, BrushTip, MixerSettings, ToolProperties};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// File extension for brush preset packs.
+pub const BRUSH_PRESET_PACK_EXTENSION: &str = "pfebrushes";
+
+/// Newest pack format version this build can read.
+const BRUSH_PRESET_PACK_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BrushPreset {
+    pub name: String,
+    /// Image tip name, empty for the built-in circle.
+    pub tip: String,
+    pub size: f32,
+    pub hardness: f32,
+    pub spacing: f32,
+    pub flow: f32,
+    pub scatter: f32,
+    pub hue_jitter: f32,
+    pub brightness_jitter: f32,
+    pub anti_aliased: bool,
+    pub tip_rotation: f32,
+    pub tip_random_rotation: bool,
+    pub tip_rotation_range: (f32, f32),
+    pub pressure_size: bool,
+    pub pressure_opacity: bool,
+    pub pressure_min_size: f32,
+    pub pressure_min_opacity: f32,
+    pub brush_mode: BrushMode,
+    pub mixer: MixerSettings,
+    pub dynamics: BrushDynamics,
+}
+
+impl Default for BrushPreset {
+    fn default() -> Self {
+        Self::from_properties("", &ToolProperties::default())
+    }
+}
+
+impl BrushPreset {
+    pub fn from_properties(name: &str, props: &ToolProperties) -> Self {
+        Self {
+            name: name.to_string(),
+            tip: match &props.brush_tip {
+                BrushTip::Circle => String::new(),
+                BrushTip::Image(tip) => tip.clone(),
+            },
+            size: props.size,
+            hardness: props.hardness,
+            spacing: props.spacing,
+            flow: props.flow,
+            scatter: props.scatter,
+            hue_jitter: props.hue_jitter,
+            brightness_jitter: props.brightness_jitter,
+            anti_aliased: props.anti_aliased,
+            tip_rotation: props.tip_rotation,
+            tip_random_rotation: props.tip_random_rotation,
+            tip_rotation_range: props.tip_rotation_range,
+            pressure_size: props.pressure_size,
+            pressure_opacity: props.pressure_opacity,
+            pressure_min_size: props.pressure_min_size,
+            pressure_min_opacity: props.pressure_min_opacity,
+            brush_mode: props.brush_mode,
+            mixer: props.mixer.clone(),
+            dynamics: props.dynamics.clone(),
+        }
+    }
+
+    /// Load this preset into the tool properties. Color and blend mode are kept.
+    pub fn apply_to(&self, props: &mut ToolProperties) {
+        props.brush_tip = if self.tip.is_empty() {
+            BrushTip::Circle
+        } else {
+            BrushTip::Image(self.tip.clone())
+        };
+        props.size = self.size.clamp(1.0, 1024.0);
+        props.hardness = self.hardness.clamp(0.0, 1.0);
+        props.spacing = self.spacing.clamp(0.01, 2.0);
+        props.flow = self.flow.clamp(0.0, 1.0);
+        props.scatter = self.scatter.clamp(0.0, 1.0);
+        props.hue_jitter = self.hue_jitter.clamp(0.0, 1.0);
+        props.brightness_jitter = self.brightness_jitter.clamp(0.0, 1.0);
+        props.anti_aliased = self.anti_aliased;
+        props.tip_rotation = self.tip_rotation;
+        props.tip_random_rotation = self.tip_random_rotation;
+        let (lo, hi) = self.tip_rotation_range;
+        let lo = lo.clamp(0.0, 360.0);
+        props.tip_rotation_range = (lo, hi.clamp(lo, 360.0));
+        props.pressure_size = self.pressure_size;
+        props.pressure_opacity = self.pressure_opacity;
+        props.pressure_min_size = self.pressure_min_size.clamp(0.0, 1.0);
+        props.pressure_min_opacity = self.pressure_min_opacity.clamp(0.0, 1.0);
+        props.brush_mode = self.brush_mode;
+        props.mixer = self.mixer.clamped();
+        props.dynamics = self.dynamics.clamped();
+    }
+
+    /// True when the preset's settings match `props` (name ignored).
+    pub fn matches(&self, props: &ToolProperties) -> bool {
+        *self == Self::from_properties(&self.name, props)
+    }
+}
+
+/// Add `preset`, replacing any existing preset with the same name.
+pub fn upsert_brush_preset(presets: &mut Vec<BrushPreset>, preset: BrushPreset) {
+    if let Some(existing) = presets.iter_mut().find(|p| p.name == preset.name) {
+        *existing = preset;
+    } else {
+        presets.push(preset);
+    }
+}
+
+/// A custom tip bitmap embedded in a pack.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PackedBrushTip {
+    pub name: String,
+    pub category: String,
+    /// Grayscale PNG, base64-encoded (same encoding as `AppSettings::custom_brush_tips`).
+    pub png_base64: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BrushPresetPack {
+    pub version: u32,
+    pub presets: Vec<BrushPreset>,
+    #[serde(default)]
+    pub tips: Vec<PackedBrushTip>,
+}
+
+impl BrushPresetPack {
+    pub fn new(presets: Vec<BrushPreset>, tips: Vec<PackedBrushTip>) -> Self {
+        Self {
+            version: BRUSH_PRESET_PACK_VERSION,
+            presets,
+            tips,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let pack: Self = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        if pack.version > BRUSH_PRESET_PACK_VERSION {
+            return Err(format!(
+                "Brush pack version {} is newer than this version of PaintFE supports",
+                pack.version
+            ));
+        }
+        Ok(pack)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_json()?).map_err(|e| e.to_string())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::from_json(&text)
+    }
+}
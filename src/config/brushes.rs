@@ -129,6 +129,11 @@ impl Assets {
             Icon::PerspectiveCrop,
             include_bytes!("../../assets/icons/tools/perspective_crop.png"),
         );
+        self.load_icon(
+            ctx,
+            Icon::Slice,
+            include_bytes!("../../assets/icons/tools/slice.png"),
+        );
         self.load_icon(
             ctx,
             Icon::ColorPicker,
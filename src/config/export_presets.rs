@@ -0,0 +1,232 @@
+//! Named export presets and the preset file (`export_presets.json`).
+//!
+//! A preset describes one output file: format and encoder options, a scale
+//! factor, color depth, what happens to source metadata and the suffix added
+//! to the document name (`@2x`, `-web`, ...). File > Export As writes one
+//! file per checked preset; the CLI's `--preset` reads the same file.
+
+use crate::components::dialogs::SaveFormat;
+use crate::jpeg_io::JpegOptions;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Preset file name, stored next to the settings file.
+pub const EXPORT_PRESETS_FILE: &str = "export_presets.json";
+
+/// Newest preset file version this build can read.
+const EXPORT_PRESETS_VERSION: u32 = 1;
+
+/// Bit depth of the written pixels, for formats that support more than one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportDepth {
+    /// Whatever the document holds: 16-bit and float layers stay deep where
+    /// the format allows.
+    #[default]
+    Source,
+    Eight,
+    /// 16 bits per channel for PNG, TIFF and Netpbm; 8-bit formats ignore it.
+    Sixteen,
+}
+
+impl ExportDepth {
+    pub const ALL: [ExportDepth; 3] = [Self::Source, Self::Eight, Self::Sixteen];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Source => "Document",
+            Self::Eight => "8-bit",
+            Self::Sixteen => "16-bit",
+        }
+    }
+}
+
+/// What happens to metadata carried over from the imported file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetadataPolicy {
+    /// Write pixels only.
+    #[default]
+    Strip,
+    /// Copy the source's PNG text chunks into PNG output.
+    Keep,
+}
+
+impl MetadataPolicy {
+    pub const ALL: [MetadataPolicy; 2] = [Self::Strip, Self::Keep];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Strip => "Strip",
+            Self::Keep => "Keep text chunks",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportPreset {
+    pub name: String,
+    pub format: SaveFormat,
+    /// JPEG / WebP quality (1–100).
+    pub quality: u8,
+    pub webp_lossless: bool,
+    pub jpeg: JpegOptions,
+    /// Output size relative to the canvas (`2.0` for `@2x`).
+    pub scale: f32,
+    pub depth: ExportDepth,
+    pub metadata: MetadataPolicy,
+    /// Appended to the file stem before the extension.
+    pub suffix: String,
+}
+
+impl Default for ExportPreset {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            format: SaveFormat::Png,
+            quality: 90,
+            webp_lossless: true,
+            jpeg: JpegOptions::default(),
+            scale: 1.0,
+            depth: ExportDepth::Source,
+            metadata: MetadataPolicy::Strip,
+            suffix: String::new(),
+        }
+    }
+}
+
+impl ExportPreset {
+    /// Check the preset can be exported with; names the problem otherwise.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.format == SaveFormat::Pfe {
+            return Err(format!(
+                "Preset '{}': PFE projects are saved, not exported",
+                self.name
+            ));
+        }
+        if !(self.scale.is_finite() && self.scale > 0.0 && self.scale <= 16.0) {
+            return Err(format!(
+                "Preset '{}': scale must be between 0 and 16",
+                self.name
+            ));
+        }
+        if self.suffix.contains(['/', '\\']) {
+            return Err(format!(
+                "Preset '{}': suffix may not contain path separators",
+                self.name
+            ));
+        }
+        Ok(())
+    }
+
+    /// `dir/<stem><suffix>.<ext>`.
+    pub fn output_path(&self, dir: &Path, stem: &str) -> PathBuf {
+        dir.join(self.file_name(stem))
+    }
+
+    pub fn file_name(&self, stem: &str) -> String {
+        format!("{}{}.{}", stem, self.suffix, self.format.extension())
+    }
+
+    /// Output dimensions for a `width`×`height` canvas, at least 1×1.
+    pub fn scaled_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let scale = |v: u32| ((v as f64 * self.scale as f64).round() as u32).max(1);
+        (scale(width), scale(height))
+    }
+}
+
+/// Presets used until the user saves their own: `@1x` and `@2x` PNG, WebP
+/// and JPEG.
+pub fn default_export_presets() -> Vec<ExportPreset> {
+    vec![
+        ExportPreset {
+            name: "PNG @1x".to_string(),
+            ..Default::default()
+        },
+        ExportPreset {
+            name: "PNG @2x".to_string(),
+            scale: 2.0,
+            suffix: "@2x".to_string(),
+            ..Default::default()
+        },
+        ExportPreset {
+            name: "WebP".to_string(),
+            format: SaveFormat::Webp,
+            quality: 85,
+            webp_lossless: false,
+            ..Default::default()
+        },
+        ExportPreset {
+            name: "JPEG".to_string(),
+            format: SaveFormat::Jpeg,
+            quality: 85,
+            jpeg: JpegOptions {
+                progressive: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    ]
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExportPresetFile {
+    pub version: u32,
+    pub presets: Vec<ExportPreset>,
+}
+
+impl ExportPresetFile {
+    pub fn new(presets: Vec<ExportPreset>) -> Self {
+        Self {
+            version: EXPORT_PRESETS_VERSION,
+            presets,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let file: Self = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        if file.version > EXPORT_PRESETS_VERSION {
+            return Err(format!(
+                "Export preset file version {} is newer than this version of PaintFE supports",
+                file.version
+            ));
+        }
+        Ok(file)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_json()?).map_err(|e| e.to_string())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::from_json(&text)
+    }
+}
+
+/// Where the app and the CLI keep the preset file, beside the settings file.
+pub fn export_presets_path() -> Option<PathBuf> {
+    crate::config::settings::AppSettings::settings_path()
+        .and_then(|p| p.parent().map(|dir| dir.join(EXPORT_PRESETS_FILE)))
+}
+
+/// The user's presets, or the defaults when no preset file has been saved.
+/// A file that exists but fails to parse is reported rather than replaced.
+pub fn load_export_presets() -> Result<Vec<ExportPreset>, String> {
+    match export_presets_path() {
+        Some(path) if path.exists() => ExportPresetFile::load(&path)
+            .map(|file| file.presets)
+            .map_err(|e| format!("{}: {}", path.display(), e)),
+        _ => Ok(default_export_presets()),
+    }
+}
+
+pub fn save_export_presets(presets: &[ExportPreset]) -> Result<(), String> {
+    let path = export_presets_path().ok_or("No settings directory")?;
+    ExportPresetFile::new(presets.to_vec()).save(&path)
+}
@@ -16,6 +16,7 @@ pub enum Icon {
     MovePixels,
     MoveSelection,
     PerspectiveCrop,
+    Slice,
     ColorPicker,
     CloneStamp,
     Zoom,
@@ -221,6 +222,7 @@ impl Icon {
             Icon::MovePixels => "[M]",
             Icon::MoveSelection => "[S]",
             Icon::PerspectiveCrop => "[P]",
+            Icon::Slice => "[#]",
             Icon::ColorPicker => "[C]",
             Icon::CloneStamp => "[S]",
             Icon::Zoom => "[Z]",
@@ -415,6 +417,7 @@ impl Icon {
             Icon::MovePixels => "Move Selected Pixels",
             Icon::MoveSelection => "Move Selection",
             Icon::PerspectiveCrop => "Perspective Crop",
+            Icon::Slice => "Slice Tool",
             Icon::ColorPicker => "Color Picker",
             Icon::CloneStamp => "Clone Stamp",
             Icon::Zoom => "Zoom Tool",
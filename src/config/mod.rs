@@ -1,4 +1,6 @@
+pub mod brush_presets;
 pub mod brushes;
+pub mod export_presets;
 pub mod icons;
 pub mod keybindings;
 pub mod settings;
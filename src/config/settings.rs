@@ -1,4 +1,6 @@
+use crate::config::brush_presets::BrushPreset;
 use crate::config::keybindings::KeyBindings;
+use crate::gradient::Gradient;
 use crate::theme::{AccentColors, ThemeMode, ThemeOverrides, ThemePreset, UiDensity};
 use egui::Color32;
 use std::path::PathBuf;
@@ -122,6 +124,11 @@ pub struct AppSettings {
     // Dialog option persistence
     pub persist_new_file_lock_aspect: bool,
     pub persist_resize_lock_aspect: bool,
+    pub persist_page_setup: crate::ops::page_setup::PageSetup,
+    /// Folder File > Export As last wrote to; separate from project save paths.
+    pub persist_export_directory: String,
+    /// Names of the presets checked in File > Export As.
+    pub persist_export_presets: Vec<String>,
 
     // Tool option persistence
     pub persisted_active_tool: String,
@@ -139,8 +146,33 @@ pub struct AppSettings {
     pub persisted_pressure_min_opacity: f32,
     pub persisted_brush_mode: String,
     pub persisted_brush_tip: String,
+    /// Brush dynamics (see `tools::BrushDynamics`)
+    pub persisted_dyn_pressure_curve: String,
+    pub persisted_dyn_tilt_size: f32,
+    pub persisted_dyn_tilt_opacity: f32,
+    pub persisted_dyn_tilt_scatter: f32,
+    pub persisted_dyn_tilt_angle: bool,
+    pub persisted_dyn_velocity_curve: String,
+    pub persisted_dyn_velocity_size: f32,
+    pub persisted_dyn_velocity_opacity: f32,
+    pub persisted_dyn_velocity_scatter: f32,
+    pub persisted_dyn_direction_angle: bool,
+    pub persisted_dyn_taper_start: f32,
+    pub persisted_dyn_taper_end: f32,
+    pub persisted_dyn_stabilizer: String,
+    pub persisted_dyn_stabilizer_strength: f32,
+    pub persisted_dyn_stabilizer_radius: f32,
+    /// Mixer brush (see `tools::MixerSettings`)
+    pub persisted_mixer_wetness: f32,
+    pub persisted_mixer_load: f32,
+    pub persisted_mixer_mix: f32,
+    pub persisted_mixer_sample_all_layers: bool,
     /// Custom brush tips persisted across restarts: (name, category, base64_png_data)
     pub custom_brush_tips: Vec<(String, String, String)>,
+    /// Named brush presets (one JSON object per settings line)
+    pub brush_presets: Vec<BrushPreset>,
+    /// Saved gradients (one JSON object per settings line)
+    pub gradient_library: Vec<Gradient>,
     /// Custom vector shapes persisted across restarts: (name, category, base64_svg_path_data)
     pub custom_shapes: Vec<(String, String, String)>,
     pub persisted_fill_tolerance: f32,
@@ -304,6 +336,9 @@ impl Default for AppSettings {
 
             persist_new_file_lock_aspect: true,
             persist_resize_lock_aspect: true,
+            persist_page_setup: crate::ops::page_setup::PageSetup::default(),
+            persist_export_directory: String::new(),
+            persist_export_presets: Vec::new(),
 
             persisted_active_tool: "brush".to_string(),
             persisted_brush_size: 10.0,
@@ -320,7 +355,28 @@ impl Default for AppSettings {
             persisted_pressure_min_opacity: 0.1,
             persisted_brush_mode: "normal".to_string(),
             persisted_brush_tip: String::new(),
+            persisted_dyn_pressure_curve: "linear".to_string(),
+            persisted_dyn_tilt_size: 0.0,
+            persisted_dyn_tilt_opacity: 0.0,
+            persisted_dyn_tilt_scatter: 0.0,
+            persisted_dyn_tilt_angle: false,
+            persisted_dyn_velocity_curve: "linear".to_string(),
+            persisted_dyn_velocity_size: 0.0,
+            persisted_dyn_velocity_opacity: 0.0,
+            persisted_dyn_velocity_scatter: 0.0,
+            persisted_dyn_direction_angle: false,
+            persisted_dyn_taper_start: 0.0,
+            persisted_dyn_taper_end: 0.0,
+            persisted_dyn_stabilizer: "off".to_string(),
+            persisted_dyn_stabilizer_strength: 0.5,
+            persisted_dyn_stabilizer_radius: 20.0,
+            persisted_mixer_wetness: 0.5,
+            persisted_mixer_load: 0.5,
+            persisted_mixer_mix: 0.5,
+            persisted_mixer_sample_all_layers: false,
             custom_brush_tips: Vec::new(),
+            brush_presets: Vec::new(),
+            gradient_library: Vec::new(),
             custom_shapes: Vec::new(),
             persisted_fill_tolerance: 5.0,
             persisted_fill_anti_aliased: true,
@@ -997,6 +1053,16 @@ impl AppSettings {
             "persist_resize_lock_aspect={}\n",
             self.persist_resize_lock_aspect
         ));
+        if let Ok(json) = serde_json::to_string(&self.persist_page_setup) {
+            content.push_str(&format!("persist_page_setup={}\n", json));
+        }
+        content.push_str(&format!(
+            "persist_export_directory={}\n",
+            self.persist_export_directory
+        ));
+        if let Ok(json) = serde_json::to_string(&self.persist_export_presets) {
+            content.push_str(&format!("persist_export_presets={}\n", json));
+        }
         content.push_str(&format!(
             "persisted_active_tool={}\n",
             self.persisted_active_tool
@@ -1057,6 +1123,82 @@ impl AppSettings {
             "persisted_brush_tip={}\n",
             self.persisted_brush_tip
         ));
+        content.push_str(&format!(
+            "persisted_dyn_pressure_curve={}\n",
+            self.persisted_dyn_pressure_curve
+        ));
+        content.push_str(&format!(
+            "persisted_dyn_tilt_size={}\n",
+            self.persisted_dyn_tilt_size
+        ));
+        content.push_str(&format!(
+            "persisted_dyn_tilt_opacity={}\n",
+            self.persisted_dyn_tilt_opacity
+        ));
+        content.push_str(&format!(
+            "persisted_dyn_tilt_scatter={}\n",
+            self.persisted_dyn_tilt_scatter
+        ));
+        content.push_str(&format!(
+            "persisted_dyn_tilt_angle={}\n",
+            self.persisted_dyn_tilt_angle
+        ));
+        content.push_str(&format!(
+            "persisted_dyn_velocity_curve={}\n",
+            self.persisted_dyn_velocity_curve
+        ));
+        content.push_str(&format!(
+            "persisted_dyn_velocity_size={}\n",
+            self.persisted_dyn_velocity_size
+        ));
+        content.push_str(&format!(
+            "persisted_dyn_velocity_opacity={}\n",
+            self.persisted_dyn_velocity_opacity
+        ));
+        content.push_str(&format!(
+            "persisted_dyn_velocity_scatter={}\n",
+            self.persisted_dyn_velocity_scatter
+        ));
+        content.push_str(&format!(
+            "persisted_dyn_direction_angle={}\n",
+            self.persisted_dyn_direction_angle
+        ));
+        content.push_str(&format!(
+            "persisted_dyn_taper_start={}\n",
+            self.persisted_dyn_taper_start
+        ));
+        content.push_str(&format!(
+            "persisted_dyn_taper_end={}\n",
+            self.persisted_dyn_taper_end
+        ));
+        content.push_str(&format!(
+            "persisted_dyn_stabilizer={}\n",
+            self.persisted_dyn_stabilizer
+        ));
+        content.push_str(&format!(
+            "persisted_dyn_stabilizer_strength={}\n",
+            self.persisted_dyn_stabilizer_strength
+        ));
+        content.push_str(&format!(
+            "persisted_dyn_stabilizer_radius={}\n",
+            self.persisted_dyn_stabilizer_radius
+        ));
+        content.push_str(&format!(
+            "persisted_mixer_wetness={}\n",
+            self.persisted_mixer_wetness
+        ));
+        content.push_str(&format!(
+            "persisted_mixer_load={}\n",
+            self.persisted_mixer_load
+        ));
+        content.push_str(&format!(
+            "persisted_mixer_mix={}\n",
+            self.persisted_mixer_mix
+        ));
+        content.push_str(&format!(
+            "persisted_mixer_sample_all_layers={}\n",
+            self.persisted_mixer_sample_all_layers
+        ));
         // Save custom brush tips (name, category, base64 png)
         for (name, cat, b64) in &self.custom_brush_tips {
             content.push_str(&format!("custom_brush_tip={},{},{}\n", name, cat, b64));
@@ -1064,6 +1206,16 @@ impl AppSettings {
         for (name, cat, b64) in &self.custom_shapes {
             content.push_str(&format!("custom_shape={},{},{}\n", name, cat, b64));
         }
+        for preset in &self.brush_presets {
+            if let Ok(json) = serde_json::to_string(preset) {
+                content.push_str(&format!("brush_preset={}\n", json));
+            }
+        }
+        for gradient in &self.gradient_library {
+            if let Ok(json) = serde_json::to_string(gradient) {
+                content.push_str(&format!("gradient={}\n", json));
+            }
+        }
         content.push_str(&format!(
             "persisted_fill_tolerance={}\n",
             self.persisted_fill_tolerance
@@ -1455,6 +1607,19 @@ impl AppSettings {
                 "persist_resize_lock_aspect" => {
                     s.persist_resize_lock_aspect = val == "true";
                 }
+                "persist_page_setup" => {
+                    if let Ok(setup) = serde_json::from_str(val) {
+                        s.persist_page_setup = setup;
+                    }
+                }
+                "persist_export_directory" => {
+                    s.persist_export_directory = val.to_string();
+                }
+                "persist_export_presets" => {
+                    if let Ok(names) = serde_json::from_str(val) {
+                        s.persist_export_presets = names;
+                    }
+                }
                 "persisted_active_tool" => {
                     s.persisted_active_tool = val.to_string();
                 }
@@ -1500,6 +1665,63 @@ impl AppSettings {
                 "persisted_brush_tip" => {
                     s.persisted_brush_tip = val.to_string();
                 }
+                "persisted_dyn_pressure_curve" => {
+                    s.persisted_dyn_pressure_curve = val.to_string();
+                }
+                "persisted_dyn_tilt_size" => {
+                    s.persisted_dyn_tilt_size = val.parse().unwrap_or(0.0);
+                }
+                "persisted_dyn_tilt_opacity" => {
+                    s.persisted_dyn_tilt_opacity = val.parse().unwrap_or(0.0);
+                }
+                "persisted_dyn_tilt_scatter" => {
+                    s.persisted_dyn_tilt_scatter = val.parse().unwrap_or(0.0);
+                }
+                "persisted_dyn_tilt_angle" => {
+                    s.persisted_dyn_tilt_angle = val == "true";
+                }
+                "persisted_dyn_velocity_curve" => {
+                    s.persisted_dyn_velocity_curve = val.to_string();
+                }
+                "persisted_dyn_velocity_size" => {
+                    s.persisted_dyn_velocity_size = val.parse().unwrap_or(0.0);
+                }
+                "persisted_dyn_velocity_opacity" => {
+                    s.persisted_dyn_velocity_opacity = val.parse().unwrap_or(0.0);
+                }
+                "persisted_dyn_velocity_scatter" => {
+                    s.persisted_dyn_velocity_scatter = val.parse().unwrap_or(0.0);
+                }
+                "persisted_dyn_direction_angle" => {
+                    s.persisted_dyn_direction_angle = val == "true";
+                }
+                "persisted_dyn_taper_start" => {
+                    s.persisted_dyn_taper_start = val.parse().unwrap_or(0.0);
+                }
+                "persisted_dyn_taper_end" => {
+                    s.persisted_dyn_taper_end = val.parse().unwrap_or(0.0);
+                }
+                "persisted_dyn_stabilizer" => {
+                    s.persisted_dyn_stabilizer = val.to_string();
+                }
+                "persisted_dyn_stabilizer_strength" => {
+                    s.persisted_dyn_stabilizer_strength = val.parse().unwrap_or(0.5);
+                }
+                "persisted_dyn_stabilizer_radius" => {
+                    s.persisted_dyn_stabilizer_radius = val.parse().unwrap_or(20.0);
+                }
+                "persisted_mixer_wetness" => {
+                    s.persisted_mixer_wetness = val.parse().unwrap_or(0.5);
+                }
+                "persisted_mixer_load" => {
+                    s.persisted_mixer_load = val.parse().unwrap_or(0.5);
+                }
+                "persisted_mixer_mix" => {
+                    s.persisted_mixer_mix = val.parse().unwrap_or(0.5);
+                }
+                "persisted_mixer_sample_all_layers" => {
+                    s.persisted_mixer_sample_all_layers = val == "true";
+                }
                 "custom_brush_tip" => {
                     // Format: name,category,base64data
                     if let Some((rest, b64)) = val.rsplit_once(',')
@@ -1512,6 +1734,16 @@ impl AppSettings {
                         ));
                     }
                 }
+                "brush_preset" => {
+                    if let Ok(preset) = serde_json::from_str::<BrushPreset>(val) {
+                        s.brush_presets.push(preset);
+                    }
+                }
+                "gradient" => {
+                    if let Ok(gradient) = serde_json::from_str::<Gradient>(val) {
+                        s.gradient_library.push(gradient);
+                    }
+                }
                 "custom_shape" => {
                     if let Some((rest, b64)) = val.rsplit_once(',')
                         && let Some((name, cat)) = rest.split_once(',')
@@ -0,0 +1,900 @@
+//! DirectDraw Surface (`.dds`) textures.
+//!
+//! Import reads the top mip level of the first surface: BC1/BC2/BC3
+//! (DXT1/3/5), BC7, and uncompressed RGB(A) or luminance layouts described by
+//! bit masks or a DX10 format code.
+//!
+//! Export writes uncompressed BGRA8, BC1, BC3 or BC7, optionally with a full
+//! mip chain. The block encoders fit each 4×4 block's endpoints along its
+//! principal colour axis. BC7 output uses mode 6 (one subset, RGBA endpoints,
+//! 4-bit indices) for every block.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use image::imageops::FilterType;
+use image::{Rgba, RgbaImage};
+
+use crate::canvas::{CanvasState, Layer, TiledImage};
+#[cfg(target_arch = "wasm32")]
+use crate::web_fs::File;
+
+const MAGIC: &[u8; 4] = b"DDS ";
+const HEADER_LEN: usize = 128;
+const DX10_HEADER_LEN: usize = 20;
+
+const DDSD_CAPS: u32 = 0x1;
+const DDSD_HEIGHT: u32 = 0x2;
+const DDSD_WIDTH: u32 = 0x4;
+const DDSD_PITCH: u32 = 0x8;
+const DDSD_PIXELFORMAT: u32 = 0x1000;
+const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+const DDSD_LINEARSIZE: u32 = 0x80000;
+
+const DDPF_ALPHAPIXELS: u32 = 0x1;
+const DDPF_ALPHA: u32 = 0x2;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+const DDPF_LUMINANCE: u32 = 0x20000;
+
+const DDSCAPS_COMPLEX: u32 = 0x8;
+const DDSCAPS_TEXTURE: u32 = 0x1000;
+const DDSCAPS_MIPMAP: u32 = 0x400000;
+
+const DXGI_BC7_UNORM: u32 = 98;
+
+/// Pixel encoding for DDS export.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum DdsCompression {
+    /// Uncompressed 32-bit BGRA.
+    #[default]
+    None,
+    /// DXT1: RGB with 1-bit alpha, 4 bits per pixel.
+    Bc1,
+    /// DXT5: RGB with interpolated alpha, 8 bits per pixel.
+    Bc3,
+    /// High-quality RGBA, 8 bits per pixel (DX10 header).
+    Bc7,
+}
+
+impl DdsCompression {
+    pub const ALL: [DdsCompression; 4] = [Self::None, Self::Bc1, Self::Bc3, Self::Bc7];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::None => "Uncompressed (BGRA8)",
+            Self::Bc1 => "BC1 / DXT1",
+            Self::Bc3 => "BC3 / DXT5",
+            Self::Bc7 => "BC7",
+        }
+    }
+
+    /// Bytes per 4×4 block, or `None` for uncompressed output.
+    fn block_len(self) -> Option<usize> {
+        match self {
+            Self::None => None,
+            Self::Bc1 => Some(8),
+            Self::Bc3 | Self::Bc7 => Some(16),
+        }
+    }
+}
+
+/// DDS export settings chosen in the save dialog.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct DdsOptions {
+    pub compression: DdsCompression,
+    /// Append successively halved levels down to 1×1.
+    pub mipmaps: bool,
+}
+
+/// Flattened canvas ready to encode as a DDS texture.
+pub struct DdsData {
+    image: RgbaImage,
+    options: DdsOptions,
+}
+
+// ============================================================================
+// IMPORT
+// ============================================================================
+
+/// Read the top level of a `.dds` file.
+pub fn load_dds(path: &Path) -> Result<RgbaImage, String> {
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    decode_dds(&data)
+}
+
+/// Open in-memory DDS bytes as a single-layer canvas named `name`.
+pub fn parse_dds(data: &[u8], name: &str) -> Result<CanvasState, String> {
+    let image = decode_dds(data)?;
+    let (width, height) = image.dimensions();
+    let mut state = CanvasState::new(width, height);
+    state.layers.clear();
+    let mut layer = Layer::new(name.to_string(), width, height, Rgba([0, 0, 0, 0]));
+    layer.pixels = TiledImage::from_rgba_image(&image);
+    state.layers.push(layer);
+    state.active_layer_index = 0;
+    state.composite_cache = None;
+    Ok(state)
+}
+
+enum Layout {
+    Bc1,
+    Bc2,
+    Bc3,
+    Bc7,
+    /// Uncompressed pixels of `bits` bits with R, G, B, A masks (0 = absent).
+    Masked {
+        bits: u32,
+        masks: [u32; 4],
+    },
+}
+
+pub fn decode_dds(data: &[u8]) -> Result<RgbaImage, String> {
+    if data.len() < HEADER_LEN || &data[..4] != MAGIC {
+        return Err("Not a DDS file".to_string());
+    }
+    let u32_at = |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+    let height = u32_at(12);
+    let width = u32_at(16);
+    crate::io::validate_open_dimensions(width, height)?;
+
+    let pf_flags = u32_at(80);
+    let mut offset = HEADER_LEN;
+    let layout = if pf_flags & DDPF_FOURCC != 0 {
+        match &data[84..88] {
+            b"DXT1" => Layout::Bc1,
+            b"DXT2" | b"DXT3" => Layout::Bc2,
+            b"DXT4" | b"DXT5" => Layout::Bc3,
+            b"DX10" => {
+                if data.len() < HEADER_LEN + DX10_HEADER_LEN {
+                    return Err("DDS DX10 header is truncated".to_string());
+                }
+                offset += DX10_HEADER_LEN;
+                match u32_at(HEADER_LEN) {
+                    71 | 72 => Layout::Bc1,
+                    74 | 75 => Layout::Bc2,
+                    77 | 78 => Layout::Bc3,
+                    98 | 99 => Layout::Bc7,
+                    28 | 29 => Layout::Masked {
+                        bits: 32,
+                        masks: [0xff, 0xff00, 0xff0000, 0xff000000],
+                    },
+                    87 | 91 => Layout::Masked {
+                        bits: 32,
+                        masks: [0xff0000, 0xff00, 0xff, 0xff000000],
+                    },
+                    88 | 93 => Layout::Masked {
+                        bits: 32,
+                        masks: [0xff0000, 0xff00, 0xff, 0],
+                    },
+                    other => return Err(format!("Unsupported DDS DXGI format {}", other)),
+                }
+            }
+            fourcc => {
+                return Err(format!(
+                    "Unsupported DDS compression '{}'",
+                    String::from_utf8_lossy(fourcc)
+                ));
+            }
+        }
+    } else {
+        let bits = u32_at(88);
+        if !matches!(bits, 8 | 16 | 24 | 32) {
+            return Err(format!("Unsupported DDS pixel size {} bits", bits));
+        }
+        let alpha = if pf_flags & (DDPF_ALPHAPIXELS | DDPF_ALPHA) != 0 {
+            u32_at(104)
+        } else {
+            0
+        };
+        let masks = if pf_flags & DDPF_LUMINANCE != 0 {
+            let luma = u32_at(92);
+            [luma, luma, luma, alpha]
+        } else if pf_flags & DDPF_RGB != 0 {
+            [u32_at(92), u32_at(96), u32_at(100), alpha]
+        } else {
+            [0, 0, 0, alpha]
+        };
+        Layout::Masked { bits, masks }
+    };
+
+    let body = &data[offset..];
+    match layout {
+        Layout::Bc1 => decode_blocks(body, width, height, 8, decode_bc1),
+        Layout::Bc2 => decode_blocks(body, width, height, 16, decode_bc2),
+        Layout::Bc3 => decode_blocks(body, width, height, 16, decode_bc3),
+        Layout::Bc7 => decode_blocks(body, width, height, 16, decode_bc7),
+        Layout::Masked { bits, masks } => decode_masked(body, width, height, bits, masks),
+    }
+}
+
+fn decode_blocks(
+    body: &[u8],
+    width: u32,
+    height: u32,
+    block_len: usize,
+    decode: fn(&[u8]) -> [[u8; 4]; 16],
+) -> Result<RgbaImage, String> {
+    let blocks_wide = width.div_ceil(4);
+    let needed = blocks_wide as usize * height.div_ceil(4) as usize * block_len;
+    if body.len() < needed {
+        return Err("DDS pixel data is truncated".to_string());
+    }
+    let mut image = RgbaImage::new(width, height);
+    for (i, block) in body[..needed].chunks_exact(block_len).enumerate() {
+        let bx = (i as u32 % blocks_wide) * 4;
+        let by = (i as u32 / blocks_wide) * 4;
+        for (j, texel) in decode(block).iter().enumerate() {
+            let (x, y) = (bx + j as u32 % 4, by + j as u32 / 4);
+            if x < width && y < height {
+                image.put_pixel(x, y, Rgba(*texel));
+            }
+        }
+    }
+    Ok(image)
+}
+
+fn decode_masked(
+    body: &[u8],
+    width: u32,
+    height: u32,
+    bits: u32,
+    masks: [u32; 4],
+) -> Result<RgbaImage, String> {
+    let bytes = (bits / 8) as usize;
+    let needed = width as usize * height as usize * bytes;
+    if body.len() < needed {
+        return Err("DDS pixel data is truncated".to_string());
+    }
+    let channel = |value: u32, mask: u32, absent: u8| {
+        if mask == 0 {
+            return absent;
+        }
+        let shift = mask.trailing_zeros();
+        let max = (mask >> shift) as u64;
+        ((((value & mask) >> shift) as u64 * 255 + max / 2) / max) as u8
+    };
+    let mut image = RgbaImage::new(width, height);
+    for (i, px) in body[..needed].chunks_exact(bytes).enumerate() {
+        let value = px.iter().rev().fold(0u32, |acc, &b| (acc << 8) | b as u32);
+        image.put_pixel(
+            i as u32 % width,
+            i as u32 / width,
+            Rgba([
+                channel(value, masks[0], 0),
+                channel(value, masks[1], 0),
+                channel(value, masks[2], 0),
+                channel(value, masks[3], 255),
+            ]),
+        );
+    }
+    Ok(image)
+}
+
+fn rgb565(c: u16) -> [u8; 4] {
+    let (r, g, b) = ((c >> 11) & 31, (c >> 5) & 63, c & 31);
+    [
+        (r << 3 | r >> 2) as u8,
+        (g << 2 | g >> 4) as u8,
+        (b << 3 | b >> 2) as u8,
+        255,
+    ]
+}
+
+/// The four colours a BC1 block can pick from. `c0 <= c1` selects the
+/// three-colour mode with transparent black unless `four` forces otherwise
+/// (BC2/BC3 colour blocks are always four-colour).
+fn bc1_palette(c0: u16, c1: u16, four: bool) -> [[u8; 4]; 4] {
+    let (a, b) = (rgb565(c0), rgb565(c1));
+    let mix = |wa: u32, wb: u32| -> [u8; 4] {
+        let d = wa + wb;
+        let ch = |i: usize| ((a[i] as u32 * wa + b[i] as u32 * wb + d / 2) / d) as u8;
+        [ch(0), ch(1), ch(2), 255]
+    };
+    if four || c0 > c1 {
+        [a, b, mix(2, 1), mix(1, 2)]
+    } else {
+        [a, b, mix(1, 1), [0, 0, 0, 0]]
+    }
+}
+
+fn decode_color_block(block: &[u8], four: bool) -> [[u8; 4]; 16] {
+    let c0 = u16::from_le_bytes([block[0], block[1]]);
+    let c1 = u16::from_le_bytes([block[2], block[3]]);
+    let palette = bc1_palette(c0, c1, four);
+    let indices = u32::from_le_bytes(block[4..8].try_into().unwrap());
+    std::array::from_fn(|i| palette[(indices >> (2 * i)) as usize & 3])
+}
+
+fn decode_bc1(block: &[u8]) -> [[u8; 4]; 16] {
+    decode_color_block(block, false)
+}
+
+fn decode_bc2(block: &[u8]) -> [[u8; 4]; 16] {
+    let mut texels = decode_color_block(&block[8..], true);
+    let alpha = u64::from_le_bytes(block[..8].try_into().unwrap());
+    for (i, texel) in texels.iter_mut().enumerate() {
+        texel[3] = ((alpha >> (4 * i)) & 15) as u8 * 17;
+    }
+    texels
+}
+
+/// The eight alpha levels a BC3 alpha block can pick from.
+fn alpha_palette(a0: u8, a1: u8) -> [u8; 8] {
+    let (a0, a1) = (a0 as u32, a1 as u32);
+    let mut palette = [a0 as u8, a1 as u8, 0, 0, 0, 0, 0, 255];
+    if a0 > a1 {
+        for i in 1..7 {
+            palette[i + 1] = (((7 - i as u32) * a0 + i as u32 * a1 + 3) / 7) as u8;
+        }
+    } else {
+        for i in 1..5 {
+            palette[i + 1] = (((5 - i as u32) * a0 + i as u32 * a1 + 2) / 5) as u8;
+        }
+    }
+    palette
+}
+
+fn decode_bc3(block: &[u8]) -> [[u8; 4]; 16] {
+    let mut texels = decode_color_block(&block[8..], true);
+    let palette = alpha_palette(block[0], block[1]);
+    let mut bits = [0u8; 8];
+    bits[..6].copy_from_slice(&block[2..8]);
+    let indices = u64::from_le_bytes(bits);
+    for (i, texel) in texels.iter_mut().enumerate() {
+        texel[3] = palette[(indices >> (3 * i)) as usize & 7];
+    }
+    texels
+}
+
+// ----------------------------------------------------------------------------
+// BC7
+// ----------------------------------------------------------------------------
+
+struct Bc7Mode {
+    subsets: usize,
+    partition_bits: usize,
+    rotation_bits: usize,
+    index_selection_bits: usize,
+    color_bits: u32,
+    alpha_bits: u32,
+    /// One p-bit per endpoint.
+    endpoint_pbits: bool,
+    /// One p-bit per subset, shared by both endpoints.
+    shared_pbits: bool,
+    index_bits: usize,
+    index2_bits: usize,
+}
+
+const fn mode(
+    subsets: usize,
+    partition_bits: usize,
+    rotation_bits: usize,
+    index_selection_bits: usize,
+    color_bits: u32,
+    alpha_bits: u32,
+    endpoint_pbits: bool,
+    shared_pbits: bool,
+    index_bits: usize,
+    index2_bits: usize,
+) -> Bc7Mode {
+    Bc7Mode {
+        subsets,
+        partition_bits,
+        rotation_bits,
+        index_selection_bits,
+        color_bits,
+        alpha_bits,
+        endpoint_pbits,
+        shared_pbits,
+        index_bits,
+        index2_bits,
+    }
+}
+
+const BC7_MODES: [Bc7Mode; 8] = [
+    mode(3, 4, 0, 0, 4, 0, true, false, 3, 0),
+    mode(2, 6, 0, 0, 6, 0, false, true, 3, 0),
+    mode(3, 6, 0, 0, 5, 0, false, false, 2, 0),
+    mode(2, 6, 0, 0, 7, 0, true, false, 2, 0),
+    mode(1, 0, 2, 1, 5, 6, false, false, 2, 3),
+    mode(1, 0, 2, 0, 7, 8, false, false, 2, 2),
+    mode(1, 0, 0, 0, 7, 7, true, false, 4, 0),
+    mode(2, 6, 0, 0, 5, 5, true, false, 2, 0),
+];
+
+/// Two-subset partitions: bit `i` set puts texel `i` in subset 1.
+const PARTITIONS_2: [u16; 64] = [
+    0xCCCC, 0x8888, 0xEEEE, 0xECC8, 0xC880, 0xFEEC, 0xFEC8, 0xEC80, 0xC800, 0xFFEC, 0xFE80, 0xE800,
+    0xFFE8, 0xFF00, 0xFFF0, 0xF000, 0xF710, 0x008E, 0x7100, 0x08CE, 0x008C, 0x7310, 0x3100, 0x8CCE,
+    0x088C, 0x3110, 0x6666, 0x366C, 0x17E8, 0x0FF0, 0x718E, 0x399C, 0xAAAA, 0xF0F0, 0x5A5A, 0x33CC,
+    0x3C3C, 0x55AA, 0x9696, 0xA55A, 0x73CE, 0x13C8, 0x324C, 0x3BDC, 0x6996, 0xC33C, 0x9966, 0x0660,
+    0x0272, 0x04E4, 0x4E40, 0x2720, 0xC936, 0x936C, 0x39C6, 0x639C, 0x9336, 0x9CC6, 0x817E, 0xE718,
+    0xCCF0, 0x0FCC, 0x7744, 0xEE22,
+];
+
+/// Three-subset partitions: bits `2i..2i+2` hold texel `i`'s subset.
+const PARTITIONS_3: [u32; 64] = [
+    0xAA685050, 0x6A5A5040, 0x5A5A4200, 0x5450A0A8, 0xA5A50000, 0xA0A05050, 0x5555A0A0, 0x5A5A5050,
+    0xAA550000, 0xAA555500, 0xAAAA5500, 0x90909090, 0x94949494, 0xA4A4A4A4, 0xA9A59450, 0x2A0A4250,
+    0xA5945040, 0x0A425054, 0xA5A5A500, 0x55A0A0A0, 0xA8A85454, 0x6A6A4040, 0xA4A45000, 0x1A1A0500,
+    0x0050A4A4, 0xAAA59090, 0x14696914, 0x69691400, 0xA08585A0, 0xAA821414, 0x50A4A450, 0x6A5A0200,
+    0xA9A58000, 0x5090A0A8, 0xA8A09050, 0x24242424, 0x00AA5500, 0x24924924, 0x24499224, 0x50A50A50,
+    0x500AA550, 0xAAAA4444, 0x66660000, 0xA5A0A5A0, 0x50A050A0, 0x69286928, 0x44AAAA44, 0x66666600,
+    0xAA444444, 0x54A854A8, 0x95809580, 0x96969600, 0xA85454A8, 0x80959580, 0xAA141414, 0x96960000,
+    0xAAAA1414, 0xA05050A0, 0xA0A5A5A0, 0x96000000, 0x40804080, 0xA9A8A9A8, 0xAAAAAA44, 0x2A4A5254,
+];
+
+/// Anchor texel of subset 1 in two-subset partitions.
+const ANCHORS_2: [u8; 64] = [
+    15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 2, 8, 2, 2, 8, 8, 15, 2, 8,
+    2, 2, 8, 8, 2, 2, 15, 15, 6, 8, 2, 8, 15, 15, 2, 8, 2, 2, 2, 15, 15, 6, 6, 2, 6, 8, 15, 15, 2,
+    2, 15, 15, 15, 15, 15, 2, 2, 15,
+];
+
+/// Anchor texels of subsets 1 and 2 in three-subset partitions.
+const ANCHORS_3A: [u8; 64] = [
+    3, 3, 15, 15, 8, 3, 15, 15, 8, 8, 6, 6, 6, 5, 3, 3, 3, 3, 8, 15, 3, 3, 6, 10, 5, 8, 8, 6, 8, 5,
+    15, 15, 8, 15, 3, 5, 6, 10, 8, 15, 15, 3, 15, 5, 15, 15, 15, 15, 3, 15, 5, 5, 5, 8, 5, 10, 5,
+    10, 8, 13, 15, 12, 3, 3,
+];
+const ANCHORS_3B: [u8; 64] = [
+    15, 8, 8, 3, 15, 15, 3, 8, 15, 15, 15, 15, 15, 15, 15, 8, 15, 8, 15, 3, 15, 8, 15, 8, 3, 15, 6,
+    10, 15, 15, 10, 8, 15, 3, 15, 10, 10, 8, 9, 10, 6, 15, 8, 15, 3, 6, 6, 8, 15, 3, 15, 15, 15,
+    15, 15, 15, 15, 15, 15, 15, 3, 15, 15, 8,
+];
+
+const WEIGHTS_2: [u32; 4] = [0, 21, 43, 64];
+const WEIGHTS_3: [u32; 8] = [0, 9, 18, 27, 37, 46, 55, 64];
+const WEIGHTS_4: [u32; 16] = [0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64];
+
+fn interpolate(a: u32, b: u32, index: u32, bits: usize) -> u8 {
+    let w = match bits {
+        2 => WEIGHTS_2[index as usize],
+        3 => WEIGHTS_3[index as usize],
+        _ => WEIGHTS_4[index as usize],
+    };
+    (((64 - w) * a + w * b + 32) >> 6) as u8
+}
+
+/// LSB-first reader over one 128-bit block.
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl BitReader<'_> {
+    fn read(&mut self, count: usize) -> u32 {
+        let mut value = 0;
+        for i in 0..count {
+            let bit = self.pos + i;
+            value |= (((self.data[bit >> 3] >> (bit & 7)) & 1) as u32) << i;
+        }
+        self.pos += count;
+        value
+    }
+}
+
+fn decode_bc7(block: &[u8]) -> [[u8; 4]; 16] {
+    let mode_index = block[0].trailing_zeros() as usize;
+    // Reserved mode 8 (first byte zero) decodes to transparent black.
+    let Some(mode) = BC7_MODES.get(mode_index) else {
+        return [[0; 4]; 16];
+    };
+    let mut bits = BitReader {
+        data: block,
+        pos: mode_index + 1,
+    };
+    let partition = bits.read(mode.partition_bits) as usize;
+    let rotation = bits.read(mode.rotation_bits);
+    let index_selection = bits.read(mode.index_selection_bits);
+
+    let endpoint_count = mode.subsets * 2;
+    let mut endpoints = [[0u32; 4]; 6];
+    for channel in 0..3 {
+        for endpoint in endpoints.iter_mut().take(endpoint_count) {
+            endpoint[channel] = bits.read(mode.color_bits as usize);
+        }
+    }
+    for endpoint in endpoints.iter_mut().take(endpoint_count) {
+        endpoint[3] = bits.read(mode.alpha_bits as usize);
+    }
+    let mut pbits = [0u32; 6];
+    if mode.endpoint_pbits {
+        for pbit in pbits.iter_mut().take(endpoint_count) {
+            *pbit = bits.read(1);
+        }
+    } else if mode.shared_pbits {
+        for subset in 0..mode.subsets {
+            let pbit = bits.read(1);
+            pbits[subset * 2] = pbit;
+            pbits[subset * 2 + 1] = pbit;
+        }
+    }
+    let has_pbits = mode.endpoint_pbits || mode.shared_pbits;
+    for (endpoint, pbit) in endpoints.iter_mut().zip(pbits).take(endpoint_count) {
+        for (channel, value) in endpoint.iter_mut().enumerate() {
+            let mut precision = if channel < 3 {
+                mode.color_bits
+            } else {
+                mode.alpha_bits
+            };
+            if precision == 0 {
+                *value = 255;
+                continue;
+            }
+            if has_pbits {
+                *value = (*value << 1) | pbit;
+                precision += 1;
+            }
+            *value <<= 8 - precision;
+            *value |= *value >> precision;
+        }
+    }
+
+    let subset_of = |i: usize| match mode.subsets {
+        1 => 0,
+        2 => (PARTITIONS_2[partition] >> i) as usize & 1,
+        _ => (PARTITIONS_3[partition] >> (2 * i)) as usize & 3,
+    };
+    let is_anchor = |i: usize| {
+        i == 0
+            || match mode.subsets {
+                2 => i == ANCHORS_2[partition] as usize,
+                3 => i == ANCHORS_3A[partition] as usize || i == ANCHORS_3B[partition] as usize,
+                _ => false,
+            }
+    };
+    let mut indices = [0u32; 16];
+    for (i, index) in indices.iter_mut().enumerate() {
+        *index = bits.read(mode.index_bits - is_anchor(i) as usize);
+    }
+    let mut indices2 = [0u32; 16];
+    if mode.index2_bits > 0 {
+        for (i, index) in indices2.iter_mut().enumerate() {
+            *index = bits.read(mode.index2_bits - (i == 0) as usize);
+        }
+    }
+
+    std::array::from_fn(|i| {
+        let subset = subset_of(i);
+        let (e0, e1) = (endpoints[subset * 2], endpoints[subset * 2 + 1]);
+        let ((ci, cbits), (ai, abits)) = if mode.index2_bits == 0 {
+            ((indices[i], mode.index_bits), (indices[i], mode.index_bits))
+        } else if index_selection == 0 {
+            (
+                (indices[i], mode.index_bits),
+                (indices2[i], mode.index2_bits),
+            )
+        } else {
+            (
+                (indices2[i], mode.index2_bits),
+                (indices[i], mode.index_bits),
+            )
+        };
+        let mut texel = [
+            interpolate(e0[0], e1[0], ci, cbits),
+            interpolate(e0[1], e1[1], ci, cbits),
+            interpolate(e0[2], e1[2], ci, cbits),
+            interpolate(e0[3], e1[3], ai, abits),
+        ];
+        match rotation {
+            1 => texel.swap(0, 3),
+            2 => texel.swap(1, 3),
+            3 => texel.swap(2, 3),
+            _ => {}
+        }
+        texel
+    })
+}
+
+// ============================================================================
+// EXPORT
+// ============================================================================
+
+/// Snapshot the visible canvas for DDS export.
+pub fn build_dds(state: &CanvasState, options: &DdsOptions) -> DdsData {
+    DdsData {
+        image: state.composite(),
+        options: options.clone(),
+    }
+}
+
+pub fn write_dds(data: &DdsData, path: &Path) -> std::io::Result<()> {
+    File::create(path)?.write_all(&encode_dds(&data.image, &data.options))
+}
+
+/// Encode `image` as a complete `.dds` file.
+pub fn encode_dds(image: &RgbaImage, options: &DdsOptions) -> Vec<u8> {
+    let levels = if options.mipmaps {
+        mip_chain(image)
+    } else {
+        vec![image.clone()]
+    };
+    let (width, height) = image.dimensions();
+    let compression = options.compression;
+
+    let mut flags = DDSD_CAPS | DDSD_HEIGHT | DDSD_WIDTH | DDSD_PIXELFORMAT;
+    let mut caps = DDSCAPS_TEXTURE;
+    if levels.len() > 1 {
+        flags |= DDSD_MIPMAPCOUNT;
+        caps |= DDSCAPS_COMPLEX | DDSCAPS_MIPMAP;
+    }
+    let pitch = match compression.block_len() {
+        Some(block_len) => {
+            flags |= DDSD_LINEARSIZE;
+            (width.div_ceil(4) * height.div_ceil(4)) as usize * block_len
+        }
+        None => {
+            flags |= DDSD_PITCH;
+            width as usize * 4
+        }
+    };
+
+    let mut header = [0u32; 31];
+    header[0] = 124;
+    header[1] = flags;
+    header[2] = height;
+    header[3] = width;
+    header[4] = pitch as u32;
+    header[6] = levels.len() as u32;
+    // Pixel format
+    header[18] = 32;
+    match compression {
+        DdsCompression::None => {
+            header[19] = DDPF_RGB | DDPF_ALPHAPIXELS;
+            header[21] = 32;
+            header[22] = 0x00ff0000;
+            header[23] = 0x0000ff00;
+            header[24] = 0x000000ff;
+            header[25] = 0xff000000;
+        }
+        DdsCompression::Bc1 | DdsCompression::Bc3 | DdsCompression::Bc7 => {
+            header[19] = DDPF_FOURCC;
+            header[20] = u32::from_le_bytes(match compression {
+                DdsCompression::Bc1 => *b"DXT1",
+                DdsCompression::Bc3 => *b"DXT5",
+                _ => *b"DX10",
+            });
+        }
+    }
+    header[26] = caps;
+
+    let mut out = Vec::with_capacity(HEADER_LEN + DX10_HEADER_LEN + pitch * 2);
+    out.extend_from_slice(MAGIC);
+    for value in header {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    if compression == DdsCompression::Bc7 {
+        // DXGI format, 2D texture, no flags, array size 1, straight alpha
+        for value in [DXGI_BC7_UNORM, 3, 0, 1, 0] {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    for level in &levels {
+        match compression {
+            DdsCompression::None => {
+                for px in level.pixels() {
+                    out.extend_from_slice(&[px[2], px[1], px[0], px[3]]);
+                }
+            }
+            DdsCompression::Bc1 => encode_blocks(level, &mut out, encode_bc1),
+            DdsCompression::Bc3 => encode_blocks(level, &mut out, encode_bc3),
+            DdsCompression::Bc7 => encode_blocks(level, &mut out, encode_bc7),
+        }
+    }
+    out
+}
+
+/// `image` followed by successively halved levels down to 1×1.
+fn mip_chain(image: &RgbaImage) -> Vec<RgbaImage> {
+    let mut levels = vec![image.clone()];
+    loop {
+        let last = &levels[levels.len() - 1];
+        let (w, h) = last.dimensions();
+        if w <= 1 && h <= 1 {
+            break levels;
+        }
+        let next =
+            image::imageops::resize(last, (w / 2).max(1), (h / 2).max(1), FilterType::Triangle);
+        levels.push(next);
+    }
+}
+
+fn encode_blocks(image: &RgbaImage, out: &mut Vec<u8>, encode: fn(&[[u8; 4]; 16], &mut Vec<u8>)) {
+    let (width, height) = image.dimensions();
+    for by in (0..height).step_by(4) {
+        for bx in (0..width).step_by(4) {
+            // Edge blocks repeat the last row/column.
+            let texels = std::array::from_fn(|i| {
+                let x = (bx + i as u32 % 4).min(width - 1);
+                let y = (by + i as u32 / 4).min(height - 1);
+                image.get_pixel(x, y).0
+            });
+            encode(&texels, out);
+        }
+    }
+}
+
+/// The two points of `points` furthest apart along their principal axis.
+fn principal_extremes<const N: usize>(points: &[[f32; N]]) -> ([f32; N], [f32; N]) {
+    let count = points.len() as f32;
+    let mut mean = [0.0; N];
+    for p in points {
+        for c in 0..N {
+            mean[c] += p[c] / count;
+        }
+    }
+    let mut cov = [[0.0f32; N]; N];
+    for p in points {
+        for i in 0..N {
+            for j in 0..N {
+                cov[i][j] += (p[i] - mean[i]) * (p[j] - mean[j]);
+            }
+        }
+    }
+    let mut axis = [1.0f32; N];
+    for _ in 0..8 {
+        let mut next = [0.0f32; N];
+        for i in 0..N {
+            for j in 0..N {
+                next[i] += cov[i][j] * axis[j];
+            }
+        }
+        let len = next.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if len < 1e-6 {
+            break;
+        }
+        axis = next.map(|v| v / len);
+    }
+    let project = |p: &[f32; N]| (0..N).map(|c| (p[c] - mean[c]) * axis[c]).sum::<f32>();
+    let mut lo = points[0];
+    let mut hi = points[0];
+    for p in points {
+        if project(p) < project(&lo) {
+            lo = *p;
+        }
+        if project(p) > project(&hi) {
+            hi = *p;
+        }
+    }
+    (lo, hi)
+}
+
+fn nearest<const N: usize>(palette: &[[u8; 4]], texel: &[u8; 4]) -> u32 {
+    let distance = |c: &[u8; 4]| {
+        (0..N)
+            .map(|i| (c[i] as i32 - texel[i] as i32).pow(2))
+            .sum::<i32>()
+    };
+    (0..palette.len())
+        .min_by_key(|&i| distance(&palette[i]))
+        .unwrap_or(0) as u32
+}
+
+fn pack565(c: [f32; 3]) -> u16 {
+    let q = |v: f32, max: f32| (v * max / 255.0).round().clamp(0.0, max) as u16;
+    (q(c[0], 31.0) << 11) | (q(c[1], 63.0) << 5) | q(c[2], 31.0)
+}
+
+/// BC1 colour block. With `punch_through`, texels under half alpha use the
+/// transparent entry of the three-colour mode.
+fn encode_color_block(texels: &[[u8; 4]; 16], punch_through: bool, out: &mut Vec<u8>) {
+    let transparent = punch_through && texels.iter().any(|t| t[3] < 128);
+    let colors: Vec<[f32; 3]> = texels
+        .iter()
+        .filter(|t| !transparent || t[3] >= 128)
+        .map(|t| [t[0] as f32, t[1] as f32, t[2] as f32])
+        .collect();
+    if colors.is_empty() {
+        out.extend_from_slice(&[0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]);
+        return;
+    }
+    let (lo, hi) = principal_extremes(&colors);
+    let (mut c0, mut c1) = (pack565(hi), pack565(lo));
+    // c0 > c1 selects four colours; c0 <= c1 the mode with transparency.
+    if (transparent && c0 > c1) || (!transparent && c0 < c1) {
+        std::mem::swap(&mut c0, &mut c1);
+    }
+    let palette = bc1_palette(c0, c1, false);
+    let usable = if c0 > c1 { 4 } else { 3 };
+    let mut indices = 0u32;
+    for (i, texel) in texels.iter().enumerate() {
+        let index = if transparent && texel[3] < 128 {
+            3
+        } else {
+            nearest::<3>(&palette[..usable], texel)
+        };
+        indices |= index << (2 * i);
+    }
+    out.extend_from_slice(&c0.to_le_bytes());
+    out.extend_from_slice(&c1.to_le_bytes());
+    out.extend_from_slice(&indices.to_le_bytes());
+}
+
+fn encode_bc1(texels: &[[u8; 4]; 16], out: &mut Vec<u8>) {
+    encode_color_block(texels, true, out);
+}
+
+fn encode_bc3(texels: &[[u8; 4]; 16], out: &mut Vec<u8>) {
+    let min = texels.iter().map(|t| t[3]).min().unwrap_or(255);
+    let max = texels.iter().map(|t| t[3]).max().unwrap_or(255);
+    let palette = alpha_palette(max, min);
+    let mut indices = 0u64;
+    for (i, texel) in texels.iter().enumerate() {
+        let index = (0..8)
+            .min_by_key(|&j| (palette[j] as i32 - texel[3] as i32).abs())
+            .unwrap_or(0);
+        indices |= (index as u64) << (3 * i);
+    }
+    out.extend_from_slice(&[max, min]);
+    out.extend_from_slice(&indices.to_le_bytes()[..6]);
+    encode_color_block(texels, false, out);
+}
+
+/// LSB-first writer for one 128-bit block.
+#[derive(Default)]
+struct BitWriter {
+    data: [u8; 16],
+    pos: usize,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, count: usize) {
+        for i in 0..count {
+            let bit = self.pos + i;
+            self.data[bit >> 3] |= (((value >> i) & 1) as u8) << (bit & 7);
+        }
+        self.pos += count;
+    }
+}
+
+/// Nearest 7-bit RGBA endpoint plus p-bit to `value`.
+fn quantize_bc7(value: [f32; 4]) -> ([u32; 4], u32) {
+    let candidate = |pbit: u32| {
+        let e = value.map(|v| ((v - pbit as f32) / 2.0).round().clamp(0.0, 127.0) as u32);
+        let error: f32 = (0..4)
+            .map(|c| ((e[c] << 1 | pbit) as f32 - value[c]).powi(2))
+            .sum();
+        (e, error)
+    };
+    let (e0, err0) = candidate(0);
+    let (e1, err1) = candidate(1);
+    if err1 < err0 { (e1, 1) } else { (e0, 0) }
+}
+
+/// BC7 mode 6: one subset, 7-bit RGBA endpoints with p-bits, 4-bit indices.
+fn encode_bc7(texels: &[[u8; 4]; 16], out: &mut Vec<u8>) {
+    let points: Vec<[f32; 4]> = texels.iter().map(|t| t.map(|v| v as f32)).collect();
+    let (lo, hi) = principal_extremes(&points);
+    let (mut a, mut pa) = quantize_bc7(lo);
+    let (mut b, mut pb) = quantize_bc7(hi);
+    let expand = |e: [u32; 4], p: u32| e.map(|v| v << 1 | p);
+    let (ea, eb) = (expand(a, pa), expand(b, pb));
+    let palette: Vec<[u8; 4]> = (0..16)
+        .map(|i| std::array::from_fn(|c| interpolate(ea[c], eb[c], i, 4)))
+        .collect();
+    let mut indices = texels.map(|t| nearest::<4>(&palette, &t));
+    // The anchor index is stored without its top bit, so it must be < 8.
+    if indices[0] >= 8 {
+        std::mem::swap(&mut a, &mut b);
+        std::mem::swap(&mut pa, &mut pb);
+        indices = indices.map(|i| 15 - i);
+    }
+
+    let mut bits = BitWriter::default();
+    bits.write(1 << 6, 7);
+    for c in 0..4 {
+        bits.write(a[c], 7);
+        bits.write(b[c], 7);
+    }
+    bits.write(pa, 1);
+    bits.write(pb, 1);
+    bits.write(indices[0], 3);
+    for &index in &indices[1..] {
+        bits.write(index, 4);
+    }
+    out.extend_from_slice(&bits.data);
+}
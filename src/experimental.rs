@@ -1,4 +1,4 @@
-use crate::canvas::PixelFormat;
+use crate::canvas::{PixelFormat, linear_to_srgb8, srgb8_to_linear};
 use image::{Rgba, RgbaImage};
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -19,6 +19,7 @@ impl DeepRgbaBuffer {
         }
     }
 
+    /// Float payloads are display-referred: samples are `value / 255`.
     pub fn from_rgba8(image: &RgbaImage, format: PixelFormat) -> Self {
         match format {
             PixelFormat::RgbaU8 => DeepRgbaBuffer::U8(image.as_raw().clone()),
@@ -68,6 +69,140 @@ pub fn reinhard_tone_map_rgba(pixel: [f32; 4], exposure: f32) -> Rgba<u8> {
     ])
 }
 
+/// Curve that brings linear float values into the 8-bit preview/export range.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HdrToneMap {
+    /// Scale by exposure and clamp to [0, 1].
+    #[default]
+    Clip,
+    /// Reinhard `x / (1 + x)` after exposure.
+    Reinhard,
+}
+
+impl HdrToneMap {
+    pub fn label(self) -> String {
+        match self {
+            HdrToneMap::Clip => t!("hdr.tone_map.clip"),
+            HdrToneMap::Reinhard => t!("hdr.tone_map.reinhard"),
+        }
+    }
+}
+
+/// What a float payload's samples mean.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HdrTransfer {
+    /// Display values stored as floats: `1.0` is 8-bit `255`, no curve.
+    #[default]
+    Display,
+    /// Scene-linear light (OpenEXR, Radiance HDR); sRGB-encoded for display.
+    SceneLinear,
+}
+
+/// How a float layer is viewed: exposure in stops plus a tone-map curve.
+/// Drives the 8-bit preview pixels and 8-bit exports of HDR data; the float
+/// payload itself is never changed by it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HdrView {
+    pub exposure: f32,
+    pub tone_map: HdrToneMap,
+    /// Transfer of the payload being viewed. Comes from the layer's
+    /// [`HdrMetadata`](crate::canvas::HdrMetadata), not from the user.
+    pub transfer: HdrTransfer,
+}
+
+impl Default for HdrView {
+    fn default() -> Self {
+        Self {
+            exposure: 0.0,
+            tone_map: HdrToneMap::Clip,
+            transfer: HdrTransfer::Display,
+        }
+    }
+}
+
+impl HdrView {
+    fn gain(&self) -> f32 {
+        self.exposure.exp2()
+    }
+
+    /// Map a float RGBA value to display RGBA8.
+    pub fn map(&self, pixel: [f32; 4]) -> Rgba<u8> {
+        let gain = self.gain();
+        let map = |v: f32| {
+            let x = (v * gain).max(0.0);
+            let x = match self.tone_map {
+                HdrToneMap::Clip => x,
+                HdrToneMap::Reinhard => x / (1.0 + x),
+            };
+            match self.transfer {
+                HdrTransfer::Display => (x.min(1.0) * 255.0).round() as u8,
+                HdrTransfer::SceneLinear => linear_to_srgb8(x),
+            }
+        };
+        Rgba([
+            map(pixel[0]),
+            map(pixel[1]),
+            map(pixel[2]),
+            (pixel[3].clamp(0.0, 1.0) * 255.0).round() as u8,
+        ])
+    }
+
+    /// Inverse of [`Self::map`], used when an edited preview pixel is written
+    /// back to the float payload.
+    pub fn unmap(&self, pixel: Rgba<u8>) -> [f32; 4] {
+        let gain = self.gain().max(f32::MIN_POSITIVE);
+        let decode = |v: u8| match self.transfer {
+            HdrTransfer::Display => v as f32 / 255.0,
+            HdrTransfer::SceneLinear => srgb8_to_linear(v),
+        };
+        let unmap = |v: u8| {
+            let x = decode(v);
+            match self.tone_map {
+                HdrToneMap::Clip => x / gain,
+                // Full white would be infinite; cap it half-way between the
+                // two brightest steps.
+                HdrToneMap::Reinhard => {
+                    let x = x.min((decode(254) + 1.0) * 0.5);
+                    x / (1.0 - x) / gain
+                }
+            }
+        };
+        [
+            unmap(pixel[0]),
+            unmap(pixel[1]),
+            unmap(pixel[2]),
+            pixel[3] as f32 / 255.0,
+        ]
+    }
+
+    /// Preview RGBA8 for a deep payload. Integer payloads ignore the view.
+    pub fn preview(&self, deep: &DeepRgbaBuffer, width: u32, height: u32) -> Option<RgbaImage> {
+        let mut data = Vec::new();
+        match deep {
+            DeepRgbaBuffer::F16(values) => {
+                data.reserve(values.len());
+                for px in values.chunks_exact(4) {
+                    let pixel = [
+                        f16_bits_to_f32(px[0]),
+                        f16_bits_to_f32(px[1]),
+                        f16_bits_to_f32(px[2]),
+                        f16_bits_to_f32(px[3]),
+                    ];
+                    data.extend_from_slice(&self.map(pixel).0);
+                }
+            }
+            DeepRgbaBuffer::F32(values) => {
+                data.reserve(values.len());
+                for px in values.chunks_exact(4) {
+                    data.extend_from_slice(&self.map([px[0], px[1], px[2], px[3]]).0);
+                }
+            }
+            _ => return deep.to_rgba8(width, height),
+        }
+        RgbaImage::from_raw(width, height, data)
+    }
+}
+
 pub fn f32_to_f16_bits(value: f32) -> u16 {
     let bits = value.to_bits();
     let sign = ((bits >> 16) & 0x8000) as u16;
@@ -54,18 +54,32 @@ impl ViewUniforms {
     }
 }
 
-/// Uniforms for the uber-compositor: includes blend_mode.
+/// Blend-if ranges for one layer: `(black_lo, black_hi, white_lo, white_hi)`
+/// on the 0–1 scale for gray/R/G/B of this layer, then of the underlying
+/// composite. See `BlendIf::gpu_ranges`.
+pub type BlendIfRanges = [[f32; 4]; 8];
+
+/// Uniforms for the uber-compositor: includes blend_mode and blend-if ranges.
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
 pub struct BlendUniforms {
     pub view_proj: [[f32; 4]; 4],
     pub opacity: f32,
     pub blend_mode: u32,
-    pub _pad: [f32; 2],
+    /// Non-zero when `blend_if` should be evaluated.
+    pub blend_if_enabled: u32,
+    /// Non-zero to blend in linear light.
+    pub linear_light: u32,
+    pub blend_if: BlendIfRanges,
 }
 
 impl BlendUniforms {
-    pub fn identity(opacity: f32, blend_mode: u32) -> Self {
+    pub fn identity(
+        opacity: f32,
+        blend_mode: u32,
+        blend_if: Option<BlendIfRanges>,
+        linear: bool,
+    ) -> Self {
         let view_proj: [[f32; 4]; 4] = [
             [2.0, 0.0, 0.0, 0.0],
             [0.0, -2.0, 0.0, 0.0],
@@ -76,7 +90,9 @@ impl BlendUniforms {
             view_proj,
             opacity,
             blend_mode,
-            _pad: [0.0; 2],
+            blend_if_enabled: blend_if.is_some() as u32,
+            linear_light: linear as u32,
+            blend_if: blend_if.unwrap_or_default(),
         }
     }
 }
@@ -256,7 +272,7 @@ impl Compositor {
             source: wgpu::ShaderSource::Wgsl(super::shaders::UBER_COMPOSITE_SHADER.into()),
         });
 
-        // Group 0: BlendUniforms (view_proj, opacity, blend_mode)
+        // Group 0: BlendUniforms (view_proj, opacity, blend_mode, blend_if)
         let blend_uniform_bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("blend_uniform_bgl"),
             entries: &[wgpu::BindGroupLayoutEntry {
@@ -474,7 +490,10 @@ impl Compositor {
     ///   - `pong`: destination (write)
     ///   - After each layer, swap ping ↔ pong.
     ///
-    /// `layers`: `(opacity, blend_mode_u8, &LayerTexture)` in back-to-front order.
+    /// `layers`: `(opacity, blend_mode_u8, blend_if, &TextureView)` in
+    /// back-to-front order — a layer texture or an already composited group.
+    /// With `linear` the shader blends in linear light; the accumulator stays
+    /// premultiplied sRGB either way.
     ///
     /// Returns which of the two ping-pong textures holds the final result
     /// (0 or 1) so the caller knows which to read back.
@@ -482,7 +501,8 @@ impl Compositor {
         &mut self,
         ctx: &GpuContext,
         ping_pong: [&wgpu::TextureView; 2],
-        layers: &[(f32, u32, &LayerTexture)],
+        layers: &[(f32, u32, Option<BlendIfRanges>, &wgpu::TextureView)],
+        linear: bool,
         _width: u32,
         _height: u32,
     ) -> usize {
@@ -517,9 +537,9 @@ impl Compositor {
         let mut read_idx: usize = 0; // ping = background (read)
         let mut write_idx: usize = 1; // pong = destination (write)
 
-        for (layer_i, (opacity, blend_mode, layer_tex)) in layers.iter().enumerate() {
+        for (layer_i, (opacity, blend_mode, blend_if, layer_view)) in layers.iter().enumerate() {
             // ---- Uniforms: reuse cached buffer + bind group ----
-            let uniforms = BlendUniforms::identity(*opacity, *blend_mode);
+            let uniforms = BlendUniforms::identity(*opacity, *blend_mode, *blend_if, linear);
             if layer_i >= self.cached_blend_slots.len() {
                 // First time seeing this many layers — allocate new slot
                 let buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -553,7 +573,7 @@ impl Compositor {
                 entries: &[
                     wgpu::BindGroupEntry {
                         binding: 0,
-                        resource: wgpu::BindingResource::TextureView(&layer_tex.view),
+                        resource: wgpu::BindingResource::TextureView(layer_view),
                     },
                     wgpu::BindGroupEntry {
                         binding: 1,
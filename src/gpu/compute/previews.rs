@@ -84,10 +84,10 @@ pub struct GradientGpuParams {
     pub end_y: f32,
     pub width: u32,
     pub height: u32,
-    pub shape: u32,     // 0=Linear, 1=LinearReflected, 2=Radial, 3=Diamond
+    pub shape: u32,     // 0=Linear, 1=LinearReflected, 2=Radial, 3=Diamond, 4=Conic, 5=Spiral
     pub repeat: u32,    // 0=clamp, 1=repeat
     pub is_eraser: u32, // 0=color, 1=transparency/eraser
-    pub _pad0: u32,
+    pub dither: u32,    // 0=nearest LUT entry, 1=interpolate + ordered dither
     pub _pad1: u32,
     pub _pad2: u32,
 }
@@ -5,7 +5,7 @@
 use egui;
 use std::collections::HashMap;
 
-use super::compositor::Compositor;
+use super::compositor::{BlendIfRanges, Compositor};
 use super::context::GpuContext;
 
 // ============================================================================
@@ -230,6 +230,9 @@ pub struct GpuRenderer {
     output_texture: Option<wgpu::Texture>,
     /// Ping-pong pair for uber-compositor.
     ping_pong: [Option<wgpu::Texture>; 2],
+    /// Scratch textures holding composited isolated layer groups.
+    group_textures: Vec<wgpu::Texture>,
+    group_tex_size: (u32, u32),
     pp_width: u32,
     pp_height: u32,
     output_width: u32,
@@ -239,6 +242,8 @@ pub struct GpuRenderer {
     /// Double-buffered async readback for stall-free compositing
     pub async_readback: AsyncReadback,
     pub available: bool,
+    /// Blend in linear light; mirrors `CanvasState::linear_blending`.
+    linear_blending: bool,
 }
 
 impl GpuRenderer {
@@ -303,6 +308,8 @@ impl GpuRenderer {
             layer_textures: HashMap::new(),
             output_texture: None,
             ping_pong: [None, None],
+            group_textures: Vec::new(),
+            group_tex_size: (0, 0),
             pp_width: 0,
             pp_height: 0,
             output_width: 0,
@@ -310,6 +317,7 @@ impl GpuRenderer {
             cached_staging_buf: None,
             async_readback: AsyncReadback::new(),
             available: true,
+            linear_blending: false,
         }
     }
 
@@ -496,6 +504,11 @@ impl GpuRenderer {
     // PING-PONG TEXTURE MANAGEMENT
     // ========================================================================
 
+    /// Blend layers in linear light (`CanvasState::linear_blending`).
+    pub fn set_linear_blending(&mut self, linear: bool) {
+        self.linear_blending = linear;
+    }
+
     fn ensure_ping_pong(&mut self, w: u32, h: u32) {
         if self.pp_width == w && self.pp_height == h && self.ping_pong[0].is_some() {
             return;
@@ -523,48 +536,143 @@ impl GpuRenderer {
         self.pp_height = h;
     }
 
+    fn ensure_group_textures(&mut self, count: usize, w: u32, h: u32) {
+        if self.group_tex_size != (w, h) {
+            self.group_textures.clear();
+            self.group_tex_size = (w, h);
+        }
+        while self.group_textures.len() < count {
+            self.group_textures
+                .push(self.ctx.device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some("layer_group"),
+                    size: wgpu::Extent3d {
+                        width: w,
+                        height: h,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: self.compositor.output_format,
+                    usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                    view_formats: &[],
+                }));
+        }
+    }
+
     // ========================================================================
     // COMPOSITION (with blend modes)
     // ========================================================================
 
-    /// Composite all visible layers with full blend-mode support.
-    ///
-    /// `layer_info`: `(layer_idx, opacity, visible, blend_mode_u8)`.
-    pub fn composite(
+    /// Run the uber-compositor over `layer_info` into the ping-pong pair and
+    /// return the index holding the result. Each isolated group is composited
+    /// on its own first (innermost first) and copied to a group texture,
+    /// which then stands in for the group's layers in the enclosing pass.
+    fn composite_blended(
         &mut self,
         canvas_w: u32,
         canvas_h: u32,
-        layer_info: &[(usize, f32, bool, u8)],
-    ) -> Option<Vec<u8>> {
-        if !self.available {
-            return None;
-        }
-
+        layer_info: &[(usize, f32, bool, u8, Option<BlendIfRanges>)],
+        groups: &[(usize, usize, f32, u8)],
+    ) -> usize {
         self.ensure_ping_pong(canvas_w, canvas_h);
+        self.ensure_group_textures(groups.len(), canvas_w, canvas_h);
 
         let pp0 = self.ping_pong[0].as_ref().unwrap();
         let pp1 = self.ping_pong[1].as_ref().unwrap();
         let view0 = pp0.create_view(&wgpu::TextureViewDescriptor::default());
         let view1 = pp1.create_view(&wgpu::TextureViewDescriptor::default());
+        let group_views: Vec<wgpu::TextureView> = self
+            .group_textures
+            .iter()
+            .map(|t| t.create_view(&wgpu::TextureViewDescriptor::default()))
+            .collect();
 
-        // Collect visible layers.
-        let mut visible_layers: Vec<(f32, u32, &LayerTexture)> = Vec::new();
-        for &(idx, opacity, visible, blend_mode) in layer_info.iter() {
-            if !visible {
-                continue;
-            }
-            if let Some(state) = self.layer_textures.get(&idx) {
-                visible_layers.push((opacity, blend_mode as u32, &state.texture));
+        // Entries for `range`, with already composited groups (`done`) that
+        // start inside it standing in for their layers.
+        let collect = |range: std::ops::Range<usize>, done: usize| {
+            let mut entries: Vec<(f32, u32, Option<BlendIfRanges>, &wgpu::TextureView)> =
+                Vec::new();
+            let mut i = range.start;
+            while i < range.end {
+                // The last finished group starting here is the outermost one.
+                let child = groups[..done]
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .find(|(_, g)| g.0 == i && g.1 <= range.end);
+                if let Some((gi, &(_, end, opacity, blend_mode))) = child {
+                    entries.push((opacity, blend_mode as u32, None, &group_views[gi]));
+                    i = end;
+                    continue;
+                }
+                if let Some(&(idx, opacity, visible, blend_mode, blend_if)) = layer_info.get(i)
+                    && visible
+                    && let Some(state) = self.layer_textures.get(&idx)
+                {
+                    entries.push((opacity, blend_mode as u32, blend_if, &state.texture.view));
+                }
+                i += 1;
             }
+            entries
+        };
+
+        for (gi, &(start, end, _, _)) in groups.iter().enumerate() {
+            let entries = collect(start..end.min(layer_info.len()), gi);
+            let result_idx = self.compositor.composite_layers_blended(
+                &self.ctx,
+                [&view0, &view1],
+                &entries,
+                self.linear_blending,
+                canvas_w,
+                canvas_h,
+            );
+            let mut encoder =
+                self.ctx
+                    .device
+                    .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                        label: Some("layer_group_copy"),
+                    });
+            encoder.copy_texture_to_texture(
+                self.ping_pong[result_idx].as_ref().unwrap().as_image_copy(),
+                self.group_textures[gi].as_image_copy(),
+                wgpu::Extent3d {
+                    width: canvas_w,
+                    height: canvas_h,
+                    depth_or_array_layers: 1,
+                },
+            );
+            self.ctx.queue.submit(std::iter::once(encoder.finish()));
         }
 
-        let result_idx = self.compositor.composite_layers_blended(
+        let entries = collect(0..layer_info.len(), groups.len());
+        self.compositor.composite_layers_blended(
             &self.ctx,
             [&view0, &view1],
-            &visible_layers,
+            &entries,
+            self.linear_blending,
             canvas_w,
             canvas_h,
-        );
+        )
+    }
+
+    /// Composite all visible layers with full blend-mode support.
+    ///
+    /// `layer_info`: `(layer_idx, opacity, visible, blend_mode_u8, blend_if)`.
+    /// `groups`: isolated groups as `(first_layer, end_layer, opacity,
+    /// blend_mode_u8)` spans over `layer_info`, innermost first.
+    pub fn composite(
+        &mut self,
+        canvas_w: u32,
+        canvas_h: u32,
+        layer_info: &[(usize, f32, bool, u8, Option<BlendIfRanges>)],
+        groups: &[(usize, usize, f32, u8)],
+    ) -> Option<Vec<u8>> {
+        if !self.available {
+            return None;
+        }
+
+        let result_idx = self.composite_blended(canvas_w, canvas_h, layer_info, groups);
 
         // Read back from whichever texture holds the result.
         let result_tex = if result_idx == 0 {
@@ -589,37 +697,15 @@ impl GpuRenderer {
         &mut self,
         canvas_w: u32,
         canvas_h: u32,
-        layer_info: &[(usize, f32, bool, u8)],
+        layer_info: &[(usize, f32, bool, u8, Option<BlendIfRanges>)],
+        groups: &[(usize, usize, f32, u8)],
         dirty_rect: Option<egui::Rect>,
     ) -> Option<(Vec<u8>, u32, u32, u32, u32, bool)> {
         if !self.available {
             return None;
         }
 
-        self.ensure_ping_pong(canvas_w, canvas_h);
-
-        let pp0 = self.ping_pong[0].as_ref().unwrap();
-        let pp1 = self.ping_pong[1].as_ref().unwrap();
-        let view0 = pp0.create_view(&wgpu::TextureViewDescriptor::default());
-        let view1 = pp1.create_view(&wgpu::TextureViewDescriptor::default());
-
-        let mut visible_layers: Vec<(f32, u32, &LayerTexture)> = Vec::new();
-        for &(idx, opacity, visible, blend_mode) in layer_info.iter() {
-            if !visible {
-                continue;
-            }
-            if let Some(state) = self.layer_textures.get(&idx) {
-                visible_layers.push((opacity, blend_mode as u32, &state.texture));
-            }
-        }
-
-        let result_idx = self.compositor.composite_layers_blended(
-            &self.ctx,
-            [&view0, &view1],
-            &visible_layers,
-            canvas_w,
-            canvas_h,
-        );
+        let result_idx = self.composite_blended(canvas_w, canvas_h, layer_info, groups);
 
         let result_tex = if result_idx == 0 {
             self.ping_pong[0].as_ref().unwrap()
@@ -679,7 +765,8 @@ impl GpuRenderer {
         &mut self,
         canvas_w: u32,
         canvas_h: u32,
-        layer_info: &[(usize, f32, bool, u8)],
+        layer_info: &[(usize, f32, bool, u8, Option<BlendIfRanges>)],
+        groups: &[(usize, usize, f32, u8)],
         dirty_rect: Option<egui::Rect>,
     ) -> Option<(Vec<u8>, u32, u32, u32, u32, bool)> {
         if !self.available {
@@ -690,30 +777,7 @@ impl GpuRenderer {
         let previous_data = self.async_readback.try_read(&self.ctx.device);
 
         // --- Step 2: Do GPU composite (same as sync path) ---
-        self.ensure_ping_pong(canvas_w, canvas_h);
-
-        let pp0 = self.ping_pong[0].as_ref().unwrap();
-        let pp1 = self.ping_pong[1].as_ref().unwrap();
-        let view0 = pp0.create_view(&wgpu::TextureViewDescriptor::default());
-        let view1 = pp1.create_view(&wgpu::TextureViewDescriptor::default());
-
-        let mut visible_layers: Vec<(f32, u32, &LayerTexture)> = Vec::new();
-        for &(idx, opacity, visible, blend_mode) in layer_info.iter() {
-            if !visible {
-                continue;
-            }
-            if let Some(state) = self.layer_textures.get(&idx) {
-                visible_layers.push((opacity, blend_mode as u32, &state.texture));
-            }
-        }
-
-        let result_idx = self.compositor.composite_layers_blended(
-            &self.ctx,
-            [&view0, &view1],
-            &visible_layers,
-            canvas_w,
-            canvas_h,
-        );
+        let result_idx = self.composite_blended(canvas_w, canvas_h, layer_info, groups);
 
         let result_tex = if result_idx == 0 {
             self.ping_pong[0].as_ref().unwrap()
@@ -806,19 +870,22 @@ impl GpuRenderer {
         &mut self,
         canvas_w: u32,
         canvas_h: u32,
-        layer_info: &[(usize, f32, bool, u8)],
+        layer_info: &[(usize, f32, bool, u8, Option<BlendIfRanges>)],
+        groups: &[(usize, usize, f32, u8)],
         use_linear_filter: bool,
     ) -> Option<(wgpu::TextureView, u64)> {
         if !self.available {
             return None;
         }
 
-        let all_normal = layer_info
-            .iter()
-            .filter(|(_, _, visible, _)| *visible)
-            .all(|(_, _, _, blend_mode)| *blend_mode == 0);
+        // The plain source-over pass blends stored sRGB values directly.
+        let all_normal = !self.linear_blending
+            && layer_info
+                .iter()
+                .filter(|(_, _, visible, _, _)| *visible)
+                .all(|(_, _, _, blend_mode, blend_if)| *blend_mode == 0 && blend_if.is_none());
 
-        if all_normal {
+        if all_normal && groups.is_empty() {
             if self.output_texture.is_none()
                 || self.output_width != canvas_w
                 || self.output_height != canvas_h
@@ -847,7 +914,7 @@ impl GpuRenderer {
             let output_view = output.create_view(&wgpu::TextureViewDescriptor::default());
             let normal_layers: Vec<(f32, &LayerTexture)> = layer_info
                 .iter()
-                .filter_map(|(idx, opacity, visible, _)| {
+                .filter_map(|(idx, opacity, visible, _, _)| {
                     visible
                         .then(|| self.layer_textures.get(idx).map(|s| (*opacity, &s.texture)))
                         .flatten()
@@ -859,31 +926,7 @@ impl GpuRenderer {
             return Some((output_view, key));
         }
 
-        self.ensure_ping_pong(canvas_w, canvas_h);
-
-        let pp0 = self.ping_pong[0].as_ref().unwrap();
-        let pp1 = self.ping_pong[1].as_ref().unwrap();
-        let view0 = pp0.create_view(&wgpu::TextureViewDescriptor::default());
-        let view1 = pp1.create_view(&wgpu::TextureViewDescriptor::default());
-
-        // Collect visible layers.
-        let mut visible_layers: Vec<(f32, u32, &LayerTexture)> = Vec::new();
-        for &(idx, opacity, visible, blend_mode) in layer_info.iter() {
-            if !visible {
-                continue;
-            }
-            if let Some(state) = self.layer_textures.get(&idx) {
-                visible_layers.push((opacity, blend_mode as u32, &state.texture));
-            }
-        }
-
-        let result_idx = self.compositor.composite_layers_blended(
-            &self.ctx,
-            [&view0, &view1],
-            &visible_layers,
-            canvas_w,
-            canvas_h,
-        );
+        let result_idx = self.composite_blended(canvas_w, canvas_h, layer_info, groups);
 
         // Return the view for the result texture
         let result_tex = if result_idx == 0 {
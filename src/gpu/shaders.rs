@@ -133,7 +133,9 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
 //  12 = Darken,  13 = Xor,      14 = Overwrite,
 //  15 = HardLight, 16 = SoftLight, 17 = Exclusion,
 //  18 = Subtract, 19 = Divide, 20 = LinearBurn,
-//  21 = VividLight, 22 = LinearLight, 23 = PinLight, 24 = HardMix
+//  21 = VividLight, 22 = LinearLight, 23 = PinLight, 24 = HardMix,
+//  25 = Hue, 26 = Saturation, 27 = Color, 28 = Luminosity,
+//  29 = DarkerColor, 30 = LighterColor, 31 = Dissolve
 // ============================================================================
 
 pub const UBER_COMPOSITE_SHADER: &str = r#"
@@ -141,8 +143,12 @@ struct BlendUniforms {
     view_proj: mat4x4<f32>,
     opacity:    f32,
     blend_mode: u32,
-    _pad0:      f32,
-    _pad1:      f32,
+    blend_if_enabled: u32,
+    // Non-zero: blend in linear light (CanvasState::linear_blending).
+    linear_light: u32,
+    // Blend-if ranges (black lo, black hi, white lo, white hi) for gray, R,
+    // G, B of this layer (0..3), then of the underlying composite (4..7).
+    blend_if: array<vec4<f32>, 8>,
 };
 
 @group(0) @binding(0) var<uniform> u: BlendUniforms;
@@ -242,13 +248,113 @@ fn pin_light_ch(base: f32, top: f32) -> f32 {
     }
 }
 
+// ---- Non-separable (HSL) blend helpers, W3C compositing spec ----
+
+fn lum(c: vec3<f32>) -> f32 {
+    return 0.3 * c.r + 0.59 * c.g + 0.11 * c.b;
+}
+
+fn clip_color(c_in: vec3<f32>) -> vec3<f32> {
+    let l = lum(c_in);
+    let n = min(min(c_in.r, c_in.g), c_in.b);
+    let x = max(max(c_in.r, c_in.g), c_in.b);
+    var c = c_in;
+    if (n < 0.0) {
+        c = vec3<f32>(l) + (c - vec3<f32>(l)) * l / (l - n);
+    }
+    if (x > 1.0) {
+        c = vec3<f32>(l) + (c - vec3<f32>(l)) * (1.0 - l) / (x - l);
+    }
+    return c;
+}
+
+fn set_lum(c: vec3<f32>, l: f32) -> vec3<f32> {
+    return clip_color(c + vec3<f32>(l - lum(c)));
+}
+
+fn sat(c: vec3<f32>) -> f32 {
+    return max(max(c.r, c.g), c.b) - min(min(c.r, c.g), c.b);
+}
+
+fn set_sat(c: vec3<f32>, s: f32) -> vec3<f32> {
+    let mn = min(min(c.r, c.g), c.b);
+    let mx = max(max(c.r, c.g), c.b);
+    if (mx > mn) {
+        return (c - vec3<f32>(mn)) * s / (mx - mn);
+    }
+    return vec3<f32>(0.0);
+}
+
+// Per-pixel noise in 0..255 for Dissolve (mirrors CanvasState::dissolve_threshold).
+fn dissolve_threshold(p: vec2<u32>) -> u32 {
+    var h = (p.x * 0x8da6b343u) ^ (p.y * 0xd8163841u);
+    h = h ^ (h >> 16u);
+    h = h * 0x7feb352du;
+    h = h ^ (h >> 15u);
+    h = h * 0x846ca68bu;
+    h = h ^ (h >> 16u);
+    return h % 255u;
+}
+
+// ---- Linear light (mirrors srgb8_to_linear / linear_to_srgb8) ----
+
+fn srgb_to_linear(c: vec3<f32>) -> vec3<f32> {
+    let hi = pow((max(c, vec3<f32>(0.0)) + vec3<f32>(0.055)) / 1.055, vec3<f32>(2.4));
+    return select(hi, c / 12.92, c <= vec3<f32>(0.04045));
+}
+
+fn linear_to_srgb(c: vec3<f32>) -> vec3<f32> {
+    let hi = 1.055 * pow(max(c, vec3<f32>(0.0)), vec3<f32>(1.0 / 2.4)) - vec3<f32>(0.055);
+    return select(hi, c * 12.92, c <= vec3<f32>(0.0031308));
+}
+
+// ---- Blend if (mirrors BlendRange::coverage / BlendIf::coverage) ----
+
+fn blend_range(v: f32, r: vec4<f32>) -> f32 {
+    if (v < r.x || v > r.w) { return 0.0; }
+    if (v < r.y) { return (v - r.x) / (r.y - r.x); }
+    if (v > r.z) { return (r.w - v) / (r.w - r.z); }
+    return 1.0;
+}
+
+fn blend_if_channels(c: vec3<f32>, first: u32) -> f32 {
+    let gray = dot(c, vec3<f32>(0.299, 0.587, 0.114));
+    return blend_range(gray, u.blend_if[first])
+        * blend_range(c.r, u.blend_if[first + 1u])
+        * blend_range(c.g, u.blend_if[first + 2u])
+        * blend_range(c.b, u.blend_if[first + 3u]);
+}
+
+fn blend_if_coverage(fg: vec3<f32>, bg: vec4<f32>) -> f32 {
+    var cov = blend_if_channels(fg, 0u);
+    // Underlying ranges only apply where there is a backdrop to test.
+    if (bg.a > 0.0) {
+        cov = cov * blend_if_channels(bg.rgb / bg.a, 4u);
+    }
+    return cov;
+}
+
 @fragment
 fn fs_blend(in: VertexOutput) -> @location(0) vec4<f32> {
     let fg_raw = textureSample(fg_tex, fg_samp, in.uv);
-    let bg     = textureSample(bg_tex, bg_samp, in.uv);
+    let bg_raw = textureSample(bg_tex, bg_samp, in.uv);
 
-    // Apply layer opacity to foreground alpha.
-    let fg_a = fg_raw.a * u.opacity;
+    // Apply layer opacity (and blend-if coverage) to foreground alpha.
+    var fg_a = fg_raw.a * u.opacity;
+    if (u.blend_if_enabled != 0u && fg_a > 0.0) {
+        fg_a = fg_a * blend_if_coverage(fg_raw.rgb, bg_raw);
+    }
+
+    // Linear light: decode both sides here and encode the result at the end.
+    // The accumulator itself stays premultiplied sRGB.
+    var fg_rgb = fg_raw.rgb;
+    var bg = bg_raw;
+    if (u.linear_light != 0u) {
+        fg_rgb = srgb_to_linear(fg_rgb);
+        if (bg.a > 0.0) {
+            bg = vec4<f32>(srgb_to_linear(bg.rgb / bg.a) * bg.a, bg.a);
+        }
+    }
 
     // ---- Overwrite: replace entirely ----
     if (u.blend_mode == 14u) {
@@ -261,14 +367,22 @@ fn fs_blend(in: VertexOutput) -> @location(0) vec4<f32> {
         let xor_a = bg.a * (1.0 - fg_a) + fg_a * (1.0 - bg.a);
         if (xor_a <= 0.0) { return vec4<f32>(0.0, 0.0, 0.0, 0.0); }
         // bg.rgb is already premultiplied from previous pass
-        let xor_rgb = (bg.rgb * (1.0 - fg_a) + fg_raw.rgb * fg_a * (1.0 - bg.a)) / xor_a;
+        var xor_rgb = (bg.rgb * (1.0 - fg_a) + fg_rgb * fg_a * (1.0 - bg.a)) / xor_a;
+        if (u.linear_light != 0u) { xor_rgb = linear_to_srgb(xor_rgb); }
         // Output premultiplied alpha
         let xor_premul = clamp(xor_rgb * xor_a, vec3<f32>(0.0), vec3<f32>(1.0));
         return vec4<f32>(xor_premul, xor_a);
     }
 
     // If foreground is fully transparent, pass through background.
-    if (fg_a <= 0.0) { return bg; }
+    if (fg_a <= 0.0) { return bg_raw; }
+
+    // ---- Dissolve: all or nothing per pixel, then Normal ----
+    if (u.blend_mode == 31u) {
+        let threshold = dissolve_threshold(vec2<u32>(in.position.xy));
+        if (threshold >= u32(round(fg_a * 255.0))) { return bg_raw; }
+        fg_a = 1.0;
+    }
 
     // ---- Compute blended RGB (blend mode math on straight-alpha colors) ----
     var blended: vec3<f32>;
@@ -276,146 +390,174 @@ fn fs_blend(in: VertexOutput) -> @location(0) vec4<f32> {
     switch (u.blend_mode) {
         // Normal
         case 0u {
-            blended = fg_raw.rgb;
+            blended = fg_rgb;
         }
         // Multiply
         case 1u {
-            blended = fg_raw.rgb * bg.rgb;
+            blended = fg_rgb * bg.rgb;
         }
         // Screen
         case 2u {
-            blended = vec3<f32>(1.0) - (vec3<f32>(1.0) - fg_raw.rgb) * (vec3<f32>(1.0) - bg.rgb);
+            blended = vec3<f32>(1.0) - (vec3<f32>(1.0) - fg_rgb) * (vec3<f32>(1.0) - bg.rgb);
         }
         // Additive
         case 3u {
-            blended = min(bg.rgb + fg_raw.rgb, vec3<f32>(1.0));
+            blended = min(bg.rgb + fg_rgb, vec3<f32>(1.0));
         }
         // Reflect
         case 4u {
             blended = vec3<f32>(
-                reflect_ch(bg.r, fg_raw.r),
-                reflect_ch(bg.g, fg_raw.g),
-                reflect_ch(bg.b, fg_raw.b),
+                reflect_ch(bg.r, fg_rgb.r),
+                reflect_ch(bg.g, fg_rgb.g),
+                reflect_ch(bg.b, fg_rgb.b),
             );
         }
         // Glow (reflect with swapped args)
         case 5u {
             blended = vec3<f32>(
-                reflect_ch(fg_raw.r, bg.r),
-                reflect_ch(fg_raw.g, bg.g),
-                reflect_ch(fg_raw.b, bg.b),
+                reflect_ch(fg_rgb.r, bg.r),
+                reflect_ch(fg_rgb.g, bg.g),
+                reflect_ch(fg_rgb.b, bg.b),
             );
         }
         // ColorBurn
         case 6u {
             blended = vec3<f32>(
-                color_burn_ch(bg.r, fg_raw.r),
-                color_burn_ch(bg.g, fg_raw.g),
-                color_burn_ch(bg.b, fg_raw.b),
+                color_burn_ch(bg.r, fg_rgb.r),
+                color_burn_ch(bg.g, fg_rgb.g),
+                color_burn_ch(bg.b, fg_rgb.b),
             );
         }
         // ColorDodge
         case 7u {
             blended = vec3<f32>(
-                color_dodge_ch(bg.r, fg_raw.r),
-                color_dodge_ch(bg.g, fg_raw.g),
-                color_dodge_ch(bg.b, fg_raw.b),
+                color_dodge_ch(bg.r, fg_rgb.r),
+                color_dodge_ch(bg.g, fg_rgb.g),
+                color_dodge_ch(bg.b, fg_rgb.b),
             );
         }
         // Overlay
         case 8u {
             blended = vec3<f32>(
-                overlay_ch(bg.r, fg_raw.r),
-                overlay_ch(bg.g, fg_raw.g),
-                overlay_ch(bg.b, fg_raw.b),
+                overlay_ch(bg.r, fg_rgb.r),
+                overlay_ch(bg.g, fg_rgb.g),
+                overlay_ch(bg.b, fg_rgb.b),
             );
         }
         // Difference
         case 9u {
-            blended = abs(bg.rgb - fg_raw.rgb);
+            blended = abs(bg.rgb - fg_rgb);
         }
         // Negation
         case 10u {
-            blended = vec3<f32>(1.0) - abs(vec3<f32>(1.0) - bg.rgb - fg_raw.rgb);
+            blended = vec3<f32>(1.0) - abs(vec3<f32>(1.0) - bg.rgb - fg_rgb);
         }
         // Lighten
         case 11u {
-            blended = max(bg.rgb, fg_raw.rgb);
+            blended = max(bg.rgb, fg_rgb);
         }
         // Darken
         case 12u {
-            blended = min(bg.rgb, fg_raw.rgb);
+            blended = min(bg.rgb, fg_rgb);
         }
         // HardLight (overlay with swapped base/top)
         case 15u {
             blended = vec3<f32>(
-                overlay_ch(fg_raw.r, bg.r),
-                overlay_ch(fg_raw.g, bg.g),
-                overlay_ch(fg_raw.b, bg.b),
+                overlay_ch(fg_rgb.r, bg.r),
+                overlay_ch(fg_rgb.g, bg.g),
+                overlay_ch(fg_rgb.b, bg.b),
             );
         }
         // SoftLight (W3C formula)
         case 16u {
             blended = vec3<f32>(
-                soft_light_ch(bg.r, fg_raw.r),
-                soft_light_ch(bg.g, fg_raw.g),
-                soft_light_ch(bg.b, fg_raw.b),
+                soft_light_ch(bg.r, fg_rgb.r),
+                soft_light_ch(bg.g, fg_rgb.g),
+                soft_light_ch(bg.b, fg_rgb.b),
             );
         }
         // Exclusion
         case 17u {
-            blended = bg.rgb + fg_raw.rgb - 2.0 * bg.rgb * fg_raw.rgb;
+            blended = bg.rgb + fg_rgb - 2.0 * bg.rgb * fg_rgb;
         }
         // Subtract
         case 18u {
-            blended = max(bg.rgb - fg_raw.rgb, vec3<f32>(0.0));
+            blended = max(bg.rgb - fg_rgb, vec3<f32>(0.0));
         }
         // Divide
         case 19u {
             blended = vec3<f32>(
-                divide_ch(bg.r, fg_raw.r),
-                divide_ch(bg.g, fg_raw.g),
-                divide_ch(bg.b, fg_raw.b),
+                divide_ch(bg.r, fg_rgb.r),
+                divide_ch(bg.g, fg_rgb.g),
+                divide_ch(bg.b, fg_rgb.b),
             );
         }
         // Linear Burn
         case 20u {
-            blended = max(bg.rgb + fg_raw.rgb - vec3<f32>(1.0), vec3<f32>(0.0));
+            blended = max(bg.rgb + fg_rgb - vec3<f32>(1.0), vec3<f32>(0.0));
         }
         // Vivid Light
         case 21u {
             blended = vec3<f32>(
-                vivid_light_ch(bg.r, fg_raw.r),
-                vivid_light_ch(bg.g, fg_raw.g),
-                vivid_light_ch(bg.b, fg_raw.b),
+                vivid_light_ch(bg.r, fg_rgb.r),
+                vivid_light_ch(bg.g, fg_rgb.g),
+                vivid_light_ch(bg.b, fg_rgb.b),
             );
         }
         // Linear Light
         case 22u {
-            blended = clamp(bg.rgb + 2.0 * fg_raw.rgb - vec3<f32>(1.0), vec3<f32>(0.0), vec3<f32>(1.0));
+            blended = clamp(bg.rgb + 2.0 * fg_rgb - vec3<f32>(1.0), vec3<f32>(0.0), vec3<f32>(1.0));
         }
         // Pin Light
         case 23u {
             blended = vec3<f32>(
-                pin_light_ch(bg.r, fg_raw.r),
-                pin_light_ch(bg.g, fg_raw.g),
-                pin_light_ch(bg.b, fg_raw.b),
+                pin_light_ch(bg.r, fg_rgb.r),
+                pin_light_ch(bg.g, fg_rgb.g),
+                pin_light_ch(bg.b, fg_rgb.b),
             );
         }
         // Hard Mix
         case 24u {
             blended = vec3<f32>(
-                select(0.0, 1.0, bg.r + fg_raw.r >= 1.0),
-                select(0.0, 1.0, bg.g + fg_raw.g >= 1.0),
-                select(0.0, 1.0, bg.b + fg_raw.b >= 1.0),
+                select(0.0, 1.0, bg.r + fg_rgb.r >= 1.0),
+                select(0.0, 1.0, bg.g + fg_rgb.g >= 1.0),
+                select(0.0, 1.0, bg.b + fg_rgb.b >= 1.0),
             );
         }
-        // Fallback: Normal
+        // Hue
+        case 25u {
+            blended = set_lum(set_sat(fg_rgb, sat(bg.rgb)), lum(bg.rgb));
+        }
+        // Saturation
+        case 26u {
+            blended = set_lum(set_sat(bg.rgb, sat(fg_rgb)), lum(bg.rgb));
+        }
+        // Color
+        case 27u {
+            blended = set_lum(fg_rgb, lum(bg.rgb));
+        }
+        // Luminosity
+        case 28u {
+            blended = set_lum(bg.rgb, lum(fg_rgb));
+        }
+        // Darker Color
+        case 29u {
+            blended = select(bg.rgb, fg_rgb, lum(fg_rgb) < lum(bg.rgb));
+        }
+        // Lighter Color
+        case 30u {
+            blended = select(bg.rgb, fg_rgb, lum(fg_rgb) > lum(bg.rgb));
+        }
+        // Fallback: Normal (also Dissolve, 31)
         default {
-            blended = fg_raw.rgb;
+            blended = fg_rgb;
         }
     }
 
+    // Where the backdrop is transparent the blend has nothing to act on, so
+    // the source shows through unchanged (matters for isolated groups).
+    blended = mix(fg_rgb, blended, bg.a);
+
     // ---- Alpha compositing (Porter-Duff source-over, premultiplied) ----
     // bg.rgb is already premultiplied from previous pass, so:
     // result_a = fg_a + bg_a * (1 - fg_a)
@@ -423,7 +565,8 @@ fn fs_blend(in: VertexOutput) -> @location(0) vec4<f32> {
     let out_a = fg_a + bg.a * (1.0 - fg_a);
     if (out_a <= 0.0) { return vec4<f32>(0.0, 0.0, 0.0, 0.0); }
 
-    let out_rgb = (blended * fg_a + bg.rgb * (1.0 - fg_a)) / out_a;
+    var out_rgb = (blended * fg_a + bg.rgb * (1.0 - fg_a)) / out_a;
+    if (u.linear_light != 0u) { out_rgb = linear_to_srgb(out_rgb); }
     
     // Output PREMULTIPLIED alpha: multiply RGB by alpha before output.
     // This prevents color desaturation when blending over light backgrounds.
@@ -1279,10 +1422,10 @@ struct GradientParams {
     end: vec2<f32>,       // gradient end point
     width: u32,           // canvas width
     height: u32,          // canvas height
-    shape: u32,           // 0=Linear, 1=LinearReflected, 2=Radial, 3=Diamond
+    shape: u32,           // 0=Linear, 1=LinearReflected, 2=Radial, 3=Diamond, 4=Conic, 5=Spiral
     repeat: u32,          // 0=clamp, 1=repeat
     is_eraser: u32,       // 0=color mode, 1=transparency/eraser mode
-    _pad0: u32,
+    dither: u32,          // 0=nearest LUT entry, 1=interpolate + ordered dither
 };
 
 @group(0) @binding(0) var output_tex: texture_storage_2d<rgba8unorm, write>;
@@ -1298,6 +1441,19 @@ fn unpack_rgba(packed: u32) -> vec4<f32> {
     return vec4<f32>(r, g, b, a);
 }
 
+// 4x4 Bayer threshold in -0.5..0.5 of one 8-bit step (matches gradient::dither_offset)
+fn bayer4(x: u32, y: u32) -> f32 {
+    var m = array<f32, 16>(
+        0.0, 8.0, 2.0, 10.0,
+        12.0, 4.0, 14.0, 6.0,
+        3.0, 11.0, 1.0, 9.0,
+        15.0, 7.0, 13.0, 5.0
+    );
+    return (m[(y & 3u) * 4u + (x & 3u)] + 0.5) / 16.0 - 0.5;
+}
+
+const TAU: f32 = 6.28318530718;
+
 @compute @workgroup_size(16, 16)
 fn cs_gradient(@builtin(global_invocation_id) gid: vec3<u32>) {
     if (gid.x >= params.width || gid.y >= params.height) {
@@ -1359,14 +1515,33 @@ fn cs_gradient(@builtin(global_invocation_id) gid: vec3<u32>) {
                 t = clamp(dist, 0.0, 1.0);
             }
         }
+        case 4u: { // Conic — wraps by nature, repeat ignored
+            let angle = atan2(ry, rx) - atan2(dy, dx);
+            let turns = angle / TAU;
+            t = turns - floor(turns);
+        }
+        case 5u: { // Spiral
+            let angle = atan2(ry, rx) - atan2(dy, dx);
+            let turns = angle / TAU + sqrt(rx * rx + ry * ry) * inv_len;
+            t = turns - floor(turns);
+        }
         default: {
             t = 0.0;
         }
     }
 
     // LUT lookup
-    let idx = u32(t * 255.0);
-    var color = unpack_rgba(lut[idx]);
+    var color: vec4<f32>;
+    if (params.dither != 0u) {
+        let f = clamp(t, 0.0, 1.0) * 255.0;
+        let i0 = u32(f);
+        let i1 = min(i0 + 1u, 255u);
+        let lerped = mix(unpack_rgba(lut[i0]), unpack_rgba(lut[i1]), f - f32(i0));
+        color = clamp(lerped + vec4<f32>(bayer4(gid.x, gid.y) / 255.0), vec4<f32>(0.0), vec4<f32>(1.0));
+    } else {
+        let idx = u32(t * 255.0);
+        color = unpack_rgba(lut[idx]);
+    }
 
     // Transparency/eraser mode: compute luminance → mask alpha
     if (params.is_eraser != 0u) {
@@ -0,0 +1,541 @@
+//! Gradient definitions shared by the gradient tool, Gradient Map and text
+//! gradient fills, plus the user gradient library and `.grd`/`.ggr` import.
+//!
+//! A [`Gradient`] has separate color and opacity stops, Photoshop-style. Each
+//! stop carries a midpoint — the fraction of the span to the next stop at which
+//! the blend is half way. Color stops also keep their own alpha (GIMP gradients
+//! and the older single-list stops rely on it); it is multiplied with the
+//! opacity stops.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::psd::{Descriptor, Reader, Value};
+
+/// File extensions accepted by the gradient import action.
+pub const GRADIENT_IMPORT_EXTENSIONS: &[&str] = &["grd", "ggr"];
+
+fn default_midpoint() -> f32 {
+    0.5
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ColorStop {
+    /// Position along the gradient, 0.0..=1.0.
+    pub position: f32,
+    /// RGBA color (un-premultiplied).
+    pub color: [u8; 4],
+    /// Where the blend towards the next stop reaches 50%, as a fraction of the span.
+    #[serde(default = "default_midpoint")]
+    pub midpoint: f32,
+}
+
+impl ColorStop {
+    pub fn new(position: f32, color: [u8; 4]) -> Self {
+        Self {
+            position,
+            color,
+            midpoint: 0.5,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OpacityStop {
+    /// Position along the gradient, 0.0..=1.0.
+    pub position: f32,
+    pub opacity: u8,
+    /// Where the blend towards the next stop reaches 50%, as a fraction of the span.
+    #[serde(default = "default_midpoint")]
+    pub midpoint: f32,
+}
+
+impl OpacityStop {
+    pub fn new(position: f32, opacity: u8) -> Self {
+        Self {
+            position,
+            opacity,
+            midpoint: 0.5,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Gradient {
+    pub name: String,
+    pub color_stops: Vec<ColorStop>,
+    /// Empty means fully opaque.
+    pub opacity_stops: Vec<OpacityStop>,
+}
+
+impl Default for Gradient {
+    fn default() -> Self {
+        Self::two_color("", [0, 0, 0, 255], [255, 255, 255, 255])
+    }
+}
+
+impl Gradient {
+    pub fn two_color(name: &str, start: [u8; 4], end: [u8; 4]) -> Self {
+        Self {
+            name: name.to_string(),
+            color_stops: vec![ColorStop::new(0.0, start), ColorStop::new(1.0, end)],
+            opacity_stops: vec![OpacityStop::new(0.0, 255), OpacityStop::new(1.0, 255)],
+        }
+    }
+
+    /// Sample the gradient at `t` (clamped to 0..=1) as un-premultiplied RGBA floats (0..255).
+    pub fn sample_f32(&self, t: f32) -> [f32; 4] {
+        Sampler::new(self).sample(t)
+    }
+
+    /// Sample the gradient at `t` (clamped to 0..=1).
+    pub fn sample(&self, t: f32) -> [u8; 4] {
+        quantize(self.sample_f32(t))
+    }
+
+    /// 256-entry lookup table, the form `ops::adjustments::gradient_map` takes.
+    pub fn build_lut(&self) -> [[u8; 4]; 256] {
+        let sampler = Sampler::new(self);
+        let mut lut = [[0u8; 4]; 256];
+        for (i, entry) in lut.iter_mut().enumerate() {
+            *entry = quantize(sampler.sample(i as f32 / 255.0));
+        }
+        lut
+    }
+
+    /// Same as [`Gradient::build_lut`] flattened to 256 × RGBA bytes (GPU upload layout).
+    pub fn build_lut_flat(&self) -> Vec<u8> {
+        self.build_lut().iter().flatten().copied().collect()
+    }
+
+    /// Deterministic "noise" gradient: `stops` random color stops generated
+    /// from `seed`. `roughness` (0..=1) controls how far each stop may drift
+    /// from the previous one; 1.0 gives fully independent colors.
+    pub fn noise(name: &str, seed: u32, roughness: f32, stops: usize) -> Self {
+        let roughness = roughness.clamp(0.0, 1.0);
+        let stops = stops.clamp(2, 64);
+        let mut rng = seed ^ 0x9E37_79B9;
+        let mut next = move || {
+            // xorshift32 — no need for a real RNG, only reproducibility.
+            rng ^= rng << 13;
+            rng ^= rng >> 17;
+            rng ^= rng << 5;
+            rng as f32 / u32::MAX as f32
+        };
+        let mut color = [next() * 255.0, next() * 255.0, next() * 255.0];
+        let mut color_stops = Vec::with_capacity(stops);
+        for i in 0..stops {
+            if i > 0 {
+                for c in &mut color {
+                    *c = (*c + (next() * 2.0 - 1.0) * roughness * 255.0).clamp(0.0, 255.0);
+                }
+            }
+            color_stops.push(ColorStop::new(
+                i as f32 / (stops - 1) as f32,
+                [
+                    color[0].round() as u8,
+                    color[1].round() as u8,
+                    color[2].round() as u8,
+                    255,
+                ],
+            ));
+        }
+        Self {
+            name: name.to_string(),
+            color_stops,
+            opacity_stops: vec![OpacityStop::new(0.0, 255), OpacityStop::new(1.0, 255)],
+        }
+    }
+}
+
+/// Stops sorted once so a whole LUT can be sampled cheaply.
+struct Sampler {
+    colors: Vec<(f32, f32, [f32; 4])>,
+    opacity: Vec<(f32, f32, f32)>,
+}
+
+impl Sampler {
+    fn new(gradient: &Gradient) -> Self {
+        let mut colors: Vec<(f32, f32, [f32; 4])> = gradient
+            .color_stops
+            .iter()
+            .map(|s| {
+                (
+                    s.position,
+                    s.midpoint,
+                    [
+                        s.color[0] as f32,
+                        s.color[1] as f32,
+                        s.color[2] as f32,
+                        s.color[3] as f32,
+                    ],
+                )
+            })
+            .collect();
+        // Stable sort keeps coincident stops (hard edges) in their given order.
+        colors.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut opacity: Vec<(f32, f32, f32)> = gradient
+            .opacity_stops
+            .iter()
+            .map(|s| (s.position, s.midpoint, s.opacity as f32))
+            .collect();
+        opacity.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { colors, opacity }
+    }
+
+    fn sample(&self, t: f32) -> [f32; 4] {
+        let t = t.clamp(0.0, 1.0);
+        let mut out = match locate(self.colors.iter().map(|s| (s.0, s.1)), t) {
+            None => [0.0; 4],
+            Some((lo, hi, w)) => {
+                let a = self.colors[lo].2;
+                let b = self.colors[hi].2;
+                std::array::from_fn(|c| a[c] + (b[c] - a[c]) * w)
+            }
+        };
+        if let Some((lo, hi, w)) = locate(self.opacity.iter().map(|s| (s.0, s.1)), t) {
+            let a = self.opacity[lo].2;
+            let b = self.opacity[hi].2;
+            out[3] *= (a + (b - a) * w) / 255.0;
+        }
+        out
+    }
+}
+
+/// Find the stops around `t` in sorted `(position, midpoint)` pairs and the
+/// midpoint-biased blend weight between them.
+fn locate(stops: impl ExactSizeIterator<Item = (f32, f32)>, t: f32) -> Option<(usize, usize, f32)> {
+    let stops: Vec<(f32, f32)> = stops.collect();
+    let last = stops.len().checked_sub(1)?;
+    if t <= stops[0].0 {
+        return Some((0, 0, 0.0));
+    }
+    if t >= stops[last].0 {
+        return Some((last, last, 0.0));
+    }
+    let i = stops.windows(2).position(|w| t >= w[0].0 && t < w[1].0)?;
+    let (p0, mid) = stops[i];
+    let span = stops[i + 1].0 - p0;
+    let local = if span > 0.0 { (t - p0) / span } else { 0.0 };
+    Some((i, i + 1, apply_midpoint(local, mid)))
+}
+
+/// Remap a 0..1 blend so it reaches 0.5 at `midpoint` (Photoshop's diamond handle).
+pub fn apply_midpoint(local: f32, midpoint: f32) -> f32 {
+    let m = midpoint.clamp(0.01, 0.99);
+    if (m - 0.5).abs() < 1e-4 || local <= 0.0 {
+        return local;
+    }
+    local.powf(0.5f32.ln() / m.ln())
+}
+
+fn quantize(c: [f32; 4]) -> [u8; 4] {
+    c.map(|v| v.round().clamp(0.0, 255.0) as u8)
+}
+
+/// 4×4 Bayer thresholds, centered on zero.
+const BAYER_4X4: [[f32; 4]; 4] = [
+    [0.0, 8.0, 2.0, 10.0],
+    [12.0, 4.0, 14.0, 6.0],
+    [3.0, 11.0, 1.0, 9.0],
+    [15.0, 7.0, 13.0, 5.0],
+];
+
+/// Ordered-dither offset for pixel `(x, y)`, in -0.5..0.5 of one 8-bit step.
+#[inline(always)]
+pub fn dither_offset(x: u32, y: u32) -> f32 {
+    (BAYER_4X4[(y & 3) as usize][(x & 3) as usize] + 0.5) / 16.0 - 0.5
+}
+
+/// Sample a flat 256 × RGBA LUT at `t`, interpolating between entries and
+/// ordered-dithering the result so long, smooth gradients don't band.
+#[inline(always)]
+pub fn sample_lut_dithered(lut: &[u8], t: f32, x: u32, y: u32) -> [u8; 4] {
+    let f = t.clamp(0.0, 1.0) * 255.0;
+    let i0 = f as usize;
+    let i1 = (i0 + 1).min(255);
+    let frac = f - i0 as f32;
+    let d = dither_offset(x, y);
+    std::array::from_fn(|c| {
+        let a = lut[i0 * 4 + c] as f32;
+        let b = lut[i1 * 4 + c] as f32;
+        (a + (b - a) * frac + d).round().clamp(0.0, 255.0) as u8
+    })
+}
+
+/// Add `gradient` to the library, replacing any gradient with the same name.
+pub fn upsert_gradient(library: &mut Vec<Gradient>, gradient: Gradient) {
+    if let Some(existing) = library.iter_mut().find(|g| g.name == gradient.name) {
+        *existing = gradient;
+    } else {
+        library.push(gradient);
+    }
+}
+
+// ============================================================================
+// IMPORT
+// ============================================================================
+
+/// Read a `.grd` or `.ggr` file. Foreground/background stops in Photoshop
+/// gradients resolve to `foreground`/`background`.
+pub fn load_gradients(
+    path: &Path,
+    foreground: [u8; 4],
+    background: [u8; 4],
+) -> Result<Vec<Gradient>, String> {
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Gradient".to_string());
+    parse_gradients(&data, &stem, foreground, background)
+}
+
+/// Parse `.grd` or `.ggr` bytes, detected by signature.
+pub fn parse_gradients(
+    data: &[u8],
+    base_name: &str,
+    foreground: [u8; 4],
+    background: [u8; 4],
+) -> Result<Vec<Gradient>, String> {
+    if data.starts_with(b"8BGR") {
+        parse_grd(data, foreground, background)
+    } else if data.starts_with(b"GIMP Gradient") {
+        let text = std::str::from_utf8(data).map_err(|e| e.to_string())?;
+        parse_ggr(text, base_name).map(|g| vec![g])
+    } else {
+        Err("Not a Photoshop (.grd) or GIMP (.ggr) gradient file".to_string())
+    }
+}
+
+/// Parse a GIMP `.ggr` gradient. Segment blend curves other than linear and
+/// curved are approximated as linear; HSV coloring is approximated in RGB.
+pub fn parse_ggr(text: &str, base_name: &str) -> Result<Gradient, String> {
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+    if lines.next() != Some("GIMP Gradient") {
+        return Err("Missing GIMP Gradient header".to_string());
+    }
+    let mut name = base_name.to_string();
+    let mut line = lines.next().ok_or("Truncated GIMP gradient")?;
+    if let Some(n) = line.strip_prefix("Name:") {
+        name = n.trim().to_string();
+        line = lines.next().ok_or("Truncated GIMP gradient")?;
+    }
+    let count: usize = line
+        .parse()
+        .map_err(|_| format!("Invalid segment count '{}'", line))?;
+    if count == 0 {
+        return Err("GIMP gradient has no segments".to_string());
+    }
+
+    // `count` comes from the file: let the segments that are really there
+    // size the list.
+    let mut stops: Vec<ColorStop> = Vec::new();
+    for i in 0..count {
+        let line = lines
+            .next()
+            .ok_or_else(|| format!("Missing segment {}", i + 1))?;
+        let v: Vec<f32> = line
+            .split_whitespace()
+            .take(11)
+            .map(|s| s.parse::<f32>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| format!("Invalid segment {}", i + 1))?;
+        if v.len() < 11 {
+            return Err(format!("Invalid segment {}", i + 1));
+        }
+        let (left, mid, right) = (v[0], v[1], v[2]);
+        let to_u8 = |c: f32| (c * 255.0).round().clamp(0.0, 255.0) as u8;
+        let left_color = [to_u8(v[3]), to_u8(v[4]), to_u8(v[5]), to_u8(v[6])];
+        let right_color = [to_u8(v[7]), to_u8(v[8]), to_u8(v[9]), to_u8(v[10])];
+        let span = right - left;
+        let midpoint = if span > 0.0 {
+            ((mid - left) / span).clamp(0.0, 1.0)
+        } else {
+            0.5
+        };
+        // Segments that continue the previous color share its end stop.
+        match stops.last_mut() {
+            Some(prev) if prev.color == left_color && (prev.position - left).abs() < 1e-6 => {
+                prev.midpoint = midpoint;
+            }
+            _ => stops.push(ColorStop {
+                position: left,
+                color: left_color,
+                midpoint,
+            }),
+        }
+        stops.push(ColorStop::new(right, right_color));
+    }
+    Ok(Gradient {
+        name,
+        color_stops: stops,
+        opacity_stops: vec![OpacityStop::new(0.0, 255), OpacityStop::new(1.0, 255)],
+    })
+}
+
+/// Parse a Photoshop `.grd` file (version 5, Photoshop 6 and later).
+/// Noise gradients are regenerated from their seed and roughness with
+/// [`Gradient::noise`], so they look similar but not identical.
+pub fn parse_grd(
+    data: &[u8],
+    foreground: [u8; 4],
+    background: [u8; 4],
+) -> Result<Vec<Gradient>, String> {
+    let mut r = Reader::new(data);
+    if r.bytes(4)? != b"8BGR" {
+        return Err("Not a Photoshop gradient file".to_string());
+    }
+    let version = r.u16()?;
+    if version != 5 {
+        return Err(format!(
+            "Unsupported .grd version {} (only Photoshop 6+ files are supported)",
+            version
+        ));
+    }
+    let _descriptor_version = r.u32()?;
+    let root = r.descriptor(0)?;
+    let list = match root.get("GrdL") {
+        Some(Value::List(items)) => items,
+        _ => return Err("Gradient file has no gradient list".to_string()),
+    };
+
+    let mut gradients = Vec::new();
+    for (i, item) in list.iter().enumerate() {
+        let Value::Object(entry) = item else {
+            continue;
+        };
+        let grad = match entry.get("Grad") {
+            Some(Value::Object(grad)) => grad,
+            _ => entry,
+        };
+        let name = match grad.get("Nm") {
+            Some(Value::Text(s)) if !s.trim().is_empty() => s.trim().to_string(),
+            _ => format!("Gradient {}", i + 1),
+        };
+        if matches!(grad.get("GrdF"), Some(Value::Enum(e)) if e == "ClNs") {
+            let seed = grad.number("RndS").unwrap_or(0.0) as u32;
+            let roughness = grad.number("Smth").unwrap_or(2048.0) as f32 / 4096.0;
+            gradients.push(Gradient::noise(&name, seed, roughness, 16));
+            continue;
+        }
+
+        let mut color_stops = Vec::new();
+        if let Some(Value::List(stops)) = grad.get("Clrs") {
+            for stop in stops {
+                let Value::Object(stop) = stop else {
+                    continue;
+                };
+                let color = match stop.get("Type") {
+                    Some(Value::Enum(e)) if e == "FrgC" => foreground,
+                    Some(Value::Enum(e)) if e == "BckC" => background,
+                    _ => match stop.get("Clr") {
+                        Some(Value::Object(c)) => descriptor_color(c),
+                        _ => [0, 0, 0, 255],
+                    },
+                };
+                color_stops.push(ColorStop {
+                    position: grd_location(stop),
+                    color: [color[0], color[1], color[2], 255],
+                    midpoint: grd_midpoint(stop),
+                });
+            }
+        }
+        if color_stops.is_empty() {
+            continue;
+        }
+        let mut opacity_stops = Vec::new();
+        if let Some(Value::List(stops)) = grad.get("Trns") {
+            for stop in stops {
+                let Value::Object(stop) = stop else {
+                    continue;
+                };
+                let percent = stop.number("Opct").unwrap_or(100.0);
+                opacity_stops.push(OpacityStop {
+                    position: grd_location(stop),
+                    opacity: (percent * 2.55).round().clamp(0.0, 255.0) as u8,
+                    midpoint: grd_midpoint(stop),
+                });
+            }
+        }
+        gradients.push(Gradient {
+            name,
+            color_stops,
+            opacity_stops,
+        });
+    }
+    if gradients.is_empty() {
+        return Err("No gradients found in .grd file".to_string());
+    }
+    Ok(gradients)
+}
+
+/// `Lctn` is 0..4096 across the gradient.
+fn grd_location(stop: &Descriptor) -> f32 {
+    (stop.number("Lctn").unwrap_or(0.0) / 4096.0).clamp(0.0, 1.0) as f32
+}
+
+/// `Mdpn` is a percentage.
+fn grd_midpoint(stop: &Descriptor) -> f32 {
+    (stop.number("Mdpn").unwrap_or(50.0) / 100.0).clamp(0.0, 1.0) as f32
+}
+
+/// Convert a Photoshop color descriptor (`RGBC`, `HSBC`, `Grsc`, `CMYC`, `LbCl`) to RGBA.
+fn descriptor_color(c: &Descriptor) -> [u8; 4] {
+    let n = |key: &str| c.number(key).unwrap_or(0.0) as f32;
+    let rgb = match c.class.as_str() {
+        "RGBC" => [n("Rd"), n("Grn"), n("Bl")],
+        "HSBC" => {
+            let hsv = [n("H") / 360.0, n("Strt") / 100.0, n("Brgh") / 100.0];
+            let c32 = egui::ecolor::Hsva::new(hsv[0].rem_euclid(1.0), hsv[1], hsv[2], 1.0);
+            let [r, g, b] = c32.to_srgb();
+            [r as f32, g as f32, b as f32]
+        }
+        "Grsc" => {
+            let v = (1.0 - n("Gry") / 100.0) * 255.0;
+            [v, v, v]
+        }
+        "CMYC" => {
+            let k = 1.0 - n("Blck") / 100.0;
+            [
+                (1.0 - n("Cyn") / 100.0) * k * 255.0,
+                (1.0 - n("Mgnt") / 100.0) * k * 255.0,
+                (1.0 - n("Ylw") / 100.0) * k * 255.0,
+            ]
+        }
+        "LbCl" => lab_to_srgb(n("Lmnc"), n("A"), n("B")),
+        _ => [0.0, 0.0, 0.0],
+    };
+    let q = |v: f32| v.round().clamp(0.0, 255.0) as u8;
+    [q(rgb[0]), q(rgb[1]), q(rgb[2]), 255]
+}
+
+/// CIE Lab (D65) to 8-bit sRGB.
+fn lab_to_srgb(l: f32, a: f32, b: f32) -> [f32; 3] {
+    let fy = (l + 16.0) / 116.0;
+    let fx = fy + a / 500.0;
+    let fz = fy - b / 200.0;
+    let inv = |t: f32| {
+        if t > 6.0 / 29.0 {
+            t * t * t
+        } else {
+            3.0 * (6.0f32 / 29.0).powi(2) * (t - 4.0 / 29.0)
+        }
+    };
+    let (x, y, z) = (0.950_47 * inv(fx), inv(fy), 1.088_83 * inv(fz));
+    let lin = [
+        3.240_45 * x - 1.537_14 * y - 0.498_53 * z,
+        -0.969_27 * x + 1.876_01 * y + 0.041_56 * z,
+        0.055_64 * x - 0.204_03 * y + 1.057_23 * z,
+    ];
+    lin.map(|c| {
+        let c = c.clamp(0.0, 1.0);
+        let s = if c <= 0.003_130_8 {
+            12.92 * c
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        };
+        s * 255.0
+    })
+}
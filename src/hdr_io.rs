@@ -0,0 +1,498 @@
+//! OpenEXR and Radiance HDR (`.hdr`) import and export.
+//!
+//! Float samples go straight into each layer's `deep_pixels` — half-float
+//! EXR channels stay `F16`, everything else becomes `F32` — tagged as
+//! scene-linear, and the 8-bit layer pixels are an sRGB preview rendered
+//! through the layer's [`HdrView`].
+//!
+//! EXR parts and channel groups (`diffuse.R`, `diffuse.G`, …) each become a
+//! layer, the first one on top. Channels outside R/G/B/A/Y (depth, IDs, …)
+//! come in as grayscale layers of their own. On export every raster layer is
+//! written as its own named part; a single-layer document is written as a
+//! plain RGBA image.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use exr::prelude::{
+    AnyChannel, AnyChannels, Encoding, FlatSamples, Image, ImageAttributes, IntegerBounds,
+    LayerAttributes, ReadChannels, ReadLayers, SmallVec, Text, Vec2, WritableImage, f16,
+};
+use image::RgbaImage;
+
+use crate::canvas::{CanvasState, HdrMetadata, Layer, LayerContent, TiledImage, srgb_to_linear};
+use crate::experimental::{DeepRgbaBuffer, HdrTransfer, HdrView, f16_bits_to_f32, f32_to_f16_bits};
+#[cfg(target_arch = "wasm32")]
+use crate::web_fs::File;
+
+/// Interleaved RGBA samples for one EXR layer.
+pub enum ExrSamples {
+    F16(Vec<u16>),
+    F32(Vec<f32>),
+}
+
+/// Snapshot of a canvas ready to write as OpenEXR.
+pub struct ExrData {
+    width: u32,
+    height: u32,
+    /// `(part name, samples)`, top layer first. Unnamed for single-layer files.
+    layers: Vec<(Option<String>, ExrSamples)>,
+}
+
+// ============================================================================
+// OPENEXR IMPORT
+// ============================================================================
+
+pub fn load_exr(path: &Path) -> Result<CanvasState, String> {
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    parse_exr(&data)
+}
+
+/// Color/alpha channels of one layer; `y` doubles as the value of a
+/// single-channel (grayscale) group.
+#[derive(Default)]
+struct ChannelGroup<'a> {
+    name: String,
+    r: Option<&'a FlatSamples>,
+    g: Option<&'a FlatSamples>,
+    b: Option<&'a FlatSamples>,
+    a: Option<&'a FlatSamples>,
+    y: Option<&'a FlatSamples>,
+}
+
+impl ChannelGroup<'_> {
+    fn sources(&self) -> impl Iterator<Item = &FlatSamples> {
+        [self.r, self.g, self.b, self.a, self.y]
+            .into_iter()
+            .flatten()
+    }
+}
+
+pub fn parse_exr(data: &[u8]) -> Result<CanvasState, String> {
+    let image = exr::prelude::read()
+        .no_deep_data()
+        .largest_resolution_level()
+        .all_channels()
+        .all_layers()
+        .all_attributes()
+        .from_buffered(std::io::Cursor::new(data))
+        .map_err(|e| format!("Invalid OpenEXR file: {}", e))?;
+
+    let display = image.attributes.display_window;
+    let (width, height) = (display.size.width() as u32, display.size.height() as u32);
+    crate::io::validate_open_dimensions(width, height)?;
+
+    let mut state = CanvasState::new(width, height);
+    state.layers.clear();
+    // EXR lists the main part first; PaintFE stacks bottom-up.
+    for part in image.layer_data.iter() {
+        let part_name = part.attributes.layer_name.as_ref().map(Text::to_string);
+        let origin = part.attributes.layer_position - display.position;
+        let part_width = part.size.width();
+        for group in group_channels(&part.channel_data.list) {
+            if state.layers.len() >= crate::io::MAX_LAYERS {
+                return Err(format!(
+                    "OpenEXR file has more than {} layers",
+                    crate::io::MAX_LAYERS
+                ));
+            }
+            let name = match (&part_name, group.name.is_empty()) {
+                (Some(part), true) => part.clone(),
+                (Some(part), false) => format!("{}.{}", part, group.name),
+                (None, false) => group.name.clone(),
+                (None, true) => "Background".to_string(),
+            };
+            let place = |i: usize| {
+                let x = origin.x() as i64 + (i % part_width) as i64;
+                let y = origin.y() as i64 + (i / part_width) as i64;
+                ((0..width as i64).contains(&x) && (0..height as i64).contains(&y))
+                    .then(|| (y as usize * width as usize + x as usize) * 4)
+            };
+            let len = width as usize * height as usize * 4;
+            let half = group.sources().all(|s| matches!(s, FlatSamples::F16(_)));
+            let deep = if half {
+                let mut values = vec![0u16; len];
+                fill_group(
+                    &group,
+                    &mut values,
+                    place,
+                    f16::ONE.to_bits(),
+                    |s, i| match s {
+                        FlatSamples::F16(v) => v[i].to_bits(),
+                        _ => 0,
+                    },
+                );
+                DeepRgbaBuffer::F16(values)
+            } else {
+                let mut values = vec![0.0f32; len];
+                fill_group(&group, &mut values, place, 1.0, |s, i| {
+                    s.value_by_flat_index(i).to_f32()
+                });
+                DeepRgbaBuffer::F32(values)
+            };
+            state
+                .layers
+                .push(float_layer(name, width, height, deep, "exr"));
+        }
+    }
+    if state.layers.is_empty() {
+        return Err("OpenEXR file has no readable channels".to_string());
+    }
+    state.layers.reverse();
+    state.active_layer_index = state.layers.len() - 1;
+    state.composite_cache = None;
+    Ok(state)
+}
+
+/// Split a part's channels into layers: `prefix.R`/`.G`/`.B`/`.A`/`.Y` share
+/// a layer per prefix (unprefixed first), any other channel stands alone.
+fn group_channels(channels: &[AnyChannel<FlatSamples>]) -> Vec<ChannelGroup<'_>> {
+    let mut groups: Vec<ChannelGroup> = Vec::new();
+    for channel in channels {
+        if channel.sampling != Vec2(1, 1) {
+            continue; // Chroma-subsampled channels are not supported
+        }
+        let full = channel.name.to_string();
+        let (prefix, suffix) = match full.rsplit_once('.') {
+            Some((prefix, suffix)) => (prefix.to_string(), suffix),
+            None => (String::new(), full.as_str()),
+        };
+        let slot = match suffix.to_ascii_uppercase().as_str() {
+            "R" => 0,
+            "G" => 1,
+            "B" => 2,
+            "A" => 3,
+            "Y" => 4,
+            _ => {
+                groups.push(ChannelGroup {
+                    name: full.clone(),
+                    y: Some(&channel.sample_data),
+                    ..Default::default()
+                });
+                continue;
+            }
+        };
+        let idx = match groups.iter().position(|g| g.name == prefix) {
+            Some(idx) => idx,
+            None => {
+                groups.push(ChannelGroup {
+                    name: prefix,
+                    ..Default::default()
+                });
+                groups.len() - 1
+            }
+        };
+        let group = &mut groups[idx];
+        let target = match slot {
+            0 => &mut group.r,
+            1 => &mut group.g,
+            2 => &mut group.b,
+            3 => &mut group.a,
+            _ => &mut group.y,
+        };
+        *target = Some(&channel.sample_data);
+    }
+    if let Some(main) = groups.iter().position(|g| g.name.is_empty()) {
+        let main = groups.remove(main);
+        groups.insert(0, main);
+    }
+    for group in &mut groups {
+        // A lone alpha channel reads as a grayscale matte.
+        if group.r.is_none() && group.g.is_none() && group.b.is_none() && group.y.is_none() {
+            group.y = group.a.take();
+        }
+    }
+    groups
+}
+
+/// Write a group's samples into interleaved canvas-sized RGBA `out`.
+/// Missing color channels stay zero and missing alpha is opaque.
+fn fill_group<T: Copy + Default>(
+    group: &ChannelGroup,
+    out: &mut [T],
+    place: impl Fn(usize) -> Option<usize>,
+    one: T,
+    sample: impl Fn(&FlatSamples, usize) -> T,
+) {
+    let has_color = group.r.is_some() || group.g.is_some() || group.b.is_some();
+    let channels = if has_color {
+        [group.r, group.g, group.b]
+    } else {
+        [group.y, group.y, group.y]
+    };
+    let Some(count) = group.sources().next().map(FlatSamples::len) else {
+        return;
+    };
+    for i in 0..count {
+        let Some(idx) = place(i) else {
+            continue;
+        };
+        for (c, source) in channels.iter().enumerate() {
+            out[idx + c] = source.map_or(T::default(), |s| sample(s, i));
+        }
+        out[idx + 3] = group.a.map_or(one, |s| sample(s, i));
+    }
+}
+
+/// Single raster layer holding a scene-linear float payload, with its
+/// preview rendered through the default view.
+pub(crate) fn float_layer(
+    name: String,
+    width: u32,
+    height: u32,
+    deep: DeepRgbaBuffer,
+    source_format: &str,
+) -> Layer {
+    let (max, transfer) = match &deep {
+        DeepRgbaBuffer::F16(v) => (
+            v.iter()
+                .map(|&b| f16_bits_to_f32(b))
+                .fold(0.0_f32, f32::max),
+            "scene-linear-f16",
+        ),
+        DeepRgbaBuffer::F32(v) => (
+            v.iter().copied().fold(0.0_f32, f32::max),
+            "scene-linear-f32",
+        ),
+        _ => (0.0, HdrMetadata::SCENE_LINEAR),
+    };
+    let hdr_metadata = crate::io::linear_hdr_metadata(max, transfer);
+    let preview = hdr_metadata
+        .payload_view()
+        .preview(&deep, width, height)
+        .unwrap_or_else(|| RgbaImage::new(width, height));
+    let mut layer = Layer::new(name, width, height, image::Rgba([0, 0, 0, 0]));
+    layer.pixels = TiledImage::from_rgba_image(&preview);
+    layer.pixel_format = deep.format();
+    layer.hdr_metadata = hdr_metadata;
+    layer.source_metadata.source_format = Some(source_format.to_string());
+    layer.deep_pixels = Some(deep);
+    layer
+}
+
+// ============================================================================
+// OPENEXR EXPORT
+// ============================================================================
+
+/// Save `state` as a (possibly multi-part) OpenEXR file.
+pub fn save_exr(state: &CanvasState, path: &Path) -> std::io::Result<()> {
+    write_exr(&build_exr(state), path)
+}
+
+/// Snapshot every raster layer's linear samples. Half and 8-bit layers are
+/// written as half floats, 16-bit and float layers as full floats.
+pub fn build_exr(state: &CanvasState) -> ExrData {
+    let raster: Vec<&Layer> = state
+        .layers
+        .iter()
+        .filter(|l| matches!(l.content, LayerContent::Raster | LayerContent::Text(_)))
+        .collect();
+    let single = raster.len() == 1;
+    let mut used: Vec<String> = Vec::new();
+    let layers = raster
+        .iter()
+        .rev()
+        .map(|layer| {
+            let name = (!single).then(|| {
+                let mut name = layer.name.clone();
+                let mut n = 2;
+                while used.contains(&name) {
+                    name = format!("{} {}", layer.name, n);
+                    n += 1;
+                }
+                used.push(name.clone());
+                name
+            });
+            (name, layer_samples(layer, state.width, state.height))
+        })
+        .collect();
+    ExrData {
+        width: state.width,
+        height: state.height,
+        layers,
+    }
+}
+
+/// Linear samples for `layer`, with its mask folded into alpha. Uses the
+/// deep payload when it still matches the preview; otherwise maps the
+/// preview back through the layer's view. Integer samples are sRGB, so their
+/// colour channels are decoded to linear light.
+fn layer_samples(layer: &Layer, width: u32, height: u32) -> ExrSamples {
+    let preview = layer.pixels.to_rgba_image();
+    let view = layer.hdr_metadata.payload_view();
+    let deep = layer
+        .deep_pixels
+        .as_ref()
+        .filter(|deep| view.preview(deep, width, height).as_ref() == Some(&preview));
+    let reveal = |i: usize| {
+        let x = (i as u32) % width;
+        let y = (i as u32) / width;
+        layer.apply_mask_alpha_at(x, y, 255) as f32 / 255.0
+    };
+    let masked = layer.mask.is_some() && layer.mask_enabled;
+    let preview_view = if layer.has_float_pixels() {
+        view
+    } else {
+        HdrView {
+            transfer: HdrTransfer::SceneLinear,
+            ..HdrView::default()
+        }
+    };
+
+    let mut samples = match deep {
+        Some(DeepRgbaBuffer::F16(values)) => ExrSamples::F16(values.clone()),
+        Some(DeepRgbaBuffer::F32(values)) => ExrSamples::F32(values.clone()),
+        Some(DeepRgbaBuffer::U16(values)) => ExrSamples::F32(
+            values
+                .chunks_exact(4)
+                .flat_map(|px| {
+                    let decode = |v: u16| srgb_to_linear(v as f32 / 65535.0);
+                    [
+                        decode(px[0]),
+                        decode(px[1]),
+                        decode(px[2]),
+                        px[3] as f32 / 65535.0,
+                    ]
+                })
+                .collect(),
+        ),
+        _ => ExrSamples::F16(
+            preview
+                .pixels()
+                .flat_map(|&p| preview_view.unmap(p))
+                .map(f32_to_f16_bits)
+                .collect(),
+        ),
+    };
+    if masked {
+        match &mut samples {
+            ExrSamples::F16(values) => {
+                for (i, px) in values.chunks_exact_mut(4).enumerate() {
+                    px[3] = f32_to_f16_bits(f16_bits_to_f32(px[3]) * reveal(i));
+                }
+            }
+            ExrSamples::F32(values) => {
+                for (i, px) in values.chunks_exact_mut(4).enumerate() {
+                    px[3] *= reveal(i);
+                }
+            }
+        }
+    }
+    samples
+}
+
+pub fn write_exr(data: &ExrData, path: &Path) -> std::io::Result<()> {
+    let bytes = encode_exr(data)?;
+    File::create(path)?.write_all(&bytes)
+}
+
+fn encode_exr(data: &ExrData) -> std::io::Result<Vec<u8>> {
+    let size = Vec2(data.width as usize, data.height as usize);
+    let layers: Vec<_> = data
+        .layers
+        .iter()
+        .map(|(name, samples)| {
+            let channel = |c: usize, name: &str| {
+                let values = match samples {
+                    ExrSamples::F16(v) => FlatSamples::F16(
+                        v.iter()
+                            .skip(c)
+                            .step_by(4)
+                            .map(|&b| f16::from_bits(b))
+                            .collect(),
+                    ),
+                    ExrSamples::F32(v) => {
+                        FlatSamples::F32(v.iter().skip(c).step_by(4).copied().collect())
+                    }
+                };
+                AnyChannel::new(name, values)
+            };
+            let channels: SmallVec<[AnyChannel<FlatSamples>; 4]> = [
+                channel(0, "R"),
+                channel(1, "G"),
+                channel(2, "B"),
+                channel(3, "A"),
+            ]
+            .into_iter()
+            .collect();
+            let attributes = name
+                .as_deref()
+                .map(|n| LayerAttributes::named(exr_text(n)))
+                .unwrap_or_default();
+            exr::prelude::Layer::new(
+                size,
+                attributes,
+                Encoding::SMALL_LOSSLESS,
+                AnyChannels::sort(channels),
+            )
+        })
+        .collect();
+    let image = Image::from_layers(
+        ImageAttributes::new(IntegerBounds::from_dimensions(size)),
+        layers,
+    );
+    let mut out = std::io::Cursor::new(Vec::new());
+    image
+        .write()
+        .to_buffered(&mut out)
+        .map_err(|e| std::io::Error::other(format!("EXR encode error: {}", e)))?;
+    Ok(out.into_inner())
+}
+
+/// EXR names are Latin-1; replace anything else.
+fn exr_text(name: &str) -> Text {
+    let latin1: String = name
+        .chars()
+        .map(|c| if (c as u32) < 256 { c } else { '?' })
+        .collect();
+    Text::new_or_none(latin1).unwrap_or_else(|| Text::from("Layer"))
+}
+
+/// Single-layer EXR from interleaved linear RGBA samples.
+pub fn encode_flat_exr(width: u32, height: u32, samples: ExrSamples) -> std::io::Result<Vec<u8>> {
+    encode_exr(&ExrData {
+        width,
+        height,
+        layers: vec![(None, samples)],
+    })
+}
+
+// ============================================================================
+// RADIANCE HDR
+// ============================================================================
+
+pub fn parse_radiance(data: &[u8]) -> Result<CanvasState, String> {
+    let decoded = image::load_from_memory_with_format(data, image::ImageFormat::Hdr)
+        .map_err(|e| e.to_string())?
+        .into_rgba32f();
+    let (width, height) = decoded.dimensions();
+    crate::io::validate_open_dimensions(width, height)?;
+    let mut state = CanvasState::new(width, height);
+    state.layers[0] = float_layer(
+        "Background".to_string(),
+        width,
+        height,
+        DeepRgbaBuffer::F32(decoded.into_raw()),
+        "hdr",
+    );
+    Ok(state)
+}
+
+/// Radiance RGBE from interleaved linear RGBA. RGBE has no alpha, so
+/// transparent pixels are flattened onto black.
+pub fn encode_radiance(width: u32, height: u32, pixels: &[f32]) -> std::io::Result<Vec<u8>> {
+    let rgb: Vec<image::Rgb<f32>> = pixels
+        .chunks_exact(4)
+        .map(|px| {
+            let a = px[3].clamp(0.0, 1.0);
+            image::Rgb([px[0].max(0.0) * a, px[1].max(0.0) * a, px[2].max(0.0) * a])
+        })
+        .collect();
+    let mut out = Vec::new();
+    image::codecs::hdr::HdrEncoder::new(&mut out)
+        .encode(&rgb, width as usize, height as usize)
+        .map_err(|e| std::io::Error::other(format!("HDR encode error: {}", e)))?;
+    Ok(out)
+}
@@ -0,0 +1,375 @@
+//! Multi-resolution Windows icon (`.ico`) and macOS icon (`.icns`) support.
+//!
+//! Each requested size is taken from a layer named after it (`32x32`) when
+//! one exists, so small sizes can be hand-tuned; otherwise the visible
+//! composite is scaled down to fit. ICO entries below [`PNG_ENTRY_MIN`] are
+//! stored as 32-bit BMP for older readers, larger ones as PNG. ICNS entries
+//! are always PNG.
+//!
+//! Importing an ICO turns every entry into a layer named `WxH`, centred on a
+//! canvas the size of the largest entry. Only the largest is left visible.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use image::codecs::ico::{IcoEncoder, IcoFrame};
+use image::codecs::png::PngEncoder;
+use image::imageops::FilterType;
+use image::{ImageEncoder, Rgba, RgbaImage};
+
+use crate::canvas::{CanvasState, Layer, TiledImage};
+#[cfg(target_arch = "wasm32")]
+use crate::web_fs::File;
+
+/// Entry sizes offered for `.ico` output.
+pub const ICO_SIZES: [u32; 7] = [16, 24, 32, 48, 64, 128, 256];
+
+/// Entry sizes offered for `.icns` output (the PNG-backed icon types).
+pub const ICNS_SIZES: [u32; 7] = [16, 32, 64, 128, 256, 512, 1024];
+
+/// ICO entries at least this large are PNG-compressed when enabled.
+pub const PNG_ENTRY_MIN: u32 = 64;
+
+/// Options picked in the save dialog for `.ico` / `.icns` output.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IconOptions {
+    /// Requested square sizes; ones the target format can't hold are ignored.
+    pub sizes: Vec<u32>,
+    /// Take each size from a layer named like `32x32` when present.
+    pub use_size_layers: bool,
+    /// Store ICO entries of [`PNG_ENTRY_MIN`] and up as PNG instead of BMP.
+    pub png_large: bool,
+}
+
+impl Default for IconOptions {
+    fn default() -> Self {
+        let mut sizes: Vec<u32> = ICO_SIZES.iter().chain(&ICNS_SIZES).copied().collect();
+        sizes.sort_unstable();
+        sizes.dedup();
+        Self {
+            sizes,
+            use_size_layers: true,
+            png_large: true,
+        }
+    }
+}
+
+/// Rendered icon entries, smallest first, ready to encode.
+pub struct IconData {
+    pub entries: Vec<RgbaImage>,
+    pub png_large: bool,
+}
+
+// ============================================================================
+// BUILDING ENTRIES
+// ============================================================================
+
+pub fn build_ico(state: &CanvasState, options: &IconOptions) -> IconData {
+    build_icon(state, &ICO_SIZES, options)
+}
+
+pub fn build_icns(state: &CanvasState, options: &IconOptions) -> IconData {
+    build_icon(state, &ICNS_SIZES, options)
+}
+
+/// ICO entries for a flat image (no size layers), for the CLI and other
+/// single-image save paths.
+pub fn ico_from_image(image: &RgbaImage, options: &IconOptions) -> IconData {
+    flat_icon(image, &ICO_SIZES, options)
+}
+
+/// ICNS counterpart of [`ico_from_image`].
+pub fn icns_from_image(image: &RgbaImage, options: &IconOptions) -> IconData {
+    flat_icon(image, &ICNS_SIZES, options)
+}
+
+fn flat_icon(image: &RgbaImage, supported: &[u32], options: &IconOptions) -> IconData {
+    IconData {
+        entries: selected_sizes(supported, options, image.width().max(image.height()))
+            .into_iter()
+            .map(|size| fit_square(image, size))
+            .collect(),
+        png_large: options.png_large,
+    }
+}
+
+fn build_icon(state: &CanvasState, supported: &[u32], options: &IconOptions) -> IconData {
+    let size_layer = |size: u32| {
+        options
+            .use_size_layers
+            .then(|| {
+                state
+                    .layers
+                    .iter()
+                    .find(|l| parse_size_name(&l.name) == Some(size))
+            })
+            .flatten()
+    };
+    let longest = state.width.max(state.height);
+    let mut composite: Option<RgbaImage> = None;
+    let mut entries = Vec::new();
+    for size in selected_sizes(supported, options, longest) {
+        let entry = match size_layer(size) {
+            Some(layer) => layer_entry(layer, size),
+            None => fit_square(composite.get_or_insert_with(|| state.composite()), size),
+        };
+        entries.push(entry);
+    }
+    // Hand-tuned layers larger than the canvas are still honoured.
+    for &size in supported {
+        if size > longest
+            && options.sizes.contains(&size)
+            && let Some(layer) = size_layer(size)
+            && !entries.iter().any(|e| e.width() == size)
+        {
+            entries.push(layer_entry(layer, size));
+        }
+    }
+    entries.sort_by_key(|e| e.width());
+    IconData {
+        entries,
+        png_large: options.png_large,
+    }
+}
+
+/// Requested sizes the format supports, skipping ones that would only
+/// upscale the source — but always keeping at least the smallest.
+fn selected_sizes(supported: &[u32], options: &IconOptions, longest: u32) -> Vec<u32> {
+    let wanted: Vec<u32> = supported
+        .iter()
+        .copied()
+        .filter(|s| options.sizes.contains(s))
+        .collect();
+    let fitting: Vec<u32> = wanted.iter().copied().filter(|&s| s <= longest).collect();
+    if fitting.is_empty() {
+        wanted.into_iter().take(1).collect()
+    } else {
+        fitting
+    }
+}
+
+/// `"32x32"` (or `32×32`, any case, surrounding spaces ignored) → `Some(32)`.
+pub fn parse_size_name(name: &str) -> Option<u32> {
+    let lower = name.trim().to_lowercase();
+    let (w, h) = lower.split_once('x').or_else(|| lower.split_once('×'))?;
+    let (w, h) = (w.trim().parse::<u32>().ok()?, h.trim().parse::<u32>().ok()?);
+    (w == h && w > 0).then_some(w)
+}
+
+/// The `size`×`size` square centred on the canvas, or the whole layer scaled
+/// to fit when the canvas is smaller than the entry.
+fn layer_entry(layer: &Layer, size: u32) -> RgbaImage {
+    let flat = layer.to_masked_rgba_image();
+    if flat.width() < size || flat.height() < size {
+        return fit_square(&flat, size);
+    }
+    let x = (flat.width() - size) / 2;
+    let y = (flat.height() - size) / 2;
+    image::imageops::crop_imm(&flat, x, y, size, size).to_image()
+}
+
+/// Scale `image` to fit a `size`×`size` square, centred on transparency.
+fn fit_square(image: &RgbaImage, size: u32) -> RgbaImage {
+    let (w, h) = image.dimensions();
+    if w == size && h == size {
+        return image.clone();
+    }
+    let scale = size as f32 / w.max(h) as f32;
+    let new_w = ((w as f32 * scale).round() as u32).clamp(1, size);
+    let new_h = ((h as f32 * scale).round() as u32).clamp(1, size);
+    let scaled = image::imageops::resize(image, new_w, new_h, FilterType::Lanczos3);
+    let mut out = RgbaImage::from_pixel(size, size, Rgba([0, 0, 0, 0]));
+    image::imageops::overlay(
+        &mut out,
+        &scaled,
+        ((size - new_w) / 2) as i64,
+        ((size - new_h) / 2) as i64,
+    );
+    out
+}
+
+// ============================================================================
+// ENCODING
+// ============================================================================
+
+pub fn write_ico(data: &IconData, path: &Path) -> std::io::Result<()> {
+    let bytes = encode_ico(data)?;
+    File::create(path)?.write_all(&bytes)
+}
+
+pub fn write_icns(data: &IconData, path: &Path) -> std::io::Result<()> {
+    let bytes = encode_icns(data)?;
+    File::create(path)?.write_all(&bytes)
+}
+
+pub fn encode_ico(data: &IconData) -> std::io::Result<Vec<u8>> {
+    let frames = data
+        .entries
+        .iter()
+        .map(|entry| {
+            let (w, h) = entry.dimensions();
+            if data.png_large && w.max(h) >= PNG_ENTRY_MIN {
+                IcoFrame::as_png(entry.as_raw(), w, h, image::ExtendedColorType::Rgba8)
+            } else {
+                IcoFrame::with_encoded(bmp_entry(entry), w, h, image::ExtendedColorType::Rgba8)
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(std::io::Error::other)?;
+    let mut out = Vec::new();
+    IcoEncoder::new(&mut out)
+        .encode_images(&frames)
+        .map_err(std::io::Error::other)?;
+    Ok(out)
+}
+
+/// 32-bit BGRA DIB with its (all-opaque) AND mask, as stored inside an ICO.
+fn bmp_entry(image: &RgbaImage) -> Vec<u8> {
+    let (w, h) = image.dimensions();
+    let mask_stride = w.div_ceil(32) * 4;
+    let mut out = Vec::with_capacity(40 + (w * h * 4 + mask_stride * h) as usize);
+    out.extend_from_slice(&40u32.to_le_bytes());
+    out.extend_from_slice(&(w as i32).to_le_bytes());
+    // Height covers the XOR bitmap plus the AND mask.
+    out.extend_from_slice(&(h as i32 * 2).to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&32u16.to_le_bytes());
+    out.extend_from_slice(&[0; 24]);
+    for y in (0..h).rev() {
+        for x in 0..w {
+            let [r, g, b, a] = image.get_pixel(x, y).0;
+            out.extend_from_slice(&[b, g, r, a]);
+        }
+    }
+    out.resize(out.len() + (mask_stride * h) as usize, 0);
+    out
+}
+
+/// Four-character ICNS type holding a PNG of the given size.
+fn icns_type(size: u32) -> Option<&'static [u8; 4]> {
+    match size {
+        16 => Some(b"icp4"),
+        32 => Some(b"icp5"),
+        64 => Some(b"icp6"),
+        128 => Some(b"ic07"),
+        256 => Some(b"ic08"),
+        512 => Some(b"ic09"),
+        1024 => Some(b"ic10"),
+        _ => None,
+    }
+}
+
+pub fn encode_icns(data: &IconData) -> std::io::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    for entry in &data.entries {
+        let (w, h) = entry.dimensions();
+        let Some(kind) = icns_type(w).filter(|_| w == h) else {
+            continue;
+        };
+        let mut png = Vec::new();
+        PngEncoder::new(&mut png)
+            .write_image(entry.as_raw(), w, h, image::ExtendedColorType::Rgba8)
+            .map_err(std::io::Error::other)?;
+        body.extend_from_slice(kind);
+        body.extend_from_slice(&(png.len() as u32 + 8).to_be_bytes());
+        body.extend_from_slice(&png);
+    }
+    let mut out = Vec::with_capacity(body.len() + 8);
+    out.extend_from_slice(b"icns");
+    out.extend_from_slice(&(body.len() as u32 + 8).to_be_bytes());
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+// ============================================================================
+// ICO IMPORT
+// ============================================================================
+
+pub fn load_ico(path: &Path) -> Result<CanvasState, String> {
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    parse_ico(&data)
+}
+
+/// Read every entry of an ICO (or CUR) file as its own layer.
+pub fn parse_ico(data: &[u8]) -> Result<CanvasState, String> {
+    let u16_at = |at: usize| {
+        data.get(at..at + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+    };
+    let u32_at = |at: usize| {
+        data.get(at..at + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+    if u16_at(0) != Some(0) || !matches!(u16_at(2), Some(1 | 2)) {
+        return Err("Not an ICO file".to_string());
+    }
+    let count = u16_at(4).unwrap_or(0) as usize;
+
+    // (bits per pixel, image) per distinct size; the deepest entry wins.
+    let mut entries: Vec<(u16, RgbaImage)> = Vec::new();
+    for i in 0..count {
+        let dir = 6 + i * 16;
+        let (Some(entry), Some(size), Some(offset)) =
+            (data.get(dir..dir + 16), u32_at(dir + 8), u32_at(dir + 12))
+        else {
+            return Err("Truncated ICO directory".to_string());
+        };
+        let Some(payload) = (offset as usize)
+            .checked_add(size as usize)
+            .and_then(|end| data.get(offset as usize..end))
+        else {
+            return Err("Truncated ICO entry".to_string());
+        };
+        // Re-wrap the entry as a one-image icon so the decoder handles every
+        // BMP depth and the PNG case for us.
+        let mut single = vec![0, 0, 1, 0, 1, 0];
+        single.extend_from_slice(&entry[..8]);
+        single.extend_from_slice(&size.to_le_bytes());
+        single.extend_from_slice(&22u32.to_le_bytes());
+        single.extend_from_slice(payload);
+        let image = image::load_from_memory_with_format(&single, image::ImageFormat::Ico)
+            .map_err(|e| format!("Invalid ICO entry: {}", e))?
+            .to_rgba8();
+        let bpp = u16::from_le_bytes([entry[6], entry[7]]);
+        match entries
+            .iter_mut()
+            .find(|(_, e)| e.dimensions() == image.dimensions())
+        {
+            Some(existing) if existing.0 < bpp => *existing = (bpp, image),
+            Some(_) => {}
+            None => entries.push((bpp, image)),
+        }
+    }
+    if entries.is_empty() {
+        return Err("ICO file has no images".to_string());
+    }
+
+    let width = entries.iter().map(|(_, e)| e.width()).max().unwrap_or(1);
+    let height = entries.iter().map(|(_, e)| e.height()).max().unwrap_or(1);
+    crate::io::validate_open_dimensions(width, height)?;
+
+    // Largest entry at the bottom, smaller hand-tuned sizes stacked above it.
+    entries.sort_by_key(|(_, e)| std::cmp::Reverse(e.width() * e.height()));
+    let mut state = CanvasState::new(width, height);
+    state.layers.clear();
+    for (index, (_, image)) in entries.iter().enumerate() {
+        let (w, h) = image.dimensions();
+        let mut layer = Layer::new(format!("{}x{}", w, h), width, height, Rgba([0, 0, 0, 0]));
+        layer.pixels = TiledImage::from_region_rgba(
+            width,
+            height,
+            image.as_raw(),
+            w,
+            h,
+            ((width - w) / 2) as i32,
+            ((height - h) / 2) as i32,
+        );
+        layer.visible = index == 0;
+        state.layers.push(layer);
+    }
+    state.active_layer_index = 0;
+    state.composite_cache = None;
+    Ok(state)
+}
@@ -3,8 +3,8 @@ use crate::components::dialogs::TiffCompression;
 use crate::web_fs::File;
 use image::ImageEncoder;
 use image::codecs::bmp::BmpEncoder;
-use image::codecs::jpeg::JpegEncoder;
 use image::codecs::png::PngEncoder;
+use image::codecs::qoi::QoiEncoder;
 use image::codecs::tga::TgaEncoder;
 use image::{DynamicImage, ImageError, Rgba, RgbaImage};
 #[cfg(not(target_arch = "wasm32"))]
@@ -17,11 +17,17 @@ use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 
 use crate::canvas::{
-    BlendMode, CHUNK_SIZE, CanvasState, Layer, LayerContent, PixelFormat, TiledImage,
-    WebpFrameCompression,
+    BlendIf, BlendMode, CHUNK_SIZE, CanvasState, Layer, LayerComp, LayerCompEntry, LayerContent,
+    LayerFolder, LayerLocks, OffCanvasPixels, PixelFormat, Slice, TiledImage, WebpFrameCompression,
 };
 use crate::components::dialogs::SaveFormat;
-use crate::experimental::{DeepRgbaBuffer, f16_bits_to_f32, reinhard_tone_map_rgba};
+use crate::config::export_presets::{ExportDepth, ExportPreset, MetadataPolicy};
+use crate::dds_io::DdsOptions;
+use crate::experimental::{DeepRgbaBuffer, HdrTransfer, HdrView, f16_bits_to_f32, f32_to_f16_bits};
+use crate::hdr_io::ExrSamples;
+use crate::icon_io::IconOptions;
+use crate::jpeg_io::JpegOptions;
+use crate::tiff_io::TiffPixels;
 
 /// Minimum frame delay in milliseconds for animated images.
 const MIN_FRAME_DELAY_MS: u16 = 10;
@@ -37,6 +43,25 @@ pub fn is_raw_extension(ext: &str) -> bool {
     RAW_EXTENSIONS.contains(&ext.to_lowercase().as_str())
 }
 
+/// Project, layered and vector formats the Open dialog accepts (lowercase).
+pub const DOCUMENT_EXTENSIONS: &[&str] = &["pfe", "pdn", "psd", "psb", "ora", "exr", "hdr", "svg"];
+
+/// Flat image formats the Open dialog accepts, RAW aside (lowercase).
+pub const IMAGE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "webp", "bmp", "tga", "gif", "ico", "tiff", "tif", "qoi", "pgm", "ppm",
+    "pbm", "pnm", "pam", "dds",
+];
+
+/// Open dialog extensions that load from bytes alone, as the browser picker
+/// delivers them. Paint.NET projects and RAW files are decoded from a path.
+pub fn byte_open_extensions() -> impl Iterator<Item = &'static str> {
+    DOCUMENT_EXTENSIONS
+        .iter()
+        .chain(IMAGE_EXTENSIONS)
+        .copied()
+        .filter(|ext| *ext != "pdn")
+}
+
 /// Decode a RAW camera file to an sRGB RgbaImage.
 /// Uses rawloader for decoding and imagepipe for demosaicing + colour processing.
 /// Not available on web — imagepipe/rawloader are native-only (require a C
@@ -93,6 +118,20 @@ const PFE_MAGIC_V1: &str = "PFE1";
 const PFE_MAGIC_V2: &str = "PFE2";
 /// Magic header for experimental metadata, adjustment and format support (v3)
 const PFE_MAGIC_V3: &str = "PFE3";
+/// Magic header for nested layer groups with opacity, blend mode and masks (v4)
+const PFE_MAGIC_V4: &str = "PFE4";
+/// Magic header for per-layer locks (v5)
+const PFE_MAGIC_V5: &str = "PFE5";
+/// Magic header for off-canvas layer content and document bounds (v6)
+const PFE_MAGIC_V6: &str = "PFE6";
+/// Magic header for layer comps (v7)
+const PFE_MAGIC_V7: &str = "PFE7";
+/// Magic header for per-layer blending ranges (v8)
+const PFE_MAGIC_V8: &str = "PFE8";
+/// Magic header for linear-light blending (v9)
+const PFE_MAGIC_V9: &str = "PFE9";
+/// Magic header for slices (v10)
+const PFE_MAGIC_V10: &str = "PFEA";
 
 /// V0 (legacy) serializable project file structure
 #[derive(Serialize, Deserialize)]
@@ -176,12 +215,173 @@ pub(crate) struct ProjectFileV3 {
     height: u32,
     active_layer_index: usize,
     #[serde(default)]
-    folders: Vec<crate::canvas::LayerFolder>,
+    folders: Vec<FolderDataV3>,
     #[serde(default = "default_next_layer_folder_id")]
     next_layer_folder_id: u64,
     layers: Vec<LayerDataV3>,
 }
 
+/// V3 layer folder record — flat folders, no group compositing.
+#[derive(Serialize, Deserialize)]
+struct FolderDataV3 {
+    id: u64,
+    name: String,
+    visible: bool,
+    collapsed: bool,
+    #[serde(default)]
+    insert_above_layer: Option<usize>,
+    #[serde(default)]
+    color_index: Option<u8>,
+}
+
+impl FolderDataV3 {
+    fn from_folder(folder: &LayerFolder) -> Self {
+        Self {
+            id: folder.id,
+            name: folder.name.clone(),
+            visible: folder.visible,
+            collapsed: folder.collapsed,
+            insert_above_layer: folder.insert_above_layer,
+            color_index: folder.color_index,
+        }
+    }
+
+    fn into_folder(self) -> LayerFolder {
+        LayerFolder {
+            id: self.id,
+            name: self.name,
+            visible: self.visible,
+            collapsed: self.collapsed,
+            insert_above_layer: self.insert_above_layer,
+            color_index: self.color_index,
+            ..Default::default()
+        }
+    }
+}
+
+/// V4 project file — V3 layers plus nested layer groups.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ProjectFileV4 {
+    magic: String,
+    width: u32,
+    height: u32,
+    active_layer_index: usize,
+    folders: Vec<FolderDataV4>,
+    next_layer_folder_id: u64,
+    layers: Vec<LayerDataV3>,
+}
+
+/// V5 project file — V4 plus per-layer locks.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ProjectFileV5 {
+    magic: String,
+    width: u32,
+    height: u32,
+    active_layer_index: usize,
+    folders: Vec<FolderDataV4>,
+    next_layer_folder_id: u64,
+    layers: Vec<LayerDataV3>,
+    /// `LayerLocks::to_bits` per layer, parallel to `layers`.
+    layer_locks: Vec<u8>,
+}
+
+/// V6 project file — V5 plus off-canvas layer content and the infinite
+/// canvas document rectangle.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ProjectFileV6 {
+    magic: String,
+    width: u32,
+    height: u32,
+    active_layer_index: usize,
+    folders: Vec<FolderDataV4>,
+    next_layer_folder_id: u64,
+    layers: Vec<LayerDataV3>,
+    layer_locks: Vec<u8>,
+    /// Content outside the canvas per layer, parallel to `layers`.
+    off_canvas: Vec<Option<OffCanvasDataV6>>,
+    /// `(x, y, w, h)` when infinite canvas mode is on.
+    document_bounds: Option<(u32, u32, u32, u32)>,
+}
+
+/// V7 project file — V6 plus layer comps.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ProjectFileV7 {
+    base: ProjectFileV6,
+    layer_comps: Vec<LayerCompDataV7>,
+    /// `Layer::move_offset` per layer, parallel to `layers`.
+    layer_move_offsets: Vec<(i32, i32)>,
+}
+
+/// V8 project file — V7 plus per-layer blending ranges.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ProjectFileV8 {
+    base: ProjectFileV7,
+    /// `BlendIf::to_bytes` per layer, parallel to `layers`; `None` when the
+    /// layer uses the full ranges.
+    layer_blend_if: Vec<Option<[u8; 32]>>,
+}
+
+/// V9 project file — V8 plus the document's linear-light blending flag.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ProjectFileV9 {
+    base: ProjectFileV8,
+    linear_blending: bool,
+}
+
+/// V10 project file — V9 plus slices.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ProjectFileV10 {
+    base: ProjectFileV9,
+    slices: Vec<SliceDataV10>,
+}
+
+/// V10 slice record.
+#[derive(Serialize, Deserialize)]
+struct SliceDataV10 {
+    name: String,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+/// V7 layer comp record.
+#[derive(Serialize, Deserialize)]
+struct LayerCompDataV7 {
+    name: String,
+    entries: Vec<LayerCompEntryDataV7>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LayerCompEntryDataV7 {
+    layer_name: String,
+    visible: bool,
+    opacity: f32,
+    blend_mode: u8,
+    offset: Option<(i32, i32)>,
+}
+
+/// V6 off-canvas pixels: a sparse tiled image placed at `origin`.
+#[derive(Serialize, Deserialize)]
+struct OffCanvasDataV6 {
+    origin: (i32, i32),
+    width: u32,
+    height: u32,
+    chunks: Vec<ChunkData>,
+}
+
+/// V4 layer folder record: the V3 fields plus nesting and group compositing.
+#[derive(Serialize, Deserialize)]
+struct FolderDataV4 {
+    base: FolderDataV3,
+    parent_id: Option<u64>,
+    opacity: f32,
+    /// `None` = pass through, otherwise `BlendMode::to_u8`.
+    blend_mode: Option<u8>,
+    mask_enabled: bool,
+    mask: Option<Vec<ChunkData>>,
+}
+
 fn default_next_layer_folder_id() -> u64 {
     1
 }
@@ -249,6 +449,13 @@ pub enum PfeData {
     V1(ProjectFileV1),
     V2(ProjectFileV2),
     V3(ProjectFileV3),
+    V4(ProjectFileV4),
+    V5(ProjectFileV5),
+    V6(ProjectFileV6),
+    V7(ProjectFileV7),
+    V8(ProjectFileV8),
+    V9(ProjectFileV9),
+    V10(ProjectFileV10),
 }
 
 /// Build a serializable PFE project, auto-selecting V1 or V2 based on content.
@@ -272,7 +479,25 @@ pub fn build_pfe(state: &CanvasState) -> PfeData {
         .layers
         .iter()
         .any(|l| matches!(l.content, crate::canvas::LayerContent::Text(_)));
-    if has_experimental_layers || has_layer_folders {
+    let has_group_features = state.layer_folders.iter().any(|f| {
+        f.parent_id.is_some() || f.opacity < 1.0 || f.blend_mode.is_some() || f.mask.is_some()
+    });
+    if !state.slices.is_empty() {
+        PfeData::V10(build_pfe_v10(state))
+    } else if state.linear_blending {
+        PfeData::V9(build_pfe_v9(state))
+    } else if state.layers.iter().any(|l| !l.blend_if.is_default()) {
+        PfeData::V8(build_pfe_v8(state))
+    } else if !state.layer_comps.is_empty() {
+        PfeData::V7(build_pfe_v7(state))
+    } else if state.document_bounds.is_some() || state.layers.iter().any(|l| l.off_canvas.is_some())
+    {
+        PfeData::V6(build_pfe_v6(state))
+    } else if state.layers.iter().any(|l| l.locks.any()) {
+        PfeData::V5(build_pfe_v5(state))
+    } else if has_group_features {
+        PfeData::V4(build_pfe_v4(state))
+    } else if has_experimental_layers || has_layer_folders {
         PfeData::V3(build_pfe_v3(state))
     } else if has_text_layers {
         PfeData::V2(build_pfe_v2(state))
@@ -287,6 +512,13 @@ pub fn write_pfe(data: &PfeData, path: &Path) -> Result<(), PfeError> {
         PfeData::V1(project) => write_pfe_v1(project, path),
         PfeData::V2(project) => write_pfe_v2(project, path),
         PfeData::V3(project) => write_pfe_v3(project, path),
+        PfeData::V4(project) => write_pfe_v4(project, path),
+        PfeData::V5(project) => write_pfe_v5(project, path),
+        PfeData::V6(project) => write_pfe_v6(project, path),
+        PfeData::V7(project) => write_pfe_v7(project, path),
+        PfeData::V8(project) => write_pfe_v8(project, path),
+        PfeData::V9(project) => write_pfe_v9(project, path),
+        PfeData::V10(project) => write_pfe_v10(project, path),
     }
 }
 
@@ -452,7 +684,11 @@ pub fn build_pfe_v3(state: &CanvasState) -> ProjectFileV3 {
         width: state.width,
         height: state.height,
         active_layer_index: state.active_layer_index,
-        folders: state.layer_folders.clone(),
+        folders: state
+            .layer_folders
+            .iter()
+            .map(FolderDataV3::from_folder)
+            .collect(),
         next_layer_folder_id: state.next_layer_folder_id,
         layers,
     }
@@ -465,6 +701,241 @@ pub fn write_pfe_v3(project: &ProjectFileV3, path: &Path) -> Result<(), PfeError
     Ok(())
 }
 
+/// Build the v4 project data: v3 layers plus nested group folders.
+pub fn build_pfe_v4(state: &CanvasState) -> ProjectFileV4 {
+    let v3 = build_pfe_v3(state);
+    let folders = state
+        .layer_folders
+        .iter()
+        .map(|folder| FolderDataV4 {
+            base: FolderDataV3::from_folder(folder),
+            parent_id: folder.parent_id,
+            opacity: folder.opacity,
+            blend_mode: folder.blend_mode.map(BlendMode::to_u8),
+            mask_enabled: folder.mask_enabled,
+            mask: folder.mask.as_ref().map(tiled_to_chunks),
+        })
+        .collect();
+    ProjectFileV4 {
+        magic: PFE_MAGIC_V4.to_string(),
+        width: v3.width,
+        height: v3.height,
+        active_layer_index: v3.active_layer_index,
+        folders,
+        next_layer_folder_id: v3.next_layer_folder_id,
+        layers: v3.layers,
+    }
+}
+
+pub fn write_pfe_v4(project: &ProjectFileV4, path: &Path) -> Result<(), PfeError> {
+    let file = File::create(path)?;
+    let writer = BufWriter::new(file);
+    bincode::serialize_into(writer, &project)?;
+    Ok(())
+}
+
+/// Build the v5 project data: v4 plus per-layer locks.
+pub fn build_pfe_v5(state: &CanvasState) -> ProjectFileV5 {
+    let v4 = build_pfe_v4(state);
+    ProjectFileV5 {
+        magic: PFE_MAGIC_V5.to_string(),
+        width: v4.width,
+        height: v4.height,
+        active_layer_index: v4.active_layer_index,
+        folders: v4.folders,
+        next_layer_folder_id: v4.next_layer_folder_id,
+        layers: v4.layers,
+        layer_locks: state.layers.iter().map(|l| l.locks.to_bits()).collect(),
+    }
+}
+
+pub fn write_pfe_v5(project: &ProjectFileV5, path: &Path) -> Result<(), PfeError> {
+    let file = File::create(path)?;
+    let writer = BufWriter::new(file);
+    bincode::serialize_into(writer, &project)?;
+    Ok(())
+}
+
+/// Build the v6 project data: v5 plus off-canvas content and document bounds.
+pub fn build_pfe_v6(state: &CanvasState) -> ProjectFileV6 {
+    let v5 = build_pfe_v5(state);
+    ProjectFileV6 {
+        magic: PFE_MAGIC_V6.to_string(),
+        width: v5.width,
+        height: v5.height,
+        active_layer_index: v5.active_layer_index,
+        folders: v5.folders,
+        next_layer_folder_id: v5.next_layer_folder_id,
+        layers: v5.layers,
+        layer_locks: v5.layer_locks,
+        off_canvas: state
+            .layers
+            .iter()
+            .map(|l| {
+                l.off_canvas.as_ref().map(|off| OffCanvasDataV6 {
+                    origin: off.origin,
+                    width: off.pixels.width(),
+                    height: off.pixels.height(),
+                    chunks: tiled_to_chunks(&off.pixels),
+                })
+            })
+            .collect(),
+        document_bounds: state.document_bounds,
+    }
+}
+
+pub fn write_pfe_v6(project: &ProjectFileV6, path: &Path) -> Result<(), PfeError> {
+    let file = File::create(path)?;
+    let writer = BufWriter::new(file);
+    bincode::serialize_into(writer, &project)?;
+    Ok(())
+}
+
+/// Build the v7 project data: v6 plus layer comps.
+pub fn build_pfe_v7(state: &CanvasState) -> ProjectFileV7 {
+    let mut base = build_pfe_v6(state);
+    base.magic = PFE_MAGIC_V7.to_string();
+    ProjectFileV7 {
+        base,
+        layer_comps: state
+            .layer_comps
+            .iter()
+            .map(|comp| LayerCompDataV7 {
+                name: comp.name.clone(),
+                entries: comp
+                    .entries
+                    .iter()
+                    .map(|e| LayerCompEntryDataV7 {
+                        layer_name: e.layer_name.clone(),
+                        visible: e.visible,
+                        opacity: e.opacity,
+                        blend_mode: e.blend_mode.to_u8(),
+                        offset: e.offset,
+                    })
+                    .collect(),
+            })
+            .collect(),
+        layer_move_offsets: state.layers.iter().map(|l| l.move_offset).collect(),
+    }
+}
+
+pub fn write_pfe_v7(project: &ProjectFileV7, path: &Path) -> Result<(), PfeError> {
+    let file = File::create(path)?;
+    let writer = BufWriter::new(file);
+    bincode::serialize_into(writer, &project)?;
+    Ok(())
+}
+
+/// Build the v8 project data: v7 plus per-layer blending ranges.
+pub fn build_pfe_v8(state: &CanvasState) -> ProjectFileV8 {
+    let mut base = build_pfe_v7(state);
+    base.base.magic = PFE_MAGIC_V8.to_string();
+    ProjectFileV8 {
+        base,
+        layer_blend_if: state
+            .layers
+            .iter()
+            .map(|l| (!l.blend_if.is_default()).then(|| l.blend_if.to_bytes()))
+            .collect(),
+    }
+}
+
+pub fn write_pfe_v8(project: &ProjectFileV8, path: &Path) -> Result<(), PfeError> {
+    let file = File::create(path)?;
+    let writer = BufWriter::new(file);
+    bincode::serialize_into(writer, &project)?;
+    Ok(())
+}
+
+/// Build the v9 project data: v8 plus linear-light blending.
+pub fn build_pfe_v9(state: &CanvasState) -> ProjectFileV9 {
+    let mut base = build_pfe_v8(state);
+    base.base.base.magic = PFE_MAGIC_V9.to_string();
+    ProjectFileV9 {
+        base,
+        linear_blending: state.linear_blending,
+    }
+}
+
+pub fn write_pfe_v9(project: &ProjectFileV9, path: &Path) -> Result<(), PfeError> {
+    let file = File::create(path)?;
+    let writer = BufWriter::new(file);
+    bincode::serialize_into(writer, &project)?;
+    Ok(())
+}
+
+/// Build the v10 project data: v9 plus slices.
+pub fn build_pfe_v10(state: &CanvasState) -> ProjectFileV10 {
+    let mut base = build_pfe_v9(state);
+    base.base.base.base.magic = PFE_MAGIC_V10.to_string();
+    ProjectFileV10 {
+        base,
+        slices: state
+            .slices
+            .iter()
+            .map(|s| SliceDataV10 {
+                name: s.name.clone(),
+                x: s.x,
+                y: s.y,
+                width: s.width,
+                height: s.height,
+            })
+            .collect(),
+    }
+}
+
+pub fn write_pfe_v10(project: &ProjectFileV10, path: &Path) -> Result<(), PfeError> {
+    let file = File::create(path)?;
+    let writer = BufWriter::new(file);
+    bincode::serialize_into(writer, &project)?;
+    Ok(())
+}
+
+fn tiled_to_chunks(image: &TiledImage) -> Vec<ChunkData> {
+    image
+        .chunk_keys()
+        .filter_map(|(cx, cy)| {
+            image.get_chunk(cx, cy).map(|chunk| ChunkData {
+                cx,
+                cy,
+                pixels: chunk.as_raw().clone(),
+            })
+        })
+        .collect()
+}
+
+/// Rebuild a tiled image from serialized chunks; `what` names it in errors.
+fn chunks_to_tiled(
+    width: u32,
+    height: u32,
+    chunks: Vec<ChunkData>,
+    what: &str,
+) -> Result<TiledImage, PfeError> {
+    let expected_chunk_bytes = (CHUNK_SIZE * CHUNK_SIZE * 4) as usize;
+    let mut tiled = TiledImage::new(width, height);
+    for cd in chunks {
+        if cd.pixels.len() != expected_chunk_bytes {
+            return Err(PfeError::InvalidFormat(format!(
+                "Chunk ({},{}) in {} has {} bytes, expected {}",
+                cd.cx,
+                cd.cy,
+                what,
+                cd.pixels.len(),
+                expected_chunk_bytes,
+            )));
+        }
+        let chunk_img =
+            RgbaImage::from_raw(CHUNK_SIZE, CHUNK_SIZE, cd.pixels).ok_or_else(|| {
+                PfeError::InvalidFormat(format!(
+                    "Failed to reconstruct chunk ({},{}) for {}",
+                    cd.cx, cd.cy, what
+                ))
+            })?;
+        tiled.set_chunk(cd.cx, cd.cy, chunk_img);
+    }
+    Ok(tiled)
+}
+
 /// Load a .pfe project file (supports both v0 flat and v1 tiled formats)
 pub fn load_pfe(path: &Path) -> Result<CanvasState, PfeError> {
     let raw = std::fs::read(path)?;
@@ -484,6 +955,13 @@ pub fn load_pfe_from_bytes(raw: &[u8]) -> Result<CanvasState, PfeError> {
     let magic = std::str::from_utf8(&raw[8..12]).unwrap_or("");
 
     match magic {
+        PFE_MAGIC_V10 => load_pfe_v10(raw),
+        PFE_MAGIC_V9 => load_pfe_v9(raw),
+        PFE_MAGIC_V8 => load_pfe_v8(raw),
+        PFE_MAGIC_V7 => load_pfe_v7(raw),
+        PFE_MAGIC_V6 => load_pfe_v6(raw),
+        PFE_MAGIC_V5 => load_pfe_v5(raw),
+        PFE_MAGIC_V4 => load_pfe_v4(raw),
         PFE_MAGIC_V3 => load_pfe_v3(raw),
         PFE_MAGIC_V2 => load_pfe_v2(raw),
         PFE_MAGIC_V1 => load_pfe_v1(raw),
@@ -500,7 +978,7 @@ pub fn load_pfe_from_bytes(raw: &[u8]) -> Result<CanvasState, PfeError> {
 pub const MAX_OPEN_IMAGE_DIM: u32 = 25_000;
 const MAX_CANVAS_DIM: u32 = MAX_OPEN_IMAGE_DIM;
 /// Maximum number of layers in a project file.
-const MAX_LAYERS: usize = 256;
+pub(crate) const MAX_LAYERS: usize = 256;
 
 pub fn validate_open_dimensions(width: u32, height: u32) -> Result<(), String> {
     if width == 0 || height == 0 {
@@ -515,6 +993,586 @@ pub fn validate_open_dimensions(width: u32, height: u32) -> Result<(), String> {
     Ok(())
 }
 
+// ============================================================================
+// OPENRASTER (.ora) AND LAYERED INTERCHANGE
+// ============================================================================
+
+const ORA_MIMETYPE: &str = "image/openraster";
+const ORA_THUMBNAIL_MAX: u32 = 256;
+
+/// Layer-preserving snapshot for the interchange formats (PSD, OpenRaster,
+/// OpenEXR). Built on the UI thread, then encoded and written on a
+/// background thread.
+pub enum LayeredExport {
+    Psd(crate::psd::PsdData),
+    Ora(OraData),
+    Exr(crate::hdr_io::ExrData),
+    Ico(crate::icon_io::IconData),
+    Icns(crate::icon_io::IconData),
+    Tiff(crate::tiff_io::TiffData),
+    Dds(crate::dds_io::DdsData),
+    Jpeg(crate::jpeg_io::JpegData),
+}
+
+/// Per-format settings a layered save needs from the save dialog.
+#[derive(Clone, Debug, Default)]
+pub struct LayeredExportOptions {
+    pub icon: IconOptions,
+    /// Write TIFF layers as pages; otherwise TIFF saves the composite.
+    pub tiff_pages: bool,
+    pub tiff_compression: TiffCompression,
+    /// JPEG quality for JPEG files and JPEG-compressed TIFF pages.
+    pub quality: u8,
+    /// Block compression and mipmaps for DDS textures.
+    pub dds: DdsOptions,
+    /// Progressive, subsampling and target size settings for JPEG files.
+    pub jpeg: JpegOptions,
+}
+
+impl LayeredExport {
+    /// Whether saving `format` with `options` goes through a layered export.
+    pub fn handles(format: SaveFormat, options: &LayeredExportOptions) -> bool {
+        format != SaveFormat::Pfe
+            && (format.supports_layers()
+                || format.is_icon()
+                || format == SaveFormat::Dds
+                || format == SaveFormat::Jpeg
+                || (format == SaveFormat::Tiff && options.tiff_pages))
+    }
+
+    /// Snapshot `state` for `format`; `None` for formats without layers (and PFE,
+    /// which has its own project path). Icon formats render their entries from
+    /// the layer stack using `options.icon`.
+    pub fn build(
+        format: SaveFormat,
+        state: &CanvasState,
+        options: &LayeredExportOptions,
+    ) -> Option<Self> {
+        match format {
+            SaveFormat::Psd => Some(Self::Psd(crate::psd::build_psd(state))),
+            SaveFormat::Ora => Some(Self::Ora(build_ora(state))),
+            SaveFormat::Exr => Some(Self::Exr(crate::hdr_io::build_exr(state))),
+            SaveFormat::Ico => Some(Self::Ico(crate::icon_io::build_ico(state, &options.icon))),
+            SaveFormat::Icns => Some(Self::Icns(crate::icon_io::build_icns(state, &options.icon))),
+            SaveFormat::Tiff if options.tiff_pages => Some(Self::Tiff(crate::tiff_io::build_tiff(
+                state,
+                options.tiff_compression,
+                options.quality,
+            ))),
+            SaveFormat::Dds => Some(Self::Dds(crate::dds_io::build_dds(state, &options.dds))),
+            SaveFormat::Jpeg => Some(Self::Jpeg(crate::jpeg_io::build_jpeg(
+                state,
+                options.quality,
+                &options.jpeg,
+            ))),
+            _ => None,
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        match self {
+            Self::Psd(data) => crate::psd::write_psd(data, path),
+            Self::Ora(data) => write_ora(data, path),
+            Self::Exr(data) => crate::hdr_io::write_exr(data, path),
+            Self::Ico(data) => crate::icon_io::write_ico(data, path),
+            Self::Icns(data) => crate::icon_io::write_icns(data, path),
+            Self::Tiff(data) => crate::tiff_io::write_tiff(data, path),
+            Self::Dds(data) => crate::dds_io::write_dds(data, path),
+            Self::Jpeg(data) => crate::jpeg_io::write_jpeg(data, path),
+        }
+    }
+}
+
+/// Parse an in-memory layered or float document (`.psd`, `.psb`, `.ora`,
+/// `.exr`, `.hdr`, multi-entry `.ico`, multi-page `.tif`), a `.dds` texture or
+/// an `.svg` rendered at its intrinsic size, or `None` when `ext` isn't one.
+pub fn parse_layered_document(ext: &str, data: &[u8]) -> Option<Result<CanvasState, String>> {
+    match ext.to_ascii_lowercase().as_str() {
+        "psd" | "psb" => Some(crate::psd::parse_psd(data)),
+        "ora" => Some(parse_ora(data)),
+        "exr" => Some(crate::hdr_io::parse_exr(data)),
+        "hdr" => Some(crate::hdr_io::parse_radiance(data)),
+        "ico" => Some(crate::icon_io::parse_ico(data)),
+        "tif" | "tiff" => Some(crate::tiff_io::parse_tiff(data, "Background")),
+        "dds" => Some(crate::dds_io::parse_dds(data, "Background")),
+        "svg" => Some(crate::svg_io::parse_svg(
+            data,
+            "Background",
+            &crate::svg_io::SvgOptions::default(),
+        )),
+        _ => None,
+    }
+}
+
+/// Read an OpenRaster file into a layered [`CanvasState`].
+pub fn load_ora(path: &Path) -> Result<CanvasState, String> {
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    parse_ora(&data)
+}
+
+/// Attributes shared by `<layer>` and `<stack>` elements.
+struct OraAttrs {
+    name: String,
+    src: String,
+    x: i64,
+    y: i64,
+    opacity: f32,
+    visible: bool,
+    composite_op: String,
+    /// Stack `isolation="isolate"`.
+    isolate: bool,
+}
+
+enum OraNode {
+    Layer(OraAttrs),
+    Stack(OraAttrs, Vec<OraNode>),
+}
+
+/// Parse OpenRaster bytes: `stack.xml` plus one PNG per layer. Nested stacks
+/// become nested [`LayerFolder`]s; isolated stacks, and stacks with a blend
+/// mode of their own, composite as isolated groups, the rest pass through.
+pub fn parse_ora(data: &[u8]) -> Result<CanvasState, String> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data))
+        .map_err(|e| format!("Not an OpenRaster file: {}", e))?;
+    let xml = {
+        let mut entry = archive
+            .by_name("stack.xml")
+            .map_err(|_| "OpenRaster file has no stack.xml".to_string())?;
+        let mut xml = String::new();
+        std::io::Read::read_to_string(&mut entry, &mut xml).map_err(|e| e.to_string())?;
+        xml
+    };
+    let (width, height, root) = parse_ora_stack_xml(&xml)?;
+    validate_open_dimensions(width, height)?;
+
+    let mut state = CanvasState::new(width, height);
+    state.layers.clear();
+    add_ora_nodes(&mut state, &mut archive, &root, None)?;
+    if state.layers.is_empty() {
+        state.layers.push(Layer::new(
+            "Background".to_string(),
+            width,
+            height,
+            Rgba([0, 0, 0, 0]),
+        ));
+    }
+    state.active_layer_index = state.layers.len() - 1;
+    state.composite_cache = None;
+    Ok(state)
+}
+
+fn parse_ora_stack_xml(xml: &str) -> Result<(u32, u32, Vec<OraNode>), String> {
+    use quick_xml::events::{BytesStart, Event};
+
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let attrs = |e: &BytesStart, decoder| -> Result<OraAttrs, String> {
+        let mut out = OraAttrs {
+            name: String::new(),
+            src: String::new(),
+            x: 0,
+            y: 0,
+            opacity: 1.0,
+            visible: true,
+            composite_op: String::new(),
+            isolate: false,
+        };
+        for attr in e.attributes() {
+            let attr = attr.map_err(|e| e.to_string())?;
+            let value = attr
+                .decode_and_unescape_value(decoder)
+                .map_err(|e| e.to_string())?;
+            match attr.key.as_ref() {
+                b"name" => out.name = value.into_owned(),
+                b"src" => out.src = value.into_owned(),
+                b"x" => out.x = value.trim().parse().unwrap_or(0),
+                b"y" => out.y = value.trim().parse().unwrap_or(0),
+                b"opacity" => out.opacity = value.trim().parse().unwrap_or(1.0),
+                b"visibility" => out.visible = value.trim() != "hidden",
+                b"composite-op" => out.composite_op = value.into_owned(),
+                b"isolation" => out.isolate = value.trim() == "isolate",
+                _ => {}
+            }
+        }
+        Ok(out)
+    };
+
+    let mut size = None;
+    // Open stacks; the bottom entry collects the root stack's children.
+    let mut stacks: Vec<(Option<OraAttrs>, Vec<OraNode>)> = Vec::new();
+    let mut root = None;
+    loop {
+        let event = reader.read_event().map_err(|e| e.to_string())?;
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let empty = matches!(event, Event::Empty(_));
+                match e.name().as_ref() {
+                    b"image" => {
+                        let (mut w, mut h) = (0, 0);
+                        for attr in e.attributes().flatten() {
+                            let value = String::from_utf8_lossy(&attr.value).trim().to_string();
+                            match attr.key.as_ref() {
+                                b"w" => w = value.parse().unwrap_or(0),
+                                b"h" => h = value.parse().unwrap_or(0),
+                                _ => {}
+                            }
+                        }
+                        size = Some((w, h));
+                    }
+                    b"stack" => {
+                        let attrs = (!stacks.is_empty())
+                            .then(|| attrs(e, reader.decoder()))
+                            .transpose()?;
+                        if empty {
+                            if let Some(attrs) = attrs
+                                && let Some((_, children)) = stacks.last_mut()
+                            {
+                                children.push(OraNode::Stack(attrs, Vec::new()));
+                            }
+                        } else {
+                            stacks.push((attrs, Vec::new()));
+                        }
+                    }
+                    b"layer" => {
+                        let layer = attrs(e, reader.decoder())?;
+                        if let Some((_, children)) = stacks.last_mut() {
+                            children.push(OraNode::Layer(layer));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(ref e) if e.name().as_ref() == b"stack" => {
+                let Some((attrs, children)) = stacks.pop() else {
+                    continue;
+                };
+                match (attrs, stacks.last_mut()) {
+                    (Some(attrs), Some((_, parent))) => {
+                        parent.push(OraNode::Stack(attrs, children));
+                    }
+                    _ => root = Some(children),
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    let (w, h) = size.ok_or("stack.xml has no <image> element")?;
+    Ok((w, h, root.unwrap_or_default()))
+}
+
+/// Add `nodes` (listed top to bottom, as in `stack.xml`) bottom-up.
+fn add_ora_nodes<R: std::io::Read + std::io::Seek>(
+    state: &mut CanvasState,
+    archive: &mut zip::ZipArchive<R>,
+    nodes: &[OraNode],
+    folder_id: Option<u64>,
+) -> Result<(), String> {
+    for node in nodes.iter().rev() {
+        match node {
+            OraNode::Layer(attrs) => {
+                if state.layers.len() >= MAX_LAYERS {
+                    return Err(format!(
+                        "OpenRaster file has more than {} layers",
+                        MAX_LAYERS
+                    ));
+                }
+                let mut pixels = RgbaImage::new(state.width, state.height);
+                if let Ok(mut entry) = archive.by_name(&attrs.src) {
+                    let mut png = Vec::new();
+                    std::io::Read::read_to_end(&mut entry, &mut png).map_err(|e| e.to_string())?;
+                    let image = image::load_from_memory(&png)
+                        .map_err(|e| format!("Layer '{}': {}", attrs.name, e))?
+                        .to_rgba8();
+                    image::imageops::replace(&mut pixels, &image, attrs.x, attrs.y);
+                }
+                let mut layer = Layer::new(
+                    attrs.name.clone(),
+                    state.width,
+                    state.height,
+                    Rgba([0, 0, 0, 0]),
+                );
+                layer.pixels = TiledImage::from_rgba_image(&pixels);
+                layer.visible = attrs.visible;
+                layer.opacity = attrs.opacity.clamp(0.0, 1.0);
+                layer.blend_mode = ora_blend_mode(&attrs.composite_op);
+                layer.folder_id = folder_id;
+                layer.source_metadata.source_format = Some("ora".to_string());
+                state.layers.push(layer);
+            }
+            OraNode::Stack(attrs, children) => {
+                let id = state.next_layer_folder_id;
+                state.next_layer_folder_id += 1;
+                let first_layer = state.layers.len();
+                let mode = ora_blend_mode(&attrs.composite_op);
+                state.layer_folders.push(LayerFolder {
+                    id,
+                    name: if attrs.name.is_empty() {
+                        format!("Group {}", state.layer_folders.len() + 1)
+                    } else {
+                        attrs.name.clone()
+                    },
+                    visible: attrs.visible,
+                    parent_id: folder_id,
+                    opacity: attrs.opacity.clamp(0.0, 1.0),
+                    blend_mode: (attrs.isolate || mode != BlendMode::Normal).then_some(mode),
+                    ..Default::default()
+                });
+                add_ora_nodes(state, archive, children, Some(id))?;
+                if state.layers.len() == first_layer
+                    && let Some(folder) = state.layer_folders.iter_mut().find(|f| f.id == id)
+                {
+                    folder.insert_above_layer = first_layer.checked_sub(1);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn ora_blend_mode(op: &str) -> BlendMode {
+    match op {
+        "svg:multiply" => BlendMode::Multiply,
+        "svg:screen" => BlendMode::Screen,
+        "svg:overlay" => BlendMode::Overlay,
+        "svg:darken" => BlendMode::Darken,
+        "svg:lighten" => BlendMode::Lighten,
+        "svg:color-dodge" => BlendMode::ColorDodge,
+        "svg:color-burn" => BlendMode::ColorBurn,
+        "svg:hard-light" => BlendMode::HardLight,
+        "svg:soft-light" => BlendMode::SoftLight,
+        "svg:difference" => BlendMode::Difference,
+        "svg:exclusion" => BlendMode::Exclusion,
+        "svg:plus" => BlendMode::Additive,
+        "svg:src" => BlendMode::Overwrite,
+        "svg:hue" => BlendMode::Hue,
+        "svg:saturation" => BlendMode::Saturation,
+        "svg:color" => BlendMode::Color,
+        "svg:luminosity" => BlendMode::Luminosity,
+        "krita:reflect" => BlendMode::Reflect,
+        "krita:glow" => BlendMode::Glow,
+        "krita:negation" => BlendMode::Negation,
+        "krita:xor" => BlendMode::Xor,
+        "krita:subtract" => BlendMode::Subtract,
+        "krita:divide" => BlendMode::Divide,
+        "krita:linear_burn" => BlendMode::LinearBurn,
+        "krita:vivid_light" => BlendMode::VividLight,
+        "krita:linear_light" => BlendMode::LinearLight,
+        "krita:pin_light" => BlendMode::PinLight,
+        "krita:hard_mix" => BlendMode::HardMix,
+        "krita:darker color" => BlendMode::DarkerColor,
+        "krita:lighter color" => BlendMode::LighterColor,
+        "krita:dissolve" => BlendMode::Dissolve,
+        _ => BlendMode::Normal,
+    }
+}
+
+/// `composite-op` for a blend mode: the OpenRaster `svg:` set where one
+/// exists, Krita's extensions otherwise.
+fn ora_composite_op(mode: BlendMode) -> &'static str {
+    match mode {
+        BlendMode::Normal => "svg:src-over",
+        BlendMode::Multiply => "svg:multiply",
+        BlendMode::Screen => "svg:screen",
+        BlendMode::Overlay => "svg:overlay",
+        BlendMode::Darken => "svg:darken",
+        BlendMode::Lighten => "svg:lighten",
+        BlendMode::ColorDodge => "svg:color-dodge",
+        BlendMode::ColorBurn => "svg:color-burn",
+        BlendMode::HardLight => "svg:hard-light",
+        BlendMode::SoftLight => "svg:soft-light",
+        BlendMode::Difference => "svg:difference",
+        BlendMode::Exclusion => "svg:exclusion",
+        BlendMode::Additive => "svg:plus",
+        BlendMode::Overwrite => "svg:src",
+        BlendMode::Reflect => "krita:reflect",
+        BlendMode::Glow => "krita:glow",
+        BlendMode::Negation => "krita:negation",
+        BlendMode::Xor => "krita:xor",
+        BlendMode::Subtract => "krita:subtract",
+        BlendMode::Divide => "krita:divide",
+        BlendMode::LinearBurn => "krita:linear_burn",
+        BlendMode::VividLight => "krita:vivid_light",
+        BlendMode::LinearLight => "krita:linear_light",
+        BlendMode::PinLight => "krita:pin_light",
+        BlendMode::HardMix => "krita:hard_mix",
+        BlendMode::Hue => "svg:hue",
+        BlendMode::Saturation => "svg:saturation",
+        BlendMode::Color => "svg:color",
+        BlendMode::Luminosity => "svg:luminosity",
+        BlendMode::DarkerColor => "krita:darker color",
+        BlendMode::LighterColor => "krita:lighter color",
+        BlendMode::Dissolve => "krita:dissolve",
+    }
+}
+
+/// Snapshot of a canvas ready to write as OpenRaster.
+pub struct OraData {
+    stack_xml: String,
+    /// `(src, pixels)` for each layer PNG.
+    layers: Vec<(String, RgbaImage)>,
+    composite: RgbaImage,
+}
+
+/// Save `state` as OpenRaster.
+pub fn save_ora(state: &CanvasState, path: &Path) -> std::io::Result<()> {
+    write_ora(&build_ora(state), path)
+}
+
+/// Snapshot layers (cropped to their painted bounds, masks applied), folders
+/// and the composite. Text layers should be rasterized first.
+pub fn build_ora(state: &CanvasState) -> OraData {
+    let mut layers = Vec::new();
+    let mut layer_xml = |index: usize, indent: &str, xml: &mut String| {
+        let layer = &state.layers[index];
+        let src = format!("data/layer{}.png", index);
+        let (x, y, image) = crop_to_content(&layer.to_masked_rgba_image());
+        xml.push_str(&format!(
+            "{}<layer name=\"{}\" src=\"{}\" x=\"{}\" y=\"{}\" opacity=\"{:.3}\" visibility=\"{}\" composite-op=\"{}\"/>\n",
+            indent,
+            quick_xml::escape::escape(layer.name.as_str()),
+            src,
+            x,
+            y,
+            layer.opacity.clamp(0.0, 1.0),
+            if layer.visible { "visible" } else { "hidden" },
+            ora_composite_op(layer.blend_mode),
+        ));
+        layers.push((src, image));
+    };
+
+    // Nested stacks, top to bottom as stack.xml lists them.
+    fn write_nodes(
+        state: &CanvasState,
+        nodes: &[crate::canvas::LayerTreeNode],
+        depth: usize,
+        xml: &mut String,
+        layer_xml: &mut dyn FnMut(usize, &str, &mut String),
+    ) {
+        use crate::canvas::LayerTreeNode;
+        let indent = "  ".repeat(depth + 2);
+        for node in nodes.iter().rev() {
+            match node {
+                LayerTreeNode::Layer(index) => layer_xml(*index, &indent, xml),
+                LayerTreeNode::Folder(id, children) => {
+                    let Some(folder) = state.layer_folder(*id) else {
+                        write_nodes(state, children, depth, xml, layer_xml);
+                        continue;
+                    };
+                    xml.push_str(&format!(
+                        "{}<stack name=\"{}\" visibility=\"{}\" opacity=\"{:.3}\" composite-op=\"{}\" isolation=\"{}\">\n",
+                        indent,
+                        quick_xml::escape::escape(folder.name.as_str()),
+                        if folder.visible { "visible" } else { "hidden" },
+                        folder.opacity.clamp(0.0, 1.0),
+                        ora_composite_op(folder.blend_mode.unwrap_or_default()),
+                        if folder.blend_mode.is_some() { "isolate" } else { "auto" },
+                    ));
+                    write_nodes(state, children, depth + 1, xml, layer_xml);
+                    xml.push_str(&format!("{}</stack>\n", indent));
+                }
+            }
+        }
+    }
+
+    let mut xml = format!(
+        "<?xml version='1.0' encoding='UTF-8'?>\n<image version=\"0.0.5\" w=\"{}\" h=\"{}\">\n  <stack>\n",
+        state.width, state.height
+    );
+    write_nodes(state, &state.layer_tree(), 0, &mut xml, &mut layer_xml);
+    xml.push_str("  </stack>\n</image>\n");
+
+    OraData {
+        stack_xml: xml,
+        layers,
+        composite: state.composite(),
+    }
+}
+
+/// Crop to the bounding box of non-transparent pixels, returning its offset.
+/// Fully transparent images become a single transparent pixel at the origin.
+fn crop_to_content(image: &RgbaImage) -> (u32, u32, RgbaImage) {
+    let mut bounds: Option<(u32, u32, u32, u32)> = None;
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if pixel[3] == 0 {
+            continue;
+        }
+        bounds = Some(match bounds {
+            Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x + 1), y1.max(y + 1)),
+            None => (x, y, x + 1, y + 1),
+        });
+    }
+    match bounds {
+        Some((x0, y0, x1, y1)) => (
+            x0,
+            y0,
+            image::imageops::crop_imm(image, x0, y0, x1 - x0, y1 - y0).to_image(),
+        ),
+        None => (0, 0, RgbaImage::new(1, 1)),
+    }
+}
+
+/// Write a snapshot from [`build_ora`] to `path`.
+pub fn write_ora(data: &OraData, path: &Path) -> std::io::Result<()> {
+    let bytes = encode_ora(data)?;
+    let mut file = File::create(path)?;
+    file.write_all(&bytes)
+}
+
+fn encode_ora(data: &OraData) -> std::io::Result<Vec<u8>> {
+    use zip::write::SimpleFileOptions;
+
+    let png = |image: &RgbaImage| -> std::io::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        PngEncoder::new(&mut out)
+            .write_image(
+                image.as_raw(),
+                image.width(),
+                image.height(),
+                image::ExtendedColorType::Rgba8,
+            )
+            .map_err(std::io::Error::other)?;
+        Ok(out)
+    };
+    let stored = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let deflated = SimpleFileOptions::default();
+
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    // The mimetype must be the first entry, uncompressed.
+    zip.start_file("mimetype", stored)?;
+    zip.write_all(ORA_MIMETYPE.as_bytes())?;
+    zip.start_file("stack.xml", deflated)?;
+    zip.write_all(data.stack_xml.as_bytes())?;
+    for (src, image) in &data.layers {
+        zip.start_file(src.as_str(), stored)?;
+        zip.write_all(&png(image)?)?;
+    }
+    zip.start_file("mergedimage.png", stored)?;
+    zip.write_all(&png(&data.composite)?)?;
+    let (w, h) = data.composite.dimensions();
+    let scale = (ORA_THUMBNAIL_MAX as f32 / w.max(h) as f32).min(1.0);
+    let thumbnail = image::imageops::thumbnail(
+        &data.composite,
+        ((w as f32 * scale) as u32).max(1),
+        ((h as f32 * scale) as u32).max(1),
+    );
+    zip.start_file("Thumbnails/thumbnail.png", stored)?;
+    zip.write_all(&png(&thumbnail)?)?;
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Encode a single flattened image as a one-layer OpenRaster file.
+pub fn encode_flat_ora(image: &RgbaImage) -> std::io::Result<Vec<u8>> {
+    encode_ora(&OraData {
+        stack_xml: format!(
+            "<?xml version='1.0' encoding='UTF-8'?>\n<image version=\"0.0.5\" w=\"{}\" h=\"{}\">\n  <stack>\n    <layer name=\"Background\" src=\"data/layer0.png\" x=\"0\" y=\"0\" opacity=\"1.000\" visibility=\"visible\" composite-op=\"svg:src-over\"/>\n  </stack>\n</image>\n",
+            image.width(),
+            image.height()
+        ),
+        layers: vec![("data/layer0.png".to_string(), image.clone())],
+        composite: image.clone(),
+    })
+}
+
 // ============================================================================
 // SYNCHRONOUS IMAGE LOADER (CLI / headless mode)
 // ============================================================================
@@ -528,9 +1586,260 @@ pub fn autosave_dir() -> Option<std::path::PathBuf> {
     crate::assets::AppSettings::settings_path().and_then(|p| p.parent().map(|d| d.join("autosave")))
 }
 
+/// Longest edge of the thumbnail stored next to each autosave.
+pub const AUTOSAVE_THUMBNAIL_EDGE: u32 = 128;
+
+/// Sidecar written next to every `<project id>.autosave.pfe`.
+///
+/// Autosaves of projects that were closed cleanly are deleted, so any entry
+/// still on disk whose session is no longer running belongs to a session that
+/// crashed and is offered for recovery with the original name, path and dirty
+/// state.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AutosaveManifest {
+    /// `Project::id` of the autosaved project, hyphenated.
+    pub project_id: String,
+    /// [`AutosaveSession::id`] of the app instance that wrote the autosave.
+    /// `None` for manifests written before sessions were recorded.
+    #[serde(default)]
+    pub session: Option<String>,
+    pub name: String,
+    /// File the project was opened from / last saved to (`None` when untitled).
+    pub original_path: Option<PathBuf>,
+    pub is_dirty: bool,
+    /// Seconds since the Unix epoch when the autosave was written.
+    pub saved_at: u64,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// An autosave found on disk, as listed by [`list_autosaves`].
+#[derive(Clone, Debug)]
+pub struct AutosaveEntry {
+    pub manifest: AutosaveManifest,
+    pub pfe_path: PathBuf,
+    /// `None` when the thumbnail was never written or has been removed.
+    pub thumbnail_path: Option<PathBuf>,
+}
+
+/// Marks the autosaves of one running app instance as owned.
+///
+/// Holds an exclusive lock on `<session id>.session.lock` in the autosave
+/// directory for the life of the process. The OS drops the lock when the
+/// process exits, crashes included, so [`list_autosaves`] can tell autosaves
+/// of another live instance apart from those left behind by a crash.
+#[derive(Debug)]
+pub struct AutosaveSession {
+    id: String,
+    lock_path: PathBuf,
+    lock: Option<std::fs::File>,
+}
+
+impl AutosaveSession {
+    /// Create and lock a new session file in `dir`, and delete the lock files
+    /// of sessions that are no longer running.
+    pub fn start(dir: &Path) -> std::io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        remove_stale_session_locks(dir);
+        let id = uuid::Uuid::new_v4().to_string();
+        let lock_path = session_lock_path(dir, &id);
+        let mut lock = std::fs::File::create(&lock_path)?;
+        lock.try_lock().map_err(std::io::Error::from)?;
+        // The PID is only informational; the lock is what marks the session live.
+        let _ = std::io::Write::write_all(&mut lock, std::process::id().to_string().as_bytes());
+        Ok(Self {
+            id,
+            lock_path,
+            lock: Some(lock),
+        })
+    }
+
+    /// Value stored in [`AutosaveManifest::session`] by this instance.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl Drop for AutosaveSession {
+    fn drop(&mut self) {
+        // Close (and unlock) first; Windows cannot delete an open file.
+        drop(self.lock.take());
+        let _ = std::fs::remove_file(&self.lock_path);
+    }
+}
+
+fn session_lock_path(dir: &Path, session: &str) -> PathBuf {
+    dir.join(format!("{}.session.lock", session))
+}
+
+/// True while the instance that started `session` is still running.
+fn session_is_live(dir: &Path, session: &str) -> bool {
+    let Ok(file) = std::fs::File::open(session_lock_path(dir, session)) else {
+        return false;
+    };
+    matches!(file.try_lock(), Err(std::fs::TryLockError::WouldBlock))
+}
+
+fn remove_stale_session_locks(dir: &Path) {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for dir_entry in read_dir.flatten() {
+        let path = dir_entry.path();
+        let Some(session) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_suffix(".session.lock"))
+        else {
+            continue;
+        };
+        if !session_is_live(dir, session) {
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+}
+
+fn autosave_file(dir: &Path, project_id: &str, suffix: &str) -> PathBuf {
+    dir.join(format!("{}.autosave.{}", project_id, suffix))
+}
+
+/// Path of the autosaved project data for `project_id`.
+pub fn autosave_pfe_path(dir: &Path, project_id: &str) -> PathBuf {
+    autosave_file(dir, project_id, "pfe")
+}
+
+/// Current wall-clock time as seconds since the Unix epoch.
+pub fn unix_time_now() -> u64 {
+    crate::time_compat::SystemTime::now()
+        .duration_since(crate::time_compat::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Small composite preview used by the recovery dialog.
+/// Must run on the main thread; the result can be moved to a writer thread.
+pub fn autosave_thumbnail(state: &CanvasState) -> RgbaImage {
+    let lod = state.composite_lod();
+    let (w, h) = (lod.width().max(1), lod.height().max(1));
+    let scale = AUTOSAVE_THUMBNAIL_EDGE as f32 / w.max(h) as f32;
+    if scale >= 1.0 {
+        return lod;
+    }
+    let nw = ((w as f32 * scale).round() as u32).max(1);
+    let nh = ((h as f32 * scale).round() as u32).max(1);
+    image::imageops::resize(&lod, nw, nh, image::imageops::FilterType::Triangle)
+}
+
+/// Write one project's autosave: project data, then thumbnail, then manifest.
+/// The manifest goes last so a crash mid-write never leaves an entry that
+/// points at a truncated project file.
+pub fn write_autosave(
+    dir: &Path,
+    manifest: &AutosaveManifest,
+    data: &PfeData,
+    thumbnail: Option<&RgbaImage>,
+) -> Result<(), PfeError> {
+    std::fs::create_dir_all(dir)?;
+    let id = manifest.project_id.as_str();
+
+    let pfe_path = autosave_pfe_path(dir, id);
+    let tmp_path = autosave_file(dir, id, "pfe.tmp");
+    write_pfe(data, &tmp_path)?;
+    std::fs::rename(&tmp_path, &pfe_path)?;
+
+    if let Some(thumb) = thumbnail {
+        // A missing thumbnail only degrades the recovery dialog; not fatal.
+        let _ = thumb.save_with_format(autosave_file(dir, id, "png"), image::ImageFormat::Png);
+    }
+
+    let json =
+        serde_json::to_vec_pretty(manifest).map_err(|e| PfeError::Serialize(e.to_string()))?;
+    std::fs::write(autosave_file(dir, id, "json"), json)?;
+    Ok(())
+}
+
+/// List every autosave in `dir` that no running instance owns, newest first.
+///
+/// Autosaves whose [`AutosaveSession`] is still live belong to another open
+/// window and are skipped. Autosaves written before manifests existed
+/// (`<name>.autosave.pfe` with no sidecar) are listed too, as untitled dirty
+/// projects named after the file.
+pub fn list_autosaves(dir: &Path) -> Vec<AutosaveEntry> {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut entries = Vec::new();
+    for dir_entry in read_dir.flatten() {
+        let path = dir_entry.path();
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let Some(stem) = file_name.strip_suffix(".autosave.pfe") else {
+            continue;
+        };
+        let manifest_path = autosave_file(dir, stem, "json");
+        let manifest = std::fs::read(&manifest_path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<AutosaveManifest>(&bytes).ok());
+        let manifest = match manifest {
+            Some(m) if m.project_id == stem => m,
+            _ => AutosaveManifest {
+                project_id: stem.to_string(),
+                session: None,
+                name: stem.to_string(),
+                original_path: None,
+                is_dirty: true,
+                saved_at: dir_entry
+                    .metadata()
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+                width: 0,
+                height: 0,
+            },
+        };
+        if let Some(session) = &manifest.session
+            && session_is_live(dir, session)
+        {
+            continue;
+        }
+        let thumbnail_path = Some(autosave_file(dir, stem, "png")).filter(|p| p.is_file());
+        entries.push(AutosaveEntry {
+            manifest,
+            pfe_path: path,
+            thumbnail_path,
+        });
+    }
+    entries.sort_by_key(|e| std::cmp::Reverse(e.manifest.saved_at));
+    entries
+}
+
+/// Delete every file belonging to the autosave of `project_id`.
+pub fn remove_autosave(dir: &Path, project_id: &str) {
+    for suffix in ["pfe", "pfe.tmp", "png", "json"] {
+        let _ = std::fs::remove_file(autosave_file(dir, project_id, suffix));
+    }
+}
+
+/// Human-readable age of an autosave ("just now", "5 minutes ago", ...).
+pub fn format_autosave_age(saved_at: u64, now: u64) -> String {
+    let secs = now.saturating_sub(saved_at);
+    match secs {
+        0..=59 => t!("dialog.recovery.age.just_now"),
+        60..=119 => t!("dialog.recovery.age.minute"),
+        120..=3599 => t!("dialog.recovery.age.minutes", count = secs / 60),
+        3600..=7199 => t!("dialog.recovery.age.hour"),
+        7200..=86_399 => t!("dialog.recovery.age.hours", count = secs / 3600),
+        86_400..=172_799 => t!("dialog.recovery.age.yesterday"),
+        _ => t!("dialog.recovery.age.days", count = secs / 86_400),
+    }
+}
+
 // ============================================================================
 
-fn metadata_for_path(path: &Path) -> crate::canvas::ImageMetadata {
+pub(crate) fn metadata_for_path(path: &Path) -> crate::canvas::ImageMetadata {
     let mut meta = crate::canvas::ImageMetadata {
         source_format: path
             .extension()
@@ -586,7 +1895,25 @@ fn metadata_for_path(path: &Path) -> crate::canvas::ImageMetadata {
     meta
 }
 
-fn dynamic_image_to_rgba_and_deep(
+/// HDR metadata for a float payload whose brightest sample is `max`.
+pub(crate) fn linear_hdr_metadata(max: f32, transfer_function: &str) -> crate::canvas::HdrMetadata {
+    crate::canvas::HdrMetadata {
+        enabled: max > 1.0,
+        max_luminance_nits: (max > 1.0).then_some(max * 100.0),
+        reference_white_nits: Some(100.0),
+        transfer_function: Some(transfer_function.to_string()),
+        ..Default::default()
+    }
+}
+
+/// 8-bit preview of display-referred RGBA floats through the default HDR view.
+fn float_preview(samples: &[f32], width: u32, height: u32) -> RgbaImage {
+    HdrView::default()
+        .preview(&DeepRgbaBuffer::F32(samples.to_vec()), width, height)
+        .unwrap_or_else(|| RgbaImage::new(width, height))
+}
+
+pub(crate) fn dynamic_image_to_rgba_and_deep(
     img: DynamicImage,
 ) -> (
     RgbaImage,
@@ -645,15 +1972,10 @@ fn dynamic_image_to_rgba_and_deep(
             let raw = buf.as_raw().clone();
             let max = raw.iter().copied().fold(0.0_f32, f32::max);
             (
-                img.to_rgba8(),
+                float_preview(&raw, buf.width(), buf.height()),
                 crate::canvas::PixelFormat::RgbaF32,
                 Some(crate::experimental::DeepRgbaBuffer::F32(raw)),
-                crate::canvas::HdrMetadata {
-                    enabled: max > 1.0,
-                    max_luminance_nits: (max > 1.0).then_some(max * 100.0),
-                    reference_white_nits: Some(100.0),
-                    transfer_function: Some("linear-f32".to_string()),
-                },
+                linear_hdr_metadata(max, "linear-f32"),
             )
         }
         DynamicImage::ImageRgb32F(buf) => {
@@ -663,15 +1985,10 @@ fn dynamic_image_to_rgba_and_deep(
             }
             let max = deep.iter().copied().fold(0.0_f32, f32::max);
             (
-                img.to_rgba8(),
+                float_preview(&deep, buf.width(), buf.height()),
                 crate::canvas::PixelFormat::RgbaF32,
                 Some(crate::experimental::DeepRgbaBuffer::F32(deep)),
-                crate::canvas::HdrMetadata {
-                    enabled: max > 1.0,
-                    max_luminance_nits: (max > 1.0).then_some(max * 100.0),
-                    reference_white_nits: Some(100.0),
-                    transfer_function: Some("linear-f32".to_string()),
-                },
+                linear_hdr_metadata(max, "linear-f32"),
             )
         }
         _ => (
@@ -688,6 +2005,12 @@ fn dynamic_image_to_rgba_and_deep(
 /// Supported inputs:
 /// - `.pfe` — PaintFE native project (layers preserved, format returned as-is)
 /// - `.pdn` — Paint.NET project (read-only import with raster layers preserved)
+/// - `.psd`/`.psb` — Photoshop document (layers, groups and masks preserved)
+/// - `.ora` — OpenRaster (layers, offsets and stacks preserved)
+/// - `.exr` — OpenEXR (parts and channel groups as float layers)
+/// - `.hdr` — Radiance RGBE (float payload preserved)
+/// - `.ico` — Windows icon (one layer per entry size)
+/// - `.tif`/`.tiff` — TIFF (one layer per page, 16-bit/float payload preserved)
 /// - RAW camera files (CR2, NEF, ARW, DNG, etc.) — decoded to 8-bit sRGB RGBA
 /// - All standard raster formats supported by the `image` crate (PNG, JPEG, WEBP, BMP, …)
 pub fn load_image_sync(path: &Path) -> Result<CanvasState, String> {
@@ -701,6 +2024,32 @@ pub fn load_image_sync(path: &Path) -> Result<CanvasState, String> {
     if ext == "pfe" {
         return load_pfe(path).map_err(|e| format!("{:?}", e));
     }
+    if ext == "psd" || ext == "psb" {
+        return crate::psd::load_psd(path);
+    }
+    if ext == "ora" {
+        return load_ora(path);
+    }
+    if ext == "exr" {
+        return crate::hdr_io::load_exr(path);
+    }
+    if ext == "ico" {
+        return crate::icon_io::load_ico(path);
+    }
+    if ext == "svg" {
+        return crate::svg_io::load_svg(path, &crate::svg_io::SvgOptions::default());
+    }
+    if ext == "tif" || ext == "tiff" {
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Background");
+        let mut state = crate::tiff_io::load_tiff(path, name)?;
+        for layer in &mut state.layers {
+            layer.source_metadata = metadata_for_path(path);
+        }
+        return Ok(state);
+    }
     if ext == "pdn" {
         #[cfg(not(target_arch = "wasm32"))]
         return crate::pdn::load_pdn(path);
@@ -716,6 +2065,13 @@ pub fn load_image_sync(path: &Path) -> Result<CanvasState, String> {
             None,
             crate::canvas::HdrMetadata::default(),
         )
+    } else if ext == "dds" {
+        (
+            crate::dds_io::load_dds(path)?,
+            crate::canvas::PixelFormat::RgbaU8,
+            None,
+            crate::canvas::HdrMetadata::default(),
+        )
     } else {
         if let Ok((w, h)) = image::image_dimensions(path) {
             validate_open_dimensions(w, h)?;
@@ -749,6 +2105,10 @@ pub fn load_image_sync(path: &Path) -> Result<CanvasState, String> {
         source_metadata: metadata_for_path(path),
         webp_frame_compression: WebpFrameCompression::default(),
         deep_pixels,
+        locks: LayerLocks::default(),
+        off_canvas: None,
+        blend_if: BlendIf::default(),
+        move_offset: (0, 0),
     };
 
     Ok(CanvasState {
@@ -806,49 +2166,220 @@ pub fn load_image_sync(path: &Path) -> Result<CanvasState, String> {
         text_glyph_cache: Default::default(),
         text_editing_layer: None,
         canvas_widget_id: None,
+        document_bounds: None,
+        layer_comps: Vec::new(),
+        slices: Vec::new(),
+        linear_blending: false,
     })
 }
 
 /// Load a v3 tiled project file with experimental feature support.
 fn load_pfe_v3(raw: &[u8]) -> Result<CanvasState, PfeError> {
-    use crate::canvas::{AdjustmentLayerData, LayerContent};
-
     let project: ProjectFileV3 = bincode::deserialize(raw)?;
+    let folders = project
+        .folders
+        .into_iter()
+        .map(FolderDataV3::into_folder)
+        .collect();
+    canvas_from_v3_layers(
+        project.width,
+        project.height,
+        project.active_layer_index,
+        folders,
+        project.next_layer_folder_id,
+        project.layers,
+    )
+}
 
-    validate_open_dimensions(project.width, project.height).map_err(PfeError::InvalidFormat)?;
-    if project.layers.len() > MAX_LAYERS {
-        return Err(PfeError::InvalidFormat(format!(
-            "Project contains {} layers, which exceeds the maximum of {}",
-            project.layers.len(),
-            MAX_LAYERS
-        )));
-    }
+/// Load a v10 project: v9 plus slices. Slices outside the canvas are cut
+/// to it or dropped.
+fn load_pfe_v10(raw: &[u8]) -> Result<CanvasState, PfeError> {
+    let project: ProjectFileV10 = bincode::deserialize(raw)?;
+    let mut state = canvas_from_v9(project.base)?;
+    let (width, height) = (state.width, state.height);
+    state.slices = project
+        .slices
+        .into_iter()
+        .filter_map(|s| Slice::new(s.name, s.x, s.y, s.width, s.height).clamped(width, height))
+        .collect();
+    Ok(state)
+}
 
-    let expected_chunk_bytes = (CHUNK_SIZE * CHUNK_SIZE * 4) as usize;
-    let mut layers = Vec::with_capacity(project.layers.len());
+/// Load a v9 project: v8 plus linear-light blending.
+fn load_pfe_v9(raw: &[u8]) -> Result<CanvasState, PfeError> {
+    canvas_from_v9(bincode::deserialize(raw)?)
+}
 
-    for ld in project.layers {
-        let mut tiled = TiledImage::new(project.width, project.height);
-        for cd in ld.chunks {
-            if cd.pixels.len() != expected_chunk_bytes {
-                return Err(PfeError::InvalidFormat(format!(
-                    "Chunk ({},{}) in layer '{}' has {} bytes, expected {}",
-                    cd.cx,
-                    cd.cy,
-                    ld.name,
-                    cd.pixels.len(),
-                    expected_chunk_bytes,
-                )));
-            }
-            let chunk_img =
-                RgbaImage::from_raw(CHUNK_SIZE, CHUNK_SIZE, cd.pixels).ok_or_else(|| {
-                    PfeError::InvalidFormat(format!(
-                        "Failed to reconstruct chunk ({},{}) for layer '{}'",
-                        cd.cx, cd.cy, ld.name
-                    ))
-                })?;
-            tiled.set_chunk(cd.cx, cd.cy, chunk_img);
+fn canvas_from_v9(project: ProjectFileV9) -> Result<CanvasState, PfeError> {
+    let mut state = canvas_from_v8(project.base)?;
+    state.linear_blending = project.linear_blending;
+    Ok(state)
+}
+
+/// Load a v8 project: v7 plus per-layer blending ranges.
+fn load_pfe_v8(raw: &[u8]) -> Result<CanvasState, PfeError> {
+    canvas_from_v8(bincode::deserialize(raw)?)
+}
+
+fn canvas_from_v8(project: ProjectFileV8) -> Result<CanvasState, PfeError> {
+    let mut state = canvas_from_v7(project.base)?;
+    for (layer, bytes) in state.layers.iter_mut().zip(project.layer_blend_if) {
+        if let Some(bytes) = bytes {
+            layer.blend_if = BlendIf::from_bytes(&bytes);
         }
+    }
+    Ok(state)
+}
+
+/// Load a v7 project: v6 plus layer comps.
+fn load_pfe_v7(raw: &[u8]) -> Result<CanvasState, PfeError> {
+    canvas_from_v7(bincode::deserialize(raw)?)
+}
+
+fn canvas_from_v7(project: ProjectFileV7) -> Result<CanvasState, PfeError> {
+    let mut state = canvas_from_v6(project.base)?;
+    state.layer_comps = project
+        .layer_comps
+        .into_iter()
+        .map(|comp| LayerComp {
+            name: comp.name,
+            entries: comp
+                .entries
+                .into_iter()
+                .map(|e| LayerCompEntry {
+                    layer_name: e.layer_name,
+                    visible: e.visible,
+                    opacity: e.opacity,
+                    blend_mode: BlendMode::from_u8(e.blend_mode),
+                    offset: e.offset,
+                })
+                .collect(),
+        })
+        .collect();
+    for (layer, offset) in state.layers.iter_mut().zip(project.layer_move_offsets) {
+        layer.move_offset = offset;
+    }
+    Ok(state)
+}
+
+/// Load a v6 project: v5 plus off-canvas content and document bounds.
+fn load_pfe_v6(raw: &[u8]) -> Result<CanvasState, PfeError> {
+    canvas_from_v6(bincode::deserialize(raw)?)
+}
+
+fn canvas_from_v6(project: ProjectFileV6) -> Result<CanvasState, PfeError> {
+    let off_canvas = project.off_canvas;
+    let document_bounds = project.document_bounds;
+    let mut state = canvas_from_v5(ProjectFileV5 {
+        magic: project.magic,
+        width: project.width,
+        height: project.height,
+        active_layer_index: project.active_layer_index,
+        folders: project.folders,
+        next_layer_folder_id: project.next_layer_folder_id,
+        layers: project.layers,
+        layer_locks: project.layer_locks,
+    })?;
+    for (i, (layer, off)) in state.layers.iter_mut().zip(off_canvas).enumerate() {
+        let Some(off) = off else {
+            continue;
+        };
+        validate_open_dimensions(off.width, off.height).map_err(PfeError::InvalidFormat)?;
+        let what = format!("off-canvas content of layer {}", i);
+        layer.off_canvas = Some(OffCanvasPixels {
+            origin: off.origin,
+            pixels: chunks_to_tiled(off.width, off.height, off.chunks, &what)?,
+        });
+    }
+    state.document_bounds = document_bounds;
+    Ok(state)
+}
+
+/// Load a v5 project: v4 plus per-layer locks.
+fn load_pfe_v5(raw: &[u8]) -> Result<CanvasState, PfeError> {
+    canvas_from_v5(bincode::deserialize(raw)?)
+}
+
+fn canvas_from_v5(project: ProjectFileV5) -> Result<CanvasState, PfeError> {
+    let layer_locks = project.layer_locks;
+    let mut state = canvas_from_v4(ProjectFileV4 {
+        magic: project.magic,
+        width: project.width,
+        height: project.height,
+        active_layer_index: project.active_layer_index,
+        folders: project.folders,
+        next_layer_folder_id: project.next_layer_folder_id,
+        layers: project.layers,
+    })?;
+    for (layer, bits) in state.layers.iter_mut().zip(layer_locks) {
+        layer.locks = LayerLocks::from_bits(bits);
+    }
+    Ok(state)
+}
+
+/// Load a v4 project: v3 layers plus nested group folders.
+fn load_pfe_v4(raw: &[u8]) -> Result<CanvasState, PfeError> {
+    canvas_from_v4(bincode::deserialize(raw)?)
+}
+
+fn canvas_from_v4(project: ProjectFileV4) -> Result<CanvasState, PfeError> {
+    validate_open_dimensions(project.width, project.height).map_err(PfeError::InvalidFormat)?;
+    let mut folders = Vec::with_capacity(project.folders.len());
+    for fd in project.folders {
+        let mask = match fd.mask {
+            Some(chunks) => Some(chunks_to_tiled(
+                project.width,
+                project.height,
+                chunks,
+                &format!("mask of folder '{}'", fd.base.name),
+            )?),
+            None => None,
+        };
+        folders.push(LayerFolder {
+            parent_id: fd.parent_id,
+            opacity: fd.opacity.clamp(0.0, 1.0),
+            blend_mode: fd.blend_mode.map(BlendMode::from_u8),
+            mask,
+            mask_enabled: fd.mask_enabled,
+            ..fd.base.into_folder()
+        });
+    }
+    let mut state = canvas_from_v3_layers(
+        project.width,
+        project.height,
+        project.active_layer_index,
+        folders,
+        project.next_layer_folder_id,
+        project.layers,
+    )?;
+    state.repair_folder_parents();
+    Ok(state)
+}
+
+/// Shared tail of the v3/v4 loaders: rebuild layers and the canvas state.
+fn canvas_from_v3_layers(
+    width: u32,
+    height: u32,
+    active_layer_index: usize,
+    layer_folders: Vec<LayerFolder>,
+    next_layer_folder_id: u64,
+    layer_data: Vec<LayerDataV3>,
+) -> Result<CanvasState, PfeError> {
+    use crate::canvas::{AdjustmentLayerData, LayerContent};
+
+    validate_open_dimensions(width, height).map_err(PfeError::InvalidFormat)?;
+    if layer_data.len() > MAX_LAYERS {
+        return Err(PfeError::InvalidFormat(format!(
+            "Project contains {} layers, which exceeds the maximum of {}",
+            layer_data.len(),
+            MAX_LAYERS
+        )));
+    }
+
+    let mut layers = Vec::with_capacity(layer_data.len());
+
+    for ld in layer_data {
+        let tiled = chunks_to_tiled(width, height, ld.chunks, &format!("layer '{}'", ld.name))?;
 
         let content = match ld.layer_type {
             1 => ld
@@ -887,6 +2418,10 @@ fn load_pfe_v3(raw: &[u8]) -> Result<CanvasState, PfeError> {
             source_metadata: ld.source_metadata,
             webp_frame_compression: ld.webp_frame_compression,
             deep_pixels: ld.deep_pixels,
+            locks: LayerLocks::default(),
+            off_canvas: None,
+            blend_if: BlendIf::default(),
+            move_offset: (0, 0),
         });
     }
 
@@ -894,13 +2429,13 @@ fn load_pfe_v3(raw: &[u8]) -> Result<CanvasState, PfeError> {
         return Err(PfeError::InvalidFormat("Project contains no layers".into()));
     }
 
-    let active = project.active_layer_index.min(layers.len() - 1);
+    let active = active_layer_index.min(layers.len() - 1);
     Ok(CanvasState {
-        width: project.width,
-        height: project.height,
+        width,
+        height,
         layers,
-        layer_folders: project.folders,
-        next_layer_folder_id: project.next_layer_folder_id,
+        layer_folders,
+        next_layer_folder_id,
         active_layer_index: active,
         edit_layer_mask: false,
         composite_cache: None,
@@ -950,6 +2485,10 @@ fn load_pfe_v3(raw: &[u8]) -> Result<CanvasState, PfeError> {
         text_glyph_cache: Default::default(),
         text_editing_layer: None,
         canvas_widget_id: None,
+        document_bounds: None,
+        layer_comps: Vec::new(),
+        slices: Vec::new(),
+        linear_blending: false,
     })
 }
 
@@ -1039,6 +2578,10 @@ fn load_pfe_v2(raw: &[u8]) -> Result<CanvasState, PfeError> {
             source_metadata: crate::canvas::ImageMetadata::default(),
             webp_frame_compression: WebpFrameCompression::default(),
             deep_pixels: None,
+            locks: LayerLocks::default(),
+            off_canvas: None,
+            blend_if: BlendIf::default(),
+            move_offset: (0, 0),
         });
     }
 
@@ -1103,6 +2646,10 @@ fn load_pfe_v2(raw: &[u8]) -> Result<CanvasState, PfeError> {
         text_glyph_cache: Default::default(),
         text_editing_layer: None,
         canvas_widget_id: None,
+        document_bounds: None,
+        layer_comps: Vec::new(),
+        slices: Vec::new(),
+        linear_blending: false,
     })
 }
 
@@ -1162,6 +2709,10 @@ fn load_pfe_v1(raw: &[u8]) -> Result<CanvasState, PfeError> {
             source_metadata: crate::canvas::ImageMetadata::default(),
             webp_frame_compression: WebpFrameCompression::default(),
             deep_pixels: None,
+            locks: LayerLocks::default(),
+            off_canvas: None,
+            blend_if: BlendIf::default(),
+            move_offset: (0, 0),
         });
     }
 
@@ -1226,6 +2777,10 @@ fn load_pfe_v1(raw: &[u8]) -> Result<CanvasState, PfeError> {
         text_glyph_cache: Default::default(),
         text_editing_layer: None,
         canvas_widget_id: None,
+        document_bounds: None,
+        layer_comps: Vec::new(),
+        slices: Vec::new(),
+        linear_blending: false,
     })
 }
 
@@ -1282,6 +2837,10 @@ fn load_pfe_v0(raw: &[u8]) -> Result<CanvasState, PfeError> {
             source_metadata: crate::canvas::ImageMetadata::default(),
             webp_frame_compression: WebpFrameCompression::default(),
             deep_pixels: None,
+            locks: LayerLocks::default(),
+            off_canvas: None,
+            blend_if: BlendIf::default(),
+            move_offset: (0, 0),
         });
     }
 
@@ -1346,6 +2905,10 @@ fn load_pfe_v0(raw: &[u8]) -> Result<CanvasState, PfeError> {
         text_glyph_cache: Default::default(),
         text_editing_layer: None,
         canvas_widget_id: None,
+        document_bounds: None,
+        layer_comps: Vec::new(),
+        slices: Vec::new(),
+        linear_blending: false,
     })
 }
 
@@ -1365,11 +2928,99 @@ pub enum PreparedExportImage {
         width: u32,
         height: u32,
         pixels: Vec<f32>,
+        /// Exposure and tone map for 8-bit targets.
+        view: HdrView,
     },
 }
 
 impl PreparedExportImage {
-    fn rgba8(&self) -> RgbaImage {
+    fn dimensions(&self) -> (u32, u32) {
+        match self {
+            PreparedExportImage::Rgba8(image) => image.dimensions(),
+            PreparedExportImage::Rgba16 { width, height, .. }
+            | PreparedExportImage::RgbaF32 { width, height, .. } => (*width, *height),
+        }
+    }
+
+    /// Crop to `(x, y, w, h)`, clamped to the image.
+    fn cropped(self, bounds: (u32, u32, u32, u32)) -> Self {
+        let (width, height) = self.dimensions();
+        let x = bounds.0.min(width.saturating_sub(1));
+        let y = bounds.1.min(height.saturating_sub(1));
+        let w = bounds.2.clamp(1, (width - x).max(1));
+        let h = bounds.3.clamp(1, (height - y).max(1));
+        if (x, y, w, h) == (0, 0, width, height) || width == 0 || height == 0 {
+            return self;
+        }
+        fn crop_samples<T: Copy>(
+            pixels: &[T],
+            width: u32,
+            (x, y, w, h): (u32, u32, u32, u32),
+        ) -> Vec<T> {
+            let mut out = Vec::with_capacity(w as usize * h as usize * 4);
+            for row in y..y + h {
+                let start = (row as usize * width as usize + x as usize) * 4;
+                out.extend_from_slice(&pixels[start..start + w as usize * 4]);
+            }
+            out
+        }
+        match self {
+            PreparedExportImage::Rgba8(image) => {
+                PreparedExportImage::Rgba8(image::imageops::crop_imm(&image, x, y, w, h).to_image())
+            }
+            PreparedExportImage::Rgba16 { pixels, .. } => PreparedExportImage::Rgba16 {
+                width: w,
+                height: h,
+                pixels: crop_samples(&pixels, width, (x, y, w, h)),
+            },
+            PreparedExportImage::RgbaF32 { pixels, view, .. } => PreparedExportImage::RgbaF32 {
+                width: w,
+                height: h,
+                pixels: crop_samples(&pixels, width, (x, y, w, h)),
+                view,
+            },
+        }
+    }
+
+    /// `(width, height, linear RGBA samples)`. Integer images are sRGB, so
+    /// their colour channels are decoded to linear light.
+    fn to_f32(&self) -> (u32, u32, Vec<f32>) {
+        match self {
+            PreparedExportImage::Rgba8(image) => (
+                image.width(),
+                image.height(),
+                srgb8_samples_to_linear(image.as_raw()),
+            ),
+            PreparedExportImage::Rgba16 {
+                width,
+                height,
+                pixels,
+            } => (
+                *width,
+                *height,
+                pixels
+                    .chunks_exact(4)
+                    .flat_map(|px| {
+                        let decode = |v: u16| crate::canvas::srgb_to_linear(v as f32 / 65535.0);
+                        [
+                            decode(px[0]),
+                            decode(px[1]),
+                            decode(px[2]),
+                            px[3] as f32 / 65535.0,
+                        ]
+                    })
+                    .collect(),
+            ),
+            PreparedExportImage::RgbaF32 {
+                width,
+                height,
+                pixels,
+                ..
+            } => (*width, *height, pixels.clone()),
+        }
+    }
+
+    pub(crate) fn rgba8(&self) -> RgbaImage {
         match self {
             PreparedExportImage::Rgba8(image) => image.clone(),
             PreparedExportImage::Rgba16 {
@@ -1388,20 +3039,11 @@ impl PreparedExportImage {
                 width,
                 height,
                 pixels,
+                view,
             } => {
                 let mut data = Vec::with_capacity(pixels.len());
                 for px in pixels.chunks_exact(4) {
-                    let mapped = if px[0] > 1.0 || px[1] > 1.0 || px[2] > 1.0 {
-                        reinhard_tone_map_rgba([px[0], px[1], px[2], px[3]], 1.0)
-                    } else {
-                        Rgba([
-                            (px[0].clamp(0.0, 1.0) * 255.0).round() as u8,
-                            (px[1].clamp(0.0, 1.0) * 255.0).round() as u8,
-                            (px[2].clamp(0.0, 1.0) * 255.0).round() as u8,
-                            (px[3].clamp(0.0, 1.0) * 255.0).round() as u8,
-                        ])
-                    };
-                    data.extend_from_slice(&mapped.0);
+                    data.extend_from_slice(&view.map([px[0], px[1], px[2], px[3]]).0);
                 }
                 RgbaImage::from_raw(*width, *height, data)
                     .unwrap_or_else(|| RgbaImage::new(*width, *height))
@@ -1411,6 +3053,15 @@ impl PreparedExportImage {
 }
 
 pub fn prepare_export_image(state: &CanvasState) -> PreparedExportImage {
+    let image = prepare_canvas_export_image(state);
+    match state.document_bounds {
+        // Infinite canvas mode: only the document rectangle is exported.
+        Some(bounds) => image.cropped(bounds),
+        None => image,
+    }
+}
+
+fn prepare_canvas_export_image(state: &CanvasState) -> PreparedExportImage {
     if let Some(deep_adjusted) = adjusted_deep_export(state) {
         return deep_adjusted;
     }
@@ -1430,11 +3081,13 @@ pub fn prepare_export_image(state: &CanvasState) -> PreparedExportImage {
         return PreparedExportImage::RgbaF32 {
             width: composite.width(),
             height: composite.height(),
-            pixels: composite
-                .as_raw()
-                .iter()
-                .map(|&v| v as f32 / 255.0)
-                .collect(),
+            pixels: srgb8_samples_to_linear(composite.as_raw()),
+            // The composite is built from previews that are already tone-mapped;
+            // the linear samples only need sRGB encoding again.
+            view: HdrView {
+                transfer: HdrTransfer::SceneLinear,
+                ..HdrView::default()
+            },
         };
     }
     if state.layers.iter().enumerate().any(|(idx, layer)| {
@@ -1462,6 +3115,7 @@ fn adjusted_deep_export(state: &CanvasState) -> Option<PreparedExportImage> {
         .collect();
     let base = *visible_layers.first()?;
     if visible_layers.len() < 2
+        || state.has_group_compositing()
         || !matches!(base.content, LayerContent::Raster)
         || base.opacity < 0.999
         || base.blend_mode != BlendMode::Normal
@@ -1477,11 +3131,11 @@ fn adjusted_deep_export(state: &CanvasState) -> Option<PreparedExportImage> {
         return None;
     }
     let mut pixels = deep_buffer_to_f32(base.deep_pixels.as_ref()?, state.width, state.height)?;
-    if base
-        .deep_pixels
-        .as_ref()?
-        .to_rgba8(state.width, state.height)?
-        != base.pixels.to_rgba_image()
+    if base.hdr_metadata.payload_view().preview(
+        base.deep_pixels.as_ref()?,
+        state.width,
+        state.height,
+    )? != base.pixels.to_rgba_image()
     {
         return None;
     }
@@ -1506,6 +3160,7 @@ fn adjusted_deep_export(state: &CanvasState) -> Option<PreparedExportImage> {
             width: state.width,
             height: state.height,
             pixels,
+            view: base.hdr_metadata.payload_view(),
         })
     } else if base.pixel_format == PixelFormat::RgbaU16 {
         Some(PreparedExportImage::Rgba16 {
@@ -1547,6 +3202,7 @@ fn exact_single_layer_deep_export(state: &CanvasState) -> Option<PreparedExportI
         .collect();
     let layer = *visible_layers.first()?;
     if visible_layers.len() != 1
+        || state.has_group_compositing()
         || !matches!(layer.content, LayerContent::Raster)
         || layer.opacity < 0.999
         || layer.blend_mode != BlendMode::Normal
@@ -1556,7 +3212,8 @@ fn exact_single_layer_deep_export(state: &CanvasState) -> Option<PreparedExportI
     }
 
     let deep = layer.deep_pixels.as_ref()?;
-    if deep.to_rgba8(state.width, state.height)? != layer.pixels.to_rgba_image() {
+    let view = layer.hdr_metadata.payload_view();
+    if view.preview(deep, state.width, state.height)? != layer.pixels.to_rgba_image() {
         return None;
     }
 
@@ -1574,11 +3231,13 @@ fn exact_single_layer_deep_export(state: &CanvasState) -> Option<PreparedExportI
             width: state.width,
             height: state.height,
             pixels: pixels.iter().map(|&v| f16_bits_to_f32(v)).collect(),
+            view,
         }),
         DeepRgbaBuffer::F32(pixels) => Some(PreparedExportImage::RgbaF32 {
             width: state.width,
             height: state.height,
             pixels: pixels.clone(),
+            view,
         }),
     }
 }
@@ -1607,15 +3266,57 @@ pub fn encode_prepared_and_write(
                 pixels,
             },
             SaveFormat::Tiff,
-        ) => return write_tiff16(*width, *height, pixels, path, tiff_compression),
+        ) => {
+            return write_flat_tiff(
+                *width,
+                *height,
+                TiffPixels::U16(pixels),
+                path,
+                tiff_compression,
+                quality,
+            );
+        }
         (
             PreparedExportImage::RgbaF32 {
                 width,
                 height,
                 pixels,
+                ..
             },
             SaveFormat::Tiff,
-        ) => return write_tiff_f32(*width, *height, pixels, path),
+        ) => {
+            return write_flat_tiff(
+                *width,
+                *height,
+                TiffPixels::F32(pixels),
+                path,
+                tiff_compression,
+                quality,
+            );
+        }
+        (
+            PreparedExportImage::Rgba16 {
+                width,
+                height,
+                pixels,
+            },
+            SaveFormat::Pgm | SaveFormat::Ppm | SaveFormat::Pam,
+        ) => {
+            let bytes = encode_netpbm(format, *width, *height, pixels, u16::MAX);
+            File::create(path)?.write_all(&bytes)?;
+            return Ok(());
+        }
+        (_, SaveFormat::Exr | SaveFormat::Hdr) => {
+            // Float targets take the linear samples, not the 8-bit preview.
+            let (width, height, pixels) = image.to_f32();
+            let bytes = if format == SaveFormat::Exr {
+                crate::hdr_io::encode_flat_exr(width, height, ExrSamples::F32(pixels))?
+            } else {
+                crate::hdr_io::encode_radiance(width, height, &pixels)?
+            };
+            File::create(path)?.write_all(&bytes)?;
+            return Ok(());
+        }
         _ => {}
     }
 
@@ -1648,6 +3349,25 @@ pub fn encode_canvas_state_and_write(
     )
 }
 
+/// Interleaved sRGB RGBA8 as linear RGBA floats (alpha stays linear).
+fn srgb8_samples_to_linear(raw: &[u8]) -> Vec<f32> {
+    raw.chunks_exact(4)
+        .flat_map(|px| {
+            [
+                crate::canvas::srgb8_to_linear(px[0]),
+                crate::canvas::srgb8_to_linear(px[1]),
+                crate::canvas::srgb8_to_linear(px[2]),
+                px[3] as f32 / 255.0,
+            ]
+        })
+        .collect()
+}
+
+/// `(width, height, linear samples)` for writing an 8-bit image to a float format.
+fn flat_float_samples(image: &RgbaImage) -> (u32, u32, Vec<f32>) {
+    PreparedExportImage::Rgba8(image.clone()).to_f32()
+}
+
 fn write_png16(width: u32, height: u32, pixels: &[u16], path: &Path) -> Result<(), ImageError> {
     let file = File::create(path)?;
     let writer = BufWriter::new(file);
@@ -1667,53 +3387,71 @@ fn write_png16(width: u32, height: u32, pixels: &[u16], path: &Path) -> Result<(
     Ok(())
 }
 
-fn write_tiff16(
+/// Binary Netpbm bytes from interleaved RGBA samples: `P5` greyscale (Rec. 709
+/// luma), `P6` RGB, or `P7` PAM with `RGB_ALPHA` tuples. A `maxval` above 255
+/// writes big-endian 16-bit samples.
+pub fn encode_netpbm<T: Copy + Into<u32>>(
+    format: SaveFormat,
     width: u32,
     height: u32,
-    pixels: &[u16],
-    path: &Path,
-    tiff_compression: TiffCompression,
-) -> Result<(), ImageError> {
-    let file = File::create(path)?;
-    let mut writer = BufWriter::new(file);
-    let err_map = |e: tiff::TiffError| {
-        ImageError::IoError(std::io::Error::other(format!("TIFF encode error: {}", e)))
+    rgba: &[T],
+    maxval: T,
+) -> Vec<u8> {
+    let maxval: u32 = maxval.into();
+    let mut out = match format {
+        SaveFormat::Pgm => format!("P5\n{} {}\n{}\n", width, height, maxval),
+        SaveFormat::Ppm => format!("P6\n{} {}\n{}\n", width, height, maxval),
+        _ => format!(
+            "P7\nWIDTH {}\nHEIGHT {}\nDEPTH 4\nMAXVAL {}\nTUPLTYPE RGB_ALPHA\nENDHDR\n",
+            width, height, maxval
+        ),
+    }
+    .into_bytes();
+    let mut push = |value: u32| {
+        if maxval > 255 {
+            out.extend_from_slice(&(value as u16).to_be_bytes());
+        } else {
+            out.push(value as u8);
+        }
     };
-    let mut tiff_enc = tiff::encoder::TiffEncoder::new(&mut writer).map_err(err_map)?;
-    match tiff_compression {
-        TiffCompression::None => tiff_enc
-            .write_image::<tiff::encoder::colortype::RGBA16>(width, height, pixels)
-            .map_err(err_map)?,
-        TiffCompression::Lzw => tiff_enc
-            .write_image_with_compression::<tiff::encoder::colortype::RGBA16, _>(
-                width,
-                height,
-                tiff::encoder::compression::Lzw,
-                pixels,
-            )
-            .map_err(err_map)?,
-        TiffCompression::Deflate => tiff_enc
-            .write_image_with_compression::<tiff::encoder::colortype::RGBA16, _>(
-                width,
-                height,
-                tiff::encoder::compression::Deflate::default(),
-                pixels,
-            )
-            .map_err(err_map)?,
+    for px in rgba.chunks_exact(4) {
+        let [r, g, b, a] = [px[0].into(), px[1].into(), px[2].into(), px[3].into()];
+        match format {
+            SaveFormat::Pgm => push((r * 2126 + g * 7152 + b * 722 + 5000) / 10000),
+            SaveFormat::Ppm => {
+                push(r);
+                push(g);
+                push(b);
+            }
+            _ => {
+                push(r);
+                push(g);
+                push(b);
+                push(a);
+            }
+        }
     }
-    Ok(())
+    out
 }
 
-fn write_tiff_f32(width: u32, height: u32, pixels: &[f32], path: &Path) -> Result<(), ImageError> {
-    let file = File::create(path)?;
-    let mut writer = BufWriter::new(file);
-    let err_map = |e: tiff::TiffError| {
-        ImageError::IoError(std::io::Error::other(format!("TIFF encode error: {}", e)))
-    };
-    let mut tiff_enc = tiff::encoder::TiffEncoder::new(&mut writer).map_err(err_map)?;
-    tiff_enc
-        .write_image::<tiff::encoder::colortype::RGBA32Float>(width, height, pixels)
-        .map_err(err_map)?;
+fn write_flat_tiff(
+    width: u32,
+    height: u32,
+    pixels: TiffPixels,
+    path: &Path,
+    tiff_compression: TiffCompression,
+    quality: u8,
+) -> Result<(), ImageError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    crate::tiff_io::encode_flat(
+        &mut writer,
+        width,
+        height,
+        pixels,
+        tiff_compression,
+        quality,
+    )?;
+    writer.flush()?;
     Ok(())
 }
 
@@ -1741,15 +3479,11 @@ pub fn encode_and_write(
                 image::ExtendedColorType::Rgba8,
             )?;
         }
+        // Dialog saves go through LayeredExport with the chosen encoder options.
         SaveFormat::Jpeg => {
-            let rgb_image = DynamicImage::ImageRgba8(image.clone()).to_rgb8();
-            let encoder = JpegEncoder::new_with_quality(&mut writer, quality);
-            encoder.write_image(
-                rgb_image.as_raw(),
-                rgb_image.width(),
-                rgb_image.height(),
-                image::ExtendedColorType::Rgb8,
-            )?;
+            let bytes = crate::jpeg_io::encode_jpeg(image, quality, &JpegOptions::default())
+                .map_err(|e| ImageError::IoError(std::io::Error::other(e)))?;
+            writer.write_all(&bytes)?;
         }
         SaveFormat::Webp => {
             encode_static_webp(image, &mut writer, quality, webp_lossless)?;
@@ -1772,60 +3506,58 @@ pub fn encode_and_write(
                 image::ExtendedColorType::Rgba8,
             )?;
         }
+        SaveFormat::Qoi => {
+            let encoder = QoiEncoder::new(&mut writer);
+            encoder.write_image(
+                image.as_raw(),
+                image.width(),
+                image.height(),
+                image::ExtendedColorType::Rgba8,
+            )?;
+        }
+        SaveFormat::Pgm | SaveFormat::Ppm | SaveFormat::Pam => {
+            let bytes = encode_netpbm(format, image.width(), image.height(), image.as_raw(), 255);
+            writer.write_all(&bytes)?;
+        }
+        // Dialog saves go through LayeredExport with the chosen compression.
+        SaveFormat::Dds => {
+            writer.write_all(&crate::dds_io::encode_dds(image, &DdsOptions::default()))?;
+        }
         SaveFormat::Ico => {
-            // ICO entries limited to 256×256; scale down if needed
-            let dyn_img = if image.width() > 256 || image.height() > 256 {
-                let scale = 256.0 / image.width().max(image.height()) as f32;
-                let new_w = ((image.width() as f32 * scale) as u32).max(1);
-                let new_h = ((image.height() as f32 * scale) as u32).max(1);
-                let resized = image::imageops::resize(
-                    image,
-                    new_w,
-                    new_h,
-                    image::imageops::FilterType::Lanczos3,
-                );
-                DynamicImage::ImageRgba8(resized)
-            } else {
-                DynamicImage::ImageRgba8(image.clone())
-            };
-            dyn_img.write_to(&mut writer, image::ImageFormat::Ico)?;
+            // Every standard size up to the image's own, scaled from it
+            let icon = crate::icon_io::ico_from_image(image, &IconOptions::default());
+            writer.write_all(&crate::icon_io::encode_ico(&icon)?)?;
+        }
+        SaveFormat::Icns => {
+            let icon = crate::icon_io::icns_from_image(image, &IconOptions::default());
+            writer.write_all(&crate::icon_io::encode_icns(&icon)?)?;
         }
         SaveFormat::Tiff => {
-            let err_map = |e: tiff::TiffError| {
-                ImageError::IoError(std::io::Error::other(format!("TIFF encode error: {}", e)))
-            };
-            let mut tiff_enc = tiff::encoder::TiffEncoder::new(&mut writer).map_err(err_map)?;
-            match tiff_compression {
-                TiffCompression::None => {
-                    tiff_enc
-                        .write_image::<tiff::encoder::colortype::RGBA8>(
-                            image.width(),
-                            image.height(),
-                            image.as_raw(),
-                        )
-                        .map_err(err_map)?;
-                }
-                TiffCompression::Lzw => {
-                    tiff_enc
-                        .write_image_with_compression::<tiff::encoder::colortype::RGBA8, _>(
-                            image.width(),
-                            image.height(),
-                            tiff::encoder::compression::Lzw,
-                            image.as_raw(),
-                        )
-                        .map_err(err_map)?;
-                }
-                TiffCompression::Deflate => {
-                    tiff_enc
-                        .write_image_with_compression::<tiff::encoder::colortype::RGBA8, _>(
-                            image.width(),
-                            image.height(),
-                            tiff::encoder::compression::Deflate::default(),
-                            image.as_raw(),
-                        )
-                        .map_err(err_map)?;
-                }
-            }
+            crate::tiff_io::encode_flat(
+                &mut writer,
+                image.width(),
+                image.height(),
+                TiffPixels::U8(image.as_raw()),
+                tiff_compression,
+                quality,
+            )?;
+        }
+        // Layered saves go through LayeredExport; this path only has the composite.
+        SaveFormat::Psd => {
+            writer.write_all(&crate::psd::encode_flat_psd(image))?;
+        }
+        SaveFormat::Ora => {
+            writer.write_all(&encode_flat_ora(image)?)?;
+        }
+        // 8-bit sources only; float exports go through encode_prepared_and_write.
+        SaveFormat::Exr => {
+            let (width, height, samples) = flat_float_samples(image);
+            let samples = ExrSamples::F16(samples.into_iter().map(f32_to_f16_bits).collect());
+            writer.write_all(&crate::hdr_io::encode_flat_exr(width, height, samples)?)?;
+        }
+        SaveFormat::Hdr => {
+            let (width, height, samples) = flat_float_samples(image);
+            writer.write_all(&crate::hdr_io::encode_radiance(width, height, &samples)?)?;
         }
         SaveFormat::Pfe => {
             unreachable!("PFE format should be handled via save_pfe(), not encode_and_write()");
@@ -1876,6 +3608,186 @@ fn encode_static_webp<W: Write>(
     )
 }
 
+// ============================================================================
+// EXPORT PRESETS
+// ============================================================================
+
+/// The document flattened for one [`ExportPreset`]: converted to the preset's
+/// depth and scaled, with any metadata it keeps. Built on the UI thread, then
+/// written on a background thread.
+pub struct PresetExport {
+    image: PreparedExportImage,
+    preset: ExportPreset,
+    /// Raw source PNG text chunks to copy into PNG output.
+    png_chunks: Vec<Vec<u8>>,
+}
+
+impl PresetExport {
+    pub fn build(state: &CanvasState, preset: &ExportPreset) -> Result<Self, String> {
+        preset.validate()?;
+        let mut image = match preset.depth {
+            ExportDepth::Source => prepare_export_image(state),
+            ExportDepth::Eight => PreparedExportImage::Rgba8(prepare_export_image(state).rgba8()),
+            ExportDepth::Sixteen => widen_to_rgba16(prepare_export_image(state)),
+        };
+        // Scale what is exported, which is less than the canvas when the
+        // document bounds crop it.
+        let source = image.dimensions();
+        let (width, height) = preset.scaled_size(source.0, source.1);
+        if (width, height) != source {
+            image = resize_prepared(image, width, height);
+        }
+        let png_chunks = match preset.metadata {
+            MetadataPolicy::Strip => Vec::new(),
+            MetadataPolicy::Keep => state
+                .layers
+                .iter()
+                .flat_map(|l| l.source_metadata.raw_png_chunks.iter().cloned())
+                .collect(),
+        };
+        Ok(Self {
+            image,
+            preset: preset.clone(),
+            png_chunks,
+        })
+    }
+
+    pub fn preset(&self) -> &ExportPreset {
+        &self.preset
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let preset = &self.preset;
+        match preset.format {
+            SaveFormat::Jpeg => {
+                let bytes =
+                    crate::jpeg_io::encode_jpeg(&self.image.rgba8(), preset.quality, &preset.jpeg)?;
+                File::create(path)
+                    .and_then(|mut f| f.write_all(&bytes))
+                    .map_err(|e| e.to_string())
+            }
+            SaveFormat::Png if !self.png_chunks.is_empty() => {
+                let bytes = insert_png_chunks(encode_png_bytes(&self.image)?, &self.png_chunks)?;
+                File::create(path)
+                    .and_then(|mut f| f.write_all(&bytes))
+                    .map_err(|e| e.to_string())
+            }
+            format => encode_prepared_and_write(
+                self.image.clone(),
+                path,
+                format,
+                preset.quality,
+                TiffCompression::None,
+                preset.webp_lossless,
+            )
+            .map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// 16 bits per channel: 8-bit samples are widened, float samples go through
+/// their exposure and tone map first.
+fn widen_to_rgba16(image: PreparedExportImage) -> PreparedExportImage {
+    match image {
+        PreparedExportImage::Rgba16 { .. } => image,
+        other => {
+            let rgba8 = other.rgba8();
+            PreparedExportImage::Rgba16 {
+                width: rgba8.width(),
+                height: rgba8.height(),
+                pixels: rgba8.as_raw().iter().map(|&v| v as u16 * 257).collect(),
+            }
+        }
+    }
+}
+
+fn resize_prepared(image: PreparedExportImage, width: u32, height: u32) -> PreparedExportImage {
+    use image::imageops::{FilterType, resize};
+    use image::{ImageBuffer, Rgba32FImage};
+    match image {
+        PreparedExportImage::Rgba8(img) => {
+            PreparedExportImage::Rgba8(resize(&img, width, height, FilterType::Lanczos3))
+        }
+        PreparedExportImage::Rgba16 {
+            width: w,
+            height: h,
+            pixels,
+        } => {
+            let buffer: ImageBuffer<Rgba<u16>, Vec<u16>> =
+                ImageBuffer::from_raw(w, h, pixels).unwrap_or_else(|| ImageBuffer::new(w, h));
+            PreparedExportImage::Rgba16 {
+                width,
+                height,
+                pixels: resize(&buffer, width, height, FilterType::Lanczos3).into_raw(),
+            }
+        }
+        PreparedExportImage::RgbaF32 {
+            width: w,
+            height: h,
+            pixels,
+            view,
+        } => {
+            let buffer =
+                Rgba32FImage::from_raw(w, h, pixels).unwrap_or_else(|| Rgba32FImage::new(w, h));
+            PreparedExportImage::RgbaF32 {
+                width,
+                height,
+                pixels: resize(&buffer, width, height, FilterType::Lanczos3).into_raw(),
+                view,
+            }
+        }
+    }
+}
+
+/// PNG bytes at the image's own depth (16-bit stays 16-bit).
+fn encode_png_bytes(image: &PreparedExportImage) -> Result<Vec<u8>, String> {
+    let (width, height, depth, data) = match image {
+        PreparedExportImage::Rgba16 {
+            width,
+            height,
+            pixels,
+        } => (
+            *width,
+            *height,
+            png::BitDepth::Sixteen,
+            pixels.iter().flat_map(|v| v.to_be_bytes()).collect(),
+        ),
+        other => {
+            let rgba8 = other.rgba8();
+            (
+                rgba8.width(),
+                rgba8.height(),
+                png::BitDepth::Eight,
+                rgba8.into_raw(),
+            )
+        }
+    };
+    let mut out = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut out, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(depth);
+        let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+        writer.write_image_data(&data).map_err(|e| e.to_string())?;
+    }
+    Ok(out)
+}
+
+/// Splice complete PNG chunks (length, type, data, CRC) in after IHDR.
+fn insert_png_chunks(mut png: Vec<u8>, chunks: &[Vec<u8>]) -> Result<Vec<u8>, String> {
+    // Signature (8) + IHDR length, type, 13 data bytes and CRC (25).
+    const IHDR_END: usize = 33;
+    if png.len() < IHDR_END || &png[12..16] != b"IHDR" {
+        return Err("Encoded PNG has no IHDR chunk".to_string());
+    }
+    let tail = png.split_off(IHDR_END);
+    for chunk in chunks {
+        png.extend_from_slice(chunk);
+    }
+    png.extend_from_slice(&tail);
+    Ok(png)
+}
+
 // ============================================================================
 // FILE HANDLER
 // ============================================================================
@@ -1899,6 +3811,14 @@ pub struct FileHandler {
     pub last_gif_colors: u16,
     /// Last used GIF dithering setting
     pub last_gif_dither: bool,
+    /// Last used ICO / ICNS entry options
+    pub last_icon: IconOptions,
+    /// Whether the last TIFF save wrote layers as pages
+    pub last_tiff_pages: bool,
+    /// Last used DDS compression and mipmap setting
+    pub last_dds: DdsOptions,
+    /// Last used JPEG encoder settings
+    pub last_jpeg: JpegOptions,
 }
 
 impl Default for FileHandler {
@@ -1919,6 +3839,22 @@ impl FileHandler {
             last_animation_fps: 10.0,
             last_gif_colors: 256,
             last_gif_dither: true,
+            last_icon: IconOptions::default(),
+            last_tiff_pages: false,
+            last_dds: DdsOptions::default(),
+            last_jpeg: JpegOptions::default(),
+        }
+    }
+
+    /// Settings for re-saving through [`LayeredExport`] with the last options.
+    pub fn layered_options(&self) -> LayeredExportOptions {
+        LayeredExportOptions {
+            icon: self.last_icon.clone(),
+            tiff_pages: self.last_tiff_pages,
+            tiff_compression: self.last_tiff_compression,
+            quality: self.last_quality,
+            dds: self.last_dds.clone(),
+            jpeg: self.last_jpeg.clone(),
         }
     }
 
@@ -1938,32 +3874,22 @@ impl FileHandler {
 
     #[cfg(not(target_arch = "wasm32"))]
     pub fn pick_file_paths(&self) -> Vec<PathBuf> {
+        let all_supported: Vec<&str> = DOCUMENT_EXTENSIONS
+            .iter()
+            .chain(IMAGE_EXTENSIONS)
+            .chain(RAW_EXTENSIONS)
+            .copied()
+            .collect();
         FileDialog::new()
-            .add_filter(
-                "All Supported",
-                &[
-                    "pfe", "pdn", "png", "jpg", "jpeg", "webp", "bmp", "tga", "gif", "ico", "tiff",
-                    "tif", "cr2", "cr3", "nef", "nrw", "arw", "srf", "sr2", "dng", "orf", "rw2",
-                    "pef", "raf", "raw", "rwl", "srw", "x3f", "3fr", "fff", "iiq", "mrw", "mef",
-                    "mos", "kdc", "dcr", "erf",
-                ],
-            )
+            .add_filter("All Supported", &all_supported)
             .add_filter("PaintFE Project", &["pfe"])
             .add_filter("Paint.NET Project (read-only)", &["pdn"])
-            .add_filter(
-                "Images",
-                &[
-                    "png", "jpg", "jpeg", "webp", "bmp", "tga", "gif", "ico", "tiff", "tif",
-                ],
-            )
-            .add_filter(
-                "RAW Files",
-                &[
-                    "cr2", "cr3", "nef", "nrw", "arw", "srf", "sr2", "dng", "orf", "rw2", "pef",
-                    "raf", "raw", "rwl", "srw", "x3f", "3fr", "fff", "iiq", "mrw", "mef", "mos",
-                    "kdc", "dcr", "erf",
-                ],
-            )
+            .add_filter("Photoshop Document", &["psd", "psb"])
+            .add_filter("OpenRaster", &["ora"])
+            .add_filter("HDR Images", &["exr", "hdr"])
+            .add_filter("SVG Images", &["svg"])
+            .add_filter("Images", IMAGE_EXTENSIONS)
+            .add_filter("RAW Files", RAW_EXTENSIONS)
             .add_filter("All Files", &["*"])
             .pick_files()
             .unwrap_or_default()
@@ -1984,16 +3910,17 @@ impl FileHandler {
                 "All Supported",
                 &[
                     "pfe", "png", "jpg", "jpeg", "webp", "bmp", "tga", "gif", "ico", "tiff", "tif",
-                    "cr2", "cr3", "nef", "nrw", "arw", "srf", "sr2", "dng", "orf", "rw2", "pef",
-                    "raf", "raw", "rwl", "srw", "x3f", "3fr", "fff", "iiq", "mrw", "mef", "mos",
-                    "kdc", "dcr", "erf",
+                    "qoi", "pgm", "ppm", "pbm", "pnm", "pam", "cr2", "cr3", "nef", "nrw", "arw",
+                    "srf", "sr2", "dng", "orf", "rw2", "pef", "raf", "raw", "rwl", "srw", "x3f",
+                    "3fr", "fff", "iiq", "mrw", "mef", "mos", "kdc", "dcr", "erf",
                 ],
             )
             .add_filter("PaintFE Project", &["pfe"])
             .add_filter(
                 "Images",
                 &[
-                    "png", "jpg", "jpeg", "webp", "bmp", "tga", "gif", "ico", "tiff", "tif",
+                    "png", "jpg", "jpeg", "webp", "bmp", "tga", "gif", "ico", "tiff", "tif", "qoi",
+                    "pgm", "ppm", "pbm", "pnm", "pam", "dds",
                 ],
             )
             .add_filter(
@@ -2034,6 +3961,10 @@ impl FileHandler {
                         "ico" => SaveFormat::Ico,
                         "tiff" | "tif" => SaveFormat::Tiff,
                         "gif" => SaveFormat::Gif,
+                        "qoi" => SaveFormat::Qoi,
+                        "pgm" => SaveFormat::Pgm,
+                        "ppm" => SaveFormat::Ppm,
+                        "pam" => SaveFormat::Pam,
                         _ => SaveFormat::Png,
                     };
                 }
@@ -2071,15 +4002,10 @@ impl FileHandler {
                 )?;
             }
             SaveFormat::Jpeg => {
-                // JPEG doesn't support alpha, convert to RGB
-                let rgb_image = DynamicImage::ImageRgba8(image.clone()).to_rgb8();
-                let encoder = JpegEncoder::new_with_quality(&mut writer, quality);
-                encoder.write_image(
-                    rgb_image.as_raw(),
-                    rgb_image.width(),
-                    rgb_image.height(),
-                    image::ExtendedColorType::Rgb8,
-                )?;
+                // JPEG doesn't support alpha; the encoder drops it
+                let bytes = crate::jpeg_io::encode_jpeg(image, quality, &self.last_jpeg)
+                    .map_err(|e| ImageError::IoError(std::io::Error::other(e)))?;
+                writer.write_all(&bytes)?;
             }
             SaveFormat::Webp => {
                 encode_static_webp(image, &mut writer, quality, webp_lossless)?;
@@ -2102,60 +4028,56 @@ impl FileHandler {
                     image::ExtendedColorType::Rgba8,
                 )?;
             }
+            SaveFormat::Qoi => {
+                let encoder = QoiEncoder::new(&mut writer);
+                encoder.write_image(
+                    image.as_raw(),
+                    image.width(),
+                    image.height(),
+                    image::ExtendedColorType::Rgba8,
+                )?;
+            }
+            SaveFormat::Pgm | SaveFormat::Ppm | SaveFormat::Pam => {
+                let bytes =
+                    encode_netpbm(format, image.width(), image.height(), image.as_raw(), 255);
+                writer.write_all(&bytes)?;
+            }
+            SaveFormat::Dds => {
+                writer.write_all(&crate::dds_io::encode_dds(image, &self.last_dds))?;
+            }
             SaveFormat::Ico => {
-                // ICO entries limited to 256×256; scale down if needed
-                let dyn_img = if image.width() > 256 || image.height() > 256 {
-                    let scale = 256.0 / image.width().max(image.height()) as f32;
-                    let new_w = ((image.width() as f32 * scale) as u32).max(1);
-                    let new_h = ((image.height() as f32 * scale) as u32).max(1);
-                    let resized = image::imageops::resize(
-                        image,
-                        new_w,
-                        new_h,
-                        image::imageops::FilterType::Lanczos3,
-                    );
-                    DynamicImage::ImageRgba8(resized)
-                } else {
-                    DynamicImage::ImageRgba8(image.clone())
-                };
-                dyn_img.write_to(&mut writer, image::ImageFormat::Ico)?;
+                // Every standard size up to the image's own, scaled from it
+                let icon = crate::icon_io::ico_from_image(image, &IconOptions::default());
+                writer.write_all(&crate::icon_io::encode_ico(&icon)?)?;
+            }
+            SaveFormat::Icns => {
+                let icon = crate::icon_io::icns_from_image(image, &IconOptions::default());
+                writer.write_all(&crate::icon_io::encode_icns(&icon)?)?;
             }
             SaveFormat::Tiff => {
-                let err_map = |e: tiff::TiffError| {
-                    ImageError::IoError(std::io::Error::other(format!("TIFF encode error: {}", e)))
-                };
-                let mut tiff_enc = tiff::encoder::TiffEncoder::new(&mut writer).map_err(err_map)?;
-                match tiff_compression {
-                    TiffCompression::None => {
-                        tiff_enc
-                            .write_image::<tiff::encoder::colortype::RGBA8>(
-                                image.width(),
-                                image.height(),
-                                image.as_raw(),
-                            )
-                            .map_err(err_map)?;
-                    }
-                    TiffCompression::Lzw => {
-                        tiff_enc
-                            .write_image_with_compression::<tiff::encoder::colortype::RGBA8, _>(
-                                image.width(),
-                                image.height(),
-                                tiff::encoder::compression::Lzw,
-                                image.as_raw(),
-                            )
-                            .map_err(err_map)?;
-                    }
-                    TiffCompression::Deflate => {
-                        tiff_enc
-                            .write_image_with_compression::<tiff::encoder::colortype::RGBA8, _>(
-                                image.width(),
-                                image.height(),
-                                tiff::encoder::compression::Deflate::default(),
-                                image.as_raw(),
-                            )
-                            .map_err(err_map)?;
-                    }
-                }
+                crate::tiff_io::encode_flat(
+                    &mut writer,
+                    image.width(),
+                    image.height(),
+                    TiffPixels::U8(image.as_raw()),
+                    tiff_compression,
+                    quality,
+                )?;
+            }
+            SaveFormat::Psd => {
+                writer.write_all(&crate::psd::encode_flat_psd(image))?;
+            }
+            SaveFormat::Ora => {
+                writer.write_all(&encode_flat_ora(image)?)?;
+            }
+            SaveFormat::Exr => {
+                let (width, height, samples) = flat_float_samples(image);
+                let samples = ExrSamples::F16(samples.into_iter().map(f32_to_f16_bits).collect());
+                writer.write_all(&crate::hdr_io::encode_flat_exr(width, height, samples)?)?;
+            }
+            SaveFormat::Hdr => {
+                let (width, height, samples) = flat_float_samples(image);
+                writer.write_all(&crate::hdr_io::encode_radiance(width, height, &samples)?)?;
             }
             SaveFormat::Pfe => {
                 // PFE projects are saved via save_pfe(), not save_image()
@@ -2214,6 +4136,10 @@ impl FileHandler {
             last_animation_fps: self.last_animation_fps,
             last_gif_colors: self.last_gif_colors,
             last_gif_dither: self.last_gif_dither,
+            last_icon: self.last_icon.clone(),
+            last_tiff_pages: self.last_tiff_pages,
+            last_dds: self.last_dds.clone(),
+            last_jpeg: self.last_jpeg.clone(),
         };
 
         Some((image, path, project_handler))
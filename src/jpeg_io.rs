@@ -0,0 +1,204 @@
+//! JPEG export with encoder controls beyond a quality number.
+//!
+//! Default options (baseline, 4:4:4, standard Huffman tables) go through the
+//! `image` crate's encoder, so plain JPEG exports are unchanged. Anything else
+//! goes through `jpeg-encoder`, which supports progressive scans, explicit
+//! chroma subsampling and per-image optimized Huffman tables. The "target
+//! file size" mode binary-searches the quality for the largest file that
+//! still fits the requested byte budget.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::{ImageEncoder, RgbaImage};
+use jpeg_encoder::{ColorType, Encoder, SamplingFactor};
+use serde::{Deserialize, Serialize};
+
+use crate::canvas::CanvasState;
+#[cfg(target_arch = "wasm32")]
+use crate::web_fs::File;
+
+/// Chroma resolution relative to luma.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ChromaSubsampling {
+    /// Full-resolution colour.
+    #[default]
+    S444,
+    /// Colour halved horizontally.
+    S422,
+    /// Colour halved in both directions; smallest files.
+    S420,
+}
+
+impl ChromaSubsampling {
+    pub const ALL: [ChromaSubsampling; 3] = [Self::S444, Self::S422, Self::S420];
+
+    pub fn label(self) -> String {
+        match self {
+            Self::S444 => t!("jpeg.chroma.444"),
+            Self::S422 => t!("jpeg.chroma.422"),
+            Self::S420 => t!("jpeg.chroma.420"),
+        }
+    }
+
+    /// Parse `444`, `4:2:2`, `420` and similar spellings.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.replace(':', "").as_str() {
+            "444" => Some(Self::S444),
+            "422" => Some(Self::S422),
+            "420" => Some(Self::S420),
+            _ => None,
+        }
+    }
+
+    fn sampling_factor(self) -> SamplingFactor {
+        match self {
+            Self::S444 => SamplingFactor::R_4_4_4,
+            Self::S422 => SamplingFactor::R_4_2_2,
+            Self::S420 => SamplingFactor::R_4_2_0,
+        }
+    }
+}
+
+/// JPEG export settings chosen in the save dialog. The quality itself is
+/// passed alongside, as for WebP and JPEG-compressed TIFF.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JpegOptions {
+    /// Write a progressive (multi-scan) file instead of baseline.
+    pub progressive: bool,
+    pub subsampling: ChromaSubsampling,
+    /// Build Huffman tables from the image instead of the standard ones.
+    /// Lossless, usually a few percent smaller.
+    pub optimize_huffman: bool,
+    /// Pick the highest quality whose file fits in this many bytes,
+    /// ignoring the fixed quality.
+    pub target_size: Option<usize>,
+}
+
+impl Default for JpegOptions {
+    fn default() -> Self {
+        Self {
+            progressive: false,
+            subsampling: ChromaSubsampling::S444,
+            optimize_huffman: false,
+            target_size: None,
+        }
+    }
+}
+
+/// Flattened canvas ready to encode as a JPEG.
+pub struct JpegData {
+    image: RgbaImage,
+    quality: u8,
+    options: JpegOptions,
+}
+
+pub fn build_jpeg(state: &CanvasState, quality: u8, options: &JpegOptions) -> JpegData {
+    JpegData {
+        // Float layers go through their exposure and tone map like other
+        // 8-bit exports.
+        image: crate::io::prepare_export_image(state).rgba8(),
+        quality,
+        options: options.clone(),
+    }
+}
+
+pub fn write_jpeg(data: &JpegData, path: &Path) -> std::io::Result<()> {
+    let bytes =
+        encode_jpeg(&data.image, data.quality, &data.options).map_err(std::io::Error::other)?;
+    File::create(path)?.write_all(&bytes)
+}
+
+/// Encode `image` with `options`, at `quality` or, in target size mode, at
+/// the quality [`fit_quality`] finds. Alpha is dropped.
+pub fn encode_jpeg(
+    image: &RgbaImage,
+    quality: u8,
+    options: &JpegOptions,
+) -> Result<Vec<u8>, String> {
+    match options.target_size {
+        Some(target) => fit_quality(image, target, options).map(|(_, bytes)| bytes),
+        None => encode_at_quality(image, quality, options),
+    }
+}
+
+/// Encode at a fixed `quality` (1–100), ignoring `options.target_size`.
+pub fn encode_at_quality(
+    image: &RgbaImage,
+    quality: u8,
+    options: &JpegOptions,
+) -> Result<Vec<u8>, String> {
+    if !options.progressive
+        && options.subsampling == ChromaSubsampling::S444
+        && !options.optimize_huffman
+    {
+        return encode_baseline(image, quality);
+    }
+    let (width, height) = image.dimensions();
+    let (Ok(w), Ok(h)) = (u16::try_from(width), u16::try_from(height)) else {
+        return Err(format!(
+            "JPEG is limited to 65535×65535 pixels; image is {}×{}",
+            width, height
+        ));
+    };
+    let rgb: Vec<u8> = image
+        .pixels()
+        .flat_map(|px| [px[0], px[1], px[2]])
+        .collect();
+    let mut out = Vec::new();
+    let mut encoder = Encoder::new(&mut out, quality.clamp(1, 100));
+    encoder.set_sampling_factor(options.subsampling.sampling_factor());
+    encoder.set_progressive(options.progressive);
+    encoder.set_optimized_huffman_tables(options.optimize_huffman);
+    encoder
+        .encode(&rgb, w, h, ColorType::Rgb)
+        .map_err(|e| format!("JPEG encode failed: {}", e))?;
+    Ok(out)
+}
+
+/// The encoder plain JPEG exports have always used.
+fn encode_baseline(image: &RgbaImage, quality: u8) -> Result<Vec<u8>, String> {
+    let rgb = image::DynamicImage::ImageRgba8(image.clone()).to_rgb8();
+    let mut out = Vec::new();
+    JpegEncoder::new_with_quality(&mut out, quality.clamp(1, 100))
+        .write_image(
+            rgb.as_raw(),
+            rgb.width(),
+            rgb.height(),
+            image::ExtendedColorType::Rgb8,
+        )
+        .map_err(|e| format!("JPEG encode failed: {}", e))?;
+    Ok(out)
+}
+
+/// Binary-search the highest quality whose file is at most `target` bytes.
+/// Returns that quality and its file; when even quality 1 is too large, its
+/// file is returned anyway since nothing smaller is possible.
+pub fn fit_quality(
+    image: &RgbaImage,
+    target: usize,
+    options: &JpegOptions,
+) -> Result<(u8, Vec<u8>), String> {
+    let (mut lo, mut hi) = (1u8, 100u8);
+    let mut best: Option<(u8, Vec<u8>)> = None;
+    while lo <= hi {
+        let mid = lo + (hi - lo) / 2;
+        let bytes = encode_at_quality(image, mid, options)?;
+        if bytes.len() <= target {
+            best = Some((mid, bytes));
+            lo = mid + 1;
+        } else if mid == 1 {
+            break;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    match best {
+        Some(found) => Ok(found),
+        None => encode_at_quality(image, 1, options).map(|bytes| (1, bytes)),
+    }
+}
@@ -10,6 +10,7 @@
 
 #[macro_use]
 pub mod i18n;
+pub mod abr;
 pub mod app;
 pub mod assets;
 pub mod canvas;
@@ -17,11 +18,16 @@ pub mod canvas;
 pub mod cli;
 pub mod components;
 pub mod config;
+pub mod dds_io;
 pub mod document;
 pub mod experimental;
 pub mod gpu;
+pub mod gradient;
+pub mod hdr_io;
+pub mod icon_io;
 pub mod io;
 pub mod ipc;
+pub mod jpeg_io;
 pub mod linux_key_probe;
 pub mod logger;
 pub mod ops;
@@ -30,12 +36,16 @@ pub mod paintdotnet_plugins;
 pub mod par_compat;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod pdn;
+pub mod pen_probe;
 pub mod project;
+pub mod psd;
 pub mod render;
 pub mod services;
 pub mod signal_draw;
 pub mod signal_widgets;
+pub mod svg_io;
 pub mod theme;
+pub mod tiff_io;
 pub mod time_compat;
 pub mod ui;
 #[cfg(target_arch = "wasm32")]
@@ -352,6 +352,8 @@ fn configure_event_loop(builder: &mut eframe::EventLoopBuilder<eframe::UserEvent
         // Temporary low-level keyboard diagnostics: capture WM_* key state
         // without altering message dispatch behavior.
         paintfe::windows_key_probe::observe_windows_message(msg.message, msg.wParam);
+        // Pen tilt, which winit does not forward.
+        paintfe::pen_probe::observe_windows_message(msg.message, msg.wParam);
 
         // Suppress only Ctrl+letter WM_CHAR control codes (1..=26), while
         // preserving essential control keys used by dialog navigation/editing.
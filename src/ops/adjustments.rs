@@ -764,25 +764,7 @@ pub fn crop_to_selection(state: &mut CanvasState) {
         return;
     } // empty selection
 
-    let new_w = max_x - min_x + 1;
-    let new_h = max_y - min_y + 1;
-    if new_w == 0 || new_h == 0 {
-        return;
-    }
-
-    // Crop each layer
-    for layer in &mut state.layers {
-        let flat = layer.pixels.to_rgba_image();
-        let cropped = image::imageops::crop_imm(&flat, min_x, min_y, new_w, new_h).to_image();
-        layer.pixels = TiledImage::from_rgba_image(&cropped);
-    }
-
-    state.width = new_w;
-    state.height = new_h;
-    state.selection_mask = None;
-    state.invalidate_selection_overlay();
-    state.composite_cache = None;
-    state.mark_dirty(None);
+    crate::ops::trim::crop_canvas(state, min_x, min_y, max_x - min_x + 1, max_y - min_y + 1);
 }
 
 // ============================================================================
@@ -1345,8 +1327,15 @@ pub fn gradient_map(state: &mut CanvasState, layer_idx: usize, lut: &[[u8; 4]; 2
     let lut_copy = *lut;
     apply_pixel_transform(state, layer_idx, move |r, g, b, a| {
         let lum = ((0.2126 * r + 0.7152 * g + 0.0722 * b) as usize).min(255);
-        let [lr, lg, lb, _] = lut_copy[lum];
-        (lr as f32, lg as f32, lb as f32, a)
+        let [lr, lg, lb, la] = lut_copy[lum];
+        // Transparent gradient stops let the original color show through.
+        let t = la as f32 / 255.0;
+        (
+            r + (lr as f32 - r) * t,
+            g + (lg as f32 - g) * t,
+            b + (lb as f32 - b) * t,
+            a,
+        )
     });
 }
 
@@ -1359,8 +1348,15 @@ pub fn gradient_map_from_flat(
     let lut_copy = *lut;
     apply_pixel_transform_from_flat(state, layer_idx, original_flat, move |r, g, b, a| {
         let lum = ((0.2126 * r + 0.7152 * g + 0.0722 * b) as usize).min(255);
-        let [lr, lg, lb, _] = lut_copy[lum];
-        (lr as f32, lg as f32, lb as f32, a)
+        let [lr, lg, lb, la] = lut_copy[lum];
+        // Transparent gradient stops let the original color show through.
+        let t = la as f32 / 255.0;
+        (
+            r + (lr as f32 - r) * t,
+            g + (lg as f32 - g) * t,
+            b + (lb as f32 - b) * t,
+            a,
+        )
     });
 }
 
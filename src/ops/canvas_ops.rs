@@ -162,6 +162,78 @@ pub fn merge_down_as_mask(state: &mut CanvasState, layer_idx: usize) {
     state.mark_dirty(None);
 }
 
+/// Blend the layer at `layer_idx` into the layer below with its blend mode,
+/// opacity and Blend If, then remove it. Works over both layers' full
+/// extents, so off-canvas content of either layer survives and the merged
+/// layer grows to hold it. A hidden top layer is dropped without blending.
+///
+/// This function does NOT push undo history itself; callers should wrap it in
+/// `do_snapshot_op` to get a full-canvas undo snapshot.
+pub fn merge_down(state: &mut CanvasState, layer_idx: usize) {
+    if layer_idx == 0 || layer_idx >= state.layers.len() {
+        return;
+    }
+
+    // Auto-rasterize text layers before merge (pixels must be up-to-date)
+    for idx in [layer_idx, layer_idx - 1] {
+        if state.layers[idx].is_text_layer() {
+            state.ensure_all_text_layers_rasterized();
+            state.layers[idx].content = LayerContent::Raster;
+        }
+    }
+
+    let top = &state.layers[layer_idx];
+    if top.visible {
+        let (blend_mode, opacity, blend_if) = (top.blend_mode, top.opacity, top.blend_if);
+        let linear = state.linear_blending;
+        let (top_origin, top_image) = top.extent_rgba();
+        let (bottom_origin, bottom_image) = state.layers[layer_idx - 1].extent_rgba();
+
+        // Union of both extents, in canvas coordinates.
+        let x0 = top_origin.0.min(bottom_origin.0);
+        let y0 = top_origin.1.min(bottom_origin.1);
+        let x1 = (top_origin.0 + top_image.width() as i32)
+            .max(bottom_origin.0 + bottom_image.width() as i32);
+        let y1 = (top_origin.1 + top_image.height() as i32)
+            .max(bottom_origin.1 + bottom_image.height() as i32);
+        let mut merged = image::RgbaImage::new((x1 - x0) as u32, (y1 - y0) as u32);
+        image::imageops::replace(
+            &mut merged,
+            &bottom_image,
+            (bottom_origin.0 - x0) as i64,
+            (bottom_origin.1 - y0) as i64,
+        );
+        let (tx, ty) = (top_origin.0 - x0, top_origin.1 - y0);
+        for (mx, my, base) in merged.enumerate_pixels_mut() {
+            let (sx, sy) = (mx as i32 - tx, my as i32 - ty);
+            let top_pixel = if sx >= 0
+                && sy >= 0
+                && (sx as u32) < top_image.width()
+                && (sy as u32) < top_image.height()
+            {
+                *top_image.get_pixel(sx as u32, sy as u32)
+            } else {
+                Rgba([0, 0, 0, 0])
+            };
+            let top_pixel = blend_if.apply(top_pixel, *base);
+            // Dissolve hashes canvas coordinates; off-canvas ones just wrap.
+            let (cx, cy) = (mx as i32 + x0, my as i32 + y0);
+            *base = CanvasState::blend_pixel_at(
+                *base, top_pixel, blend_mode, opacity, cx as u32, cy as u32, linear,
+            );
+        }
+        let (w, h) = (state.width, state.height);
+        state.layers[layer_idx - 1].set_extent_rgba((x0, y0), &merged, w, h);
+    }
+
+    state.layers.remove(layer_idx);
+    if state.active_layer_index >= layer_idx && state.active_layer_index > 0 {
+        state.active_layer_index -= 1;
+    }
+
+    state.mark_dirty(None);
+}
+
 pub fn add_layer_mask_reveal_all(state: &mut CanvasState, layer_idx: usize) {
     let Some(layer) = state.layers.get_mut(layer_idx) else {
         return;
@@ -340,6 +412,28 @@ pub fn add_text_layer(state: &mut CanvasState, history: &mut HistoryManager) {
     state.mark_dirty(None);
 }
 
+/// Add rendered SVG layers above the active layer, bottom first, centered on
+/// the canvas. Unnamed layers are called `name`.
+///
+/// Does not push history; callers wrap it in `do_snapshot_op`.
+pub fn import_svg_layers(
+    state: &mut CanvasState,
+    layers: Vec<crate::svg_io::SvgLayer>,
+    name: &str,
+) {
+    for svg_layer in layers {
+        let layer_name = if svg_layer.name.is_empty() {
+            name
+        } else {
+            svg_layer.name.as_str()
+        };
+        crate::ops::adjustments::import_layer_from_image(state, &svg_layer.image, layer_name);
+        if let Some(layer) = state.layers.get_mut(state.active_layer_index) {
+            layer.visible = svg_layer.visible;
+        }
+    }
+}
+
 /// Delete the active layer.
 pub fn delete_layer(state: &mut CanvasState, history: &mut HistoryManager) {
     if state.layers.is_empty() {
@@ -363,6 +457,10 @@ pub fn delete_layer(state: &mut CanvasState, history: &mut HistoryManager) {
         source_metadata: removed.source_metadata,
         webp_frame_compression: removed.webp_frame_compression,
         deep_pixels: removed.deep_pixels,
+        locks: removed.locks,
+        off_canvas: removed.off_canvas,
+        blend_if: removed.blend_if,
+        move_offset: removed.move_offset,
     })));
 
     // Clear active text layer if it was the deleted layer
@@ -411,6 +509,10 @@ pub fn duplicate_layer(state: &mut CanvasState, history: &mut HistoryManager) {
     dup.source_metadata = src.source_metadata.clone();
     dup.webp_frame_compression = src.webp_frame_compression;
     dup.deep_pixels = src.deep_pixels.clone();
+    dup.locks = src.locks;
+    dup.off_canvas = src.off_canvas.clone();
+    dup.blend_if = src.blend_if;
+    dup.move_offset = src.move_offset;
 
     let new_idx = idx + 1;
     let dup_pixels = dup.pixels.clone();
@@ -426,6 +528,10 @@ pub fn duplicate_layer(state: &mut CanvasState, history: &mut HistoryManager) {
     let dup_source_metadata = dup.source_metadata.clone();
     let dup_webp_frame_compression = dup.webp_frame_compression;
     let dup_deep_pixels = dup.deep_pixels.clone();
+    let dup_locks = dup.locks;
+    let dup_off_canvas = dup.off_canvas.clone();
+    let dup_blend_if = dup.blend_if;
+    let dup_move_offset = dup.move_offset;
 
     state.layers.insert(new_idx, dup);
     state.active_layer_index = new_idx;
@@ -446,6 +552,10 @@ pub fn duplicate_layer(state: &mut CanvasState, history: &mut HistoryManager) {
         source_metadata: dup_source_metadata,
         webp_frame_compression: dup_webp_frame_compression,
         deep_pixels: dup_deep_pixels,
+        locks: dup_locks,
+        off_canvas: dup_off_canvas,
+        blend_if: dup_blend_if,
+        move_offset: dup_move_offset,
     })));
 
     state.mark_dirty(None);
@@ -728,7 +728,7 @@ pub fn cut_selection(state: &mut CanvasState, transparent_cutout: bool) -> bool
 /// Returns None only if the layer is empty / fully transparent.
 pub fn extract_to_overlay(state: &mut CanvasState) -> Option<PasteOverlay> {
     let idx = state.active_layer_index;
-    if idx >= state.layers.len() {
+    if idx >= state.layers.len() || state.layers[idx].locks.blocks_transform() {
         return None;
     }
 
@@ -789,9 +789,9 @@ pub fn extract_to_overlay(state: &mut CanvasState) -> Option<PasteOverlay> {
         overlay.overwrite_mask = Some(overwrite_mask);
         Some(overlay)
     } else {
-        // -- No selection: extract entire active layer --
+        // -- No selection: extract entire active layer, off-canvas content included --
         let layer = &state.layers[idx];
-        let img = layer.pixels.to_rgba_image();
+        let (origin, img) = layer.extent_rgba();
         // Check if there's any content.
         let has_content = img.pixels().any(|p| p[3] > 0);
         if !has_content {
@@ -801,10 +801,14 @@ pub fn extract_to_overlay(state: &mut CanvasState) -> Option<PasteOverlay> {
         // Blank the layer.
         let blank = TiledImage::new(cw, ch);
         state.layers[idx].pixels = blank;
+        state.layers[idx].off_canvas = None;
         state.mark_dirty(None);
 
+        let center_x = origin.0 as f32 + img.width() as f32 / 2.0;
+        let center_y = origin.1 as f32 + img.height() as f32 / 2.0;
         let mut overlay = PasteOverlay::new(img, cw, ch);
-        overlay.center = Pos2::new(cw as f32 / 2.0, ch as f32 / 2.0);
+        overlay.center = Pos2::new(center_x, center_y);
+        overlay.lifted_layer_center = Some(overlay.center);
         Some(overlay)
     }
 }
@@ -835,6 +839,9 @@ pub struct PasteOverlay {
     pub overwrite_transparent_pixels: bool,
     /// Optional mask limiting transparent overwrite to the copied selection shape.
     pub overwrite_mask: Option<GrayImage>,
+    /// Center the whole layer was lifted from by Move Pixels. A commit that
+    /// only translates adds the distance to the layer's move offset.
+    pub lifted_layer_center: Option<Pos2>,
 
     // --- Interaction state ---
     /// Which handle is being dragged, if any.
@@ -901,6 +908,7 @@ impl PasteOverlay {
             anti_aliasing: true,
             overwrite_transparent_pixels: false,
             overwrite_mask: None,
+            lifted_layer_center: None,
             active_handle: None,
             drag_start_mouse: None,
             drag_start_center: Pos2::ZERO,
@@ -2063,10 +2071,13 @@ impl PasteOverlay {
             bb_max_x = bb_max_x.max(c.x);
             bb_max_y = bb_max_y.max(c.y);
         }
-        let row_start = (bb_min_y.floor().max(0.0)) as u32;
-        let row_end = (bb_max_y.ceil().min(ch as f32 - 1.0)) as u32;
-        let col_start = (bb_min_x.floor().max(0.0)) as u32;
-        let col_end = (bb_max_x.ceil().min(cw as f32 - 1.0)) as u32;
+        // Not clamped to the canvas: pixels landing outside it are kept as
+        // off-canvas layer content.
+        let row_start = bb_min_y.floor() as i32;
+        let row_end = bb_max_y.ceil() as i32;
+        let col_start = bb_min_x.floor() as i32;
+        let col_end = bb_max_x.ceil() as i32;
+        let inside = |x: i32, y: i32| x >= 0 && y >= 0 && (x as u32) < cw && (y as u32) < ch;
 
         let anchor = self.anchor_canvas();
         let cos = self.rotation.cos();
@@ -2079,13 +2090,21 @@ impl PasteOverlay {
         // Flatten layer for fast reads, compute source pixels in parallel,
         // then apply patches back to the tiled layer.
         let layer = &mut state.layers[idx];
+        if layer.locks.pixels_locked() {
+            return;
+        }
+        let locks = layer.locks;
         let flat = layer.pixels.to_rgba_image();
 
-        let rows: Vec<u32> = (row_start..=row_end).collect();
-        let patches: Vec<(u32, u32, Rgba<u8>)> = rows
+        let rows: Vec<i32> = (row_start..=row_end).collect();
+        // Per row: final pixels on the canvas, and (source pixel, overwrite)
+        // for pixels outside it, which are blended against off-canvas content.
+        type OutsidePatch = (i32, i32, Rgba<u8>, bool);
+        let row_results: Vec<(Vec<(u32, u32, Rgba<u8>)>, Vec<OutsidePatch>)> = rows
             .par_iter()
-            .flat_map(|&dy| {
+            .map(|&dy| {
                 let mut row_patches = Vec::new();
+                let mut outside_patches = Vec::new();
                 let py = dy as f32 + 0.5;
                 let ry = py - anchor.y;
                 for dx in col_start..=col_end {
@@ -2123,22 +2142,52 @@ impl PasteOverlay {
                         let iy = (local_y as u32).min(scaled_h - 1);
                         *scaled.get_pixel(ix, iy)
                     };
-                    if overwrite_transparent
-                        && self.overwrite_mask_allows(local_x, local_y, scaled_mask.as_ref())
-                    {
-                        row_patches.push((dx, dy, src_px));
-                    } else if src_px[3] > 0 {
-                        let dst = *flat.get_pixel(dx, dy);
+                    let overwrite = overwrite_transparent
+                        && self.overwrite_mask_allows(local_x, local_y, scaled_mask.as_ref());
+                    if !overwrite && src_px[3] == 0 {
+                        continue;
+                    }
+                    if !inside(dx, dy) {
+                        outside_patches.push((dx, dy, src_px, overwrite));
+                    } else if overwrite {
+                        row_patches.push((dx as u32, dy as u32, src_px));
+                    } else {
+                        let dst = *flat.get_pixel(dx as u32, dy as u32);
                         let blended = alpha_blend(dst, src_px);
-                        row_patches.push((dx, dy, blended));
+                        row_patches.push((dx as u32, dy as u32, blended));
                     }
                 }
-                row_patches
+                (row_patches, outside_patches)
             })
             .collect();
 
-        for (dx, dy, px) in patches {
-            layer.pixels.put_pixel(dx, dy, px);
+        let mut outside_overwrite = Vec::new();
+        let mut outside_blend = Vec::new();
+        for (patches, outside) in row_results {
+            for (dx, dy, px) in patches {
+                let px = locks.filter_pixel(*flat.get_pixel(dx, dy), px);
+                layer.pixels.put_pixel(dx, dy, px);
+            }
+            for (dx, dy, px, overwrite) in outside {
+                if overwrite {
+                    outside_overwrite.push((dx, dy, px));
+                } else {
+                    outside_blend.push((dx, dy, px));
+                }
+            }
+        }
+        layer.apply_off_canvas_pixels(&outside_overwrite, |dst, src| locks.filter_pixel(dst, src));
+        layer.apply_off_canvas_pixels(&outside_blend, |dst, src| {
+            locks.filter_pixel(dst, alpha_blend(dst, src))
+        });
+        if let Some(from) = self.lifted_layer_center
+            && self.rotation == 0.0
+            && self.scale_x == 1.0
+            && self.scale_y == 1.0
+        {
+            let delta = (self.center - from).round();
+            layer.move_offset.0 += delta.x as i32;
+            layer.move_offset.1 += delta.y as i32;
         }
 
         state.mark_dirty(None);
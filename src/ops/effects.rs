@@ -2,7 +2,7 @@
 // EFFECTS ENGINE -- GPU-ready, rayon-parallelized image effects
 // ============================================================================
 
-use crate::canvas::{CanvasState, TiledImage};
+use crate::canvas::CanvasState;
 use crate::par_compat::*;
 use image::{GrayImage, Rgba, RgbaImage};
 
@@ -101,8 +101,9 @@ fn commit_to_layer(state: &mut CanvasState, layer_idx: usize, result: &RgbaImage
     if layer_idx >= state.layers.len() {
         return;
     }
-    state.layers[layer_idx].pixels = TiledImage::from_rgba_image(result);
-    state.mark_dirty(None);
+    if state.layers[layer_idx].set_pixels_with_locks(result) {
+        state.mark_dirty(None);
+    }
 }
 
 #[inline]
@@ -2,13 +2,14 @@
 // IMAGE FILTERS — Gaussian blur, desaturation, etc.
 // ============================================================================
 
-use crate::canvas::{CanvasState, TiledImage};
+use crate::canvas::{CanvasState, TiledImage, linear_to_srgb8, srgb8_to_linear};
 use crate::par_compat::*;
 use image::{RgbaImage, imageops};
 
 /// Apply a Gaussian blur to the active layer.
 /// `sigma` controls the blur radius / strength.
 /// If a selection mask exists, only selected pixels are blurred.
+/// Colours are averaged in linear light when `state.linear_blending` is on.
 ///
 /// When `gpu` is `Some`, the blur is executed on the GPU (compute shader)
 /// for dramatically faster processing on large images.
@@ -18,7 +19,12 @@ pub fn gaussian_blur_layer(state: &mut CanvasState, layer_idx: usize, sigma: f32
     }
     let layer = &mut state.layers[layer_idx];
     let flat = layer.pixels.to_rgba_image();
-    let result = blur_with_selection(&flat, sigma, state.selection_mask.as_ref());
+    let result = blur_with_selection(
+        &flat,
+        sigma,
+        state.selection_mask.as_ref(),
+        state.linear_blending,
+    );
     let layer = &mut state.layers[layer_idx];
     layer.pixels = TiledImage::from_rgba_image(&result);
     state.mark_dirty(None);
@@ -55,9 +61,15 @@ pub fn gaussian_blur_layer_gpu(
     let flat = layer.pixels.to_rgba_image();
     let (w, h) = (flat.width(), flat.height());
 
-    // GPU blur (no selection support yet — use CPU fallback for selections).
-    if state.selection_mask.is_some() {
-        let result = blur_with_selection(&flat, sigma, state.selection_mask.as_ref());
+    // GPU blur (no selection or linear-light support yet — use the CPU
+    // fallback for those).
+    if state.selection_mask.is_some() || state.linear_blending {
+        let result = blur_with_selection(
+            &flat,
+            sigma,
+            state.selection_mask.as_ref(),
+            state.linear_blending,
+        );
         let layer = &mut state.layers[layer_idx];
         layer.pixels = TiledImage::from_rgba_image(&result);
     } else {
@@ -80,7 +92,12 @@ pub fn gaussian_blur_layer_from_flat(
     if layer_idx >= state.layers.len() {
         return;
     }
-    let result = blur_with_selection(original_flat, sigma, state.selection_mask.as_ref());
+    let result = blur_with_selection(
+        original_flat,
+        sigma,
+        state.selection_mask.as_ref(),
+        state.linear_blending,
+    );
     let layer = &mut state.layers[layer_idx];
     layer.pixels = TiledImage::from_rgba_image(&result);
     state.mark_dirty(None);
@@ -111,9 +128,14 @@ pub fn gaussian_blur_layer_from_flat_gpu(
         return;
     }
 
-    if state.selection_mask.is_some() {
-        // Fall back to CPU for selection-masked blurs.
-        let result = blur_with_selection(original_flat, sigma, state.selection_mask.as_ref());
+    if state.selection_mask.is_some() || state.linear_blending {
+        // Fall back to CPU for selection-masked and linear-light blurs.
+        let result = blur_with_selection(
+            original_flat,
+            sigma,
+            state.selection_mask.as_ref(),
+            state.linear_blending,
+        );
         let layer = &mut state.layers[layer_idx];
         layer.pixels = TiledImage::from_rgba_image(&result);
     } else {
@@ -131,14 +153,21 @@ pub fn blur_with_selection_pub(
     flat: &RgbaImage,
     sigma: f32,
     mask: Option<&image::GrayImage>,
+    linear: bool,
 ) -> RgbaImage {
-    blur_with_selection(flat, sigma, mask)
+    blur_with_selection(flat, sigma, mask, linear)
 }
 
 /// Core blur logic: when a selection mask exists, only blur the bounding-box
 /// region of the selection, then composite back.  This is dramatically faster
-/// for small selections on large canvases.
-fn blur_with_selection(flat: &RgbaImage, sigma: f32, mask: Option<&image::GrayImage>) -> RgbaImage {
+/// for small selections on large canvases. `linear` averages colors in
+/// linear light.
+fn blur_with_selection(
+    flat: &RgbaImage,
+    sigma: f32,
+    mask: Option<&image::GrayImage>,
+    linear: bool,
+) -> RgbaImage {
     if let Some(mask) = mask {
         let (mw, mh) = (mask.width(), mask.height());
         let mask_raw = mask.as_raw();
@@ -174,7 +203,7 @@ fn blur_with_selection(flat: &RgbaImage, sigma: f32, mask: Option<&image::GrayIm
 
         // Extract sub-image and blur only that region.
         let sub = imageops::crop_imm(flat, crop_x, crop_y, crop_w, crop_h).to_image();
-        let blurred_sub = parallel_gaussian_blur(&sub, sigma);
+        let blurred_sub = parallel_gaussian_blur(&sub, sigma, linear);
 
         // Composite: start from original, replace only selected pixels.
         let mut out = flat.clone();
@@ -202,7 +231,7 @@ fn blur_with_selection(flat: &RgbaImage, sigma: f32, mask: Option<&image::GrayIm
         out
     } else {
         // No selection — blur entire image.
-        parallel_gaussian_blur(flat, sigma)
+        parallel_gaussian_blur(flat, sigma, linear)
     }
 }
 
@@ -236,10 +265,12 @@ fn build_gaussian_kernel(sigma: f32) -> Vec<f32> {
 /// Rayon-parallelized separable Gaussian blur operating on raw f32 buffers.
 /// Public alias for use by other effect modules.
 pub fn parallel_gaussian_blur_pub(src: &RgbaImage, sigma: f32) -> RgbaImage {
-    parallel_gaussian_blur(src, sigma)
+    parallel_gaussian_blur(src, sigma, false)
 }
 
-fn parallel_gaussian_blur(src: &RgbaImage, sigma: f32) -> RgbaImage {
+/// With `linear` the color channels are decoded from sRGB on the way in and
+/// encoded again on the way out; alpha is blurred as stored.
+fn parallel_gaussian_blur(src: &RgbaImage, sigma: f32, linear: bool) -> RgbaImage {
     let w = src.width() as usize;
     let h = src.height() as usize;
     if w == 0 || h == 0 {
@@ -251,6 +282,21 @@ fn parallel_gaussian_blur(src: &RgbaImage, sigma: f32) -> RgbaImage {
     let src_raw = src.as_raw();
 
     let pixel_count = w * h * 4;
+    // Colour channels on the 0..255 scale, in linear light when asked.
+    let load = |v: u8| {
+        if linear {
+            srgb8_to_linear(v) * 255.0
+        } else {
+            v as f32
+        }
+    };
+    let store = |v: f32| {
+        if linear {
+            linear_to_srgb8(v / 255.0)
+        } else {
+            v.round().clamp(0.0, 255.0) as u8
+        }
+    };
 
     // Keep only the precision-bearing intermediate in f32. Reading the first
     // pass from u8 and writing the final pass directly to u8 cuts peak working
@@ -271,9 +317,9 @@ fn parallel_gaussian_blur(src: &RgbaImage, sigma: f32) -> RgbaImage {
                         .max(0)
                         .min(w as isize - 1) as usize;
                     let idx = row_in_start + sx * 4;
-                    r += src_raw[idx] as f32 * kv;
-                    g += src_raw[idx + 1] as f32 * kv;
-                    b += src_raw[idx + 2] as f32 * kv;
+                    r += load(src_raw[idx]) * kv;
+                    g += load(src_raw[idx + 1]) * kv;
+                    b += load(src_raw[idx + 2]) * kv;
                     a += src_raw[idx + 3] as f32 * kv;
                 }
                 let out_idx = x * 4;
@@ -305,9 +351,9 @@ fn parallel_gaussian_blur(src: &RgbaImage, sigma: f32) -> RgbaImage {
                     a += buf_h[idx + 3] * kv;
                 }
                 let out_idx = x * 4;
-                row_out[out_idx] = r.round().clamp(0.0, 255.0) as u8;
-                row_out[out_idx + 1] = g.round().clamp(0.0, 255.0) as u8;
-                row_out[out_idx + 2] = b.round().clamp(0.0, 255.0) as u8;
+                row_out[out_idx] = store(r);
+                row_out[out_idx + 1] = store(g);
+                row_out[out_idx + 2] = store(b);
                 row_out[out_idx + 3] = a.round().clamp(0.0, 255.0) as u8;
             }
         });
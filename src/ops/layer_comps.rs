@@ -0,0 +1,72 @@
+// ============================================================================
+// LAYER COMP EXPORT — write each comp of a document to its own file
+// ============================================================================
+
+use std::path::{Path, PathBuf};
+
+use crate::canvas::CanvasState;
+use crate::components::dialogs::{SaveFormat, TiffCompression};
+use crate::components::history::CanvasSnapshot;
+
+/// `name` trimmed, with characters that are not safe in file names replaced
+/// by `_`.
+pub fn safe_file_name(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ' ') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// File stem for a comp export: `<stem>-<comp>`, the comp name made safe
+/// with [`safe_file_name`].
+pub fn comp_file_stem(stem: &str, comp: &str) -> String {
+    format!("{}-{}", stem, safe_file_name(comp))
+}
+
+/// Export every layer comp as a flat image in `dir`, named
+/// `<stem>-<comp>.<ext>`. The layer stack is restored afterwards, even when
+/// a write fails. Returns the paths written.
+pub fn export_all_comps(
+    state: &mut CanvasState,
+    dir: &Path,
+    stem: &str,
+    format: SaveFormat,
+    quality: u8,
+) -> Result<Vec<PathBuf>, String> {
+    if state.layer_comps.is_empty() {
+        return Err("the document has no layer comps".to_string());
+    }
+    state.ensure_all_text_layers_rasterized();
+    let before = CanvasSnapshot::capture(state);
+    let mut written = Vec::with_capacity(state.layer_comps.len());
+    let mut result = Ok(());
+    for idx in 0..state.layer_comps.len() {
+        let name = state.layer_comps[idx].name.clone();
+        state.apply_layer_comp(idx);
+        let path = dir.join(format!(
+            "{}.{}",
+            comp_file_stem(stem, &name),
+            format.extension()
+        ));
+        if let Err(e) = crate::io::encode_canvas_state_and_write(
+            state,
+            &path,
+            format,
+            quality,
+            TiffCompression::default(),
+            true,
+        ) {
+            result = Err(format!("comp '{}' failed: {}", name, e));
+            break;
+        }
+        written.push(path);
+    }
+    before.restore_into(state);
+    result.map(|()| written)
+}
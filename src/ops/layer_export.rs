@@ -0,0 +1,328 @@
+// ============================================================================
+// LAYER EXPORT — write each layer or top-level group to its own file
+// ============================================================================
+
+use std::path::{Path, PathBuf};
+
+use image::RgbaImage;
+
+use crate::canvas::CanvasState;
+use crate::components::dialogs::{SaveFormat, TiffCompression};
+use crate::config::export_presets::ExportPreset;
+use crate::ops::layer_comps::safe_file_name;
+use crate::ops::slices::unique_stem;
+
+/// Name template used unless the user gives one.
+pub const DEFAULT_LAYER_NAME_TEMPLATE: &str = "{doc}_{layer}_{index}";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerExportOptions {
+    /// File stem template. `{doc}`, `{layer}` and `{index}` are replaced by
+    /// the document name, the layer or group name and the 1-based position.
+    pub template: String,
+    /// Export each top-level folder as one file instead of its layers one
+    /// by one. Layers outside any folder are still exported on their own.
+    pub groups: bool,
+    /// Crop each file to the bounds of its non-transparent pixels.
+    pub trim: bool,
+    /// Also export hidden layers and groups.
+    pub include_hidden: bool,
+    /// Format and quality used without a preset.
+    pub format: SaveFormat,
+    pub quality: u8,
+    /// Write through this preset instead: its format, scale, depth and
+    /// suffix apply to every file.
+    pub preset: Option<ExportPreset>,
+}
+
+impl Default for LayerExportOptions {
+    fn default() -> Self {
+        Self {
+            template: DEFAULT_LAYER_NAME_TEMPLATE.to_string(),
+            groups: false,
+            trim: false,
+            include_hidden: false,
+            format: SaveFormat::Png,
+            quality: 90,
+            preset: None,
+        }
+    }
+}
+
+impl LayerExportOptions {
+    /// Check the options can be exported with; names the problem otherwise.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.template.trim().is_empty() {
+            return Err("the name template is empty".to_string());
+        }
+        if self.template.contains(['/', '\\']) {
+            return Err("the name template may not contain path separators".to_string());
+        }
+        match &self.preset {
+            Some(preset) => preset.validate(),
+            None if self.format == SaveFormat::Pfe => {
+                Err("layers are exported as images, not PFE projects".to_string())
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// One exported file: a single layer, or a top-level folder with
+/// everything inside it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerExportUnit {
+    /// Layer or folder name, used for `{layer}`.
+    pub name: String,
+    /// Layers drawn into the file, bottom first.
+    pub layers: Vec<usize>,
+    /// The top-level folder, when a whole group is exported.
+    pub folder_id: Option<u64>,
+}
+
+/// The files [`export_layers`] writes, in layer panel order (top of the
+/// stack first). Adjustment layers are only exported as part of a group.
+pub fn layer_export_units(
+    state: &CanvasState,
+    options: &LayerExportOptions,
+) -> Vec<LayerExportUnit> {
+    let mut units: Vec<LayerExportUnit> = Vec::new();
+    for idx in (0..state.layers.len()).rev() {
+        let layer = &state.layers[idx];
+        let top_folder = layer
+            .folder_id
+            .filter(|_| options.groups)
+            .and_then(|id| state.folder_ancestors(id).last().copied());
+        match top_folder {
+            Some(folder_id) => {
+                if let Some(unit) = units.iter_mut().find(|u| u.folder_id == Some(folder_id)) {
+                    unit.layers.insert(0, idx);
+                    continue;
+                }
+                let Some(folder) = state.layer_folder(folder_id) else {
+                    continue;
+                };
+                if !options.include_hidden && !folder.visible {
+                    continue;
+                }
+                units.push(LayerExportUnit {
+                    name: folder.name.clone(),
+                    layers: vec![idx],
+                    folder_id: Some(folder_id),
+                });
+            }
+            None => {
+                if layer.is_adjustment_layer()
+                    || (!options.include_hidden && !state.layer_effectively_visible(idx))
+                {
+                    continue;
+                }
+                units.push(LayerExportUnit {
+                    name: layer.name.clone(),
+                    layers: vec![idx],
+                    folder_id: None,
+                });
+            }
+        }
+    }
+    // A visible group whose layers are all hidden would export empty.
+    units.retain(|unit| {
+        options.include_hidden || unit.layers.iter().any(|&idx| state.layers[idx].visible)
+    });
+    units
+}
+
+/// File stem for the `index`-th (1-based) of `count` files. Names are made
+/// safe with [`safe_file_name`]; `{index}` is zero-padded to the width of
+/// `count` so the files sort in panel order.
+pub fn layer_file_stem(
+    template: &str,
+    doc: &str,
+    layer: &str,
+    index: usize,
+    count: usize,
+) -> String {
+    let width = count.max(1).to_string().len();
+    let fields = [
+        ("{doc}", safe_file_name(doc)),
+        ("{layer}", safe_file_name(layer)),
+        ("{index}", format!("{:0width$}", index, width = width)),
+    ];
+    let mut stem = String::new();
+    let mut rest = template.trim();
+    'outer: while let Some(c) = rest.chars().next() {
+        for (token, value) in &fields {
+            if let Some(tail) = rest.strip_prefix(token) {
+                stem.push_str(value);
+                rest = tail;
+                continue 'outer;
+            }
+        }
+        stem.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    stem
+}
+
+/// Bounding box `(x, y, w, h)` of the non-transparent pixels, or `None`
+/// when the image is fully transparent.
+pub fn content_bounds(image: &RgbaImage) -> Option<(u32, u32, u32, u32)> {
+    let mut bounds: Option<(u32, u32, u32, u32)> = None;
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if pixel[3] == 0 {
+            continue;
+        }
+        bounds = Some(match bounds {
+            Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x + 1), y1.max(y + 1)),
+            None => (x, y, x + 1, y + 1),
+        });
+    }
+    bounds.map(|(x0, y0, x1, y1)| (x0, y0, x1 - x0, y1 - y0))
+}
+
+/// Export every unit from [`layer_export_units`] into `dir`, using `doc`
+/// for `{doc}`. Layer and folder visibility and the document bounds are
+/// restored afterwards, even when a write fails. Returns the paths written.
+pub fn export_layers(
+    state: &mut CanvasState,
+    dir: &Path,
+    doc: &str,
+    options: &LayerExportOptions,
+) -> Result<Vec<PathBuf>, String> {
+    options.validate()?;
+    let units = layer_export_units(state, options);
+    if units.is_empty() {
+        return Err("the document has no layers to export".to_string());
+    }
+    state.ensure_all_text_layers_rasterized();
+    let layer_visible: Vec<bool> = state.layers.iter().map(|l| l.visible).collect();
+    let folder_visible: Vec<bool> = state.layer_folders.iter().map(|f| f.visible).collect();
+    let document_bounds = state.document_bounds;
+
+    let mut stems: Vec<String> = Vec::with_capacity(units.len());
+    let mut written = Vec::with_capacity(units.len());
+    let mut result = Ok(());
+    for (i, unit) in units.iter().enumerate() {
+        show_only(state, unit, &layer_visible, &folder_visible, options);
+        state.document_bounds = document_bounds;
+        if options.trim {
+            state.document_bounds = trimmed_bounds(state).or(document_bounds);
+        }
+
+        let stem = unique_stem(
+            &stems,
+            layer_file_stem(&options.template, doc, &unit.name, i + 1, units.len()),
+        );
+        stems.push(stem.clone());
+
+        let outcome = match &options.preset {
+            Some(preset) => {
+                let path = preset.output_path(dir, &stem);
+                crate::io::PresetExport::build(state, preset)
+                    .and_then(|export| export.write(&path))
+                    .map(|()| path)
+            }
+            None => {
+                let path = dir.join(format!("{}.{}", stem, options.format.extension()));
+                crate::io::encode_canvas_state_and_write(
+                    state,
+                    &path,
+                    options.format,
+                    options.quality,
+                    TiffCompression::default(),
+                    true,
+                )
+                .map(|()| path)
+                .map_err(|e| e.to_string())
+            }
+        };
+        match outcome {
+            Ok(path) => written.push(path),
+            Err(e) => {
+                result = Err(format!("'{}' failed: {}", unit.name, e));
+                break;
+            }
+        }
+    }
+
+    for (layer, visible) in state.layers.iter_mut().zip(layer_visible) {
+        layer.visible = visible;
+    }
+    for (folder, visible) in state.layer_folders.iter_mut().zip(folder_visible) {
+        folder.visible = visible;
+    }
+    state.document_bounds = document_bounds;
+    result.map(|()| written)
+}
+
+/// The composite of each unit on its own, in the order given. Layer and
+/// folder visibility are restored afterwards.
+pub fn render_units(
+    state: &mut CanvasState,
+    units: &[LayerExportUnit],
+    options: &LayerExportOptions,
+) -> Vec<RgbaImage> {
+    state.ensure_all_text_layers_rasterized();
+    let layer_visible: Vec<bool> = state.layers.iter().map(|l| l.visible).collect();
+    let folder_visible: Vec<bool> = state.layer_folders.iter().map(|f| f.visible).collect();
+    let images = units
+        .iter()
+        .map(|unit| {
+            show_only(state, unit, &layer_visible, &folder_visible, options);
+            state.composite()
+        })
+        .collect();
+    for (layer, visible) in state.layers.iter_mut().zip(layer_visible) {
+        layer.visible = visible;
+    }
+    for (folder, visible) in state.layer_folders.iter_mut().zip(folder_visible) {
+        folder.visible = visible;
+    }
+    images
+}
+
+/// Hide everything but `unit`. With `include_hidden` a hidden layer and the
+/// folders around it are shown; inside an exported group, hidden layers
+/// stay hidden, as they are usually alternates rather than part of it.
+fn show_only(
+    state: &mut CanvasState,
+    unit: &LayerExportUnit,
+    layer_visible: &[bool],
+    folder_visible: &[bool],
+    options: &LayerExportOptions,
+) {
+    let force = options.include_hidden && unit.folder_id.is_none();
+    for (idx, layer) in state.layers.iter_mut().enumerate() {
+        layer.visible = unit.layers.contains(&idx) && (layer_visible[idx] || force);
+    }
+    for (folder, &visible) in state.layer_folders.iter_mut().zip(folder_visible) {
+        folder.visible = visible;
+    }
+    if options.include_hidden {
+        let reveal = match unit.folder_id {
+            Some(id) => vec![id],
+            None => unit
+                .layers
+                .first()
+                .and_then(|&idx| state.layers[idx].folder_id)
+                .map(|id| state.folder_ancestors(id))
+                .unwrap_or_default(),
+        };
+        for folder in &mut state.layer_folders {
+            if reveal.contains(&folder.id) {
+                folder.visible = true;
+            }
+        }
+    }
+}
+
+/// Content bounds of the current composite, within the document bounds.
+fn trimmed_bounds(state: &CanvasState) -> Option<(u32, u32, u32, u32)> {
+    let (x, y, w, h) = content_bounds(&state.composite())?;
+    let (bx, by, bw, bh) = state
+        .document_bounds
+        .unwrap_or((0, 0, state.width, state.height));
+    let (x0, y0) = (x.max(bx), y.max(by));
+    let (x1, y1) = ((x + w).min(bx + bw), (y + h).min(by + bh));
+    (x1 > x0 && y1 > y0).then(|| (x0, y0, x1 - x0, y1 - y0))
+}
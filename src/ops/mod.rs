@@ -10,12 +10,17 @@ pub mod filters;
 #[cfg(target_arch = "wasm32")]
 pub mod google_fonts;
 pub mod inpaint;
+pub mod layer_comps;
+pub mod layer_export;
+pub mod page_setup;
 pub mod print;
 pub mod scripting;
 pub mod shapes;
+pub mod slices;
 pub mod text;
 pub mod text_layer;
 pub mod transform;
+pub mod trim;
 
 /// Open a URL in a new browser tab (web only) — e.g. linking out to the
 /// desktop-download page from an in-app prompt.
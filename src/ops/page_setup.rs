@@ -0,0 +1,578 @@
+// ============================================================================
+// PAGE SETUP — paper layout and PDF export for printing
+// ============================================================================
+//
+// Layout works in PostScript points (1/72 in) with a top-left origin. Each
+// page places the image somewhere on the paper and clips it to the trim box
+// grown by the bleed. The PDF writer flips to PDF's bottom-left origin and
+// embeds only the pixels each page shows, so tiled posters don't repeat the
+// whole image on every page.
+
+use image::{Rgba, RgbaImage};
+use kurbo::Rect;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+
+const MM_TO_PT: f64 = 72.0 / 25.4;
+/// Length of a crop mark and its gap from the bleed edge.
+const CROP_MARK_LEN: f64 = 12.0;
+const CROP_MARK_GAP: f64 = 3.0;
+/// Most pages a tiled poster may span; past this the DPI is surely a typo.
+pub const MAX_TILE_PAGES: usize = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PaperSize {
+    #[default]
+    A4,
+    A3,
+    A5,
+    Letter,
+    Legal,
+    Tabloid,
+    /// Uses [`PageSetup::custom_size_mm`].
+    Custom,
+}
+
+impl PaperSize {
+    pub const ALL: [PaperSize; 7] = [
+        PaperSize::A4,
+        PaperSize::A3,
+        PaperSize::A5,
+        PaperSize::Letter,
+        PaperSize::Legal,
+        PaperSize::Tabloid,
+        PaperSize::Custom,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PaperSize::A4 => "A4 (210 × 297 mm)",
+            PaperSize::A3 => "A3 (297 × 420 mm)",
+            PaperSize::A5 => "A5 (148 × 210 mm)",
+            PaperSize::Letter => "Letter (8.5 × 11 in)",
+            PaperSize::Legal => "Legal (8.5 × 14 in)",
+            PaperSize::Tabloid => "Tabloid (11 × 17 in)",
+            PaperSize::Custom => "Custom",
+        }
+    }
+
+    /// Portrait width and height in millimetres, or `None` for `Custom`.
+    pub fn size_mm(&self) -> Option<(f32, f32)> {
+        match self {
+            PaperSize::A4 => Some((210.0, 297.0)),
+            PaperSize::A3 => Some((297.0, 420.0)),
+            PaperSize::A5 => Some((148.0, 210.0)),
+            PaperSize::Letter => Some((215.9, 279.4)),
+            PaperSize::Legal => Some((215.9, 355.6)),
+            PaperSize::Tabloid => Some((279.4, 431.8)),
+            PaperSize::Custom => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Orientation {
+    #[default]
+    Portrait,
+    Landscape,
+}
+
+/// How the image is sized on the paper.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PageScaling {
+    /// Largest size that fits inside the margins.
+    #[default]
+    Fit,
+    /// Smallest size that covers the margins; the overflow is cropped.
+    Fill,
+    /// Printed at [`PageSetup::dpi`], centred and cropped to the margins.
+    Dpi,
+    /// Printed at [`PageSetup::dpi`] across as many pages as it needs.
+    Tile,
+}
+
+impl PageScaling {
+    pub const ALL: [PageScaling; 4] = [
+        PageScaling::Fit,
+        PageScaling::Fill,
+        PageScaling::Dpi,
+        PageScaling::Tile,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PageScaling::Fit => "Fit to page",
+            PageScaling::Fill => "Fill page",
+            PageScaling::Dpi => "Actual size (DPI)",
+            PageScaling::Tile => "Tile across pages",
+        }
+    }
+}
+
+/// Paper, placement and output options shared by printing and PDF export.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PageSetup {
+    pub paper: PaperSize,
+    /// Portrait width and height in millimetres for [`PaperSize::Custom`].
+    pub custom_size_mm: (f32, f32),
+    pub orientation: Orientation,
+    /// Top, right, bottom and left margins in millimetres.
+    pub margins_mm: [f32; 4],
+    pub scaling: PageScaling,
+    /// Pixels per inch for [`PageScaling::Dpi`] and [`PageScaling::Tile`].
+    pub dpi: f32,
+    pub crop_marks: bool,
+    /// Extra image printed past the trim edge, in millimetres.
+    pub bleed_mm: f32,
+    /// Embed page images as JPEG at this quality instead of lossless Flate.
+    pub jpeg_quality: Option<u8>,
+}
+
+impl Default for PageSetup {
+    fn default() -> Self {
+        Self {
+            paper: PaperSize::A4,
+            custom_size_mm: (210.0, 297.0),
+            orientation: Orientation::Portrait,
+            margins_mm: [10.0; 4],
+            scaling: PageScaling::Fit,
+            dpi: 300.0,
+            crop_marks: false,
+            bleed_mm: 0.0,
+            jpeg_quality: None,
+        }
+    }
+}
+
+/// Placement of the image on one page, in points from the top-left corner.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PageLayout {
+    /// Paper width and height.
+    pub page_size: (f64, f64),
+    /// Where the whole image would land; usually larger than the page for
+    /// cropped or tiled layouts.
+    pub image_rect: Rect,
+    /// The part of the image that belongs to this page.
+    pub trim: Rect,
+    /// `trim` grown by the bleed; everything drawn is clipped to this.
+    pub clip: Rect,
+}
+
+impl PageSetup {
+    /// Paper width and height in points after orientation.
+    pub fn page_size_pt(&self) -> (f64, f64) {
+        let (w, h) = self.paper.size_mm().unwrap_or(self.custom_size_mm);
+        let (w, h) = (w.max(1.0) as f64 * MM_TO_PT, h.max(1.0) as f64 * MM_TO_PT);
+        match self.orientation {
+            Orientation::Portrait => (w.min(h), w.max(h)),
+            Orientation::Landscape => (w.max(h), w.min(h)),
+        }
+    }
+
+    /// The area inside the margins.
+    pub fn printable_rect(&self) -> Rect {
+        let (pw, ph) = self.page_size_pt();
+        let [top, right, bottom, left] = self.margins_mm.map(|m| m.max(0.0) as f64 * MM_TO_PT);
+        Rect::new(left, top, pw - right, ph - bottom)
+    }
+
+    /// Lay out a `width`×`height` pixel image, one entry per page.
+    pub fn layout(&self, width: u32, height: u32) -> Result<Vec<PageLayout>, String> {
+        if width == 0 || height == 0 {
+            return Err("Image is empty".to_string());
+        }
+        let page_size = self.page_size_pt();
+        let area = self.printable_rect();
+        if area.width() < 1.0 || area.height() < 1.0 {
+            return Err("Margins leave no printable area".to_string());
+        }
+        let (w, h) = (width as f64, height as f64);
+        let bleed = self.bleed_mm.max(0.0) as f64 * MM_TO_PT;
+        let dpi_size = (
+            w * 72.0 / self.dpi.max(1.0) as f64,
+            h * 72.0 / self.dpi.max(1.0) as f64,
+        );
+        let centered = |size: (f64, f64), within: Rect| {
+            let c = within.center();
+            Rect::from_center_size(c, size)
+        };
+        let single = |image_rect: Rect, trim: Rect| {
+            let clip = trim.inflate(bleed, bleed).intersect(image_rect);
+            vec![PageLayout {
+                page_size,
+                image_rect,
+                trim,
+                clip,
+            }]
+        };
+
+        Ok(match self.scaling {
+            PageScaling::Fit => {
+                let s = (area.width() / w).min(area.height() / h);
+                let trim = centered((w * s, h * s), area);
+                // Enlarge the image just enough to cover the bleed.
+                let k = ((trim.width() + 2.0 * bleed) / trim.width())
+                    .max((trim.height() + 2.0 * bleed) / trim.height());
+                single(centered((trim.width() * k, trim.height() * k), trim), trim)
+            }
+            PageScaling::Fill => {
+                let bled = area.inflate(bleed, bleed);
+                let s = (bled.width() / w).max(bled.height() / h);
+                single(centered((w * s, h * s), area), area)
+            }
+            PageScaling::Dpi => {
+                let image_rect = centered(dpi_size, area);
+                single(image_rect, image_rect.intersect(area))
+            }
+            PageScaling::Tile => {
+                let cols = (dpi_size.0 / area.width() - 1e-6).ceil().max(1.0);
+                let rows = (dpi_size.1 / area.height() - 1e-6).ceil().max(1.0);
+                if cols * rows > MAX_TILE_PAGES as f64 {
+                    return Err(format!(
+                        "Tiling needs {} pages (at most {}); raise the DPI",
+                        cols * rows,
+                        MAX_TILE_PAGES
+                    ));
+                }
+                let (cols, rows) = (cols as usize, rows as usize);
+                let mut pages = Vec::with_capacity(cols * rows);
+                for row in 0..rows {
+                    for col in 0..cols {
+                        let origin = (
+                            area.x0 - col as f64 * area.width(),
+                            area.y0 - row as f64 * area.height(),
+                        );
+                        let image_rect = Rect::from_origin_size(origin, dpi_size);
+                        pages.extend(single(image_rect, image_rect.intersect(area)));
+                    }
+                }
+                pages
+            }
+        })
+    }
+}
+
+impl PageLayout {
+    /// Crop-mark line segments around the trim corners.
+    pub fn crop_marks(&self, bleed_pt: f64) -> Vec<(kurbo::Point, kurbo::Point)> {
+        let offset = bleed_pt + CROP_MARK_GAP;
+        let t = self.trim;
+        let mut lines = Vec::with_capacity(8);
+        for (x, sx) in [(t.x0, -1.0), (t.x1, 1.0)] {
+            for (y, sy) in [(t.y0, -1.0), (t.y1, 1.0)] {
+                lines.push((
+                    (x + sx * offset, y).into(),
+                    (x + sx * (offset + CROP_MARK_LEN), y).into(),
+                ));
+                lines.push((
+                    (x, y + sy * offset).into(),
+                    (x, y + sy * (offset + CROP_MARK_LEN)).into(),
+                ));
+            }
+        }
+        lines
+    }
+
+    /// Pixel rectangle of a `width`×`height` image visible through `clip`,
+    /// as `(x, y, w, h)`, or `None` when nothing is.
+    fn source_region(&self, width: u32, height: u32) -> Option<(u32, u32, u32, u32)> {
+        let sx = width as f64 / self.image_rect.width();
+        let sy = height as f64 / self.image_rect.height();
+        let x0 = ((self.clip.x0 - self.image_rect.x0) * sx).floor().max(0.0) as u32;
+        let y0 = ((self.clip.y0 - self.image_rect.y0) * sy).floor().max(0.0) as u32;
+        let x1 = (((self.clip.x1 - self.image_rect.x0) * sx).ceil().max(0.0) as u32).min(width);
+        let y1 = (((self.clip.y1 - self.image_rect.y0) * sy).ceil().max(0.0) as u32).min(height);
+        (x1 > x0 && y1 > y0).then_some((x0, y0, x1 - x0, y1 - y0))
+    }
+}
+
+// ============================================================================
+// PREVIEW
+// ============================================================================
+
+/// Render one page at `scale` pixels per point on white paper, for previews.
+/// Transparent image areas show the paper.
+pub fn render_page(
+    image: &RgbaImage,
+    setup: &PageSetup,
+    page: &PageLayout,
+    scale: f64,
+) -> RgbaImage {
+    let (pw, ph) = page.page_size;
+    let out_w = ((pw * scale).round() as u32).max(1);
+    let out_h = ((ph * scale).round() as u32).max(1);
+    let mut out = RgbaImage::from_pixel(out_w, out_h, Rgba([255, 255, 255, 255]));
+    let (iw, ih) = image.dimensions();
+    let r = page.image_rect;
+    let x_range = ((page.clip.x0 * scale).floor().max(0.0) as u32)
+        ..((page.clip.x1 * scale).ceil().max(0.0) as u32).min(out_w);
+    let y_range = ((page.clip.y0 * scale).floor().max(0.0) as u32)
+        ..((page.clip.y1 * scale).ceil().max(0.0) as u32).min(out_h);
+    for y in y_range {
+        let py = (y as f64 + 0.5) / scale;
+        let sy = ((py - r.y0) / r.height() * ih as f64).floor();
+        if py < page.clip.y0 || py > page.clip.y1 || sy < 0.0 || sy >= ih as f64 {
+            continue;
+        }
+        for x in x_range.clone() {
+            let px = (x as f64 + 0.5) / scale;
+            let sx = ((px - r.x0) / r.width() * iw as f64).floor();
+            if px < page.clip.x0 || px > page.clip.x1 || sx < 0.0 || sx >= iw as f64 {
+                continue;
+            }
+            let src = image.get_pixel(sx as u32, sy as u32);
+            out.put_pixel(x, y, over_white(*src));
+        }
+    }
+    if setup.crop_marks {
+        let bleed = setup.bleed_mm.max(0.0) as f64 * MM_TO_PT;
+        for (a, b) in page.crop_marks(bleed) {
+            let steps = ((b - a).hypot() * scale).ceil().max(1.0) as usize;
+            for i in 0..=steps {
+                let p = a.lerp(b, i as f64 / steps as f64);
+                let (x, y) = ((p.x * scale) as i64, (p.y * scale) as i64);
+                if x >= 0 && y >= 0 && (x as u32) < out_w && (y as u32) < out_h {
+                    out.put_pixel(x as u32, y as u32, Rgba([0, 0, 0, 255]));
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Composite over white paper.
+fn over_white(px: Rgba<u8>) -> Rgba<u8> {
+    let a = px[3] as u32;
+    let blend = |c: u8| ((c as u32 * a + 255 * (255 - a) + 127) / 255) as u8;
+    Rgba([blend(px[0]), blend(px[1]), blend(px[2]), 255])
+}
+
+// ============================================================================
+// PDF EXPORT
+// ============================================================================
+
+/// Lay out `image` with `setup` and encode it as a PDF, one image per page.
+pub fn export_pdf(image: &RgbaImage, setup: &PageSetup) -> Result<Vec<u8>, String> {
+    let pages = setup.layout(image.width(), image.height())?;
+    let bleed = setup.bleed_mm.max(0.0) as f64 * MM_TO_PT;
+    let mut pdf = PdfWriter::new();
+    // Objects 1 and 2 are the catalog and page tree; each page then takes
+    // three: the page, its content stream and its image.
+    let page_ids: Vec<usize> = (0..pages.len()).map(|i| 3 + i * 3).collect();
+    pdf.object(1, b"<< /Type /Catalog /Pages 2 0 R >>");
+    let kids: Vec<String> = page_ids.iter().map(|id| format!("{} 0 R", id)).collect();
+    pdf.object(
+        2,
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        )
+        .as_bytes(),
+    );
+
+    for (page, &id) in pages.iter().zip(&page_ids) {
+        let (pw, ph) = page.page_size;
+        // PDF's y axis points up.
+        let flip = |r: Rect| (r.x0, ph - r.y1, r.width(), r.height());
+        let mut content = String::new();
+        let mut resources = String::new();
+
+        if let Some((sx, sy, sw, sh)) = page.source_region(image.width(), image.height()) {
+            let crop = image::imageops::crop_imm(image, sx, sy, sw, sh).to_image();
+            let (filter, data) = encode_page_image(&crop, setup.jpeg_quality)?;
+            pdf.stream(
+                id + 2,
+                &format!(
+                    "/Type /XObject /Subtype /Image /Width {} /Height {} \
+                     /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /{}",
+                    sw, sh, filter
+                ),
+                &data,
+            );
+            resources = format!("/XObject << /Im0 {} 0 R >>", id + 2);
+
+            let px_w = page.image_rect.width() / image.width() as f64;
+            let px_h = page.image_rect.height() / image.height() as f64;
+            let placed = Rect::from_origin_size(
+                (
+                    page.image_rect.x0 + sx as f64 * px_w,
+                    page.image_rect.y0 + sy as f64 * px_h,
+                ),
+                (sw as f64 * px_w, sh as f64 * px_h),
+            );
+            let (cx, cy, cw, ch) = flip(page.clip);
+            let (dx, dy, dw, dh) = flip(placed);
+            content.push_str(&format!(
+                "q\n{} {} {} {} re W n\n{} 0 0 {} {} {} cm\n/Im0 Do\nQ\n",
+                num(cx),
+                num(cy),
+                num(cw),
+                num(ch),
+                num(dw),
+                num(dh),
+                num(dx),
+                num(dy)
+            ));
+        }
+        if setup.crop_marks {
+            content.push_str("0 G 0.25 w\n");
+            for (a, b) in page.crop_marks(bleed) {
+                content.push_str(&format!(
+                    "{} {} m {} {} l S\n",
+                    num(a.x),
+                    num(ph - a.y),
+                    num(b.x),
+                    num(ph - b.y)
+                ));
+            }
+        }
+
+        let (tx, ty, tw, th) = flip(page.trim);
+        let (bx, by, bw, bh) = flip(page.clip);
+        pdf.object(
+            id,
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                 /TrimBox [{} {} {} {}] /BleedBox [{} {} {} {}] \
+                 /Resources << {} >> /Contents {} 0 R >>",
+                num(pw),
+                num(ph),
+                num(tx),
+                num(ty),
+                num(tx + tw),
+                num(ty + th),
+                num(bx),
+                num(by),
+                num(bx + bw),
+                num(by + bh),
+                resources,
+                id + 1
+            )
+            .as_bytes(),
+        );
+        pdf.stream(id + 1, "", content.as_bytes());
+    }
+    let info_id = 3 + pages.len() * 3;
+    pdf.object(info_id, b"<< /Producer (PaintFE) >>");
+    Ok(pdf.finish(1, info_id))
+}
+
+/// [`export_pdf`] straight to a file.
+pub fn write_pdf(image: &RgbaImage, setup: &PageSetup, path: &Path) -> Result<(), String> {
+    let bytes = export_pdf(image, setup)?;
+    std::fs::write(path, bytes).map_err(|e| format!("Failed to write PDF: {}", e))
+}
+
+/// Flatten onto white and encode as DCT (JPEG) or Flate (zlib) RGB.
+fn encode_page_image(
+    image: &RgbaImage,
+    jpeg_quality: Option<u8>,
+) -> Result<(&'static str, Vec<u8>), String> {
+    let rgb: Vec<u8> = image
+        .pixels()
+        .flat_map(|px| {
+            let c = over_white(*px);
+            [c[0], c[1], c[2]]
+        })
+        .collect();
+    match jpeg_quality {
+        Some(quality) => {
+            let mut data = Vec::new();
+            image::codecs::jpeg::JpegEncoder::new_with_quality(&mut data, quality.clamp(1, 100))
+                .encode(
+                    &rgb,
+                    image.width(),
+                    image.height(),
+                    image::ExtendedColorType::Rgb8,
+                )
+                .map_err(|e| format!("JPEG encode failed: {}", e))?;
+            Ok(("DCTDecode", data))
+        }
+        None => {
+            let mut encoder =
+                flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder
+                .write_all(&rgb)
+                .and_then(|_| encoder.finish())
+                .map(|data| ("FlateDecode", data))
+                .map_err(|e| format!("Deflate failed: {}", e))
+        }
+    }
+}
+
+/// Format a coordinate without trailing zeros.
+fn num(v: f64) -> String {
+    let s = format!("{:.3}", v);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" {
+        "0".to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+/// Minimal PDF 1.4 serializer: numbered objects plus a cross-reference table.
+struct PdfWriter {
+    out: Vec<u8>,
+    /// Byte offset of each object, indexed by object number.
+    offsets: Vec<usize>,
+}
+
+impl PdfWriter {
+    fn new() -> Self {
+        Self {
+            // The binary comment marks the file as 8-bit for transfer tools.
+            out: b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec(),
+            offsets: Vec::new(),
+        }
+    }
+
+    fn begin(&mut self, id: usize) {
+        if self.offsets.len() <= id {
+            self.offsets.resize(id + 1, 0);
+        }
+        self.offsets[id] = self.out.len();
+        self.out
+            .extend_from_slice(format!("{} 0 obj\n", id).as_bytes());
+    }
+
+    fn object(&mut self, id: usize, body: &[u8]) {
+        self.begin(id);
+        self.out.extend_from_slice(body);
+        self.out.extend_from_slice(b"\nendobj\n");
+    }
+
+    fn stream(&mut self, id: usize, dict: &str, data: &[u8]) {
+        self.begin(id);
+        self.out.extend_from_slice(
+            format!("<< {} /Length {} >>\nstream\n", dict, data.len()).as_bytes(),
+        );
+        self.out.extend_from_slice(data);
+        self.out.extend_from_slice(b"\nendstream\nendobj\n");
+    }
+
+    fn finish(mut self, root: usize, info: usize) -> Vec<u8> {
+        let xref = self.out.len();
+        let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", self.offsets.len());
+        for &offset in self.offsets.iter().skip(1) {
+            // Pages with nothing to draw leave their image number unused.
+            if offset == 0 {
+                table.push_str("0000000000 65535 f \n");
+            } else {
+                table.push_str(&format!("{:010} 00000 n \n", offset));
+            }
+        }
+        table.push_str(&format!(
+            "trailer\n<< /Size {} /Root {} 0 R /Info {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+            self.offsets.len(),
+            root,
+            info,
+            xref
+        ));
+        self.out.extend_from_slice(table.as_bytes());
+        self.out
+    }
+}
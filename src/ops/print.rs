@@ -1,7 +1,8 @@
 // ============================================================================
-// PRINT — save composite to temp PNG and open with OS default viewer
+// PRINT — lay the composite out with the page setup and hand it to the OS
 // ============================================================================
 
+use super::page_setup::PageSetup;
 use image::RgbaImage;
 #[cfg(not(target_arch = "wasm32"))]
 use std::path::Path;
@@ -10,9 +11,11 @@ use std::path::Path;
 /// tab sized to just the image, and trigger the browser's own print dialog
 /// once it has loaded. There's no OS temp dir or default viewer to shell out
 /// to like the native path does, so `window.print()` is the closest
-/// equivalent — it still goes through the user's real printer picker.
+/// equivalent — it still goes through the user's real printer picker. The
+/// page setup only picks the paper size and margins; the browser does the
+/// scaling.
 #[cfg(target_arch = "wasm32")]
-pub fn print_image(composite: &RgbaImage) -> Result<(), String> {
+pub fn print_image(composite: &RgbaImage, setup: &PageSetup) -> Result<(), String> {
     use base64::Engine;
     use image::ImageEncoder;
 
@@ -37,8 +40,13 @@ pub fn print_image(composite: &RgbaImage) -> Result<(), String> {
         .ok_or("print window has no document")?;
     document.set_title("PaintFE — Print");
     let body = document.body().ok_or("print window has no body")?;
+    // Orientation is already applied to the size: CSS rejects a `size` that
+    // combines explicit lengths with `portrait`/`landscape`.
+    let (paper_w, paper_h) = setup.page_size_pt();
+    let [top, right, bottom, left] = setup.margins_mm;
     let html = format!(
         r#"<style>
+            @page {{ size: {paper_w:.1}pt {paper_h:.1}pt; margin: {top}mm {right}mm {bottom}mm {left}mm; }}
             html, body {{ margin: 0; padding: 0; background: #fff; }}
             img {{ display: block; max-width: 100%; height: auto; margin: 0 auto; }}
             @media print {{ img {{ max-width: 100%; }} }}
@@ -49,13 +57,13 @@ pub fn print_image(composite: &RgbaImage) -> Result<(), String> {
     Ok(())
 }
 
+/// Write the laid-out pages to a temporary PDF and send it to the OS print
+/// handler (or default viewer).
 #[cfg(not(target_arch = "wasm32"))]
-pub fn print_image(composite: &RgbaImage) -> Result<(), String> {
+pub fn print_image(composite: &RgbaImage, setup: &PageSetup) -> Result<(), String> {
     let dir = std::env::temp_dir();
-    let path = dir.join("paintfe_print.png");
-    composite
-        .save(&path)
-        .map_err(|e| format!("Failed to save print image: {}", e))?;
+    let path = dir.join("paintfe_print.pdf");
+    super::page_setup::write_pdf(composite, setup, &path)?;
 
     open_with_os(&path)
 }
@@ -13,6 +13,11 @@ use std::sync::{
     atomic::{AtomicBool, Ordering},
 };
 
+use crate::ops::transform::{Interpolation, rotate_image_arbitrary};
+use crate::ops::trim::{
+    DEFAULT_SCAN_TOLERANCE, TrimBasis, TrimOptions, TrimSides, fit_scan, trim_bounds,
+};
+
 // ============================================================================
 // Canvas-wide transform operations to replay on non-active layers
 // ============================================================================
@@ -55,6 +60,16 @@ pub enum CanvasOpRequest {
         new_h: u32,
         anchor: (u32, u32),
     },
+    Crop {
+        x: u32,
+        y: u32,
+        w: u32,
+        h: u32,
+    },
+    /// Clockwise rotation in degrees, keeping the canvas size.
+    Rotate {
+        degrees: f32,
+    },
 }
 
 fn parse_script_filter(s: &str) -> ScriptFilterType {
@@ -817,6 +832,115 @@ fn register_transform_api(engine: &mut Engine, ctx: SharedContext) {
             });
         },
     );
+
+    // crop_canvas(x, y, w, h) — crop every layer to a rectangle
+    let c = ctx.clone();
+    engine.register_fn("crop_canvas", move |x: i64, y: i64, w: i64, h: i64| {
+        let mut lock = c.lock().unwrap_or_else(|e| e.into_inner());
+        let x = x.clamp(0, lock.width as i64) as u32;
+        let y = y.clamp(0, lock.height as i64) as u32;
+        let w = w.clamp(0, (lock.width - x) as i64) as u32;
+        let h = h.clamp(0, (lock.height - y) as i64) as u32;
+        crop_context(&mut lock, x, y, w, h);
+    });
+
+    // trim_canvas(based_on, sides, tolerance) — cut margins from this
+    // layer's edges, then crop every layer to match.
+    // based_on: "transparent", "top-left" or "bottom-right" pixel colour
+    // sides: "all" or any of "top", "bottom", "left", "right", e.g. "top, bottom"
+    // An unknown basis or side is a script error.
+    let c = ctx.clone();
+    engine.register_fn(
+        "trim_canvas",
+        move |based_on: ImmutableString,
+              sides: ImmutableString,
+              tolerance: i64|
+              -> Result<(), Box<EvalAltResult>> {
+            let basis = TrimBasis::parse(&based_on).ok_or_else(|| {
+                format!(
+                    "trim_canvas: unknown basis '{}' (use \"transparent\", \"top-left\" or \"bottom-right\")",
+                    based_on
+                )
+            })?;
+            let sides = TrimSides::parse(&sides).ok_or_else(|| {
+                format!(
+                    "trim_canvas: unknown sides '{}' (use \"all\" or any of \"top\", \"bottom\", \"left\", \"right\")",
+                    sides
+                )
+            })?;
+            let options = TrimOptions {
+                basis,
+                sides,
+                tolerance: tolerance.clamp(0, 255) as u8,
+            };
+            trim_context(&c, &options);
+            Ok(())
+        },
+    );
+
+    // trim_canvas() / crop_to_content() — crop every layer to this layer's
+    // non-transparent pixels
+    let c = ctx.clone();
+    engine.register_fn("trim_canvas", move || {
+        trim_context(&c, &TrimOptions::default());
+    });
+    let c = ctx.clone();
+    engine.register_fn("crop_to_content", move || {
+        trim_context(&c, &TrimOptions::default());
+    });
+
+    // auto_straighten(tolerance) — level a scanned page on this layer, rotate
+    // every layer to match and crop to the page. Returns the angle rotated by.
+    let c = ctx.clone();
+    engine.register_fn("auto_straighten", move |tolerance: i64| {
+        auto_straighten_context(&c, tolerance.clamp(0, 255) as u8)
+    });
+    let c = ctx.clone();
+    engine.register_fn("auto_straighten", move || {
+        auto_straighten_context(&c, DEFAULT_SCAN_TOLERANCE)
+    });
+}
+
+/// Crop the script's layer to `(x, y, w, h)` and queue the crop for the
+/// other layers. The selection is dropped, as cropping the canvas does.
+fn crop_context(lock: &mut ScriptContext, x: u32, y: u32, w: u32, h: u32) {
+    if w == 0 || h == 0 || (x, y, w, h) == (0, 0, lock.width, lock.height) {
+        return;
+    }
+    if let Some(img) = RgbaImage::from_raw(lock.width, lock.height, lock.pixels.clone()) {
+        lock.pixels = imageops::crop_imm(&img, x, y, w, h).to_image().into_raw();
+        lock.width = w;
+        lock.height = h;
+        lock.mask = None;
+        lock.canvas_ops.push(CanvasOpRequest::Crop { x, y, w, h });
+    }
+}
+
+fn trim_context(ctx: &SharedContext, options: &TrimOptions) {
+    let mut lock = ctx.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(img) = RgbaImage::from_raw(lock.width, lock.height, lock.pixels.clone()) else {
+        return;
+    };
+    if let Some((x, y, w, h)) = trim_bounds(&img, options) {
+        crop_context(&mut lock, x, y, w, h);
+    }
+}
+
+fn auto_straighten_context(ctx: &SharedContext, tolerance: u8) -> f64 {
+    let mut lock = ctx.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(img) = RgbaImage::from_raw(lock.width, lock.height, lock.pixels.clone()) else {
+        return 0.0;
+    };
+    let fit = fit_scan(&img, tolerance);
+    if fit.angle != 0.0 {
+        lock.pixels = rotate_image_arbitrary(&img, fit.angle, Interpolation::Bilinear).into_raw();
+        lock.canvas_ops
+            .push(CanvasOpRequest::Rotate { degrees: fit.angle });
+    }
+    if let Some((x, y, w, h)) = fit.crop {
+        crop_context(&mut lock, x, y, w, h);
+    }
+    fit.angle as f64
 }
 
 fn register_effect_api(engine: &mut Engine, ctx: SharedContext) {
@@ -824,7 +948,7 @@ fn register_effect_api(engine: &mut Engine, ctx: SharedContext) {
     let c = ctx.clone();
     engine.register_fn("apply_blur", move |sigma: f64| {
         apply_effect_to_context(&c, |img, mask| {
-            crate::ops::filters::blur_with_selection_pub(img, sigma as f32, mask)
+            crate::ops::filters::blur_with_selection_pub(img, sigma as f32, mask, false)
         });
     });
 
@@ -1695,6 +1819,12 @@ pub fn apply_canvas_ops(
                         }
                         out
                     }
+                    CanvasOpRequest::Crop { x, y, w, h } => {
+                        imageops::crop_imm(&img, *x, *y, *w, *h).to_image()
+                    }
+                    CanvasOpRequest::Rotate { degrees } => {
+                        rotate_image_arbitrary(&img, *degrees, Interpolation::Bilinear)
+                    }
                 };
                 state.layers[i].pixels = crate::canvas::TiledImage::from_rgba_image(&new_img);
             }
@@ -1713,6 +1843,10 @@ pub fn apply_canvas_ops(
                 cur_w = *new_w;
                 cur_h = *new_h;
             }
+            CanvasOpRequest::Crop { w, h, .. } => {
+                cur_w = *w;
+                cur_h = *h;
+            }
             _ => {}
         }
     }
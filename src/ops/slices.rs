@@ -0,0 +1,588 @@
+// ============================================================================
+// SLICES & SPRITE SHEETS — export slices, pack and split sprite sheets
+// ============================================================================
+
+use std::path::{Path, PathBuf};
+
+use image::RgbaImage;
+
+use crate::canvas::{CHUNK_SIZE, CanvasState, Layer, Slice, TiledImage};
+use crate::components::dialogs::{SaveFormat, TiffCompression};
+use crate::config::export_presets::ExportPreset;
+use crate::ops::layer_comps::safe_file_name;
+use crate::ops::layer_export::{
+    LayerExportOptions, content_bounds, layer_export_units, render_units,
+};
+
+/// A `columns`×`rows` grid over a `width`×`height` canvas, row by row, named
+/// "Slice 1", "Slice 2", …. Cells split the remainder so they cover the canvas.
+pub fn grid_slices(width: u32, height: u32, columns: u32, rows: u32) -> Vec<Slice> {
+    let columns = columns.clamp(1, width.max(1));
+    let rows = rows.clamp(1, height.max(1));
+    let edge = |i: u32, n: u32, size: u32| (u64::from(i) * u64::from(size) / u64::from(n)) as u32;
+    let mut slices = Vec::with_capacity((columns * rows) as usize);
+    for row in 0..rows {
+        let (y0, y1) = (edge(row, rows, height), edge(row + 1, rows, height));
+        for col in 0..columns {
+            let (x0, x1) = (edge(col, columns, width), edge(col + 1, columns, width));
+            let n = slices.len() + 1;
+            slices.push(Slice::new(format!("Slice {}", n), x0, y0, x1 - x0, y1 - y0));
+        }
+    }
+    slices
+}
+
+/// One slice per visible layer with pixels, bottom layer first, covering the
+/// layer's non-transparent pixels and named after it. Adjustment layers are
+/// skipped.
+pub fn layer_slices(state: &CanvasState) -> Vec<Slice> {
+    state
+        .layers
+        .iter()
+        .enumerate()
+        .filter(|(idx, layer)| {
+            !layer.is_adjustment_layer() && state.layer_effectively_visible(*idx)
+        })
+        .filter_map(|(_, layer)| {
+            let (x, y, w, h) = tiled_content_bounds(&layer.pixels)?;
+            Some(Slice::new(layer.name.clone(), x, y, w, h))
+        })
+        .collect()
+}
+
+/// [`content_bounds`] of a tiled image, looking only at populated chunks.
+pub fn tiled_content_bounds(image: &TiledImage) -> Option<(u32, u32, u32, u32)> {
+    let mut bounds: Option<(u32, u32, u32, u32)> = None;
+    for (cx, cy) in image.chunk_keys() {
+        let Some((x, y, w, h)) = image.get_chunk(cx, cy).and_then(content_bounds) else {
+            continue;
+        };
+        let (x0, y0) = (cx * CHUNK_SIZE + x, cy * CHUNK_SIZE + y);
+        let (x1, y1) = ((x0 + w).min(image.width()), (y0 + h).min(image.height()));
+        if x1 <= x0 || y1 <= y0 {
+            continue;
+        }
+        bounds = Some(match bounds {
+            Some((bx0, by0, bx1, by1)) => (bx0.min(x0), by0.min(y0), bx1.max(x1), by1.max(y1)),
+            None => (x0, y0, x1, y1),
+        });
+    }
+    bounds.map(|(x0, y0, x1, y1)| (x0, y0, x1 - x0, y1 - y0))
+}
+
+// ============================================================================
+// SLICE EXPORT
+// ============================================================================
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SliceExportOptions {
+    /// Format and quality used without a preset.
+    pub format: SaveFormat,
+    pub quality: u8,
+    /// Write through this preset instead.
+    pub preset: Option<ExportPreset>,
+}
+
+impl Default for SliceExportOptions {
+    fn default() -> Self {
+        Self {
+            format: SaveFormat::Png,
+            quality: 90,
+            preset: None,
+        }
+    }
+}
+
+impl SliceExportOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        match &self.preset {
+            Some(preset) => preset.validate(),
+            None if self.format == SaveFormat::Pfe => {
+                Err("slices are exported as images, not PFE projects".to_string())
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Write the visible composite inside each slice to `dir`, named after the
+/// slice. The document bounds are restored afterwards. Returns the paths
+/// written.
+pub fn export_slices(
+    state: &mut CanvasState,
+    dir: &Path,
+    options: &SliceExportOptions,
+) -> Result<Vec<PathBuf>, String> {
+    options.validate()?;
+    let slices: Vec<Slice> = state
+        .slices
+        .iter()
+        .filter_map(|s| s.clamped(state.width, state.height))
+        .collect();
+    if slices.is_empty() {
+        return Err("the document has no slices".to_string());
+    }
+    state.ensure_all_text_layers_rasterized();
+    let document_bounds = state.document_bounds;
+
+    let mut stems: Vec<String> = Vec::with_capacity(slices.len());
+    let mut written = Vec::with_capacity(slices.len());
+    let mut result = Ok(());
+    for slice in &slices {
+        state.document_bounds = Some(slice.rect());
+        let stem = unique_stem(&stems, safe_file_name(&slice.name));
+        stems.push(stem.clone());
+
+        let outcome = match &options.preset {
+            Some(preset) => {
+                let path = preset.output_path(dir, &stem);
+                crate::io::PresetExport::build(state, preset)
+                    .and_then(|export| export.write(&path))
+                    .map(|()| path)
+            }
+            None => {
+                let path = dir.join(format!("{}.{}", stem, options.format.extension()));
+                crate::io::encode_canvas_state_and_write(
+                    state,
+                    &path,
+                    options.format,
+                    options.quality,
+                    TiffCompression::default(),
+                    true,
+                )
+                .map(|()| path)
+                .map_err(|e| e.to_string())
+            }
+        };
+        match outcome {
+            Ok(path) => written.push(path),
+            Err(e) => {
+                result = Err(format!("'{}' failed: {}", slice.name, e));
+                break;
+            }
+        }
+    }
+    state.document_bounds = document_bounds;
+    result.map(|()| written)
+}
+
+/// `stem`, or `stem_2`, `stem_3`, … when already used.
+pub(crate) fn unique_stem(used: &[String], stem: String) -> String {
+    if !used.contains(&stem) {
+        return stem;
+    }
+    let mut n = 2;
+    while used.contains(&format!("{}_{}", stem, n)) {
+        n += 1;
+    }
+    format!("{}_{}", stem, n)
+}
+
+// ============================================================================
+// SPRITE SHEET PACKING
+// ============================================================================
+
+/// What becomes a frame of the sprite sheet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpriteSource {
+    /// Each slice of the visible composite.
+    Slices,
+    /// Each visible layer on its own, bottom layer first.
+    Layers,
+}
+
+impl SpriteSource {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "slices" => Some(Self::Slices),
+            "layers" => Some(Self::Layers),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpriteSheetOptions {
+    pub source: SpriteSource,
+    /// Frames per row; 0 picks a roughly square sheet.
+    pub columns: u32,
+    /// Transparent pixels around and between frames.
+    pub padding: u32,
+    /// Crop each frame to its non-transparent pixels. The atlas records
+    /// where the trimmed frame sits in the original.
+    pub trim: bool,
+}
+
+impl Default for SpriteSheetOptions {
+    fn default() -> Self {
+        Self {
+            source: SpriteSource::Layers,
+            columns: 0,
+            padding: 2,
+            trim: false,
+        }
+    }
+}
+
+/// One frame before packing.
+#[derive(Clone, Debug)]
+pub struct SpriteFrame {
+    pub name: String,
+    pub image: RgbaImage,
+    /// Top-left of `image` within the untrimmed frame.
+    pub offset: (u32, u32),
+    /// Size of the untrimmed frame.
+    pub source_size: (u32, u32),
+}
+
+/// A frame's place in a packed sheet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackedFrame {
+    pub name: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub offset: (u32, u32),
+    pub source_size: (u32, u32),
+}
+
+impl PackedFrame {
+    pub fn trimmed(&self) -> bool {
+        (self.width, self.height) != self.source_size
+    }
+}
+
+pub struct SpriteSheet {
+    pub image: RgbaImage,
+    pub frames: Vec<PackedFrame>,
+}
+
+/// The frames of `options.source`, trimmed when asked. Names are made unique
+/// so they can key the atlas. Layer visibility is restored afterwards.
+pub fn sprite_frames(state: &mut CanvasState, options: &SpriteSheetOptions) -> Vec<SpriteFrame> {
+    state.ensure_all_text_layers_rasterized();
+    let mut frames: Vec<(String, RgbaImage)> = match options.source {
+        SpriteSource::Slices => {
+            let composite = state.composite();
+            state
+                .slices
+                .iter()
+                .filter_map(|s| s.clamped(composite.width(), composite.height()))
+                .map(|s| {
+                    let image = image::imageops::crop_imm(&composite, s.x, s.y, s.width, s.height)
+                        .to_image();
+                    (s.name, image)
+                })
+                .collect()
+        }
+        SpriteSource::Layers => {
+            let layer_options = LayerExportOptions::default();
+            let mut units = layer_export_units(state, &layer_options);
+            units.reverse();
+            let (x, y, w, h) = state
+                .document_bounds
+                .unwrap_or((0, 0, state.width, state.height));
+            render_units(state, &units, &layer_options)
+                .into_iter()
+                .zip(units)
+                .map(|(image, unit)| {
+                    let image = if (w, h) == image.dimensions() {
+                        image
+                    } else {
+                        image::imageops::crop_imm(&image, x, y, w, h).to_image()
+                    };
+                    (unit.name, image)
+                })
+                .collect()
+        }
+    };
+
+    let mut names: Vec<String> = Vec::with_capacity(frames.len());
+    for (name, _) in &mut frames {
+        let base = if name.trim().is_empty() {
+            "frame".to_string()
+        } else {
+            name.trim().to_string()
+        };
+        let mut unique = base.clone();
+        let mut n = 2;
+        while names.contains(&unique) {
+            unique = format!("{} {}", base, n);
+            n += 1;
+        }
+        names.push(unique.clone());
+        *name = unique;
+    }
+
+    frames
+        .into_iter()
+        .map(|(name, image)| {
+            let source_size = image.dimensions();
+            if !options.trim {
+                return SpriteFrame {
+                    name,
+                    image,
+                    offset: (0, 0),
+                    source_size,
+                };
+            }
+            // A fully transparent frame keeps one pixel so it still has a
+            // place in the atlas.
+            let (x, y, w, h) = content_bounds(&image).unwrap_or((0, 0, 1, 1));
+            SpriteFrame {
+                name,
+                image: image::imageops::crop_imm(&image, x, y, w, h).to_image(),
+                offset: (x, y),
+                source_size,
+            }
+        })
+        .collect()
+}
+
+/// Lay `frames` out in rows of `columns` (0 = roughly square). Each column
+/// is as wide as its widest frame and each row as tall as its tallest, so
+/// equally sized frames form a regular grid.
+pub fn pack_sprite_sheet(
+    frames: &[SpriteFrame],
+    columns: u32,
+    padding: u32,
+) -> Result<SpriteSheet, String> {
+    if frames.is_empty() {
+        return Err("there are no frames to pack".to_string());
+    }
+    let columns = match columns {
+        0 => (frames.len() as f64).sqrt().ceil() as usize,
+        n => n as usize,
+    }
+    .min(frames.len());
+    let rows = frames.len().div_ceil(columns);
+
+    let mut col_widths = vec![0u32; columns];
+    let mut row_heights = vec![0u32; rows];
+    for (i, frame) in frames.iter().enumerate() {
+        let (w, h) = frame.image.dimensions();
+        col_widths[i % columns] = col_widths[i % columns].max(w);
+        row_heights[i / columns] = row_heights[i / columns].max(h);
+    }
+    let starts = |sizes: &[u32]| -> (Vec<u64>, u64) {
+        let mut pos = u64::from(padding);
+        let starts = sizes
+            .iter()
+            .map(|&size| {
+                let start = pos;
+                pos += u64::from(size) + u64::from(padding);
+                start
+            })
+            .collect();
+        (starts, pos)
+    };
+    let (col_x, sheet_w) = starts(&col_widths);
+    let (row_y, sheet_h) = starts(&row_heights);
+    let max = u64::from(crate::io::MAX_OPEN_IMAGE_DIM);
+    if sheet_w > max || sheet_h > max {
+        return Err(format!(
+            "the sprite sheet would be {}x{}, larger than the {}x{} maximum",
+            sheet_w, sheet_h, max, max
+        ));
+    }
+
+    let mut image = RgbaImage::new(sheet_w as u32, sheet_h as u32);
+    let mut packed = Vec::with_capacity(frames.len());
+    for (i, frame) in frames.iter().enumerate() {
+        let (x, y) = (col_x[i % columns] as u32, row_y[i / columns] as u32);
+        image::imageops::replace(&mut image, &frame.image, i64::from(x), i64::from(y));
+        packed.push(PackedFrame {
+            name: frame.name.clone(),
+            x,
+            y,
+            width: frame.image.width(),
+            height: frame.image.height(),
+            offset: frame.offset,
+            source_size: frame.source_size,
+        });
+    }
+    Ok(SpriteSheet {
+        image,
+        frames: packed,
+    })
+}
+
+/// TexturePacker "JSON (Hash)" atlas for `sheet`, saved as `image_name`.
+pub fn atlas_json(sheet: &SpriteSheet, image_name: &str) -> String {
+    let frames: serde_json::Map<String, serde_json::Value> = sheet
+        .frames
+        .iter()
+        .map(|f| {
+            let value = serde_json::json!({
+                "frame": { "x": f.x, "y": f.y, "w": f.width, "h": f.height },
+                "rotated": false,
+                "trimmed": f.trimmed(),
+                "spriteSourceSize": {
+                    "x": f.offset.0, "y": f.offset.1, "w": f.width, "h": f.height
+                },
+                "sourceSize": { "w": f.source_size.0, "h": f.source_size.1 },
+            });
+            (f.name.clone(), value)
+        })
+        .collect();
+    let atlas = serde_json::json!({
+        "frames": frames,
+        "meta": {
+            "app": "PaintFE",
+            "version": env!("CARGO_PKG_VERSION"),
+            "image": image_name,
+            "format": "RGBA8888",
+            "size": { "w": sheet.image.width(), "h": sheet.image.height() },
+            "scale": "1",
+        },
+    });
+    serde_json::to_string_pretty(&atlas).unwrap_or_default()
+}
+
+/// Pack the sheet for `options` and write `<stem>.png` and `<stem>.json`
+/// into `dir`. Returns the paths written.
+pub fn export_sprite_sheet(
+    state: &mut CanvasState,
+    dir: &Path,
+    stem: &str,
+    options: &SpriteSheetOptions,
+) -> Result<Vec<PathBuf>, String> {
+    let frames = sprite_frames(state, options);
+    if frames.is_empty() {
+        return Err(match options.source {
+            SpriteSource::Slices => "the document has no slices".to_string(),
+            SpriteSource::Layers => "the document has no visible layers".to_string(),
+        });
+    }
+    let sheet = pack_sprite_sheet(&frames, options.columns, options.padding)?;
+    let stem = safe_file_name(stem);
+    let image_path = dir.join(format!("{}.png", stem));
+    let atlas_path = dir.join(format!("{}.json", stem));
+    sheet
+        .image
+        .save_with_format(&image_path, image::ImageFormat::Png)
+        .map_err(|e| format!("could not write '{}': {}", image_path.display(), e))?;
+    std::fs::write(&atlas_path, atlas_json(&sheet, &format!("{}.png", stem)))
+        .map_err(|e| format!("could not write '{}': {}", atlas_path.display(), e))?;
+    Ok(vec![image_path, atlas_path])
+}
+
+// ============================================================================
+// SPRITE SHEET IMPORT
+// ============================================================================
+
+/// Cell layout of a sprite sheet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpriteGrid {
+    pub cell_width: u32,
+    pub cell_height: u32,
+    /// Pixels before the first row and column.
+    pub margin: u32,
+    /// Pixels between cells.
+    pub spacing: u32,
+}
+
+impl Default for SpriteGrid {
+    fn default() -> Self {
+        Self {
+            cell_width: 32,
+            cell_height: 32,
+            margin: 0,
+            spacing: 0,
+        }
+    }
+}
+
+impl SpriteGrid {
+    /// Whole cells across and down a `width`×`height` sheet.
+    pub fn dimensions(&self, width: u32, height: u32) -> (u32, u32) {
+        let count = |size: u32, cell: u32| match size
+            .checked_sub(self.margin)
+            .and_then(|rest| rest.checked_sub(cell))
+        {
+            Some(rest) if cell > 0 => rest / cell.saturating_add(self.spacing) + 1,
+            _ => 0,
+        };
+        (
+            count(width, self.cell_width),
+            count(height, self.cell_height),
+        )
+    }
+
+    /// Top-left of each whole cell, row by row.
+    pub fn cells(&self, width: u32, height: u32) -> Vec<(u32, u32)> {
+        let (columns, rows) = self.dimensions(width, height);
+        (0..rows)
+            .flat_map(|row| {
+                (0..columns).map(move |col| {
+                    let step = |i: u32, cell: u32| {
+                        self.margin
+                            .saturating_add(i.saturating_mul(cell.saturating_add(self.spacing)))
+                    };
+                    (step(col, self.cell_width), step(row, self.cell_height))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Cut `sheet` into cells, row by row. With `skip_empty`, fully transparent
+/// cells are left out.
+pub fn split_sprite_sheet(
+    sheet: &RgbaImage,
+    grid: &SpriteGrid,
+    skip_empty: bool,
+) -> Vec<RgbaImage> {
+    grid.cells(sheet.width(), sheet.height())
+        .into_iter()
+        .map(|(x, y)| {
+            image::imageops::crop_imm(sheet, x, y, grid.cell_width, grid.cell_height).to_image()
+        })
+        .filter(|cell| !skip_empty || content_bounds(cell).is_some())
+        .collect()
+}
+
+/// A new document one cell in size with a layer per cell, named "Frame 1",
+/// "Frame 2", … from the bottom, as animated files open.
+pub fn sprite_sheet_document(
+    sheet: &RgbaImage,
+    grid: &SpriteGrid,
+    skip_empty: bool,
+) -> Result<CanvasState, String> {
+    crate::io::validate_open_dimensions(grid.cell_width, grid.cell_height)?;
+    // Checked before cutting, so a tiny cell size cannot allocate millions
+    // of cell images.
+    let (columns, rows) = grid.dimensions(sheet.width(), sheet.height());
+    let count = columns as u64 * rows as u64;
+    if count > crate::io::MAX_LAYERS as u64 {
+        return Err(format!(
+            "the grid makes {} frames, more than the {} layer maximum",
+            count,
+            crate::io::MAX_LAYERS
+        ));
+    }
+    let cells = split_sprite_sheet(sheet, grid, skip_empty);
+    if cells.is_empty() {
+        return Err("the grid does not fit any cell in the image".to_string());
+    }
+    let mut state = CanvasState::new(grid.cell_width, grid.cell_height);
+    state.layers = cells
+        .iter()
+        .enumerate()
+        .map(|(i, cell)| {
+            let mut layer = Layer::new(
+                format!("Frame {}", i + 1),
+                grid.cell_width,
+                grid.cell_height,
+                image::Rgba([0, 0, 0, 0]),
+            );
+            layer.pixels = TiledImage::from_rgba_image(cell);
+            layer
+        })
+        .collect();
+    state.active_layer_index = 0;
+    state.composite_cache = None;
+    state.mark_dirty(None);
+    Ok(state)
+}
@@ -361,6 +361,9 @@ pub struct GradientFillEffect {
     /// Translation of the gradient field in canvas pixels.
     pub offset: [f32; 2],
     pub repeat: bool,
+    /// Multi-stop gradient from the library; replaces `start_color`/`end_color` when set.
+    #[serde(default)]
+    pub gradient: Option<crate::gradient::Gradient>,
 }
 
 impl Default for OutlineEffect {
@@ -417,6 +420,7 @@ impl Default for GradientFillEffect {
             scale: 200.0,
             offset: [0.0, 0.0],
             repeat: false,
+            gradient: None,
         }
     }
 }
@@ -425,6 +425,7 @@ fn render_gradient_fill(
     let off_y = gradient.offset[1];
     let [sr, sg, sb, sa] = gradient.start_color;
     let [er, eg, eb, ea] = gradient.end_color;
+    let lut = gradient.gradient.as_ref().map(|g| g.build_lut());
 
     let mut filled = vec![0u8; count * 4];
     filled
@@ -442,8 +443,16 @@ fn render_gradient_fill(
                 } else {
                     proj.clamp(0.0, 1.0)
                 };
-                let inv_t = 1.0 - t;
                 let idx = x * 4;
+                if let Some(lut) = &lut {
+                    let [r, g, b, a] = lut[(t * 255.0).round() as usize];
+                    row[idx] = r;
+                    row[idx + 1] = g;
+                    row[idx + 2] = b;
+                    row[idx + 3] = (a as f32 * cov).round().clamp(0.0, 255.0) as u8;
+                    continue;
+                }
+                let inv_t = 1.0 - t;
                 row[idx] = (sr as f32 * inv_t + er as f32 * t)
                     .round()
                     .clamp(0.0, 255.0) as u8;
@@ -2,7 +2,10 @@
 // TRANSFORM OPERATIONS — flip, rotate, affine for images and layers
 // ============================================================================
 
-use crate::canvas::{CanvasState, Layer, LayerContent, TiledImage};
+use crate::canvas::{
+    CanvasState, Layer, LayerContent, OffCanvasPixels, TiledImage, linear_to_srgb8, srgb8_to_linear,
+};
+use crate::log_info;
 use crate::par_compat::*;
 use image::{GrayImage, Luma, Rgba, RgbaImage, imageops};
 
@@ -66,6 +69,7 @@ pub fn flip_canvas_horizontal(state: &mut CanvasState) {
     state.layers.par_iter_mut().for_each(|layer| {
         layer.pixels.flip_horizontal_chunked();
     });
+    transform_canvas_extras(state, SelectionCanvasTransform::FlipHorizontal);
     state.mark_dirty(None);
 }
 
@@ -77,6 +81,7 @@ pub fn flip_canvas_vertical(state: &mut CanvasState) {
     state.layers.par_iter_mut().for_each(|layer| {
         layer.pixels.flip_vertical_chunked();
     });
+    transform_canvas_extras(state, SelectionCanvasTransform::FlipVertical);
     state.mark_dirty(None);
 }
 
@@ -93,6 +98,7 @@ pub fn rotate_canvas_90cw(state: &mut CanvasState) {
     for (layer, new_px) in state.layers.iter_mut().zip(new_pixels) {
         layer.pixels = new_px;
     }
+    transform_canvas_extras(state, SelectionCanvasTransform::Rotate90Cw);
     std::mem::swap(&mut state.width, &mut state.height);
     state.composite_cache = None;
     state.clear_preview_state();
@@ -112,6 +118,7 @@ pub fn rotate_canvas_90ccw(state: &mut CanvasState) {
     for (layer, new_px) in state.layers.iter_mut().zip(new_pixels) {
         layer.pixels = new_px;
     }
+    transform_canvas_extras(state, SelectionCanvasTransform::Rotate90Ccw);
     std::mem::swap(&mut state.width, &mut state.height);
     state.composite_cache = None;
     state.clear_preview_state();
@@ -126,11 +133,70 @@ pub fn rotate_canvas_180(state: &mut CanvasState) {
     state.layers.par_iter_mut().for_each(|layer| {
         layer.pixels.rotate_180_chunked();
     });
+    transform_canvas_extras(state, SelectionCanvasTransform::Rotate180);
     state.mark_dirty(None);
 }
 
+/// Apply a whole-canvas flip or quarter rotation to off-canvas content and
+/// the document rectangle. Must run before `width`/`height` are swapped.
+fn transform_canvas_extras(state: &mut CanvasState, transform: SelectionCanvasTransform) {
+    let swaps = matches!(
+        transform,
+        SelectionCanvasTransform::Rotate90Cw | SelectionCanvasTransform::Rotate90Ccw
+    );
+    let canvas = (state.width, state.height);
+    for layer in &mut state.layers {
+        transform_off_canvas(layer, canvas, transform);
+    }
+    if let Some((x, y, w, h)) = state.document_bounds {
+        let (nx, ny) = map_canvas_rect(transform, canvas, (x as i32, y as i32, w as i32, h as i32));
+        let (nw, nh) = if swaps { (h, w) } else { (w, h) };
+        state.document_bounds = Some((nx.max(0) as u32, ny.max(0) as u32, nw, nh));
+    }
+}
+
+/// New top-left of the canvas-space rectangle `(x, y, w, h)` after a
+/// canvas-wide flip or quarter rotation of a `canvas` sized canvas.
+fn map_canvas_rect(
+    transform: SelectionCanvasTransform,
+    canvas: (u32, u32),
+    (x, y, w, h): (i32, i32, i32, i32),
+) -> (i32, i32) {
+    let (cw, ch) = (canvas.0 as i32, canvas.1 as i32);
+    match transform {
+        SelectionCanvasTransform::FlipHorizontal => (cw - (x + w), y),
+        SelectionCanvasTransform::FlipVertical => (x, ch - (y + h)),
+        SelectionCanvasTransform::Rotate90Cw => (ch - (y + h), x),
+        SelectionCanvasTransform::Rotate90Ccw => (y, cw - (x + w)),
+        SelectionCanvasTransform::Rotate180 => (cw - (x + w), ch - (y + h)),
+    }
+}
+
+/// Apply a canvas-wide flip or quarter rotation to one layer's off-canvas
+/// content, so it stays in place relative to the layer's canvas pixels.
+/// `canvas` is the size before the transform.
+fn transform_off_canvas(
+    layer: &mut crate::canvas::Layer,
+    canvas: (u32, u32),
+    transform: SelectionCanvasTransform,
+) {
+    let Some(off) = layer.off_canvas.as_mut() else {
+        return;
+    };
+    let (w, h) = (off.pixels.width() as i32, off.pixels.height() as i32);
+    off.origin = map_canvas_rect(transform, canvas, (off.origin.0, off.origin.1, w, h));
+    match transform {
+        SelectionCanvasTransform::FlipHorizontal => off.pixels.flip_horizontal_chunked(),
+        SelectionCanvasTransform::FlipVertical => off.pixels.flip_vertical_chunked(),
+        SelectionCanvasTransform::Rotate90Cw => off.pixels = off.pixels.rotate_90cw_chunked(),
+        SelectionCanvasTransform::Rotate90Ccw => off.pixels = off.pixels.rotate_90ccw_chunked(),
+        SelectionCanvasTransform::Rotate180 => off.pixels.rotate_180_chunked(),
+    }
+}
+
 /// Rotate every canvas layer in place without changing canvas dimensions.
-/// Samples outside the original bounds as transparent.
+/// Each layer's whole extent turns about the canvas centre, so content that
+/// ends up outside the canvas is kept as off-canvas pixels.
 pub fn rotate_canvas_arbitrary(
     state: &mut CanvasState,
     degrees: f32,
@@ -139,28 +205,17 @@ pub fn rotate_canvas_arbitrary(
     if degrees.abs() < 0.001 {
         return;
     }
-    let width = state.width;
-    let height = state.height;
+    let (w, h) = (state.width, state.height);
     let rotated: Vec<_> = state
         .layers
         .par_iter()
         .map(|layer| {
-            let flat = layer.pixels.to_rgba_image();
-            TiledImage::from_rgba_image(&apply_affine(
-                &flat,
-                width,
-                height,
-                degrees,
-                0.0,
-                0.0,
-                1.0,
-                (0.0, 0.0),
-                interpolation,
-            ))
+            let (origin, extent) = layer.extent_rgba();
+            rotate_extent_arbitrary(origin, &extent, (w, h), degrees, interpolation)
         })
         .collect();
-    for (layer, pixels) in state.layers.iter_mut().zip(rotated) {
-        layer.pixels = pixels;
+    for (layer, (origin, extent)) in state.layers.iter_mut().zip(rotated) {
+        layer.set_extent_rgba(origin, &extent, w, h);
         // Text geometry is not affine-transformable yet; preserve the exact
         // rotated appearance by converting its current raster cache to pixels.
         if matches!(layer.content, LayerContent::Text(_)) {
@@ -168,15 +223,9 @@ pub fn rotate_canvas_arbitrary(
         }
         if let Some(mask) = layer.mask.as_ref() {
             let flat = mask.to_rgba_image();
-            layer.mask = Some(TiledImage::from_rgba_image(&apply_affine(
+            layer.mask = Some(TiledImage::from_rgba_image(&rotate_image_arbitrary(
                 &flat,
-                width,
-                height,
                 degrees,
-                0.0,
-                0.0,
-                1.0,
-                (0.0, 0.0),
                 interpolation,
             )));
         }
@@ -185,6 +234,83 @@ pub fn rotate_canvas_arbitrary(
     state.mark_dirty(None);
 }
 
+/// Rotate a layer extent (top-left at `origin` in canvas coordinates) by
+/// `degrees` clockwise about the centre of a `canvas` sized canvas. Returns
+/// the rotated image, grown to hold every rotated pixel, and its new origin.
+fn rotate_extent_arbitrary(
+    origin: (i32, i32),
+    extent: &RgbaImage,
+    canvas: (u32, u32),
+    degrees: f32,
+    interpolation: Interpolation,
+) -> ((i32, i32), RgbaImage) {
+    let (c_x, c_y) = (canvas.0 as f32 * 0.5, canvas.1 as f32 * 0.5);
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let rotate = |x: f32, y: f32| (cos * x - sin * y, sin * x + cos * y);
+
+    // `apply_affine` maps pixel indices, not pixel areas, so bound the
+    // rotated corner pixels and pad by one for bilinear spill.
+    let (x0, y0) = (origin.0 as f32, origin.1 as f32);
+    let (x1, y1) = (
+        x0 + extent.width().saturating_sub(1) as f32,
+        y0 + extent.height().saturating_sub(1) as f32,
+    );
+    let (mut min_x, mut min_y) = (f32::MAX, f32::MAX);
+    let (mut max_x, mut max_y) = (f32::MIN, f32::MIN);
+    for (x, y) in [(x0, y0), (x1, y0), (x0, y1), (x1, y1)] {
+        let (rx, ry) = rotate(x - c_x, y - c_y);
+        min_x = min_x.min(rx + c_x);
+        min_y = min_y.min(ry + c_y);
+        max_x = max_x.max(rx + c_x);
+        max_y = max_y.max(ry + c_y);
+    }
+    let new_origin = (min_x.floor() as i32 - 1, min_y.floor() as i32 - 1);
+    let out_w = (max_x.ceil() as i32 + 2 - new_origin.0) as u32;
+    let out_h = (max_y.ceil() as i32 + 2 - new_origin.1) as u32;
+
+    // `apply_affine` turns about the output centre and reads the source in
+    // the output's pixel frame; shift it so the turn happens about the canvas
+    // centre and the source is read at its own origin.
+    let (d_x, d_y) = (out_w as f32 * 0.5, out_h as f32 * 0.5);
+    let (rx, ry) = rotate(d_x - c_x + x0, d_y - c_y + y0);
+    let offset = (
+        rx - new_origin.0 as f32 + c_x - d_x,
+        ry - new_origin.1 as f32 + c_y - d_y,
+    );
+    let rotated = apply_affine(
+        extent,
+        out_w,
+        out_h,
+        degrees,
+        0.0,
+        0.0,
+        1.0,
+        offset,
+        interpolation,
+    );
+    (new_origin, rotated)
+}
+
+/// Rotate `src` by `degrees` (clockwise) about its centre, keeping its size.
+/// Samples outside the original bounds as transparent.
+pub fn rotate_image_arbitrary(
+    src: &RgbaImage,
+    degrees: f32,
+    interpolation: Interpolation,
+) -> RgbaImage {
+    apply_affine(
+        src,
+        src.width(),
+        src.height(),
+        degrees,
+        0.0,
+        0.0,
+        1.0,
+        (0.0, 0.0),
+        interpolation,
+    )
+}
+
 fn try_transform_selected_region(
     state: &mut CanvasState,
     transform: SelectionCanvasTransform,
@@ -343,14 +469,55 @@ fn transform_gray_region(src: &GrayImage, transform: SelectionCanvasTransform) -
     }
 }
 
+/// Resample `src` to `new_w`×`new_h`. With `linear` the color channels are
+/// filtered in linear light (`CanvasState::linear_blending`).
+pub fn resize_rgba(
+    src: &RgbaImage,
+    new_w: u32,
+    new_h: u32,
+    filter: imageops::FilterType,
+    linear: bool,
+) -> RgbaImage {
+    if !linear {
+        return imageops::resize(src, new_w, new_h, filter);
+    }
+    let decoded = image::Rgba32FImage::from_fn(src.width(), src.height(), |x, y| {
+        let p = src.get_pixel(x, y);
+        Rgba([
+            srgb8_to_linear(p[0]),
+            srgb8_to_linear(p[1]),
+            srgb8_to_linear(p[2]),
+            p[3] as f32 / 255.0,
+        ])
+    });
+    let resized = imageops::resize(&decoded, new_w, new_h, filter);
+    RgbaImage::from_fn(new_w, new_h, |x, y| {
+        let p = resized.get_pixel(x, y);
+        Rgba([
+            linear_to_srgb8(p[0]),
+            linear_to_srgb8(p[1]),
+            linear_to_srgb8(p[2]),
+            (p[3] * 255.0).round().clamp(0.0, 255.0) as u8,
+        ])
+    })
+}
+
 /// Resize the entire image (all layers) to new dimensions with given interpolation.
 pub fn resize_image(state: &mut CanvasState, new_w: u32, new_h: u32, interp: Interpolation) {
     let filter = interp.to_filter();
+    let (old_w, old_h) = (state.width, state.height);
+    let linear = state.linear_blending;
     for layer in &mut state.layers {
         let flat = layer.pixels.to_rgba_image();
-        let resized = imageops::resize(&flat, new_w, new_h, filter);
+        let resized = resize_rgba(&flat, new_w, new_h, filter, linear);
         layer.pixels = TiledImage::from_rgba_image(&resized);
+        layer.off_canvas = layer
+            .off_canvas
+            .as_ref()
+            .and_then(|off| scale_off_canvas(off, (old_w, old_h), (new_w, new_h), interp, linear));
     }
+    state.document_bounds =
+        scale_document_bounds(state.document_bounds, (old_w, old_h), (new_w, new_h));
     state.width = new_w;
     state.height = new_h;
     state.composite_cache = None;
@@ -365,17 +532,81 @@ pub fn resize_layers(
     new_w: u32,
     new_h: u32,
     interp: Interpolation,
+    linear: bool,
 ) -> Vec<TiledImage> {
     let filter = interp.to_filter();
     flat_layers
         .into_par_iter()
         .map(|flat| {
-            let resized = imageops::resize(&flat, new_w, new_h, filter);
+            let resized = resize_rgba(&flat, new_w, new_h, filter, linear);
             TiledImage::from_rgba_image(&resized)
         })
         .collect()
 }
 
+/// Scale off-canvas content by the same factor as an image resize from
+/// `old_size` to `new_size`. Returns `None` if nothing remains outside.
+pub fn scale_off_canvas(
+    off: &OffCanvasPixels,
+    old_size: (u32, u32),
+    new_size: (u32, u32),
+    interp: Interpolation,
+    linear: bool,
+) -> Option<OffCanvasPixels> {
+    let sx = new_size.0 as f64 / old_size.0.max(1) as f64;
+    let sy = new_size.1 as f64 / old_size.1.max(1) as f64;
+    let (x0, y0, x1, y1) = off.bounds();
+    let nx0 = (x0 as f64 * sx).floor() as i32;
+    let ny0 = (y0 as f64 * sy).floor() as i32;
+    let nw = ((x1 as f64 * sx).ceil() as i32 - nx0).max(1) as u32;
+    let nh = ((y1 as f64 * sy).ceil() as i32 - ny0).max(1) as u32;
+    let scaled = resize_rgba(
+        &off.pixels.to_rgba_image(),
+        nw,
+        nh,
+        interp.to_filter(),
+        linear,
+    );
+    OffCanvasPixels::from_outside((nx0, ny0), &scaled, new_size.0, new_size.1)
+}
+
+/// Scale the document rectangle along with an image resize.
+pub fn scale_document_bounds(
+    bounds: Option<(u32, u32, u32, u32)>,
+    old_size: (u32, u32),
+    new_size: (u32, u32),
+) -> Option<(u32, u32, u32, u32)> {
+    let (x, y, w, h) = bounds?;
+    let sx = new_size.0 as f64 / old_size.0.max(1) as f64;
+    let sy = new_size.1 as f64 / old_size.1.max(1) as f64;
+    Some((
+        (x as f64 * sx).round() as u32,
+        (y as f64 * sy).round() as u32,
+        ((w as f64 * sx).round() as u32).max(1),
+        ((h as f64 * sy).round() as u32).max(1),
+    ))
+}
+
+/// Pixel offset of the old canvas inside a resized one for a 3x3 `anchor`
+/// (each axis in {0, 1, 2} mapping to start/center/end).
+pub fn canvas_anchor_offset(
+    old_size: (u32, u32),
+    new_size: (u32, u32),
+    anchor: (u32, u32),
+) -> (i32, i32) {
+    let axis = |old: u32, new: u32, a: u32| -> i32 {
+        match a {
+            0 => 0,
+            1 => ((new as i32) - (old as i32)) / 2,
+            _ => (new as i32) - (old as i32),
+        }
+    };
+    (
+        axis(old_size.0, new_size.0, anchor.0),
+        axis(old_size.1, new_size.1, anchor.1),
+    )
+}
+
 /// Resize the canvas (change dimensions), placing the old content at an anchor position.
 /// `anchor` is (ax, ay) each in {0, 1, 2} mapping to start/center/end.
 /// `fill` is the colour used to fill new empty space.
@@ -386,36 +617,37 @@ pub fn resize_canvas(
     anchor: (u32, u32),
     fill: Rgba<u8>,
 ) {
-    let old_w = state.width;
-    let old_h = state.height;
-
-    // The pixel offset of the old image within the new canvas
-    let offset_x: i32 = match anchor.0 {
-        0 => 0,
-        1 => ((new_w as i32) - (old_w as i32)) / 2,
-        _ => (new_w as i32) - (old_w as i32),
-    };
-    let offset_y: i32 = match anchor.1 {
-        0 => 0,
-        1 => ((new_h as i32) - (old_h as i32)) / 2,
-        _ => (new_h as i32) - (old_h as i32),
-    };
+    let offset = canvas_anchor_offset((state.width, state.height), (new_w, new_h), anchor);
+    resize_canvas_with_offset(state, new_w, new_h, offset, fill);
+}
 
-    for layer in &mut state.layers {
-        let old_flat = layer.pixels.to_rgba_image();
-        let mut new_img = RgbaImage::from_pixel(new_w, new_h, fill);
-
-        for y in 0..old_h {
-            for x in 0..old_w {
-                let nx = x as i32 + offset_x;
-                let ny = y as i32 + offset_y;
-                if nx >= 0 && ny >= 0 && (nx as u32) < new_w && (ny as u32) < new_h {
-                    new_img.put_pixel(nx as u32, ny as u32, *old_flat.get_pixel(x, y));
-                }
-            }
-        }
-        layer.pixels = TiledImage::from_rgba_image(&new_img);
-    }
+/// Resize the canvas with the old canvas's top-left corner placed at
+/// `offset` in the new one. Content that ends up outside the new bounds is
+/// kept as off-canvas pixels rather than cropped, and off-canvas content that
+/// the new bounds cover is brought back onto the canvas.
+pub fn resize_canvas_with_offset(
+    state: &mut CanvasState,
+    new_w: u32,
+    new_h: u32,
+    offset: (i32, i32),
+    fill: Rgba<u8>,
+) {
+    let (old_w, old_h) = (state.width, state.height);
+    state.layers.par_iter_mut().for_each(|layer| {
+        let (origin, extent) = layer.extent_rgba();
+        let (pixels, off_canvas) = place_extent_in_canvas(
+            origin,
+            &extent,
+            (old_w, old_h),
+            (new_w, new_h),
+            offset,
+            fill,
+        );
+        layer.pixels = pixels;
+        layer.off_canvas = off_canvas;
+        layer.invalidate_lod();
+    });
+    state.document_bounds = offset_document_bounds(state.document_bounds, offset, (new_w, new_h));
     state.width = new_w;
     state.height = new_h;
     state.composite_cache = None;
@@ -424,45 +656,225 @@ pub fn resize_canvas(
 }
 
 /// Resize canvas for layers without a `CanvasState` — used by async resize pipeline.
-/// Takes a vec of flat `RgbaImage` layers and returns repositioned `TiledImage` layers.
+/// Takes each layer's full extent (see `Layer::extent_rgba`) and returns the
+/// repositioned canvas pixels plus any content left outside the new bounds.
 pub fn resize_canvas_layers(
-    flat_layers: Vec<RgbaImage>,
+    extents: Vec<((i32, i32), RgbaImage)>,
     old_w: u32,
     old_h: u32,
     new_w: u32,
     new_h: u32,
     anchor: (u32, u32),
     fill: Rgba<u8>,
-) -> Vec<TiledImage> {
-    let offset_x: i32 = match anchor.0 {
-        0 => 0,
-        1 => ((new_w as i32) - (old_w as i32)) / 2,
-        _ => (new_w as i32) - (old_w as i32),
-    };
-    let offset_y: i32 = match anchor.1 {
-        0 => 0,
-        1 => ((new_h as i32) - (old_h as i32)) / 2,
-        _ => (new_h as i32) - (old_h as i32),
-    };
-
-    flat_layers
+) -> Vec<(TiledImage, Option<OffCanvasPixels>)> {
+    let offset = canvas_anchor_offset((old_w, old_h), (new_w, new_h), anchor);
+    extents
         .into_par_iter()
-        .map(|old_flat| {
-            let mut new_img = RgbaImage::from_pixel(new_w, new_h, fill);
-            for y in 0..old_h {
-                for x in 0..old_w {
-                    let nx = x as i32 + offset_x;
-                    let ny = y as i32 + offset_y;
-                    if nx >= 0 && ny >= 0 && (nx as u32) < new_w && (ny as u32) < new_h {
-                        new_img.put_pixel(nx as u32, ny as u32, *old_flat.get_pixel(x, y));
-                    }
-                }
-            }
-            TiledImage::from_rgba_image(&new_img)
+        .map(|(origin, extent)| {
+            place_extent_in_canvas(
+                origin,
+                &extent,
+                (old_w, old_h),
+                (new_w, new_h),
+                offset,
+                fill,
+            )
         })
         .collect()
 }
 
+/// Move the document rectangle along with a canvas resize, clamped to the
+/// new canvas. Falls back to the whole canvas if it no longer overlaps.
+pub fn offset_document_bounds(
+    bounds: Option<(u32, u32, u32, u32)>,
+    offset: (i32, i32),
+    new_size: (u32, u32),
+) -> Option<(u32, u32, u32, u32)> {
+    let (x, y, w, h) = bounds?;
+    let x0 = (x as i32 + offset.0).clamp(0, new_size.0 as i32);
+    let y0 = (y as i32 + offset.1).clamp(0, new_size.1 as i32);
+    let x1 = (x as i32 + w as i32 + offset.0).clamp(0, new_size.0 as i32);
+    let y1 = (y as i32 + h as i32 + offset.1).clamp(0, new_size.1 as i32);
+    if x1 <= x0 || y1 <= y0 {
+        return Some((0, 0, new_size.0, new_size.1));
+    }
+    Some((x0 as u32, y0 as u32, (x1 - x0) as u32, (y1 - y0) as u32))
+}
+
+/// Place one layer's extent (top-left at `origin` in old canvas coordinates)
+/// into a resized canvas. Newly exposed area is filled with `fill` (with any
+/// off-canvas content drawn over it), the old canvas area is copied verbatim,
+/// and everything outside the new canvas becomes off-canvas pixels.
+fn place_extent_in_canvas(
+    origin: (i32, i32),
+    extent: &RgbaImage,
+    old_size: (u32, u32),
+    new_size: (u32, u32),
+    offset: (i32, i32),
+    fill: Rgba<u8>,
+) -> (TiledImage, Option<OffCanvasPixels>) {
+    let shifted = (origin.0 + offset.0, origin.1 + offset.1);
+    let x0 = shifted.0.min(0);
+    let y0 = shifted.1.min(0);
+    let x1 = (shifted.0 + extent.width() as i32).max(new_size.0 as i32);
+    let y1 = (shifted.1 + extent.height() as i32).max(new_size.1 as i32);
+    let mut full = RgbaImage::new((x1 - x0) as u32, (y1 - y0) as u32);
+    let extent_at = ((shifted.0 - x0) as i64, (shifted.1 - y0) as i64);
+    if fill[3] > 0 {
+        let canvas_fill = RgbaImage::from_pixel(new_size.0, new_size.1, fill);
+        imageops::replace(&mut full, &canvas_fill, -x0 as i64, -y0 as i64);
+        imageops::overlay(&mut full, extent, extent_at.0, extent_at.1);
+        // The old canvas area replaces rather than blends over the fill.
+        let old_canvas = imageops::crop_imm(
+            extent,
+            (-origin.0) as u32,
+            (-origin.1) as u32,
+            old_size.0,
+            old_size.1,
+        )
+        .to_image();
+        imageops::replace(
+            &mut full,
+            &old_canvas,
+            (offset.0 - x0) as i64,
+            (offset.1 - y0) as i64,
+        );
+    } else {
+        imageops::replace(&mut full, extent, extent_at.0, extent_at.1);
+    }
+    let canvas =
+        imageops::crop_imm(&full, (-x0) as u32, (-y0) as u32, new_size.0, new_size.1).to_image();
+    (
+        TiledImage::from_rgba_image(&canvas),
+        OffCanvasPixels::from_outside((x0, y0), &full, new_size.0, new_size.1),
+    )
+}
+
+/// Grow the canvas so every layer's off-canvas content fits ("Reveal All").
+/// Returns `false` when nothing lies outside the canvas or the result would
+/// exceed the maximum image size.
+pub fn reveal_all(state: &mut CanvasState) -> bool {
+    let canvas = (0, 0, state.width as i32, state.height as i32);
+    let bounds = state.layers.iter().fold(canvas, |acc, layer| {
+        let e = layer.extent();
+        (
+            acc.0.min(e.0),
+            acc.1.min(e.1),
+            acc.2.max(e.2),
+            acc.3.max(e.3),
+        )
+    });
+    grow_canvas_to(state, canvas, bounds)
+}
+
+/// Extra room kept around content when the infinite canvas grows.
+pub const INFINITE_CANVAS_MARGIN: u32 = 256;
+
+/// Infinite canvas mode only: grow the canvas so off-canvas content and the
+/// freshly edited canvas rectangle `touched` (`min_x, min_y, max_x, max_y`)
+/// keep `INFINITE_CANVAS_MARGIN` pixels of room to every edge.
+/// Returns `true` when the canvas changed.
+pub fn extend_infinite_canvas(
+    state: &mut CanvasState,
+    touched: Option<(i32, i32, i32, i32)>,
+) -> bool {
+    grow_infinite_canvas(state, touched).is_some()
+}
+
+/// Like `extend_infinite_canvas`, but returns where the old canvas's top-left
+/// corner now sits, so callers can shift coordinates they hold.
+pub fn grow_infinite_canvas(
+    state: &mut CanvasState,
+    touched: Option<(i32, i32, i32, i32)>,
+) -> Option<(i32, i32)> {
+    let bounds = infinite_canvas_bounds(state, touched)?;
+    let canvas = (0, 0, state.width as i32, state.height as i32);
+    grow_canvas_to(state, canvas, bounds).then_some((-bounds.0, -bounds.1))
+}
+
+/// Whether `extend_infinite_canvas` would grow the canvas. Cheap, so callers
+/// can skip capturing an undo snapshot when nothing will change.
+pub fn infinite_canvas_needs_growth(
+    state: &CanvasState,
+    touched: Option<(i32, i32, i32, i32)>,
+) -> bool {
+    infinite_canvas_bounds(state, touched).is_some()
+}
+
+fn infinite_canvas_bounds(
+    state: &CanvasState,
+    touched: Option<(i32, i32, i32, i32)>,
+) -> Option<(i32, i32, i32, i32)> {
+    state.document_bounds?;
+    let m = INFINITE_CANVAS_MARGIN as i32;
+    let canvas = (0, 0, state.width as i32, state.height as i32);
+    let rects = state
+        .layers
+        .iter()
+        .filter_map(|layer| layer.off_canvas.as_ref().map(|off| off.bounds()))
+        .chain(touched);
+    let bounds = rects.fold(canvas, |b, r| {
+        (
+            b.0.min(r.0 - m),
+            b.1.min(r.1 - m),
+            b.2.max(r.2 + m),
+            b.3.max(r.3 + m),
+        )
+    });
+    (bounds != canvas).then_some(bounds)
+}
+
+/// Turn infinite canvas mode on or off. Enabling records the current canvas
+/// as the document; disabling shrinks the canvas back to the document, with
+/// content outside it kept off-canvas.
+pub fn set_infinite_canvas(state: &mut CanvasState, enabled: bool) {
+    match (enabled, state.document_bounds) {
+        (true, None) => {
+            state.document_bounds = Some((0, 0, state.width, state.height));
+        }
+        (false, Some((x, y, w, h))) => {
+            state.document_bounds = None;
+            if (x, y, w, h) != (0, 0, state.width, state.height) {
+                resize_canvas_with_offset(
+                    state,
+                    w,
+                    h,
+                    (-(x as i32), -(y as i32)),
+                    Rgba([0, 0, 0, 0]),
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+fn grow_canvas_to(
+    state: &mut CanvasState,
+    canvas: (i32, i32, i32, i32),
+    bounds: (i32, i32, i32, i32),
+) -> bool {
+    if bounds == canvas {
+        return false;
+    }
+    let new_w = (bounds.2 - bounds.0) as u32;
+    let new_h = (bounds.3 - bounds.1) as u32;
+    if new_w > crate::io::MAX_OPEN_IMAGE_DIM || new_h > crate::io::MAX_OPEN_IMAGE_DIM {
+        log_info!(
+            "Canvas would exceed {} px; not growing",
+            crate::io::MAX_OPEN_IMAGE_DIM
+        );
+        return false;
+    }
+    resize_canvas_with_offset(
+        state,
+        new_w,
+        new_h,
+        (-bounds.0, -bounds.1),
+        Rgba([0, 0, 0, 0]),
+    );
+    true
+}
+
 /// Flatten all visible layers into a single "Background" layer.
 pub fn flatten_image(state: &mut CanvasState) {
     state.ensure_all_text_layers_rasterized();
@@ -486,28 +898,48 @@ pub fn flatten_image(state: &mut CanvasState) {
 //  Single-layer transforms
 // ---------------------------------------------------------------------------
 
+/// Whether the layer's locks forbid moving or resampling it.
+fn layer_transform_locked(state: &CanvasState, layer_idx: usize) -> bool {
+    state
+        .layers
+        .get(layer_idx)
+        .is_some_and(|layer| layer.locks.blocks_transform())
+}
+
 /// Flip a single layer horizontally, respecting selection if present.
 pub fn flip_layer_horizontal(state: &mut CanvasState, layer_idx: usize) {
+    if layer_transform_locked(state, layer_idx) {
+        return;
+    }
     // Try selection-aware flip first
     if flip_layer_selected_region_horizontal(state, layer_idx) {
         return;
     }
-    // Fall back to full layer flip
+    // Fall back to full layer flip, off-canvas content included
+    let canvas = (state.width, state.height);
     if let Some(layer) = state.layers.get_mut(layer_idx) {
         layer.pixels.flip_horizontal_chunked();
+        transform_off_canvas(layer, canvas, SelectionCanvasTransform::FlipHorizontal);
+        layer.invalidate_lod();
     }
     state.mark_dirty(None);
 }
 
 /// Flip a single layer vertically, respecting selection if present.
 pub fn flip_layer_vertical(state: &mut CanvasState, layer_idx: usize) {
+    if layer_transform_locked(state, layer_idx) {
+        return;
+    }
     // Try selection-aware flip first
     if flip_layer_selected_region_vertical(state, layer_idx) {
         return;
     }
-    // Fall back to full layer flip
+    // Fall back to full layer flip, off-canvas content included
+    let canvas = (state.width, state.height);
     if let Some(layer) = state.layers.get_mut(layer_idx) {
         layer.pixels.flip_vertical_chunked();
+        transform_off_canvas(layer, canvas, SelectionCanvasTransform::FlipVertical);
+        layer.invalidate_lod();
     }
     state.mark_dirty(None);
 }
@@ -652,7 +1084,7 @@ pub fn align_layer_to_anchor_from_flat(
     original_flat: &RgbaImage,
     target_bounds: Option<(u32, u32, u32, u32)>,
 ) {
-    if layer_idx >= state.layers.len() {
+    if layer_idx >= state.layers.len() || layer_transform_locked(state, layer_idx) {
         return;
     }
 
@@ -756,31 +1188,19 @@ pub fn affine_transform_layer(
     scale: f32,
     offset: (f32, f32),
 ) {
-    if layer_idx >= state.layers.len() {
+    if layer_idx >= state.layers.len() || layer_transform_locked(state, layer_idx) {
         return;
     }
-    let layer = &mut state.layers[layer_idx];
-    let w = state.width;
-    let h = state.height;
-
-    let flat = layer.pixels.to_rgba_image();
-    let result = apply_affine(
-        &flat,
-        w,
-        h,
-        rotation_z,
-        rotation_x,
-        rotation_y,
-        scale,
-        offset,
-        Interpolation::Bilinear,
+    let (origin, extent) = state.layers[layer_idx].extent_rgba();
+    affine_transform_layer_from_flat(
+        state, layer_idx, rotation_z, rotation_x, rotation_y, scale, offset, origin, &extent,
     );
-    layer.pixels = TiledImage::from_rgba_image(&result);
-    state.mark_dirty(None);
 }
 
-/// Fast-path affine transform for live preview: works from a pre-flattened
-/// `RgbaImage` so we skip the clone→flatten round-trip on every slider tick.
+/// Fast-path affine transform for live preview: works from the layer's
+/// pre-flattened full extent (see `Layer::extent_rgba`, top-left at
+/// `original_origin`) so we skip the clone→flatten round-trip on every
+/// slider tick. Content that lands outside the canvas is kept off-canvas.
 pub fn affine_transform_layer_from_flat(
     state: &mut CanvasState,
     layer_idx: usize,
@@ -789,17 +1209,29 @@ pub fn affine_transform_layer_from_flat(
     rotation_y: f32,
     scale: f32,
     offset: (f32, f32),
+    original_origin: (i32, i32),
     original_flat: &RgbaImage,
 ) {
-    if layer_idx >= state.layers.len() {
+    if layer_idx >= state.layers.len() || layer_transform_locked(state, layer_idx) {
         return;
     }
-    let w = state.width;
-    let h = state.height;
-    let result = apply_affine(
+    let canvas = (state.width, state.height);
+    let (out_origin, out_size) = affine_output_bounds(
+        original_origin,
+        (original_flat.width(), original_flat.height()),
+        canvas,
+        rotation_z,
+        rotation_x,
+        rotation_y,
+        scale,
+        offset,
+    );
+    let result = apply_affine_framed(
         original_flat,
-        w,
-        h,
+        original_origin,
+        out_origin,
+        out_size,
+        canvas,
         rotation_z,
         rotation_x,
         rotation_y,
@@ -808,10 +1240,56 @@ pub fn affine_transform_layer_from_flat(
         Interpolation::Bilinear,
     );
     let layer = &mut state.layers[layer_idx];
-    layer.pixels = TiledImage::from_rgba_image(&result);
+    layer.set_extent_rgba(out_origin, &result, canvas.0, canvas.1);
     state.mark_dirty(None);
 }
 
+/// Canvas-space rectangle (top-left, size) that holds a `size` image at
+/// `origin` after the layer transform, padded for bilinear spill. Limited to
+/// the canvas plus one canvas size on every side, since a steep perspective
+/// tilt can throw content arbitrarily far; when a corner falls behind the
+/// viewer the whole limit is used.
+fn affine_output_bounds(
+    origin: (i32, i32),
+    size: (u32, u32),
+    canvas: (u32, u32),
+    rotation_z: f32,
+    rotation_x: f32,
+    rotation_y: f32,
+    scale: f32,
+    offset: (f32, f32),
+) -> ((i32, i32), (u32, u32)) {
+    let (cw, ch) = (canvas.0 as i32, canvas.1 as i32);
+    let (lo_x, lo_y, hi_x, hi_y) = (-cw, -ch, 2 * cw, 2 * ch);
+    let (cx, cy) = (canvas.0 as f32 * 0.5, canvas.1 as f32 * 0.5);
+    let h = affine_homography(canvas, rotation_z, rotation_x, rotation_y);
+
+    let (x0, y0) = (origin.0 as f32 - cx, origin.1 as f32 - cy);
+    let (x1, y1) = (
+        x0 + size.0.saturating_sub(1) as f32,
+        y0 + size.1.saturating_sub(1) as f32,
+    );
+    let (mut min_x, mut min_y) = (f32::MAX, f32::MAX);
+    let (mut max_x, mut max_y) = (f32::MIN, f32::MIN);
+    for (x, y) in [(x0, y0), (x1, y0), (x0, y1), (x1, y1)] {
+        let w = h[2][0] * x + h[2][1] * y + h[2][2];
+        if w <= 1e-6 {
+            return ((lo_x, lo_y), ((hi_x - lo_x) as u32, (hi_y - lo_y) as u32));
+        }
+        let u = (h[0][0] * x + h[0][1] * y + h[0][2]) / w * scale + cx + offset.0;
+        let v = (h[1][0] * x + h[1][1] * y + h[1][2]) / w * scale + cy + offset.1;
+        min_x = min_x.min(u);
+        min_y = min_y.min(v);
+        max_x = max_x.max(u);
+        max_y = max_y.max(v);
+    }
+    let left = (min_x.floor() as i32 - 1).clamp(lo_x, hi_x);
+    let top = (min_y.floor() as i32 - 1).clamp(lo_y, hi_y);
+    let right = (max_x.ceil() as i32 + 2).clamp(left + 1, hi_x.max(left + 1));
+    let bottom = (max_y.ceil() as i32 + 2).clamp(top + 1, hi_y.max(top + 1));
+    ((left, top), ((right - left) as u32, (bottom - top) as u32))
+}
+
 // ---------------------------------------------------------------------------
 //  TiledImage helpers (legacy, kept for reference)
 // ---------------------------------------------------------------------------
@@ -834,13 +1312,32 @@ fn apply_affine(
     offset: (f32, f32),
     interpolation: Interpolation,
 ) -> RgbaImage {
-    let mut dst = RgbaImage::new(canvas_w, canvas_h);
-    let cx = canvas_w as f32 * 0.5;
-    let cy = canvas_h as f32 * 0.5;
-    let inv_scale = if scale.abs() > 1e-6 { 1.0 / scale } else { 1.0 };
+    apply_affine_framed(
+        src,
+        (0, 0),
+        (0, 0),
+        (canvas_w, canvas_h),
+        (canvas_w, canvas_h),
+        rotation_z,
+        rotation_x,
+        rotation_y,
+        scale,
+        offset,
+        interpolation,
+    )
+}
 
+/// Homography of the layer transform about the centre of a `canvas` sized
+/// canvas: maps centre-relative source points to centre-relative output
+/// points before scaling and offset.
+fn affine_homography(
+    canvas: (u32, u32),
+    rotation_z: f32,
+    rotation_x: f32,
+    rotation_y: f32,
+) -> [[f32; 3]; 3] {
     // Focal length for perspective projection (proportional to image size).
-    let focal = (canvas_w.max(canvas_h) as f32) * 1.5;
+    let focal = (canvas.0.max(canvas.1) as f32) * 1.5;
 
     // 3D rotation matrix R = Rz * Ry * Rx  (first two columns, since z_input = 0).
     let (sz, cz) = rotation_z.to_radians().sin_cos();
@@ -854,12 +1351,42 @@ fn apply_affine(
     let r20 = -syr;
     let r21 = cyr * sxr;
 
-    let h = [
+    [
         [focal * r00, focal * r01, 0.0f32],
         [focal * r10, focal * r11, 0.0f32],
         [r20, r21, focal],
-    ];
-    let hi = invert_3x3(h);
+    ]
+}
+
+/// [`apply_affine`] for images that sit anywhere in canvas coordinates:
+/// `src` has its top-left at `src_origin`, and the `out_size` result has
+/// its top-left at `out_origin`. The transform still pivots about the
+/// centre of the `canvas` sized canvas.
+fn apply_affine_framed(
+    src: &RgbaImage,
+    src_origin: (i32, i32),
+    out_origin: (i32, i32),
+    out_size: (u32, u32),
+    canvas: (u32, u32),
+    rotation_z: f32,
+    rotation_x: f32,
+    rotation_y: f32,
+    scale: f32,
+    offset: (f32, f32),
+    interpolation: Interpolation,
+) -> RgbaImage {
+    let (out_w, out_h) = out_size;
+    let mut dst = RgbaImage::new(out_w, out_h);
+    let cx = canvas.0 as f32 * 0.5;
+    let cy = canvas.1 as f32 * 0.5;
+    let inv_scale = if scale.abs() > 1e-6 { 1.0 / scale } else { 1.0 };
+    // Output pixel index -> canvas-centred coordinate, and the way back for the source.
+    let (out_x, out_y) = (out_origin.0 as f32 - cx, out_origin.1 as f32 - cy);
+    let (src_cx, src_cy) = (cx - src_origin.0 as f32, cy - src_origin.1 as f32);
+
+    let hi = invert_3x3(affine_homography(
+        canvas, rotation_z, rotation_x, rotation_y,
+    ));
 
     let (h00, h01, h02) = (hi[0][0], hi[0][1], hi[0][2]);
     let (h10, h11, h12) = (hi[1][0], hi[1][1], hi[1][2]);
@@ -870,7 +1397,7 @@ fn apply_affine(
     let src_stride = src_w as usize * 4;
     let src_raw = src.as_raw();
 
-    let row_bytes = canvas_w as usize * 4;
+    let row_bytes = out_w as usize * 4;
     let dst_raw = dst.as_mut();
 
     // Process rows in parallel using rayon.
@@ -878,21 +1405,21 @@ fn apply_affine(
         .par_chunks_mut(row_bytes)
         .enumerate()
         .for_each(|(dy, row)| {
-            let v = (dy as f32 - cy - offset.1) * inv_scale;
+            let v = (dy as f32 + out_y - offset.1) * inv_scale;
             let base_sx = h01 * v + h02;
             let base_sy = h11 * v + h12;
             let base_sw = h21 * v + h22;
 
-            for dx in 0..canvas_w as usize {
-                let u = (dx as f32 - cx - offset.0) * inv_scale;
+            for dx in 0..out_w as usize {
+                let u = (dx as f32 + out_x - offset.0) * inv_scale;
 
                 let w = h20 * u + base_sw;
                 if w.abs() < 1e-8 {
                     continue;
                 }
                 let inv_w = 1.0 / w;
-                let src_x = (h00 * u + base_sx) * inv_w + cx;
-                let src_y = (h10 * u + base_sy) * inv_w + cy;
+                let src_x = (h00 * u + base_sx) * inv_w + src_cx;
+                let src_y = (h10 * u + base_sy) * inv_w + src_cy;
 
                 if interpolation == Interpolation::Nearest {
                     let nx = src_x.round() as i32;
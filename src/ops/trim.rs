@@ -0,0 +1,389 @@
+// ============================================================================
+// TRIM & AUTO-CROP — crop the canvas to its content
+// ============================================================================
+
+use image::{Rgba, RgbaImage};
+
+use crate::canvas::{CanvasState, LayerContent, TiledImage};
+use crate::ops::slices::tiled_content_bounds;
+use crate::ops::transform::{Interpolation, rotate_canvas_arbitrary, rotate_image_arbitrary};
+
+/// Largest skew, in degrees either way, that auto-straighten looks for.
+pub const MAX_SKEW_DEGREES: f32 = 45.0;
+
+/// Default colour tolerance for telling a scanned page from the scanner bed.
+pub const DEFAULT_SCAN_TOLERANCE: u8 = 32;
+
+/// What Trim treats as margin.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TrimBasis {
+    /// Transparent pixels.
+    #[default]
+    Transparent,
+    /// Pixels matching the top-left pixel's colour.
+    TopLeftColor,
+    /// Pixels matching the bottom-right pixel's colour.
+    BottomRightColor,
+}
+
+impl TrimBasis {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "transparent" | "alpha" => Some(Self::Transparent),
+            "top-left" | "top_left" | "tl" => Some(Self::TopLeftColor),
+            "bottom-right" | "bottom_right" | "br" => Some(Self::BottomRightColor),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Self::Transparent => t!("dialog.trim.transparent"),
+            Self::TopLeftColor => t!("dialog.trim.top_left"),
+            Self::BottomRightColor => t!("dialog.trim.bottom_right"),
+        }
+    }
+}
+
+/// Which edges Trim may cut.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrimSides {
+    pub top: bool,
+    pub bottom: bool,
+    pub left: bool,
+    pub right: bool,
+}
+
+impl TrimSides {
+    pub const ALL: Self = Self {
+        top: true,
+        bottom: true,
+        left: true,
+        right: true,
+    };
+
+    /// `"all"`, or side names separated by commas or spaces
+    /// (`"top, left"`).
+    pub fn parse(s: &str) -> Option<Self> {
+        if s.trim().eq_ignore_ascii_case("all") {
+            return Some(Self::ALL);
+        }
+        let mut sides = Self {
+            top: false,
+            bottom: false,
+            left: false,
+            right: false,
+        };
+        for word in s.split([',', ' ']).filter(|w| !w.is_empty()) {
+            match word.to_ascii_lowercase().as_str() {
+                "top" => sides.top = true,
+                "bottom" => sides.bottom = true,
+                "left" => sides.left = true,
+                "right" => sides.right = true,
+                _ => return None,
+            }
+        }
+        sides.any().then_some(sides)
+    }
+
+    pub fn any(self) -> bool {
+        self.top || self.bottom || self.left || self.right
+    }
+}
+
+impl Default for TrimSides {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct TrimOptions {
+    pub basis: TrimBasis,
+    pub sides: TrimSides,
+    /// Largest per-channel difference (alpha for `Transparent`) still
+    /// counted as margin.
+    pub tolerance: u8,
+}
+
+/// The rectangle left after trimming `image`, as `(x, y, w, h)`, or `None`
+/// when every pixel is margin.
+pub fn trim_bounds(image: &RgbaImage, options: &TrimOptions) -> Option<(u32, u32, u32, u32)> {
+    let (w, h) = image.dimensions();
+    if w == 0 || h == 0 {
+        return None;
+    }
+    let reference = match options.basis {
+        TrimBasis::Transparent => None,
+        TrimBasis::TopLeftColor => Some(*image.get_pixel(0, 0)),
+        TrimBasis::BottomRightColor => Some(*image.get_pixel(w - 1, h - 1)),
+    };
+    let is_margin = |p: &Rgba<u8>| match reference {
+        None => p[3] <= options.tolerance,
+        Some(r) => (0..4).all(|c| p[c].abs_diff(r[c]) <= options.tolerance),
+    };
+    let (x0, y0, x1, y1) = pixel_bounds(image, |p| !is_margin(p))?;
+    let sides = options.sides;
+    let x0 = if sides.left { x0 } else { 0 };
+    let x1 = if sides.right { x1 } else { w };
+    let y0 = if sides.top { y0 } else { 0 };
+    let y1 = if sides.bottom { y1 } else { h };
+    Some((x0, y0, x1 - x0, y1 - y0))
+}
+
+/// Bounds `(x0, y0, x1, y1)`, exclusive, of the pixels `keep` accepts.
+fn pixel_bounds(
+    image: &RgbaImage,
+    keep: impl Fn(&Rgba<u8>) -> bool,
+) -> Option<(u32, u32, u32, u32)> {
+    let mut bounds: Option<(u32, u32, u32, u32)> = None;
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if !keep(pixel) {
+            continue;
+        }
+        bounds = Some(match bounds {
+            Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x + 1), y1.max(y + 1)),
+            None => (x, y, x + 1, y + 1),
+        });
+    }
+    bounds
+}
+
+/// Crop the canvas to `(x, y, w, h)`: every layer, layer mask and group mask,
+/// the slices and the document bounds. Content outside the rectangle is
+/// discarded, including off-canvas pixels, and the selection is cleared.
+/// The rectangle is clamped to the canvas; nothing happens when it is empty.
+pub fn crop_canvas(state: &mut CanvasState, x: u32, y: u32, w: u32, h: u32) {
+    let x = x.min(state.width);
+    let y = y.min(state.height);
+    let w = w.min(state.width - x);
+    let h = h.min(state.height - y);
+    if w == 0 || h == 0 {
+        return;
+    }
+
+    let crop = |image: &TiledImage| {
+        let raw = image.extract_region_rgba(x, y, w, h);
+        RgbaImage::from_raw(w, h, raw)
+            .map(|cropped| TiledImage::from_rgba_image(&cropped))
+            .unwrap_or_else(|| TiledImage::new(w, h))
+    };
+    for layer in &mut state.layers {
+        layer.pixels = crop(&layer.pixels);
+        if let Some(mask) = layer.mask.as_ref() {
+            layer.mask = Some(crop(mask));
+        }
+        // Cropping is destructive: content outside the canvas goes too.
+        layer.off_canvas = None;
+        // Text keeps its look as pixels rather than shifting its geometry.
+        if matches!(layer.content, LayerContent::Text(_)) {
+            layer.content = LayerContent::Raster;
+        }
+        layer.invalidate_lod();
+    }
+    for folder in &mut state.layer_folders {
+        if let Some(mask) = folder.mask.as_ref() {
+            folder.mask = Some(crop(mask));
+        }
+    }
+
+    let slices = std::mem::take(&mut state.slices);
+    state.slices = slices
+        .into_iter()
+        .filter_map(|s| {
+            let x0 = s.x.max(x);
+            let y0 = s.y.max(y);
+            let x1 = s.x.saturating_add(s.width).min(x + w);
+            let y1 = s.y.saturating_add(s.height).min(y + h);
+            (x1 > x0 && y1 > y0).then(|| crate::canvas::Slice {
+                x: x0 - x,
+                y: y0 - y,
+                width: x1 - x0,
+                height: y1 - y0,
+                ..s
+            })
+        })
+        .collect();
+
+    if state.document_bounds.is_some() {
+        state.document_bounds = Some((0, 0, w, h));
+    }
+    state.width = w;
+    state.height = h;
+    state.selection_mask = None;
+    state.invalidate_selection_overlay();
+    state.composite_cache = None;
+    state.clear_preview_state();
+    state.mark_dirty(None);
+}
+
+/// Trim margins from the visible composite as `options` describes. Returns
+/// whether the canvas changed.
+pub fn trim_canvas(state: &mut CanvasState, options: &TrimOptions) -> bool {
+    let Some((x, y, w, h)) = trim_bounds(&state.composite(), options) else {
+        return false;
+    };
+    crop_if_smaller(state, x, y, w, h)
+}
+
+/// Crop the canvas to the non-transparent pixels of layer `index`. Returns
+/// whether the canvas changed.
+pub fn crop_to_layer_content(state: &mut CanvasState, index: usize) -> bool {
+    let Some((x, y, w, h)) = state
+        .layers
+        .get(index)
+        .and_then(|layer| tiled_content_bounds(&layer.pixels))
+    else {
+        return false;
+    };
+    crop_if_smaller(state, x, y, w, h)
+}
+
+/// [`crop_canvas`] unless the rectangle is the whole canvas, so a trim that
+/// finds nothing to cut keeps off-canvas pixels and the selection.
+fn crop_if_smaller(state: &mut CanvasState, x: u32, y: u32, w: u32, h: u32) -> bool {
+    if (x, y, w, h) == (0, 0, state.width, state.height) {
+        return false;
+    }
+    crop_canvas(state, x, y, w, h);
+    true
+}
+
+// ============================================================================
+// AUTO-STRAIGHTEN
+// ============================================================================
+
+/// The scanner bed colour: the per-channel median of the outermost pixels.
+pub fn border_color(image: &RgbaImage) -> Rgba<u8> {
+    let (w, h) = image.dimensions();
+    if w == 0 || h == 0 {
+        return Rgba([0, 0, 0, 0]);
+    }
+    let mut border: Vec<Rgba<u8>> = Vec::with_capacity(2 * (w + h) as usize);
+    for x in 0..w {
+        border.push(*image.get_pixel(x, 0));
+        border.push(*image.get_pixel(x, h - 1));
+    }
+    for y in 1..h.saturating_sub(1) {
+        border.push(*image.get_pixel(0, y));
+        border.push(*image.get_pixel(w - 1, y));
+    }
+    let mut median = [0u8; 4];
+    for (c, value) in median.iter_mut().enumerate() {
+        let mut channel: Vec<u8> = border.iter().map(|p| p[c]).collect();
+        channel.sort_unstable();
+        *value = channel[channel.len() / 2];
+    }
+    Rgba(median)
+}
+
+/// Whether `pixel` differs from `background` by more than `tolerance` in
+/// any colour channel. Transparent pixels never do.
+fn is_foreground(pixel: &Rgba<u8>, background: Rgba<u8>, tolerance: u8) -> bool {
+    pixel[3] > 0 && (0..3).any(|c| pixel[c].abs_diff(background[c]) > tolerance)
+}
+
+/// The angle, in degrees clockwise, that levels the document in `image`
+/// (a scan on a plain background), or `None` when there is no document or
+/// it is already level. The angle is the rotation that fits the document's
+/// outline into the smallest axis-aligned box.
+pub fn detect_skew(image: &RgbaImage, tolerance: u8) -> Option<f32> {
+    let background = border_color(image);
+    let foreground = |x: u32, y: u32| is_foreground(image.get_pixel(x, y), background, tolerance);
+    let (w, h) = image.dimensions();
+    let (cx, cy) = (w as f32 * 0.5, h as f32 * 0.5);
+
+    // The outline: the first and last foreground pixel of every row and
+    // every column.
+    let mut outline: Vec<(f32, f32)> = Vec::new();
+    let mut push = |x: u32, y: u32| outline.push((x as f32 + 0.5 - cx, y as f32 + 0.5 - cy));
+    for y in 0..h {
+        let mut xs = (0..w).filter(|&x| foreground(x, y));
+        if let Some(first) = xs.next() {
+            push(first, y);
+            push(xs.next_back().unwrap_or(first), y);
+        }
+    }
+    for x in 0..w {
+        let mut ys = (0..h).filter(|&y| foreground(x, y));
+        if let Some(first) = ys.next() {
+            push(x, first);
+            push(x, ys.next_back().unwrap_or(first));
+        }
+    }
+    if outline.len() < 8 {
+        return None;
+    }
+
+    // Same convention as `rotate_canvas_arbitrary`: positive is clockwise
+    // on screen (y points down).
+    let box_area = |degrees: f32| {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let (mut x0, mut y0, mut x1, mut y1) = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+        for &(x, y) in &outline {
+            let (rx, ry) = (x * cos - y * sin, x * sin + y * cos);
+            x0 = x0.min(rx);
+            x1 = x1.max(rx);
+            y0 = y0.min(ry);
+            y1 = y1.max(ry);
+        }
+        (x1 - x0) * (y1 - y0)
+    };
+    let best_in = |center: f32, span: f32, step: f32| {
+        let steps = (span / step).round() as i32;
+        (-steps..=steps)
+            .map(|i| center + i as f32 * step)
+            .filter(|a| a.abs() <= MAX_SKEW_DEGREES)
+            .map(|a| (a, box_area(a)))
+            // Prefer the smaller rotation when areas tie.
+            .min_by(|a, b| a.1.total_cmp(&b.1).then(a.0.abs().total_cmp(&b.0.abs())))
+            .map(|(a, _)| a)
+            .unwrap_or(center)
+    };
+    let coarse = best_in(0.0, MAX_SKEW_DEGREES, 0.5);
+    let fine = best_in(coarse, 0.5, 0.05);
+    let angle = best_in(fine, 0.05, 0.01);
+    (angle.abs() >= 0.05).then_some(angle)
+}
+
+/// How to level and crop a scan: the rotation from [`detect_skew`] (0 when
+/// level) and the page's bounds once rotated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScanFit {
+    pub angle: f32,
+    pub crop: Option<(u32, u32, u32, u32)>,
+}
+
+/// Work out how to level and crop the scan in `image`.
+pub fn fit_scan(image: &RgbaImage, tolerance: u8) -> ScanFit {
+    let background = border_color(image);
+    let angle = detect_skew(image, tolerance).unwrap_or(0.0);
+    let rotated;
+    let leveled = if angle == 0.0 {
+        image
+    } else {
+        rotated = rotate_image_arbitrary(image, angle, Interpolation::Bilinear);
+        &rotated
+    };
+    // The corners the rotation uncovered are transparent, and the pixels
+    // along their edges are blended towards transparent black, so only fully
+    // opaque pixels can count as the page.
+    let crop = pixel_bounds(leveled, |p| {
+        p[3] == 255 && is_foreground(p, background, tolerance)
+    })
+    .map(|(x0, y0, x1, y1)| (x0, y0, x1 - x0, y1 - y0));
+    ScanFit { angle, crop }
+}
+
+/// Level a scanned document and crop away the background around it, judged
+/// from the visible composite. Returns the angle it was rotated by (0 when
+/// it was already level), or `None` when nothing changed.
+pub fn auto_straighten_and_crop(state: &mut CanvasState, tolerance: u8) -> Option<f32> {
+    let fit = fit_scan(&state.composite(), tolerance);
+    rotate_canvas_arbitrary(state, fit.angle, Interpolation::Bilinear);
+    let cropped = match fit.crop {
+        Some((x, y, w, h)) => crop_if_smaller(state, x, y, w, h),
+        None => false,
+    };
+    (cropped || fit.angle != 0.0).then_some(fit.angle)
+}
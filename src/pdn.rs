@@ -174,6 +174,13 @@ fn blend_mode(name: &str) -> BlendMode {
         "Darken" => BlendMode::Darken,
         "Screen" => BlendMode::Screen,
         "Xor" => BlendMode::Xor,
+        "Hue" => BlendMode::Hue,
+        "Saturation" => BlendMode::Saturation,
+        "Color" => BlendMode::Color,
+        "Luminosity" => BlendMode::Luminosity,
+        "DarkerColor" => BlendMode::DarkerColor,
+        "LighterColor" => BlendMode::LighterColor,
+        "Dissolve" => BlendMode::Dissolve,
         _ => BlendMode::Normal,
     }
 }
@@ -187,6 +194,7 @@ mod tests {
         assert_eq!(blend_mode("Multiply"), BlendMode::Multiply);
         assert_eq!(blend_mode("Additive"), BlendMode::Additive);
         assert_eq!(blend_mode("ColorDodge"), BlendMode::ColorDodge);
+        assert_eq!(blend_mode("Luminosity"), BlendMode::Luminosity);
         assert_eq!(blend_mode("future-mode"), BlendMode::Normal);
     }
 
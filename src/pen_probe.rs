@@ -0,0 +1,119 @@
+// Pen tilt probe.
+//
+// egui only forwards pen pressure, so tilt is read beside it: on Windows from
+// the WM_POINTER* messages the event-loop hook in main.rs observes, in the
+// browser from pointer events on the window. Both report the W3C/Windows Ink
+// `tiltX`/`tiltY` pair in degrees; see `tools::PenTilt::from_tilt_degrees`.
+// Other platforms report no tilt.
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+
+    const WM_POINTERUPDATE: u32 = 0x0245;
+    const WM_POINTERDOWN: u32 = 0x0246;
+    const WM_POINTERUP: u32 = 0x0247;
+    const WM_POINTERLEAVE: u32 = 0x024A;
+    const WM_POINTERCAPTURECHANGED: u32 = 0x024C;
+
+    static TILT_VALID: AtomicBool = AtomicBool::new(false);
+    static TILT_X: AtomicI32 = AtomicI32::new(0);
+    static TILT_Y: AtomicI32 = AtomicI32::new(0);
+
+    pub fn observe_windows_message(message: u32, wparam: usize) {
+        use winapi::um::winuser::{
+            GetPointerPenInfo, PEN_MASK_TILT_X, PEN_MASK_TILT_Y, POINTER_PEN_INFO,
+        };
+        match message {
+            WM_POINTERDOWN | WM_POINTERUPDATE => {
+                let pointer_id = (wparam & 0xFFFF) as u32;
+                let mut info: POINTER_PEN_INFO = unsafe { std::mem::zeroed() };
+                // Fails for mouse and touch pointers, which have no tilt.
+                let is_pen = unsafe { GetPointerPenInfo(pointer_id, &mut info) } != 0;
+                if is_pen && info.penMask & (PEN_MASK_TILT_X | PEN_MASK_TILT_Y) != 0 {
+                    TILT_X.store(info.tiltX, Ordering::Relaxed);
+                    TILT_Y.store(info.tiltY, Ordering::Relaxed);
+                    TILT_VALID.store(true, Ordering::Relaxed);
+                } else {
+                    TILT_VALID.store(false, Ordering::Relaxed);
+                }
+            }
+            WM_POINTERUP | WM_POINTERLEAVE | WM_POINTERCAPTURECHANGED => {
+                TILT_VALID.store(false, Ordering::Relaxed);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn install() {}
+
+    pub fn tilt_degrees() -> Option<(f32, f32)> {
+        TILT_VALID.load(Ordering::Relaxed).then(|| {
+            (
+                TILT_X.load(Ordering::Relaxed) as f32,
+                TILT_Y.load(Ordering::Relaxed) as f32,
+            )
+        })
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod imp {
+    use std::cell::Cell;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen::prelude::*;
+
+    thread_local! {
+        static TILT: Cell<Option<(f32, f32)>> = const { Cell::new(None) };
+    }
+
+    pub fn observe_windows_message(_message: u32, _wparam: usize) {}
+
+    /// Listen for pointer events on the window. eframe's own listeners sit on
+    /// the canvas, so these see every event without interfering with them.
+    pub fn install() {
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        let on_move = Closure::<dyn FnMut(_)>::new(|event: web_sys::PointerEvent| {
+            let tilt = (event.pointer_type() == "pen")
+                .then(|| (event.tilt_x() as f32, event.tilt_y() as f32));
+            TILT.with(|t| t.set(tilt));
+        });
+        let on_end = Closure::<dyn FnMut(_)>::new(|_event: web_sys::PointerEvent| {
+            TILT.with(|t| t.set(None));
+        });
+        for name in ["pointerdown", "pointermove"] {
+            let _ = window.add_event_listener_with_callback(name, on_move.as_ref().unchecked_ref());
+        }
+        for name in ["pointerup", "pointercancel"] {
+            let _ = window.add_event_listener_with_callback(name, on_end.as_ref().unchecked_ref());
+        }
+        // Leak intentionally: the listeners live as long as the page.
+        on_move.forget();
+        on_end.forget();
+    }
+
+    pub fn tilt_degrees() -> Option<(f32, f32)> {
+        TILT.with(|t| t.get())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_arch = "wasm32")))]
+mod imp {
+    pub fn observe_windows_message(_message: u32, _wparam: usize) {}
+
+    pub fn install() {}
+
+    pub fn tilt_degrees() -> Option<(f32, f32)> {
+        None
+    }
+}
+
+/// Start listening for pen input where that needs setting up (the browser).
+pub use imp::install;
+/// Feed a Win32 message from the event-loop hook.
+pub use imp::observe_windows_message;
+/// The latest `(tiltX, tiltY)` of a pen in contact or hovering, in degrees,
+/// or `None` for mouse/touch input and platforms without tilt.
+pub use imp::tilt_degrees;
@@ -0,0 +1,1388 @@
+//! Photoshop PSD/PSB import and layered PSD export.
+//!
+//! The reader maps raster layers, groups (to [`LayerFolder`]s), opacity,
+//! visibility, blend modes and layer masks. Text layers come in as their
+//! rasterized pixels with the text kept in the layer's source metadata under
+//! [`PSD_TEXT_KEY`]. Nested groups keep their nesting, opacity, blend mode
+//! (`pass` = pass through) and group mask.
+//!
+//! The writer emits 8-bit RGB with the same structure: one layer record per
+//! layer, section dividers around (nested) folders, masks, and a merged
+//! composite for readers that ignore layers.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use image::{Rgba, RgbaImage};
+
+use crate::canvas::{BlendMode, CanvasState, Layer, LayerFolder, TiledImage};
+#[cfg(target_arch = "wasm32")]
+use crate::web_fs::File;
+
+/// Source metadata key holding the text of an imported Photoshop text layer.
+pub const PSD_TEXT_KEY: &str = "psd:text";
+
+const MODE_GRAYSCALE: u16 = 1;
+const MODE_RGB: u16 = 3;
+
+/// Layer record flag: layer is hidden.
+const FLAG_HIDDEN: u8 = 0x02;
+/// Layer record flag: bit 4 carries meaningful data (set by Photoshop).
+const FLAG_BIT4_USEFUL: u8 = 0x08;
+
+/// Section divider types from the `lsct` block.
+const SECTION_OPEN_FOLDER: u32 = 1;
+const SECTION_CLOSED_FOLDER: u32 = 2;
+const SECTION_DIVIDER: u32 = 3;
+
+const DIVIDER_NAME: &str = "</Layer group>";
+
+/// Channel IDs in layer records.
+const CHANNEL_ALPHA: i16 = -1;
+const CHANNEL_MASK: i16 = -2;
+
+/// Photoshop allows 56 channels per layer; anything beyond is a corrupt record.
+const MAX_LAYER_CHANNELS: usize = 56;
+
+// ============================================================================
+// IMPORT
+// ============================================================================
+
+/// Read a `.psd` or `.psb` file into a layered [`CanvasState`].
+pub fn load_psd(path: &Path) -> Result<CanvasState, String> {
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    parse_psd(&data)
+}
+
+struct Header {
+    width: u32,
+    height: u32,
+    channels: u16,
+    depth: u16,
+    mode: u16,
+    psb: bool,
+}
+
+struct MaskInfo {
+    rect: [i32; 4],
+    default_color: u8,
+    disabled: bool,
+}
+
+struct LayerRecord {
+    /// Top, left, bottom, right.
+    rect: [i32; 4],
+    channels: Vec<(i16, u64)>,
+    blend_key: [u8; 4],
+    opacity: u8,
+    flags: u8,
+    name: String,
+    mask: Option<MaskInfo>,
+    section: Option<u32>,
+    text: Option<String>,
+}
+
+/// A layer record with its decoded channel planes (one byte per sample).
+struct ParsedLayer {
+    record: LayerRecord,
+    planes: Vec<(i16, Vec<u8>)>,
+}
+
+/// Parse PSD/PSB bytes. Only RGB and grayscale documents are supported.
+pub fn parse_psd(data: &[u8]) -> Result<CanvasState, String> {
+    let mut r = Reader::new(data);
+    if r.bytes(4)? != b"8BPS" {
+        return Err("Not a Photoshop document".to_string());
+    }
+    let psb = match r.u16()? {
+        1 => false,
+        2 => true,
+        version => return Err(format!("Unsupported PSD version {}", version)),
+    };
+    r.skip(6)?;
+    let channels = r.u16()?;
+    let height = r.u32()?;
+    let width = r.u32()?;
+    let depth = r.u16()?;
+    let mode = r.u16()?;
+    crate::io::validate_open_dimensions(width, height)?;
+    if !matches!(depth, 8 | 16 | 32) {
+        return Err(format!("Unsupported PSD bit depth {}", depth));
+    }
+    if !matches!(mode, MODE_GRAYSCALE | MODE_RGB) {
+        return Err("Only RGB and grayscale PSD files are supported".to_string());
+    }
+    let header = Header {
+        width,
+        height,
+        channels,
+        depth,
+        mode,
+        psb,
+    };
+
+    let color_mode_len = r.u32()? as usize;
+    r.skip(color_mode_len)?;
+    let resources_len = r.u32()? as usize;
+    r.skip(resources_len)?;
+    let section_len = r.length(psb)?;
+    let layers = parse_layer_section(r.bytes(section_len)?, &header)?;
+
+    let mut state = CanvasState::new(width, height);
+    state.layers.clear();
+    if layers.is_empty() {
+        let merged = parse_merged_image(&mut r, &header)?;
+        let mut layer = Layer::new("Background".to_string(), width, height, Rgba([0, 0, 0, 0]));
+        layer.pixels = TiledImage::from_rgba_image(&merged);
+        layer.source_metadata.source_format = Some("psd".to_string());
+        state.layers.push(layer);
+    } else {
+        build_layers(&mut state, layers, &header);
+        if state.layers.is_empty() {
+            state.layers.push(Layer::new(
+                "Background".to_string(),
+                width,
+                height,
+                Rgba([0, 0, 0, 0]),
+            ));
+        }
+    }
+    state.active_layer_index = state.layers.len() - 1;
+    state.composite_cache = None;
+    Ok(state)
+}
+
+/// Parse the layer and mask information section. 16- and 32-bit documents
+/// leave the regular layer info empty and store it in an `Lr16`/`Lr32` block.
+fn parse_layer_section(section: &[u8], header: &Header) -> Result<Vec<ParsedLayer>, String> {
+    let mut r = Reader::new(section);
+    if r.remaining() == 0 {
+        return Ok(Vec::new());
+    }
+    let info_len = r.length(header.psb)?;
+    let layers = parse_layer_info(r.bytes(info_len)?, header)?;
+    if !layers.is_empty() || r.remaining() < 4 {
+        return Ok(layers);
+    }
+    let global_mask_len = r.u32()? as usize;
+    r.skip(global_mask_len.min(r.remaining()))?;
+    for (key, block) in tagged_blocks(&mut r, header.psb) {
+        if matches!(&key, b"Layr" | b"Lr16" | b"Lr32") {
+            return parse_layer_info(block, header);
+        }
+    }
+    Ok(layers)
+}
+
+fn parse_layer_info(info: &[u8], header: &Header) -> Result<Vec<ParsedLayer>, String> {
+    if info.len() < 2 {
+        return Ok(Vec::new());
+    }
+    let mut r = Reader::new(info);
+    // A negative count means the first alpha channel holds the merged transparency.
+    let count = (r.u16()? as i16).unsigned_abs() as usize;
+    let mut records = Vec::with_capacity(count);
+    for _ in 0..count {
+        records.push(read_layer_record(&mut r, header.psb)?);
+    }
+
+    let mut layers = Vec::with_capacity(count);
+    for record in records {
+        let mut planes = Vec::with_capacity(record.channels.len());
+        for &(id, len) in &record.channels {
+            let len = usize::try_from(len).map_err(|_| "PSD channel too large".to_string())?;
+            let data = r.bytes(len)?;
+            let rect = match (id, &record.mask) {
+                (CHANNEL_MASK, Some(mask)) => mask.rect,
+                (CHANNEL_MASK, None) => [0; 4],
+                _ => record.rect,
+            };
+            // Skip spot channels and the "real" user mask behind a vector mask.
+            if !(CHANNEL_MASK..=2).contains(&id) {
+                continue;
+            }
+            planes.push((id, decode_channel(data, rect, id, header)?));
+        }
+        layers.push(ParsedLayer { record, planes });
+    }
+    Ok(layers)
+}
+
+fn read_layer_record(r: &mut Reader, psb: bool) -> Result<LayerRecord, String> {
+    let rect = [r.i32()?, r.i32()?, r.i32()?, r.i32()?];
+    let channel_count = r.u16()? as usize;
+    if channel_count > MAX_LAYER_CHANNELS {
+        return Err("Corrupt PSD layer record".to_string());
+    }
+    let mut channels = Vec::with_capacity(channel_count);
+    for _ in 0..channel_count {
+        let id = r.u16()? as i16;
+        channels.push((id, r.length(psb)? as u64));
+    }
+    if r.bytes(4)? != b"8BIM" {
+        return Err("Corrupt PSD layer record".to_string());
+    }
+    let mut blend_key: [u8; 4] = r.bytes(4)?.try_into().unwrap_or(*b"norm");
+    let opacity = r.u8()?;
+    let _clipping = r.u8()?;
+    let flags = r.u8()?;
+    r.skip(1)?;
+
+    let extra_len = r.u32()? as usize;
+    let mut extra = Reader::new(r.bytes(extra_len)?);
+    let mask_len = extra.u32()? as usize;
+    let mask = read_mask_info(extra.bytes(mask_len)?);
+    let ranges_len = extra.u32()? as usize;
+    extra.skip(ranges_len)?;
+    let name_len = extra.u8()? as usize;
+    let mut name: String = extra.bytes(name_len)?.iter().map(|&b| b as char).collect();
+    let padding = (4 - (1 + name_len) % 4) % 4;
+    extra.skip(padding.min(extra.remaining()))?;
+
+    let mut section = None;
+    let mut text = None;
+    for (key, block) in tagged_blocks(&mut extra, psb) {
+        match &key {
+            b"luni" => {
+                if let Ok(unicode) = Reader::new(block).ucs2_string() {
+                    name = unicode;
+                }
+            }
+            b"lsct" | b"lsdk" => {
+                section = Reader::new(block).u32().ok();
+                // Group headers carry their blend mode ("pass" for pass through) here.
+                if block.len() >= 12 && &block[4..8] == b"8BIM" {
+                    blend_key.copy_from_slice(&block[8..12]);
+                }
+            }
+            b"TySh" => text = read_type_tool_text(block),
+            _ => {}
+        }
+    }
+
+    Ok(LayerRecord {
+        rect,
+        channels,
+        blend_key,
+        opacity,
+        flags,
+        name,
+        mask,
+        section,
+        text,
+    })
+}
+
+fn read_mask_info(data: &[u8]) -> Option<MaskInfo> {
+    let mut r = Reader::new(data);
+    let rect = [r.i32().ok()?, r.i32().ok()?, r.i32().ok()?, r.i32().ok()?];
+    let default_color = r.u8().ok()?;
+    let flags = r.u8().ok()?;
+    Some(MaskInfo {
+        rect,
+        default_color,
+        disabled: flags & 0x02 != 0,
+    })
+}
+
+/// Pull the plain text out of a `TySh` (type tool) block.
+fn read_type_tool_text(block: &[u8]) -> Option<String> {
+    let mut r = Reader::new(block);
+    // Version, transform (6 × f64), text version, descriptor version.
+    r.skip(2 + 48 + 2 + 4).ok()?;
+    let descriptor = r.descriptor(0).ok()?;
+    match descriptor.get("Txt") {
+        Some(Value::Text(text)) => Some(text.replace('\r', "\n")),
+        _ => None,
+    }
+}
+
+/// Collect `8BIM`/`8B64` tagged blocks until the data runs out or stops matching.
+fn tagged_blocks<'a>(r: &mut Reader<'a>, psb: bool) -> Vec<([u8; 4], &'a [u8])> {
+    let mut blocks = Vec::new();
+    while r.remaining() >= 12 {
+        let Ok(signature) = r.bytes(4) else {
+            break;
+        };
+        if signature != b"8BIM" && signature != b"8B64" {
+            break;
+        }
+        let Ok(key) = r.bytes(4) else {
+            break;
+        };
+        let key: [u8; 4] = key.try_into().unwrap_or([0; 4]);
+        let len = if psb && has_long_length(&key) {
+            r.length(true)
+        } else {
+            r.u32().map(|n| n as usize)
+        };
+        let Ok(len) = len else {
+            break;
+        };
+        let Ok(block) = r.bytes(len.min(r.remaining())) else {
+            break;
+        };
+        blocks.push((key, block));
+    }
+    blocks
+}
+
+/// PSB widens the length field of these tagged blocks to 64 bits.
+fn has_long_length(key: &[u8; 4]) -> bool {
+    matches!(
+        key,
+        b"LMsk"
+            | b"Lr16"
+            | b"Lr32"
+            | b"Layr"
+            | b"Mt16"
+            | b"Mt32"
+            | b"Mtrn"
+            | b"Alph"
+            | b"FMsk"
+            | b"lnk2"
+            | b"FEid"
+            | b"FXid"
+            | b"PxSD"
+    )
+}
+
+/// Decode one layer channel (compression tag + data) to 8-bit samples.
+fn decode_channel(
+    data: &[u8],
+    rect: [i32; 4],
+    id: i16,
+    header: &Header,
+) -> Result<Vec<u8>, String> {
+    let (w, h) = rect_size(rect)?;
+    if data.len() < 2 || w == 0 || h == 0 {
+        return Ok(Vec::new());
+    }
+    let compression = u16::from_be_bytes([data[0], data[1]]);
+    let raw = decode_planes(compression, &data[2..], w, h, 1, header)?;
+    Ok(samples_to_u8(&raw, header.depth, id >= 0))
+}
+
+fn rect_size(rect: [i32; 4]) -> Result<(u32, u32), String> {
+    let w = (rect[3] as i64 - rect[1] as i64).max(0);
+    let h = (rect[2] as i64 - rect[0] as i64).max(0);
+    if w > crate::io::MAX_OPEN_IMAGE_DIM as i64 * 2 || h > crate::io::MAX_OPEN_IMAGE_DIM as i64 * 2
+    {
+        return Err("PSD layer bounds are too large".to_string());
+    }
+    Ok((w as u32, h as u32))
+}
+
+/// Decompress `planes` consecutive channel planes of `w × h` samples at the
+/// document bit depth, returning the raw big-endian sample bytes.
+fn decode_planes(
+    compression: u16,
+    data: &[u8],
+    w: u32,
+    h: u32,
+    planes: usize,
+    header: &Header,
+) -> Result<Vec<u8>, String> {
+    let sample_bytes = header.depth as usize / 8;
+    let row = w as usize * sample_bytes;
+    let total = row * h as usize * planes;
+    // A PackBits run turns 2 bytes into at most 128 and deflate tops out near
+    // 1032:1, so claims beyond that come from a corrupt rect and must not
+    // drive the allocations below.
+    let max_expansion = match compression {
+        1 => 64,
+        2 | 3 => 1032,
+        _ => 1,
+    };
+    if total > data.len().saturating_mul(max_expansion) {
+        return Err("Corrupt PSD channel data".to_string());
+    }
+    match compression {
+        0 => {
+            if data.len() < total {
+                return Err("Truncated PSD channel data".to_string());
+            }
+            Ok(data[..total].to_vec())
+        }
+        1 => {
+            let count_bytes = if header.psb { 4 } else { 2 };
+            let skip = h as usize * planes * count_bytes;
+            if data.len() < skip {
+                return Err("Truncated PSD channel data".to_string());
+            }
+            Ok(unpack_bits(&data[skip..], total))
+        }
+        2 | 3 => {
+            let mut out = Vec::with_capacity(total);
+            flate2::read::ZlibDecoder::new(data)
+                .take(total as u64)
+                .read_to_end(&mut out)
+                .map_err(|e| format!("PSD channel inflate failed: {}", e))?;
+            out.resize(total, 0);
+            if compression == 3 {
+                for row_bytes in out.chunks_exact_mut(row.max(1)) {
+                    undo_prediction(row_bytes, w as usize, sample_bytes);
+                }
+            }
+            Ok(out)
+        }
+        other => Err(format!("Unsupported PSD compression {}", other)),
+    }
+}
+
+/// Reverse the per-row delta prediction of ZIP-with-prediction channels.
+/// 32-bit rows are additionally split into byte planes before the delta.
+fn undo_prediction(row: &mut [u8], width: usize, sample_bytes: usize) {
+    match sample_bytes {
+        1 => {
+            for i in 1..row.len() {
+                row[i] = row[i].wrapping_add(row[i - 1]);
+            }
+        }
+        2 => {
+            let mut prev = 0u16;
+            for sample in row.chunks_exact_mut(2) {
+                let value = u16::from_be_bytes([sample[0], sample[1]]).wrapping_add(prev);
+                sample.copy_from_slice(&value.to_be_bytes());
+                prev = value;
+            }
+        }
+        _ => {
+            for i in 1..row.len() {
+                row[i] = row[i].wrapping_add(row[i - 1]);
+            }
+            let planar = row.to_vec();
+            for x in 0..width {
+                for b in 0..4 {
+                    row[x * 4 + b] = planar[b * width + x];
+                }
+            }
+        }
+    }
+}
+
+/// Convert big-endian samples to 8-bit. 32-bit documents are linear light,
+/// so their color channels are gamma-encoded to sRGB.
+fn samples_to_u8(raw: &[u8], depth: u16, color: bool) -> Vec<u8> {
+    match depth {
+        16 => raw
+            .chunks_exact(2)
+            .map(|s| ((u16::from_be_bytes([s[0], s[1]]) as u32 * 255 + 32767) / 65535) as u8)
+            .collect(),
+        32 => raw
+            .chunks_exact(4)
+            .map(|s| {
+                let v = f32::from_be_bytes([s[0], s[1], s[2], s[3]]).clamp(0.0, 1.0);
+                let v = if color { linear_to_srgb(v) } else { v };
+                (v * 255.0).round() as u8
+            })
+            .collect(),
+        _ => raw.to_vec(),
+    }
+}
+
+fn linear_to_srgb(v: f32) -> f32 {
+    if v <= 0.003_130_8 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// PackBits decoder; pads or truncates to `expected` bytes.
+fn unpack_bits(data: &[u8], expected: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(expected);
+    let mut i = 0;
+    while out.len() < expected && i < data.len() {
+        let n = data[i] as i8;
+        i += 1;
+        if n >= 0 {
+            let end = (i + n as usize + 1).min(data.len());
+            out.extend_from_slice(&data[i..end]);
+            i = end;
+        } else if n != -128 {
+            let count = (1 - n as i32) as usize;
+            if let Some(&value) = data.get(i) {
+                out.extend(std::iter::repeat_n(value, count));
+            }
+            i += 1;
+        }
+    }
+    out.resize(expected, 0);
+    out
+}
+
+/// Decode the merged composite at the end of the file (used when a document
+/// has no layer records).
+fn parse_merged_image(r: &mut Reader, header: &Header) -> Result<RgbaImage, String> {
+    let compression = r.u16()?;
+    let planes = header.channels.max(1) as usize;
+    let rest = r.bytes(r.remaining())?;
+    let raw = decode_planes(
+        compression,
+        rest,
+        header.width,
+        header.height,
+        planes,
+        header,
+    )?;
+    let plane_len = raw.len() / planes;
+    let alpha_plane = if header.mode == MODE_RGB { 3 } else { 1 };
+    let plane = |index: usize| -> Option<Vec<u8>> {
+        (index < planes).then(|| {
+            samples_to_u8(
+                &raw[index * plane_len..(index + 1) * plane_len],
+                header.depth,
+                index != alpha_plane,
+            )
+        })
+    };
+    let gray = plane(0).unwrap_or_default();
+    let (red, green, blue) = if header.mode == MODE_RGB {
+        (
+            gray.clone(),
+            plane(1).unwrap_or_else(|| gray.clone()),
+            plane(2).unwrap_or_else(|| gray.clone()),
+        )
+    } else {
+        (gray.clone(), gray.clone(), gray)
+    };
+    let alpha = plane(alpha_plane);
+    let mut image = RgbaImage::new(header.width, header.height);
+    for (i, pixel) in image.pixels_mut().enumerate() {
+        *pixel = Rgba([
+            red.get(i).copied().unwrap_or(0),
+            green.get(i).copied().unwrap_or(0),
+            blue.get(i).copied().unwrap_or(0),
+            alpha
+                .as_ref()
+                .map_or(255, |a| a.get(i).copied().unwrap_or(255)),
+        ]);
+    }
+    Ok(image)
+}
+
+/// Turn parsed records (bottom to top) into layers and nested folders.
+fn build_layers(state: &mut CanvasState, layers: Vec<ParsedLayer>, header: &Header) {
+    struct OpenGroup {
+        folder: usize,
+        first_layer: usize,
+    }
+    let mut stack: Vec<OpenGroup> = Vec::new();
+
+    for ParsedLayer { record, planes } in layers {
+        match record.section {
+            // Groups are stored bottom-up: the divider opens, the header closes.
+            Some(SECTION_DIVIDER) => {
+                let id = state.next_layer_folder_id;
+                state.next_layer_folder_id += 1;
+                let parent_id = stack
+                    .last()
+                    .map(|group| state.layer_folders[group.folder].id);
+                state.layer_folders.push(LayerFolder {
+                    id,
+                    parent_id,
+                    ..Default::default()
+                });
+                stack.push(OpenGroup {
+                    folder: state.layer_folders.len() - 1,
+                    first_layer: state.layers.len(),
+                });
+                continue;
+            }
+            Some(section @ (SECTION_OPEN_FOLDER | SECTION_CLOSED_FOLDER)) => {
+                if let Some(group) = stack.pop() {
+                    let mask = mask_from_record(&record, &planes, header);
+                    let folder = &mut state.layer_folders[group.folder];
+                    folder.name = record.name;
+                    folder.visible = record.flags & FLAG_HIDDEN == 0;
+                    folder.collapsed = section == SECTION_CLOSED_FOLDER;
+                    folder.opacity = record.opacity as f32 / 255.0;
+                    folder.blend_mode = (&record.blend_key != b"pass")
+                        .then(|| blend_mode_from_key(&record.blend_key));
+                    if let Some((mask, enabled)) = mask {
+                        folder.mask = Some(mask);
+                        folder.mask_enabled = enabled;
+                    }
+                    if group.first_layer == state.layers.len() {
+                        folder.insert_above_layer = state.layers.len().checked_sub(1);
+                    }
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        let mut layer = layer_from_record(&record, &planes, header);
+        layer.folder_id = stack
+            .last()
+            .map(|group| state.layer_folders[group.folder].id);
+        state.layers.push(layer);
+    }
+
+    for (index, folder) in state.layer_folders.iter_mut().enumerate() {
+        if folder.name.is_empty() {
+            folder.name = format!("Group {}", index + 1);
+        }
+    }
+}
+
+/// The record's user mask as PaintFE concealment, with its enabled flag.
+fn mask_from_record(
+    record: &LayerRecord,
+    planes: &[(i16, Vec<u8>)],
+    header: &Header,
+) -> Option<(TiledImage, bool)> {
+    let mask = record.mask.as_ref()?;
+    let values = planes
+        .iter()
+        .find(|(plane_id, _)| *plane_id == CHANNEL_MASK)
+        .map(|(_, data)| data)?;
+    let (width, height) = (header.width, header.height);
+    // PSD masks are reveal-white; PaintFE stores concealment in alpha.
+    let outside = 255 - mask.default_color;
+    let mut concealment = RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, outside]));
+    let (mask_w, mask_h) = rect_size(mask.rect).unwrap_or((0, 0));
+    for_each_in_canvas(mask.rect, mask_w, mask_h, width, height, |i, x, y| {
+        let value = values.get(i).copied().unwrap_or(mask.default_color);
+        concealment.put_pixel(x, y, Rgba([0, 0, 0, 255 - value]));
+    });
+    Some((TiledImage::from_rgba_image(&concealment), !mask.disabled))
+}
+
+fn layer_from_record(record: &LayerRecord, planes: &[(i16, Vec<u8>)], header: &Header) -> Layer {
+    let (width, height) = (header.width, header.height);
+    let plane = |id: i16| {
+        planes
+            .iter()
+            .find(|(plane_id, _)| *plane_id == id)
+            .map(|(_, data)| data.as_slice())
+    };
+    let (red, green, blue) = if header.mode == MODE_RGB {
+        (plane(0), plane(1), plane(2))
+    } else {
+        (plane(0), plane(0), plane(0))
+    };
+    let alpha = plane(CHANNEL_ALPHA);
+
+    let mut image = RgbaImage::new(width, height);
+    let (layer_w, layer_h) = rect_size(record.rect).unwrap_or((0, 0));
+    for_each_in_canvas(record.rect, layer_w, layer_h, width, height, |i, x, y| {
+        let sample =
+            |p: Option<&[u8]>, default| p.and_then(|p| p.get(i)).copied().unwrap_or(default);
+        image.put_pixel(
+            x,
+            y,
+            Rgba([
+                sample(red, 0),
+                sample(green, 0),
+                sample(blue, 0),
+                sample(alpha, 255),
+            ]),
+        );
+    });
+
+    let mut layer = Layer::new(record.name.clone(), width, height, Rgba([0, 0, 0, 0]));
+    layer.pixels = TiledImage::from_rgba_image(&image);
+    layer.visible = record.flags & FLAG_HIDDEN == 0;
+    layer.opacity = record.opacity as f32 / 255.0;
+    layer.blend_mode = blend_mode_from_key(&record.blend_key);
+    layer.source_metadata.source_format = Some("psd".to_string());
+    if let Some(text) = &record.text {
+        layer
+            .source_metadata
+            .png_text_chunks
+            .push((PSD_TEXT_KEY.to_string(), text.clone()));
+    }
+
+    if let Some((mask, enabled)) = mask_from_record(record, planes, header) {
+        layer.mask = Some(mask);
+        layer.mask_enabled = enabled;
+    }
+    layer
+}
+
+/// Visit the samples of a `rect_w × rect_h` plane at `rect` that land on the
+/// canvas, passing the sample index and canvas coordinates.
+fn for_each_in_canvas(
+    rect: [i32; 4],
+    rect_w: u32,
+    rect_h: u32,
+    width: u32,
+    height: u32,
+    mut f: impl FnMut(usize, u32, u32),
+) {
+    for row in 0..rect_h {
+        let y = rect[0] as i64 + row as i64;
+        if y < 0 || y >= height as i64 {
+            continue;
+        }
+        for col in 0..rect_w {
+            let x = rect[1] as i64 + col as i64;
+            if x < 0 || x >= width as i64 {
+                continue;
+            }
+            f((row * rect_w + col) as usize, x as u32, y as u32);
+        }
+    }
+}
+
+fn blend_mode_from_key(key: &[u8; 4]) -> BlendMode {
+    match key {
+        b"mul " => BlendMode::Multiply,
+        b"scrn" => BlendMode::Screen,
+        b"over" => BlendMode::Overlay,
+        b"sLit" => BlendMode::SoftLight,
+        b"hLit" => BlendMode::HardLight,
+        b"vLit" => BlendMode::VividLight,
+        b"lLit" => BlendMode::LinearLight,
+        b"pLit" => BlendMode::PinLight,
+        b"hMix" => BlendMode::HardMix,
+        b"diff" => BlendMode::Difference,
+        b"smud" => BlendMode::Exclusion,
+        b"fsub" => BlendMode::Subtract,
+        b"fdiv" => BlendMode::Divide,
+        b"lbrn" => BlendMode::LinearBurn,
+        b"idiv" => BlendMode::ColorBurn,
+        b"div " => BlendMode::ColorDodge,
+        b"lddg" => BlendMode::Additive,
+        b"dark" => BlendMode::Darken,
+        b"lite" => BlendMode::Lighten,
+        b"dkCl" => BlendMode::DarkerColor,
+        b"lgCl" => BlendMode::LighterColor,
+        b"hue " => BlendMode::Hue,
+        b"sat " => BlendMode::Saturation,
+        b"colr" => BlendMode::Color,
+        b"lum " => BlendMode::Luminosity,
+        b"diss" => BlendMode::Dissolve,
+        _ => BlendMode::Normal,
+    }
+}
+
+/// Photoshop has no equivalent of Reflect, Glow, Negation, Xor or Overwrite;
+/// those export as Normal.
+fn blend_mode_key(mode: BlendMode) -> [u8; 4] {
+    *match mode {
+        BlendMode::Multiply => b"mul ",
+        BlendMode::Screen => b"scrn",
+        BlendMode::Overlay => b"over",
+        BlendMode::SoftLight => b"sLit",
+        BlendMode::HardLight => b"hLit",
+        BlendMode::VividLight => b"vLit",
+        BlendMode::LinearLight => b"lLit",
+        BlendMode::PinLight => b"pLit",
+        BlendMode::HardMix => b"hMix",
+        BlendMode::Difference => b"diff",
+        BlendMode::Exclusion => b"smud",
+        BlendMode::Subtract => b"fsub",
+        BlendMode::Divide => b"fdiv",
+        BlendMode::LinearBurn => b"lbrn",
+        BlendMode::ColorBurn => b"idiv",
+        BlendMode::ColorDodge => b"div ",
+        BlendMode::Additive => b"lddg",
+        BlendMode::Darken => b"dark",
+        BlendMode::Lighten => b"lite",
+        BlendMode::DarkerColor => b"dkCl",
+        BlendMode::LighterColor => b"lgCl",
+        BlendMode::Hue => b"hue ",
+        BlendMode::Saturation => b"sat ",
+        BlendMode::Color => b"colr",
+        BlendMode::Luminosity => b"lum ",
+        BlendMode::Dissolve => b"diss",
+        BlendMode::Normal
+        | BlendMode::Reflect
+        | BlendMode::Glow
+        | BlendMode::Negation
+        | BlendMode::Xor
+        | BlendMode::Overwrite => b"norm",
+    }
+}
+
+// ============================================================================
+// EXPORT
+// ============================================================================
+
+/// Snapshot of a canvas ready to encode as PSD. Built on the UI thread with
+/// [`build_psd`] so [`write_psd`] can run in the background.
+pub struct PsdData {
+    width: u32,
+    height: u32,
+    records: Vec<PsdRecord>,
+    composite: RgbaImage,
+}
+
+struct PsdRecord {
+    name: String,
+    /// Top, left, bottom, right.
+    rect: [i32; 4],
+    /// Uncompressed planes; the mask plane covers `mask_rect` instead of `rect`.
+    channels: Vec<(i16, Vec<u8>)>,
+    mask_rect: Option<[i32; 4]>,
+    mask_disabled: bool,
+    blend_key: [u8; 4],
+    opacity: u8,
+    hidden: bool,
+    section: Option<u32>,
+}
+
+impl PsdRecord {
+    fn group_marker(name: &str, hidden: bool, section: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            rect: [0; 4],
+            channels: [CHANNEL_ALPHA, 0, 1, 2]
+                .into_iter()
+                .map(|id| (id, Vec::new()))
+                .collect(),
+            mask_rect: None,
+            mask_disabled: false,
+            blend_key: *b"pass",
+            opacity: 255,
+            hidden,
+            section: Some(section),
+        }
+    }
+}
+
+/// Save `state` as a layered PSD.
+pub fn save_psd(state: &CanvasState, path: &Path) -> std::io::Result<()> {
+    write_psd(&build_psd(state), path)
+}
+
+/// Write a snapshot from [`build_psd`] to `path`.
+pub fn write_psd(data: &PsdData, path: &Path) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(&encode_psd(data))
+}
+
+/// Encode a single flattened image as a one-layer PSD.
+pub fn encode_flat_psd(image: &RgbaImage) -> Vec<u8> {
+    let mut layer = Layer::new(
+        "Background".to_string(),
+        image.width(),
+        image.height(),
+        Rgba([0, 0, 0, 0]),
+    );
+    layer.pixels = TiledImage::from_rgba_image(image);
+    encode_psd(&PsdData {
+        width: image.width(),
+        height: image.height(),
+        records: vec![layer_record(&layer)],
+        composite: image.clone(),
+    })
+}
+
+/// Snapshot the layers, folders and composite of `state`. Text layers should
+/// be rasterized first.
+pub fn build_psd(state: &CanvasState) -> PsdData {
+    use crate::canvas::LayerTreeNode;
+    // Groups are written bottom-up: divider, contents, then the header.
+    fn write_nodes(state: &CanvasState, nodes: &[LayerTreeNode], records: &mut Vec<PsdRecord>) {
+        for node in nodes {
+            match node {
+                LayerTreeNode::Layer(index) => records.push(layer_record(&state.layers[*index])),
+                LayerTreeNode::Folder(id, children) => {
+                    let Some(folder) = state.layer_folder(*id) else {
+                        write_nodes(state, children, records);
+                        continue;
+                    };
+                    records.push(PsdRecord::group_marker(
+                        DIVIDER_NAME,
+                        false,
+                        SECTION_DIVIDER,
+                    ));
+                    write_nodes(state, children, records);
+                    records.push(folder_header(folder));
+                }
+            }
+        }
+    }
+
+    let (width, height) = (state.width, state.height);
+    let mut records = Vec::new();
+    write_nodes(state, &state.layer_tree(), &mut records);
+
+    PsdData {
+        width,
+        height,
+        records,
+        composite: state.composite(),
+    }
+}
+
+fn folder_header(folder: &LayerFolder) -> PsdRecord {
+    let section = if folder.collapsed {
+        SECTION_CLOSED_FOLDER
+    } else {
+        SECTION_OPEN_FOLDER
+    };
+    let mut record = PsdRecord::group_marker(&folder.name, !folder.visible, section);
+    record.opacity = (folder.opacity.clamp(0.0, 1.0) * 255.0).round() as u8;
+    record.blend_key = folder.blend_mode.map_or(*b"pass", blend_mode_key);
+    if let Some(mask) = &folder.mask {
+        let (channel, rect) = mask_channel(mask);
+        record.channels.push(channel);
+        record.mask_rect = Some(rect);
+        record.mask_disabled = !folder.mask_enabled;
+    }
+    record
+}
+
+/// PSD user-mask plane (reveal-white) covering the canvas.
+fn mask_channel(mask: &TiledImage) -> ((i16, Vec<u8>), [i32; 4]) {
+    let mask = mask.to_rgba_image();
+    let values = mask.pixels().map(|p| 255 - p[3]).collect();
+    (
+        (CHANNEL_MASK, values),
+        [0, 0, mask.height() as i32, mask.width() as i32],
+    )
+}
+
+fn layer_record(layer: &Layer) -> PsdRecord {
+    let image = layer.pixels.to_rgba_image();
+    // Crop to the painted bounds; fully transparent layers get an empty rect.
+    let mut bounds: Option<(u32, u32, u32, u32)> = None;
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if pixel[3] == 0 {
+            continue;
+        }
+        bounds = Some(match bounds {
+            Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x + 1), y1.max(y + 1)),
+            None => (x, y, x + 1, y + 1),
+        });
+    }
+    let (x0, y0, x1, y1) = bounds.unwrap_or((0, 0, 0, 0));
+    let mut channels: Vec<(i16, Vec<u8>)> = [CHANNEL_ALPHA, 0, 1, 2]
+        .into_iter()
+        .map(|id| (id, Vec::with_capacity(((x1 - x0) * (y1 - y0)) as usize)))
+        .collect();
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let pixel = image.get_pixel(x, y);
+            channels[0].1.push(pixel[3]);
+            channels[1].1.push(pixel[0]);
+            channels[2].1.push(pixel[1]);
+            channels[3].1.push(pixel[2]);
+        }
+    }
+
+    let mut mask_rect = None;
+    if let Some(mask) = &layer.mask {
+        let (channel, rect) = mask_channel(mask);
+        channels.push(channel);
+        mask_rect = Some(rect);
+    }
+
+    PsdRecord {
+        name: layer.name.clone(),
+        rect: [y0 as i32, x0 as i32, y1 as i32, x1 as i32],
+        channels,
+        mask_rect,
+        mask_disabled: !layer.mask_enabled,
+        blend_key: blend_mode_key(layer.blend_mode),
+        opacity: (layer.opacity.clamp(0.0, 1.0) * 255.0).round() as u8,
+        hidden: !layer.visible,
+        section: None,
+    }
+}
+
+/// Encode a snapshot as an 8-bit RGB PSD.
+pub fn encode_psd(data: &PsdData) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(b"8BPS");
+    put_u16(&mut out, 1);
+    out.extend_from_slice(&[0; 6]);
+    put_u16(&mut out, 4);
+    put_u32(&mut out, data.height);
+    put_u32(&mut out, data.width);
+    put_u16(&mut out, 8);
+    put_u16(&mut out, MODE_RGB);
+    put_u32(&mut out, 0); // color mode data
+    put_u32(&mut out, 0); // image resources
+
+    let layer_info = encode_layer_info(data);
+    let mut section = Vec::with_capacity(layer_info.len() + 8);
+    put_u32(&mut section, layer_info.len() as u32);
+    section.extend_from_slice(&layer_info);
+    put_u32(&mut section, 0); // global layer mask info
+    put_u32(&mut out, section.len() as u32);
+    out.extend_from_slice(&section);
+
+    // Merged composite: RLE, all row counts first, then all rows.
+    let (w, h) = (data.width as usize, data.height as usize);
+    let raw = data.composite.as_raw();
+    let mut counts = Vec::with_capacity(4 * h * 2);
+    let mut rows = Vec::new();
+    for channel in 0..4 {
+        let mut row = Vec::with_capacity(w);
+        for y in 0..h {
+            row.clear();
+            row.extend((0..w).map(|x| raw[(y * w + x) * 4 + channel]));
+            let packed = pack_bits(&row);
+            put_u16(&mut counts, packed.len() as u16);
+            rows.extend_from_slice(&packed);
+        }
+    }
+    put_u16(&mut out, 1);
+    out.extend_from_slice(&counts);
+    out.extend_from_slice(&rows);
+    out
+}
+
+fn encode_layer_info(data: &PsdData) -> Vec<u8> {
+    let mut info = Vec::new();
+    // Negative count: the merged image's alpha channel is its transparency.
+    put_u16(&mut info, (-(data.records.len() as i32)) as i16 as u16);
+
+    let mut channel_data: Vec<Vec<Vec<u8>>> = Vec::with_capacity(data.records.len());
+    for record in &data.records {
+        let encoded: Vec<Vec<u8>> = record
+            .channels
+            .iter()
+            .map(|(id, plane)| {
+                let rect = match (*id, record.mask_rect) {
+                    (CHANNEL_MASK, Some(rect)) => rect,
+                    _ => record.rect,
+                };
+                encode_channel(plane, rect)
+            })
+            .collect();
+
+        for value in record.rect {
+            put_u32(&mut info, value as u32);
+        }
+        put_u16(&mut info, record.channels.len() as u16);
+        for ((id, _), bytes) in record.channels.iter().zip(&encoded) {
+            put_u16(&mut info, *id as u16);
+            put_u32(&mut info, bytes.len() as u32);
+        }
+        info.extend_from_slice(b"8BIM");
+        info.extend_from_slice(&record.blend_key);
+        info.push(record.opacity);
+        info.push(0); // clipping
+        info.push(FLAG_BIT4_USEFUL | if record.hidden { FLAG_HIDDEN } else { 0 });
+        info.push(0);
+
+        let mut extra = Vec::new();
+        match record.mask_rect {
+            Some(rect) => {
+                put_u32(&mut extra, 20);
+                for value in rect {
+                    put_u32(&mut extra, value as u32);
+                }
+                extra.push(255); // default color: reveal outside the mask
+                extra.push(if record.mask_disabled { 0x02 } else { 0 });
+                extra.extend_from_slice(&[0, 0]);
+            }
+            None => put_u32(&mut extra, 0),
+        }
+        put_u32(&mut extra, 0); // blending ranges
+        put_pascal_name(&mut extra, &record.name);
+        put_unicode_name(&mut extra, &record.name);
+        if let Some(section) = record.section {
+            extra.extend_from_slice(b"8BIMlsct");
+            if section == SECTION_DIVIDER {
+                put_u32(&mut extra, 4);
+                put_u32(&mut extra, section);
+            } else {
+                put_u32(&mut extra, 12);
+                put_u32(&mut extra, section);
+                extra.extend_from_slice(b"8BIM");
+                extra.extend_from_slice(&record.blend_key);
+            }
+        }
+        put_u32(&mut info, extra.len() as u32);
+        info.extend_from_slice(&extra);
+        channel_data.push(encoded);
+    }
+
+    for encoded in channel_data {
+        for bytes in encoded {
+            info.extend_from_slice(&bytes);
+        }
+    }
+    if !info.len().is_multiple_of(2) {
+        info.push(0);
+    }
+    info
+}
+
+/// Compression tag + data for one layer channel. Empty planes are stored raw.
+fn encode_channel(plane: &[u8], rect: [i32; 4]) -> Vec<u8> {
+    let (w, h) = rect_size(rect).unwrap_or((0, 0));
+    let (w, h) = (w as usize, h as usize);
+    let mut out = Vec::new();
+    if w == 0 || h == 0 || plane.len() < w * h {
+        put_u16(&mut out, 0);
+        return out;
+    }
+    put_u16(&mut out, 1);
+    let rows: Vec<Vec<u8>> = plane.chunks_exact(w).take(h).map(pack_bits).collect();
+    for row in &rows {
+        put_u16(&mut out, row.len() as u16);
+    }
+    for row in rows {
+        out.extend_from_slice(&row);
+    }
+    out
+}
+
+/// PackBits encoder for one row.
+fn pack_bits(row: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(row.len() + row.len() / 128 + 1);
+    let mut i = 0;
+    while i < row.len() {
+        let mut run = 1;
+        while i + run < row.len() && run < 128 && row[i + run] == row[i] {
+            run += 1;
+        }
+        if run >= 2 {
+            out.push((257 - run) as u8);
+            out.push(row[i]);
+            i += run;
+            continue;
+        }
+        let start = i;
+        while i < row.len() && i - start < 128 {
+            if i + 1 < row.len() && row[i] == row[i + 1] {
+                break;
+            }
+            i += 1;
+        }
+        out.push((i - start - 1) as u8);
+        out.extend_from_slice(&row[start..i]);
+    }
+    out
+}
+
+/// Legacy Pascal name (padded to 4 bytes); `luni` carries the real name.
+fn put_pascal_name(out: &mut Vec<u8>, name: &str) {
+    let bytes: Vec<u8> = name
+        .chars()
+        .map(|c| if c.is_ascii() { c as u8 } else { b'?' })
+        .take(255)
+        .collect();
+    out.push(bytes.len() as u8);
+    out.extend_from_slice(&bytes);
+    let padding = (4 - (1 + bytes.len()) % 4) % 4;
+    out.extend(std::iter::repeat_n(0, padding));
+}
+
+fn put_unicode_name(out: &mut Vec<u8>, name: &str) {
+    let units: Vec<u16> = name.encode_utf16().collect();
+    let mut block = Vec::with_capacity(4 + units.len() * 2);
+    put_u32(&mut block, units.len() as u32);
+    for unit in units {
+        put_u16(&mut block, unit);
+    }
+    while !block.len().is_multiple_of(4) {
+        block.push(0);
+    }
+    out.extend_from_slice(b"8BIMluni");
+    put_u32(out, block.len() as u32);
+    out.extend_from_slice(&block);
+}
+
+fn put_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+// ----------------------------------------------------------------------------
+// Photoshop action descriptor reader
+// ----------------------------------------------------------------------------
+
+/// Descriptors nest objects inside lists inside objects; cap the depth so a
+/// corrupt file can't recurse without bound.
+const MAX_DESCRIPTOR_DEPTH: usize = 32;
+
+#[derive(Debug)]
+pub(crate) enum Value {
+    Object(Descriptor),
+    List(Vec<Value>),
+    Number(f64),
+    Text(String),
+    Enum(String),
+    Bool(bool),
+    Other,
+}
+
+#[derive(Debug)]
+pub(crate) struct Descriptor {
+    pub(crate) class: String,
+    pub(crate) items: Vec<(String, Value)>,
+}
+
+impl Descriptor {
+    pub(crate) fn get(&self, key: &str) -> Option<&Value> {
+        self.items.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub(crate) fn number(&self, key: &str) -> Option<f64> {
+        match self.get(key)? {
+            Value::Number(n) => Some(*n),
+            Value::Bool(b) => Some(*b as u8 as f64),
+            _ => None,
+        }
+    }
+}
+
+/// Big-endian cursor over Photoshop file bytes.
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub(crate) fn bytes(&mut self, n: usize) -> Result<&'a [u8], String> {
+        if self.data.len().saturating_sub(self.pos) < n {
+            return Err("Unexpected end of Photoshop data".to_string());
+        }
+        let out = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(out)
+    }
+
+    pub(crate) fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.pos)
+    }
+
+    pub(crate) fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Move to `pos`, clamped to the end of the data.
+    pub(crate) fn seek(&mut self, pos: usize) {
+        self.pos = pos.min(self.data.len());
+    }
+
+    pub(crate) fn skip(&mut self, n: usize) -> Result<(), String> {
+        self.bytes(n).map(|_| ())
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, String> {
+        let b = self.bytes(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, String> {
+        let b = self.bytes(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub(crate) fn i32(&mut self) -> Result<i32, String> {
+        Ok(self.u32()? as i32)
+    }
+
+    /// Section length: u32 in PSD, u64 in PSB.
+    pub(crate) fn length(&mut self, psb: bool) -> Result<usize, String> {
+        let len = if psb {
+            let b = self.bytes(8)?;
+            u64::from_be_bytes(b.try_into().unwrap_or([0; 8]))
+        } else {
+            self.u32()? as u64
+        };
+        usize::try_from(len).map_err(|_| "Photoshop section too large".to_string())
+    }
+
+    pub(crate) fn f64(&mut self) -> Result<f64, String> {
+        let b = self.bytes(8)?;
+        Ok(f64::from_be_bytes(b.try_into().unwrap_or([0; 8])))
+    }
+
+    /// Unicode string: u32 length in UTF-16 units, then the units.
+    pub(crate) fn ucs2_string(&mut self) -> Result<String, String> {
+        let len = self.u32()? as usize;
+        let raw = self.bytes(len.saturating_mul(2))?;
+        let units: Vec<u16> = raw
+            .chunks_exact(2)
+            .map(|c| u16::from_be_bytes([c[0], c[1]]))
+            .take_while(|&u| u != 0)
+            .collect();
+        Ok(String::from_utf16_lossy(&units))
+    }
+
+    /// Key or class ID: u32 length, then that many bytes (4 when the length is 0).
+    pub(crate) fn id(&mut self) -> Result<String, String> {
+        let len = self.u32()? as usize;
+        let raw = self.bytes(if len == 0 { 4 } else { len })?;
+        Ok(String::from_utf8_lossy(raw).trim_end().to_string())
+    }
+
+    pub(crate) fn descriptor(&mut self, depth: usize) -> Result<Descriptor, String> {
+        if depth > MAX_DESCRIPTOR_DEPTH {
+            return Err("Photoshop descriptor nested too deeply".to_string());
+        }
+        let _name = self.ucs2_string()?;
+        let class = self.id()?;
+        let count = self.u32()?;
+        let mut items = Vec::new();
+        for _ in 0..count {
+            let key = self.id()?;
+            let value = self.value(depth)?;
+            items.push((key, value));
+        }
+        Ok(Descriptor { class, items })
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, String> {
+        let ty: [u8; 4] = self.bytes(4)?.try_into().unwrap_or([0; 4]);
+        Ok(match &ty {
+            b"Objc" | b"GlbO" => Value::Object(self.descriptor(depth + 1)?),
+            b"VlLs" => {
+                let count = self.u32()?;
+                let mut items = Vec::new();
+                for _ in 0..count {
+                    items.push(self.value(depth + 1)?);
+                }
+                Value::List(items)
+            }
+            b"doub" => Value::Number(self.f64()?),
+            b"UntF" => {
+                let _unit = self.bytes(4)?;
+                Value::Number(self.f64()?)
+            }
+            b"long" => Value::Number(self.u32()? as i32 as f64),
+            b"comp" => {
+                let hi = self.u32()? as u64;
+                let lo = self.u32()? as u64;
+                Value::Number(((hi << 32) | lo) as i64 as f64)
+            }
+            b"bool" => Value::Bool(self.bytes(1)?[0] != 0),
+            b"TEXT" => Value::Text(self.ucs2_string()?),
+            b"enum" => {
+                let _type = self.id()?;
+                Value::Enum(self.id()?)
+            }
+            b"type" | b"GlbC" => {
+                let _name = self.ucs2_string()?;
+                let _class = self.id()?;
+                Value::Other
+            }
+            b"tdta" | b"alis" => {
+                let len = self.u32()? as usize;
+                self.bytes(len)?;
+                Value::Other
+            }
+            _ => {
+                return Err(format!(
+                    "Unsupported descriptor type '{}'",
+                    String::from_utf8_lossy(&ty)
+                ));
+            }
+        })
+    }
+}
@@ -0,0 +1,374 @@
+//! SVG import.
+//!
+//! Documents are parsed by usvg and rasterized by resvg. Text and embedded
+//! raster images are not rendered.
+//!
+//! With [`SvgOptions::split_groups`] each top-level `<g>` renders to its own
+//! layer named after its `id`. Loose top-level shapes between groups are
+//! collected into layers of their own. Each layer is rendered from a copy of
+//! the source in which every other top-level element is wrapped in
+//! `<g display="none">`, so gradients and `use` targets defined inside them
+//! still resolve.
+
+use std::ops::Range;
+use std::path::Path;
+
+use image::{Rgba, RgbaImage};
+use resvg::{tiny_skia as sk, usvg};
+
+use crate::canvas::{CanvasState, Layer, TiledImage};
+
+/// Resolution of SVG user units (CSS pixels).
+pub const SVG_BASE_DPI: f32 = 96.0;
+
+const INKSCAPE_NS: &str = "http://www.inkscape.org/namespaces/inkscape";
+
+/// Root children that draw something and so belong to a layer.
+const DRAWABLE_TAGS: &[&str] = &[
+    "path", "rect", "circle", "ellipse", "line", "polyline", "polygon", "g", "a", "switch", "use",
+    "svg", "text", "image",
+];
+
+/// How an SVG is rasterized when opened or imported.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgOptions {
+    /// The document's intrinsic size is scaled by `dpi / 96`.
+    pub dpi: f32,
+    /// Explicit output width; with only one of `width`/`height` set the other
+    /// follows the aspect ratio. Either overrides `dpi`.
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Render each top-level `<g>` to its own layer.
+    pub split_groups: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            dpi: SVG_BASE_DPI,
+            width: None,
+            height: None,
+            split_groups: true,
+        }
+    }
+}
+
+/// One rendered layer of an SVG document.
+pub struct SvgLayer {
+    pub name: String,
+    pub image: RgbaImage,
+    /// False for top-level groups hidden with `display: none`.
+    pub visible: bool,
+}
+
+/// A parsed SVG document, ready to render at any size.
+pub struct SvgDocument {
+    /// Decompressed source text.
+    source: String,
+    /// Intrinsic size in CSS pixels.
+    width: f64,
+    height: f64,
+    /// Drawable children of the root element, in document order.
+    elements: Vec<TopLevel>,
+}
+
+/// A drawable child of the root `<svg>` element.
+struct TopLevel {
+    /// Byte range of the whole element in the source.
+    range: Range<usize>,
+    is_group: bool,
+    /// The `id` or Inkscape label a group's layer is named after.
+    name: Option<String>,
+    /// For an element hidden with an inline `display: none`: its start tag
+    /// rewritten without it, and where the original start tag ends.
+    shown_start_tag: Option<(String, usize)>,
+}
+
+// ============================================================================
+// ENTRY POINTS
+// ============================================================================
+
+/// Read and rasterize an `.svg` file into a layered canvas named after the file.
+pub fn load_svg(path: &Path, options: &SvgOptions) -> Result<CanvasState, String> {
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    let name = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("Background");
+    parse_svg(&data, name, options)
+}
+
+/// Rasterize in-memory SVG bytes. A document rendered to one layer names it `name`.
+pub fn parse_svg(data: &[u8], name: &str, options: &SvgOptions) -> Result<CanvasState, String> {
+    let document = SvgDocument::parse(data)?;
+    document.to_canvas(options, name)
+}
+
+impl SvgDocument {
+    pub fn parse(data: &[u8]) -> Result<Self, String> {
+        let data = if data.starts_with(&[0x1f, 0x8b]) {
+            usvg::decompress_svgz(data).map_err(|e| format!("Invalid SVGZ: {}", e))?
+        } else {
+            data.to_vec()
+        };
+        let source = String::from_utf8(data).map_err(|_| "SVG is not valid UTF-8".to_string())?;
+
+        let xml = roxmltree::Document::parse_with_options(
+            &source,
+            roxmltree::ParsingOptions {
+                allow_dtd: true,
+                ..Default::default()
+            },
+        )
+        .map_err(|e| format!("Invalid SVG: {}", e))?;
+        let root = xml.root_element();
+        if root.tag_name().name() != "svg" {
+            return Err("Not an SVG document".to_string());
+        }
+        let elements = root
+            .children()
+            .filter(|n| n.is_element() && DRAWABLE_TAGS.contains(&n.tag_name().name()))
+            .map(|node| TopLevel {
+                range: node.range(),
+                is_group: node.tag_name().name() == "g",
+                name: node
+                    .attribute("id")
+                    .or_else(|| node.attribute((INKSCAPE_NS, "label")))
+                    .map(str::to_string),
+                shown_start_tag: shown_start_tag(&source, node),
+            })
+            .collect();
+
+        let size = load_tree(&source)?.size();
+        Ok(Self {
+            width: size.width() as f64,
+            height: size.height() as f64,
+            elements,
+            source,
+        })
+    }
+
+    /// Intrinsic size in CSS pixels (the size at 96 DPI).
+    pub fn intrinsic_size(&self) -> (f64, f64) {
+        (self.width, self.height)
+    }
+
+    /// Pixel size `options` render at.
+    pub fn target_size(&self, options: &SvgOptions) -> (u32, u32) {
+        let aspect = self.width / self.height;
+        let (w, h) = match (options.width, options.height) {
+            (Some(w), Some(h)) => (w as f64, h as f64),
+            (Some(w), None) => (w as f64, w as f64 / aspect),
+            (None, Some(h)) => (h as f64 * aspect, h as f64),
+            (None, None) => {
+                let scale = (options.dpi / SVG_BASE_DPI).max(0.01) as f64;
+                (self.width * scale, self.height * scale)
+            }
+        };
+        ((w.round() as u32).max(1), (h.round() as u32).max(1))
+    }
+
+    /// Number of top-level `<g>` elements (the layers `split_groups` yields).
+    pub fn group_count(&self) -> usize {
+        self.elements.iter().filter(|e| e.is_group).count()
+    }
+
+    /// Render to layers, bottom first.
+    pub fn render_layers(&self, options: &SvgOptions) -> Result<Vec<SvgLayer>, String> {
+        let (w, h) = self.target_size(options);
+        crate::io::validate_open_dimensions(w, h)?;
+
+        if !options.split_groups {
+            return Ok(vec![SvgLayer {
+                name: String::new(),
+                image: render(&self.source, w, h)?,
+                visible: true,
+            }]);
+        }
+
+        let mut layers = Vec::new();
+        let mut loose: Vec<usize> = Vec::new();
+        let flush = |loose: &mut Vec<usize>, layers: &mut Vec<SvgLayer>| -> Result<(), String> {
+            if !loose.is_empty() {
+                layers.push(SvgLayer {
+                    name: format!("Layer {}", layers.len() + 1),
+                    image: render(&self.isolate(loose), w, h)?,
+                    visible: true,
+                });
+            }
+            loose.clear();
+            Ok(())
+        };
+        let mut group_index = 0;
+        for (index, element) in self.elements.iter().enumerate() {
+            if !element.is_group {
+                loose.push(index);
+                continue;
+            }
+            flush(&mut loose, &mut layers)?;
+            group_index += 1;
+            // Hidden groups still render, onto a hidden layer.
+            layers.push(SvgLayer {
+                name: element
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("Group {}", group_index)),
+                image: render(&self.isolate(&[index]), w, h)?,
+                visible: element.shown_start_tag.is_none(),
+            });
+        }
+        flush(&mut loose, &mut layers)?;
+        if layers.is_empty() {
+            layers.push(SvgLayer {
+                name: String::new(),
+                image: RgbaImage::new(w, h),
+                visible: true,
+            });
+        }
+        Ok(layers)
+    }
+
+    /// Render into a new canvas; a single unnamed layer is called `name`.
+    pub fn to_canvas(&self, options: &SvgOptions, name: &str) -> Result<CanvasState, String> {
+        let layers = self.render_layers(options)?;
+        let (w, h) = self.target_size(options);
+        let mut state = CanvasState::new(w, h);
+        state.layers.clear();
+        for svg_layer in layers {
+            let layer_name = if svg_layer.name.is_empty() {
+                name.to_string()
+            } else {
+                svg_layer.name
+            };
+            let mut layer = Layer::new(layer_name, w, h, Rgba([0, 0, 0, 0]));
+            layer.pixels = TiledImage::from_rgba_image(&svg_layer.image);
+            layer.visible = svg_layer.visible;
+            state.layers.push(layer);
+        }
+        state.active_layer_index = state.layers.len() - 1;
+        state.composite_cache = None;
+        Ok(state)
+    }
+
+    /// The source with every top-level element except `keep` hidden, and
+    /// the kept ones shown even if they were hidden.
+    fn isolate(&self, keep: &[usize]) -> String {
+        let mut out = String::with_capacity(self.source.len() + self.elements.len() * 24);
+        let mut at = 0;
+        for (index, element) in self.elements.iter().enumerate() {
+            out.push_str(&self.source[at..element.range.start]);
+            let text = &self.source[element.range.clone()];
+            if !keep.contains(&index) {
+                out.push_str("<g display=\"none\">");
+                out.push_str(text);
+                out.push_str("</g>");
+            } else if let Some((tag, end)) = &element.shown_start_tag {
+                out.push_str(tag);
+                out.push_str(&self.source[*end..element.range.end]);
+            } else {
+                out.push_str(text);
+            }
+            at = element.range.end;
+        }
+        out.push_str(&self.source[at..]);
+        out
+    }
+}
+
+// ============================================================================
+// RENDERING
+// ============================================================================
+
+fn load_tree(source: &str) -> Result<usvg::Tree, String> {
+    let mut options = usvg::Options::default();
+    // The CSS default for a replaced element without a size.
+    if let Some(size) = usvg::Size::from_wh(300.0, 150.0) {
+        options.default_size = size;
+    }
+    usvg::Tree::from_str(source, &options).map_err(|e| format!("Invalid SVG: {}", e))
+}
+
+/// Rasterize `source` to `w`×`h` pixels, stretching its intrinsic size.
+fn render(source: &str, w: u32, h: u32) -> Result<RgbaImage, String> {
+    let tree = load_tree(source)?;
+    let mut pixmap = sk::Pixmap::new(w, h).ok_or("SVG size is too large".to_string())?;
+    let size = tree.size();
+    let ts = sk::Transform::from_scale(w as f32 / size.width(), h as f32 / size.height());
+    resvg::render(&tree, ts, &mut pixmap.as_mut());
+    Ok(pixmap_to_rgba(&pixmap))
+}
+
+fn pixmap_to_rgba(pixmap: &sk::Pixmap) -> RgbaImage {
+    let mut image = RgbaImage::new(pixmap.width(), pixmap.height());
+    for (out, px) in image.pixels_mut().zip(pixmap.pixels()) {
+        let c = px.demultiply();
+        *out = Rgba([c.red(), c.green(), c.blue(), c.alpha()]);
+    }
+    image
+}
+
+/// For an element hidden by its own `display` attribute or `style`, a start
+/// tag without that declaration and the byte offset the original one ends at.
+fn shown_start_tag(source: &str, node: roxmltree::Node) -> Option<(String, usize)> {
+    let is_display_none =
+        |name: &str, value: &str| name.trim() == "display" && value.trim() == "none";
+    let style = node.attribute("style").unwrap_or("");
+    let hidden_by_style = style
+        .split(';')
+        .filter_map(|d| d.split_once(':'))
+        .any(|(name, value)| is_display_none(name, value));
+    if !hidden_by_style && node.attribute("display").is_none_or(|v| v.trim() != "none") {
+        return None;
+    }
+
+    let start = node.range().start;
+    let mut quote = None;
+    let mut end = node.range().end;
+    for (i, c) in source[start..node.range().end].char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => {
+                end = start + i + 1;
+                break;
+            }
+            _ => {}
+        }
+    }
+    let original = &source[start..end];
+    // Keep the prefix the document spelled the element with.
+    let qualified: String = original[1..]
+        .chars()
+        .take_while(|c| !c.is_whitespace() && *c != '>' && *c != '/')
+        .collect();
+
+    let mut tag = format!("<{}", qualified);
+    for attr in node.attributes() {
+        let value = match (attr.namespace(), attr.name()) {
+            (None, "display") => continue,
+            (None, "style") => style
+                .split(';')
+                .filter(|d| {
+                    !d.split_once(':')
+                        .is_some_and(|(n, v)| is_display_none(n, v))
+                })
+                .collect::<Vec<_>>()
+                .join(";"),
+            _ => attr.value().to_string(),
+        };
+        let name = match attr.namespace() {
+            None => attr.name().to_string(),
+            Some("http://www.w3.org/1999/xlink") => format!("xlink:{}", attr.name()),
+            Some("http://www.w3.org/XML/1998/namespace") => format!("xml:{}", attr.name()),
+            // Editor metadata does not affect rendering.
+            Some(_) => continue,
+        };
+        let value = value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('"', "&quot;");
+        tag.push_str(&format!(" {}=\"{}\"", name, value));
+    }
+    tag.push_str(if original.ends_with("/>") { "/>" } else { ">" });
+    Some((tag, end))
+}
@@ -1183,6 +1183,7 @@ pub struct WindowVisibility {
     pub history: bool,
     pub colors: bool,
     pub palette: bool,
+    pub layer_comps: bool,
     pub script_editor: bool,
 }
 
@@ -1194,6 +1195,7 @@ impl WindowVisibility {
             history: false,       // History hidden by default
             colors: false,        // Colors hidden by default (toggle from swatch)
             palette: false,       // Palette hidden by default
+            layer_comps: false,   // Layer comps hidden by default
             script_editor: false, // Script editor hidden by default
         }
     }
@@ -0,0 +1,582 @@
+//! Multi-page TIFF import and export.
+//!
+//! Every page of a TIFF comes in as a layer, the first page on top, named
+//! from its `PageName` tag. Reduced-resolution pages (thumbnails) are
+//! skipped. 16-bit pages keep their samples in the layer's deep buffer and
+//! float pages are loaded like other float images (see [`crate::hdr_io`]).
+//! Gray, RGB, CMYK and JPEG (YCbCr) pages are understood, and an alpha extra
+//! sample is read as straight or premultiplied according to `ExtraSamples`.
+//!
+//! Export writes either one flattened page or, with "layers as pages", one
+//! page per layer in the same order. Each page keeps its layer's 16-bit or
+//! float payload unless JPEG compression is picked, which stores 8-bit RGB.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+use std::io::{BufWriter, Seek, Write};
+use std::path::Path;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageBuffer, Rgba, RgbaImage};
+use tiff::ColorType;
+use tiff::decoder::{Decoder, DecodingResult, Limits};
+use tiff::encoder::colortype::{self, ColorType as EncodeColor};
+use tiff::encoder::compression::{Compression, Deflate, Lzw, Packbits, Uncompressed};
+use tiff::encoder::{DirectoryEncoder, TiffEncoder, TiffKindStandard, TiffValue};
+use tiff::tags::Tag;
+
+use crate::canvas::{CanvasState, Layer, LayerContent, TiledImage};
+use crate::components::dialogs::TiffCompression;
+use crate::experimental::{DeepRgbaBuffer, f16_bits_to_f32};
+#[cfg(target_arch = "wasm32")]
+use crate::web_fs::File;
+
+/// `PageName` (285) and `PageNumber` (297) have no named variant in `tiff`.
+const PAGE_NAME: Tag = Tag::Unknown(285);
+const PAGE_NUMBER: Tag = Tag::Unknown(297);
+const YCBCR_SUBSAMPLING: Tag = Tag::Unknown(530);
+
+/// RGBA samples for one page at its native depth.
+pub enum TiffSamples {
+    U8(Vec<u8>),
+    U16(Vec<u16>),
+    F32(Vec<f32>),
+}
+
+/// Borrowed RGBA samples, for writing a composite without copying it.
+#[derive(Clone, Copy)]
+pub enum TiffPixels<'a> {
+    U8(&'a [u8]),
+    U16(&'a [u16]),
+    F32(&'a [f32]),
+}
+
+impl TiffSamples {
+    fn as_pixels(&self) -> TiffPixels<'_> {
+        match self {
+            Self::U8(v) => TiffPixels::U8(v),
+            Self::U16(v) => TiffPixels::U16(v),
+            Self::F32(v) => TiffPixels::F32(v),
+        }
+    }
+}
+
+/// Snapshot of a canvas ready to write as a multi-page TIFF.
+pub struct TiffData {
+    width: u32,
+    height: u32,
+    /// `(page name, samples)`, top layer first.
+    pages: Vec<(String, TiffSamples)>,
+    compression: TiffCompression,
+    quality: u8,
+}
+
+// ============================================================================
+// IMPORT
+// ============================================================================
+
+/// Read every page of a TIFF; a lone page without a `PageName` is called `name`.
+pub fn load_tiff(path: &Path, name: &str) -> Result<CanvasState, String> {
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    parse_tiff(&data, name)
+}
+
+/// How an alpha extra sample relates to the colour samples.
+#[derive(Clone, Copy, PartialEq)]
+enum Alpha {
+    Straight,
+    Premultiplied,
+    /// An extra sample that isn't alpha (`ExtraSamples` = 0) — dropped.
+    Ignored,
+}
+
+pub fn parse_tiff(data: &[u8], name: &str) -> Result<CanvasState, String> {
+    let pages = match read_pages(data) {
+        Ok(pages) if !pages.is_empty() => pages,
+        // Layouts the page reader doesn't handle (palette, 1-bit, …) still
+        // open as a single flattened page through `image`.
+        result => {
+            let fallback = image::load_from_memory_with_format(data, image::ImageFormat::Tiff);
+            match (fallback, result) {
+                (Ok(img), _) => vec![(None, img)],
+                (Err(_), Err(e)) => return Err(e),
+                (Err(e), Ok(_)) => return Err(e.to_string()),
+            }
+        }
+    };
+
+    let width = pages.iter().map(|(_, p)| p.width()).max().unwrap_or(1);
+    let height = pages.iter().map(|(_, p)| p.height()).max().unwrap_or(1);
+    crate::io::validate_open_dimensions(width, height)?;
+
+    let single = pages.len() == 1;
+    let mut state = CanvasState::new(width, height);
+    state.layers.clear();
+    // Pages are stored top-first; layers are bottom-first.
+    for (index, (page_name, page)) in pages.into_iter().enumerate().rev() {
+        let name = page_name.unwrap_or_else(|| {
+            if single {
+                name.to_string()
+            } else {
+                format!("Page {}", index + 1)
+            }
+        });
+        state
+            .layers
+            .push(page_layer(name, pad_to(page, width, height)));
+    }
+    state.active_layer_index = state.layers.len() - 1;
+    state.composite_cache = None;
+    Ok(state)
+}
+
+fn read_pages(data: &[u8]) -> Result<Vec<(Option<String>, DynamicImage)>, String> {
+    let err = |e: tiff::TiffError| format!("Invalid TIFF file: {}", e);
+    let mut decoder = Decoder::new(std::io::Cursor::new(data))
+        .map_err(err)?
+        .with_limits(Limits::unlimited());
+    let mut pages = Vec::new();
+    loop {
+        let subfile = decoder
+            .find_tag_unsigned::<u32>(Tag::NewSubfileType)
+            .map_err(err)?
+            .unwrap_or(0);
+        if subfile & 1 == 0 {
+            let (w, h) = decoder.dimensions().map_err(err)?;
+            crate::io::validate_open_dimensions(w, h)?;
+            let name = decoder
+                .get_tag_ascii_string(PAGE_NAME)
+                .ok()
+                .map(|s| s.trim_end_matches('\0').to_string())
+                .filter(|s| !s.is_empty());
+            let alpha = match decoder
+                .find_tag_unsigned_vec::<u16>(Tag::ExtraSamples)
+                .ok()
+                .flatten()
+                .and_then(|v| v.first().copied())
+            {
+                Some(0) => Alpha::Ignored,
+                Some(1) => Alpha::Premultiplied,
+                _ => Alpha::Straight,
+            };
+            let color = decoder.colortype().map_err(err)?;
+            let samples = decoder.read_image().map_err(err)?;
+            pages.push((name, page_image(w, h, color, alpha, samples)?));
+        }
+        if !decoder.more_images() {
+            break;
+        }
+        decoder.next_image().map_err(err)?;
+    }
+    Ok(pages)
+}
+
+/// A sample type pages are expanded into.
+trait Sample: Copy {
+    const MAX: Self;
+    fn to_unit(self) -> f32;
+    fn from_unit(v: f32) -> Self;
+}
+
+impl Sample for u8 {
+    const MAX: Self = u8::MAX;
+    fn to_unit(self) -> f32 {
+        self as f32 / 255.0
+    }
+    fn from_unit(v: f32) -> Self {
+        (v.clamp(0.0, 1.0) * 255.0).round() as u8
+    }
+}
+
+impl Sample for u16 {
+    const MAX: Self = u16::MAX;
+    fn to_unit(self) -> f32 {
+        self as f32 / 65535.0
+    }
+    fn from_unit(v: f32) -> Self {
+        (v.clamp(0.0, 1.0) * 65535.0).round() as u16
+    }
+}
+
+impl Sample for f32 {
+    const MAX: Self = 1.0;
+    fn to_unit(self) -> f32 {
+        self
+    }
+    fn from_unit(v: f32) -> Self {
+        v
+    }
+}
+
+/// Expand interleaved page samples to RGBA.
+fn expand<T: Sample>(src: &[T], color: ColorType, alpha: Alpha) -> Result<Vec<T>, String> {
+    let (channels, has_alpha) = match color {
+        ColorType::Gray(_) => (1, false),
+        ColorType::GrayA(_) => (2, true),
+        // JPEG pages arrive already converted from YCbCr.
+        ColorType::RGB(_) | ColorType::YCbCr(_) => (3, false),
+        ColorType::RGBA(_) => (4, true),
+        ColorType::CMYK(_) => (4, false),
+        other => return Err(format!("Unsupported TIFF color type {:?}", other)),
+    };
+    let mut out = Vec::with_capacity(src.len() / channels * 4);
+    for px in src.chunks_exact(channels) {
+        let mut rgba = match color {
+            ColorType::Gray(_) | ColorType::GrayA(_) => [px[0], px[0], px[0], T::MAX],
+            ColorType::CMYK(_) => {
+                let k = 1.0 - px[3].to_unit();
+                let ink = |c: T| T::from_unit((1.0 - c.to_unit()) * k);
+                [ink(px[0]), ink(px[1]), ink(px[2]), T::MAX]
+            }
+            _ => [px[0], px[1], px[2], T::MAX],
+        };
+        if has_alpha && alpha != Alpha::Ignored {
+            let a = px[channels - 1];
+            rgba[3] = a;
+            if alpha == Alpha::Premultiplied && a.to_unit() > 0.0 {
+                for c in &mut rgba[..3] {
+                    *c = T::from_unit(c.to_unit() / a.to_unit());
+                }
+            }
+        }
+        out.extend_from_slice(&rgba);
+    }
+    Ok(out)
+}
+
+fn page_image(
+    w: u32,
+    h: u32,
+    color: ColorType,
+    alpha: Alpha,
+    samples: DecodingResult,
+) -> Result<DynamicImage, String> {
+    let bad = || "TIFF page size mismatch".to_string();
+    Ok(match samples {
+        DecodingResult::U8(v) => DynamicImage::ImageRgba8(
+            RgbaImage::from_raw(w, h, expand(&v, color, alpha)?).ok_or_else(bad)?,
+        ),
+        DecodingResult::U16(v) => DynamicImage::ImageRgba16(
+            ImageBuffer::from_raw(w, h, expand(&v, color, alpha)?).ok_or_else(bad)?,
+        ),
+        DecodingResult::U32(v) => {
+            let v: Vec<u16> = v.iter().map(|&s| (s >> 16) as u16).collect();
+            DynamicImage::ImageRgba16(
+                ImageBuffer::from_raw(w, h, expand(&v, color, alpha)?).ok_or_else(bad)?,
+            )
+        }
+        DecodingResult::F32(v) => DynamicImage::ImageRgba32F(
+            ImageBuffer::from_raw(w, h, expand(&v, color, alpha)?).ok_or_else(bad)?,
+        ),
+        DecodingResult::F64(v) => {
+            let v: Vec<f32> = v.iter().map(|&s| s as f32).collect();
+            DynamicImage::ImageRgba32F(
+                ImageBuffer::from_raw(w, h, expand(&v, color, alpha)?).ok_or_else(bad)?,
+            )
+        }
+        _ => return Err("Unsupported TIFF sample format".to_string()),
+    })
+}
+
+/// Grow a smaller page to the canvas size, anchored top-left.
+fn pad_to(page: DynamicImage, width: u32, height: u32) -> DynamicImage {
+    if page.width() == width && page.height() == height {
+        return page;
+    }
+    let mut canvas = match &page {
+        DynamicImage::ImageRgba16(_) => DynamicImage::new_rgba16(width, height),
+        DynamicImage::ImageRgba32F(_) => {
+            DynamicImage::new(width, height, image::ColorType::Rgba32F)
+        }
+        _ => DynamicImage::new_rgba8(width, height),
+    };
+    image::imageops::replace(&mut canvas, &page, 0, 0);
+    canvas
+}
+
+fn page_layer(name: String, page: DynamicImage) -> Layer {
+    let (width, height) = (page.width(), page.height());
+    if let DynamicImage::ImageRgba32F(buf) = page {
+        return crate::hdr_io::float_layer(
+            name,
+            width,
+            height,
+            DeepRgbaBuffer::F32(buf.into_raw()),
+            "tiff",
+        );
+    }
+    let (rgba, pixel_format, deep_pixels, hdr_metadata) =
+        crate::io::dynamic_image_to_rgba_and_deep(page);
+    let mut layer = Layer::new(name, width, height, Rgba([0, 0, 0, 0]));
+    layer.pixels = TiledImage::from_rgba_image(&rgba);
+    layer.pixel_format = pixel_format;
+    layer.deep_pixels = deep_pixels;
+    layer.hdr_metadata = hdr_metadata;
+    layer.source_metadata.source_format = Some("tiff".to_string());
+    layer
+}
+
+// ============================================================================
+// EXPORT
+// ============================================================================
+
+/// Snapshot each raster/text layer of `state` as a page, top layer first.
+pub fn build_tiff(state: &CanvasState, compression: TiffCompression, quality: u8) -> TiffData {
+    let pages = state
+        .layers
+        .iter()
+        .rev()
+        .filter(|l| matches!(l.content, LayerContent::Raster | LayerContent::Text(_)))
+        .map(|layer| {
+            (
+                layer.name.clone(),
+                layer_samples(layer, state.width, state.height),
+            )
+        })
+        .collect();
+    TiffData {
+        width: state.width,
+        height: state.height,
+        pages,
+        compression,
+        quality,
+    }
+}
+
+/// A layer's samples at its deepest up-to-date precision, mask folded into alpha.
+fn layer_samples(layer: &Layer, width: u32, height: u32) -> TiffSamples {
+    let preview = layer.pixels.to_rgba_image();
+    let view = layer.hdr_metadata.payload_view();
+    let deep = layer
+        .deep_pixels
+        .as_ref()
+        .filter(|deep| view.preview(deep, width, height).as_ref() == Some(&preview));
+    let mut samples = match deep {
+        Some(DeepRgbaBuffer::U16(values)) => TiffSamples::U16(values.clone()),
+        Some(DeepRgbaBuffer::F16(values)) => {
+            TiffSamples::F32(values.iter().map(|&v| f16_bits_to_f32(v)).collect())
+        }
+        Some(DeepRgbaBuffer::F32(values)) => TiffSamples::F32(values.clone()),
+        _ => TiffSamples::U8(preview.into_raw()),
+    };
+    if layer.mask.is_some() && layer.mask_enabled {
+        let reveal = |i: usize| {
+            let x = (i as u32) % width;
+            let y = (i as u32) / width;
+            layer.apply_mask_alpha_at(x, y, 255) as f32 / 255.0
+        };
+        match &mut samples {
+            TiffSamples::U8(values) => fold_mask(values, reveal),
+            TiffSamples::U16(values) => fold_mask(values, reveal),
+            TiffSamples::F32(values) => fold_mask(values, reveal),
+        }
+    }
+    samples
+}
+
+fn fold_mask<T: Sample>(values: &mut [T], reveal: impl Fn(usize) -> f32) {
+    for (i, px) in values.chunks_exact_mut(4).enumerate() {
+        px[3] = T::from_unit(px[3].to_unit() * reveal(i));
+    }
+}
+
+pub fn write_tiff(data: &TiffData, path: &Path) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let pages: Vec<(Option<&str>, TiffPixels)> = data
+        .pages
+        .iter()
+        .map(|(name, samples)| (Some(name.as_str()), samples.as_pixels()))
+        .collect();
+    encode_pages(
+        &mut writer,
+        data.width,
+        data.height,
+        &pages,
+        data.compression,
+        data.quality,
+    )?;
+    writer.flush()
+}
+
+/// Write a single unnamed page.
+pub fn encode_flat<W: Write + Seek>(
+    writer: W,
+    width: u32,
+    height: u32,
+    pixels: TiffPixels,
+    compression: TiffCompression,
+    quality: u8,
+) -> std::io::Result<()> {
+    encode_pages(
+        writer,
+        width,
+        height,
+        &[(None, pixels)],
+        compression,
+        quality,
+    )
+}
+
+fn encode_pages<W: Write + Seek>(
+    writer: W,
+    width: u32,
+    height: u32,
+    pages: &[(Option<&str>, TiffPixels)],
+    compression: TiffCompression,
+    quality: u8,
+) -> std::io::Result<()> {
+    let err = |e: tiff::TiffError| std::io::Error::other(format!("TIFF encode error: {}", e));
+    let mut tiff = TiffEncoder::new(writer).map_err(err)?;
+    for (index, (name, pixels)) in pages.iter().enumerate() {
+        let page = PageTags {
+            name: *name,
+            number: (pages.len() > 1).then_some((index as u16, pages.len() as u16)),
+        };
+        if compression == TiffCompression::Jpeg {
+            write_jpeg_page(&mut tiff, width, height, &page, &to_rgba8(*pixels), quality)?;
+            continue;
+        }
+        match *pixels {
+            TiffPixels::U8(data) => write_page::<_, colortype::RGBA8>(
+                &mut tiff,
+                width,
+                height,
+                &page,
+                data,
+                compression,
+            ),
+            TiffPixels::U16(data) => write_page::<_, colortype::RGBA16>(
+                &mut tiff,
+                width,
+                height,
+                &page,
+                data,
+                compression,
+            ),
+            TiffPixels::F32(data) => write_page::<_, colortype::RGBA32Float>(
+                &mut tiff,
+                width,
+                height,
+                &page,
+                data,
+                compression,
+            ),
+        }
+        .map_err(err)?;
+    }
+    Ok(())
+}
+
+/// Per-page tags shared by every compression.
+struct PageTags<'a> {
+    name: Option<&'a str>,
+    /// `(index, count)` for multi-page files.
+    number: Option<(u16, u16)>,
+}
+
+impl PageTags<'_> {
+    fn write<W: Write + Seek>(
+        &self,
+        dir: &mut DirectoryEncoder<W, TiffKindStandard>,
+    ) -> tiff::TiffResult<()> {
+        if let Some(name) = self.name {
+            dir.write_tag(PAGE_NAME, name)?;
+        }
+        if let Some((index, count)) = self.number {
+            // NewSubfileType bit 1: one page of a multi-page image.
+            dir.write_tag(Tag::NewSubfileType, 2u32)?;
+            dir.write_tag(PAGE_NUMBER, &[index, count][..])?;
+        }
+        Ok(())
+    }
+}
+
+fn write_page<W: Write + Seek, C: EncodeColor>(
+    tiff: &mut TiffEncoder<W>,
+    width: u32,
+    height: u32,
+    page: &PageTags,
+    data: &[C::Inner],
+    compression: TiffCompression,
+) -> tiff::TiffResult<()>
+where
+    [C::Inner]: TiffValue,
+{
+    match compression {
+        TiffCompression::Lzw => write_with::<W, C, _>(tiff, width, height, page, data, Lzw),
+        TiffCompression::Deflate => {
+            write_with::<W, C, _>(tiff, width, height, page, data, Deflate::default())
+        }
+        TiffCompression::PackBits => {
+            write_with::<W, C, _>(tiff, width, height, page, data, Packbits)
+        }
+        TiffCompression::None | TiffCompression::Jpeg => {
+            write_with::<W, C, _>(tiff, width, height, page, data, Uncompressed)
+        }
+    }
+}
+
+fn write_with<W: Write + Seek, C: EncodeColor, D: Compression>(
+    tiff: &mut TiffEncoder<W>,
+    width: u32,
+    height: u32,
+    page: &PageTags,
+    data: &[C::Inner],
+    compression: D,
+) -> tiff::TiffResult<()>
+where
+    [C::Inner]: TiffValue,
+{
+    let mut image = tiff.new_image_with_compression::<C, D>(width, height, compression)?;
+    // The fourth sample is straight (unassociated) alpha.
+    image.encoder().write_tag(Tag::ExtraSamples, 2u16)?;
+    page.write(image.encoder())?;
+    image.write_data(data)
+}
+
+/// One JPEG-compressed (new-style, compression 7) RGB page in a single strip.
+fn write_jpeg_page<W: Write + Seek>(
+    tiff: &mut TiffEncoder<W>,
+    width: u32,
+    height: u32,
+    page: &PageTags,
+    rgba: &[u8],
+    quality: u8,
+) -> std::io::Result<()> {
+    let rgb: Vec<u8> = rgba
+        .chunks_exact(4)
+        .flat_map(|p| [p[0], p[1], p[2]])
+        .collect();
+    let mut jpeg = Vec::new();
+    JpegEncoder::new_with_quality(&mut jpeg, quality)
+        .encode(&rgb, width, height, image::ExtendedColorType::Rgb8)
+        .map_err(std::io::Error::other)?;
+
+    let mut write = || -> tiff::TiffResult<()> {
+        let mut dir = tiff.new_directory()?;
+        let offset = dir.write_data(&jpeg[..])?;
+        dir.write_tag(Tag::ImageWidth, width)?;
+        dir.write_tag(Tag::ImageLength, height)?;
+        dir.write_tag(Tag::BitsPerSample, &[8u16, 8, 8][..])?;
+        dir.write_tag(Tag::Compression, 7u16)?;
+        dir.write_tag(Tag::PhotometricInterpretation, 6u16)?;
+        dir.write_tag(Tag::SamplesPerPixel, 3u16)?;
+        dir.write_tag(Tag::RowsPerStrip, height)?;
+        dir.write_tag(Tag::StripOffsets, u32::try_from(offset)?)?;
+        dir.write_tag(Tag::StripByteCounts, jpeg.len() as u32)?;
+        dir.write_tag(Tag::PlanarConfiguration, 1u16)?;
+        // The encoder doesn't subsample chroma.
+        dir.write_tag(YCBCR_SUBSAMPLING, &[1u16, 1][..])?;
+        page.write(&mut dir)?;
+        dir.finish()
+    };
+    write().map_err(|e| std::io::Error::other(format!("TIFF encode error: {}", e)))
+}
+
+fn to_rgba8(pixels: TiffPixels) -> Vec<u8> {
+    match pixels {
+        TiffPixels::U8(v) => v.to_vec(),
+        TiffPixels::U16(v) => v.iter().map(|&x| ((x as u32 + 128) / 257) as u8).collect(),
+        TiffPixels::F32(v) => v.iter().map(|&x| u8::from_unit(x)).collect(),
+    }
+}
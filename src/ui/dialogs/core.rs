@@ -32,6 +32,7 @@ pub enum ActiveDialog {
     AddShape(AddShapeDialog),
     ResizeImage(ResizeImageDialog),
     ResizeCanvas(ResizeCanvasDialog),
+    Trim(TrimDialog),
     AlignLayer(AlignLayerDialog),
     GaussianBlur(GaussianBlurDialog),
     LayerTransform(LayerTransformDialog),
@@ -88,6 +89,15 @@ pub enum ActiveDialog {
     Vibrance(VibranceDialog),
     // Selection
     ColorRange(ColorRangeDialog),
+    // Import
+    SvgImport(SvgImportDialog),
+    // Print
+    PageSetup(PageSetupDialog),
+    // Export
+    ExportAs(ExportAsDialog),
+    ExportLayers(ExportLayersDialog),
+    ExportSlices(ExportSlicesDialog),
+    ImportSpriteSheet(ImportSpriteSheetDialog),
     #[cfg(not(target_arch = "wasm32"))]
     PaintDotNetPlugin(Box<crate::paintdotnet_plugins::PaintDotNetPluginDialog>),
 }
@@ -106,6 +116,7 @@ impl ActiveDialog {
             ActiveDialog::AddShape(_) => "AddShape",
             ActiveDialog::ResizeImage(_) => "ResizeImage",
             ActiveDialog::ResizeCanvas(_) => "ResizeCanvas",
+            ActiveDialog::Trim(_) => "Trim",
             ActiveDialog::AlignLayer(_) => "AlignLayer",
             ActiveDialog::GaussianBlur(_) => "GaussianBlur",
             ActiveDialog::LayerTransform(_) => "LayerTransform",
@@ -151,10 +162,45 @@ impl ActiveDialog {
             ActiveDialog::BlackAndWhite(_) => "BlackAndWhite",
             ActiveDialog::Vibrance(_) => "Vibrance",
             ActiveDialog::ColorRange(_) => "ColorRange",
+            ActiveDialog::SvgImport(_) => "SvgImport",
+            ActiveDialog::PageSetup(_) => "PageSetup",
+            ActiveDialog::ExportAs(_) => "ExportAs",
+            ActiveDialog::ExportLayers(_) => "ExportLayers",
+            ActiveDialog::ExportSlices(_) => "ExportSlices",
+            ActiveDialog::ImportSpriteSheet(_) => "ImportSpriteSheet",
             #[cfg(not(target_arch = "wasm32"))]
             ActiveDialog::PaintDotNetPlugin(_) => "PaintDotNetPlugin",
         }
     }
+
+    /// Whether the dialog moves or resamples the active layer.
+    pub fn moves_active_layer(&self) -> bool {
+        matches!(
+            self,
+            ActiveDialog::AlignLayer(_) | ActiveDialog::LayerTransform(_)
+        )
+    }
+
+    /// Whether the dialog writes the active layer's pixels (filters,
+    /// adjustments, layer transforms).
+    pub fn edits_active_layer(&self) -> bool {
+        !matches!(
+            self,
+            ActiveDialog::None
+                | ActiveDialog::AddBrushTip(_)
+                | ActiveDialog::AddShape(_)
+                | ActiveDialog::ResizeImage(_)
+                | ActiveDialog::ResizeCanvas(_)
+                | ActiveDialog::Trim(_)
+                | ActiveDialog::ColorRange(_)
+                | ActiveDialog::SvgImport(_)
+                | ActiveDialog::PageSetup(_)
+                | ActiveDialog::ExportAs(_)
+                | ActiveDialog::ExportLayers(_)
+                | ActiveDialog::ExportSlices(_)
+                | ActiveDialog::ImportSpriteSheet(_)
+        )
+    }
 }
 
 /// Result returned by each dialog's `show()` method every frame.
@@ -850,3 +896,39 @@ mod selection {
     include!("core/selection.rs");
 }
 pub use selection::*;
+
+mod page_setup {
+    use super::*;
+    include!("core/page_setup.rs");
+}
+pub use page_setup::*;
+
+mod export_as {
+    use super::*;
+    include!("core/export_as.rs");
+}
+pub use export_as::*;
+
+mod export_layers {
+    use super::*;
+    include!("core/export_layers.rs");
+}
+pub use export_layers::*;
+
+mod export_slices {
+    use super::*;
+    include!("core/export_slices.rs");
+}
+pub use export_slices::*;
+
+mod import_sprite_sheet {
+    use super::*;
+    include!("core/import_sprite_sheet.rs");
+}
+pub use import_sprite_sheet::*;
+
+mod trim {
+    use super::*;
+    include!("core/trim.rs");
+}
+pub use trim::*;
@@ -1978,6 +1978,10 @@ pub struct GradientMapDialog {
     pub shadow_color: [u8; 3],
     pub highlight_color: [u8; 3],
     pub preset: GradientMapPreset,
+    /// Library gradient in use; `None` maps shadows → highlights.
+    pub gradient: Option<crate::gradient::Gradient>,
+    /// Saved gradients offered alongside the presets.
+    pub library: Vec<crate::gradient::Gradient>,
     pub original_pixels: Option<TiledImage>,
     pub original_flat: Option<image::RgbaImage>,
     pub layer_idx: usize,
@@ -1991,6 +1995,8 @@ impl GradientMapDialog {
             shadow_color: [0, 0, 0],
             highlight_color: [255, 255, 255],
             preset: GradientMapPreset::BlackToWhite,
+            gradient: None,
+            library: Vec::new(),
             original_pixels: state.layers.get(idx).map(|l| l.pixels.clone()),
             original_flat: state.layers.get(idx).map(|l| l.pixels.to_rgba_image()),
             layer_idx: idx,
@@ -1998,7 +2004,15 @@ impl GradientMapDialog {
         }
     }
 
+    pub fn with_library(mut self, library: Vec<crate::gradient::Gradient>) -> Self {
+        self.library = library;
+        self
+    }
+
     pub fn build_lut(&self) -> [[u8; 4]; 256] {
+        if let Some(gradient) = &self.gradient {
+            return gradient.build_lut();
+        }
         let mut lut = [[0u8; 4]; 256];
         let [sr, sg, sb] = self.shadow_color;
         let [hr, hg, hb] = self.highlight_color;
@@ -2016,6 +2030,7 @@ impl GradientMapDialog {
 
     fn apply_preset(&mut self, preset: GradientMapPreset) {
         self.preset = preset;
+        self.gradient = None;
         match preset {
             GradientMapPreset::BlackToWhite => {
                 self.shadow_color = [0, 0, 0];
@@ -2083,6 +2098,32 @@ impl GradientMapDialog {
                     }
                 });
 
+                if !self.library.is_empty() {
+                    ui.add_space(4.0);
+                    section_label(ui, &colors, "LIBRARY");
+                    let mut picked = None;
+                    ui.horizontal_wrapped(|ui| {
+                        for (i, gradient) in self.library.iter().enumerate() {
+                            let active = self.gradient.as_ref() == Some(gradient);
+                            let btn = if active {
+                                egui::Button::new(&gradient.name).fill(colors.accent_faint)
+                            } else {
+                                egui::Button::new(&gradient.name)
+                            };
+                            if ui.add(btn).clicked() && !active {
+                                picked = Some(i);
+                            }
+                        }
+                    });
+                    if let Some(i) = picked {
+                        self.gradient = Some(self.library[i].clone());
+                        self.preset = GradientMapPreset::Custom;
+                        if self.live_preview {
+                            result = DialogResult::Changed;
+                        }
+                    }
+                }
+
                 // Gradient preview strip
                 ui.add_space(4.0);
                 let lut = self.build_lut();
@@ -2123,6 +2164,7 @@ impl GradientMapDialog {
                         if ui.color_edit_button_srgba(&mut c).changed() {
                             self.shadow_color = [c.r(), c.g(), c.b()];
                             self.preset = GradientMapPreset::Custom;
+                            self.gradient = None;
                             changed_color = true;
                         }
                         ui.end_row();
@@ -2136,6 +2178,7 @@ impl GradientMapDialog {
                         if ui.color_edit_button_srgba(&mut c).changed() {
                             self.highlight_color = [c.r(), c.g(), c.b()];
                             self.preset = GradientMapPreset::Custom;
+                            self.gradient = None;
                             changed_color = true;
                         }
                         ui.end_row();
@@ -0,0 +1,350 @@
+use crate::components::dialogs::SaveFormat;
+use crate::config::export_presets::{ExportDepth, ExportPreset, MetadataPolicy};
+use crate::jpeg_io::ChromaSubsampling;
+
+/// File > Export As: write the document once per checked preset.
+pub struct ExportAsDialog {
+    pub presets: Vec<ExportPreset>,
+    pub checked: Vec<bool>,
+    /// Preset shown in the editor.
+    selected: usize,
+    /// Destination folder (native only; the web build downloads by name).
+    pub directory: String,
+    /// File stem each preset's suffix and extension are appended to.
+    pub base_name: String,
+    /// Presets were edited and should be written back to the preset file.
+    pub presets_changed: bool,
+    /// Problem with the preset file or the checked presets.
+    pub error: Option<String>,
+}
+
+impl ExportAsDialog {
+    pub fn new(
+        presets: Result<Vec<ExportPreset>, String>,
+        checked_names: &[String],
+        directory: String,
+        base_name: String,
+    ) -> Self {
+        let (presets, error) = match presets {
+            Ok(presets) => (presets, None),
+            Err(e) => (
+                crate::config::export_presets::default_export_presets(),
+                Some(e),
+            ),
+        };
+        let checked = presets
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                if checked_names.is_empty() {
+                    i == 0
+                } else {
+                    checked_names.contains(&p.name)
+                }
+            })
+            .collect();
+        Self {
+            presets,
+            checked,
+            selected: 0,
+            directory,
+            base_name,
+            presets_changed: false,
+            error,
+        }
+    }
+
+    /// Checked presets, in list order.
+    pub fn chosen(&self) -> Vec<ExportPreset> {
+        self.presets
+            .iter()
+            .zip(&self.checked)
+            .filter(|(_, checked)| **checked)
+            .map(|(p, _)| p.clone())
+            .collect()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let chosen = self.chosen();
+        if chosen.is_empty() {
+            return Err(t!("dialog.export_as.none_checked"));
+        }
+        if self.base_name.trim().is_empty() {
+            return Err(t!("dialog.export_as.no_name"));
+        }
+        for preset in &chosen {
+            preset.validate()?;
+        }
+        for (i, a) in chosen.iter().enumerate() {
+            if chosen[..i]
+                .iter()
+                .any(|b| b.file_name(&self.base_name) == a.file_name(&self.base_name))
+            {
+                return Err(t!("dialog.export_as.duplicate_file")
+                    .replace("{0}", &a.file_name(&self.base_name)));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> DialogResult<()> {
+        let mut result = DialogResult::Open;
+        let colors = DialogColors::from_ctx(ctx);
+
+        egui::Window::new("dialog_export_as")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .default_pos(egui::pos2(
+                ctx.content_rect().center().x - 280.0,
+                ctx.content_rect().center().y - 220.0,
+            ))
+            .show(ctx, |ui| {
+                ui.set_min_width(560.0);
+
+                if paint_dialog_header(ui, &colors, "\u{1F4E4}", &t!("dialog.export_as")) {
+                    result = DialogResult::Cancel;
+                }
+                ui.add_space(4.0);
+
+                ui.horizontal_top(|ui| {
+                    ui.vertical(|ui| {
+                        ui.set_width(230.0);
+                        self.show_preset_list(ui, &colors);
+                    });
+                    ui.add_space(8.0);
+                    ui.vertical(|ui| {
+                        ui.set_width(310.0);
+                        self.show_preset_editor(ui, &colors);
+                    });
+                });
+
+                accent_separator(ui, &colors);
+                section_label(ui, &colors, &t!("dialog.export_as.destination"));
+                self.show_destination(ui);
+
+                let validation = self.validate();
+                if let Some(e) = validation.as_ref().err().or(self.error.as_ref()) {
+                    ui.add_space(4.0);
+                    ui.label(egui::RichText::new(e).color(Color32::from_rgb(220, 60, 60)));
+                }
+
+                // -- Footer: Export / Cancel --
+                ui.add_space(4.0);
+                accent_separator(ui, &colors);
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(t!("common.cancel")).clicked() {
+                            result = DialogResult::Cancel;
+                        }
+                        let export_btn = egui::Button::new(
+                            egui::RichText::new(format!("  {}  ", t!("dialog.export_as.export")))
+                                .color(contrast_text_color(colors.accent))
+                                .strong(),
+                        )
+                        .fill(colors.accent);
+                        if ui.add_enabled(validation.is_ok(), export_btn).clicked() {
+                            result = DialogResult::Ok(());
+                        }
+                    });
+                });
+            });
+
+        if matches!(result, DialogResult::Open) && ctx.input(|i| i.key_pressed(egui::Key::Escape))
+        {
+            result = DialogResult::Cancel;
+        }
+        result
+    }
+
+    fn show_preset_list(&mut self, ui: &mut egui::Ui, colors: &DialogColors) {
+        section_label(ui, colors, &t!("dialog.export_as.presets"));
+        egui::ScrollArea::vertical()
+            .id_salt("export_as_presets")
+            .max_height(220.0)
+            .show(ui, |ui| {
+                for i in 0..self.presets.len() {
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.checked[i], "");
+                        let preset = &self.presets[i];
+                        let label = format!(
+                            "{}  \u{00B7}  {}",
+                            preset.name,
+                            preset.file_name(&self.base_name)
+                        );
+                        if ui.selectable_label(self.selected == i, label).clicked() {
+                            self.selected = i;
+                        }
+                    });
+                }
+            });
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            if ui.button(t!("dialog.export_as.add")).clicked() {
+                let mut preset = self
+                    .presets
+                    .get(self.selected)
+                    .cloned()
+                    .unwrap_or_default();
+                preset.name = format!(
+                    "{} {}",
+                    t!("dialog.export_as.preset"),
+                    self.presets.len() + 1
+                );
+                self.presets.push(preset);
+                self.checked.push(true);
+                self.selected = self.presets.len() - 1;
+                self.presets_changed = true;
+            }
+            if ui
+                .add_enabled(
+                    self.presets.len() > 1,
+                    egui::Button::new(t!("dialog.export_as.remove")),
+                )
+                .clicked()
+            {
+                self.presets.remove(self.selected);
+                self.checked.remove(self.selected);
+                self.selected = self.selected.min(self.presets.len() - 1);
+                self.presets_changed = true;
+            }
+        });
+    }
+
+    fn show_preset_editor(&mut self, ui: &mut egui::Ui, colors: &DialogColors) {
+        section_label(ui, colors, &t!("dialog.export_as.preset_settings"));
+        let Some(preset) = self.presets.get_mut(self.selected) else {
+            return;
+        };
+        let before = preset.clone();
+        egui::Grid::new("export_as_preset")
+            .num_columns(2)
+            .min_col_width(90.0)
+            .spacing([8.0, 5.0])
+            .show(ui, |ui| {
+                ui.label(t!("dialog.export_as.name"));
+                ui.text_edit_singleline(&mut preset.name);
+                ui.end_row();
+
+                ui.label(t!("dialog.export_as.format"));
+                egui::ComboBox::from_id_salt("export_as_format")
+                    .width(200.0)
+                    .selected_text(preset.format.label())
+                    .show_ui(ui, |ui| {
+                        for format in SaveFormat::all() {
+                            if *format != SaveFormat::Pfe {
+                                ui.selectable_value(&mut preset.format, *format, format.label());
+                            }
+                        }
+                    });
+                ui.end_row();
+
+                if preset.format == SaveFormat::Webp {
+                    ui.label("");
+                    ui.checkbox(&mut preset.webp_lossless, t!("dialog.export_as.lossless"));
+                    ui.end_row();
+                }
+                if preset.format == SaveFormat::Jpeg
+                    || (preset.format == SaveFormat::Webp && !preset.webp_lossless)
+                {
+                    ui.label(t!("dialog.export_as.quality"));
+                    let mut q = preset.quality as f32;
+                    if numeric_field_with_buttons(ui, &mut q, 0.5, 1.0..=100.0, "", 5.0) {
+                        preset.quality = q.round() as u8;
+                    }
+                    ui.end_row();
+                }
+                if preset.format == SaveFormat::Jpeg {
+                    ui.label("");
+                    ui.checkbox(
+                        &mut preset.jpeg.progressive,
+                        t!("dialog.export_as.progressive"),
+                    );
+                    ui.end_row();
+                    ui.label(t!("dialog.export_as.chroma"));
+                    egui::ComboBox::from_id_salt("export_as_chroma")
+                        .width(200.0)
+                        .selected_text(preset.jpeg.subsampling.label())
+                        .show_ui(ui, |ui| {
+                            for mode in ChromaSubsampling::ALL {
+                                ui.selectable_value(
+                                    &mut preset.jpeg.subsampling,
+                                    mode,
+                                    mode.label(),
+                                );
+                            }
+                        });
+                    ui.end_row();
+                }
+
+                ui.label(t!("dialog.export_as.scale"));
+                let mut percent = preset.scale * 100.0;
+                if numeric_field_with_buttons(ui, &mut percent, 1.0, 1.0..=1600.0, "%", 25.0) {
+                    preset.scale = percent / 100.0;
+                }
+                ui.end_row();
+
+                ui.label(t!("dialog.export_as.depth"));
+                egui::ComboBox::from_id_salt("export_as_depth")
+                    .width(200.0)
+                    .selected_text(preset.depth.label())
+                    .show_ui(ui, |ui| {
+                        for depth in ExportDepth::ALL {
+                            ui.selectable_value(&mut preset.depth, depth, depth.label());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(t!("dialog.export_as.metadata"));
+                egui::ComboBox::from_id_salt("export_as_metadata")
+                    .width(200.0)
+                    .selected_text(preset.metadata.label())
+                    .show_ui(ui, |ui| {
+                        for policy in MetadataPolicy::ALL {
+                            ui.selectable_value(&mut preset.metadata, policy, policy.label());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(t!("dialog.export_as.suffix"));
+                ui.text_edit_singleline(&mut preset.suffix);
+                ui.end_row();
+            });
+        if *preset != before {
+            self.presets_changed = true;
+        }
+    }
+
+    fn show_destination(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("export_as_destination")
+            .num_columns(2)
+            .min_col_width(90.0)
+            .spacing([8.0, 5.0])
+            .show(ui, |ui| {
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    ui.label(t!("dialog.export_as.folder"));
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.directory).desired_width(340.0),
+                        );
+                        if ui.button(t!("dialog.export_as.browse")).clicked() {
+                            let mut picker = rfd::FileDialog::new();
+                            if !self.directory.is_empty() {
+                                picker = picker.set_directory(&self.directory);
+                            }
+                            if let Some(dir) = picker.pick_folder() {
+                                self.directory = dir.to_string_lossy().into_owned();
+                            }
+                        }
+                    });
+                    ui.end_row();
+                }
+
+                ui.label(t!("dialog.export_as.file_name"));
+                ui.add(egui::TextEdit::singleline(&mut self.base_name).desired_width(340.0));
+                ui.end_row();
+            });
+    }
+}
@@ -0,0 +1,202 @@
+use crate::config::export_presets::ExportPreset;
+use crate::ops::layer_export::{LayerExportOptions, layer_file_stem};
+
+/// File > Export Layers: write each layer or top-level group to its own file.
+pub struct ExportLayersDialog {
+    pub options: LayerExportOptions,
+    pub presets: Vec<ExportPreset>,
+    /// Index into `presets`; `None` writes PNG.
+    preset: Option<usize>,
+    /// Destination folder.
+    pub directory: String,
+    /// Document name used for `{doc}`.
+    pub doc_name: String,
+    /// Top layer's name, for the file name example.
+    sample_layer: String,
+    /// Problem with the preset file.
+    pub error: Option<String>,
+}
+
+impl ExportLayersDialog {
+    pub fn new(
+        presets: Result<Vec<ExportPreset>, String>,
+        directory: String,
+        doc_name: String,
+        sample_layer: String,
+    ) -> Self {
+        let (presets, error) = match presets {
+            Ok(presets) => (presets, None),
+            Err(e) => (
+                crate::config::export_presets::default_export_presets(),
+                Some(e),
+            ),
+        };
+        Self {
+            options: LayerExportOptions::default(),
+            presets,
+            preset: None,
+            directory,
+            doc_name,
+            sample_layer,
+            error,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.directory.trim().is_empty() {
+            return Err(t!("dialog.export_layers.no_folder"));
+        }
+        self.options.validate()
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> DialogResult<()> {
+        let mut result = DialogResult::Open;
+        let colors = DialogColors::from_ctx(ctx);
+
+        egui::Window::new("dialog_export_layers")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .default_pos(egui::pos2(
+                ctx.content_rect().center().x - 220.0,
+                ctx.content_rect().center().y - 160.0,
+            ))
+            .show(ctx, |ui| {
+                ui.set_min_width(440.0);
+
+                if paint_dialog_header(ui, &colors, "\u{1F4E4}", &t!("dialog.export_layers")) {
+                    result = DialogResult::Cancel;
+                }
+                ui.add_space(4.0);
+
+                section_label(ui, &colors, &t!("dialog.export_layers.files"));
+                egui::Grid::new("export_layers_options")
+                    .num_columns(2)
+                    .min_col_width(90.0)
+                    .spacing([8.0, 5.0])
+                    .show(ui, |ui| {
+                        ui.label(t!("dialog.export_layers.template"));
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.options.template)
+                                .desired_width(300.0),
+                        )
+                        .on_hover_text(t!("dialog.export_layers.template_hint"));
+                        ui.end_row();
+
+                        ui.label(t!("dialog.export_as.preset"));
+                        let label = |p: Option<&ExportPreset>| match p {
+                            Some(p) => p.name.clone(),
+                            None => t!("dialog.export_layers.plain_png"),
+                        };
+                        let current = label(self.preset.and_then(|i| self.presets.get(i)));
+                        egui::ComboBox::from_id_salt("export_layers_preset")
+                            .width(300.0)
+                            .selected_text(current)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.preset, None, label(None));
+                                for (i, preset) in self.presets.iter().enumerate() {
+                                    ui.selectable_value(
+                                        &mut self.preset,
+                                        Some(i),
+                                        preset.name.as_str(),
+                                    );
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label("");
+                        ui.vertical(|ui| {
+                            ui.checkbox(
+                                &mut self.options.groups,
+                                t!("dialog.export_layers.groups"),
+                            );
+                            ui.checkbox(&mut self.options.trim, t!("dialog.export_layers.trim"));
+                            ui.checkbox(
+                                &mut self.options.include_hidden,
+                                t!("dialog.export_layers.include_hidden"),
+                            );
+                        });
+                        ui.end_row();
+                    });
+                self.options.preset = self.preset.and_then(|i| self.presets.get(i)).cloned();
+
+                accent_separator(ui, &colors);
+                section_label(ui, &colors, &t!("dialog.export_as.destination"));
+                egui::Grid::new("export_layers_destination")
+                    .num_columns(2)
+                    .min_col_width(90.0)
+                    .spacing([8.0, 5.0])
+                    .show(ui, |ui| {
+                        ui.label(t!("dialog.export_as.folder"));
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.directory)
+                                    .desired_width(220.0),
+                            );
+                            #[cfg(not(target_arch = "wasm32"))]
+                            if ui.button(t!("dialog.export_as.browse")).clicked() {
+                                let mut picker = rfd::FileDialog::new();
+                                if !self.directory.is_empty() {
+                                    picker = picker.set_directory(&self.directory);
+                                }
+                                if let Some(dir) = picker.pick_folder() {
+                                    self.directory = dir.to_string_lossy().into_owned();
+                                }
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label(t!("dialog.export_layers.doc_name"));
+                        ui.add(egui::TextEdit::singleline(&mut self.doc_name).desired_width(300.0));
+                        ui.end_row();
+
+                        let stem = layer_file_stem(
+                            &self.options.template,
+                            &self.doc_name,
+                            &self.sample_layer,
+                            1,
+                            1,
+                        );
+                        let example = match &self.options.preset {
+                            Some(preset) => preset.file_name(&stem),
+                            None => format!("{}.{}", stem, self.options.format.extension()),
+                        };
+                        ui.label(t!("dialog.export_layers.example"));
+                        ui.weak(example);
+                        ui.end_row();
+                    });
+
+                let validation = self.validate();
+                if let Some(e) = validation.as_ref().err().or(self.error.as_ref()) {
+                    ui.add_space(4.0);
+                    ui.label(egui::RichText::new(e).color(Color32::from_rgb(220, 60, 60)));
+                }
+
+                // -- Footer: Export / Cancel --
+                ui.add_space(4.0);
+                accent_separator(ui, &colors);
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(t!("common.cancel")).clicked() {
+                            result = DialogResult::Cancel;
+                        }
+                        let export_btn = egui::Button::new(
+                            egui::RichText::new(format!("  {}  ", t!("dialog.export_as.export")))
+                                .color(contrast_text_color(colors.accent))
+                                .strong(),
+                        )
+                        .fill(colors.accent);
+                        if ui.add_enabled(validation.is_ok(), export_btn).clicked() {
+                            result = DialogResult::Ok(());
+                        }
+                    });
+                });
+            });
+
+        if matches!(result, DialogResult::Open) && ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            result = DialogResult::Cancel;
+        }
+        result
+    }
+}
@@ -0,0 +1,264 @@
+use crate::config::export_presets::ExportPreset;
+use crate::ops::slices::{SliceExportOptions, SpriteSheetOptions, SpriteSource};
+
+/// What File > Export Slices writes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SliceExportMode {
+    /// One file per slice.
+    Files,
+    /// One packed image plus a JSON atlas.
+    SpriteSheet,
+}
+
+/// File > Export Slices: write each slice, or pack slices or layers into a
+/// sprite sheet.
+pub struct ExportSlicesDialog {
+    pub mode: SliceExportMode,
+    pub files: SliceExportOptions,
+    pub sheet: SpriteSheetOptions,
+    pub presets: Vec<ExportPreset>,
+    /// Index into `presets`; `None` writes PNG.
+    preset: Option<usize>,
+    /// Destination folder.
+    pub directory: String,
+    /// File stem of the sheet and its atlas.
+    pub sheet_name: String,
+    slice_count: usize,
+    /// Problem with the preset file.
+    pub error: Option<String>,
+}
+
+impl ExportSlicesDialog {
+    pub fn new(
+        presets: Result<Vec<ExportPreset>, String>,
+        directory: String,
+        doc_name: String,
+        slice_count: usize,
+    ) -> Self {
+        let (presets, error) = match presets {
+            Ok(presets) => (presets, None),
+            Err(e) => (
+                crate::config::export_presets::default_export_presets(),
+                Some(e),
+            ),
+        };
+        let sheet = SpriteSheetOptions {
+            source: if slice_count > 0 {
+                SpriteSource::Slices
+            } else {
+                SpriteSource::Layers
+            },
+            ..SpriteSheetOptions::default()
+        };
+        Self {
+            mode: if slice_count > 0 {
+                SliceExportMode::Files
+            } else {
+                SliceExportMode::SpriteSheet
+            },
+            files: SliceExportOptions::default(),
+            sheet,
+            presets,
+            preset: None,
+            directory,
+            sheet_name: doc_name,
+            slice_count,
+            error,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.directory.trim().is_empty() {
+            return Err(t!("dialog.export_layers.no_folder"));
+        }
+        let needs_slices = match self.mode {
+            SliceExportMode::Files => true,
+            SliceExportMode::SpriteSheet => self.sheet.source == SpriteSource::Slices,
+        };
+        if needs_slices && self.slice_count == 0 {
+            return Err(t!("dialog.export_slices.no_slices"));
+        }
+        match self.mode {
+            SliceExportMode::Files => self.files.validate(),
+            SliceExportMode::SpriteSheet if self.sheet_name.trim().is_empty() => {
+                Err(t!("dialog.export_slices.no_name"))
+            }
+            SliceExportMode::SpriteSheet => Ok(()),
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> DialogResult<()> {
+        let mut result = DialogResult::Open;
+        let colors = DialogColors::from_ctx(ctx);
+
+        egui::Window::new("dialog_export_slices")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .default_pos(egui::pos2(
+                ctx.content_rect().center().x - 220.0,
+                ctx.content_rect().center().y - 160.0,
+            ))
+            .show(ctx, |ui| {
+                ui.set_min_width(440.0);
+
+                if paint_dialog_header(ui, &colors, "\u{1F4E4}", &t!("dialog.export_slices")) {
+                    result = DialogResult::Cancel;
+                }
+                ui.add_space(4.0);
+
+                ui.horizontal(|ui| {
+                    ui.radio_value(
+                        &mut self.mode,
+                        SliceExportMode::Files,
+                        t!("dialog.export_slices.files"),
+                    );
+                    ui.radio_value(
+                        &mut self.mode,
+                        SliceExportMode::SpriteSheet,
+                        t!("dialog.export_slices.sprite_sheet"),
+                    );
+                });
+                ui.add_space(4.0);
+
+                match self.mode {
+                    SliceExportMode::Files => {
+                        section_label(ui, &colors, &t!("dialog.export_layers.files"));
+                        egui::Grid::new("export_slices_files")
+                            .num_columns(2)
+                            .min_col_width(90.0)
+                            .spacing([8.0, 5.0])
+                            .show(ui, |ui| {
+                                ui.label(t!("dialog.export_as.preset"));
+                                let label = |p: Option<&ExportPreset>| match p {
+                                    Some(p) => p.name.clone(),
+                                    None => t!("dialog.export_layers.plain_png"),
+                                };
+                                let current = label(self.preset.and_then(|i| self.presets.get(i)));
+                                egui::ComboBox::from_id_salt("export_slices_preset")
+                                    .width(300.0)
+                                    .selected_text(current)
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut self.preset, None, label(None));
+                                        for (i, preset) in self.presets.iter().enumerate() {
+                                            ui.selectable_value(
+                                                &mut self.preset,
+                                                Some(i),
+                                                preset.name.as_str(),
+                                            );
+                                        }
+                                    });
+                                ui.end_row();
+
+                                ui.label(t!("dialog.export_slices.count"));
+                                ui.weak(self.slice_count.to_string());
+                                ui.end_row();
+                            });
+                        self.files.preset = self.preset.and_then(|i| self.presets.get(i)).cloned();
+                    }
+                    SliceExportMode::SpriteSheet => {
+                        section_label(ui, &colors, &t!("dialog.export_slices.sheet"));
+                        egui::Grid::new("export_slices_sheet")
+                            .num_columns(2)
+                            .min_col_width(90.0)
+                            .spacing([8.0, 5.0])
+                            .show(ui, |ui| {
+                                ui.label(t!("dialog.export_slices.source"));
+                                ui.horizontal(|ui| {
+                                    ui.radio_value(
+                                        &mut self.sheet.source,
+                                        SpriteSource::Slices,
+                                        t!("dialog.export_slices.source_slices"),
+                                    );
+                                    ui.radio_value(
+                                        &mut self.sheet.source,
+                                        SpriteSource::Layers,
+                                        t!("dialog.export_slices.source_layers"),
+                                    );
+                                });
+                                ui.end_row();
+
+                                ui.label(t!("dialog.export_slices.columns"));
+                                ui.add(
+                                    egui::DragValue::new(&mut self.sheet.columns)
+                                        .range(0..=256)
+                                        .speed(0.1),
+                                )
+                                .on_hover_text(t!("dialog.export_slices.columns_hint"));
+                                ui.end_row();
+
+                                ui.label(t!("dialog.export_slices.padding"));
+                                ui.add(
+                                    egui::DragValue::new(&mut self.sheet.padding)
+                                        .range(0..=64)
+                                        .speed(0.1)
+                                        .suffix("px"),
+                                );
+                                ui.end_row();
+
+                                ui.label("");
+                                ui.checkbox(&mut self.sheet.trim, t!("dialog.export_layers.trim"));
+                                ui.end_row();
+
+                                ui.label(t!("dialog.export_slices.name"));
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut self.sheet_name)
+                                        .desired_width(300.0),
+                                )
+                                .on_hover_text(t!("dialog.export_slices.name_hint"));
+                                ui.end_row();
+                            });
+                    }
+                }
+
+                accent_separator(ui, &colors);
+                section_label(ui, &colors, &t!("dialog.export_as.destination"));
+                ui.horizontal(|ui| {
+                    ui.label(t!("dialog.export_as.folder"));
+                    ui.add(egui::TextEdit::singleline(&mut self.directory).desired_width(260.0));
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if ui.button(t!("dialog.export_as.browse")).clicked() {
+                        let mut picker = rfd::FileDialog::new();
+                        if !self.directory.is_empty() {
+                            picker = picker.set_directory(&self.directory);
+                        }
+                        if let Some(dir) = picker.pick_folder() {
+                            self.directory = dir.to_string_lossy().into_owned();
+                        }
+                    }
+                });
+
+                let validation = self.validate();
+                if let Some(e) = validation.as_ref().err().or(self.error.as_ref()) {
+                    ui.add_space(4.0);
+                    ui.label(egui::RichText::new(e).color(Color32::from_rgb(220, 60, 60)));
+                }
+
+                // -- Footer: Export / Cancel --
+                ui.add_space(4.0);
+                accent_separator(ui, &colors);
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(t!("common.cancel")).clicked() {
+                            result = DialogResult::Cancel;
+                        }
+                        let export_btn = egui::Button::new(
+                            egui::RichText::new(format!("  {}  ", t!("dialog.export_as.export")))
+                                .color(contrast_text_color(colors.accent))
+                                .strong(),
+                        )
+                        .fill(colors.accent);
+                        if ui.add_enabled(validation.is_ok(), export_btn).clicked() {
+                            result = DialogResult::Ok(());
+                        }
+                    });
+                });
+            });
+
+        if matches!(result, DialogResult::Open) && ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            result = DialogResult::Cancel;
+        }
+        result
+    }
+}
@@ -1519,3 +1519,197 @@ impl AddShapeDialog {
         result
     }
 }
+
+// ============================================================================
+// SVG IMPORT DIALOG
+// ============================================================================
+
+/// Where a rasterized SVG goes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SvgImportMode {
+    /// Open as a new document.
+    NewDocument,
+    /// Add to the active document above the active layer.
+    Layers,
+}
+
+pub struct SvgImportDialog {
+    pub document: std::sync::Arc<crate::svg_io::SvgDocument>,
+    pub path: std::path::PathBuf,
+    pub mode: SvgImportMode,
+    pub dpi: f32,
+    pub width: f32,
+    pub height: f32,
+    pub lock_aspect: bool,
+    pub split_groups: bool,
+    group_count: usize,
+}
+
+impl SvgImportDialog {
+    pub fn new(
+        document: crate::svg_io::SvgDocument,
+        path: std::path::PathBuf,
+        mode: SvgImportMode,
+    ) -> Self {
+        let (width, height) = document.target_size(&crate::svg_io::SvgOptions::default());
+        let group_count = document.group_count();
+        Self {
+            document: std::sync::Arc::new(document),
+            path,
+            mode,
+            dpi: crate::svg_io::SVG_BASE_DPI,
+            width: width as f32,
+            height: height as f32,
+            lock_aspect: true,
+            split_groups: group_count > 0,
+            group_count,
+        }
+    }
+
+    fn options(&self) -> crate::svg_io::SvgOptions {
+        crate::svg_io::SvgOptions {
+            dpi: self.dpi,
+            width: Some((self.width.round() as u32).max(1)),
+            height: Some((self.height.round() as u32).max(1)),
+            split_groups: self.split_groups,
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> DialogResult<crate::svg_io::SvgOptions> {
+        let mut result = DialogResult::Open;
+        let colors = DialogColors::from_ctx(ctx);
+        let (doc_w, doc_h) = self.document.intrinsic_size();
+        let title = match self.mode {
+            SvgImportMode::NewDocument => t!("dialog.svg_import.open"),
+            SvgImportMode::Layers => t!("dialog.svg_import.import"),
+        };
+
+        egui::Window::new("dialog_svg_import")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .default_pos(egui::pos2(
+                ctx.content_rect().center().x - 170.0,
+                ctx.content_rect().center().y - 140.0,
+            ))
+            .show(ctx, |ui| {
+                ui.set_min_width(340.0);
+
+                if paint_dialog_header(ui, &colors, "\u{1F5BC}", &title) {
+                    result = DialogResult::Cancel;
+                }
+                ui.add_space(4.0);
+
+                section_label(ui, &colors, &t!("dialog.svg_import.size"));
+                egui::Grid::new("svg_import_size")
+                    .num_columns(2)
+                    .min_col_width(80.0)
+                    .spacing([8.0, 5.0])
+                    .show(ui, |ui| {
+                        ui.label(t!("dialog.svg_import.resolution"));
+                        if numeric_field_with_buttons(
+                            ui,
+                            &mut self.dpi,
+                            0.5,
+                            1.0..=2400.0,
+                            " DPI",
+                            12.0,
+                        ) {
+                            let scale = self.dpi as f64 / crate::svg_io::SVG_BASE_DPI as f64;
+                            self.width = (doc_w * scale).round().clamp(1.0, 20000.0) as f32;
+                            self.height = (doc_h * scale).round().clamp(1.0, 20000.0) as f32;
+                        }
+                        ui.end_row();
+
+                        ui.label(t!("dialog.resize_image.width"));
+                        if numeric_field_with_buttons(
+                            ui,
+                            &mut self.width,
+                            1.0,
+                            1.0..=20000.0,
+                            "px",
+                            10.0,
+                        ) && self.lock_aspect
+                        {
+                            self.height =
+                                (self.width as f64 * doc_h / doc_w).round().max(1.0) as f32;
+                            self.dpi = (self.width as f64 / doc_w
+                                * crate::svg_io::SVG_BASE_DPI as f64)
+                                as f32;
+                        }
+                        ui.end_row();
+
+                        ui.label(t!("dialog.resize_image.height"));
+                        if numeric_field_with_buttons(
+                            ui,
+                            &mut self.height,
+                            1.0,
+                            1.0..=20000.0,
+                            "px",
+                            10.0,
+                        ) && self.lock_aspect
+                        {
+                            self.width =
+                                (self.height as f64 * doc_w / doc_h).round().max(1.0) as f32;
+                            self.dpi = (self.height as f64 / doc_h
+                                * crate::svg_io::SVG_BASE_DPI as f64)
+                                as f32;
+                        }
+                        ui.end_row();
+
+                        ui.label("");
+                        if ui
+                            .checkbox(&mut self.lock_aspect, t!("dialog.resize_image.lock_aspect"))
+                            .changed()
+                            && self.lock_aspect
+                        {
+                            self.height =
+                                (self.width as f64 * doc_h / doc_w).round().max(1.0) as f32;
+                        }
+                        ui.end_row();
+                    });
+
+                accent_separator(ui, &colors);
+                section_label(ui, &colors, &t!("dialog.svg_import.layers"));
+                ui.add_enabled(
+                    self.group_count > 0,
+                    egui::Checkbox::new(
+                        &mut self.split_groups,
+                        t!("dialog.svg_import.split_groups")
+                            .replace("{0}", &self.group_count.to_string()),
+                    ),
+                );
+
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.add_space(4.0);
+                    let info = t!("dialog.svg_import.document_size")
+                        .replace("{0}", &format!("{}", doc_w.round()))
+                        .replace("{1}", &format!("{}", doc_h.round()));
+                    ui.label(
+                        egui::RichText::new(info)
+                            .size(11.0)
+                            .color(colors.text_muted),
+                    );
+                });
+
+                let (ok, cancel) = dialog_footer(ui, &colors);
+                if ok {
+                    result = DialogResult::Ok(self.options());
+                }
+                if cancel {
+                    result = DialogResult::Cancel;
+                }
+            });
+
+        if matches!(result, DialogResult::Open) {
+            if ctx.input(|i| i.key_pressed(egui::Key::Enter)) {
+                result = DialogResult::Ok(self.options());
+            }
+            if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+                result = DialogResult::Cancel;
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,142 @@
+use crate::ops::slices::SpriteGrid;
+
+/// File > Import Sprite Sheet: cut an image into equal cells and open them
+/// as the layers of a new document.
+pub struct ImportSpriteSheetDialog {
+    pub sheet: image::RgbaImage,
+    /// Name of the new document.
+    pub name: String,
+    pub grid: SpriteGrid,
+    /// Leave fully transparent cells out.
+    pub skip_empty: bool,
+}
+
+impl ImportSpriteSheetDialog {
+    pub fn new(sheet: image::RgbaImage, name: String) -> Self {
+        // Guess square cells from the shorter side: a strip of frames is
+        // usually one cell high.
+        let side = sheet.width().min(sheet.height()).max(1);
+        Self {
+            sheet,
+            name,
+            grid: SpriteGrid {
+                cell_width: side,
+                cell_height: side,
+                ..SpriteGrid::default()
+            },
+            skip_empty: true,
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> DialogResult<()> {
+        let mut result = DialogResult::Open;
+        let colors = DialogColors::from_ctx(ctx);
+        let (width, height) = self.sheet.dimensions();
+
+        egui::Window::new("dialog_import_sprite_sheet")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .default_pos(egui::pos2(
+                ctx.content_rect().center().x - 180.0,
+                ctx.content_rect().center().y - 140.0,
+            ))
+            .show(ctx, |ui| {
+                ui.set_min_width(360.0);
+
+                if paint_dialog_header(ui, &colors, "\u{1F5BC}", &t!("dialog.import_sprite_sheet"))
+                {
+                    result = DialogResult::Cancel;
+                }
+                ui.add_space(4.0);
+                ui.weak(format!(
+                    "{} \u{2014} {} \u{00D7} {} px",
+                    self.name, width, height
+                ));
+                ui.add_space(4.0);
+
+                section_label(ui, &colors, &t!("dialog.import_sprite_sheet.grid"));
+                egui::Grid::new("import_sprite_sheet_grid")
+                    .num_columns(2)
+                    .min_col_width(90.0)
+                    .spacing([8.0, 5.0])
+                    .show(ui, |ui| {
+                        ui.label(t!("dialog.import_sprite_sheet.cell"));
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::DragValue::new(&mut self.grid.cell_width)
+                                    .range(1..=width)
+                                    .suffix("px"),
+                            );
+                            ui.label("\u{00D7}");
+                            ui.add(
+                                egui::DragValue::new(&mut self.grid.cell_height)
+                                    .range(1..=height)
+                                    .suffix("px"),
+                            );
+                        });
+                        ui.end_row();
+
+                        ui.label(t!("dialog.import_sprite_sheet.margin"));
+                        ui.add(
+                            egui::DragValue::new(&mut self.grid.margin)
+                                .range(0..=width.min(height))
+                                .suffix("px"),
+                        );
+                        ui.end_row();
+
+                        ui.label(t!("dialog.import_sprite_sheet.spacing"));
+                        ui.add(
+                            egui::DragValue::new(&mut self.grid.spacing)
+                                .range(0..=width.max(height))
+                                .suffix("px"),
+                        );
+                        ui.end_row();
+
+                        ui.label("");
+                        ui.checkbox(
+                            &mut self.skip_empty,
+                            t!("dialog.import_sprite_sheet.skip_empty"),
+                        );
+                        ui.end_row();
+                    });
+
+                let (columns, rows) = self.grid.dimensions(width, height);
+                ui.add_space(4.0);
+                ui.label(t!(
+                    "dialog.import_sprite_sheet.cells",
+                    columns = columns,
+                    rows = rows
+                ));
+
+                // -- Footer: Import / Cancel --
+                ui.add_space(4.0);
+                accent_separator(ui, &colors);
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(t!("common.cancel")).clicked() {
+                            result = DialogResult::Cancel;
+                        }
+                        let import_btn = egui::Button::new(
+                            egui::RichText::new(format!(
+                                "  {}  ",
+                                t!("dialog.import_sprite_sheet.import")
+                            ))
+                            .color(contrast_text_color(colors.accent))
+                            .strong(),
+                        )
+                        .fill(colors.accent);
+                        if ui.add_enabled(columns * rows > 0, import_btn).clicked() {
+                            result = DialogResult::Ok(());
+                        }
+                    });
+                });
+            });
+
+        if matches!(result, DialogResult::Open) && ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            result = DialogResult::Cancel;
+        }
+        result
+    }
+}
@@ -0,0 +1,338 @@
+use crate::ops::page_setup::{Orientation, PageScaling, PageSetup, PaperSize};
+
+/// What the Page Setup dialog was confirmed with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PageSetupAction {
+    Print,
+    ExportPdf,
+}
+
+pub struct PageSetupDialog {
+    pub setup: PageSetup,
+    /// Flattened document the layout previews.
+    pub image: std::sync::Arc<::image::RgbaImage>,
+    preview_page: usize,
+    preview_texture: Option<egui::TextureHandle>,
+    /// Setup and page the texture was rendered for.
+    preview_key: Option<(PageSetup, usize)>,
+}
+
+impl PageSetupDialog {
+    pub fn new(setup: PageSetup, image: ::image::RgbaImage) -> Self {
+        Self {
+            setup,
+            image: std::sync::Arc::new(image),
+            preview_page: 0,
+            preview_texture: None,
+            preview_key: None,
+        }
+    }
+
+    fn refresh_preview(&mut self, ctx: &egui::Context, page_count: usize) {
+        self.preview_page = self.preview_page.min(page_count.saturating_sub(1));
+        let key = (self.setup.clone(), self.preview_page);
+        if self.preview_key.as_ref() == Some(&key) {
+            return;
+        }
+        let Ok(pages) = self.setup.layout(self.image.width(), self.image.height()) else {
+            self.preview_texture = None;
+            return;
+        };
+        let Some(page) = pages.get(self.preview_page) else {
+            return;
+        };
+        // Longest paper side renders at 220 px.
+        let scale = 220.0 / page.page_size.0.max(page.page_size.1);
+        let rendered = crate::ops::page_setup::render_page(&self.image, &self.setup, page, scale);
+        let color_image = egui::ColorImage::from_rgba_unmultiplied(
+            [rendered.width() as usize, rendered.height() as usize],
+            rendered.as_raw(),
+        );
+        self.preview_texture = Some(ctx.load_texture(
+            "page_setup_preview",
+            color_image,
+            egui::TextureOptions::LINEAR,
+        ));
+        self.preview_key = Some(key);
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> DialogResult<PageSetupAction> {
+        let mut result = DialogResult::Open;
+        let colors = DialogColors::from_ctx(ctx);
+        let layout = self.setup.layout(self.image.width(), self.image.height());
+        let page_count = layout.as_ref().map_or(0, Vec::len);
+        self.refresh_preview(ctx, page_count);
+
+        egui::Window::new("dialog_page_setup")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .default_pos(egui::pos2(
+                ctx.content_rect().center().x - 280.0,
+                ctx.content_rect().center().y - 220.0,
+            ))
+            .show(ctx, |ui| {
+                ui.set_min_width(560.0);
+
+                if paint_dialog_header(ui, &colors, "\u{1F5A8}", &t!("dialog.page_setup")) {
+                    result = DialogResult::Cancel;
+                }
+                ui.add_space(4.0);
+
+                ui.horizontal_top(|ui| {
+                    ui.vertical(|ui| {
+                        ui.set_width(320.0);
+                        self.show_paper_controls(ui, &colors);
+                    });
+                    ui.add_space(8.0);
+                    ui.vertical(|ui| {
+                        ui.set_width(220.0);
+                        self.show_preview(ui, &colors, &layout, page_count);
+                    });
+                });
+
+                // -- Footer: Export PDF / Print / Cancel --
+                ui.add_space(4.0);
+                accent_separator(ui, &colors);
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(t!("common.cancel")).clicked() {
+                            result = DialogResult::Cancel;
+                        }
+                        let print_btn = egui::Button::new(
+                            egui::RichText::new(format!("  {}  ", t!("dialog.page_setup.print")))
+                                .color(contrast_text_color(colors.accent))
+                                .strong(),
+                        )
+                        .fill(colors.accent);
+                        let valid = page_count > 0;
+                        if ui.add_enabled(valid, print_btn).clicked() {
+                            result = DialogResult::Ok(PageSetupAction::Print);
+                        }
+                        if ui
+                            .add_enabled(
+                                valid,
+                                egui::Button::new(t!("dialog.page_setup.export_pdf")),
+                            )
+                            .clicked()
+                        {
+                            result = DialogResult::Ok(PageSetupAction::ExportPdf);
+                        }
+                    });
+                });
+            });
+
+        if matches!(result, DialogResult::Open) && ctx.input(|i| i.key_pressed(egui::Key::Escape))
+        {
+            result = DialogResult::Cancel;
+        }
+        result
+    }
+
+    fn show_paper_controls(&mut self, ui: &mut egui::Ui, colors: &DialogColors) {
+        let setup = &mut self.setup;
+
+        section_label(ui, colors, &t!("dialog.page_setup.paper"));
+        egui::Grid::new("page_setup_paper")
+            .num_columns(2)
+            .min_col_width(90.0)
+            .spacing([8.0, 5.0])
+            .show(ui, |ui| {
+                ui.label(t!("dialog.page_setup.size"));
+                egui::ComboBox::from_id_salt("page_setup_paper_size")
+                    .width(200.0)
+                    .selected_text(setup.paper.label())
+                    .show_ui(ui, |ui| {
+                        for paper in PaperSize::ALL {
+                            ui.selectable_value(&mut setup.paper, paper, paper.label());
+                        }
+                    });
+                ui.end_row();
+
+                if setup.paper == PaperSize::Custom {
+                    ui.label(t!("dialog.resize_image.width"));
+                    numeric_field_with_buttons(
+                        ui,
+                        &mut setup.custom_size_mm.0,
+                        0.5,
+                        10.0..=5000.0,
+                        "mm",
+                        5.0,
+                    );
+                    ui.end_row();
+                    ui.label(t!("dialog.resize_image.height"));
+                    numeric_field_with_buttons(
+                        ui,
+                        &mut setup.custom_size_mm.1,
+                        0.5,
+                        10.0..=5000.0,
+                        "mm",
+                        5.0,
+                    );
+                    ui.end_row();
+                }
+
+                ui.label(t!("dialog.page_setup.orientation"));
+                ui.horizontal(|ui| {
+                    ui.selectable_value(
+                        &mut setup.orientation,
+                        Orientation::Portrait,
+                        t!("dialog.page_setup.portrait"),
+                    );
+                    ui.selectable_value(
+                        &mut setup.orientation,
+                        Orientation::Landscape,
+                        t!("dialog.page_setup.landscape"),
+                    );
+                });
+                ui.end_row();
+            });
+
+        accent_separator(ui, colors);
+        section_label(ui, colors, &t!("dialog.page_setup.margins"));
+        egui::Grid::new("page_setup_margins")
+            .num_columns(4)
+            .spacing([6.0, 5.0])
+            .show(ui, |ui| {
+                let labels = [
+                    t!("dialog.page_setup.top"),
+                    t!("dialog.page_setup.right"),
+                    t!("dialog.page_setup.bottom"),
+                    t!("dialog.page_setup.left"),
+                ];
+                for (i, label) in labels.iter().enumerate() {
+                    ui.label(label.as_str());
+                    ui.add(
+                        egui::DragValue::new(&mut setup.margins_mm[i])
+                            .speed(0.5)
+                            .range(0.0..=500.0)
+                            .suffix(" mm"),
+                    );
+                    if i % 2 == 1 {
+                        ui.end_row();
+                    }
+                }
+            });
+
+        accent_separator(ui, colors);
+        section_label(ui, colors, &t!("dialog.page_setup.placement"));
+        egui::Grid::new("page_setup_placement")
+            .num_columns(2)
+            .min_col_width(90.0)
+            .spacing([8.0, 5.0])
+            .show(ui, |ui| {
+                ui.label(t!("dialog.page_setup.scaling"));
+                egui::ComboBox::from_id_salt("page_setup_scaling")
+                    .width(200.0)
+                    .selected_text(setup.scaling.label())
+                    .show_ui(ui, |ui| {
+                        for scaling in PageScaling::ALL {
+                            ui.selectable_value(&mut setup.scaling, scaling, scaling.label());
+                        }
+                    });
+                ui.end_row();
+
+                if matches!(setup.scaling, PageScaling::Dpi | PageScaling::Tile) {
+                    ui.label(t!("dialog.svg_import.resolution"));
+                    numeric_field_with_buttons(ui, &mut setup.dpi, 1.0, 1.0..=2400.0, " DPI", 25.0);
+                    ui.end_row();
+                }
+
+                ui.label(t!("dialog.page_setup.bleed"));
+                numeric_field_with_buttons(ui, &mut setup.bleed_mm, 0.1, 0.0..=50.0, "mm", 1.0);
+                ui.end_row();
+
+                ui.label("");
+                ui.checkbox(&mut setup.crop_marks, t!("dialog.page_setup.crop_marks"));
+                ui.end_row();
+            });
+
+        accent_separator(ui, colors);
+        section_label(ui, colors, &t!("dialog.page_setup.pdf"));
+        ui.horizontal(|ui| {
+            let mut use_jpeg = setup.jpeg_quality.is_some();
+            if ui
+                .checkbox(&mut use_jpeg, t!("dialog.page_setup.jpeg"))
+                .changed()
+            {
+                setup.jpeg_quality = use_jpeg.then_some(90);
+            }
+            if let Some(quality) = &mut setup.jpeg_quality {
+                let mut q = *quality as f32;
+                if numeric_field_with_buttons(ui, &mut q, 0.5, 1.0..=100.0, "", 5.0) {
+                    *quality = q.round() as u8;
+                }
+            }
+        });
+    }
+
+    fn show_preview(
+        &mut self,
+        ui: &mut egui::Ui,
+        colors: &DialogColors,
+        layout: &Result<Vec<crate::ops::page_setup::PageLayout>, String>,
+        page_count: usize,
+    ) {
+        section_label(ui, colors, &t!("dialog.page_setup.preview"));
+        let frame = egui::Vec2::splat(220.0);
+        let (rect, _) = ui.allocate_exact_size(frame, Sense::hover());
+        ui.painter().rect_filled(rect, 4.0, colors.accent_faint);
+        if let (Some(texture), Ok(pages)) = (&self.preview_texture, layout) {
+            let size = texture.size_vec2();
+            let paper = Rect::from_center_size(rect.center(), size);
+            ui.painter().image(
+                texture.id(),
+                paper,
+                Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                Color32::WHITE,
+            );
+            // Margin guides
+            if let Some(page) = pages.get(self.preview_page) {
+                let scale = size.x as f64 / page.page_size.0;
+                let area = self.setup.printable_rect();
+                let guide = Rect::from_min_max(
+                    paper.min + Vec2::new((area.x0 * scale) as f32, (area.y0 * scale) as f32),
+                    paper.min + Vec2::new((area.x1 * scale) as f32, (area.y1 * scale) as f32),
+                );
+                ui.painter().rect_stroke(
+                    guide,
+                    0.0,
+                    Stroke::new(1.0, colors.accent.gamma_multiply(0.6)),
+                    egui::StrokeKind::Inside,
+                );
+            }
+        }
+
+        ui.add_space(4.0);
+        match layout {
+            Ok(_) => {
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(self.preview_page > 0, egui::Button::new("\u{25C0}"))
+                        .clicked()
+                    {
+                        self.preview_page -= 1;
+                    }
+                    ui.label(
+                        t!("dialog.page_setup.page_of")
+                            .replace("{0}", &(self.preview_page + 1).to_string())
+                            .replace("{1}", &page_count.to_string()),
+                    );
+                    if ui
+                        .add_enabled(
+                            self.preview_page + 1 < page_count,
+                            egui::Button::new("\u{25B6}"),
+                        )
+                        .clicked()
+                    {
+                        self.preview_page += 1;
+                    }
+                });
+            }
+            Err(e) => {
+                ui.label(egui::RichText::new(e).color(Color32::from_rgb(220, 60, 60)));
+            }
+        }
+    }
+}
@@ -10,8 +10,11 @@ pub struct LayerTransformDialog {
     pub offset_y: f32,
     /// Snapshot of original layer pixels for preview restore.
     pub original_pixels: Option<TiledImage>,
-    /// Pre-flattened original pixels (avoids re-flattening every frame).
-    pub original_flat: Option<image::RgbaImage>,
+    /// Snapshot of the original off-canvas content for preview restore.
+    pub original_off_canvas: Option<crate::canvas::OffCanvasPixels>,
+    /// Pre-flattened full extent of the original layer (avoids re-flattening
+    /// every frame), with its top-left in canvas coordinates.
+    pub original_flat: Option<((i32, i32), image::RgbaImage)>,
     /// Layer index being transformed.
     pub layer_idx: usize,
     /// Live preview toggle.
@@ -35,7 +38,8 @@ impl LayerTransformDialog {
     pub fn new(state: &CanvasState) -> Self {
         let idx = state.active_layer_index;
         let original = state.layers.get(idx).map(|l| l.pixels.clone());
-        let flat = state.layers.get(idx).map(|l| l.pixels.to_rgba_image());
+        let original_off_canvas = state.layers.get(idx).and_then(|l| l.off_canvas.clone());
+        let flat = state.layers.get(idx).map(|l| l.extent_rgba());
         Self {
             rotation_z: 0.0,
             rotation_x: 0.0,
@@ -44,6 +48,7 @@ impl LayerTransformDialog {
             offset_x: 0.0,
             offset_y: 0.0,
             original_pixels: original,
+            original_off_canvas,
             original_flat: flat,
             layer_idx: idx,
             live_preview: true,
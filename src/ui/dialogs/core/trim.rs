@@ -0,0 +1,110 @@
+use crate::ops::trim::{TrimBasis, TrimOptions, trim_bounds};
+
+/// Canvas > Trim: crop away transparent or single-colour margins.
+pub struct TrimDialog {
+    pub options: TrimOptions,
+    /// The visible image the trim is measured on.
+    composite: image::RgbaImage,
+    /// `(options, bounds)` of the last measurement.
+    measured: Option<(TrimOptions, Option<(u32, u32, u32, u32)>)>,
+}
+
+impl TrimDialog {
+    pub fn new(state: &CanvasState) -> Self {
+        Self {
+            options: TrimOptions::default(),
+            composite: state.composite(),
+            measured: None,
+        }
+    }
+
+    /// Size of the canvas after trimming, or `None` when nothing is left.
+    fn result_bounds(&mut self) -> Option<(u32, u32, u32, u32)> {
+        match self.measured {
+            Some((options, bounds)) if options == self.options => bounds,
+            _ => {
+                let bounds = trim_bounds(&self.composite, &self.options);
+                self.measured = Some((self.options, bounds));
+                bounds
+            }
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> DialogResult<TrimOptions> {
+        let mut result = DialogResult::Open;
+        let colors = DialogColors::from_ctx(ctx);
+
+        egui::Window::new("dialog_trim")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .default_pos(egui::pos2(
+                ctx.content_rect().center().x - 160.0,
+                ctx.content_rect().center().y - 130.0,
+            ))
+            .show(ctx, |ui| {
+                ui.set_min_width(320.0);
+
+                if paint_dialog_header(ui, &colors, "\u{2702}", &t!("dialog.trim")) {
+                    result = DialogResult::Cancel;
+                }
+                ui.add_space(4.0);
+
+                section_label(ui, &colors, &t!("dialog.trim.based_on"));
+                for basis in [
+                    TrimBasis::Transparent,
+                    TrimBasis::TopLeftColor,
+                    TrimBasis::BottomRightColor,
+                ] {
+                    ui.radio_value(&mut self.options.basis, basis, basis.label());
+                }
+                ui.add_space(4.0);
+
+                egui::Grid::new("trim_grid")
+                    .num_columns(2)
+                    .min_col_width(90.0)
+                    .spacing([8.0, 5.0])
+                    .show(ui, |ui| {
+                        ui.label(t!("dialog.trim.tolerance"));
+                        ui.add(egui::Slider::new(&mut self.options.tolerance, 0..=255));
+                        ui.end_row();
+                    });
+
+                section_label(ui, &colors, &t!("dialog.trim.trim_away"));
+                ui.horizontal(|ui| {
+                    let sides = &mut self.options.sides;
+                    ui.checkbox(&mut sides.top, t!("dialog.trim.top"));
+                    ui.checkbox(&mut sides.bottom, t!("dialog.trim.bottom"));
+                    ui.checkbox(&mut sides.left, t!("dialog.trim.left"));
+                    ui.checkbox(&mut sides.right, t!("dialog.trim.right"));
+                });
+
+                let (width, height) = self.composite.dimensions();
+                let bounds = self.result_bounds();
+                ui.add_space(4.0);
+                match bounds {
+                    Some((_, _, w, h)) => ui.weak(t!(
+                        "dialog.trim.result",
+                        from_w = width,
+                        from_h = height,
+                        to_w = w,
+                        to_h = h
+                    )),
+                    None => ui.weak(t!("dialog.trim.nothing_left")),
+                };
+
+                let (ok, cancel) = dialog_footer(ui, &colors);
+                if ok && bounds.is_some() && self.options.sides.any() {
+                    result = DialogResult::Ok(self.options);
+                }
+                if cancel {
+                    result = DialogResult::Cancel;
+                }
+            });
+
+        if matches!(result, DialogResult::Open) && ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            result = DialogResult::Cancel;
+        }
+        result
+    }
+}
@@ -102,9 +102,12 @@ impl ToolsPanel {
         if changed && let Some(active_layer) = canvas_state.layers.get_mut(target_layer_idx) {
             let w = canvas_state.width.min(warped.width());
             let h = canvas_state.height.min(warped.height());
+            let locks = active_layer.locks;
             for y in 0..h {
                 for x in 0..w {
-                    active_layer.pixels.put_pixel(x, y, *warped.get_pixel(x, y));
+                    let before = *active_layer.pixels.get_pixel(x, y);
+                    let after = locks.filter_pixel(before, *warped.get_pixel(x, y));
+                    active_layer.pixels.put_pixel(x, y, after);
                 }
             }
             active_layer.invalidate_lod();
@@ -1049,6 +1052,7 @@ impl ToolsPanel {
         }
 
         let blend_mode = self.properties.blending_mode;
+        let linear = canvas_state.linear_blending;
         let stroke_event = self.stroke_tracker.finish(canvas_state);
 
         if let Some(ref preview) = canvas_state.preview_layer
@@ -1077,7 +1081,7 @@ impl ToolsPanel {
                             continue;
                         }
                         let dst = active_layer.pixels.get_pixel_mut(gx, gy);
-                        *dst = CanvasState::blend_pixel_static(*dst, src, blend_mode, 1.0);
+                        *dst = CanvasState::blend_pixel_at(*dst, src, blend_mode, 1.0, gx, gy, linear);
                     }
                 }
             }
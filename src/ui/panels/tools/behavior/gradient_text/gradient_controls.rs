@@ -318,6 +318,19 @@ impl ToolsPanel {
             self.gradient_state.repeat = repeat;
             self.gradient_state.preview_dirty = true;
         }
+        let mut dither = self.gradient_state.dither;
+        if ui
+            .checkbox(&mut dither, t!("ctx.gradient.dither"))
+            .on_hover_text(t!("ctx.gradient.dither_tooltip"))
+            .changed()
+        {
+            self.gradient_state.dither = dither;
+            self.gradient_state.preview_dirty = true;
+        }
+
+        ui.separator();
+
+        self.show_gradient_library_picker(ui);
 
         ui.separator();
 
@@ -325,19 +338,131 @@ impl ToolsPanel {
         self.show_gradient_bar(ui, assets);
     }
 
+    /// Saved gradients: click to load, right-click to update or delete, plus
+    /// saving the current stops, random noise gradients and `.grd`/`.ggr` import.
+    fn show_gradient_library_picker(&mut self, ui: &mut egui::Ui) {
+        use crate::gradient::{Gradient, upsert_gradient};
+
+        let popup_id = ui.make_persistent_id("gradient_library_popup");
+        let btn = ui
+            .button(t!("ctx.gradient.library"))
+            .on_hover_text(t!("ctx.gradient.library_tooltip"));
+        if btn.clicked() {
+            egui::Popup::toggle_id(ui.ctx(), popup_id);
+        }
+
+        egui::Popup::new(
+            popup_id,
+            ui.ctx().clone(),
+            egui::PopupAnchor::from(&btn),
+            ui.layer_id(),
+        )
+        .open_memory(None::<egui::SetOpenCommand>)
+        .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+        .show(|ui| {
+            ui.set_min_width(220.0);
+
+            if self.gradient_library.is_empty() {
+                ui.label(egui::RichText::new(t!("ctx.gradient.library_empty")).weak());
+            }
+            let current = self.gradient_state.to_gradient("");
+            let mut apply_idx = None;
+            let mut update_idx = None;
+            let mut delete_idx = None;
+            egui::ScrollArea::vertical()
+                .max_height(260.0)
+                .show(ui, |ui| {
+                    for (i, gradient) in self.gradient_library.iter().enumerate() {
+                        let selected = gradient.color_stops == current.color_stops
+                            && gradient.opacity_stops == current.opacity_stops;
+                        let resp = ui
+                            .horizontal(|ui| {
+                                paint_gradient_swatch(ui, gradient, egui::vec2(48.0, 12.0));
+                                ui.selectable_label(selected, &gradient.name)
+                            })
+                            .inner;
+                        if resp.clicked() {
+                            apply_idx = Some(i);
+                        }
+                        resp.context_menu(|ui| {
+                            if ui.button(t!("ctx.gradient.update")).clicked() {
+                                update_idx = Some(i);
+                                ui.close();
+                            }
+                            if ui.button(t!("ctx.gradient.delete")).clicked() {
+                                delete_idx = Some(i);
+                                ui.close();
+                            }
+                        });
+                    }
+                });
+            if let Some(i) = apply_idx {
+                let gradient = self.gradient_library[i].clone();
+                self.gradient_state.load_gradient(&gradient);
+            }
+            if let Some(i) = update_idx {
+                let name = self.gradient_library[i].name.clone();
+                self.gradient_library[i] = self.gradient_state.to_gradient(&name);
+                self.gradient_library_changed = true;
+            }
+            if let Some(i) = delete_idx {
+                self.gradient_library.remove(i);
+                self.gradient_library_changed = true;
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.gradient_state.library_name)
+                        .desired_width(120.0)
+                        .hint_text(t!("ctx.gradient.name_hint")),
+                );
+                let name = self.gradient_state.library_name.trim().to_string();
+                if ui
+                    .add_enabled(!name.is_empty(), egui::Button::new(t!("ctx.gradient.save")))
+                    .on_hover_text(t!("ctx.gradient.save_tooltip"))
+                    .clicked()
+                {
+                    upsert_gradient(
+                        &mut self.gradient_library,
+                        self.gradient_state.to_gradient(&name),
+                    );
+                    self.gradient_library_changed = true;
+                    self.gradient_state.library_name.clear();
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui
+                    .button(t!("ctx.gradient.random_noise"))
+                    .on_hover_text(t!("ctx.gradient.random_noise_tooltip"))
+                    .clicked()
+                {
+                    let seed = ui.ctx().input(|i| i.time.to_bits() as u32);
+                    self.gradient_state
+                        .load_gradient(&Gradient::noise("", seed, 0.5, 12));
+                }
+                if ui.button(t!("ctx.gradient.import")).clicked() {
+                    self.pending_gradient_import = true;
+                    ui.close();
+                }
+            });
+        });
+    }
+
     /// Draw the interactive gradient bar with draggable color stops.
     fn show_gradient_bar(&mut self, ui: &mut egui::Ui, assets: &Assets) {
         let bar_width = 200.0f32;
         let bar_height = 12.0f32;
         let stop_radius = 4.0f32;
         let h_pad = stop_radius + 2.0; // horizontal padding so edge handles aren't clipped
+        let handle_band = stop_radius + 3.0; // opacity stops above the bar, color stops below
 
         let (response, painter) = ui.allocate_painter(
-            egui::vec2(bar_width + h_pad * 2.0, bar_height + stop_radius + 4.0),
+            egui::vec2(bar_width + h_pad * 2.0, bar_height + handle_band * 2.0),
             egui::Sense::click_and_drag(),
         );
         let bar_rect = egui::Rect::from_min_size(
-            response.rect.min + egui::vec2(h_pad, 1.0),
+            response.rect.min + egui::vec2(h_pad, handle_band),
             egui::vec2(bar_width, bar_height),
         );
 
@@ -389,6 +514,29 @@ impl ToolsPanel {
             egui::StrokeKind::Middle,
         );
 
+        // Draw opacity stop handles (above the bar, gray = opacity)
+        let opacity_y = bar_rect.min.y - stop_radius - 1.0;
+        for (i, stop) in self.gradient_state.opacity_stops.iter().enumerate() {
+            let stop_x = bar_rect.min.x + stop.position * bar_width;
+            let is_selected = self.gradient_state.selected_opacity_stop == Some(i);
+            painter.add(egui::Shape::convex_polygon(
+                vec![
+                    Pos2::new(stop_x, bar_rect.min.y - 1.0),
+                    Pos2::new(stop_x - stop_radius, opacity_y),
+                    Pos2::new(stop_x + stop_radius, opacity_y),
+                ],
+                Color32::from_gray(stop.opacity),
+                egui::Stroke::new(
+                    if is_selected { 2.0 } else { 1.0 },
+                    if is_selected {
+                        Color32::WHITE
+                    } else {
+                        Color32::DARK_GRAY
+                    },
+                ),
+            ));
+        }
+
         // Draw stop handles
         let stop_y = bar_rect.max.y + stop_radius + 1.0;
         for (i, stop) in self.gradient_state.stops.iter().enumerate() {
@@ -423,8 +571,28 @@ impl ToolsPanel {
         let pointer_pos = response.interact_pointer_pos();
         if let Some(pp) = pointer_pos {
             let t_at_pointer = ((pp.x - bar_rect.min.x) / bar_width).clamp(0.0, 1.0);
+            let in_opacity_band = pp.y < bar_rect.min.y;
 
-            if response.drag_started() {
+            if response.drag_started() && in_opacity_band {
+                let hit = self.gradient_state.opacity_stops.iter().position(|s| {
+                    (pp.x - (bar_rect.min.x + s.position * bar_width)).abs() < stop_radius * 2.0
+                });
+                self.gradient_state.selected_stop = None;
+                if let Some(idx) = hit {
+                    self.gradient_state.selected_opacity_stop = Some(idx);
+                } else {
+                    let opacity = self.gradient_state.to_gradient("").sample(t_at_pointer)[3];
+                    self.gradient_state
+                        .opacity_stops
+                        .push(crate::gradient::OpacityStop::new(t_at_pointer, opacity));
+                    self.gradient_state.selected_opacity_stop =
+                        Some(self.gradient_state.opacity_stops.len() - 1);
+                    self.gradient_state.preset = GradientPreset::Custom;
+                    self.gradient_state.lut_dirty = true;
+                    self.gradient_state.preview_dirty = true;
+                }
+            } else if response.drag_started() {
+                self.gradient_state.selected_opacity_stop = None;
                 // Check if clicking near an existing stop
                 let mut hit_stop: Option<usize> = None;
                 for (i, stop) in self.gradient_state.stops.iter().enumerate() {
@@ -458,6 +626,17 @@ impl ToolsPanel {
                 }
             }
 
+            // Drag selected opacity stop (order is irrelevant — stops are sorted when sampled)
+            if response.dragged()
+                && let Some(sel) = self.gradient_state.selected_opacity_stop
+                && sel < self.gradient_state.opacity_stops.len()
+            {
+                self.gradient_state.opacity_stops[sel].position = t_at_pointer;
+                self.gradient_state.preset = GradientPreset::Custom;
+                self.gradient_state.lut_dirty = true;
+                self.gradient_state.preview_dirty = true;
+            }
+
             // Drag selected stop
             if response.dragged()
                 && let Some(sel) = self.gradient_state.selected_stop
@@ -485,8 +664,31 @@ impl ToolsPanel {
         }
 
         // Right-click to delete a stop (if more than 2)
-        if response.secondary_clicked()
-            && let Some(pp) = ui.input(|i| i.pointer.latest_pos())
+        let right_click_pos = if response.secondary_clicked() {
+            ui.input(|i| i.pointer.latest_pos())
+        } else {
+            None
+        };
+        if let Some(pp) = right_click_pos
+            && pp.y < bar_rect.min.y
+            && self.gradient_state.opacity_stops.len() > 2
+        {
+            let closest = self
+                .gradient_state
+                .opacity_stops
+                .iter()
+                .enumerate()
+                .map(|(i, s)| (i, (pp.x - (bar_rect.min.x + s.position * bar_width)).abs()))
+                .filter(|&(_, d)| d < stop_radius * 3.0)
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((idx, _)) = closest {
+                self.gradient_state.opacity_stops.remove(idx);
+                self.gradient_state.selected_opacity_stop = None;
+                self.gradient_state.preset = GradientPreset::Custom;
+                self.gradient_state.lut_dirty = true;
+                self.gradient_state.preview_dirty = true;
+            }
+        } else if let Some(pp) = right_click_pos
             && self.gradient_state.stops.len() > 2
         {
             let mut closest_idx: Option<usize> = None;
@@ -572,6 +774,8 @@ impl ToolsPanel {
                 }
             });
 
+            self.show_stop_midpoint(ui, false, sel);
+
             // Hint for the user
             ui.label(
                 egui::RichText::new(t!("ctx.gradient.color_hint"))
@@ -579,6 +783,94 @@ impl ToolsPanel {
                     .small(),
             );
         }
+
+        // Opacity + midpoint edit for the selected opacity stop
+        if let Some(sel) = self.gradient_state.selected_opacity_stop
+            && sel < self.gradient_state.opacity_stops.len()
+        {
+            ui.separator();
+            ui.label(t!("ctx.gradient.stop_opacity"));
+            let mut pct = (self.gradient_state.opacity_stops[sel].opacity as f32 / 2.55).round();
+            if ui
+                .add(
+                    egui::DragValue::new(&mut pct)
+                        .range(0.0..=100.0)
+                        .suffix("%"),
+                )
+                .changed()
+            {
+                self.gradient_state.opacity_stops[sel].opacity = (pct * 2.55).round() as u8;
+                self.gradient_state.preset = GradientPreset::Custom;
+                self.gradient_state.lut_dirty = true;
+                self.gradient_state.preview_dirty = true;
+            }
+            self.show_stop_midpoint(ui, true, sel);
+        }
+    }
+
+    /// Midpoint control for the selected color (`opacity == false`) or opacity stop.
+    /// Hidden for the last stop, which has no span after it.
+    fn show_stop_midpoint(&mut self, ui: &mut egui::Ui, opacity: bool, sel: usize) {
+        let positions: Vec<f32> = if opacity {
+            self.gradient_state
+                .opacity_stops
+                .iter()
+                .map(|s| s.position)
+                .collect()
+        } else {
+            self.gradient_state
+                .stops
+                .iter()
+                .map(|s| s.position)
+                .collect()
+        };
+        if positions.iter().all(|&p| p <= positions[sel]) {
+            return;
+        }
+        let midpoint = if opacity {
+            &mut self.gradient_state.opacity_stops[sel].midpoint
+        } else {
+            &mut self.gradient_state.stops[sel].midpoint
+        };
+        ui.label(t!("ctx.gradient.midpoint"));
+        let mut pct = (*midpoint * 100.0).round();
+        if ui
+            .add(egui::DragValue::new(&mut pct).range(5.0..=95.0).suffix("%"))
+            .on_hover_text(t!("ctx.gradient.midpoint_tooltip"))
+            .changed()
+        {
+            *midpoint = pct / 100.0;
+            self.gradient_state.preset = GradientPreset::Custom;
+            self.gradient_state.lut_dirty = true;
+            self.gradient_state.preview_dirty = true;
+        }
     }
 }
 
+/// Small swatch of `gradient`, used by the library picker.
+fn paint_gradient_swatch(
+    ui: &mut egui::Ui,
+    gradient: &crate::gradient::Gradient,
+    size: egui::Vec2,
+) {
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, Color32::from_gray(200));
+    let steps = size.x.max(1.0) as usize;
+    let lut = gradient.build_lut();
+    for i in 0..steps {
+        let [r, g, b, a] = lut[i * 255 / (steps - 1).max(1)];
+        let x = rect.min.x + i as f32;
+        painter.rect_filled(
+            egui::Rect::from_min_size(Pos2::new(x, rect.min.y), egui::vec2(1.0, size.y)),
+            0.0,
+            Color32::from_rgba_unmultiplied(r, g, b, a),
+        );
+    }
+    painter.rect_stroke(
+        rect,
+        1.0,
+        egui::Stroke::new(1.0, Color32::DARK_GRAY),
+        egui::StrokeKind::Middle,
+    );
+}
@@ -540,6 +540,7 @@ impl ToolsPanel {
         let stroke_event = self.stroke_tracker.finish(canvas_state);
 
         let blend_mode = self.properties.blending_mode;
+        let linear = canvas_state.linear_blending;
         let selection_mask = canvas_state.selection_mask.clone();
         let target_layer_idx = self
             .text_state
@@ -570,7 +571,7 @@ impl ToolsPanel {
                             continue;
                         }
                         let dst = active_layer.pixels.get_pixel_mut(gx, gy);
-                        *dst = CanvasState::blend_pixel_static(*dst, src, blend_mode, 1.0);
+                        *dst = CanvasState::blend_pixel_at(*dst, src, blend_mode, 1.0, gx, gy, linear);
                     }
                 }
             }
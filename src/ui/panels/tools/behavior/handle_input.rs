@@ -466,11 +466,14 @@ impl ToolsPanel {
             pressure
         });
         if let Some(p) = touch_pressure {
-            self.tool_state.current_pressure = p.clamp(0.0, 1.0);
+            self.set_pen_pressure(p);
         } else if !is_primary_down && !is_secondary_down {
             // Reset to full pressure when not actively drawing
             self.tool_state.current_pressure = 1.0;
         }
+        self.set_pen_tilt(
+            crate::pen_probe::tilt_degrees().map(|(x, y)| PenTilt::from_tilt_degrees(x, y)),
+        );
 
         match self.active_tool {
             Tool::Brush | Tool::Eraser | Tool::Pencil | Tool::Line => self.handle_stroke_tools_input(
@@ -598,7 +601,8 @@ impl ToolsPanel {
             | Tool::Lasso
             | Tool::Zoom
             | Tool::Pan
-            | Tool::PerspectiveCrop => self.handle_utility_navigation_input(
+            | Tool::PerspectiveCrop
+            | Tool::Slice => self.handle_utility_navigation_input(
                 ui,
                 canvas_state,
                 canvas_pos,
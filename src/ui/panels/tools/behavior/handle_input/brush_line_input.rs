@@ -167,6 +167,9 @@ impl ToolsPanel {
                             self.tool_state.last_precise_pos = Some(Pos2::new(cf.0, cf.1));
                             self.tool_state.distance_remainder = 0.0;
                             self.tool_state.smooth_pos = Some(Pos2::new(cf.0, cf.1));
+                            self.tool_state.stabilized_pos = Some(Pos2::new(cf.0, cf.1));
+                            self.tool_state.stroke_distance = 0.0;
+                            self.tool_state.stroke_segments.clear();
 
                             // Start stroke tracking for Undo/Redo
                             let is_eraser = self.active_tool == Tool::Eraser;
@@ -241,6 +244,7 @@ impl ToolsPanel {
                                 canvas_state.preview_mask_reveal = is_eraser;
                                 canvas_state.preview_is_eraser = false;
                             }
+                            self.begin_mixer_stroke(canvas_state);
                         }
 
                         let is_eraser = self.active_tool == Tool::Eraser;
@@ -263,6 +267,22 @@ impl ToolsPanel {
                             vec![cf]
                         };
 
+                        // Stabilizer (lazy mouse / pulled string) runs on the raw
+                        // samples; when active it replaces the EMA smoothing below.
+                        let stabilizing =
+                            self.properties.dynamics.stabilizer != StabilizerMode::Off;
+                        let positions: Vec<(f32, f32)> = if stabilizing {
+                            positions
+                                .iter()
+                                .map(|&(x, y)| {
+                                    let p = self.stabilize_position(Pos2::new(x, y), zoom);
+                                    (p.x, p.y)
+                                })
+                                .collect()
+                        } else {
+                            positions
+                        };
+
                         // ============================================================
                         // SPEED-ADAPTIVE EMA SMOOTHING
                         // Applies an exponential moving average to each raw
@@ -284,7 +304,9 @@ impl ToolsPanel {
                             let mut result = Vec::with_capacity(positions.len());
                             for &pos in &positions {
                                 let raw = Pos2::new(pos.0, pos.1);
-                                let smoothed = if let Some(prev) = self.tool_state.smooth_pos {
+                                let smoothed = if stabilizing {
+                                    raw
+                                } else if let Some(prev) = self.tool_state.smooth_pos {
                                     let dx = raw.x - prev.x;
                                     let dy = raw.y - prev.y;
                                     let dist = (dx * dx + dy * dy).sqrt();
@@ -306,6 +328,22 @@ impl ToolsPanel {
                             result
                         };
 
+                        // Stroke speed for the velocity dynamics, in screen px/s
+                        let frame_travel: f32 = {
+                            let mut prev = self.tool_state.last_precise_pos;
+                            smoothed_positions
+                                .iter()
+                                .map(|&(x, y)| {
+                                    let p = Pos2::new(x, y);
+                                    let d = prev.map_or(0.0, |q| q.distance(p));
+                                    prev = Some(p);
+                                    d
+                                })
+                                .sum()
+                        };
+                        let now = ui.input(|i| i.time);
+                        self.update_stroke_velocity(frame_travel * zoom, now);
+
                         // Accumulate a single dirty rect for the entire frame
                         let mut frame_dirty_rect = Rect::NOTHING;
 
@@ -321,6 +359,16 @@ impl ToolsPanel {
                             // Also mirror the start position for line drawing
                             let start_precise = self.tool_state.last_precise_pos;
 
+                            // Per-sample dynamics: direction of travel, tilt/velocity mappings
+                            let segment_len =
+                                start_precise.map_or(0.0, |p| p.distance(Pos2::new(pos.0, pos.1)));
+                            if let Some(p) = start_precise
+                                && segment_len > 0.5
+                            {
+                                self.tool_state.stroke_direction = (pos.1 - p.y).atan2(pos.0 - p.x);
+                            }
+                            self.refresh_stamp_dynamics();
+
                             for &mpos in positions_to_draw.iter() {
                                 // CPU-only paint step: lerp from last_precise_pos -> pos
                                 let modified_rect = if self.active_tool == Tool::Pencil {
@@ -392,6 +440,7 @@ impl ToolsPanel {
 
                             // Update last position for next paint step (always track the original, not mirrored)
                             self.tool_state.last_precise_pos = Some(Pos2::new(pos.0, pos.1));
+                            self.tool_state.stroke_distance += segment_len;
                         }
 
                         // Track stroke bounds for undo/redo
@@ -420,6 +469,16 @@ impl ToolsPanel {
                     if self.tool_state.last_pos.is_some() {
                         let is_eraser = self.active_tool == Tool::Eraser;
 
+                        // End taper needs the full stroke length: re-render before commit
+                        if self.active_tool != Tool::Pencil {
+                            self.rerender_stroke_with_end_taper(
+                                canvas_state,
+                                is_eraser,
+                                primary_color_f32,
+                                secondary_color_f32,
+                            );
+                        }
+
                         // Capture "before" NOW (layer still unchanged), then commit
                         // Finish stroke tracking BEFORE commit - this captures "before" from unchanged layer
                         *stroke_event = self.stroke_tracker.finish(canvas_state);
@@ -839,10 +839,8 @@ impl ToolsPanel {
                         self.gradient_state.stops[0] =
                             GradientStop::new(self.gradient_state.stops[0].position, p);
                         let last = self.gradient_state.stops.len() - 1;
-                        self.gradient_state.stops[last] = GradientStop::new(
-                            self.gradient_state.stops[last].position,
-                            [p[0], p[1], p[2], 0],
-                        );
+                        self.gradient_state.stops[last] =
+                            GradientStop::new(self.gradient_state.stops[last].position, p);
                         self.gradient_state.lut_dirty = true;
                     }
                 }
@@ -726,6 +726,146 @@ impl ToolsPanel {
                     }
                 }
             }
+
+            // ================================================================
+            // SLICE TOOL - drag to add a slice, click to pick, right-click to delete
+            // ================================================================
+            Tool::Slice => {
+                let min_drag_screen_px = 4.0;
+                let state = &mut self.slice_state;
+                if state
+                    .selected
+                    .is_some_and(|i| i >= canvas_state.slices.len())
+                {
+                    state.selected = None;
+                }
+                if escape_pressed_global {
+                    state.selected = None;
+                    state.dragging = false;
+                    state.drag_start = None;
+                }
+
+                if is_primary_pressed
+                    && !state.dragging
+                    && let Some(pos_f) = canvas_pos_f32
+                {
+                    let p = Pos2::new(pos_f.0, pos_f.1);
+                    state.drag_start = Some(p);
+                    state.drag_end = Some(p);
+                }
+
+                if is_primary_down
+                    && let Some(s) = state.drag_start
+                    && let Some(pos_f) = canvas_pos_f32
+                {
+                    let end = Pos2::new(pos_f.0, pos_f.1);
+                    state.drag_end = Some(end);
+                    if !state.dragging
+                        && ((end.x - s.x) * zoom)
+                            .abs()
+                            .max(((end.y - s.y) * zoom).abs())
+                            >= min_drag_screen_px
+                    {
+                        state.dragging = true;
+                    }
+                    ui.ctx().request_repaint();
+                }
+
+                // Snap the dragged rectangle to whole pixels inside the canvas.
+                let (w, h) = (canvas_state.width as f32, canvas_state.height as f32);
+                let drag_rect = match (state.dragging, state.drag_start, state.drag_end) {
+                    (true, Some(s), Some(e)) => {
+                        let x0 = s.x.min(e.x).floor().clamp(0.0, w);
+                        let y0 = s.y.min(e.y).floor().clamp(0.0, h);
+                        let x1 = s.x.max(e.x).ceil().clamp(0.0, w);
+                        let y1 = s.y.max(e.y).ceil().clamp(0.0, h);
+                        Some((x0 as u32, y0 as u32, (x1 - x0) as u32, (y1 - y0) as u32))
+                    }
+                    _ => None,
+                };
+
+                if let Some((x, y, rw, rh)) = drag_rect {
+                    let accent = ui.visuals().selection.bg_fill;
+                    let r = Rect::from_min_size(
+                        Pos2::new(
+                            canvas_rect.min.x + x as f32 * zoom,
+                            canvas_rect.min.y + y as f32 * zoom,
+                        ),
+                        Vec2::new(rw as f32 * zoom, rh as f32 * zoom),
+                    );
+                    painter.rect_filled(
+                        r,
+                        0.0,
+                        Color32::from_rgba_unmultiplied(accent.r(), accent.g(), accent.b(), 30),
+                    );
+                    painter.rect_stroke(
+                        r,
+                        0.0,
+                        egui::Stroke::new(1.0, accent),
+                        egui::StrokeKind::Middle,
+                    );
+                    painter.text(
+                        r.right_bottom() + Vec2::new(4.0, 4.0),
+                        egui::Align2::LEFT_TOP,
+                        format!("{} \u{00D7} {}", rw, rh),
+                        egui::FontId::proportional(11.0),
+                        Color32::WHITE,
+                    );
+                }
+
+                if is_primary_released {
+                    if let Some((x, y, rw, rh)) = drag_rect
+                        && rw > 0
+                        && rh > 0
+                    {
+                        let before = canvas_state.slices.clone();
+                        let name = format!("Slice {}", canvas_state.slices.len() + 1);
+                        if canvas_state.add_slices([crate::canvas::Slice::new(name, x, y, rw, rh)])
+                            > 0
+                        {
+                            let index = canvas_state.slices.len() - 1;
+                            state.selected = Some(index);
+                            state.name_edit = canvas_state.slices[index].name.clone();
+                            self.pending_history_commands.push(Box::new(
+                                crate::components::history::SlicesCommand::new(
+                                    "Add Slice",
+                                    before,
+                                    canvas_state.slices.clone(),
+                                ),
+                            ));
+                        }
+                    } else if !state.dragging
+                        && let Some((cx, cy)) = canvas_pos
+                    {
+                        state.selected = canvas_state.slice_at(cx, cy);
+                        state.name_edit = state
+                            .selected
+                            .map(|i| canvas_state.slices[i].name.clone())
+                            .unwrap_or_default();
+                    }
+                    state.dragging = false;
+                    state.drag_start = None;
+                    state.drag_end = None;
+                    ui.ctx().request_repaint();
+                }
+
+                if is_secondary_clicked
+                    && let Some((cx, cy)) = canvas_pos
+                    && let Some(index) = canvas_state.slice_at(cx, cy)
+                {
+                    let before = canvas_state.slices.clone();
+                    canvas_state.slices.remove(index);
+                    self.slice_state.selected = None;
+                    self.pending_history_commands.push(Box::new(
+                        crate::components::history::SlicesCommand::new(
+                            "Delete Slice",
+                            before,
+                            canvas_state.slices.clone(),
+                        ),
+                    ));
+                    ui.ctx().request_repaint();
+                }
+            }
             _ => {}
         }
     }
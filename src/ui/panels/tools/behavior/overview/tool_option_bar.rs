@@ -92,6 +92,9 @@ impl ToolsPanel {
                 Tool::PerspectiveCrop => {
                     self.show_perspective_crop_options(ui);
                 }
+                Tool::Slice => {
+                    self.show_slice_options(ui);
+                }
                 Tool::Gradient => {
                     self.show_gradient_options(ui, assets, primary_color, secondary_color);
                 }
@@ -137,6 +140,116 @@ impl ToolsPanel {
         ui.checkbox(&mut self.move_anti_aliasing, "Anti-aliasing");
     }
 
+    /// Named brush presets: click to load, right-click to update or delete,
+    /// plus saving the current settings and pack import/export.
+    fn show_brush_preset_picker(&mut self, ui: &mut egui::Ui) {
+        use crate::config::brush_presets::{BrushPreset, upsert_brush_preset};
+
+        let popup_id = ui.make_persistent_id("brush_preset_popup");
+        let current = self
+            .brush_presets
+            .iter()
+            .find(|p| p.matches(&self.properties))
+            .map(|p| p.name.clone());
+        let btn = ui
+            .button(current.clone().unwrap_or_else(|| t!("ctx.preset.presets")))
+            .on_hover_text(t!("ctx.preset.tooltip"));
+        if btn.clicked() {
+            egui::Popup::toggle_id(ui.ctx(), popup_id);
+        }
+
+        egui::Popup::new(
+            popup_id,
+            ui.ctx().clone(),
+            egui::PopupAnchor::from(&btn),
+            ui.layer_id(),
+        )
+        .open_memory(None::<egui::SetOpenCommand>)
+        .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+        .show(|ui| {
+            ui.set_min_width(200.0);
+
+            if self.brush_presets.is_empty() {
+                ui.label(egui::RichText::new(t!("ctx.preset.none")).weak());
+            }
+            let mut apply_idx = None;
+            let mut update_idx = None;
+            let mut delete_idx = None;
+            egui::ScrollArea::vertical()
+                .max_height(260.0)
+                .show(ui, |ui| {
+                    for (i, preset) in self.brush_presets.iter().enumerate() {
+                        let selected = current.as_deref() == Some(preset.name.as_str());
+                        let resp = ui.selectable_label(selected, &preset.name);
+                        if resp.clicked() {
+                            apply_idx = Some(i);
+                        }
+                        resp.context_menu(|ui| {
+                            if ui.button(t!("ctx.preset.update")).clicked() {
+                                update_idx = Some(i);
+                                ui.close();
+                            }
+                            if ui.button(t!("ctx.preset.delete")).clicked() {
+                                delete_idx = Some(i);
+                                ui.close();
+                            }
+                        });
+                    }
+                });
+            if let Some(i) = apply_idx {
+                self.brush_presets[i].apply_to(&mut self.properties);
+            }
+            if let Some(i) = update_idx {
+                let name = self.brush_presets[i].name.clone();
+                self.brush_presets[i] = BrushPreset::from_properties(&name, &self.properties);
+                self.brush_presets_changed = true;
+            }
+            if let Some(i) = delete_idx {
+                self.brush_presets.remove(i);
+                self.brush_presets_changed = true;
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.brush_preset_name_input)
+                        .desired_width(120.0)
+                        .hint_text(t!("ctx.preset.name_hint")),
+                );
+                let name = self.brush_preset_name_input.trim().to_string();
+                if ui
+                    .add_enabled(!name.is_empty(), egui::Button::new(t!("ctx.preset.save")))
+                    .on_hover_text(t!("ctx.preset.save_tooltip"))
+                    .clicked()
+                {
+                    upsert_brush_preset(
+                        &mut self.brush_presets,
+                        BrushPreset::from_properties(&name, &self.properties),
+                    );
+                    self.brush_presets_changed = true;
+                    self.brush_preset_name_input.clear();
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.button(t!("ctx.preset.import_pack")).clicked() {
+                    self.pending_brush_preset_action = Some(BrushPresetAction::ImportPack);
+                    ui.close();
+                }
+                if ui
+                    .add_enabled(
+                        !self.brush_presets.is_empty(),
+                        egui::Button::new(t!("ctx.preset.export_pack")),
+                    )
+                    .clicked()
+                {
+                    self.pending_brush_preset_action = Some(BrushPresetAction::ExportPack);
+                    ui.close();
+                }
+            });
+        });
+        ui.separator();
+    }
+
     /// Show brush tip picker dropdown (grid popup with categories, matching shapes tool pattern)
     fn show_brush_tip_picker(&mut self, ui: &mut egui::Ui, assets: &Assets) {
         ui.label("Tip:");
@@ -197,6 +310,14 @@ impl ToolsPanel {
                         self.pending_open_add_brush_tip = true;
                         ui.close();
                     }
+                    if ui
+                        .button(t!("ctx.preset.import_abr"))
+                        .on_hover_text(t!("ctx.preset.import_abr_tooltip"))
+                        .clicked()
+                    {
+                        self.pending_brush_preset_action = Some(BrushPresetAction::ImportAbr);
+                        ui.close();
+                    }
                 });
             });
 
@@ -601,6 +722,7 @@ impl ToolsPanel {
     fn show_brush_options(&mut self, ui: &mut egui::Ui, assets: &Assets) {
         // Brush tip picker (skip for Pencil ÔÇö always pixel)
         if self.active_tool != Tool::Pencil {
+            self.show_brush_preset_picker(ui);
             self.show_brush_tip_picker(ui, assets);
             ui.separator();
         }
@@ -658,21 +780,32 @@ impl ToolsPanel {
         }
         aa_resp.on_hover_text(t!("ctx.anti_alias_tooltip"));
 
-        // Brush Mode (Normal/Dodge/Burn/Sponge) - only for Brush tool (disabled for now)
+        // Brush Mode - only for Brush tool. Dodge/Burn/Sponge stay disabled for
+        // now: they edit existing pixels, which the stroke preview buffer lacks.
         if self.active_tool == Tool::Brush {
             ui.separator();
-            ui.add_enabled_ui(false, |ui| {
-                ui.label("Mode:");
-                let current_bm = self.properties.brush_mode;
-                egui::ComboBox::from_id_salt("ctx_brush_mode")
-                    .selected_text(current_bm.label())
-                    .width(70.0)
-                    .show_ui(ui, |ui| {
-                        for &mode in BrushMode::all() {
-                            let _ = ui.selectable_label(mode == current_bm, mode.label());
+            ui.label("Mode:");
+            let current_bm = self.properties.brush_mode;
+            egui::ComboBox::from_id_salt("ctx_brush_mode")
+                .selected_text(current_bm.label())
+                .width(70.0)
+                .show_ui(ui, |ui| {
+                    for &mode in BrushMode::all() {
+                        let available = matches!(mode, BrushMode::Normal | BrushMode::Mixer);
+                        if ui
+                            .add_enabled(
+                                available,
+                                egui::Button::selectable(mode == current_bm, mode.label()),
+                            )
+                            .clicked()
+                        {
+                            self.properties.brush_mode = mode;
                         }
-                    });
-            });
+                    }
+                });
+            if self.properties.brush_mode == BrushMode::Mixer {
+                self.show_mixer_popup(ui);
+            }
         }
 
         // Dynamics popup: Scatter, Color Jitter
@@ -681,7 +814,8 @@ impl ToolsPanel {
             || self.properties.hue_jitter > 0.01
             || self.properties.brightness_jitter > 0.01
             || self.properties.pressure_size
-            || self.properties.pressure_opacity;
+            || self.properties.pressure_opacity
+            || self.properties.dynamics.is_active();
         let dyn_popup_id = ui.make_persistent_id("brush_dyn_popup");
         let dyn_resp = assets.icon_button(ui, Icon::UiBrushDynamics, egui::Vec2::splat(20.0));
         if dyn_active {
@@ -789,6 +923,16 @@ impl ToolsPanel {
                         self.properties.pressure_min_opacity = min_opacity_pct / 100.0;
                     }
                     ui.end_row();
+
+                    ui.label(t!("ctx.dynamics.curve"));
+                    Self::response_curve_combo(
+                        ui,
+                        "ctx_pressure_curve",
+                        &mut self.properties.dynamics.pressure_curve,
+                    );
+                    ui.end_row();
+
+                    self.show_stroke_dynamics_rows(ui);
                 });
         });
 
@@ -815,6 +959,210 @@ impl ToolsPanel {
         }
     }
 
+    fn response_curve_combo(ui: &mut egui::Ui, id_salt: &str, curve: &mut ResponseCurve) {
+        egui::ComboBox::from_id_salt(id_salt)
+            .selected_text(curve.label())
+            .width(90.0)
+            .show_ui(ui, |ui| {
+                for &c in ResponseCurve::all() {
+                    if ui.selectable_label(c == *curve, c.label()).clicked() {
+                        *curve = c;
+                    }
+                }
+            });
+    }
+
+    /// "Wet" button with the Mixer brush settings popup.
+    fn show_mixer_popup(&mut self, ui: &mut egui::Ui) {
+        let popup_id = ui.make_persistent_id("brush_mixer_popup");
+        let resp = ui
+            .button(t!("ctx.mixer.button"))
+            .on_hover_text(t!("ctx.mixer.tooltip"));
+        if resp.clicked() {
+            egui::Popup::toggle_id(ui.ctx(), popup_id);
+        }
+        egui::Popup::new(
+            popup_id,
+            ui.ctx().clone(),
+            egui::PopupAnchor::from(&resp),
+            ui.layer_id(),
+        )
+        .open_memory(None::<egui::SetOpenCommand>)
+        .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+        .show(|ui| {
+            ui.set_min_width(220.0);
+            let m = &mut self.properties.mixer;
+            egui::Grid::new("brush_mixer_grid")
+                .num_columns(2)
+                .spacing([8.0, 4.0])
+                .show(ui, |ui| {
+                    Self::dynamics_amount_row(
+                        ui,
+                        &t!("ctx.mixer.wetness"),
+                        &t!("ctx.mixer.wetness_tooltip"),
+                        &mut m.wetness,
+                    );
+                    Self::dynamics_amount_row(
+                        ui,
+                        &t!("ctx.mixer.load"),
+                        &t!("ctx.mixer.load_tooltip"),
+                        &mut m.load,
+                    );
+                    Self::dynamics_amount_row(
+                        ui,
+                        &t!("ctx.mixer.mix"),
+                        &t!("ctx.mixer.mix_tooltip"),
+                        &mut m.mix,
+                    );
+                });
+            ui.checkbox(&mut m.sample_all_layers, t!("ctx.mixer.sample_all_layers"))
+                .on_hover_text(t!("ctx.mixer.sample_all_layers_tooltip"));
+        });
+    }
+
+    /// Percentage slider row for a 0..1 dynamics amount.
+    fn dynamics_amount_row(ui: &mut egui::Ui, label: &str, hover: &str, value: &mut f32) {
+        ui.label(label);
+        let mut pct = (*value * 100.0).round();
+        if ui
+            .add(
+                egui::Slider::new(&mut pct, 0.0..=100.0)
+                    .suffix("%")
+                    .max_decimals(0),
+            )
+            .on_hover_text(hover)
+            .changed()
+        {
+            *value = pct / 100.0;
+        }
+        ui.end_row();
+    }
+
+    /// Tilt, velocity, taper and stabilizer rows shared by the Brush and
+    /// Eraser dynamics popups (both grids are two columns wide).
+    fn show_stroke_dynamics_rows(&mut self, ui: &mut egui::Ui) {
+        let image_tip = !self.properties.brush_tip.is_circle();
+        let d = &mut self.properties.dynamics;
+
+        ui.separator();
+        ui.separator();
+        ui.end_row();
+        ui.label(t!("ctx.dynamics.pen_tilt"));
+        ui.label("");
+        ui.end_row();
+        Self::dynamics_amount_row(
+            ui,
+            &t!("ctx.dynamics.size"),
+            &t!("ctx.dynamics.tilt_size_tooltip"),
+            &mut d.tilt_size,
+        );
+        Self::dynamics_amount_row(
+            ui,
+            &t!("ctx.dynamics.opacity"),
+            &t!("ctx.dynamics.tilt_opacity_tooltip"),
+            &mut d.tilt_opacity,
+        );
+        Self::dynamics_amount_row(
+            ui,
+            &t!("ctx.dynamics.scatter"),
+            &t!("ctx.dynamics.tilt_scatter_tooltip"),
+            &mut d.tilt_scatter,
+        );
+        ui.add_enabled(image_tip, egui::Checkbox::new(&mut d.tilt_angle, t!("ctx.dynamics.angle")))
+            .on_hover_text(t!("ctx.dynamics.tilt_angle_tooltip"));
+        ui.label("");
+        ui.end_row();
+
+        ui.separator();
+        ui.separator();
+        ui.end_row();
+        ui.label(t!("ctx.dynamics.velocity"));
+        Self::response_curve_combo(ui, "ctx_velocity_curve", &mut d.velocity_curve);
+        ui.end_row();
+        Self::dynamics_amount_row(
+            ui,
+            &t!("ctx.dynamics.size"),
+            &t!("ctx.dynamics.velocity_size_tooltip"),
+            &mut d.velocity_size,
+        );
+        Self::dynamics_amount_row(
+            ui,
+            &t!("ctx.dynamics.opacity"),
+            &t!("ctx.dynamics.velocity_opacity_tooltip"),
+            &mut d.velocity_opacity,
+        );
+        Self::dynamics_amount_row(
+            ui,
+            &t!("ctx.dynamics.scatter"),
+            &t!("ctx.dynamics.velocity_scatter_tooltip"),
+            &mut d.velocity_scatter,
+        );
+        ui.add_enabled(
+            image_tip,
+            egui::Checkbox::new(&mut d.direction_angle, t!("ctx.dynamics.angle")),
+        )
+        .on_hover_text(t!("ctx.dynamics.direction_angle_tooltip"));
+        ui.label("");
+        ui.end_row();
+
+        ui.separator();
+        ui.separator();
+        ui.end_row();
+        ui.label(t!("ctx.dynamics.taper_start"));
+        ui.add(
+            egui::Slider::new(&mut d.taper_start, 0.0..=500.0)
+                .suffix(" px")
+                .max_decimals(0),
+        )
+        .on_hover_text(t!("ctx.dynamics.taper_start_tooltip"));
+        ui.end_row();
+        ui.label(t!("ctx.dynamics.taper_end"));
+        ui.add(
+            egui::Slider::new(&mut d.taper_end, 0.0..=500.0)
+                .suffix(" px")
+                .max_decimals(0),
+        )
+        .on_hover_text(t!("ctx.dynamics.taper_end_tooltip"));
+        ui.end_row();
+
+        ui.label(t!("ctx.dynamics.stabilizer"));
+        egui::ComboBox::from_id_salt("ctx_stabilizer_mode")
+            .selected_text(d.stabilizer.label())
+            .width(90.0)
+            .show_ui(ui, |ui| {
+                for &mode in StabilizerMode::all() {
+                    if ui
+                        .selectable_label(mode == d.stabilizer, mode.label())
+                        .clicked()
+                    {
+                        d.stabilizer = mode;
+                    }
+                }
+            });
+        ui.end_row();
+        match d.stabilizer {
+            StabilizerMode::Off => {}
+            StabilizerMode::LazyMouse => {
+                Self::dynamics_amount_row(
+                    ui,
+                    &t!("ctx.dynamics.strength"),
+                    &t!("ctx.dynamics.strength_tooltip"),
+                    &mut d.stabilizer_strength,
+                );
+            }
+            StabilizerMode::PulledString => {
+                ui.label(t!("ctx.dynamics.string"));
+                ui.add(
+                    egui::Slider::new(&mut d.stabilizer_radius, 1.0..=200.0)
+                        .suffix(" px")
+                        .max_decimals(0),
+                )
+                .on_hover_text(t!("ctx.dynamics.string_tooltip"));
+                ui.end_row();
+            }
+        }
+    }
+
     /// Show rotation controls for non-circle brush tips.
     /// Provides a fixed-angle slider OR a random-range double-slider, plus a checkbox to toggle.
     fn show_tip_rotation_controls(&mut self, ui: &mut egui::Ui) {
@@ -1016,6 +1364,7 @@ impl ToolsPanel {
     /// Show eraser-specific options (size, hardness - opacity from color alpha)
     fn show_eraser_options(&mut self, ui: &mut egui::Ui, assets: &Assets) {
         // Brush tip picker
+        self.show_brush_preset_picker(ui);
         self.show_brush_tip_picker(ui, assets);
         ui.separator();
 
@@ -1051,7 +1400,8 @@ impl ToolsPanel {
 
         // Dynamics popup for eraser: Scatter only
         ui.separator();
-        let dyn_active_eraser = self.properties.scatter > 0.01;
+        let dyn_active_eraser =
+            self.properties.scatter > 0.01 || self.properties.dynamics.is_active();
         let dyn_popup_id_e = ui.make_persistent_id("eraser_dyn_popup");
         let dyn_resp_e = assets.icon_button(ui, Icon::UiBrushDynamics, egui::Vec2::splat(20.0));
         if dyn_active_eraser {
@@ -1089,6 +1439,8 @@ impl ToolsPanel {
                         self.properties.scatter = scatter_pct / 200.0;
                     }
                     ui.end_row();
+
+                    self.show_stroke_dynamics_rows(ui);
                 });
         });
 
@@ -1236,6 +1588,54 @@ impl ToolsPanel {
         }
     }
 
+    fn show_slice_options(&mut self, ui: &mut egui::Ui) {
+        let state = &mut self.slice_state;
+        if let Some(index) = state.selected {
+            ui.label(t!("ctx.slice.name"));
+            let response =
+                ui.add(egui::TextEdit::singleline(&mut state.name_edit).desired_width(120.0));
+            if response.lost_focus() && !state.name_edit.trim().is_empty() {
+                state.pending_action = Some(SliceToolAction::Rename(
+                    index,
+                    state.name_edit.trim().to_string(),
+                ));
+            }
+            if ui.button(t!("ctx.slice.delete")).clicked() {
+                state.pending_action = Some(SliceToolAction::Delete(index));
+                state.selected = None;
+            }
+            ui.separator();
+        }
+
+        ui.label(t!("ctx.slice.grid"));
+        ui.add(
+            egui::DragValue::new(&mut state.grid_columns)
+                .range(1..=64)
+                .speed(0.1),
+        );
+        ui.label("\u{00D7}");
+        ui.add(
+            egui::DragValue::new(&mut state.grid_rows)
+                .range(1..=64)
+                .speed(0.1),
+        );
+        if ui.button(t!("ctx.slice.make_grid")).clicked() {
+            state.pending_action = Some(SliceToolAction::Grid {
+                columns: state.grid_columns,
+                rows: state.grid_rows,
+            });
+            state.selected = None;
+        }
+        ui.separator();
+        if ui.button(t!("ctx.slice.from_layers")).clicked() {
+            state.pending_action = Some(SliceToolAction::FromLayers);
+        }
+        if ui.button(t!("ctx.slice.clear")).clicked() {
+            state.pending_action = Some(SliceToolAction::Clear);
+            state.selected = None;
+        }
+    }
+
     fn show_clone_stamp_options(&mut self, ui: &mut egui::Ui) {
         // Size
         ui.label(t!("ctx.size"));
@@ -46,6 +46,7 @@ impl ToolsPanel {
             Tool::Zoom => t!("tool.zoom"),
             Tool::Pan => t!("tool.pan"),
             Tool::Shapes => t!("tool.shapes"),
+            Tool::Slice => t!("tool.slice"),
         }
     }
 
@@ -76,6 +77,7 @@ impl ToolsPanel {
             Tool::Text => "Click to place text. Configure font, size, and color in options.".into(),
             Tool::PerspectiveCrop => "Drag the four corners to define a perspective crop region.".into(),
             Tool::Shapes => "Click and drag to draw shapes. Hold Shift for constrained proportions.".into(),
+            Tool::Slice => "Drag to add a slice. Click a slice to rename it. Right-click a slice to delete it.".into(),
         }
     }
 }
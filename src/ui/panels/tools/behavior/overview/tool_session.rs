@@ -14,6 +14,130 @@ impl ToolsPanel {
         self.tool_state.distance_remainder = 0.0;
         self.tool_state.using_secondary_color = false;
         self.tool_state.smooth_pos = None;
+        self.tool_state.stabilized_pos = None;
+        self.tool_state.velocity = 0.0;
+        self.tool_state.last_sample_time = None;
+        self.tool_state.stroke_distance = 0.0;
+        self.tool_state.stroke_total = None;
+        self.tool_state.stroke_segments.clear();
+        self.tool_state.stamp = StampDynamics::default();
+        self.tool_state.mixer_source = None;
+        self.tool_state.mixer_reservoir = None;
+    }
+
+    /// Run a raw pointer sample through the active stabilizer.
+    /// `zoom` converts the pulled-string length from screen to canvas pixels.
+    fn stabilize_position(&mut self, raw: Pos2, zoom: f32) -> Pos2 {
+        let d = &self.properties.dynamics;
+        let Some(prev) = self.tool_state.stabilized_pos else {
+            self.tool_state.stabilized_pos = Some(raw);
+            return raw;
+        };
+        let next = match d.stabilizer {
+            StabilizerMode::Off => raw,
+            StabilizerMode::LazyMouse => {
+                let follow = 1.0 - d.stabilizer_strength.clamp(0.0, 1.0) * 0.95;
+                prev + (raw - prev) * follow
+            }
+            StabilizerMode::PulledString => {
+                let string_len = d.stabilizer_radius.max(0.0) / zoom.max(0.01);
+                let delta = raw - prev;
+                let dist = delta.length();
+                if dist <= string_len {
+                    prev
+                } else {
+                    prev + delta * ((dist - string_len) / dist)
+                }
+            }
+        };
+        self.tool_state.stabilized_pos = Some(next);
+        next
+    }
+
+    /// Feed the screen-space distance travelled this frame into the smoothed
+    /// stroke speed used by the velocity mappings.
+    fn update_stroke_velocity(&mut self, screen_distance: f32, now: f64) {
+        // Screen speed (px/s) treated as full velocity.
+        const FULL_SPEED: f32 = 4000.0;
+        if let Some(prev) = self.tool_state.last_sample_time {
+            let dt = (now - prev) as f32;
+            if dt <= 0.0 {
+                return;
+            }
+            let target = (screen_distance / dt / FULL_SPEED).min(1.0);
+            self.tool_state.velocity += (target - self.tool_state.velocity) * 0.35;
+        }
+        self.tool_state.last_sample_time = Some(now);
+    }
+
+    /// Record a live stroke segment for the end-taper re-render on release.
+    fn record_stroke_segment(&mut self, start: (f32, f32), end: (f32, f32)) {
+        if self.properties.dynamics.taper_end <= 0.0 || self.tool_state.stroke_total.is_some() {
+            return;
+        }
+        self.tool_state.stroke_segments.push(StrokeSegment {
+            start,
+            end,
+            arc_start: self.tool_state.stroke_distance,
+            pressure: self.tool_state.current_pressure,
+            stamp: self.tool_state.stamp,
+            stamp_counter: self.stamp_counter,
+        });
+    }
+
+    /// Re-render the finished stroke into a cleared preview layer now that
+    /// its total length is known, so the end taper can shrink the last stamps.
+    fn rerender_stroke_with_end_taper(
+        &mut self,
+        canvas_state: &mut CanvasState,
+        is_eraser: bool,
+        primary_color_f32: [f32; 4],
+        secondary_color_f32: [f32; 4],
+    ) {
+        let segments = std::mem::take(&mut self.tool_state.stroke_segments);
+        if segments.is_empty() {
+            return;
+        }
+        let use_secondary = self.tool_state.using_secondary_color;
+        let saved_pressure = self.tool_state.current_pressure;
+        let width = canvas_state.width;
+        let height = canvas_state.height;
+        let mask_ptr = canvas_state
+            .selection_mask
+            .as_ref()
+            .map(|m| m as *const GrayImage);
+        let Some(preview) = canvas_state.preview_layer.as_mut() else {
+            return;
+        };
+        preview.clear();
+        let mask_ref = mask_ptr.map(|p| unsafe { &*p });
+        self.tool_state.mixer_reservoir = None;
+
+        self.tool_state.stroke_total = Some(self.tool_state.stroke_distance);
+        for seg in &segments {
+            self.tool_state.current_pressure = seg.pressure;
+            self.tool_state.stamp = seg.stamp;
+            self.tool_state.stroke_distance = seg.arc_start;
+            self.stamp_counter = seg.stamp_counter;
+            self.draw_line_no_dirty(
+                preview,
+                width,
+                height,
+                seg.start,
+                seg.end,
+                is_eraser,
+                use_secondary,
+                primary_color_f32,
+                secondary_color_f32,
+                mask_ref,
+            );
+        }
+        self.tool_state.stroke_total = None;
+        self.tool_state.current_pressure = saved_pressure;
+
+        if let Some(bounds) = self.stroke_tracker.bounds {
+            canvas_state.mark_preview_changed_rect(bounds);
+        }
     }
 
     fn commit_brush_straight_line(
@@ -78,6 +202,7 @@ impl ToolsPanel {
             }
             canvas_state.preview_blend_mode = self.properties.blending_mode;
         }
+        self.begin_mixer_stroke(canvas_state);
 
         let mirror = canvas_state.mirror_mode;
         let mw = canvas_state.width;
@@ -87,6 +212,11 @@ impl ToolsPanel {
         let start_mirrors = mirror.mirror_positions(start_f.0, start_f.1, mw, mh);
         let end_mirrors = mirror.mirror_positions(end_f.0, end_f.1, mw, mh);
         let mut modified_rect = Rect::NOTHING;
+        // A straight line has a known length, so both tapers apply directly.
+        self.tool_state.stamp = StampDynamics::default();
+        self.tool_state.stroke_distance = 0.0;
+        self.tool_state.stroke_total =
+            Some(((end_f.0 - start_f.0).powi(2) + (end_f.1 - start_f.1).powi(2)).sqrt());
         for i in 0..end_mirrors.len {
             let s = start_mirrors.data[i];
             let e = end_mirrors.data[i];
@@ -112,6 +242,8 @@ impl ToolsPanel {
             };
             modified_rect = modified_rect.union(r);
         }
+        self.tool_state.stroke_total = None;
+        self.tool_state.mixer_source = None;
 
         self.stroke_tracker.expand_bounds(modified_rect);
 
@@ -66,6 +66,7 @@ impl ToolsPanel {
         let utility_tools: Vec<(Icon, Tool)> = vec![
             (Icon::ColorPicker, Tool::ColorPicker),
             (Icon::Text, Tool::Text),
+            (Icon::Slice, Tool::Slice),
             (Icon::Zoom, Tool::Zoom),
             (Icon::Pan, Tool::Pan),
         ];
@@ -568,6 +569,7 @@ impl ToolsPanel {
                 Tool::Zoom => t!("tool.zoom"),
                 Tool::Pan => t!("tool.pan"),
                 Tool::Shapes => t!("tool.shapes"),
+                Tool::Slice => t!("tool.slice"),
             };
             ui.label(egui::RichText::new(tool_name).strong());
         });
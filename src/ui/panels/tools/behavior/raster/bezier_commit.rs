@@ -104,6 +104,7 @@ impl ToolsPanel {
         let width = canvas_state.width;
         let height = canvas_state.height;
         let blend_mode = self.properties.blending_mode;
+        let linear = canvas_state.linear_blending;
 
         // Extract only populated chunk data from preview (clone to release borrow)
         let preview_chunks: Vec<(u32, u32, image::RgbaImage)> = match &canvas_state.preview_layer {
@@ -147,11 +148,14 @@ impl ToolsPanel {
                         if preview_pixel[3] > 0 {
                             let layer_pixel =
                                 active_layer.pixels.get_pixel_mut(base_x + lx, base_y + ly);
-                            *layer_pixel = CanvasState::blend_pixel_static(
+                            *layer_pixel = CanvasState::blend_pixel_at(
                                 *layer_pixel,
                                 preview_pixel,
                                 blend_mode,
                                 1.0,
+                                base_x + lx,
+                                base_y + ly,
+                                linear,
                             );
                         }
                     }
@@ -1,7 +1,20 @@
+/// Smallest size multiplier dynamics and taper may shrink a stamp to.
+const MIN_DYNAMICS_SCALE: f32 = 0.05;
+
+/// Stroke length, in brush diameters, a full Mixer load lasts before the
+/// brush runs dry (scaled down by `MixerSettings::load`).
+const MIXER_LOAD_DIAMETERS: f32 = 40.0;
+
 impl ToolsPanel {
+    /// Pen pressure after the dynamics response curve.
+    fn curved_pressure(&self) -> f32 {
+        let pressure = self.tool_state.current_pressure;
+        self.properties.dynamics.pressure_curve.apply(pressure)
+    }
+
     pub fn pressure_size(&self) -> f32 {
         if self.properties.pressure_size {
-            let p = self.tool_state.current_pressure;
+            let p = self.curved_pressure();
             let min = self.properties.pressure_min_size;
             self.properties.size * (min + (1.0 - min) * p)
         } else {
@@ -13,7 +26,7 @@ impl ToolsPanel {
     /// Returns `self.properties.flow` scaled by pressure when pressure_opacity is enabled.
     fn pressure_flow(&self) -> f32 {
         if self.properties.pressure_opacity {
-            let p = self.tool_state.current_pressure;
+            let p = self.curved_pressure();
             let min = self.properties.pressure_min_opacity;
             self.properties.flow * (min + (1.0 - min) * p)
         } else {
@@ -21,6 +34,217 @@ impl ToolsPanel {
         }
     }
 
+    /// Diameter of the stamp being drawn: pressure size scaled by tilt/velocity
+    /// dynamics and stroke taper.
+    pub fn stamp_size(&self) -> f32 {
+        self.pressure_size() * self.tip_dynamics_scale()
+    }
+
+    /// Size multiplier from dynamics and taper only (image tips are prescaled
+    /// to `properties.size`, so they apply this on top of the cached mask).
+    fn tip_dynamics_scale(&self) -> f32 {
+        self.tool_state.stamp.size * self.tool_state.taper_scale
+    }
+
+    fn stamp_flow(&self) -> f32 {
+        self.pressure_flow() * self.tool_state.stamp.opacity
+    }
+
+    fn stamp_scatter(&self) -> f32 {
+        self.properties.scatter + self.tool_state.stamp.scatter
+    }
+
+    /// Largest scatter any stamp can receive, used to pad dirty bounds.
+    fn max_scatter(&self) -> f32 {
+        let d = &self.properties.dynamics;
+        self.properties.scatter + d.tilt_scatter + d.velocity_scatter
+    }
+
+    /// Set the pen pressure (0.0..1.0) for subsequent stamps.
+    pub fn set_pen_pressure(&mut self, pressure: f32) {
+        self.tool_state.current_pressure = pressure.clamp(0.0, 1.0);
+    }
+
+    /// Set the pen orientation for subsequent stamps. egui only forwards pen
+    /// pressure, so this is fed from [`crate::pen_probe`]; `None` (mouse, or
+    /// a platform without tilt) disables the tilt mappings.
+    pub fn set_pen_tilt(&mut self, tilt: Option<PenTilt>) {
+        self.tool_state.current_tilt = tilt;
+        self.refresh_stamp_dynamics();
+    }
+
+    /// Resolve tilt and velocity mappings into per-stamp multipliers for the
+    /// current input sample.
+    fn refresh_stamp_dynamics(&mut self) {
+        let d = &self.properties.dynamics;
+        let mut stamp = StampDynamics::default();
+
+        if let Some(tilt) = self.tool_state.current_tilt {
+            // 0 = pen upright, 1 = pen lying flat
+            let amount = 1.0 - (tilt.altitude / std::f32::consts::FRAC_PI_2).clamp(0.0, 1.0);
+            stamp.size *= 1.0 - d.tilt_size * amount;
+            stamp.opacity *= 1.0 - d.tilt_opacity * amount;
+            stamp.scatter += d.tilt_scatter * amount;
+            if d.tilt_angle {
+                stamp.angle_deg = tilt.azimuth.to_degrees();
+            }
+        }
+
+        let v = d.velocity_curve.apply(self.tool_state.velocity);
+        stamp.size *= 1.0 - d.velocity_size * v;
+        stamp.opacity *= 1.0 - d.velocity_opacity * v;
+        stamp.scatter += d.velocity_scatter * v;
+        if d.direction_angle && !(d.tilt_angle && self.tool_state.current_tilt.is_some()) {
+            stamp.angle_deg = self.tool_state.stroke_direction.to_degrees();
+        }
+
+        stamp.size = stamp.size.max(MIN_DYNAMICS_SCALE);
+        self.tool_state.stamp = stamp;
+    }
+
+    /// Taper multiplier at arc length `arc` along the current stroke.
+    /// The end taper only applies while the total length is known.
+    fn taper_scale_at(&self, arc: f32) -> f32 {
+        let d = &self.properties.dynamics;
+        let mut scale: f32 = 1.0;
+        if d.taper_start > 0.0 {
+            scale = scale.min(arc / d.taper_start);
+        }
+        if d.taper_end > 0.0
+            && let Some(total) = self.tool_state.stroke_total
+        {
+            scale = scale.min((total - arc) / d.taper_end);
+        }
+        scale.clamp(MIN_DYNAMICS_SCALE, 1.0)
+    }
+
+    /// Capture the pixels the Mixer brush picks up from and empty its
+    /// reservoir. Outside Mixer mode (or while painting a layer mask) this
+    /// clears the source, so stamps fall back to the plain brush color.
+    pub fn begin_mixer_stroke(&mut self, canvas_state: &CanvasState) {
+        let editing_mask = canvas_state.edit_layer_mask
+            && canvas_state
+                .layers
+                .get(canvas_state.active_layer_index)
+                .is_some_and(|l| l.has_live_mask());
+        self.tool_state.mixer_reservoir = None;
+        self.tool_state.mixer_source = if self.active_tool != Tool::Brush
+            || self.properties.brush_mode != BrushMode::Mixer
+            || editing_mask
+        {
+            None
+        } else if self.properties.mixer.sample_all_layers {
+            Some(TiledImage::from_rgba_image(&canvas_state.composite()))
+        } else {
+            canvas_state
+                .layers
+                .get(canvas_state.active_layer_index)
+                .map(|l| l.pixels.clone())
+        };
+    }
+
+    /// Color a stamp paints with: the Mixer deposit during a mixer stroke,
+    /// otherwise the brush color itself.
+    fn stamp_color(&self, brush_color: [f32; 4]) -> [f32; 4] {
+        if self.properties.brush_mode == BrushMode::Mixer && self.tool_state.mixer_source.is_some()
+        {
+            self.tool_state.mixer_deposit
+        } else {
+            brush_color
+        }
+    }
+
+    /// Mixer pickup for a stamp at `pos`, `arc` pixels into the stroke and
+    /// `travel` pixels after the previous stamp: soak the color under the
+    /// stamp into the reservoir, then resolve the color this stamp deposits.
+    /// `target` is the stroke buffer, so the brush also drags its own wet paint.
+    fn mixer_pick_up(
+        &mut self,
+        target: &TiledImage,
+        pos: (f32, f32),
+        arc: f32,
+        travel: f32,
+        brush_color: [f32; 4],
+    ) {
+        let Some(source) = self.tool_state.mixer_source.as_ref() else {
+            return;
+        };
+        let m = &self.properties.mixer;
+        let [r, g, b, a] = brush_color;
+        let fresh = [r * a, g * a, b * a, a];
+        let mut reservoir = self.tool_state.mixer_reservoir.unwrap_or(fresh);
+
+        // Wetness is defined per brush diameter of travel, so the pickup rate
+        // does not depend on how densely the stroke is stamped.
+        let diameter = self.properties.size.max(1.0);
+        let wet = m.wetness.clamp(0.0, 1.0);
+        let rate = 1.0 - (1.0 - wet).powf((travel / diameter).clamp(0.0, 1.0));
+        if rate > 0.0
+            && let Some(canvas) = Self::mixer_sample(source, target, pos, self.stamp_size() / 2.0)
+        {
+            for (res, c) in reservoir.iter_mut().zip(canvas) {
+                *res += (c - *res) * rate;
+            }
+        }
+
+        let paint_left = if m.load >= 1.0 {
+            1.0
+        } else if m.load <= 0.0 {
+            0.0
+        } else {
+            (1.0 - arc / (m.load * MIXER_LOAD_DIAMETERS * diameter)).clamp(0.0, 1.0)
+        };
+        let fresh_share = paint_left * (1.0 - m.mix.clamp(0.0, 1.0));
+        let mut deposit = [0.0; 4];
+        for i in 0..4 {
+            deposit[i] = reservoir[i] + (fresh[i] - reservoir[i]) * fresh_share;
+        }
+        let da = deposit[3];
+        self.tool_state.mixer_deposit = if da > 1e-4 {
+            [deposit[0] / da, deposit[1] / da, deposit[2] / da, da]
+        } else {
+            [0.0, 0.0, 0.0, 0.0]
+        };
+        self.tool_state.mixer_reservoir = Some(reservoir);
+    }
+
+    /// Average premultiplied color of the stroke buffer over the source
+    /// inside a circle, on a sparse 5x5 grid. `None` when off canvas.
+    fn mixer_sample(
+        source: &TiledImage,
+        target: &TiledImage,
+        pos: (f32, f32),
+        radius: f32,
+    ) -> Option<[f32; 4]> {
+        const GRID: i32 = 2;
+        let (w, h) = (source.width(), source.height());
+        let step = radius.max(0.5) / GRID as f32;
+        let mut sum = [0.0f32; 4];
+        let mut count = 0u32;
+        for gy in -GRID..=GRID {
+            for gx in -GRID..=GRID {
+                if gx * gx + gy * gy > GRID * GRID {
+                    continue;
+                }
+                let x = (pos.0 + gx as f32 * step).floor();
+                let y = (pos.1 + gy as f32 * step).floor();
+                if x < 0.0 || y < 0.0 || x >= w as f32 || y >= h as f32 {
+                    continue;
+                }
+                let s = source.get_pixel(x as u32, y as u32).0;
+                let t = target.get_pixel(x as u32, y as u32).0;
+                let ta = t[3] as f32 / 255.0;
+                let sa = s[3] as f32 / 255.0 * (1.0 - ta);
+                for c in 0..3 {
+                    sum[c] += (t[c] as f32 * ta + s[c] as f32 * sa) / 255.0;
+                }
+                sum[3] += ta + sa;
+                count += 1;
+            }
+        }
+        (count > 0).then(|| sum.map(|v| v / count as f32))
+    }
+
     /// B6: Rebuild brush alpha LUT when brush properties change.
     /// The LUT maps squared-distance ratio (0..255 → 0.0..1.0 of `dist_sq/radius_sq`)
     /// to alpha (0..255).  Eliminates per-pixel `sqrt` + `smoothstep`.
@@ -160,7 +384,7 @@ impl ToolsPanel {
                 }
             } else {
                 self.properties.tip_rotation
-            };
+            } + self.tool_state.stamp.angle_deg;
             self.draw_image_tip_no_dirty(
                 target_image,
                 width,
@@ -179,19 +403,20 @@ impl ToolsPanel {
         // Scatter: randomize stamp position by up to scatter*diameter
         let (cx, cy) = {
             let (px, py) = pos;
-            if self.properties.scatter > 0.01 {
+            let scatter = self.stamp_scatter();
+            if scatter > 0.01 {
                 let diam = self.pressure_size();
                 let h1 = Self::stamp_hash(px, py, self.stamp_counter) as f32 / u32::MAX as f32;
                 let h2 = Self::stamp_hash(py, px, self.stamp_counter.wrapping_add(99991)) as f32
                     / u32::MAX as f32;
-                let ox = (h1 * 2.0 - 1.0) * self.properties.scatter * diam;
-                let oy = (h2 * 2.0 - 1.0) * self.properties.scatter * diam;
+                let ox = (h1 * 2.0 - 1.0) * scatter * diam;
+                let oy = (h2 * 2.0 - 1.0) * scatter * diam;
                 (px + ox, py + oy)
             } else {
                 (px, py)
             }
         };
-        let radius = self.pressure_size() / 2.0;
+        let radius = self.stamp_size() / 2.0;
         let radius_sq = radius * radius;
         if radius_sq < 0.001 {
             return;
@@ -214,11 +439,11 @@ impl ToolsPanel {
         }
 
         // Determine brush color (high-precision unmultiplied)
-        let brush_color_f32 = if use_secondary {
+        let brush_color_f32 = self.stamp_color(if use_secondary {
             secondary_color_f32
         } else {
             primary_color_f32
-        };
+        });
         let [src_r, src_g, src_b, src_a] = brush_color_f32;
         let base_r8 = (src_r * 255.0) as u8;
         let base_g8 = (src_g * 255.0) as u8;
@@ -256,6 +481,7 @@ impl ToolsPanel {
             };
 
         let lut = &self.brush_alpha_lut;
+        let flow = self.stamp_flow();
         let cs = crate::canvas::CHUNK_SIZE;
 
         // Determine which chunks overlap the brush bounding box
@@ -343,7 +569,7 @@ impl ToolsPanel {
                         let px_off = row_off + lx as usize * 4;
 
                         if is_eraser {
-                            let erase_strength = geom_alpha * src_a * self.pressure_flow();
+                            let erase_strength = geom_alpha * src_a * flow;
                             if erase_strength < 0.01 {
                                 continue;
                             }
@@ -355,12 +581,12 @@ impl ToolsPanel {
                                 chunk_raw[px_off + 3] = (erase_strength * 255.0) as u8;
                             }
                         } else {
-                            let brush_alpha = geom_alpha * src_a * self.pressure_flow();
+                            let brush_alpha = geom_alpha * src_a * flow;
                             if brush_alpha < 0.01 {
                                 continue;
                             }
                             match self.properties.brush_mode {
-                                BrushMode::Normal => {
+                                BrushMode::Normal | BrushMode::Mixer => {
                                     let brush_alpha_u8 = (brush_alpha * 255.0) as u8;
                                     let old_alpha = chunk_raw[px_off + 3];
                                     // Max-alpha stamping: only update if increasing opacity
@@ -551,19 +777,24 @@ impl ToolsPanel {
         // Scatter: randomize stamp position
         let (cx, cy) = {
             let (px, py) = pos;
-            if self.properties.scatter > 0.01 {
+            let scatter = self.stamp_scatter();
+            if scatter > 0.01 {
                 let diam = self.pressure_size();
                 let h1 = Self::stamp_hash(px, py, self.stamp_counter) as f32 / u32::MAX as f32;
                 let h2 = Self::stamp_hash(py, px, self.stamp_counter.wrapping_add(99991)) as f32
                     / u32::MAX as f32;
-                let ox = (h1 * 2.0 - 1.0) * self.properties.scatter * diam;
-                let oy = (h2 * 2.0 - 1.0) * self.properties.scatter * diam;
+                let ox = (h1 * 2.0 - 1.0) * scatter * diam;
+                let oy = (h2 * 2.0 - 1.0) * scatter * diam;
                 (px + ox, py + oy)
             } else {
                 (px, py)
             }
         };
         let half = mask_size as f32 / 2.0;
+        // Dynamics/taper shrink the stamp: sample the mask with scaled coordinates.
+        let tip_scale = self.tip_dynamics_scale();
+        let scaled = tip_scale < 0.999;
+        let inv_scale = 1.0 / tip_scale;
 
         // When rotated, the bounding box of the stamp expands.
         // The diagonal of the original square mask is half*sqrt(2) from center.
@@ -578,7 +809,7 @@ impl ToolsPanel {
             half * std::f32::consts::SQRT_2
         } else {
             half
-        };
+        } * tip_scale.min(1.0);
 
         // Bounding box of the stamp in canvas coordinates
         let stamp_min_x = (cx - effective_half).max(0.0) as u32;
@@ -590,11 +821,11 @@ impl ToolsPanel {
         }
 
         // Brush color
-        let brush_color_f32 = if use_secondary {
+        let brush_color_f32 = self.stamp_color(if use_secondary {
             secondary_color_f32
         } else {
             primary_color_f32
-        };
+        });
         let [src_r, src_g, src_b, src_a] = brush_color_f32;
         let base_r8 = (src_r * 255.0) as u8;
         let base_g8 = (src_g * 255.0) as u8;
@@ -631,6 +862,7 @@ impl ToolsPanel {
                 (base_r8, base_g8, base_b8)
             };
 
+        let flow = self.stamp_flow();
         let cs = crate::canvas::CHUNK_SIZE;
         let mask = &self.brush_tip_mask;
 
@@ -683,10 +915,10 @@ impl ToolsPanel {
                         let rel_x = global_x as f32 - cx;
                         let rel_y = global_y as f32 - cy;
 
-                        let geom_alpha_u8 = if rotated {
-                            // Inverse-rotate to find source mask position
-                            let rot_x = rel_x * cos_a - rel_y * sin_a + half;
-                            let rot_y = rel_x * sin_a + rel_y * cos_a + half;
+                        let geom_alpha_u8 = if rotated || scaled {
+                            // Inverse-rotate (and unscale) to find source mask position
+                            let rot_x = (rel_x * cos_a - rel_y * sin_a) * inv_scale + half;
+                            let rot_y = (rel_x * sin_a + rel_y * cos_a) * inv_scale + half;
                             // Bilinear sample from the unrotated mask
                             if rot_x < -0.5
                                 || rot_y < -0.5
@@ -731,7 +963,7 @@ impl ToolsPanel {
                         let px_off = row_off + lx as usize * 4;
 
                         if is_eraser {
-                            let erase_strength = geom_alpha * src_a * self.pressure_flow();
+                            let erase_strength = geom_alpha * src_a * flow;
                             if erase_strength < 0.01 {
                                 continue;
                             }
@@ -743,7 +975,7 @@ impl ToolsPanel {
                                 chunk_raw[px_off + 3] = (erase_strength * 255.0) as u8;
                             }
                         } else {
-                            let brush_alpha = geom_alpha * src_a * self.pressure_flow();
+                            let brush_alpha = geom_alpha * src_a * flow;
                             let brush_alpha_u8 = (brush_alpha * 255.0) as u8;
                             let old_alpha = chunk_raw[px_off + 3];
                             if brush_alpha_u8 >= old_alpha {
@@ -782,6 +1014,14 @@ impl ToolsPanel {
         let dy = y1 - y0;
         let distance = (dx * dx + dy * dy).sqrt();
 
+        let arc_start = self.tool_state.stroke_distance;
+        let mixing = !is_eraser && self.tool_state.mixer_source.is_some();
+        let brush_color = if use_secondary {
+            secondary_color_f32
+        } else {
+            primary_color_f32
+        };
+
         if distance < 0.1 {
             // Just draw one circle at start
             if start.0 >= 0.0
@@ -789,6 +1029,15 @@ impl ToolsPanel {
                 && start.1 >= 0.0
                 && (start.1 as u32) < height
             {
+                self.tool_state.taper_scale = self.taper_scale_at(arc_start);
+                if mixing {
+                    let travel = if self.tool_state.mixer_reservoir.is_none() {
+                        self.properties.size
+                    } else {
+                        distance
+                    };
+                    self.mixer_pick_up(target_image, start, arc_start, travel, brush_color);
+                }
                 self.draw_circle_no_dirty(
                     target_image,
                     width,
@@ -800,6 +1049,7 @@ impl ToolsPanel {
                     secondary_color_f32,
                     selection_mask,
                 );
+                self.tool_state.taper_scale = 1.0;
             }
             return;
         }
@@ -819,6 +1069,22 @@ impl ToolsPanel {
 
             // Pass float position directly — no rounding — for sub-pixel smooth circles
             if x >= 0.0 && (x as u32) < width && y >= 0.0 && (y as u32) < height {
+                self.tool_state.taper_scale = self.taper_scale_at(arc_start + distance * t);
+                if mixing {
+                    // The first stamp of a stroke loads a full diameter's pickup.
+                    let travel = if self.tool_state.mixer_reservoir.is_none() {
+                        self.properties.size
+                    } else {
+                        distance / steps as f32
+                    };
+                    self.mixer_pick_up(
+                        target_image,
+                        (x, y),
+                        arc_start + distance * t,
+                        travel,
+                        brush_color,
+                    );
+                }
                 self.draw_circle_no_dirty(
                     target_image,
                     width,
@@ -832,6 +1098,7 @@ impl ToolsPanel {
                 );
             }
         }
+        self.tool_state.taper_scale = 1.0;
     }
 
     fn mark_full_dirty(&self, canvas_state: &mut CanvasState) {
@@ -879,7 +1146,7 @@ impl ToolsPanel {
         let width = canvas_state.width;
         let height = canvas_state.height;
         // scatter moves center by up to scatter * size per axis
-        let scatter_pad = self.properties.scatter * self.pressure_size();
+        let scatter_pad = self.max_scatter() * self.pressure_size();
 
         let min_x = (cx - radius - scatter_pad).max(0.0) as u32;
         let max_x = ((cx + radius + scatter_pad) as u32).min(width - 1);
@@ -895,6 +1162,20 @@ impl ToolsPanel {
                 .map(|m| m as *const GrayImage);
             if let Some(ref mut preview) = canvas_state.preview_layer {
                 let mask_ref = mask_ptr.map(|p| unsafe { &*p });
+                if !is_eraser && self.tool_state.mixer_source.is_some() {
+                    let brush_color = if use_secondary {
+                        secondary_color_f32
+                    } else {
+                        primary_color_f32
+                    };
+                    let arc = self.tool_state.stroke_distance;
+                    let travel = if self.tool_state.mixer_reservoir.is_none() {
+                        self.properties.size
+                    } else {
+                        0.0
+                    };
+                    self.mixer_pick_up(preview, pos, arc, travel, brush_color);
+                }
                 self.draw_circle_no_dirty(
                     preview,
                     width,
@@ -451,7 +451,7 @@ impl ToolsPanel {
         let height = canvas_state.height;
 
         // Calculate bounding box of the line + brush radius + max scatter offset
-        let scatter_pad = self.properties.scatter * self.pressure_size();
+        let scatter_pad = self.max_scatter() * self.pressure_size();
         let min_x = (start.0.min(end.0) - radius - scatter_pad).max(0.0) as u32;
         let max_x = ((start.0.max(end.0) + radius + scatter_pad) as u32).min(width);
         let min_y = (start.1.min(end.1) - radius - scatter_pad).max(0.0) as u32;
@@ -466,6 +466,7 @@ impl ToolsPanel {
                 .map(|m| m as *const GrayImage);
             if let Some(ref mut preview) = canvas_state.preview_layer {
                 let mask_ref = mask_ptr.map(|p| unsafe { &*p });
+                self.record_stroke_segment(start, end);
                 self.draw_line_no_dirty(
                     preview,
                     width,
@@ -1185,6 +1185,7 @@ impl ToolsPanel {
             active_layer_index,
         );
         let blend_mode = self.properties.blending_mode;
+        let linear = canvas_state.linear_blending;
         let mut changed = false;
 
         if let Some(active_layer) = canvas_state.layers.get_mut(active_layer_index) {
@@ -1204,7 +1205,10 @@ impl ToolsPanel {
                         continue;
                     }
                     let dst = *active_layer.pixels.get_pixel(gx, gy);
-                    let blended = CanvasState::blend_pixel_static(dst, src, blend_mode, 1.0);
+                    let blended = active_layer.locks.filter_pixel(
+                        dst,
+                        CanvasState::blend_pixel_at(dst, src, blend_mode, 1.0, gx, gy, linear),
+                    );
                     if blended != dst {
                         active_layer.pixels.put_pixel(gx, gy, blended);
                         changed = true;
@@ -1405,6 +1409,7 @@ impl ToolsPanel {
         self.reset_fill_preview_state(clear_preview_overlay);
 
         let blend_mode = self.properties.blending_mode;
+        let linear = canvas_state.linear_blending;
 
         // IMPORTANT: Capture "before" snapshot BEFORE modifying the layer
         // For preview-based tools, the layer is still unmodified at this point
@@ -1439,7 +1444,7 @@ impl ToolsPanel {
                                 continue;
                             }
                             let dst = active_layer.pixels.get_pixel_mut(gx, gy);
-                            *dst = CanvasState::blend_pixel_static(*dst, src, blend_mode, 1.0);
+                            *dst = CanvasState::blend_pixel_at(*dst, src, blend_mode, 1.0, gx, gy, linear);
                         }
                     }
                 }
@@ -261,6 +261,7 @@ impl ToolsPanel {
 
         let shape = self.gradient_state.shape;
         let repeat = self.gradient_state.repeat;
+        let dither = self.gradient_state.dither;
         let mode = self.gradient_state.mode;
         let is_eraser = mode == GradientMode::Transparency;
 
@@ -322,6 +323,8 @@ impl ToolsPanel {
                 GradientShape::LinearReflected => 1,
                 GradientShape::Radial => 2,
                 GradientShape::Diamond => 3,
+                GradientShape::Conic => 4,
+                GradientShape::Spiral => 5,
             };
 
             let params = crate::gpu::GradientGpuParams {
@@ -334,7 +337,7 @@ impl ToolsPanel {
                 shape: shape_u32,
                 repeat: if repeat { 1 } else { 0 },
                 is_eraser: if is_eraser { 1 } else { 0 },
-                _pad0: 0,
+                dither: if dither { 1 } else { 0 },
                 _pad1: 0,
                 _pad2: 0,
             };
@@ -420,6 +423,7 @@ impl ToolsPanel {
             let inv_len_sq = if len_sq > 1e-6 { 1.0 / len_sq } else { 0.0 };
             let ux = dx * inv_len;
             let uy = dy * inv_len;
+            let base_angle = dy.atan2(dx);
 
             // Downscale during drag for responsiveness — use the
             // same preview_scale as the GPU path so buffer dimensions
@@ -495,11 +499,23 @@ impl ToolsPanel {
                                     dist.clamp(0.0, 1.0)
                                 }
                             }
+                            GradientShape::Conic => {
+                                let angle = ry.atan2(rx) - base_angle;
+                                (angle / std::f32::consts::TAU).rem_euclid(1.0)
+                            }
+                            GradientShape::Spiral => {
+                                let angle = ry.atan2(rx) - base_angle;
+                                let dist = (rx * rx + dist_y_sq).sqrt() * inv_len;
+                                (angle / std::f32::consts::TAU + dist).rem_euclid(1.0)
+                            }
                         };
 
-                        let idx = (t * 255.0) as usize;
-                        let loff = idx * 4;
-                        let mut a = lut[loff + 3];
+                        let [r, g, b, mut a] = if dither {
+                            crate::gradient::sample_lut_dithered(&lut, t, gx, gy)
+                        } else {
+                            let loff = (t * 255.0) as usize * 4;
+                            [lut[loff], lut[loff + 1], lut[loff + 2], lut[loff + 3]]
+                        };
 
                         if has_selection
                             && let Some(mask) = sel_mask
@@ -514,9 +530,9 @@ impl ToolsPanel {
 
                         if a > 0 {
                             let off = x * 4;
-                            row[off] = lut[loff];
-                            row[off + 1] = lut[loff + 1];
-                            row[off + 2] = lut[loff + 2];
+                            row[off] = r;
+                            row[off + 1] = g;
+                            row[off + 2] = b;
                             row[off + 3] = a;
                         }
                     }
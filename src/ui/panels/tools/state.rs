@@ -38,6 +38,7 @@ pub enum Tool {
     Zoom,
     Pan,
     Shapes,
+    Slice,
 }
 
 /// Identifies a brush tip — either the built-in procedural circle or a named image tip
@@ -67,12 +68,14 @@ impl BrushTip {
 /// Dodge: lightens (increases luminosity)
 /// Burn: darkens (decreases luminosity)
 /// Sponge: desaturates
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Mixer: wet paint that picks up canvas color (see `MixerSettings`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum BrushMode {
     Normal,
     Dodge,
     Burn,
     Sponge,
+    Mixer,
 }
 
 impl BrushMode {
@@ -82,6 +85,7 @@ impl BrushMode {
             BrushMode::Dodge => "Dodge",
             BrushMode::Burn => "Burn",
             BrushMode::Sponge => "Sponge",
+            BrushMode::Mixer => "Mixer",
         }
     }
     pub fn all() -> &'static [BrushMode] {
@@ -90,8 +94,291 @@ impl BrushMode {
             BrushMode::Dodge,
             BrushMode::Burn,
             BrushMode::Sponge,
+            BrushMode::Mixer,
+        ]
+    }
+}
+
+/// Wet media settings for `BrushMode::Mixer`.
+///
+/// The brush carries a reservoir that starts out filled with the brush color.
+/// Every stamp first soaks up the canvas color under it (`wetness`), then
+/// deposits a blend of fresh paint and reservoir (`mix`). Fresh paint runs
+/// out along the stroke (`load`), after which the brush only smears what it
+/// has picked up.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct MixerSettings {
+    /// Fraction of the canvas color absorbed into the reservoir per stamp (0.0..1.0).
+    pub wetness: f32,
+    /// Amount of fresh paint on the brush (0.0..1.0). 0 = dry smudge, 1 = never runs out.
+    pub load: f32,
+    /// Share of reservoir color in each stamp, versus fresh paint (0.0..1.0).
+    pub mix: f32,
+    /// Pick up color from the visible composite instead of the active layer.
+    pub sample_all_layers: bool,
+}
+
+impl Default for MixerSettings {
+    fn default() -> Self {
+        Self {
+            wetness: 0.5,
+            load: 0.5,
+            mix: 0.5,
+            sample_all_layers: false,
+        }
+    }
+}
+
+impl MixerSettings {
+    /// Copy with every amount pulled into the range the UI allows.
+    pub fn clamped(&self) -> Self {
+        Self {
+            wetness: self.wetness.clamp(0.0, 1.0),
+            load: self.load.clamp(0.0, 1.0),
+            mix: self.mix.clamp(0.0, 1.0),
+            sample_all_layers: self.sample_all_layers,
+        }
+    }
+}
+
+/// Response curve applied to a normalized 0..1 input (pen pressure, stroke speed)
+/// before it is mapped onto brush size, opacity or scatter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ResponseCurve {
+    Linear,
+    /// Light input already has a strong effect.
+    Soft,
+    /// Heavy input is needed for a strong effect.
+    Firm,
+    /// Flat at both ends, steep in the middle.
+    SCurve,
+}
+
+impl ResponseCurve {
+    pub fn label(&self) -> String {
+        match self {
+            ResponseCurve::Linear => t!("response_curve.linear"),
+            ResponseCurve::Soft => t!("response_curve.soft"),
+            ResponseCurve::Firm => t!("response_curve.firm"),
+            ResponseCurve::SCurve => t!("response_curve.s_curve"),
+        }
+    }
+
+    pub fn all() -> &'static [ResponseCurve] {
+        &[
+            ResponseCurve::Linear,
+            ResponseCurve::Soft,
+            ResponseCurve::Firm,
+            ResponseCurve::SCurve,
         ]
     }
+
+    pub fn apply(&self, x: f32) -> f32 {
+        let x = x.clamp(0.0, 1.0);
+        match self {
+            ResponseCurve::Linear => x,
+            ResponseCurve::Soft => 1.0 - (1.0 - x) * (1.0 - x),
+            ResponseCurve::Firm => x * x,
+            ResponseCurve::SCurve => x * x * (3.0 - 2.0 * x),
+        }
+    }
+}
+
+/// Pointer stabilization applied to stroke input before stamping.
+/// LazyMouse: the brush eases toward the pointer by a fixed fraction per sample.
+/// PulledString: the brush only moves once the pointer is further away than
+/// the string length, and then trails it at exactly that distance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum StabilizerMode {
+    Off,
+    LazyMouse,
+    PulledString,
+}
+
+impl StabilizerMode {
+    pub fn label(&self) -> String {
+        match self {
+            StabilizerMode::Off => t!("stabilizer.off"),
+            StabilizerMode::LazyMouse => t!("stabilizer.lazy_mouse"),
+            StabilizerMode::PulledString => t!("stabilizer.pulled_string"),
+        }
+    }
+
+    pub fn all() -> &'static [StabilizerMode] {
+        &[
+            StabilizerMode::Off,
+            StabilizerMode::LazyMouse,
+            StabilizerMode::PulledString,
+        ]
+    }
+}
+
+/// Per-stamp brush dynamics: response curves, tilt and velocity mappings,
+/// stroke tapering and stabilization. Size/opacity mappings only ever reduce
+/// the base (pressure-scaled) values, so stamp bounds never grow past them.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct BrushDynamics {
+    /// Curve applied to pen pressure before the pressure size/opacity mapping.
+    pub pressure_curve: ResponseCurve,
+    /// Size reduction at full pen tilt (0.0..1.0).
+    pub tilt_size: f32,
+    /// Opacity reduction at full pen tilt (0.0..1.0).
+    pub tilt_opacity: f32,
+    /// Extra scatter at full pen tilt, as fraction of brush diameter (0.0..1.0).
+    pub tilt_scatter: f32,
+    /// Rotate image tips to follow the pen azimuth.
+    pub tilt_angle: bool,
+    /// Curve applied to normalized stroke speed.
+    pub velocity_curve: ResponseCurve,
+    /// Size reduction at full stroke speed (0.0..1.0).
+    pub velocity_size: f32,
+    /// Opacity reduction at full stroke speed (0.0..1.0).
+    pub velocity_opacity: f32,
+    /// Extra scatter at full stroke speed, as fraction of brush diameter (0.0..1.0).
+    pub velocity_scatter: f32,
+    /// Rotate image tips to follow the stroke direction.
+    pub direction_angle: bool,
+    /// Length in canvas pixels over which the stroke grows from a point. 0 = off.
+    pub taper_start: f32,
+    /// Length in canvas pixels over which the stroke shrinks to a point. 0 = off.
+    pub taper_end: f32,
+    pub stabilizer: StabilizerMode,
+    /// Lazy mouse strength (0.0..1.0): how much of the pointer movement is held back per sample.
+    pub stabilizer_strength: f32,
+    /// Pulled string length in screen pixels.
+    pub stabilizer_radius: f32,
+}
+
+impl Default for BrushDynamics {
+    fn default() -> Self {
+        Self {
+            pressure_curve: ResponseCurve::Linear,
+            tilt_size: 0.0,
+            tilt_opacity: 0.0,
+            tilt_scatter: 0.0,
+            tilt_angle: false,
+            velocity_curve: ResponseCurve::Linear,
+            velocity_size: 0.0,
+            velocity_opacity: 0.0,
+            velocity_scatter: 0.0,
+            direction_angle: false,
+            taper_start: 0.0,
+            taper_end: 0.0,
+            stabilizer: StabilizerMode::Off,
+            stabilizer_strength: 0.5,
+            stabilizer_radius: 20.0,
+        }
+    }
+}
+
+impl BrushDynamics {
+    /// Copy with every amount and length pulled into the range the UI allows.
+    pub fn clamped(&self) -> Self {
+        Self {
+            tilt_size: self.tilt_size.clamp(0.0, 1.0),
+            tilt_opacity: self.tilt_opacity.clamp(0.0, 1.0),
+            tilt_scatter: self.tilt_scatter.clamp(0.0, 1.0),
+            velocity_size: self.velocity_size.clamp(0.0, 1.0),
+            velocity_opacity: self.velocity_opacity.clamp(0.0, 1.0),
+            velocity_scatter: self.velocity_scatter.clamp(0.0, 1.0),
+            taper_start: self.taper_start.clamp(0.0, 500.0),
+            taper_end: self.taper_end.clamp(0.0, 500.0),
+            stabilizer_strength: self.stabilizer_strength.clamp(0.0, 1.0),
+            stabilizer_radius: self.stabilizer_radius.clamp(1.0, 200.0),
+            ..self.clone()
+        }
+    }
+}
+
+impl BrushDynamics {
+    /// True when any dynamics setting differs from a plain stroke.
+    pub fn is_active(&self) -> bool {
+        self.pressure_curve != ResponseCurve::Linear
+            || self.tilt_size > 0.0
+            || self.tilt_opacity > 0.0
+            || self.tilt_scatter > 0.0
+            || self.tilt_angle
+            || self.velocity_size > 0.0
+            || self.velocity_opacity > 0.0
+            || self.velocity_scatter > 0.0
+            || self.direction_angle
+            || self.taper_start > 0.0
+            || self.taper_end > 0.0
+            || self.stabilizer != StabilizerMode::Off
+    }
+}
+
+/// File actions requested from the brush presets picker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BrushPresetAction {
+    ImportPack,
+    ExportPack,
+    ImportAbr,
+}
+
+/// Pen orientation reported by the platform.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PenTilt {
+    /// Angle between pen and tablet surface in radians (0 = flat, PI/2 = upright).
+    pub altitude: f32,
+    /// Direction the pen leans in radians, measured clockwise from canvas +X.
+    pub azimuth: f32,
+}
+
+impl PenTilt {
+    /// Convert the tilt pair pen APIs report (Windows Ink, W3C pointer
+    /// events): the plane angles in degrees, -90..90, between the pen and
+    /// the screen normal along X and along Y (Y pointing down).
+    pub fn from_tilt_degrees(tilt_x: f32, tilt_y: f32) -> Self {
+        let tan_x = tilt_x.clamp(-89.9, 89.9).to_radians().tan();
+        let tan_y = tilt_y.clamp(-89.9, 89.9).to_radians().tan();
+        let lean = tan_x.hypot(tan_y);
+        if lean == 0.0 {
+            return Self {
+                altitude: std::f32::consts::FRAC_PI_2,
+                azimuth: 0.0,
+            };
+        }
+        Self {
+            altitude: (1.0 / lean).atan(),
+            azimuth: tan_y.atan2(tan_x).rem_euclid(std::f32::consts::TAU),
+        }
+    }
+}
+
+/// Multipliers resolved from tilt/velocity for the stamps of the current sample.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct StampDynamics {
+    size: f32,
+    opacity: f32,
+    scatter: f32,
+    /// Added to the tip rotation (degrees).
+    angle_deg: f32,
+}
+
+impl Default for StampDynamics {
+    fn default() -> Self {
+        Self {
+            size: 1.0,
+            opacity: 1.0,
+            scatter: 0.0,
+            angle_deg: 0.0,
+        }
+    }
+}
+
+/// One live stroke segment, recorded so the stroke can be re-rendered with an
+/// end taper once its total length is known.
+#[derive(Clone, Copy, Debug)]
+struct StrokeSegment {
+    start: (f32, f32),
+    end: (f32, f32),
+    arc_start: f32,
+    pressure: f32,
+    stamp: StampDynamics,
+    stamp_counter: u32,
 }
 
 #[derive(Clone, Debug)]
@@ -126,8 +413,12 @@ pub struct ToolProperties {
     pub hue_jitter: f32,
     /// Brightness jitter: 0.0..1.0 — random brightness variation per stamp. Default 0.0.
     pub brightness_jitter: f32,
-    /// Painting mode: Normal, Dodge, Burn, or Sponge.
+    /// Painting mode: Normal, Dodge, Burn, Sponge or Mixer.
     pub brush_mode: BrushMode,
+    /// Wetness, load and mix for the Mixer brush mode.
+    pub mixer: MixerSettings,
+    /// Response curves, tilt/velocity mappings, taper and stabilization.
+    pub dynamics: BrushDynamics,
 }
 
 impl Default for ToolProperties {
@@ -152,6 +443,8 @@ impl Default for ToolProperties {
             hue_jitter: 0.0,
             brightness_jitter: 0.0,
             brush_mode: BrushMode::Normal,
+            mixer: MixerSettings::default(),
+            dynamics: BrushDynamics::default(),
         }
     }
 }
@@ -319,6 +612,33 @@ pub struct ToolState {
     smooth_pos: Option<Pos2>,
     /// Current pen pressure (0.0..1.0). Defaults to 1.0 (no pen / full pressure).
     pub current_pressure: f32,
+    /// Current pen tilt, when the platform reports one.
+    current_tilt: Option<PenTilt>,
+    /// Smoothed, normalized stroke speed (0.0..1.0) before the velocity curve.
+    velocity: f32,
+    /// Input time (seconds) of the last velocity sample.
+    last_sample_time: Option<f64>,
+    /// Direction of travel of the stroke in radians.
+    stroke_direction: f32,
+    /// Canvas-space arc length painted so far in the current stroke.
+    stroke_distance: f32,
+    /// Total stroke length while re-rendering with an end taper.
+    stroke_total: Option<f32>,
+    /// Live segments of the current stroke (only recorded when end taper is on).
+    stroke_segments: Vec<StrokeSegment>,
+    /// Stabilized pointer position (brush position when a stabilizer is active).
+    stabilized_pos: Option<Pos2>,
+    /// Tilt/velocity multipliers for the stamps being drawn.
+    stamp: StampDynamics,
+    /// Taper size multiplier for the stamp being drawn.
+    taper_scale: f32,
+    /// Pixels the Mixer brush picks up from, captured at stroke start
+    /// (active layer, or the visible composite when sampling all layers).
+    mixer_source: Option<TiledImage>,
+    /// Mixer paint reservoir, premultiplied RGBA. `None` until the first stamp.
+    mixer_reservoir: Option<[f32; 4]>,
+    /// Unpremultiplied color deposited by the current Mixer stamp.
+    mixer_deposit: [f32; 4],
     brush_resize_drag_origin: Option<Pos2>,
     brush_resize_drag_start_size: f32,
     brush_resize_drag_active: bool,
@@ -334,6 +654,19 @@ impl Default for ToolState {
             using_secondary_color: false,
             smooth_pos: None,
             current_pressure: 1.0,
+            current_tilt: None,
+            velocity: 0.0,
+            last_sample_time: None,
+            stroke_direction: 0.0,
+            stroke_distance: 0.0,
+            stroke_total: None,
+            stroke_segments: Vec::new(),
+            stabilized_pos: None,
+            stamp: StampDynamics::default(),
+            taper_scale: 1.0,
+            mixer_source: None,
+            mixer_reservoir: None,
+            mixer_deposit: [0.0, 0.0, 0.0, 1.0],
             brush_resize_drag_origin: None,
             brush_resize_drag_start_size: 10.0,
             brush_resize_drag_active: false,
@@ -907,6 +1240,10 @@ pub enum GradientShape {
     LinearReflected,
     Radial,
     Diamond,
+    /// Sweeps once around the start point, beginning along the start→end direction.
+    Conic,
+    /// Conic sweep whose phase also advances with distance, one turn per handle length.
+    Spiral,
 }
 
 impl GradientShape {
@@ -916,6 +1253,8 @@ impl GradientShape {
             GradientShape::LinearReflected => t!("gradient_shape.linear_reflected"),
             GradientShape::Radial => t!("gradient_shape.radial"),
             GradientShape::Diamond => t!("gradient_shape.diamond"),
+            GradientShape::Conic => t!("gradient_shape.conic"),
+            GradientShape::Spiral => t!("gradient_shape.spiral"),
         }
     }
     pub fn all() -> &'static [GradientShape] {
@@ -924,6 +1263,8 @@ impl GradientShape {
             GradientShape::LinearReflected,
             GradientShape::Radial,
             GradientShape::Diamond,
+            GradientShape::Conic,
+            GradientShape::Spiral,
         ]
     }
 }
@@ -951,6 +1292,8 @@ pub struct GradientStop {
     pub position: f32,
     /// RGBA color (un-premultiplied)
     pub color: [u8; 4],
+    /// Where the blend towards the next stop reaches 50%, as a fraction of the span
+    pub midpoint: f32,
     /// HSV state to avoid roundtrip drift (h, s, v each 0.0..1.0)
     pub hsv: [f32; 3],
 }
@@ -962,6 +1305,7 @@ impl GradientStop {
         Self {
             position,
             color,
+            midpoint: 0.5,
             hsv,
         }
     }
@@ -1004,6 +1348,8 @@ pub struct GradientToolState {
     pub mode: GradientMode,
     pub preset: GradientPreset,
     pub stops: Vec<GradientStop>,
+    /// Opacity stops, multiplied with the color stops' own alpha
+    pub opacity_stops: Vec<crate::gradient::OpacityStop>,
     /// Canvas-coordinate start point
     pub drag_start: Option<Pos2>,
     /// Canvas-coordinate end point
@@ -1015,6 +1361,12 @@ pub struct GradientToolState {
     pub repeat: bool,
     /// Which stop is selected for color editing (index)
     pub selected_stop: Option<usize>,
+    /// Which opacity stop is selected for editing (index)
+    pub selected_opacity_stop: Option<usize>,
+    /// Ordered-dither the output so long, smooth gradients don't band
+    pub dither: bool,
+    /// Name typed into the "Save to library" field
+    pub library_name: String,
     /// Pre-computed LUT for fast per-pixel lookup (256 entries × 4 channels)
     lut: Vec<u8>,
     lut_dirty: bool,
@@ -1040,12 +1392,19 @@ impl Default for GradientToolState {
             mode: GradientMode::Color,
             preset: GradientPreset::PrimarySecondary,
             stops,
+            opacity_stops: vec![
+                crate::gradient::OpacityStop::new(0.0, 255),
+                crate::gradient::OpacityStop::new(1.0, 255),
+            ],
             drag_start: None,
             drag_end: None,
             dragging: false,
             dragging_handle: None,
             repeat: false,
             selected_stop: None,
+            selected_opacity_stop: None,
+            dither: true,
+            library_name: String::new(),
             lut: Vec::new(),
             lut_dirty: true,
             preview_dirty: false,
@@ -1061,72 +1420,46 @@ impl Default for GradientToolState {
 impl GradientToolState {
     /// Build the 256-entry RGBA lookup table from current stops.
     pub fn rebuild_lut(&mut self) {
-        self.lut.resize(256 * 4, 0);
-        let stops = &self.stops;
-        if stops.is_empty() {
-            self.lut.fill(0);
-            self.lut_dirty = false;
-            return;
-        }
-        if stops.len() == 1 {
-            let c = &stops[0].color;
-            for i in 0..256 {
-                let off = i * 4;
-                self.lut[off] = c[0];
-                self.lut[off + 1] = c[1];
-                self.lut[off + 2] = c[2];
-                self.lut[off + 3] = c[3];
-            }
-            self.lut_dirty = false;
-            return;
-        }
-        // Sort stops by position
-        let mut sorted: Vec<(f32, [u8; 4])> = stops.iter().map(|s| (s.position, s.color)).collect();
-        sorted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
-
-        for i in 0..256 {
-            let t = i as f32 / 255.0;
-            let off = i * 4;
-            // Find the two surrounding stops
-            if t <= sorted[0].0 {
-                let c = &sorted[0].1;
-                self.lut[off] = c[0];
-                self.lut[off + 1] = c[1];
-                self.lut[off + 2] = c[2];
-                self.lut[off + 3] = c[3];
-            } else if t >= sorted.last().unwrap().0 {
-                let c = &sorted.last().unwrap().1;
-                self.lut[off] = c[0];
-                self.lut[off + 1] = c[1];
-                self.lut[off + 2] = c[2];
-                self.lut[off + 3] = c[3];
-            } else {
-                // Linear interpolation between surrounding stops
-                let mut left = &sorted[0];
-                let mut right = &sorted[sorted.len() - 1];
-                for j in 0..sorted.len() - 1 {
-                    if sorted[j].0 <= t && sorted[j + 1].0 >= t {
-                        left = &sorted[j];
-                        right = &sorted[j + 1];
-                        break;
-                    }
-                }
-                let span = right.0 - left.0;
-                let local_t = if span > 0.0 { (t - left.0) / span } else { 0.0 };
-                let inv = 1.0 - local_t;
-                self.lut[off] =
-                    (left.1[0] as f32 * inv + right.1[0] as f32 * local_t).round() as u8;
-                self.lut[off + 1] =
-                    (left.1[1] as f32 * inv + right.1[1] as f32 * local_t).round() as u8;
-                self.lut[off + 2] =
-                    (left.1[2] as f32 * inv + right.1[2] as f32 * local_t).round() as u8;
-                self.lut[off + 3] =
-                    (left.1[3] as f32 * inv + right.1[3] as f32 * local_t).round() as u8;
-            }
-        }
+        self.lut = self.to_gradient("").build_lut_flat();
         self.lut_dirty = false;
     }
 
+    /// The current stops as a shareable [`crate::gradient::Gradient`].
+    pub fn to_gradient(&self, name: &str) -> crate::gradient::Gradient {
+        crate::gradient::Gradient {
+            name: name.to_string(),
+            color_stops: self
+                .stops
+                .iter()
+                .map(|s| crate::gradient::ColorStop {
+                    position: s.position,
+                    color: s.color,
+                    midpoint: s.midpoint,
+                })
+                .collect(),
+            opacity_stops: self.opacity_stops.clone(),
+        }
+    }
+
+    /// Replace the stops with those of `gradient` (library entry or import).
+    pub fn load_gradient(&mut self, gradient: &crate::gradient::Gradient) {
+        self.stops = gradient
+            .color_stops
+            .iter()
+            .map(|s| {
+                let mut stop = GradientStop::new(s.position, s.color);
+                stop.midpoint = s.midpoint;
+                stop
+            })
+            .collect();
+        self.opacity_stops = gradient.opacity_stops.clone();
+        self.selected_stop = None;
+        self.selected_opacity_stop = None;
+        self.preset = GradientPreset::Custom;
+        self.lut_dirty = true;
+        self.preview_dirty = true;
+    }
+
     /// Sample the gradient LUT at position t (0.0..1.0 clamped).
     #[inline(always)]
     pub fn sample_lut(&self, t: f32) -> [u8; 4] {
@@ -1154,7 +1487,7 @@ impl GradientToolState {
             ],
             GradientPreset::ForegroundTransparent => vec![
                 GradientStop::new(0.0, primary),
-                GradientStop::new(1.0, [primary[0], primary[1], primary[2], 0]),
+                GradientStop::new(1.0, primary),
             ],
             GradientPreset::Rainbow => vec![
                 GradientStop::new(0.0, [255, 0, 0, 255]),
@@ -1167,6 +1500,16 @@ impl GradientToolState {
             ],
             GradientPreset::Custom => return, // don't modify stops
         };
+        let end_opacity = if preset == GradientPreset::ForegroundTransparent {
+            0
+        } else {
+            255
+        };
+        self.opacity_stops = vec![
+            crate::gradient::OpacityStop::new(0.0, 255),
+            crate::gradient::OpacityStop::new(1.0, end_opacity),
+        ];
+        self.selected_opacity_stop = None;
         self.lut_dirty = true;
     }
 
@@ -1225,6 +1568,16 @@ impl GradientToolState {
                     dist.clamp(0.0, 1.0)
                 }
             }
+            // Angular shapes wrap by nature, so `repeat` has no effect.
+            GradientShape::Conic => {
+                let angle = (py - ay).atan2(px - ax) - dy.atan2(dx);
+                (angle / std::f32::consts::TAU).rem_euclid(1.0)
+            }
+            GradientShape::Spiral => {
+                let angle = (py - ay).atan2(px - ax) - dy.atan2(dx);
+                let dist = ((px - ax) * (px - ax) + (py - ay) * (py - ay)).sqrt() / len_sq.sqrt();
+                (angle / std::f32::consts::TAU + dist).rem_euclid(1.0)
+            }
         }
     }
 }
@@ -1839,6 +2192,7 @@ pub struct ToolsPanel {
     pub lasso_state: LassoState,
     pub perspective_crop_state: PerspectiveCropState,
     pub zoom_tool_state: ZoomToolState,
+    pub slice_state: SliceToolState,
     /// Consumed by Canvas each frame.
     pub zoom_pan_action: ZoomPanAction,
     pub clone_stamp_state: CloneStampState,
@@ -1878,6 +2232,9 @@ pub struct ToolsPanel {
     /// Pending history commands from tool operations (e.g., perspective crop)
     /// Consumed by app.rs each frame.
     pub pending_history_commands: Vec<Box<dyn crate::components::history::Command>>,
+    /// Infinite canvas: the canvas as it was before a stroke grew it.
+    /// Folded into that stroke's history entry by app.rs.
+    pub canvas_growth_before: Option<crate::components::history::CanvasSnapshot>,
     /// When set, the active text layer at this index needs to be rasterized
     /// before a destructive tool operation can proceed. Consumed by app.rs.
     pub pending_auto_rasterize: Option<usize>,
@@ -1904,6 +2261,19 @@ pub struct ToolsPanel {
     pub pending_delete_brush_tip: Option<String>,
     /// Right-click context menu state for brush tip picker: (tip_name, screen_pos_x, screen_pos_y)
     pub brush_tip_context_menu: Option<(String, f32, f32)>,
+    /// Named brush presets shown in the presets picker (mirrors `AppSettings::brush_presets`).
+    pub brush_presets: Vec<crate::config::brush_presets::BrushPreset>,
+    /// Set when presets were added/removed in the picker; consumed by app.rs to persist them.
+    pub brush_presets_changed: bool,
+    /// File action requested from the presets/tip pickers; consumed by app.rs.
+    pub pending_brush_preset_action: Option<BrushPresetAction>,
+    /// Saved gradients shown in the gradient library picker (mirrors `AppSettings::gradient_library`).
+    pub gradient_library: Vec<crate::gradient::Gradient>,
+    /// Set when the library was edited and needs persisting.
+    pub gradient_library_changed: bool,
+    /// Set when the user asked to import `.grd`/`.ggr` gradients.
+    pub pending_gradient_import: bool,
+    brush_preset_name_input: String,
     /// Floating child UI that must block canvas input/cursor handling.
     pub cursor_blocking_rect: Option<egui::Rect>,
     canvas_pointer_active: bool,
@@ -1925,6 +2295,7 @@ impl Default for ToolsPanel {
             lasso_state: LassoState::default(),
             perspective_crop_state: PerspectiveCropState::default(),
             zoom_tool_state: ZoomToolState::default(),
+            slice_state: SliceToolState::default(),
             zoom_pan_action: ZoomPanAction::default(),
             clone_stamp_state: CloneStampState::default(),
             content_aware_state: ContentAwareBrushState::default(),
@@ -1950,6 +2321,7 @@ impl Default for ToolsPanel {
             stamp_counter: 0,
             active_tip_rotation_deg: 0.0,
             pending_history_commands: Vec::new(),
+            canvas_growth_before: None,
             pending_auto_rasterize: None,
             tool_before_text_layer: None,
             sel_modify_radius: 5.0,
@@ -1963,6 +2335,13 @@ impl Default for ToolsPanel {
             pending_open_add_brush_tip: false,
             pending_delete_brush_tip: None,
             brush_tip_context_menu: None,
+            brush_presets: Vec::new(),
+            brush_presets_changed: false,
+            pending_brush_preset_action: None,
+            gradient_library: Vec::new(),
+            gradient_library_changed: false,
+            pending_gradient_import: false,
+            brush_preset_name_input: String::new(),
             cursor_blocking_rect: None,
             canvas_pointer_active: false,
         }
@@ -2064,6 +2443,52 @@ pub struct ZoomToolState {
     pub zoom_out_mode: bool,
 }
 
+/// State for the Slice tool.
+#[derive(Clone, Debug)]
+pub struct SliceToolState {
+    pub drag_start: Option<Pos2>,
+    pub drag_end: Option<Pos2>,
+    pub dragging: bool,
+    /// Index into `CanvasState::slices` of the slice shown in the context bar.
+    pub selected: Option<usize>,
+    /// Context bar name field for the selected slice.
+    pub name_edit: String,
+    pub grid_columns: u32,
+    pub grid_rows: u32,
+    /// Set from the context bar; applied by app.rs, which has the document.
+    pub pending_action: Option<SliceToolAction>,
+}
+
+impl Default for SliceToolState {
+    fn default() -> Self {
+        Self {
+            drag_start: None,
+            drag_end: None,
+            dragging: false,
+            selected: None,
+            name_edit: String::new(),
+            grid_columns: 4,
+            grid_rows: 4,
+            pending_action: None,
+        }
+    }
+}
+
+/// Slice edits requested from the context bar.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SliceToolAction {
+    Rename(usize, String),
+    Delete(usize),
+    /// Replace all slices with a `columns`×`rows` grid.
+    Grid {
+        columns: u32,
+        rows: u32,
+    },
+    /// Add a slice around each visible layer's pixels.
+    FromLayers,
+    Clear,
+}
+
 /// State for the Clone Stamp tool.
 #[derive(Clone, Debug, Default)]
 pub struct CloneStampState {
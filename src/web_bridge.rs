@@ -101,12 +101,12 @@ pub fn drain_pending(tag: &'static str) -> Vec<(String, Vec<u8>)> {
 const OPEN_TAG: &str = "open";
 
 /// Open the browser's native file picker for importing images/.pfe projects.
+/// Accepts every format the native Open dialog lists that loads from bytes.
 pub fn trigger_open_picker() {
-    open_picker(
-        OPEN_TAG,
-        ".pfe,.png,.jpg,.jpeg,.webp,.bmp,.tga,.gif,.ico,.tiff,.tif",
-        true,
-    );
+    let accept: Vec<String> = crate::io::byte_open_extensions()
+        .map(|ext| format!(".{}", ext))
+        .collect();
+    open_picker(OPEN_TAG, &accept.join(","), true);
 }
 
 /// Take all project/image files that have finished reading since the last call.
@@ -5,6 +5,7 @@
 #![allow(dead_code)]
 
 use image::{Rgba, RgbaImage};
+use paintfe::canvas::BlendMode;
 use std::path::{Path, PathBuf};
 
 // =============================================================================
@@ -339,6 +340,85 @@ pub fn create_color_bands(w: u32, h: u32) -> RgbaImage {
     img
 }
 
+// =============================================================================
+// Blend mode reference values
+// =============================================================================
+
+/// `(mode, x, base, top, expected)`: `top` at full layer opacity over an
+/// opaque `base` at pixel `(x, 0)`. The position only matters for Dissolve.
+pub type BlendCase = (BlendMode, u32, [u8; 4], [u8; 4], [u8; 4]);
+
+/// Known blends shared by the CPU and GPU compositor tests.
+#[rustfmt::skip]
+pub const BLEND_CASES: &[BlendCase] = &[
+    (BlendMode::Normal,       0, [200,  60,  40, 255], [ 40,  90, 220, 255], [ 40,  90, 220, 255]),
+    (BlendMode::Normal,       0, [ 90, 160, 210, 255], [230, 120,  30, 128], [160, 139, 119, 255]),
+    (BlendMode::Multiply,     0, [200,  60,  40, 255], [ 40,  90, 220, 255], [ 31,  21,  34, 255]),
+    (BlendMode::Multiply,     0, [ 90, 160, 210, 255], [230, 120,  30, 128], [ 85, 117, 116, 255]),
+    (BlendMode::Screen,       0, [200,  60,  40, 255], [ 40,  90, 220, 255], [208, 128, 225, 255]),
+    (BlendMode::Screen,       0, [ 90, 160, 210, 255], [230, 120,  30, 128], [164, 182, 212, 255]),
+    (BlendMode::Additive,     0, [200,  60,  40, 255], [ 40,  90, 220, 255], [240, 150, 255, 255]),
+    (BlendMode::Additive,     0, [ 90, 160, 210, 255], [230, 120,  30, 128], [172, 207, 225, 255]),
+    (BlendMode::Reflect,      0, [200,  60,  40, 255], [ 40,  90, 220, 255], [186,  21,  45, 255]),
+    (BlendMode::Reflect,      0, [ 90, 160, 210, 255], [230, 120,  30, 128], [172, 174, 202, 255]),
+    (BlendMode::Glow,         0, [200,  60,  40, 255], [ 40,  90, 220, 255], [ 29,  41, 225, 255]),
+    (BlendMode::Glow,         0, [ 90, 160, 210, 255], [230, 120,  30, 128], [172, 155, 114, 255]),
+    (BlendMode::ColorBurn,    0, [200,  60,  40, 255], [ 40,  90, 220, 255], [  0,   0,   5, 255]),
+    (BlendMode::ColorBurn,    0, [ 90, 160, 210, 255], [230, 120,  30, 128], [ 80, 106, 104, 255]),
+    (BlendMode::ColorDodge,   0, [200,  60,  40, 255], [ 40,  90, 220, 255], [237,  92, 255, 255]),
+    (BlendMode::ColorDodge,   0, [ 90, 160, 210, 255], [230, 120,  30, 128], [172, 207, 224, 255]),
+    (BlendMode::Overlay,      0, [200,  60,  40, 255], [ 40,  90, 220, 255], [162,  42,  69, 255]),
+    (BlendMode::Overlay,      0, [ 90, 160, 210, 255], [230, 120,  30, 128], [126, 157, 192, 255]),
+    (BlendMode::Difference,   0, [200,  60,  40, 255], [ 40,  90, 220, 255], [160,  30, 180, 255]),
+    (BlendMode::Difference,   0, [ 90, 160, 210, 255], [230, 120,  30, 128], [115,  99, 194, 255]),
+    (BlendMode::Negation,     0, [200,  60,  40, 255], [ 40,  90, 220, 255], [240, 149, 250, 255]),
+    (BlendMode::Negation,     0, [ 90, 160, 210, 255], [230, 120,  30, 128], [140, 195, 225, 255]),
+    (BlendMode::Lighten,      0, [200,  60,  40, 255], [ 40,  90, 220, 255], [200,  90, 220, 255]),
+    (BlendMode::Lighten,      0, [ 90, 160, 210, 255], [230, 120,  30, 128], [160, 160, 210, 255]),
+    (BlendMode::Darken,       0, [200,  60,  40, 255], [ 40,  90, 220, 255], [ 40,  60,  39, 255]),
+    (BlendMode::Darken,       0, [ 90, 160, 210, 255], [230, 120,  30, 128], [ 90, 139, 119, 255]),
+    (BlendMode::Xor,          0, [200,  60,  40, 255], [ 40,  90, 220, 255], [  0,   0,   0,   0]),
+    (BlendMode::Xor,          0, [ 90, 160, 210, 255], [230, 120,  30, 128], [ 90, 160, 210, 126]),
+    (BlendMode::Overwrite,    0, [200,  60,  40, 255], [ 40,  90, 220, 255], [ 40,  90, 220, 255]),
+    (BlendMode::Overwrite,    0, [ 90, 160, 210, 255], [230, 120,  30, 128], [230, 120,  30, 128]),
+    (BlendMode::HardLight,    0, [200,  60,  40, 255], [ 40,  90, 220, 255], [ 62,  42, 195, 255]),
+    (BlendMode::HardLight,    0, [ 90, 160, 210, 255], [230, 120,  30, 128], [156, 155, 129, 255]),
+    (BlendMode::SoftLight,    0, [200,  60,  40, 255], [ 40,  90, 220, 255], [170,  46,  83, 255]),
+    (BlendMode::SoftLight,    0, [ 90, 160, 210, 255], [230, 120,  30, 128], [114, 158, 195, 255]),
+    (BlendMode::Exclusion,    0, [200,  60,  40, 255], [ 40,  90, 220, 255], [177, 107, 190, 255]),
+    (BlendMode::Exclusion,    0, [ 90, 160, 210, 255], [230, 120,  30, 128], [123, 144, 200, 255]),
+    (BlendMode::Subtract,     0, [200,  60,  40, 255], [ 40,  90, 220, 255], [160,   0,   0, 255]),
+    (BlendMode::Subtract,     0, [ 90, 160, 210, 255], [230, 120,  30, 128], [ 44,  99, 194, 255]),
+    (BlendMode::Divide,       0, [200,  60,  40, 255], [ 40,  90, 220, 255], [255, 169,  46, 255]),
+    (BlendMode::Divide,       0, [ 90, 160, 210, 255], [230, 120,  30, 128], [ 94, 207, 232, 255]),
+    (BlendMode::LinearBurn,   0, [200,  60,  40, 255], [ 40,  90, 220, 255], [  0,   0,   5, 255]),
+    (BlendMode::LinearBurn,   0, [ 90, 160, 210, 255], [230, 120,  30, 128], [ 77,  92, 104, 255]),
+    (BlendMode::VividLight,   0, [200,  60,  40, 255], [ 40,  90, 220, 255], [ 79,   0, 145, 255]),
+    (BlendMode::VividLight,   0, [ 90, 160, 210, 255], [230, 120,  30, 128], [172, 157, 136, 255]),
+    (BlendMode::LinearLight,  0, [200,  60,  40, 255], [ 40,  90, 220, 255], [ 25,   0, 225, 255]),
+    (BlendMode::LinearLight,  0, [ 90, 160, 210, 255], [230, 120,  30, 128], [172, 152, 112, 255]),
+    (BlendMode::PinLight,     0, [200,  60,  40, 255], [ 40,  90, 220, 255], [ 80,  60, 185, 255]),
+    (BlendMode::PinLight,     0, [ 90, 160, 210, 255], [230, 120,  30, 128], [147, 160, 134, 255]),
+    (BlendMode::HardMix,      0, [200,  60,  40, 255], [ 40,  90, 220, 255], [  0,   0, 255, 255]),
+    (BlendMode::HardMix,      0, [ 90, 160, 210, 255], [230, 120,  30, 128], [172, 207, 104, 255]),
+    (BlendMode::Hue,          0, [200,  60,  40, 255], [ 40,  90, 220, 255], [ 55, 100, 215, 255]),
+    (BlendMode::Hue,          0, [ 90, 160, 210, 255], [230, 120,  30, 128], [143, 145, 143, 255]),
+    (BlendMode::Saturation,   0, [200,  60,  40, 255], [ 40,  90, 220, 255], [212,  55,  32, 255]),
+    (BlendMode::Saturation,   0, [ 90, 160, 210, 255], [230, 120,  30, 128], [ 71, 165, 231, 255]),
+    (BlendMode::Color,        0, [200,  60,  40, 255], [ 40,  90, 220, 255], [ 50, 100, 230, 255]),
+    (BlendMode::Color,        0, [ 90, 160, 210, 255], [230, 120,  30, 128], [160, 140, 120, 255]),
+    (BlendMode::Luminosity,   0, [200,  60,  40, 255], [ 40,  90, 220, 255], [189,  49,  29, 255]),
+    (BlendMode::Luminosity,   0, [ 90, 160, 210, 255], [230, 120,  30, 128], [ 89, 159, 209, 255]),
+    (BlendMode::DarkerColor,  0, [200,  60,  40, 255], [ 40,  90, 220, 255], [ 40,  90, 220, 255]),
+    (BlendMode::DarkerColor,  0, [ 90, 160, 210, 255], [230, 120,  30, 128], [160, 139, 119, 255]),
+    (BlendMode::LighterColor, 0, [200,  60,  40, 255], [ 40,  90, 220, 255], [200,  60,  39, 255]),
+    (BlendMode::LighterColor, 0, [ 90, 160, 210, 255], [230, 120,  30, 128], [ 90, 160, 210, 255]),
+    (BlendMode::Dissolve,     0, [200,  60,  40, 255], [ 40,  90, 220, 255], [ 40,  90, 220, 255]),
+    (BlendMode::Dissolve,     0, [ 90, 160, 210, 255], [230, 120,  30, 128], [230, 120,  30, 255]),
+    (BlendMode::Dissolve,     1, [ 90, 160, 210, 255], [230, 120,  30, 128], [ 90, 160, 210, 255]),
+    (BlendMode::Dissolve,     4, [ 90, 160, 210, 255], [230, 120,  30, 128], [230, 120,  30, 255]),
+];
+
 /// Create a simple `CanvasState` with one layer containing the given image.
 pub fn canvas_from_image(img: &RgbaImage) -> paintfe::canvas::CanvasState {
     let w = img.width();
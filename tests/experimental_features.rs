@@ -293,6 +293,41 @@ fn editing_fp32_hdr_layer_preserves_untouched_overbright_samples() {
     assert_eq!(edited[3], 1.0);
 }
 
+#[test]
+fn editing_scene_linear_hdr_layer_decodes_srgb_preview() {
+    let values = vec![2.5_f32, 1.25, 0.5, 1.0, 4.0, 0.25, 0.125, 0.75];
+    let mut state = paintfe::canvas::CanvasState::new(2, 1);
+    let deep = DeepRgbaBuffer::F32(values.clone());
+    state.layers[0].pixel_format = PixelFormat::RgbaF32;
+    state.layers[0].hdr_metadata.enabled = true;
+    state.layers[0].hdr_metadata.transfer_function = Some("scene-linear-f32".to_string());
+    let preview = state.layers[0]
+        .hdr_metadata
+        .payload_view()
+        .preview(&deep, 2, 1)
+        .unwrap();
+    assert_eq!(preview.get_pixel(1, 0).0[1], 137);
+    state.layers[0].deep_pixels = Some(deep);
+    state.layers[0].pixels = paintfe::canvas::TiledImage::from_rgba_image(&preview);
+
+    state.layers[0]
+        .pixels
+        .put_pixel(0, 0, Rgba([64, 128, 255, 255]));
+    state.mark_dirty(Some(egui::Rect::from_min_max(
+        egui::pos2(0.0, 0.0),
+        egui::pos2(1.0, 1.0),
+    )));
+
+    let Some(DeepRgbaBuffer::F32(edited)) = &state.layers[0].deep_pixels else {
+        panic!("expected edited f32 payload");
+    };
+    assert_eq!(&edited[4..8], &values[4..8]);
+    assert!((edited[0] - paintfe::canvas::srgb8_to_linear(64)).abs() < 0.0001);
+    assert!((edited[1] - paintfe::canvas::srgb8_to_linear(128)).abs() < 0.0001);
+    assert_eq!(edited[2], 1.0);
+    assert_eq!(edited[3], 1.0);
+}
+
 #[test]
 fn exports_16bit_tiff_and_reopens_exact_deep_values() {
     let values = vec![
@@ -428,3 +463,228 @@ fn pfe3_round_trips_fp32_hdr_payload() {
         Some(250.0)
     );
 }
+
+fn float_state(width: u32, height: u32, deep: DeepRgbaBuffer) -> paintfe::canvas::CanvasState {
+    let mut state = paintfe::canvas::CanvasState::new(width, height);
+    state.layers[0].pixel_format = deep.format();
+    state.layers[0].hdr_metadata.enabled = true;
+    state.layers[0].pixels =
+        paintfe::canvas::TiledImage::from_rgba_image(&deep.to_rgba8(width, height).unwrap());
+    state.layers[0].deep_pixels = Some(deep);
+    state
+}
+
+#[test]
+fn exr_round_trips_half_and_float_layers_exactly() {
+    use paintfe::experimental::f32_to_f16_bits;
+
+    let half: Vec<u16> = [3.5_f32, 0.25, 0.0, 1.0, 0.5, 12.0, 0.125, 0.5]
+        .iter()
+        .map(|&v| f32_to_f16_bits(v))
+        .collect();
+    let float = vec![0.1_f32, 0.2, 7.25, 1.0, 0.0, 0.0, 0.0, 0.0];
+    let mut state = float_state(2, 1, DeepRgbaBuffer::F16(half.clone()));
+    state.layers[0].name = "Beauty".to_string();
+    let top = float_state(2, 1, DeepRgbaBuffer::F32(float.clone()))
+        .layers
+        .remove(0);
+    state.layers.push(Layer {
+        name: "Emission".to_string(),
+        ..top
+    });
+
+    let path = std::env::temp_dir().join(format!("paintfe_layers_{}.exr", std::process::id()));
+    paintfe::hdr_io::save_exr(&state, &path).unwrap();
+    let loaded = load_image_sync(&path).unwrap();
+    let _ = std::fs::remove_file(path);
+
+    assert_eq!(loaded.layers.len(), 2);
+    assert_eq!(loaded.layers[0].name, "Beauty");
+    assert_eq!(loaded.layers[1].name, "Emission");
+    assert_eq!(loaded.layers[0].pixel_format, PixelFormat::RgbaF16);
+    assert_eq!(
+        loaded.layers[0].deep_pixels,
+        Some(DeepRgbaBuffer::F16(half))
+    );
+    assert_eq!(loaded.layers[1].pixel_format, PixelFormat::RgbaF32);
+    assert_eq!(
+        loaded.layers[1].deep_pixels,
+        Some(DeepRgbaBuffer::F32(float))
+    );
+    assert!(loaded.layers[0].hdr_metadata.enabled);
+}
+
+#[test]
+fn exr_channel_groups_and_data_window_map_to_layers() {
+    use exr::prelude::*;
+
+    let size = Vec2(2, 1);
+    let f32s = |v: &[f32]| FlatSamples::F32(v.to_vec());
+    let f16s = |v: &[f32]| FlatSamples::F16(v.iter().map(|&x| f16::from_f32(x)).collect());
+    let channels: SmallVec<[AnyChannel<FlatSamples>; 4]> = [
+        AnyChannel::new("R", f32s(&[2.0, 0.5])),
+        AnyChannel::new("G", f32s(&[1.0, 0.5])),
+        AnyChannel::new("B", f32s(&[0.0, 0.5])),
+        AnyChannel::new("diffuse.R", f16s(&[0.25, 0.75])),
+        AnyChannel::new("diffuse.G", f16s(&[0.5, 0.5])),
+        AnyChannel::new("diffuse.B", f16s(&[0.75, 0.25])),
+        AnyChannel::new("Z", f32s(&[10.0, 20.0])),
+    ]
+    .into_iter()
+    .collect();
+    let mut attributes = LayerAttributes::named("main");
+    attributes.layer_position = Vec2(1, 1);
+    let layer = exr::prelude::Layer::new(
+        size,
+        attributes,
+        Encoding::UNCOMPRESSED,
+        AnyChannels::sort(channels),
+    );
+    let image = Image::from_layers(
+        ImageAttributes::new(IntegerBounds::from_dimensions(Vec2(3, 2))),
+        vec![layer],
+    );
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    image.write().to_buffered(&mut bytes).unwrap();
+
+    let state = paintfe::hdr_io::parse_exr(&bytes.into_inner()).unwrap();
+    assert_eq!((state.width, state.height), (3, 2));
+    let names: Vec<&str> = state.layers.iter().map(|l| l.name.as_str()).collect();
+    // Channels arrive sorted by name; the first group ends up on top.
+    assert_eq!(names, ["main.diffuse", "main.Z", "main"]);
+
+    // Unprefixed RGB sits on top, offset by the data window, with opaque alpha.
+    let Some(DeepRgbaBuffer::F32(main)) = &state.layers[2].deep_pixels else {
+        panic!("expected f32 main layer");
+    };
+    assert_eq!(&main[0..4], &[0.0, 0.0, 0.0, 0.0]);
+    assert_eq!(&main[16..20], &[2.0, 1.0, 0.0, 1.0]);
+    assert_eq!(state.layers[2].pixels.get_pixel(1, 1).0, [255, 255, 0, 255]);
+
+    assert!(matches!(
+        state.layers[0].deep_pixels,
+        Some(DeepRgbaBuffer::F16(_))
+    ));
+    let Some(DeepRgbaBuffer::F32(depth)) = &state.layers[1].deep_pixels else {
+        panic!("expected f32 depth layer");
+    };
+    assert_eq!(&depth[20..24], &[20.0, 20.0, 20.0, 1.0]);
+}
+
+#[test]
+fn radiance_hdr_export_keeps_overbright_values() {
+    let values = vec![4.0_f32, 1.0, 0.5, 1.0, 0.25, 0.125, 16.0, 1.0];
+    let state = float_state(2, 1, DeepRgbaBuffer::F32(values.clone()));
+
+    let path = std::env::temp_dir().join(format!("paintfe_export_{}.hdr", std::process::id()));
+    encode_canvas_state_and_write(
+        &state,
+        &path,
+        SaveFormat::Hdr,
+        95,
+        TiffCompression::None,
+        true,
+    )
+    .unwrap();
+    let loaded = load_image_sync(&path).unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let Some(DeepRgbaBuffer::F32(decoded)) = &loaded.layers[0].deep_pixels else {
+        panic!("expected f32 payload from .hdr");
+    };
+    for (got, want) in decoded.iter().zip(&values) {
+        // RGBE keeps about 1% precision per pixel.
+        assert!((got - want).abs() <= want * 0.02, "{} vs {}", got, want);
+    }
+    assert!(loaded.layers[0].hdr_metadata.enabled);
+}
+
+#[test]
+fn hdr_view_drives_preview_edits_and_8bit_export() {
+    use paintfe::experimental::{HdrToneMap, HdrView};
+
+    let values = vec![3.0_f32, 0.5, 0.25, 1.0, 0.75, 0.75, 0.75, 1.0];
+    let mut state = float_state(2, 1, DeepRgbaBuffer::F32(values.clone()));
+    let view = HdrView {
+        exposure: -1.0,
+        tone_map: HdrToneMap::Reinhard,
+        ..HdrView::default()
+    };
+    state.set_hdr_view(view);
+
+    // The payload is untouched; the preview is re-rendered through the view.
+    assert_eq!(
+        state.layers[0].deep_pixels,
+        Some(DeepRgbaBuffer::F32(values.clone()))
+    );
+    let expected = view.map([3.0, 0.5, 0.25, 1.0]);
+    assert_eq!(state.layers[0].pixels.get_pixel(0, 0), &expected);
+
+    let path = std::env::temp_dir().join(format!("paintfe_hdr_view_{}.png", std::process::id()));
+    encode_canvas_state_and_write(
+        &state,
+        &path,
+        SaveFormat::Png,
+        95,
+        TiffCompression::None,
+        true,
+    )
+    .unwrap();
+    let exported = image::open(&path).unwrap().to_rgba8();
+    let _ = std::fs::remove_file(path);
+    assert_eq!(exported.get_pixel(0, 0), &expected);
+
+    // Painting through the view writes the inverse-mapped value back.
+    state.layers[0]
+        .pixels
+        .put_pixel(1, 0, Rgba([128, 128, 128, 255]));
+    state.mark_dirty(Some(egui::Rect::from_min_max(
+        egui::pos2(1.0, 0.0),
+        egui::pos2(2.0, 1.0),
+    )));
+    let Some(DeepRgbaBuffer::F32(edited)) = &state.layers[0].deep_pixels else {
+        panic!("expected f32 payload");
+    };
+    assert_eq!(&edited[0..4], &values[0..4]);
+    assert_eq!(
+        view.map([edited[4], edited[5], edited[6], edited[7]]).0,
+        [128, 128, 128, 255]
+    );
+    assert!(edited[4] > 1.0);
+}
+
+#[test]
+fn hdr_mid_grey_is_srgb_encoded_and_round_trips() {
+    use paintfe::experimental::{HdrTransfer, HdrView};
+
+    // Scene-linear 18% grey displays as sRGB 118, not 46.
+    let view = HdrView {
+        transfer: HdrTransfer::SceneLinear,
+        ..HdrView::default()
+    };
+    let grey = view.map([0.18, 0.18, 0.18, 1.0]);
+    assert_eq!(grey.0, [118, 118, 118, 255]);
+    let back = view.unmap(grey);
+    assert!((back[0] - 0.18).abs() < 0.002, "{}", back[0]);
+
+    // An 8-bit layer is decoded to linear on the way into EXR and encoded
+    // again on the way back.
+    let mut state = paintfe::canvas::CanvasState::new(1, 1);
+    state.layers[0]
+        .pixels
+        .put_pixel(0, 0, Rgba([118, 118, 118, 255]));
+    let path = std::env::temp_dir().join(format!("paintfe_grey_{}.exr", std::process::id()));
+    paintfe::hdr_io::save_exr(&state, &path).unwrap();
+    let loaded = load_image_sync(&path).unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let Some(DeepRgbaBuffer::F16(samples)) = &loaded.layers[0].deep_pixels else {
+        panic!("expected f16 payload from EXR");
+    };
+    let linear = paintfe::experimental::f16_bits_to_f32(samples[0]);
+    assert!((linear - 0.18).abs() < 0.002, "{}", linear);
+    assert_eq!(
+        loaded.layers[0].pixels.get_pixel(0, 0).0,
+        [118, 118, 118, 255]
+    );
+}
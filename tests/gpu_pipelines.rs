@@ -7,7 +7,8 @@
 // GpuRenderer; if the software adapter is unavailable (rare), the test is skipped.
 //
 // Cover: Gaussian blur, gradient, displacement warp (liquify), mesh warp,
-//        flood fill distance map, compositor.
+//        flood fill distance map, compositor (blend modes against the CPU
+//        reference table in common::BLEND_CASES).
 
 mod common;
 
@@ -130,7 +131,7 @@ fn gpu_gradient_linear() {
         shape: 0,  // linear
         repeat: 0, // clamp
         is_eraser: 0,
-        _pad0: 0,
+        dither: 0,
         _pad1: 0,
         _pad2: 0,
     };
@@ -392,8 +393,8 @@ fn gpu_composite_single_layer() {
     gpu.ensure_layer_texture(0, w, h, &layer_data, 1);
 
     // Composite with blend mode Normal (0)
-    let layer_info = vec![(0usize, 1.0f32, true, 0u8)];
-    let result = gpu.composite(w, h, &layer_info);
+    let layer_info = vec![(0usize, 1.0f32, true, 0u8, None)];
+    let result = gpu.composite(w, h, &layer_info, &[]);
     assert!(result.is_some(), "GPU composite should succeed");
 
     let pixels = result.unwrap();
@@ -448,8 +449,11 @@ fn gpu_composite_two_layers() {
     gpu.ensure_layer_texture(1, w, h, &data1, 1);
 
     // Composite: layer 0 bottom, layer 1 top (Normal blend)
-    let layer_info = vec![(0usize, 1.0f32, true, 0u8), (1usize, 1.0f32, true, 0u8)];
-    let result = gpu.composite(w, h, &layer_info);
+    let layer_info = vec![
+        (0usize, 1.0f32, true, 0u8, None),
+        (1usize, 1.0f32, true, 0u8, None),
+    ];
+    let result = gpu.composite(w, h, &layer_info, &[]);
     assert!(result.is_some());
 
     let pixels = result.unwrap();
@@ -466,3 +470,37 @@ fn gpu_composite_two_layers() {
         pixels[idx + 2]
     );
 }
+
+#[test]
+fn gpu_composite_matches_cpu_blend_table() {
+    require_gpu!(gpu);
+
+    let (w, h) = (8u32, 1u32);
+    // A new generation per case makes the renderer re-upload the layers.
+    for (generation, &(mode, x, base, top, expected)) in (1u64..).zip(common::BLEND_CASES) {
+        let mut base_data = vec![0u8; (w * h * 4) as usize];
+        let mut top_data = base_data.clone();
+        let idx = (x * 4) as usize;
+        base_data[idx..idx + 4].copy_from_slice(&base);
+        top_data[idx..idx + 4].copy_from_slice(&top);
+        gpu.ensure_layer_texture(0, w, h, &base_data, generation);
+        gpu.ensure_layer_texture(1, w, h, &top_data, generation);
+
+        let layer_info = vec![
+            (0usize, 1.0f32, true, 0u8, None),
+            (1usize, 1.0f32, true, mode.to_u8(), None),
+        ];
+        let pixels = gpu.composite(w, h, &layer_info, &[]).unwrap();
+        let got = &pixels[idx..idx + 4];
+        assert!(
+            got.iter()
+                .zip(expected)
+                .all(|(&g, e)| (g as i32 - e as i32).abs() <= 2),
+            "{:?} at x={}: GPU {:?}, CPU {:?}",
+            mode,
+            x,
+            got,
+            expected
+        );
+    }
+}
@@ -202,6 +202,7 @@ fn roundtrip_pfe_layer_folders() {
         collapsed: true,
         insert_above_layer: None,
         color_index: Some(2),
+        ..Default::default()
     });
     state.next_layer_folder_id = 8;
 
@@ -228,6 +229,323 @@ fn roundtrip_pfe_layer_folders() {
     let _ = std::fs::remove_file(&path);
 }
 
+#[test]
+fn roundtrip_pfe_nested_groups() {
+    use paintfe::canvas::BlendMode;
+
+    let (w, h) = (8, 8);
+    let mut state = CanvasState::new(w, h);
+    state.layer_folders.push(LayerFolder {
+        id: 1,
+        name: "Outer".into(),
+        opacity: 0.5,
+        ..Default::default()
+    });
+    let mut mask = TiledImage::new(w, h);
+    mask.put_pixel(2, 3, Rgba([0, 0, 0, 200]));
+    state.layer_folders.push(LayerFolder {
+        id: 2,
+        name: "Inner".into(),
+        parent_id: Some(1),
+        blend_mode: Some(BlendMode::Multiply),
+        mask: Some(mask),
+        mask_enabled: false,
+        ..Default::default()
+    });
+    state.next_layer_folder_id = 3;
+    let mut layer = Layer::new("Deep".into(), w, h, Rgba([0, 0, 0, 0]));
+    layer.folder_id = Some(2);
+    layer.pixels.put_pixel(1, 1, Rgba([10, 20, 30, 255]));
+    state.layers.push(layer);
+
+    let path = temp_dir().join("rt_nested_groups.pfe");
+    save_pfe(&state, &path).unwrap();
+    assert_eq!(&std::fs::read(&path).unwrap()[8..12], b"PFE4");
+    let loaded = load_pfe(&path).unwrap();
+
+    let outer = loaded.layer_folder(1).unwrap();
+    assert_eq!(outer.parent_id, None);
+    assert!((outer.opacity - 0.5).abs() < 1e-6);
+    assert_eq!(outer.blend_mode, None);
+    let inner = loaded.layer_folder(2).unwrap();
+    assert_eq!(inner.parent_id, Some(1));
+    assert_eq!(inner.blend_mode, Some(BlendMode::Multiply));
+    assert!(!inner.mask_enabled);
+    assert_eq!(inner.mask.as_ref().unwrap().get_pixel(2, 3)[3], 200);
+    assert_eq!(loaded.layers[1].folder_id, Some(2));
+    assert_eq!(loaded.layers[1].pixels.get_pixel(1, 1)[2], 30);
+
+    // Flat folders keep writing the previous format.
+    state.layer_folders.truncate(1);
+    state.layer_folders[0].opacity = 1.0;
+    state.layers[1].folder_id = Some(1);
+    save_pfe(&state, &path).unwrap();
+    assert_ne!(&std::fs::read(&path).unwrap()[8..12], b"PFE4");
+    assert_eq!(load_pfe(&path).unwrap().layers[1].folder_id, Some(1));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn roundtrip_pfe_layer_locks() {
+    use paintfe::canvas::LayerLocks;
+
+    let mut state = CanvasState::new(8, 8);
+    let mut layer = Layer::new("Locked".into(), 8, 8, Rgba([0, 0, 0, 0]));
+    layer.locks = LayerLocks {
+        transparency: true,
+        position: true,
+        ..Default::default()
+    };
+    state.layers.push(layer);
+
+    let path = temp_dir().join("rt_layer_locks.pfe");
+    save_pfe(&state, &path).unwrap();
+    assert_eq!(&std::fs::read(&path).unwrap()[8..12], b"PFE5");
+    let loaded = load_pfe(&path).unwrap();
+    assert_eq!(loaded.layers[0].locks, LayerLocks::default());
+    assert_eq!(loaded.layers[1].locks, state.layers[1].locks);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn roundtrip_pfe_off_canvas_content() {
+    let mut state = CanvasState::new(8, 8);
+    let mut layer = Layer::new("Wide".into(), 8, 8, Rgba([0, 0, 0, 0]));
+    layer.pixels.put_pixel(1, 1, Rgba([10, 20, 30, 255]));
+    layer.apply_off_canvas_pixels(&[(-3, 2, Rgba([200, 100, 50, 255]))], |_, new| new);
+    state.layers.push(layer);
+    state.document_bounds = Some((1, 1, 6, 6));
+
+    let path = temp_dir().join("rt_off_canvas.pfe");
+    save_pfe(&state, &path).unwrap();
+    assert_eq!(&std::fs::read(&path).unwrap()[8..12], b"PFE6");
+    let loaded = load_pfe(&path).unwrap();
+    assert_eq!(loaded.document_bounds, Some((1, 1, 6, 6)));
+    assert!(loaded.layers[0].off_canvas.is_none());
+    let (origin, extent) = loaded.layers[1].extent_rgba();
+    assert_eq!(origin, (-3, 0));
+    assert_eq!(*extent.get_pixel(0, 2), Rgba([200, 100, 50, 255]));
+    assert_eq!(*extent.get_pixel(4, 1), Rgba([10, 20, 30, 255]));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn roundtrip_pfe_layer_comps() {
+    use paintfe::canvas::{BlendMode, LayerComp};
+
+    let mut state = CanvasState::new(8, 8);
+    let mut layer = Layer::new("Badge".into(), 8, 8, Rgba([0, 0, 0, 0]));
+    layer.pixels.put_pixel(2, 3, Rgba([1, 2, 3, 255]));
+    layer.blend_mode = BlendMode::Screen;
+    layer.translate_content(1, 2, 8, 8);
+    state.layers.push(layer);
+    state.layer_comps.push(LayerComp::capture("Screen", &state));
+    state.layers[1].visible = false;
+    state
+        .layer_comps
+        .push(LayerComp::capture("No badge", &state));
+
+    let path = temp_dir().join("rt_layer_comps.pfe");
+    save_pfe(&state, &path).unwrap();
+    assert_eq!(&std::fs::read(&path).unwrap()[8..12], b"PFE7");
+    let loaded = load_pfe(&path).unwrap();
+    assert_eq!(loaded.layer_comps, state.layer_comps);
+    assert_eq!(loaded.layer_comps[0].entries[1].offset, Some((1, 2)));
+    assert_eq!(loaded.layers[1].move_offset, (1, 2));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn roundtrip_pfe_blend_if() {
+    use paintfe::canvas::BlendRange;
+
+    let mut state = CanvasState::new(8, 8);
+    let mut layer = Layer::new("Ranged".into(), 8, 8, Rgba([0, 0, 0, 0]));
+    layer.blend_if.this_layer[0] = BlendRange {
+        black: [20, 60],
+        white: [255, 255],
+    };
+    layer.blend_if.underlying[2] = BlendRange {
+        black: [0, 0],
+        white: [180, 220],
+    };
+    state.layers.push(layer);
+
+    let path = temp_dir().join("rt_blend_if.pfe");
+    save_pfe(&state, &path).unwrap();
+    assert_eq!(&std::fs::read(&path).unwrap()[8..12], b"PFE8");
+    let loaded = load_pfe(&path).unwrap();
+    assert!(loaded.layers[0].blend_if.is_default());
+    assert_eq!(loaded.layers[1].blend_if, state.layers[1].blend_if);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn roundtrip_pfe_linear_blending() {
+    let mut state = CanvasState::new(8, 8);
+    state.linear_blending = true;
+    let mut layer = Layer::new("Ranged".into(), 8, 8, Rgba([0, 0, 0, 0]));
+    layer.blend_if.this_layer[1].black = [10, 30];
+    state.layers.push(layer);
+
+    let path = temp_dir().join("rt_linear_blending.pfe");
+    save_pfe(&state, &path).unwrap();
+    assert_eq!(&std::fs::read(&path).unwrap()[8..12], b"PFE9");
+    let loaded = load_pfe(&path).unwrap();
+    assert!(loaded.linear_blending);
+    assert_eq!(loaded.layers[1].blend_if, state.layers[1].blend_if);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn export_all_layer_comps_writes_one_file_each() {
+    use paintfe::canvas::LayerComp;
+    use paintfe::components::dialogs::SaveFormat;
+    use paintfe::ops::layer_comps::export_all_comps;
+
+    let mut state = CanvasState::new(4, 4);
+    let mut red = Layer::new("Red".into(), 4, 4, Rgba([255, 0, 0, 255]));
+    red.visible = false;
+    state.layers.push(red);
+    state.layer_comps.push(LayerComp::capture("Plain", &state));
+    state.layers[1].visible = true;
+    state
+        .layer_comps
+        .push(LayerComp::capture("Red/Alt", &state));
+    state.layers[1].visible = false;
+
+    let dir = temp_dir().join("comps");
+    std::fs::create_dir_all(&dir).unwrap();
+    let paths = export_all_comps(&mut state, &dir, "mock", SaveFormat::Png, 100).unwrap();
+    assert_eq!(
+        paths,
+        vec![dir.join("mock-Plain.png"), dir.join("mock-Red_Alt.png")]
+    );
+    let plain = image::open(&paths[0]).unwrap().to_rgba8();
+    assert_eq!(*plain.get_pixel(0, 0), Rgba([255, 255, 255, 255]));
+    let red = image::open(&paths[1]).unwrap().to_rgba8();
+    assert_eq!(*red.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+    // The live document is left as it was.
+    assert!(!state.layers[1].visible);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn export_layers_writes_trimmed_files_and_restores_visibility() {
+    use paintfe::ops::layer_export::{LayerExportOptions, export_layers};
+
+    let mut state = CanvasState::new(8, 8);
+    let mut dot = Layer::new("Dot".into(), 8, 8, Rgba([0, 0, 0, 0]));
+    dot.pixels.put_pixel(2, 3, Rgba([255, 0, 0, 255]));
+    dot.pixels.put_pixel(4, 3, Rgba([255, 0, 0, 255]));
+    state.layers.push(dot);
+    let mut hidden = Layer::new("Hidden".into(), 8, 8, Rgba([0, 0, 255, 255]));
+    hidden.visible = false;
+    state.layers.push(hidden);
+
+    let dir = temp_dir().join("layers");
+    std::fs::create_dir_all(&dir).unwrap();
+    let options = LayerExportOptions {
+        trim: true,
+        ..Default::default()
+    };
+    let paths = export_layers(&mut state, &dir, "ui", &options).unwrap();
+    assert_eq!(
+        paths,
+        vec![dir.join("ui_Dot_1.png"), dir.join("ui_Background_2.png")]
+    );
+    let dot = image::open(&paths[0]).unwrap().to_rgba8();
+    assert_eq!(dot.dimensions(), (3, 1));
+    assert_eq!(*dot.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+    assert_eq!(*dot.get_pixel(1, 0), Rgba([0, 0, 0, 0]));
+    let background = image::open(&paths[1]).unwrap().to_rgba8();
+    assert_eq!(background.dimensions(), (8, 8));
+    assert_eq!(*background.get_pixel(2, 3), Rgba([255, 255, 255, 255]));
+
+    // The live document is left as it was.
+    assert!(state.layers[0].visible && state.layers[1].visible);
+    assert!(!state.layers[2].visible);
+    assert_eq!(state.document_bounds, None);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn roundtrip_pfe_slices() {
+    use paintfe::canvas::Slice;
+
+    let mut state = CanvasState::new(16, 8);
+    state.slices.push(Slice::new("Left", 0, 0, 8, 8));
+    state.slices.push(Slice::new("Right", 8, 2, 8, 6));
+
+    let path = temp_dir().join("rt_slices.pfe");
+    save_pfe(&state, &path).unwrap();
+    assert_eq!(&std::fs::read(&path).unwrap()[8..12], b"PFEA");
+    let loaded = load_pfe(&path).unwrap();
+    assert_eq!(loaded.slices, state.slices);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn export_slices_and_sprite_sheet_write_files() {
+    use paintfe::canvas::Slice;
+    use paintfe::ops::slices::{
+        SliceExportOptions, SpriteSheetOptions, SpriteSource, export_slices, export_sprite_sheet,
+    };
+
+    let mut state = CanvasState::new(8, 4);
+    let mut red = Layer::new("Red".into(), 8, 4, Rgba([0, 0, 0, 0]));
+    for y in 0..4 {
+        for x in 4..8 {
+            red.pixels.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+        }
+    }
+    state.layers.push(red);
+    state.slices.push(Slice::new("Left", 0, 0, 4, 4));
+    state.slices.push(Slice::new("Right/Side", 4, 0, 4, 4));
+
+    let dir = temp_dir().join("slices");
+    std::fs::create_dir_all(&dir).unwrap();
+    let paths = export_slices(&mut state, &dir, &SliceExportOptions::default()).unwrap();
+    assert_eq!(
+        paths,
+        vec![dir.join("Left.png"), dir.join("Right_Side.png")]
+    );
+    let left = image::open(&paths[0]).unwrap().to_rgba8();
+    assert_eq!(left.dimensions(), (4, 4));
+    assert_eq!(*left.get_pixel(0, 0), Rgba([255, 255, 255, 255]));
+    let right = image::open(&paths[1]).unwrap().to_rgba8();
+    assert_eq!(*right.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+    assert_eq!(state.document_bounds, None);
+
+    let options = SpriteSheetOptions {
+        source: SpriteSource::Layers,
+        columns: 0,
+        padding: 0,
+        trim: true,
+    };
+    let paths = export_sprite_sheet(&mut state, &dir, "sheet", &options).unwrap();
+    assert_eq!(paths, vec![dir.join("sheet.png"), dir.join("sheet.json")]);
+    let sheet = image::open(&paths[0]).unwrap().to_rgba8();
+    // Background (8×4) then the trimmed red layer (4×4), side by side.
+    assert_eq!(sheet.dimensions(), (12, 4));
+    let atlas: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&paths[1]).unwrap()).unwrap();
+    assert_eq!(atlas["frames"]["Red"]["trimmed"], true);
+    assert_eq!(atlas["frames"]["Red"]["spriteSourceSize"]["x"], 4);
+    assert_eq!(atlas["meta"]["image"], "sheet.png");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
 #[test]
 fn roundtrip_pfe_blend_modes() {
     use paintfe::canvas::BlendMode;
@@ -322,3 +640,1661 @@ fn load_pfe_via_load_image_sync() {
 
     let _ = std::fs::remove_file(&path);
 }
+
+// =============================================================================
+// Crash-recovery autosaves
+// =============================================================================
+
+#[test]
+fn autosave_manifest_roundtrip_and_cleanup() {
+    use paintfe::io::{
+        AutosaveManifest, autosave_thumbnail, build_pfe, list_autosaves, remove_autosave,
+        write_autosave,
+    };
+
+    let dir = temp_dir().join("autosave_manifest");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let state = canvas_from_image(&create_test_gradient(300, 150));
+    let manifest = AutosaveManifest {
+        project_id: "7f1c2d3e-0000-4000-8000-000000000001".to_string(),
+        session: None,
+        name: "poster.png".to_string(),
+        original_path: Some(PathBuf::from("/work/poster.png")),
+        is_dirty: true,
+        saved_at: 1_700_000_000,
+        width: state.width,
+        height: state.height,
+    };
+    let thumb = autosave_thumbnail(&state);
+    assert_eq!((thumb.width(), thumb.height()), (128, 64));
+    write_autosave(&dir, &manifest, &build_pfe(&state), Some(&thumb)).unwrap();
+
+    // Pre-manifest autosaves are still listed, as untitled dirty projects.
+    save_pfe(&state, &dir.join("Untitled-3.autosave.pfe")).unwrap();
+
+    let entries = list_autosaves(&dir);
+    assert_eq!(entries.len(), 2);
+    let entry = entries
+        .iter()
+        .find(|e| e.manifest.project_id == manifest.project_id)
+        .unwrap();
+    assert_eq!(entry.manifest, manifest);
+    assert!(entry.thumbnail_path.is_some());
+    let restored = load_pfe(&entry.pfe_path).unwrap();
+    assert_eq!((restored.width, restored.height), (300, 150));
+
+    let legacy = entries
+        .iter()
+        .find(|e| e.manifest.project_id == "Untitled-3")
+        .unwrap();
+    assert!(legacy.manifest.is_dirty);
+    assert!(legacy.manifest.original_path.is_none());
+
+    remove_autosave(&dir, &manifest.project_id);
+    remove_autosave(&dir, "Untitled-3");
+    assert!(list_autosaves(&dir).is_empty());
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn autosaves_of_a_live_session_are_not_offered_for_recovery() {
+    use paintfe::io::{
+        AutosaveManifest, AutosaveSession, build_pfe, list_autosaves, write_autosave,
+    };
+
+    let dir = temp_dir().join("autosave_sessions");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let state = canvas_from_image(&create_test_gradient(8, 8));
+    let session = AutosaveSession::start(&dir).unwrap();
+    let manifest = AutosaveManifest {
+        project_id: "7f1c2d3e-0000-4000-8000-000000000002".to_string(),
+        session: Some(session.id().to_string()),
+        name: "Untitled-1".to_string(),
+        original_path: None,
+        is_dirty: true,
+        saved_at: 1_700_000_000,
+        width: state.width,
+        height: state.height,
+    };
+    write_autosave(&dir, &manifest, &build_pfe(&state), None).unwrap();
+    assert!(list_autosaves(&dir).is_empty(), "owner is still running");
+
+    // A second instance starting up leaves the live session's lock alone.
+    let other = AutosaveSession::start(&dir).unwrap();
+    assert!(list_autosaves(&dir).is_empty());
+
+    // Once the owner is gone (clean exit or crash) its autosave is orphaned.
+    drop(session);
+    let entries = list_autosaves(&dir);
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].manifest, manifest);
+
+    // A crash leaves the lock file behind, but nothing holds the lock.
+    let crashed = AutosaveManifest {
+        project_id: "7f1c2d3e-0000-4000-8000-000000000003".to_string(),
+        session: Some("crashed".to_string()),
+        ..manifest.clone()
+    };
+    std::fs::write(dir.join("crashed.session.lock"), "1").unwrap();
+    write_autosave(&dir, &crashed, &build_pfe(&state), None).unwrap();
+    assert_eq!(list_autosaves(&dir).len(), 2);
+
+    drop(other);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// Big-endian sample header + raw 8-bit bitmap as stored in `.abr` files.
+fn abr_sample(w: i32, h: i32, compression: u8, pixels: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for v in [0i32, 0, h, w] {
+        out.extend_from_slice(&v.to_be_bytes());
+    }
+    out.extend_from_slice(&8u16.to_be_bytes());
+    out.push(compression);
+    out.extend_from_slice(pixels);
+    out
+}
+
+#[test]
+fn abr_v2_and_v6_sampled_tips() {
+    use paintfe::abr::parse_abr;
+
+    // v2: one computed brush (skipped) and one named sampled brush, PackBits rows.
+    let mut sampled = Vec::new();
+    sampled.extend_from_slice(&0u32.to_be_bytes()); // misc
+    sampled.extend_from_slice(&25u16.to_be_bytes()); // spacing
+    let name: Vec<u16> = "Dot\0".encode_utf16().collect();
+    sampled.extend_from_slice(&(name.len() as u32).to_be_bytes());
+    for u in name {
+        sampled.extend_from_slice(&u.to_be_bytes());
+    }
+    sampled.push(1); // antialias
+    sampled.extend_from_slice(&[0u8; 8]);
+    // 3x2: row 0 = run of three 255s, row 1 = literal [0, 128, 0]
+    let mut rle = Vec::new();
+    rle.extend_from_slice(&2u16.to_be_bytes());
+    rle.extend_from_slice(&4u16.to_be_bytes());
+    rle.extend_from_slice(&[(-2i8) as u8, 255]);
+    rle.extend_from_slice(&[2, 0, 128, 0]);
+    sampled.extend(abr_sample(3, 2, 1, &rle));
+
+    let mut v2 = Vec::new();
+    v2.extend_from_slice(&2u16.to_be_bytes());
+    v2.extend_from_slice(&2u16.to_be_bytes());
+    v2.extend_from_slice(&1u16.to_be_bytes()); // computed
+    v2.extend_from_slice(&4u32.to_be_bytes());
+    v2.extend_from_slice(&[0u8; 4]);
+    v2.extend_from_slice(&2u16.to_be_bytes()); // sampled
+    v2.extend_from_slice(&(sampled.len() as u32).to_be_bytes());
+    v2.extend(sampled);
+
+    let tips = parse_abr(&v2, "set").expect("v2 parses");
+    assert_eq!(tips.len(), 1);
+    assert_eq!(tips[0].name, "Dot");
+    assert_eq!((tips[0].width, tips[0].height), (3, 2));
+    assert_eq!(tips[0].mask, vec![255, 255, 255, 0, 128, 0]);
+
+    // v6 subversion 1: skip unrelated 8BIM section, then two raw samples in "samp".
+    let mut samp = Vec::new();
+    for fill in [200u8, 50u8] {
+        let mut brush = vec![0u8; 47];
+        brush.extend(abr_sample(2, 2, 0, &[fill; 4]));
+        samp.extend_from_slice(&(brush.len() as u32).to_be_bytes());
+        let padded = brush.len().div_ceil(4) * 4;
+        brush.resize(padded, 0);
+        samp.extend(brush);
+    }
+    let mut v6 = Vec::new();
+    v6.extend_from_slice(&6u16.to_be_bytes());
+    v6.extend_from_slice(&1u16.to_be_bytes());
+    v6.extend_from_slice(b"8BIMdesc");
+    v6.extend_from_slice(&4u32.to_be_bytes());
+    v6.extend_from_slice(&[0u8; 4]);
+    v6.extend_from_slice(b"8BIMsamp");
+    v6.extend_from_slice(&(samp.len() as u32).to_be_bytes());
+    v6.extend(samp);
+
+    let tips = parse_abr(&v6, "pack").expect("v6 parses");
+    assert_eq!(tips.len(), 2);
+    assert_eq!(tips[0].name, "pack 1");
+    assert_eq!(tips[1].mask, vec![50; 4]);
+
+    // PNG export is square and decodes back to the same luminance.
+    let png = tips[0].to_png().expect("png");
+    let img = image::load_from_memory(&png).unwrap().to_luma8();
+    assert_eq!(img.dimensions(), (2, 2));
+    assert!(img.pixels().all(|p| p.0[0] == 200));
+
+    assert!(parse_abr(&[0, 5, 0, 0], "bad").is_err(), "unknown version");
+}
+
+#[test]
+fn abr_skips_corrupt_and_unsupported_samples() {
+    use paintfe::abr::parse_abr;
+
+    // v1 layout: each sampled record is misc, spacing, antialias, short
+    // bounds, then the sample.
+    let v1 = |samples: &[Vec<u8>]| {
+        let mut out = Vec::new();
+        out.extend_from_slice(&1u16.to_be_bytes());
+        out.extend_from_slice(&(samples.len() as u16).to_be_bytes());
+        for sample in samples {
+            let mut record = vec![0u8; 4 + 2 + 1 + 8];
+            record.extend_from_slice(sample);
+            out.extend_from_slice(&2u16.to_be_bytes());
+            out.extend_from_slice(&(record.len() as u32).to_be_bytes());
+            out.extend(record);
+        }
+        out
+    };
+
+    // Bounds whose difference overflows i32.
+    let mut overflow = Vec::new();
+    for v in [i32::MIN, i32::MIN, i32::MAX, i32::MAX] {
+        overflow.extend_from_slice(&v.to_be_bytes());
+    }
+    overflow.extend_from_slice(&8u16.to_be_bytes());
+    overflow.push(0);
+
+    // 16384x16384 at 16-bit claimed by a few hundred bytes of PackBits.
+    let mut huge = Vec::new();
+    for v in [0i32, 0, 16384, 16384] {
+        huge.extend_from_slice(&v.to_be_bytes());
+    }
+    huge.extend_from_slice(&16u16.to_be_bytes());
+    huge.push(1);
+    huge.extend_from_slice(&[0u8; 256]);
+
+    // A 32-bit sample: unsupported, but only that tip is dropped.
+    let mut deep = abr_sample(2, 2, 0, &[7; 16]);
+    deep[16..18].copy_from_slice(&32u16.to_be_bytes());
+
+    let good = abr_sample(2, 1, 0, &[9, 9]);
+    let tips = parse_abr(&v1(&[overflow, huge, deep, good]), "lib").expect("good tip survives");
+    assert_eq!(tips.len(), 1);
+    assert_eq!(tips[0].mask, vec![9, 9]);
+}
+
+#[test]
+fn brush_preset_pack_roundtrip() {
+    use paintfe::components::tools::{BrushTip, ResponseCurve, ToolProperties};
+    use paintfe::config::brush_presets::{
+        BrushPreset, BrushPresetPack, PackedBrushTip, upsert_brush_preset,
+    };
+
+    let mut props = ToolProperties {
+        size: 42.0,
+        brush_tip: BrushTip::Image("Splat".to_string()),
+        scatter: 0.3,
+        ..Default::default()
+    };
+    props.dynamics.pressure_curve = ResponseCurve::Soft;
+    props.dynamics.taper_end = 25.0;
+
+    let mut presets = Vec::new();
+    upsert_brush_preset(&mut presets, BrushPreset::from_properties("Ink", &props));
+    props.size = 12.0;
+    upsert_brush_preset(&mut presets, BrushPreset::from_properties("Ink", &props));
+    assert_eq!(presets.len(), 1, "same name replaces");
+    assert_eq!(presets[0].size, 12.0);
+
+    let pack = BrushPresetPack::new(
+        presets,
+        vec![PackedBrushTip {
+            name: "Splat".to_string(),
+            category: "Custom".to_string(),
+            png_base64: "AAAA".to_string(),
+        }],
+    );
+    let dir = temp_dir();
+    let path = dir.join("test.pfebrushes");
+    pack.save(&path).expect("save pack");
+    let loaded = BrushPresetPack::load(&path).expect("load pack");
+    assert_eq!(loaded, pack);
+
+    let mut applied = ToolProperties {
+        color: egui::Color32::RED,
+        ..Default::default()
+    };
+    loaded.presets[0].apply_to(&mut applied);
+    assert_eq!(applied.size, 12.0);
+    assert_eq!(applied.brush_tip, BrushTip::Image("Splat".to_string()));
+    assert_eq!(applied.dynamics.pressure_curve, ResponseCurve::Soft);
+    assert_eq!(
+        applied.color,
+        egui::Color32::RED,
+        "color is not part of a preset"
+    );
+    assert!(loaded.presets[0].matches(&applied));
+
+    let mut hostile = loaded.presets[0].clone();
+    hostile.tip_rotation_range = (300.0, -20.0);
+    hostile.mixer.wetness = 4.0;
+    hostile.mixer.load = -1.0;
+    hostile.dynamics.tilt_size = 7.0;
+    hostile.dynamics.taper_end = 1e9;
+    hostile.dynamics.stabilizer_radius = 0.0;
+    hostile.apply_to(&mut applied);
+    assert_eq!(applied.tip_rotation_range, (300.0, 300.0));
+    assert_eq!((applied.mixer.wetness, applied.mixer.load), (1.0, 0.0));
+    assert_eq!(applied.dynamics.tilt_size, 1.0);
+    assert_eq!(applied.dynamics.taper_end, 500.0);
+    assert_eq!(applied.dynamics.stabilizer_radius, 1.0);
+
+    let newer = pack
+        .to_json()
+        .unwrap()
+        .replace("\"version\": 1", "\"version\": 99");
+    assert!(BrushPresetPack::from_json(&newer).is_err());
+}
+
+/// Photoshop descriptor pieces for synthesizing `.grd` files.
+fn grd_key(out: &mut Vec<u8>, key: &str) {
+    out.extend_from_slice(&0u32.to_be_bytes());
+    out.extend_from_slice(format!("{:<4}", key).as_bytes());
+}
+
+fn grd_text(out: &mut Vec<u8>, s: &str) {
+    let units: Vec<u16> = s.encode_utf16().chain(std::iter::once(0)).collect();
+    out.extend_from_slice(&(units.len() as u32).to_be_bytes());
+    for u in units {
+        out.extend_from_slice(&u.to_be_bytes());
+    }
+}
+
+fn grd_object(out: &mut Vec<u8>, class: &str, count: u32) {
+    grd_text(out, "");
+    grd_key(out, class);
+    out.extend_from_slice(&count.to_be_bytes());
+}
+
+fn grd_long(out: &mut Vec<u8>, key: &str, v: i32) {
+    grd_key(out, key);
+    out.extend_from_slice(b"long");
+    out.extend_from_slice(&v.to_be_bytes());
+}
+
+fn grd_doub(out: &mut Vec<u8>, key: &str, v: f64) {
+    grd_key(out, key);
+    out.extend_from_slice(b"doub");
+    out.extend_from_slice(&v.to_be_bytes());
+}
+
+fn grd_enum(out: &mut Vec<u8>, key: &str, ty: &str, v: &str) {
+    grd_key(out, key);
+    out.extend_from_slice(b"enum");
+    grd_key(out, ty);
+    grd_key(out, v);
+}
+
+#[test]
+fn grd_and_ggr_gradient_import() {
+    use paintfe::gradient::parse_gradients;
+
+    // Photoshop v5: red (midpoint 25%) → background color, opacity 100% → 0%.
+    let mut grd = b"8BGR".to_vec();
+    grd.extend_from_slice(&5u16.to_be_bytes());
+    grd.extend_from_slice(&16u32.to_be_bytes());
+    grd_object(&mut grd, "null", 1);
+    grd_key(&mut grd, "GrdL");
+    grd.extend_from_slice(b"VlLs");
+    grd.extend_from_slice(&1u32.to_be_bytes());
+    grd.extend_from_slice(b"Objc");
+    grd_object(&mut grd, "Grdn", 1);
+    grd_key(&mut grd, "Grad");
+    grd.extend_from_slice(b"Objc");
+    grd_object(&mut grd, "Grdn", 5);
+    grd_key(&mut grd, "Nm");
+    grd.extend_from_slice(b"TEXT");
+    grd_text(&mut grd, "Sunset");
+    grd_enum(&mut grd, "GrdF", "GrdF", "CstS");
+    grd_doub(&mut grd, "Intr", 4096.0);
+    grd_key(&mut grd, "Clrs");
+    grd.extend_from_slice(b"VlLs");
+    grd.extend_from_slice(&2u32.to_be_bytes());
+    for (stop_type, location, midpoint) in [("UsrS", 0, 25), ("BckC", 4096, 50)] {
+        grd.extend_from_slice(b"Objc");
+        grd_object(&mut grd, "Clrt", 4);
+        grd_key(&mut grd, "Clr");
+        grd.extend_from_slice(b"Objc");
+        grd_object(&mut grd, "RGBC", 3);
+        grd_doub(&mut grd, "Rd", 255.0);
+        grd_doub(&mut grd, "Grn", 0.0);
+        grd_doub(&mut grd, "Bl", 0.0);
+        grd_enum(&mut grd, "Type", "Clry", stop_type);
+        grd_long(&mut grd, "Lctn", location);
+        grd_long(&mut grd, "Mdpn", midpoint);
+    }
+    grd_key(&mut grd, "Trns");
+    grd.extend_from_slice(b"VlLs");
+    grd.extend_from_slice(&2u32.to_be_bytes());
+    for (opacity, location) in [(100.0f64, 0), (0.0, 4096)] {
+        grd.extend_from_slice(b"Objc");
+        grd_object(&mut grd, "TrnS", 3);
+        grd_key(&mut grd, "Opct");
+        grd.extend_from_slice(b"UntF");
+        grd.extend_from_slice(b"#Prc");
+        grd.extend_from_slice(&opacity.to_be_bytes());
+        grd_long(&mut grd, "Lctn", location);
+        grd_long(&mut grd, "Mdpn", 50);
+    }
+
+    let white = [255, 255, 255, 255];
+    let gradients = parse_gradients(&grd, "file", [0, 0, 0, 255], white).expect("parse grd");
+    assert_eq!(gradients.len(), 1);
+    let g = &gradients[0];
+    assert_eq!(g.name, "Sunset");
+    assert_eq!(g.color_stops[0].color, [255, 0, 0, 255]);
+    assert_eq!(g.color_stops[1].color, white, "background stop resolves");
+    assert!((g.color_stops[0].midpoint - 0.25).abs() < 1e-6);
+    // The 25% midpoint puts the half-way blend at t = 0.25; opacity is linear.
+    let [r, green, b, a] = g.sample(0.25);
+    assert_eq!((r, b), (255, 128));
+    assert!((127..=128).contains(&green));
+    assert_eq!(a, 191);
+    assert_eq!(g.build_lut()[255][3], 0);
+
+    // GIMP: red → blue → green at half alpha; the shared blue stop is merged.
+    let ggr = "GIMP Gradient\nName: Test\n2\n\
+        0.000000 0.250000 0.500000 1 0 0 1 0 0 1 1 0 0\n\
+        0.500000 0.750000 1.000000 0 0 1 1 0 1 0 0.5 0 0\n";
+    let gradients = parse_gradients(ggr.as_bytes(), "file", white, white).expect("parse ggr");
+    let g = &gradients[0];
+    assert_eq!(g.name, "Test");
+    assert_eq!(g.color_stops.len(), 3);
+    assert_eq!(g.sample(0.25), [128, 0, 128, 255]);
+    assert_eq!(g.sample(1.0), [0, 255, 0, 128]);
+
+    assert!(parse_gradients(b"8BGR\x00\x03", "file", white, white).is_err());
+    assert!(parse_gradients(b"not a gradient", "file", white, white).is_err());
+    // A huge segment count with no segments is an error, not an allocation.
+    let huge = format!("GIMP Gradient\n{}\n", usize::MAX);
+    assert!(parse_gradients(huge.as_bytes(), "file", white, white).is_err());
+}
+
+#[test]
+fn gradient_dither_and_noise() {
+    use paintfe::gradient::{Gradient, sample_lut_dithered};
+
+    // Half-way between two LUT entries, a 4×4 block averages to the exact value.
+    let lut: Vec<u8> = (0..=255u8).flat_map(|v| [v, v, v, 255]).collect();
+    let sum: u32 = (0..4)
+        .flat_map(|y| (0..4).map(move |x| (x, y)))
+        .map(|(x, y)| sample_lut_dithered(&lut, 127.5 / 255.0, x, y)[0] as u32)
+        .sum();
+    assert_eq!(sum, 127 * 8 + 128 * 8);
+
+    let a = Gradient::noise("n", 7, 0.5, 8);
+    assert_eq!(a, Gradient::noise("n", 7, 0.5, 8), "noise is seeded");
+    assert_eq!(a.color_stops.len(), 8);
+    assert_ne!(a, Gradient::noise("n", 8, 0.5, 8));
+}
+
+#[test]
+fn gradient_library_roundtrip() {
+    use paintfe::components::tools::GradientToolState;
+    use paintfe::gradient::Gradient;
+
+    let mut gradient = Gradient::noise("Noise", 3, 1.0, 5);
+    gradient.opacity_stops[1].opacity = 0;
+    gradient.color_stops[0].midpoint = 0.3;
+
+    // Loaded into the tool and saved back, nothing is lost.
+    let mut gs = GradientToolState::default();
+    gs.load_gradient(&gradient);
+    assert_eq!(gs.to_gradient("Noise"), gradient);
+    gs.rebuild_lut();
+    assert_eq!(gs.sample_lut(0.0), gradient.sample(0.0));
+    assert_eq!(gs.sample_lut(1.0)[3], 0);
+
+    // The library is persisted as JSON.
+    let json = serde_json::to_string(&gradient).unwrap();
+    assert_eq!(serde_json::from_str::<Gradient>(&json).unwrap(), gradient);
+}
+
+#[test]
+fn roundtrip_psd_layers_folders_and_masks() {
+    use paintfe::canvas::BlendMode;
+
+    let (w, h) = (8, 6);
+    let mut state = CanvasState::new(w, h);
+    state.layer_folders.push(LayerFolder {
+        id: 1,
+        name: "Group".into(),
+        visible: true,
+        collapsed: true,
+        insert_above_layer: None,
+        color_index: None,
+        ..Default::default()
+    });
+    state.layer_folders.push(LayerFolder {
+        id: 2,
+        name: "Empty".into(),
+        visible: true,
+        collapsed: false,
+        insert_above_layer: Some(3),
+        color_index: None,
+        ..Default::default()
+    });
+    state.next_layer_folder_id = 3;
+
+    let mut shade = Layer::new("Shade".into(), w, h, Rgba([0, 0, 0, 0]));
+    for y in 1..5 {
+        for x in 2..7 {
+            shade.pixels.put_pixel(x, y, Rgba([10, 200, 30, 255]));
+        }
+    }
+    shade.folder_id = Some(1);
+    shade.opacity = 0.5;
+    shade.blend_mode = BlendMode::Multiply;
+    shade.ensure_mask();
+    if let Some(mask) = shade.mask.as_mut() {
+        for y in 0..h {
+            for x in 0..4 {
+                mask.put_pixel(x, y, Rgba([0, 0, 0, 255]));
+            }
+        }
+    }
+    state.layers.push(shade);
+
+    let mut hidden = Layer::new("Hidden".into(), w, h, Rgba([0, 0, 0, 0]));
+    hidden.pixels.put_pixel(1, 1, Rgba([0, 0, 255, 128]));
+    hidden.folder_id = Some(1);
+    hidden.visible = false;
+    state.layers.push(hidden);
+
+    let mut top = Layer::new("Top ✓".into(), w, h, Rgba([0, 0, 0, 0]));
+    top.pixels.put_pixel(7, 5, Rgba([255, 255, 0, 255]));
+    top.blend_mode = BlendMode::Screen;
+    state.layers.push(top);
+
+    let path = temp_dir().join("rt_layers.psd");
+    paintfe::psd::save_psd(&state, &path).unwrap();
+    let loaded = load_image_sync(&path).unwrap();
+
+    assert_eq!((loaded.width, loaded.height), (w, h));
+    assert_eq!(loaded.layers.len(), 4);
+    for (a, b) in state.layers.iter().zip(&loaded.layers) {
+        assert_eq!(a.name, b.name);
+        assert_eq!(a.visible, b.visible);
+        assert_eq!(a.blend_mode, b.blend_mode);
+        assert!((a.opacity - b.opacity).abs() < 0.01, "{} opacity", a.name);
+        assert_eq!(
+            a.pixels.to_rgba_image(),
+            b.pixels.to_rgba_image(),
+            "{}",
+            a.name
+        );
+    }
+
+    assert_eq!(loaded.layer_folders.len(), 2);
+    let group = &loaded.layer_folders[0];
+    assert_eq!(group.name, "Group");
+    assert!(group.collapsed);
+    assert_eq!(loaded.layers[0].folder_id, None);
+    assert_eq!(loaded.layers[1].folder_id, Some(group.id));
+    assert_eq!(loaded.layers[2].folder_id, Some(group.id));
+    assert_eq!(loaded.layers[3].folder_id, None);
+    assert_eq!(loaded.layer_folders[1].name, "Empty");
+    assert_eq!(loaded.layer_folders[1].insert_above_layer, Some(3));
+
+    let mask = loaded.layers[1].mask.as_ref().expect("mask survives");
+    assert_eq!(mask.get_pixel(0, 0)[3], 255);
+    assert_eq!(mask.get_pixel(5, 0)[3], 0);
+    assert!(loaded.layers[1].mask_enabled);
+
+    // Flat exports go through encode_and_write as a single-layer PSD.
+    roundtrip_format(&test_image(), "rt_flat.psd", SaveFormat::Psd, 100, 0);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn psd_import_nested_groups_and_text() {
+    fn record(out: &mut Vec<u8>, name: &str, rect: [i32; 4], flags: u8, blocks: &[u8]) {
+        let size = ((rect[2] - rect[0]) * (rect[3] - rect[1])) as u32;
+        for v in rect {
+            out.extend_from_slice(&v.to_be_bytes());
+        }
+        out.extend_from_slice(&4u16.to_be_bytes());
+        for id in [-1i16, 0, 1, 2] {
+            out.extend_from_slice(&id.to_be_bytes());
+            out.extend_from_slice(&(2 + size).to_be_bytes());
+        }
+        out.extend_from_slice(b"8BIMnorm");
+        out.extend_from_slice(&[255, 0, flags, 0]);
+        let mut extra = vec![0; 8]; // no mask, no blending ranges
+        extra.push(name.len() as u8);
+        extra.extend_from_slice(name.as_bytes());
+        while !extra.len().is_multiple_of(4) {
+            extra.push(0);
+        }
+        extra.extend_from_slice(blocks);
+        out.extend_from_slice(&(extra.len() as u32).to_be_bytes());
+        out.extend_from_slice(&extra);
+    }
+    fn block(key: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut out = b"8BIM".to_vec();
+        out.extend_from_slice(key);
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        out.extend_from_slice(data);
+        out
+    }
+
+    let mut type_tool = vec![0, 1];
+    type_tool.extend_from_slice(&[0; 48]);
+    type_tool.extend_from_slice(&50u16.to_be_bytes());
+    type_tool.extend_from_slice(&16u32.to_be_bytes());
+    grd_object(&mut type_tool, "TxLr", 1);
+    grd_key(&mut type_tool, "Txt");
+    type_tool.extend_from_slice(b"TEXT");
+    grd_text(&mut type_tool, "Hello\rPSD");
+
+    // Bottom to top: outer divider, inner divider, text layer, inner header,
+    // outer header (hidden).
+    let mut records = Vec::new();
+    record(
+        &mut records,
+        "</Layer group>",
+        [0; 4],
+        0,
+        &block(b"lsct", &3u32.to_be_bytes()),
+    );
+    record(
+        &mut records,
+        "</Layer group>",
+        [0; 4],
+        0,
+        &block(b"lsct", &3u32.to_be_bytes()),
+    );
+    record(
+        &mut records,
+        "Title",
+        [0, 0, 1, 2],
+        0,
+        &block(b"TySh", &type_tool),
+    );
+    record(
+        &mut records,
+        "Inner",
+        [0; 4],
+        0,
+        &block(b"lsct", &1u32.to_be_bytes()),
+    );
+    record(
+        &mut records,
+        "Outer",
+        [0; 4],
+        0x02,
+        &block(b"lsct", &2u32.to_be_bytes()),
+    );
+
+    let mut info = 5u16.to_be_bytes().to_vec();
+    info.extend_from_slice(&records);
+    for layer in 0..5 {
+        for channel in 0..4u8 {
+            info.extend_from_slice(&[0, 0]);
+            if layer == 2 {
+                info.extend_from_slice(&[channel * 50, channel * 50 + 10]);
+            }
+        }
+    }
+
+    let mut psd = b"8BPS\x00\x01\0\0\0\0\0\0\x00\x03".to_vec();
+    psd.extend_from_slice(&1u32.to_be_bytes());
+    psd.extend_from_slice(&2u32.to_be_bytes());
+    psd.extend_from_slice(&[0, 8, 0, 3]);
+    psd.extend_from_slice(&[0; 8]);
+    psd.extend_from_slice(&(info.len() as u32 + 8).to_be_bytes());
+    psd.extend_from_slice(&(info.len() as u32).to_be_bytes());
+    psd.extend_from_slice(&info);
+    psd.extend_from_slice(&[0; 4]);
+    psd.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0]);
+
+    let state = paintfe::psd::parse_psd(&psd).expect("parse psd");
+    assert_eq!(state.layers.len(), 1);
+    let title = &state.layers[0];
+    assert_eq!(title.name, "Title");
+    assert_eq!(*title.pixels.get_pixel(0, 0), Rgba([50, 100, 150, 0]));
+    assert_eq!(*title.pixels.get_pixel(1, 0), Rgba([60, 110, 160, 10]));
+    assert!(title.source_metadata.png_text_chunks.contains(&(
+        paintfe::psd::PSD_TEXT_KEY.to_string(),
+        "Hello\nPSD".to_string()
+    )));
+
+    assert_eq!(state.layer_folders.len(), 2);
+    let inner = state
+        .layer_folders
+        .iter()
+        .find(|f| f.name == "Inner")
+        .unwrap();
+    let outer = state
+        .layer_folders
+        .iter()
+        .find(|f| f.name == "Outer")
+        .unwrap();
+    assert_eq!(title.folder_id, Some(inner.id));
+    assert_eq!(inner.parent_id, Some(outer.id), "groups stay nested");
+    assert!(!outer.visible);
+    assert!(
+        !state.folder_effectively_visible(inner.id),
+        "hidden outer group hides nested groups"
+    );
+    assert!(!state.layer_effectively_visible(0));
+
+    assert!(paintfe::psd::parse_psd(b"8BPS\x00\x09").is_err());
+}
+
+#[test]
+fn psd_rejects_layer_rect_larger_than_its_data() {
+    // One layer claiming a 40000 x 40000 rect backed by four bytes of RLE.
+    let mut info = 1u16.to_be_bytes().to_vec();
+    for v in [0i32, 0, 40_000, 40_000] {
+        info.extend_from_slice(&v.to_be_bytes());
+    }
+    info.extend_from_slice(&1u16.to_be_bytes());
+    info.extend_from_slice(&0i16.to_be_bytes());
+    info.extend_from_slice(&6u32.to_be_bytes());
+    info.extend_from_slice(b"8BIMnorm");
+    info.extend_from_slice(&[255, 0, 0, 0]);
+    info.extend_from_slice(&12u32.to_be_bytes());
+    info.extend_from_slice(&[0; 12]);
+    info.extend_from_slice(&[0, 1, 0, 0, 0x81, 0]);
+
+    let mut psd = b"8BPS\x00\x01\0\0\0\0\0\0\x00\x03".to_vec();
+    psd.extend_from_slice(&1u32.to_be_bytes());
+    psd.extend_from_slice(&1u32.to_be_bytes());
+    psd.extend_from_slice(&[0, 8, 0, 3]);
+    psd.extend_from_slice(&[0; 8]);
+    psd.extend_from_slice(&(info.len() as u32 + 4).to_be_bytes());
+    psd.extend_from_slice(&(info.len() as u32).to_be_bytes());
+    psd.extend_from_slice(&info);
+
+    let err = paintfe::psd::parse_psd(&psd)
+        .err()
+        .expect("corrupt rect rejected");
+    assert!(err.contains("Corrupt"), "{err}");
+}
+
+#[test]
+fn roundtrip_ora_layers_offsets_and_stacks() {
+    use paintfe::canvas::BlendMode;
+
+    let (w, h) = (10, 8);
+    let mut state = CanvasState::new(w, h);
+    state.layer_folders.push(LayerFolder {
+        id: 1,
+        name: "Stack <1>".into(),
+        visible: true,
+        collapsed: false,
+        insert_above_layer: None,
+        color_index: None,
+        ..Default::default()
+    });
+    state.next_layer_folder_id = 2;
+
+    // Content away from the origin is written cropped with an x/y offset.
+    let mut shade = Layer::new("Shade".into(), w, h, Rgba([0, 0, 0, 0]));
+    for y in 3..6 {
+        for x in 4..9 {
+            shade.pixels.put_pixel(x, y, Rgba([10, 200, 30, 255]));
+        }
+    }
+    shade.folder_id = Some(1);
+    shade.opacity = 0.5;
+    shade.blend_mode = BlendMode::Multiply;
+    state.layers.push(shade);
+
+    let mut hidden = Layer::new("Hidden & quiet".into(), w, h, Rgba([0, 0, 0, 0]));
+    hidden.pixels.put_pixel(1, 1, Rgba([0, 0, 255, 128]));
+    hidden.folder_id = Some(1);
+    hidden.visible = false;
+    state.layers.push(hidden);
+
+    let mut top = Layer::new("Top".into(), w, h, Rgba([0, 0, 0, 0]));
+    top.pixels.put_pixel(9, 7, Rgba([255, 255, 0, 255]));
+    top.blend_mode = BlendMode::Screen;
+    state.layers.push(top);
+
+    let path = temp_dir().join("rt_layers.ora");
+    paintfe::io::save_ora(&state, &path).unwrap();
+    let loaded = load_image_sync(&path).unwrap();
+
+    assert_eq!((loaded.width, loaded.height), (w, h));
+    assert_eq!(loaded.layers.len(), 4);
+    for (a, b) in state.layers.iter().zip(&loaded.layers) {
+        assert_eq!(a.name, b.name);
+        assert_eq!(a.visible, b.visible);
+        assert_eq!(a.blend_mode, b.blend_mode);
+        assert!((a.opacity - b.opacity).abs() < 0.01, "{} opacity", a.name);
+        assert_eq!(
+            a.pixels.to_rgba_image(),
+            b.pixels.to_rgba_image(),
+            "{}",
+            a.name
+        );
+    }
+    assert_eq!(loaded.layer_folders.len(), 1);
+    assert_eq!(loaded.layer_folders[0].name, "Stack <1>");
+    assert_eq!(loaded.layers[1].folder_id, Some(loaded.layer_folders[0].id));
+    assert_eq!(loaded.layers[2].folder_id, Some(loaded.layer_folders[0].id));
+    assert_eq!(loaded.layers[3].folder_id, None);
+
+    roundtrip_format(&test_image(), "rt_flat.ora", SaveFormat::Ora, 100, 0);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn ora_import_nested_stacks() {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    let mut png = Vec::new();
+    RgbaImage::from_pixel(2, 2, Rgba([200, 100, 50, 255]))
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .unwrap();
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<image version="0.0.3" w="6" h="4">
+  <stack>
+    <stack name="Outer" opacity="0.5" visibility="hidden">
+      <stack name="Inner">
+        <layer name="Deep" src="data/a.png" x="3" y="1" composite-op="svg:overlay"/>
+      </stack>
+    </stack>
+    <stack name="Empty"/>
+    <layer name="Base" src="data/a.png" opacity="0.25"/>
+  </stack>
+</image>"#;
+
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let stored = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    zip.start_file("mimetype", stored).unwrap();
+    zip.write_all(b"image/openraster").unwrap();
+    zip.start_file("stack.xml", stored).unwrap();
+    zip.write_all(xml.as_bytes()).unwrap();
+    zip.start_file("data/a.png", stored).unwrap();
+    zip.write_all(&png).unwrap();
+    let data = zip.finish().unwrap().into_inner();
+
+    let state = paintfe::io::parse_ora(&data).unwrap();
+    assert_eq!((state.width, state.height), (6, 4));
+    assert_eq!(state.layers.len(), 2);
+
+    let base = &state.layers[0];
+    assert_eq!(base.name, "Base");
+    assert!((base.opacity - 0.25).abs() < 0.01);
+    assert_eq!(base.folder_id, None);
+    assert_eq!(base.pixels.get_pixel(1, 1)[3], 255);
+    assert_eq!(base.pixels.get_pixel(2, 0)[3], 0);
+
+    // Nested stacks become nested folders that keep their own opacity.
+    let deep = &state.layers[1];
+    assert_eq!(deep.name, "Deep");
+    assert_eq!(deep.blend_mode, paintfe::canvas::BlendMode::Overlay);
+    assert!((deep.opacity - 1.0).abs() < 0.01);
+    assert_eq!(deep.pixels.get_pixel(3, 1)[0], 200);
+    assert_eq!(deep.pixels.get_pixel(2, 1)[3], 0);
+    let inner = state.layer_folder(deep.folder_id.unwrap()).unwrap();
+    assert_eq!(inner.name, "Inner");
+    assert!(inner.visible);
+    let outer = state.layer_folder(inner.parent_id.unwrap()).unwrap();
+    assert_eq!(outer.name, "Outer");
+    assert!((outer.opacity - 0.5).abs() < 0.01);
+    assert_eq!(
+        outer.blend_mode, None,
+        "stacks pass through unless isolated"
+    );
+    assert!(!state.layer_effectively_visible(1));
+
+    let empty = state
+        .layer_folders
+        .iter()
+        .find(|f| f.name == "Empty")
+        .unwrap();
+    assert_eq!(empty.insert_above_layer, Some(0));
+}
+
+#[test]
+fn roundtrip_ico_sizes_and_size_layers() {
+    use paintfe::icon_io::{IconOptions, encode_icns, encode_ico};
+    use paintfe::io::{LayeredExport, LayeredExportOptions};
+
+    let (w, h) = (256, 256);
+    let mut state = CanvasState::new(w, h);
+    state.layers[0] = Layer::new("Art".into(), w, h, Rgba([200, 30, 30, 255]));
+
+    // Hand-tuned 32px entry, drawn centred and kept hidden.
+    let mut small = Layer::new("32x32".into(), w, h, Rgba([0, 0, 0, 0]));
+    for y in 112..144 {
+        for x in 112..144 {
+            small.pixels.put_pixel(x, y, Rgba([0, 0, 255, 255]));
+        }
+    }
+    small.visible = false;
+    state.layers.push(small);
+
+    let options = IconOptions::default();
+    let path = temp_dir().join("rt_sizes.ico");
+    let layered = LayeredExportOptions {
+        icon: options.clone(),
+        ..Default::default()
+    };
+    LayeredExport::build(SaveFormat::Ico, &state, &layered)
+        .unwrap()
+        .write(&path)
+        .unwrap();
+
+    // Small entries are BMP, 64px and up are PNG.
+    let bytes = std::fs::read(&path).unwrap();
+    assert_eq!(u16::from_le_bytes([bytes[4], bytes[5]]), 7);
+    for i in 0..7 {
+        let dir = 6 + i * 16;
+        let size = if bytes[dir] == 0 {
+            256
+        } else {
+            bytes[dir] as u32
+        };
+        let offset = u32::from_le_bytes(bytes[dir + 12..dir + 16].try_into().unwrap()) as usize;
+        let is_png = bytes[offset..].starts_with(b"\x89PNG");
+        assert_eq!(is_png, size >= 64, "{}px entry", size);
+    }
+
+    let loaded = load_image_sync(&path).unwrap();
+    assert_eq!((loaded.width, loaded.height), (w, h));
+    let names: Vec<&str> = loaded.layers.iter().map(|l| l.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "256x256", "128x128", "64x64", "48x48", "32x32", "24x24", "16x16"
+        ]
+    );
+    assert!(loaded.layers[0].visible);
+    assert!(loaded.layers[1..].iter().all(|l| !l.visible));
+    assert_eq!(
+        *loaded.layers[0].pixels.get_pixel(0, 0),
+        Rgba([200, 30, 30, 255])
+    );
+    // The 32px entry came from its layer; the others were scaled from the art.
+    assert_eq!(
+        *loaded.layers[4].pixels.get_pixel(128, 128),
+        Rgba([0, 0, 255, 255])
+    );
+    assert_eq!(loaded.layers[4].pixels.get_pixel(100, 100)[3], 0);
+    assert_eq!(
+        *loaded.layers[6].pixels.get_pixel(128, 128),
+        Rgba([200, 30, 30, 255])
+    );
+
+    // Re-exporting the opened icon reproduces every entry.
+    let again = paintfe::icon_io::build_ico(&loaded, &options);
+    let original = paintfe::icon_io::build_ico(&state, &options);
+    assert_eq!(again.entries, original.entries);
+    assert!(!encode_ico(&again).unwrap().is_empty());
+
+    // An entry whose offset + size overflows is rejected, not a panic.
+    let mut bogus = vec![0, 0, 1, 0, 1, 0, 16, 16, 0, 0, 1, 0, 32, 0];
+    bogus.extend_from_slice(&u32::MAX.to_le_bytes());
+    bogus.extend_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(
+        paintfe::icon_io::parse_ico(&bogus).err().as_deref(),
+        Some("Truncated ICO entry")
+    );
+
+    // ICNS keeps the PNG-backed sizes that fit the canvas.
+    let icns = encode_icns(&paintfe::icon_io::build_icns(&state, &options)).unwrap();
+    assert_eq!(&icns[..4], b"icns");
+    assert_eq!(
+        u32::from_be_bytes(icns[4..8].try_into().unwrap()) as usize,
+        icns.len()
+    );
+    let mut kinds = Vec::new();
+    let mut at = 8;
+    while at < icns.len() {
+        kinds.push(String::from_utf8_lossy(&icns[at..at + 4]).into_owned());
+        assert!(icns[at + 8..].starts_with(b"\x89PNG"));
+        at += u32::from_be_bytes(icns[at + 4..at + 8].try_into().unwrap()) as usize;
+    }
+    assert_eq!(kinds, ["icp4", "icp5", "icp6", "ic07", "ic08"]);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn roundtrip_tiff_pages_depth_and_photometrics() {
+    use paintfe::canvas::PixelFormat;
+    use paintfe::experimental::DeepRgbaBuffer;
+    use paintfe::io::{LayeredExport, LayeredExportOptions, parse_layered_document};
+
+    let (w, h) = (8, 6);
+    let mut state = CanvasState::new(w, h);
+
+    // 16-bit bottom layer whose preview matches its deep payload.
+    let deep = DeepRgbaBuffer::U16(
+        (0..w * h)
+            .flat_map(|i| [1000 + i as u16, 40000, 65535, 65535])
+            .collect(),
+    );
+    let preview = deep.to_rgba8(w, h).unwrap();
+    let back = &mut state.layers[0];
+    back.name = "Shadows".into();
+    back.pixels = TiledImage::from_rgba_image(&preview);
+    back.pixel_format = PixelFormat::RgbaU16;
+    back.deep_pixels = Some(deep.clone());
+    state
+        .layers
+        .push(Layer::new("Ink".into(), w, h, Rgba([220, 10, 10, 128])));
+
+    for compression in [
+        TiffCompression::None,
+        TiffCompression::Lzw,
+        TiffCompression::Deflate,
+        TiffCompression::PackBits,
+    ] {
+        let options = LayeredExportOptions {
+            tiff_pages: true,
+            tiff_compression: compression,
+            ..Default::default()
+        };
+        assert!(LayeredExport::handles(SaveFormat::Tiff, &options));
+        let path = temp_dir().join(format!("rt_pages_{:?}.tiff", compression));
+        LayeredExport::build(SaveFormat::Tiff, &state, &options)
+            .unwrap()
+            .write(&path)
+            .unwrap();
+
+        let loaded = load_image_sync(&path).unwrap();
+        let names: Vec<&str> = loaded.layers.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, ["Shadows", "Ink"], "{:?}", compression);
+        assert_eq!(loaded.layers[0].deep_pixels.as_ref(), Some(&deep));
+        assert_eq!(
+            *loaded.layers[1].pixels.get_pixel(3, 3),
+            Rgba([220, 10, 10, 128])
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+    assert!(!LayeredExport::handles(
+        SaveFormat::Tiff,
+        &LayeredExportOptions::default()
+    ));
+
+    // JPEG pages store 8-bit RGB and drop alpha.
+    let options = LayeredExportOptions {
+        tiff_pages: true,
+        tiff_compression: TiffCompression::Jpeg,
+        quality: 95,
+        ..Default::default()
+    };
+    let path = temp_dir().join("rt_pages_jpeg.tif");
+    LayeredExport::build(SaveFormat::Tiff, &state, &options)
+        .unwrap()
+        .write(&path)
+        .unwrap();
+    let loaded = load_image_sync(&path).unwrap();
+    assert_eq!(loaded.layers.len(), 2);
+    let px = *loaded.layers[1].pixels.get_pixel(3, 3);
+    assert_eq!(px[3], 255);
+    let expected = [220u8, 10, 10];
+    assert!((0..3).all(|c| px[c].abs_diff(expected[c]) <= 8), "{:?}", px);
+    let _ = std::fs::remove_file(&path);
+
+    // Premultiplied RGBA, CMYK and a thumbnail page written by hand.
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    {
+        use tiff::encoder::{TiffEncoder, colortype};
+        use tiff::tags::Tag;
+        let mut enc = TiffEncoder::new(&mut bytes).unwrap();
+        let mut page = enc.new_image::<colortype::RGBA8>(2, 2).unwrap();
+        page.encoder().write_tag(Tag::ExtraSamples, 1u16).unwrap();
+        page.encoder()
+            .write_tag(Tag::Unknown(285), "Glaze")
+            .unwrap();
+        page.write_data(&[64, 0, 0, 128].repeat(4)).unwrap();
+        enc.write_image::<colortype::CMYK8>(3, 1, &[0, 255, 0, 0].repeat(3))
+            .unwrap();
+        let mut thumb = enc.new_image::<colortype::RGB8>(1, 1).unwrap();
+        thumb
+            .encoder()
+            .write_tag(Tag::NewSubfileType, 1u32)
+            .unwrap();
+        thumb.write_data(&[0, 0, 0]).unwrap();
+    }
+    let state = parse_layered_document("tif", bytes.get_ref())
+        .unwrap()
+        .unwrap();
+    assert_eq!((state.width, state.height), (3, 2));
+    let names: Vec<&str> = state.layers.iter().map(|l| l.name.as_str()).collect();
+    assert_eq!(names, ["Page 2", "Glaze"]);
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(2, 0),
+        Rgba([255, 0, 255, 255])
+    );
+    assert_eq!(state.layers[0].pixels.get_pixel(0, 1)[3], 0);
+    assert_eq!(
+        *state.layers[1].pixels.get_pixel(1, 1),
+        Rgba([128, 0, 0, 128])
+    );
+}
+
+#[test]
+fn roundtrip_qoi_netpbm_and_dds() {
+    use paintfe::canvas::PixelFormat;
+    use paintfe::dds_io::{DdsCompression, DdsOptions, decode_dds};
+    use paintfe::experimental::DeepRgbaBuffer;
+    use paintfe::io::{LayeredExport, LayeredExportOptions, encode_canvas_state_and_write};
+
+    let img = test_image();
+    roundtrip_format(&img, "rt.qoi", SaveFormat::Qoi, 100, 0);
+    roundtrip_format(&img, "rt.pam", SaveFormat::Pam, 100, 0);
+    roundtrip_format(&img, "rt.ppm", SaveFormat::Ppm, 100, 0);
+    roundtrip_format(&img, "rt.dds", SaveFormat::Dds, 100, 0);
+
+    // 16-bit greys survive PGM, PPM and PAM into the deep buffer.
+    let (w, h) = (5, 3);
+    let deep = DeepRgbaBuffer::U16(
+        (0..w * h)
+            .flat_map(|i| {
+                let v = 257 + i as u16 * 4001;
+                [v, v, v, 65535]
+            })
+            .collect(),
+    );
+    let mut state = CanvasState::new(w, h);
+    let layer = &mut state.layers[0];
+    layer.pixels = TiledImage::from_rgba_image(&deep.to_rgba8(w, h).unwrap());
+    layer.pixel_format = PixelFormat::RgbaU16;
+    layer.deep_pixels = Some(deep.clone());
+    for format in [SaveFormat::Pgm, SaveFormat::Ppm, SaveFormat::Pam] {
+        let path = temp_dir().join(format!("rt16.{}", format.extension()));
+        encode_canvas_state_and_write(&state, &path, format, 100, TiffCompression::None, true)
+            .unwrap();
+        let loaded = load_image_sync(&path).unwrap();
+        assert_eq!(
+            loaded.layers[0].pixel_format,
+            PixelFormat::RgbaU16,
+            "{:?}",
+            format
+        );
+        assert_eq!(
+            loaded.layers[0].deep_pixels.as_ref(),
+            Some(&deep),
+            "{:?}",
+            format
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+
+    // Block-compressed textures with a full mip chain.
+    let mut state = CanvasState::new(64, 64);
+    state.layers[0].pixels = TiledImage::from_rgba_image(&img);
+    for (compression, tolerance) in [
+        (DdsCompression::Bc1, 24),
+        (DdsCompression::Bc3, 24),
+        (DdsCompression::Bc7, 16),
+    ] {
+        let options = LayeredExportOptions {
+            dds: DdsOptions {
+                compression,
+                mipmaps: true,
+            },
+            ..Default::default()
+        };
+        assert!(LayeredExport::handles(SaveFormat::Dds, &options));
+        let path = temp_dir().join(format!("rt_{:?}.dds", compression));
+        LayeredExport::build(SaveFormat::Dds, &state, &options)
+            .unwrap()
+            .write(&path)
+            .unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let mip_count = u32::from_le_bytes(bytes[28..32].try_into().unwrap());
+        assert_eq!(mip_count, 7, "{:?}", compression);
+        let loaded = load_image_sync(&path).unwrap().composite();
+        let diff = compare_images(&loaded, &img, tolerance);
+        assert!(
+            diff.matches,
+            "{:?}: max channel diff = {}",
+            compression, diff.max_channel_diff
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+
+    // Punch-through alpha in BC1, smooth alpha in BC3 / BC7.
+    let mut alpha = RgbaImage::new(8, 4);
+    for (x, _, px) in alpha.enumerate_pixels_mut() {
+        *px = Rgba([200, 100, 50, (x * 36) as u8]);
+    }
+    for compression in [
+        DdsCompression::Bc1,
+        DdsCompression::Bc3,
+        DdsCompression::Bc7,
+    ] {
+        let options = DdsOptions {
+            compression,
+            mipmaps: false,
+        };
+        let decoded = decode_dds(&paintfe::dds_io::encode_dds(&alpha, &options)).unwrap();
+        for (x, _, px) in decoded.enumerate_pixels() {
+            let expected = (x * 36) as u8;
+            if compression == DdsCompression::Bc1 {
+                assert_eq!(px[3], if expected < 128 { 0 } else { 255 });
+            } else {
+                assert!(px[3].abs_diff(expected) <= 8, "{:?} {:?}", compression, px);
+            }
+        }
+    }
+}
+
+#[test]
+fn import_svg_groups_gradients_and_size() {
+    use paintfe::svg_io::{SvgDocument, SvgOptions, parse_svg};
+
+    let svg = br##"<?xml version="1.0"?>
+<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20" viewBox="0 0 80 40">
+  <style>.blue { fill: #0000ff }</style>
+  <defs>
+    <linearGradient id="fade" x1="0" x2="1" y1="0" y2="0">
+      <stop offset="0" stop-color="black"/>
+      <stop offset="1" stop-color="white"/>
+    </linearGradient>
+  </defs>
+  <rect width="80" height="40" fill="white"/>
+  <g id="shapes">
+    <rect x="0" y="0" width="40" height="40" fill="red"/>
+    <circle class="blue" cx="60" cy="20" r="16"/>
+  </g>
+  <g id="ramp" opacity="0.5"><rect x="0" y="30" width="80" height="10" fill="url(#fade)"/></g>
+  <g id="hidden" style="display:none"><rect width="80" height="40" fill="lime"/></g>
+</svg>"##;
+
+    let document = SvgDocument::parse(svg).unwrap();
+    assert_eq!(document.intrinsic_size(), (40.0, 20.0));
+    assert_eq!(document.group_count(), 3);
+
+    // viewBox 80×40 maps onto 40×20 px at 96 DPI.
+    let state = parse_svg(svg, "Icon", &SvgOptions::default()).unwrap();
+    assert_eq!((state.width, state.height), (40, 20));
+    let names: Vec<&str> = state.layers.iter().map(|l| l.name.as_str()).collect();
+    assert_eq!(names, ["Layer 1", "shapes", "ramp", "hidden"]);
+    assert!(!state.layers[3].visible);
+    assert_eq!(
+        *state.layers[3].pixels.get_pixel(5, 5),
+        Rgba([0, 255, 0, 255])
+    );
+
+    let shapes = state.layers[1].pixels.to_rgba_image();
+    assert_eq!(*shapes.get_pixel(5, 5), Rgba([255, 0, 0, 255]));
+    assert_eq!(*shapes.get_pixel(30, 10), Rgba([0, 0, 255, 255]));
+    assert_eq!(shapes.get_pixel(21, 1)[3], 0);
+
+    // Group opacity halves the gradient, which runs dark to light.
+    let ramp = state.layers[2].pixels.to_rgba_image();
+    let (left, right) = (ramp.get_pixel(1, 17), ramp.get_pixel(38, 17));
+    assert!((left[3] as i32 - 128).abs() <= 2, "alpha {}", left[3]);
+    assert!(left[0] < 20 && right[0] > 235, "{:?} {:?}", left, right);
+    assert_eq!(ramp.get_pixel(5, 5)[3], 0);
+
+    // DPI scales, explicit sizes keep the aspect ratio, and flattening
+    // renders one layer named after the document.
+    let options = SvgOptions {
+        dpi: 192.0,
+        ..Default::default()
+    };
+    assert_eq!(document.target_size(&options), (80, 40));
+    let options = SvgOptions {
+        height: Some(50),
+        split_groups: false,
+        ..Default::default()
+    };
+    let flat = parse_svg(svg, "Icon", &options).unwrap();
+    assert_eq!((flat.width, flat.height), (100, 50));
+    assert_eq!(flat.layers.len(), 1);
+    assert_eq!(flat.layers[0].name, "Icon");
+    let composite = flat.composite();
+    assert_eq!(*composite.get_pixel(10, 10), Rgba([255, 0, 0, 255]));
+    assert_eq!(*composite.get_pixel(75, 25), Rgba([0, 0, 255, 255]));
+
+    // load_image_sync opens .svg at its intrinsic size.
+    let path = temp_dir().join("import.svg");
+    std::fs::write(&path, svg).unwrap();
+    let loaded = load_image_sync(&path).unwrap();
+    assert_eq!((loaded.width, loaded.height), (40, 20));
+    assert_eq!(loaded.layers.len(), 4);
+    let _ = std::fs::remove_file(&path);
+
+    assert!(SvgDocument::parse(b"<html></html>").is_err());
+}
+
+#[test]
+fn byte_open_extensions_all_have_a_byte_decoder() {
+    use paintfe::io::{byte_open_extensions, parse_layered_document};
+
+    let exts: Vec<&str> = byte_open_extensions().collect();
+    for ext in ["svg", "dds", "qoi", "pnm", "exr"] {
+        assert!(exts.contains(&ext), "{} missing from {:?}", ext, exts);
+    }
+    assert!(!exts.contains(&"pdn"));
+    // Mirrors the browser open dispatch: layered documents, .pfe, then the image crate.
+    for ext in exts {
+        assert!(
+            ext == "pfe"
+                || parse_layered_document(ext, &[]).is_some()
+                || image::ImageFormat::from_extension(ext).is_some(),
+            "no byte decoder for .{}",
+            ext
+        );
+    }
+}
+
+/// Check that every in-use xref entry of `pdf` points at its object.
+fn assert_pdf_xref(pdf: &[u8]) {
+    // Offsets are in bytes, so search the raw file rather than lossy UTF-8.
+    let tail = String::from_utf8_lossy(&pdf[pdf.len() - 32..]).into_owned();
+    let start: usize = tail
+        .rsplit("startxref\n")
+        .next()
+        .and_then(|s| s.lines().next())
+        .and_then(|s| s.parse().ok())
+        .unwrap();
+    let table = std::str::from_utf8(&pdf[start..]).unwrap();
+    assert!(table.starts_with("xref\n0 "));
+    for (id, line) in table.lines().skip(2).enumerate() {
+        if line.starts_with("trailer") {
+            break;
+        }
+        if line.ends_with(" n ") {
+            let offset: usize = line[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(format!("{} 0 obj", id).as_bytes()));
+        }
+    }
+}
+
+#[test]
+fn page_setup_layout_and_pdf_export() {
+    use paintfe::ops::page_setup::{
+        Orientation, PageScaling, PageSetup, PaperSize, export_pdf, render_page,
+    };
+
+    let setup = PageSetup::default();
+    let (pw, ph) = setup.page_size_pt();
+    assert!((pw - 595.28).abs() < 0.01 && (ph - 841.89).abs() < 0.01);
+    let landscape = PageSetup {
+        paper: PaperSize::Letter,
+        orientation: Orientation::Landscape,
+        ..Default::default()
+    };
+    let (lw, lh) = landscape.page_size_pt();
+    assert!((lw - 792.0).abs() < 0.01 && (lh - 612.0).abs() < 0.01);
+
+    // Fit fills the printable width of a wide image and centres it.
+    let pages = setup.layout(200, 100).unwrap();
+    assert_eq!(pages.len(), 1);
+    let area = setup.printable_rect();
+    assert!((pages[0].trim.width() - area.width()).abs() < 1e-6);
+    assert!((pages[0].trim.center().y - ph / 2.0).abs() < 1e-6);
+
+    // Actual size at 300 DPI: 300 px is one inch.
+    let dpi = PageSetup {
+        scaling: PageScaling::Dpi,
+        ..Default::default()
+    };
+    let pages = dpi.layout(300, 150).unwrap();
+    assert!((pages[0].image_rect.width() - 72.0).abs() < 1e-6);
+    assert!((pages[0].image_rect.height() - 36.0).abs() < 1e-6);
+
+    // Ten inches square tiles into two columns and one row on A4.
+    let tile = PageSetup {
+        scaling: PageScaling::Tile,
+        ..Default::default()
+    };
+    let pages = tile.layout(3000, 3000).unwrap();
+    assert_eq!(pages.len(), 2);
+    assert!((pages[0].trim.width() - area.width()).abs() < 1e-6);
+    assert!(pages[1].trim.width() < area.width());
+
+    // A typo'd DPI would tile a small image over thousands of pages.
+    let low_dpi = PageSetup {
+        scaling: PageScaling::Tile,
+        dpi: 1.0,
+        ..Default::default()
+    };
+    let err = low_dpi.layout(4000, 4000).unwrap_err();
+    assert!(err.contains("pages"), "{err}");
+
+    // Bleed grows the clip past the trim; Fill covers it.
+    let bleed = PageSetup {
+        scaling: PageScaling::Fill,
+        bleed_mm: 3.0,
+        crop_marks: true,
+        ..Default::default()
+    };
+    let page = bleed.layout(100, 100).unwrap()[0];
+    let bleed_pt = 3.0 * 72.0 / 25.4;
+    assert!((page.clip.width() - (page.trim.width() + 2.0 * bleed_pt)).abs() < 1e-3);
+    assert_eq!(page.crop_marks(bleed_pt).len(), 8);
+
+    assert!(
+        PageSetup {
+            margins_mm: [200.0; 4],
+            ..Default::default()
+        }
+        .layout(10, 10)
+        .is_err()
+    );
+
+    // The preview shows the image on white paper with black crop marks.
+    let mut image = RgbaImage::from_pixel(100, 100, Rgba([255, 0, 0, 255]));
+    image.put_pixel(0, 0, Rgba([0, 0, 0, 0]));
+    let preview = render_page(&image, &bleed, &page, 0.5);
+    let (cx, cy) = (
+        (page.trim.center().x * 0.5) as u32,
+        (page.trim.center().y * 0.5) as u32,
+    );
+    assert_eq!(*preview.get_pixel(cx, cy), Rgba([255, 0, 0, 255]));
+    assert_eq!(*preview.get_pixel(0, 0), Rgba([255, 255, 255, 255]));
+    let (a, b) = page.crop_marks(bleed_pt)[0];
+    let mark = a.midpoint(b);
+    let (mx, my) = ((mark.x * 0.5) as u32, (mark.y * 0.5) as u32);
+    assert_eq!(*preview.get_pixel(mx, my), Rgba([0, 0, 0, 255]));
+
+    // PDF: one page per tile, valid cross references, Flate or JPEG images.
+    let pdf = export_pdf(&image, &tile).unwrap();
+    assert!(pdf.starts_with(b"%PDF-1.4"));
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.contains("/Count 1"));
+    assert!(text.contains("/FlateDecode"));
+    assert_pdf_xref(&pdf);
+
+    let big = RgbaImage::from_pixel(3000, 3000, Rgba([0, 128, 255, 255]));
+    let jpeg = PageSetup {
+        jpeg_quality: Some(80),
+        ..tile.clone()
+    };
+    let pdf = export_pdf(&big, &jpeg).unwrap();
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.contains("/Count 2"));
+    assert_eq!(text.matches("/Type /Page ").count(), 2);
+    assert_eq!(text.matches("/DCTDecode").count(), 2);
+    assert!(text.contains("/TrimBox"));
+    assert_pdf_xref(&pdf);
+
+    let path = temp_dir().join("layout.pdf");
+    paintfe::ops::page_setup::write_pdf(&image, &bleed, &path).unwrap();
+    let written = std::fs::read(&path).unwrap();
+    assert!(String::from_utf8_lossy(&written).contains(" m ") && written.ends_with(b"%%EOF\n"));
+    let _ = std::fs::remove_file(&path);
+}
+
+/// Sampling factors of each component in a JPEG's start-of-frame marker,
+/// and whether that frame is progressive.
+fn jpeg_frame(data: &[u8]) -> (bool, Vec<u8>) {
+    let mut i = 2;
+    while i + 4 < data.len() {
+        let (marker, len) = (data[i + 1], u16::from_be_bytes([data[i + 2], data[i + 3]]));
+        if marker == 0xC0 || marker == 0xC2 {
+            let count = data[i + 9] as usize;
+            let factors = (0..count).map(|c| data[i + 11 + c * 3]).collect();
+            return (marker == 0xC2, factors);
+        }
+        i += 2 + len as usize;
+    }
+    panic!("no start-of-frame marker");
+}
+
+#[test]
+fn jpeg_progressive_subsampling_and_target_size() {
+    use paintfe::io::{LayeredExport, LayeredExportOptions};
+    use paintfe::jpeg_io::{
+        ChromaSubsampling, JpegOptions, encode_at_quality, encode_jpeg, fit_quality,
+    };
+
+    let img = RgbaImage::from_fn(96, 64, |x, y| {
+        Rgba([
+            (x * 7 % 256) as u8,
+            (y * 11 % 256) as u8,
+            ((x ^ y) * 5 % 256) as u8,
+            255,
+        ])
+    });
+
+    let baseline = encode_at_quality(&img, 85, &JpegOptions::default()).unwrap();
+    assert_eq!(jpeg_frame(&baseline), (false, vec![0x11, 0x11, 0x11]));
+    // Default options write exactly what plain JPEG exports always wrote.
+    let mut plain = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut plain, 85)
+        .encode_image(&image::DynamicImage::ImageRgba8(img.clone()).to_rgb8())
+        .unwrap();
+    assert_eq!(baseline, plain);
+
+    let progressive = JpegOptions {
+        progressive: true,
+        subsampling: ChromaSubsampling::S420,
+        ..Default::default()
+    };
+    let data = encode_at_quality(&img, 85, &progressive).unwrap();
+    assert_eq!(jpeg_frame(&data), (true, vec![0x22, 0x11, 0x11]));
+    let decoded = image::load_from_memory(&data).unwrap().to_rgba8();
+    assert_eq!(decoded.dimensions(), (96, 64));
+
+    let s422 = JpegOptions {
+        subsampling: ChromaSubsampling::S422,
+        ..Default::default()
+    };
+    let data = encode_at_quality(&img, 85, &s422).unwrap();
+    assert_eq!(jpeg_frame(&data).1, vec![0x21, 0x11, 0x11]);
+    assert_eq!(
+        ChromaSubsampling::parse("4:2:2"),
+        Some(ChromaSubsampling::S422)
+    );
+    assert_eq!(ChromaSubsampling::parse("411"), None);
+
+    // Optimized tables only change the entropy coding, never the pixels.
+    let optimized = JpegOptions {
+        optimize_huffman: true,
+        ..s422.clone()
+    };
+    let unoptimized = encode_at_quality(&img, 85, &s422).unwrap();
+    let data = encode_at_quality(&img, 85, &optimized).unwrap();
+    assert!(data.len() < unoptimized.len());
+    assert_eq!(
+        image::load_from_memory(&data).unwrap().to_rgba8(),
+        image::load_from_memory(&unoptimized).unwrap().to_rgba8()
+    );
+
+    // Target size picks the highest quality that fits.
+    let target = (encode_at_quality(&img, 40, &JpegOptions::default())
+        .unwrap()
+        .len()
+        + encode_at_quality(&img, 41, &JpegOptions::default())
+            .unwrap()
+            .len())
+        / 2;
+    let (quality, bytes) = fit_quality(&img, target, &JpegOptions::default()).unwrap();
+    assert!(bytes.len() <= target, "{} > {}", bytes.len(), target);
+    let above = encode_at_quality(&img, quality + 1, &JpegOptions::default()).unwrap();
+    assert!(above.len() > target);
+    let sized = JpegOptions {
+        target_size: Some(target),
+        ..Default::default()
+    };
+    assert_eq!(encode_jpeg(&img, 100, &sized).unwrap(), bytes);
+    // An impossible budget still produces the smallest file.
+    assert_eq!(fit_quality(&img, 10, &JpegOptions::default()).unwrap().0, 1);
+
+    // Layered saves carry the encoder options.
+    let mut state = CanvasState::new(96, 64);
+    state.layers[0].pixels = TiledImage::from_rgba_image(&img);
+    let options = LayeredExportOptions {
+        quality: 85,
+        jpeg: progressive,
+        ..Default::default()
+    };
+    assert!(LayeredExport::handles(SaveFormat::Jpeg, &options));
+    let path = temp_dir().join("progressive.jpg");
+    LayeredExport::build(SaveFormat::Jpeg, &state, &options)
+        .unwrap()
+        .write(&path)
+        .unwrap();
+    assert!(jpeg_frame(&std::fs::read(&path).unwrap()).0);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn export_presets_scale_depth_metadata_and_file() {
+    use paintfe::config::export_presets::{
+        ExportDepth, ExportPreset, ExportPresetFile, MetadataPolicy, default_export_presets,
+    };
+    use paintfe::io::PresetExport;
+
+    // A PNG with a text chunk, so there is source metadata to keep.
+    let dir = temp_dir();
+    let source = dir.join("preset_source.png");
+    {
+        let img = create_test_gradient(20, 10);
+        let file = std::fs::File::create(&source).unwrap();
+        let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), 20, 10);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder
+            .add_text_chunk("parameters".to_string(), "prompt data".to_string())
+            .unwrap();
+        encoder
+            .write_header()
+            .unwrap()
+            .write_image_data(img.as_raw())
+            .unwrap();
+    }
+    let state = load_image_sync(&source).unwrap();
+
+    let presets = default_export_presets();
+    let at2x = presets.iter().find(|p| p.suffix == "@2x").unwrap();
+    let path = at2x.output_path(&dir, "preset_source");
+    assert_eq!(path.file_name().unwrap(), "preset_source@2x.png");
+    PresetExport::build(&state, at2x)
+        .unwrap()
+        .write(&path)
+        .unwrap();
+    let decoder = png::Decoder::new(std::io::BufReader::new(std::fs::File::open(&path).unwrap()));
+    let reader = decoder.read_info().unwrap();
+    let info = reader.info();
+    assert_eq!((info.width, info.height), (40, 20));
+    assert_eq!(info.bit_depth, png::BitDepth::Eight);
+    assert!(info.uncompressed_latin1_text.is_empty());
+
+    // 16-bit, half size, keeping the source's text chunk.
+    let keep = ExportPreset {
+        name: "Deep".to_string(),
+        scale: 0.5,
+        depth: ExportDepth::Sixteen,
+        metadata: MetadataPolicy::Keep,
+        suffix: "-deep".to_string(),
+        ..Default::default()
+    };
+    let path = keep.output_path(&dir, "preset_source");
+    PresetExport::build(&state, &keep)
+        .unwrap()
+        .write(&path)
+        .unwrap();
+    let decoder = png::Decoder::new(std::io::BufReader::new(std::fs::File::open(&path).unwrap()));
+    let reader = decoder.read_info().unwrap();
+    let info = reader.info();
+    assert_eq!((info.width, info.height), (10, 5));
+    assert_eq!(info.bit_depth, png::BitDepth::Sixteen);
+    assert_eq!(info.uncompressed_latin1_text[0].keyword, "parameters");
+    assert_eq!(info.uncompressed_latin1_text[0].text, "prompt data");
+
+    // Presets are rejected for project files and bad scales.
+    let pfe = ExportPreset {
+        format: SaveFormat::Pfe,
+        ..Default::default()
+    };
+    assert!(PresetExport::build(&state, &pfe).is_err());
+    let zero = ExportPreset {
+        scale: 0.0,
+        ..Default::default()
+    };
+    assert!(zero.validate().is_err());
+
+    // The preset file round-trips and fills in missing fields.
+    let file = dir.join("export_presets.json");
+    ExportPresetFile::new(presets.clone()).save(&file).unwrap();
+    assert_eq!(ExportPresetFile::load(&file).unwrap().presets, presets);
+    let sparse = ExportPresetFile::from_json(
+        r#"{"version":1,"presets":[{"name":"Thumb","format":"webp","scale":0.25}]}"#,
+    )
+    .unwrap();
+    assert_eq!(sparse.presets[0].format, SaveFormat::Webp);
+    assert_eq!(sparse.presets[0].quality, 90);
+    assert!(ExportPresetFile::from_json(r#"{"version":99,"presets":[]}"#).is_err());
+
+    for name in [
+        "preset_source.png",
+        "preset_source@2x.png",
+        "preset_source-deep.png",
+        "export_presets.json",
+    ] {
+        let _ = std::fs::remove_file(dir.join(name));
+    }
+}
@@ -172,6 +172,7 @@ fn hidden_folder_hides_member_layers() {
         collapsed: false,
         insert_above_layer: None,
         color_index: None,
+        ..Default::default()
     });
 
     let mut layer = Layer::new("Red".into(), 32, 32, Rgba([0, 0, 0, 0]));
@@ -185,6 +186,97 @@ fn hidden_folder_hides_member_layers() {
     assert!(!state.layer_effectively_visible(1));
 }
 
+fn solid_layer(name: &str, color: [u8; 4]) -> Layer {
+    let mut layer = Layer::new(name.into(), 8, 8, Rgba([0, 0, 0, 0]));
+    layer.pixels = TiledImage::from_rgba_image(&RgbaImage::from_pixel(8, 8, Rgba(color)));
+    layer
+}
+
+fn group(id: u64, parent_id: Option<u64>) -> LayerFolder {
+    LayerFolder {
+        id,
+        name: format!("Group {id}"),
+        parent_id,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn isolated_group_blends_contents_before_backdrop() {
+    use paintfe::canvas::BlendMode;
+
+    let mut state = CanvasState::new(8, 8);
+    state.layers.push(solid_layer("Red", [255, 0, 0, 255]));
+    let mut gray = solid_layer("Gray", [128, 128, 128, 255]);
+    gray.blend_mode = BlendMode::Multiply;
+    gray.folder_id = Some(1);
+    state.layers.push(gray);
+
+    // Pass-through: the Multiply member sees the red backdrop.
+    state.layer_folders.push(group(1, None));
+    assert!(!state.has_group_compositing());
+    assert_eq!(*state.composite().get_pixel(4, 4), Rgba([128, 0, 0, 255]));
+
+    // Isolated Normal group: Multiply only sees the group's own transparency.
+    state.layer_folders[0].blend_mode = Some(BlendMode::Normal);
+    assert!(state.has_group_compositing());
+    assert_eq!(
+        *state.composite().get_pixel(4, 4),
+        Rgba([128, 128, 128, 255])
+    );
+    assert_eq!(
+        state.gpu_composite_groups().map(|groups| groups.len()),
+        Some(1),
+        "isolated groups without masks can run on the GPU"
+    );
+}
+
+#[test]
+fn nested_group_opacity_and_mask_apply_to_contents() {
+    let mut state = CanvasState::new(8, 8);
+    let mut black = solid_layer("Black", [0, 0, 0, 255]);
+    black.folder_id = Some(2);
+    state.layers.push(black);
+    state.layer_folders.push(group(1, None));
+    state.layer_folders.push(group(2, Some(1)));
+    state.layer_folders[0].opacity = 0.5;
+    state.layer_folders[1].opacity = 0.5;
+
+    assert_eq!(state.folder_depth(2), 1);
+    assert!(state.layer_in_folder(1, 1), "membership is transitive");
+    let px = *state.composite().get_pixel(4, 4);
+    assert!((189..=193).contains(&px[0]), "got {:?}", px);
+    assert!(
+        state.gpu_composite_groups().is_none(),
+        "pass-through opacity is composited on the CPU"
+    );
+
+    // A group mask conceals the whole group where it is opaque.
+    state.layer_folders[1].opacity = 1.0;
+    state.layer_folders[0].opacity = 1.0;
+    let mut mask = TiledImage::new(8, 8);
+    for y in 0..8 {
+        for x in 0..4 {
+            mask.put_pixel(x, y, Rgba([0, 0, 0, 255]));
+        }
+    }
+    state.layer_folders[0].mask = Some(mask);
+    let comp = state.composite();
+    assert_eq!(*comp.get_pixel(1, 4), Rgba([255, 255, 255, 255]));
+    assert_eq!(*comp.get_pixel(6, 4), Rgba([0, 0, 0, 255]));
+
+    state.layer_folders[0].mask_enabled = false;
+    assert_eq!(*state.composite().get_pixel(1, 4), Rgba([0, 0, 0, 255]));
+
+    // Hiding the outer group hides everything nested in it.
+    state.layer_folders[0].visible = false;
+    assert!(!state.layer_effectively_visible(1));
+    assert_eq!(
+        *state.composite().get_pixel(6, 4),
+        Rgba([255, 255, 255, 255])
+    );
+}
+
 #[test]
 fn folder_snapshot_undo_redo_restores_membership() {
     let mut state = CanvasState::new(16, 16);
@@ -201,6 +293,7 @@ fn folder_snapshot_undo_redo_restores_membership() {
         collapsed: true,
         insert_above_layer: None,
         color_index: None,
+        ..Default::default()
     });
     state.next_layer_folder_id = 2;
     state.layers[1].folder_id = Some(1);
@@ -309,6 +402,33 @@ fn flatten_preserves_hidden_layer_exclusion() {
     assert_eq!(before, after);
 }
 
+#[test]
+fn merge_down_keeps_off_canvas_content_of_both_layers() {
+    let mut state = CanvasState::new(8, 8);
+    state.layers[0] = Layer::new("Background".into(), 8, 8, Rgba([0, 0, 0, 0]));
+    state.layers[0]
+        .pixels
+        .put_pixel(7, 7, Rgba([0, 0, 255, 255]));
+    state.layers[0].translate_content(0, 3, 8, 8);
+    let mut top = Layer::new("Top".into(), 8, 8, Rgba([0, 0, 0, 0]));
+    top.pixels.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+    top.pixels.put_pixel(4, 4, Rgba([0, 255, 0, 255]));
+    top.translate_content(-3, 0, 8, 8);
+    state.layers.push(top);
+    state.active_layer_index = 1;
+
+    canvas_ops::merge_down(&mut state, 1);
+    assert_eq!(state.layers.len(), 1);
+    assert_eq!(state.active_layer_index, 0);
+    let merged = &state.layers[0];
+    assert_eq!(merged.extent(), (-3, 0, 8, 11));
+    let (origin, img) = merged.extent_rgba();
+    let at = |x: i32, y: i32| *img.get_pixel((x - origin.0) as u32, (y - origin.1) as u32);
+    assert_eq!(at(-3, 0), Rgba([255, 0, 0, 255]));
+    assert_eq!(at(1, 4), Rgba([0, 255, 0, 255]));
+    assert_eq!(at(7, 10), Rgba([0, 0, 255, 255]));
+}
+
 // =============================================================================
 // Active layer index safety
 // =============================================================================
@@ -329,3 +449,352 @@ fn active_layer_index_clamped_after_delete() {
         "active index should be valid after delete"
     );
 }
+
+// =============================================================================
+// Layer locks
+// =============================================================================
+
+#[test]
+fn position_and_pixel_locks_block_layer_transforms() {
+    use paintfe::canvas::LayerLocks;
+    use paintfe::ops::transform;
+
+    let mut state = CanvasState::new(8, 8);
+    state.layers[0]
+        .pixels
+        .put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+    state.layers[0].locks.position = true;
+
+    transform::flip_layer_horizontal(&mut state, 0);
+    transform::affine_transform_layer(&mut state, 0, 90.0, 0.0, 0.0, 1.0, (2.0, 0.0));
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(0, 0),
+        Rgba([255, 0, 0, 255])
+    );
+
+    state.layers[0].locks = LayerLocks {
+        pixels: true,
+        ..Default::default()
+    };
+    transform::flip_layer_vertical(&mut state, 0);
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(0, 0),
+        Rgba([255, 0, 0, 255])
+    );
+
+    state.layers[0].locks = LayerLocks::default();
+    transform::flip_layer_horizontal(&mut state, 0);
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(7, 0),
+        Rgba([255, 0, 0, 255])
+    );
+}
+
+#[test]
+fn transparency_lock_keeps_alpha_on_edits() {
+    let mut layer = Layer::new("L".into(), 4, 1, Rgba([0, 0, 0, 0]));
+    layer.pixels.put_pixel(0, 0, Rgba([10, 10, 10, 128]));
+    layer.locks.transparency = true;
+
+    let edited = RgbaImage::from_pixel(4, 1, Rgba([200, 100, 50, 255]));
+    assert!(layer.set_pixels_with_locks(&edited));
+    // Opaque-ish pixel recoloured, alpha kept; empty pixels stay empty.
+    assert_eq!(*layer.pixels.get_pixel(0, 0), Rgba([200, 100, 50, 128]));
+    assert_eq!(*layer.pixels.get_pixel(1, 0), Rgba([0, 0, 0, 0]));
+
+    layer.locks.all = true;
+    let cleared = RgbaImage::new(4, 1);
+    assert!(!layer.set_pixels_with_locks(&cleared));
+    assert_eq!(*layer.pixels.get_pixel(0, 0), Rgba([200, 100, 50, 128]));
+}
+
+// =============================================================================
+// Layer comps
+// =============================================================================
+
+#[test]
+fn layer_comp_restores_visibility_opacity_blend_and_position() {
+    use paintfe::canvas::{BlendMode, LayerComp};
+
+    let mut state = CanvasState::new(8, 8);
+    let mut logo = Layer::new("Logo".into(), 8, 8, Rgba([0, 0, 0, 0]));
+    logo.pixels.put_pixel(1, 1, Rgba([255, 0, 0, 255]));
+    state.layers.push(logo);
+    let comp = LayerComp::capture("Default", &state);
+    assert_eq!(comp.entries[1].offset, Some((0, 0)));
+    state.layer_comps.push(comp);
+
+    // Change everything the comp records, pushing the logo off the canvas.
+    let logo = &mut state.layers[1];
+    logo.visible = false;
+    logo.opacity = 0.25;
+    logo.blend_mode = BlendMode::Multiply;
+    logo.translate_content(-5, 0, 8, 8);
+    assert_eq!(logo.move_offset, (-5, 0));
+
+    state.apply_layer_comp_named("Default").unwrap();
+    let logo = &state.layers[1];
+    assert!(logo.visible);
+    assert_eq!(logo.opacity, 1.0);
+    assert_eq!(logo.blend_mode, BlendMode::Normal);
+    assert!(logo.off_canvas.is_none());
+    assert_eq!(*logo.pixels.get_pixel(1, 1), Rgba([255, 0, 0, 255]));
+    assert_eq!(logo.move_offset, (0, 0));
+    assert!(state.apply_layer_comp_named("Missing").is_err());
+}
+
+#[test]
+fn translating_far_off_canvas_keeps_content_sparse() {
+    let mut layer = Layer::new("Logo".into(), 8, 8, Rgba([0, 0, 0, 0]));
+    layer.pixels.put_pixel(1, 1, Rgba([255, 0, 0, 255]));
+    layer.pixels.put_pixel(6, 6, Rgba([0, 0, 255, 255]));
+
+    // Push one pixel off the canvas, then carry the store far away: only
+    // the painted pixels are stored, not the box between them and the canvas.
+    layer.translate_content(3, 0, 8, 8);
+    layer.translate_content(0, 1_000_000, 8, 8);
+    let off = layer.off_canvas.as_ref().expect("off-canvas content");
+    assert_eq!(off.bounds(), (4, 1_000_001, 10, 1_000_007));
+    assert_eq!(off.pixels.chunk_count(), 1);
+    assert_eq!(layer.pixels.chunk_count(), 0);
+
+    layer.translate_content(-3, -1_000_000, 8, 8);
+    assert!(layer.off_canvas.is_none());
+    assert_eq!(*layer.pixels.get_pixel(1, 1), Rgba([255, 0, 0, 255]));
+    assert_eq!(*layer.pixels.get_pixel(6, 6), Rgba([0, 0, 255, 255]));
+    assert_eq!(layer.move_offset, (0, 0));
+}
+
+#[test]
+fn layer_comp_ignores_painting_after_capture() {
+    use paintfe::canvas::LayerComp;
+    use paintfe::ops::clipboard::extract_to_overlay;
+
+    let mut state = CanvasState::new(8, 8);
+    let mut logo = Layer::new("Logo".into(), 8, 8, Rgba([0, 0, 0, 0]));
+    logo.pixels.put_pixel(4, 4, Rgba([255, 0, 0, 255]));
+    state.layers.push(logo);
+    state.active_layer_index = 1;
+    let comp = LayerComp::capture("Default", &state);
+    state.layer_comps.push(comp);
+
+    // Painting above and left of the content moves its bounds, not the layer.
+    state.layers[1]
+        .pixels
+        .put_pixel(0, 0, Rgba([0, 0, 255, 255]));
+    state.apply_layer_comp(0);
+    let logo = &state.layers[1];
+    assert_eq!(*logo.pixels.get_pixel(4, 4), Rgba([255, 0, 0, 255]));
+    assert_eq!(*logo.pixels.get_pixel(0, 0), Rgba([0, 0, 255, 255]));
+
+    // Moving the whole layer with Move Pixels is undone by the comp.
+    let mut overlay = extract_to_overlay(&mut state).unwrap();
+    overlay.anti_aliasing = false;
+    overlay.center += egui::vec2(2.0, 1.0);
+    overlay.commit(&mut state);
+    assert_eq!(state.layers[1].move_offset, (2, 1));
+    assert_eq!(
+        *state.layers[1].pixels.get_pixel(6, 5),
+        Rgba([255, 0, 0, 255])
+    );
+    state.apply_layer_comp(0);
+    let logo = &state.layers[1];
+    assert_eq!(logo.move_offset, (0, 0));
+    assert_eq!(*logo.pixels.get_pixel(4, 4), Rgba([255, 0, 0, 255]));
+    assert_eq!(*logo.pixels.get_pixel(0, 0), Rgba([0, 0, 255, 255]));
+    assert!(logo.off_canvas.is_none());
+}
+
+#[test]
+fn layer_comps_match_layers_by_name_after_reordering() {
+    use paintfe::canvas::LayerComp;
+
+    let mut state = CanvasState::new(4, 4);
+    state
+        .layers
+        .push(Layer::new("Top".into(), 4, 4, Rgba([0, 0, 0, 0])));
+    state.layers[0].visible = false;
+    let comp = LayerComp::capture("Hidden background", &state);
+    state.layer_comps.push(comp);
+
+    state.layers.swap(0, 1);
+    state.layers[1].visible = true;
+    state.layers[0].visible = false;
+    assert!(state.apply_layer_comp(0));
+    assert_eq!(state.layers[0].name, "Top");
+    assert!(state.layers[0].visible);
+    assert!(!state.layers[1].visible);
+}
+
+// =============================================================================
+// Layer export
+// =============================================================================
+
+#[test]
+fn layer_file_stem_fills_template_and_pads_index() {
+    use paintfe::ops::layer_export::layer_file_stem;
+
+    assert_eq!(
+        layer_file_stem("{doc}_{layer}_{index}", "ui", "Button/Hover", 3, 12),
+        "ui_Button_Hover_03"
+    );
+    // Names are substituted once, never re-expanded.
+    assert_eq!(
+        layer_file_stem("{layer}-{doc}", "{index}", "A", 1, 1),
+        "A-_index_"
+    );
+}
+
+#[test]
+fn layer_export_units_follow_groups_and_visibility() {
+    use paintfe::ops::layer_export::{LayerExportOptions, layer_export_units};
+
+    let mut state = CanvasState::new(8, 8);
+    let mut icon = solid_layer("Icon", [255, 0, 0, 255]);
+    icon.folder_id = Some(2);
+    state.layers.push(icon);
+    let mut label = solid_layer("Label", [0, 255, 0, 255]);
+    label.folder_id = Some(1);
+    state.layers.push(label);
+    let mut hidden = solid_layer("Hidden", [0, 0, 255, 255]);
+    hidden.visible = false;
+    state.layers.push(hidden);
+    state.layer_folders.push(group(1, None));
+    state.layer_folders.push(group(2, Some(1)));
+
+    let mut options = LayerExportOptions::default();
+    let names = |options: &LayerExportOptions| -> Vec<String> {
+        layer_export_units(&state, options)
+            .into_iter()
+            .map(|u| u.name)
+            .collect()
+    };
+    assert_eq!(names(&options), ["Label", "Icon", "Background"]);
+
+    options.include_hidden = true;
+    assert_eq!(names(&options), ["Hidden", "Label", "Icon", "Background"]);
+
+    options.include_hidden = false;
+    options.groups = true;
+    let units = layer_export_units(&state, &options);
+    assert_eq!(units.len(), 2);
+    assert_eq!(units[0].name, "Group 1");
+    assert_eq!(units[0].layers, [1, 2]);
+    assert_eq!(units[1].name, "Background");
+}
+
+// =============================================================================
+// Slices and sprite sheets
+// =============================================================================
+
+#[test]
+fn grid_slices_cover_canvas_and_add_slices_renames() {
+    use paintfe::canvas::Slice;
+    use paintfe::ops::slices::grid_slices;
+
+    let grid = grid_slices(10, 7, 3, 2);
+    assert_eq!(grid.len(), 6);
+    assert_eq!(grid[0].rect(), (0, 0, 3, 3));
+    assert_eq!(grid[2].rect(), (6, 0, 4, 3));
+    assert_eq!(grid[5].rect(), (6, 3, 4, 4));
+    assert_eq!(grid.iter().map(|s| s.width * s.height).sum::<u32>(), 70);
+
+    let mut state = CanvasState::new(8, 8);
+    let added = state.add_slices([
+        Slice::new("Icon", 2, 2, 10, 3),
+        Slice::new("Icon", 0, 0, 2, 2),
+        Slice::new("", 1, 1, 1, 1),
+        Slice::new("Off", 8, 0, 4, 4),
+    ]);
+    assert_eq!(added, 3);
+    assert_eq!(state.slices[0].rect(), (2, 2, 6, 3));
+    assert_eq!(state.slices[1].name, "Icon 2");
+    assert_eq!(state.slices[2].name, "Slice");
+    assert_eq!(state.slice_at(1, 1), Some(2));
+    assert_eq!(state.slice_at(7, 7), None);
+}
+
+#[test]
+fn pack_sprite_sheet_lays_out_frames_and_atlas() {
+    use paintfe::ops::slices::{SpriteFrame, atlas_json, pack_sprite_sheet};
+
+    let frame = |name: &str, w: u32, h: u32, color: [u8; 4]| SpriteFrame {
+        name: name.into(),
+        image: RgbaImage::from_pixel(w, h, Rgba(color)),
+        offset: (0, 0),
+        source_size: (w, h),
+    };
+    let frames = [
+        frame("a", 4, 2, [255, 0, 0, 255]),
+        frame("b", 2, 3, [0, 255, 0, 255]),
+        frame("c", 3, 3, [0, 0, 255, 255]),
+    ];
+    let sheet = pack_sprite_sheet(&frames, 0, 1).unwrap();
+    // Two columns (4 and 2 wide), two rows (3 and 3 tall).
+    assert_eq!(
+        sheet.image.dimensions(),
+        (1 + 4 + 1 + 2 + 1, 1 + 3 + 1 + 3 + 1)
+    );
+    assert_eq!((sheet.frames[1].x, sheet.frames[1].y), (6, 1));
+    assert_eq!((sheet.frames[2].x, sheet.frames[2].y), (1, 5));
+    assert_eq!(*sheet.image.get_pixel(6, 1), Rgba([0, 255, 0, 255]));
+    assert_eq!(*sheet.image.get_pixel(0, 0), Rgba([0, 0, 0, 0]));
+
+    let atlas: serde_json::Value = serde_json::from_str(&atlas_json(&sheet, "sheet.png")).unwrap();
+    assert_eq!(atlas["frames"]["b"]["frame"]["x"], 6);
+    assert_eq!(atlas["frames"]["c"]["sourceSize"]["w"], 3);
+    assert_eq!(atlas["frames"]["a"]["trimmed"], false);
+    assert_eq!(atlas["meta"]["image"], "sheet.png");
+    assert_eq!(atlas["meta"]["size"]["h"], 9);
+}
+
+#[test]
+fn sprite_sheet_document_has_a_layer_per_cell() {
+    use paintfe::ops::slices::{SpriteGrid, sprite_sheet_document};
+
+    // Three 4×4 cells in a row with 1px margin and spacing; the middle one
+    // is empty.
+    let mut sheet = RgbaImage::new(1 + 4 + 1 + 4 + 1 + 4, 6);
+    sheet.put_pixel(1, 1, Rgba([255, 0, 0, 255]));
+    sheet.put_pixel(11 + 3, 1 + 3, Rgba([0, 0, 255, 255]));
+    let grid = SpriteGrid {
+        cell_width: 4,
+        cell_height: 4,
+        margin: 1,
+        spacing: 1,
+    };
+    assert_eq!(grid.dimensions(sheet.width(), sheet.height()), (3, 1));
+
+    let state = sprite_sheet_document(&sheet, &grid, false).unwrap();
+    assert_eq!((state.width, state.height), (4, 4));
+    assert_eq!(state.layers.len(), 3);
+
+    let state = sprite_sheet_document(&sheet, &grid, true).unwrap();
+    assert_eq!(state.layers.len(), 2);
+    assert_eq!(state.layers[1].name, "Frame 2");
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(0, 0),
+        Rgba([255, 0, 0, 255])
+    );
+    assert_eq!(
+        *state.layers[1].pixels.get_pixel(3, 3),
+        Rgba([0, 0, 255, 255])
+    );
+
+    // Extreme grids neither overflow nor cut more frames than layers allowed.
+    let huge = SpriteGrid {
+        cell_width: 1,
+        cell_height: u32::MAX,
+        margin: 0,
+        spacing: u32::MAX,
+    };
+    assert_eq!(huge.dimensions(10, 10), (1, 0));
+    assert_eq!(huge.cells(10, u32::MAX), vec![(0, 0)]);
+    let tiny = SpriteGrid {
+        cell_width: 1,
+        cell_height: 1,
+        margin: 0,
+        spacing: 0,
+    };
+    assert!(sprite_sheet_document(&RgbaImage::new(17, 16), &tiny, true).is_err());
+}
@@ -12,7 +12,7 @@ mod common;
 
 use common::*;
 use image::RgbaImage;
-use paintfe::ops::scripting::{ScriptError, execute_script_sync};
+use paintfe::ops::scripting::{CanvasOpRequest, ScriptError, execute_script_sync};
 
 /// Run a script on a 64×64 gradient and return the result as RgbaImage.
 fn run_script(source: &str) -> Result<(RgbaImage, Vec<String>), ScriptError> {
@@ -180,6 +180,61 @@ fn script_flip_roundtrip() {
     assert_eq!(result, original, "script flip × 2 should be identity");
 }
 
+#[test]
+fn script_crop_canvas() {
+    let img = create_test_gradient(64, 64);
+    let (out, w, h, _, ops) = execute_script_sync(
+        "crop_canvas(8, 4, 16, 10);",
+        img.as_raw().clone(),
+        64,
+        64,
+        None,
+    )
+    .unwrap();
+    assert_eq!((w, h), (16, 10));
+    let result = RgbaImage::from_raw(w, h, out).unwrap();
+    assert_eq!(result.get_pixel(0, 0), img.get_pixel(8, 4));
+    assert!(matches!(
+        ops.as_slice(),
+        [CanvasOpRequest::Crop {
+            x: 8,
+            y: 4,
+            w: 16,
+            h: 10
+        }]
+    ));
+}
+
+#[test]
+fn script_trim_canvas() {
+    let pixels = create_transparent(64, 64).into_raw();
+    let source = r#"
+        set_pixel(10, 20, 255, 0, 0, 255);
+        set_pixel(12, 25, 255, 0, 0, 255);
+        trim_canvas();
+    "#;
+    let (_, w, h, _, ops) = execute_script_sync(source, pixels.clone(), 64, 64, None).unwrap();
+    assert_eq!((w, h), (3, 6));
+    assert_eq!(ops.len(), 1);
+
+    // Only the top and left margins go.
+    let source = r#"
+        set_pixel(10, 20, 255, 0, 0, 255);
+        trim_canvas("transparent", "top, left", 0);
+    "#;
+    let (_, w, h, _, _) = execute_script_sync(source, pixels.clone(), 64, 64, None).unwrap();
+    assert_eq!((w, h), (54, 44));
+
+    // Misspelt arguments are errors, not a silent full trim.
+    for call in [
+        r#"trim_canvas("top-lft", "all", 0);"#,
+        r#"trim_canvas("transparent", "top, lfet", 0);"#,
+    ] {
+        let err = execute_script_sync(call, pixels.clone(), 64, 64, None).unwrap_err();
+        assert!(err.message.contains("trim_canvas"), "{}", err.message);
+    }
+}
+
 // =============================================================================
 // Utility API
 // =============================================================================
@@ -616,3 +616,170 @@ fn color_picker_reads_painted_pixel() {
     assert_eq!(center[1], 0, "green channel at brush center");
     assert_eq!(center[3], 255, "alpha at brush center");
 }
+
+/// Number of painted pixels in column `x`.
+fn column_coverage(img: &RgbaImage, x: u32) -> usize {
+    (0..img.height())
+        .filter(|&y| img.get_pixel(x, y)[3] > 0)
+        .count()
+}
+
+#[test]
+fn line_taper_start_grows_from_point() {
+    let mut tp = make_brush(12.0, 1.0, true);
+    tp.properties.dynamics.taper_start = 40.0;
+    let mut tile = blank_tile(W, H);
+    tp.draw_line_no_dirty(
+        &mut tile,
+        W,
+        H,
+        (4.0, 32.0),
+        (60.0, 32.0),
+        false,
+        false,
+        BLACK_F32,
+        WHITE_F32,
+        None,
+    );
+    let img = tile_to_image(&tile, W, H);
+    let near_start = column_coverage(&img, 8);
+    let mid_taper = column_coverage(&img, 24);
+    let past_taper = column_coverage(&img, 54);
+    assert!(
+        near_start < mid_taper && mid_taper < past_taper,
+        "stroke widens along the taper: {near_start} < {mid_taper} < {past_taper}"
+    );
+    assert_eq!(
+        past_taper,
+        column_coverage(&img, 50),
+        "full width after taper"
+    );
+}
+
+#[test]
+fn pressure_response_curve() {
+    use paintfe::components::tools::ResponseCurve;
+
+    assert_eq!(ResponseCurve::Linear.apply(0.25), 0.25);
+    assert_eq!(ResponseCurve::Firm.apply(0.5), 0.25);
+    assert_eq!(ResponseCurve::Soft.apply(0.5), 0.75);
+    assert_eq!(ResponseCurve::SCurve.apply(0.5), 0.5);
+    for curve in ResponseCurve::all() {
+        assert_eq!(curve.apply(0.0), 0.0, "{curve:?} starts at 0");
+        assert_eq!(curve.apply(1.0), 1.0, "{curve:?} ends at 1");
+    }
+
+    // Pressure size goes through the curve before the min-size mapping
+    let mut tp = make_brush(20.0, 1.0, true);
+    tp.properties.pressure_size = true;
+    tp.properties.pressure_min_size = 0.0;
+    tp.properties.dynamics.pressure_curve = ResponseCurve::Firm;
+    assert_eq!(tp.pressure_size(), 20.0, "full pressure is unaffected");
+    tp.set_pen_pressure(0.5);
+    assert_eq!(tp.pressure_size(), 5.0);
+}
+
+#[test]
+fn pen_tilt_maps_to_size_opacity_and_angle() {
+    use paintfe::components::tools::PenTilt;
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    let upright = PenTilt::from_tilt_degrees(0.0, 0.0);
+    assert_eq!(upright.altitude, FRAC_PI_2);
+    let right = PenTilt::from_tilt_degrees(45.0, 0.0);
+    assert!((right.altitude - FRAC_PI_4).abs() < 1e-4);
+    assert!(right.azimuth.abs() < 1e-4, "leaning right is azimuth 0");
+    let down = PenTilt::from_tilt_degrees(0.0, 60.0);
+    assert!((down.azimuth - FRAC_PI_2).abs() < 1e-4, "+Y is clockwise");
+    let flat = PenTilt::from_tilt_degrees(90.0, 0.0);
+    assert!(flat.altitude < 0.01, "a pen lying flat has ~0 altitude");
+
+    let mut tp = make_brush(20.0, 1.0, true);
+    tp.properties.dynamics.tilt_size = 1.0;
+    tp.properties.dynamics.tilt_opacity = 0.5;
+    tp.set_pen_tilt(Some(upright));
+    assert_eq!(tp.stamp_size(), 20.0, "an upright pen keeps full size");
+    tp.set_pen_tilt(Some(right));
+    assert!(
+        (tp.stamp_size() - 10.0).abs() < 0.01,
+        "half tilt halves the size, got {}",
+        tp.stamp_size()
+    );
+    tp.set_pen_tilt(None);
+    assert_eq!(
+        tp.stamp_size(),
+        20.0,
+        "no tilt reported disables the mapping"
+    );
+}
+
+#[test]
+fn mixer_brush_smears_canvas_color() {
+    use paintfe::components::tools::{BrushMode, Tool};
+
+    // Left half red, right half blue
+    let mut state = CanvasState::new(W, H);
+    state.layers[0].pixels = TiledImage::from_rgba_image(&RgbaImage::from_fn(W, H, |x, _| {
+        if x < W / 2 {
+            Rgba([255, 0, 0, 255])
+        } else {
+            Rgba([0, 0, 255, 255])
+        }
+    }));
+
+    let mut tp = make_brush(8.0, 1.0, true);
+    tp.active_tool = Tool::Brush;
+    tp.properties.brush_mode = BrushMode::Mixer;
+    tp.properties.mixer.wetness = 1.0;
+    tp.properties.mixer.load = 0.0;
+    tp.properties.mixer.mix = 1.0;
+    tp.begin_mixer_stroke(&state);
+
+    let mut tile = blank_tile(W, H);
+    tp.draw_line_no_dirty(
+        &mut tile,
+        W,
+        H,
+        (8.0, 32.0),
+        (56.0, 32.0),
+        false,
+        false,
+        BLACK_F32,
+        WHITE_F32,
+        None,
+    );
+    let start = *tile.get_pixel(8, 32);
+    let edge = *tile.get_pixel(34, 32);
+    let end = *tile.get_pixel(56, 32);
+    assert!(
+        start[0] > 200 && start[2] < 50,
+        "dry brush picks up red: {start:?}"
+    );
+    assert!(
+        edge[0] > 50 && edge[2] > 50,
+        "red is dragged into the blue half: {edge:?}"
+    );
+    assert!(
+        end[2] > 200 && end[0] < 50,
+        "brush is blue by the end: {end:?}"
+    );
+
+    // A fully loaded brush with no mix paints the plain brush color
+    tp.properties.mixer.load = 1.0;
+    tp.properties.mixer.mix = 0.0;
+    tp.begin_mixer_stroke(&state);
+    let mut tile = blank_tile(W, H);
+    tp.draw_line_no_dirty(
+        &mut tile,
+        W,
+        H,
+        (8.0, 32.0),
+        (56.0, 32.0),
+        false,
+        false,
+        BLACK_F32,
+        WHITE_F32,
+        None,
+    );
+    assert_eq!(*tile.get_pixel(40, 32), Rgba([0, 0, 0, 255]));
+}
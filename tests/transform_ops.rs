@@ -15,6 +15,10 @@ use paintfe::ops::transform::{
     generate_displacement_from_mesh, generate_displacement_from_mesh_fast, warp_displacement_full,
     warp_mesh_catmull_rom,
 };
+use paintfe::ops::trim::{
+    DEFAULT_SCAN_TOLERANCE, TrimBasis, TrimOptions, TrimSides, auto_straighten_and_crop,
+    crop_to_layer_content, detect_skew, trim_bounds, trim_canvas,
+};
 
 // =============================================================================
 // Helpers
@@ -337,6 +341,363 @@ fn align_layer_to_bottom_right_anchor() {
     );
 }
 
+// =============================================================================
+// Off-canvas content, Reveal All and infinite canvas
+// =============================================================================
+
+/// 8x8 canvas with a red pixel at (0,0) and a blue one at (7,7).
+fn corner_marked_canvas() -> CanvasState {
+    let mut state = canvas_from_image(&create_transparent(8, 8));
+    state.layers[0]
+        .pixels
+        .put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+    state.layers[0]
+        .pixels
+        .put_pixel(7, 7, Rgba([0, 0, 255, 255]));
+    state
+}
+
+#[test]
+fn shrinking_canvas_keeps_content_off_canvas() {
+    let mut state = corner_marked_canvas();
+    transform::resize_canvas(&mut state, 4, 4, (0, 0), Rgba([0, 0, 0, 0]));
+    assert_eq!((state.width, state.height), (4, 4));
+    let off = state.layers[0]
+        .off_canvas
+        .as_ref()
+        .expect("off-canvas content");
+    assert_eq!(off.bounds(), (7, 7, 8, 8));
+
+    // Growing again brings it back in place.
+    transform::resize_canvas(&mut state, 8, 8, (0, 0), Rgba([0, 0, 0, 0]));
+    assert!(state.layers[0].off_canvas.is_none());
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(7, 7),
+        Rgba([0, 0, 255, 255])
+    );
+}
+
+#[test]
+fn reveal_all_grows_canvas_to_fit_content() {
+    let mut state = corner_marked_canvas();
+    transform::resize_canvas(&mut state, 4, 4, (2, 2), Rgba([0, 0, 0, 0]));
+    assert_eq!(
+        state.layers[0].off_canvas.as_ref().map(|off| off.bounds()),
+        Some((-4, -4, -3, -3))
+    );
+
+    assert!(transform::reveal_all(&mut state));
+    assert_eq!((state.width, state.height), (8, 8));
+    assert!(state.layers[0].off_canvas.is_none());
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(0, 0),
+        Rgba([255, 0, 0, 255])
+    );
+    assert!(!transform::reveal_all(&mut state), "nothing left to reveal");
+}
+
+#[test]
+fn move_pixels_off_canvas_and_back_is_lossless() {
+    let mut state = corner_marked_canvas();
+    let mut overlay = paintfe::ops::clipboard::extract_to_overlay(&mut state).unwrap();
+    overlay.anti_aliasing = false;
+    overlay.center.x += 6.0;
+    overlay.commit(&mut state);
+    assert_eq!(state.layers[0].extent(), (0, 0, 14, 8));
+
+    let mut overlay = paintfe::ops::clipboard::extract_to_overlay(&mut state).unwrap();
+    overlay.anti_aliasing = false;
+    overlay.center.x -= 6.0;
+    overlay.commit(&mut state);
+    assert!(state.layers[0].off_canvas.is_none());
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(7, 7),
+        Rgba([0, 0, 255, 255])
+    );
+}
+
+#[test]
+fn rotating_canvas_carries_off_canvas_content() {
+    let mut state = corner_marked_canvas();
+    transform::resize_canvas(&mut state, 4, 8, (0, 0), Rgba([0, 0, 0, 0]));
+    transform::rotate_canvas_90cw(&mut state);
+    transform::rotate_canvas_90ccw(&mut state);
+    transform::reveal_all(&mut state);
+    assert_eq!((state.width, state.height), (8, 8));
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(7, 7),
+        Rgba([0, 0, 255, 255])
+    );
+}
+
+#[test]
+fn arbitrary_rotation_keeps_off_canvas_content() {
+    let mut state = corner_marked_canvas();
+    transform::resize_canvas(&mut state, 4, 8, (0, 0), Rgba([0, 0, 0, 0]));
+    assert!(state.layers[0].off_canvas.is_some());
+
+    transform::rotate_canvas_arbitrary(&mut state, 180.0, transform::Interpolation::Nearest);
+    assert!(
+        state.layers[0].off_canvas.is_some(),
+        "rotation must not drop off-canvas pixels"
+    );
+    transform::rotate_canvas_arbitrary(&mut state, 180.0, transform::Interpolation::Nearest);
+    assert_eq!(
+        state.layers[0].off_canvas.as_ref().map(|off| off.bounds()),
+        Some((7, 7, 8, 8))
+    );
+
+    transform::reveal_all(&mut state);
+    assert_eq!((state.width, state.height), (8, 8));
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(0, 0),
+        Rgba([255, 0, 0, 255])
+    );
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(7, 7),
+        Rgba([0, 0, 255, 255])
+    );
+}
+
+#[test]
+fn flipping_a_layer_mirrors_its_off_canvas_content() {
+    let mut state = corner_marked_canvas();
+    transform::resize_canvas(&mut state, 4, 8, (0, 0), Rgba([0, 0, 0, 0]));
+
+    transform::flip_layer_horizontal(&mut state, 0);
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(3, 0),
+        Rgba([255, 0, 0, 255])
+    );
+    assert_eq!(
+        state.layers[0].off_canvas.as_ref().map(|off| off.bounds()),
+        Some((-4, 7, -3, 8))
+    );
+
+    transform::flip_layer_vertical(&mut state, 0);
+    assert_eq!(
+        state.layers[0].off_canvas.as_ref().map(|off| off.bounds()),
+        Some((-4, 0, -3, 1))
+    );
+    let ((x0, y0), extent) = state.layers[0].extent_rgba();
+    assert_eq!(
+        *extent.get_pixel((-4 - x0) as u32, (0 - y0) as u32),
+        Rgba([0, 0, 255, 255])
+    );
+}
+
+#[test]
+fn layer_transform_keeps_content_moved_off_canvas() {
+    let mut state = corner_marked_canvas();
+    transform::affine_transform_layer(&mut state, 0, 0.0, 0.0, 0.0, 1.0, (4.0, 0.0));
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(4, 0),
+        Rgba([255, 0, 0, 255])
+    );
+    assert_eq!(
+        state.layers[0].off_canvas.as_ref().map(|off| off.bounds()),
+        Some((11, 7, 12, 8))
+    );
+
+    transform::affine_transform_layer(&mut state, 0, 0.0, 0.0, 0.0, 1.0, (-4.0, 0.0));
+    assert!(state.layers[0].off_canvas.is_none());
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(0, 0),
+        Rgba([255, 0, 0, 255])
+    );
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(7, 7),
+        Rgba([0, 0, 255, 255])
+    );
+}
+
+#[test]
+fn infinite_canvas_exports_document_bounds_only() {
+    use paintfe::io::{PreparedExportImage, prepare_export_image};
+
+    let mut state = corner_marked_canvas();
+    transform::set_infinite_canvas(&mut state, true);
+    // An edit near the canvas edges makes room around it.
+    assert!(transform::extend_infinite_canvas(
+        &mut state,
+        Some((0, 0, 1, 1))
+    ));
+    let margin = transform::INFINITE_CANVAS_MARGIN;
+    // The margin is kept on every side of the 1x1 edit.
+    assert_eq!(
+        (state.width, state.height),
+        (2 * margin + 1, 2 * margin + 1)
+    );
+    assert_eq!(state.document_bounds, Some((margin, margin, 8, 8)));
+
+    let PreparedExportImage::Rgba8(exported) = prepare_export_image(&state) else {
+        panic!("expected an 8-bit export");
+    };
+    assert_eq!(exported.dimensions(), (8, 8));
+    assert_eq!(*exported.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+
+    transform::set_infinite_canvas(&mut state, false);
+    assert_eq!((state.width, state.height), (8, 8));
+    assert_eq!(state.document_bounds, None);
+}
+
+#[test]
+fn infinite_canvas_growth_reports_content_shift() {
+    let mut state = corner_marked_canvas();
+    transform::set_infinite_canvas(&mut state, true);
+    let margin = transform::INFINITE_CANVAS_MARGIN as i32;
+    // The margin is kept around the touched rect, so the old canvas moves
+    // by whatever that margin reaches past its top-left corner.
+    let shift = (margin - 9, margin - 2);
+    assert_eq!(
+        transform::grow_infinite_canvas(&mut state, Some((9, 2, 10, 3))),
+        Some(shift)
+    );
+    assert_eq!(
+        *state.layers[0]
+            .pixels
+            .get_pixel(shift.0 as u32, shift.1 as u32),
+        Rgba([255, 0, 0, 255])
+    );
+    assert_eq!(transform::grow_infinite_canvas(&mut state, None), None);
+}
+
+// =============================================================================
+// Trim, crop to content and auto-straighten
+// =============================================================================
+
+/// `w`×`h` image of `background` with a `color` rectangle at `(x, y, rw, rh)`.
+fn rect_on(
+    w: u32,
+    h: u32,
+    background: [u8; 4],
+    (x, y, rw, rh): (u32, u32, u32, u32),
+    color: [u8; 4],
+) -> RgbaImage {
+    let mut img = create_solid(w, h, background);
+    for py in y..y + rh {
+        for px in x..x + rw {
+            img.put_pixel(px, py, Rgba(color));
+        }
+    }
+    img
+}
+
+#[test]
+fn trim_bounds_only_cuts_chosen_sides() {
+    let img = rect_on(10, 10, [0, 0, 0, 0], (2, 3, 3, 4), [255, 0, 0, 255]);
+    let mut options = TrimOptions::default();
+    assert_eq!(trim_bounds(&img, &options), Some((2, 3, 3, 4)));
+
+    options.sides = TrimSides::parse("top, left").unwrap();
+    assert_eq!(trim_bounds(&img, &options), Some((2, 3, 8, 7)));
+    assert_eq!(TrimSides::parse("middle"), None);
+}
+
+#[test]
+fn trim_bounds_matches_corner_color_within_tolerance() {
+    let mut img = rect_on(10, 10, [250, 250, 250, 255], (4, 4, 2, 3), [0, 0, 0, 255]);
+    // Near-white noise is still margin at tolerance 10.
+    img.put_pixel(8, 1, Rgba([244, 250, 250, 255]));
+    let mut options = TrimOptions {
+        basis: TrimBasis::TopLeftColor,
+        tolerance: 10,
+        ..TrimOptions::default()
+    };
+    assert_eq!(trim_bounds(&img, &options), Some((4, 4, 2, 3)));
+
+    options.tolerance = 0;
+    assert_eq!(trim_bounds(&img, &options), Some((4, 1, 5, 6)));
+
+    // Transparent-based trim finds nothing to cut on an opaque image.
+    options.basis = TrimBasis::Transparent;
+    assert_eq!(trim_bounds(&img, &options), Some((0, 0, 10, 10)));
+}
+
+#[test]
+fn trim_canvas_crops_layers_and_slices() {
+    use paintfe::canvas::Slice;
+
+    let img = rect_on(8, 8, [0, 0, 0, 0], (2, 2, 4, 4), [0, 255, 0, 255]);
+    let mut state = canvas_from_image(&img);
+    state.slices.push(Slice::new("Inside", 1, 1, 4, 4));
+    state.slices.push(Slice::new("Outside", 0, 0, 2, 2));
+
+    assert!(trim_canvas(&mut state, &TrimOptions::default()));
+    assert_eq!((state.width, state.height), (4, 4));
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(0, 0),
+        Rgba([0, 255, 0, 255])
+    );
+    assert_eq!(state.slices, vec![Slice::new("Inside", 0, 0, 3, 3)]);
+
+    // Nothing left to trim.
+    assert!(!trim_canvas(&mut state, &TrimOptions::default()));
+}
+
+#[test]
+fn crop_to_layer_content_uses_active_layer() {
+    let mut state = canvas_from_image(&create_transparent(8, 8));
+    state.layers[0]
+        .pixels
+        .put_pixel(3, 2, Rgba([255, 0, 0, 255]));
+    state.layers[0]
+        .pixels
+        .put_pixel(5, 6, Rgba([0, 0, 255, 255]));
+    assert!(crop_to_layer_content(&mut state, 0));
+    assert_eq!((state.width, state.height), (3, 5));
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(0, 0),
+        Rgba([255, 0, 0, 255])
+    );
+    assert_eq!(
+        *state.layers[0].pixels.get_pixel(2, 4),
+        Rgba([0, 0, 255, 255])
+    );
+}
+
+/// A white 60×40 page rotated `degrees` clockwise on a grey scanner bed.
+fn skewed_scan(degrees: f32) -> RgbaImage {
+    let page = rect_on(
+        120,
+        120,
+        [0, 0, 0, 0],
+        (30, 40, 60, 40),
+        [255, 255, 255, 255],
+    );
+    let rotated =
+        transform::rotate_image_arbitrary(&page, degrees, transform::Interpolation::Bilinear);
+    let mut scan = create_solid(120, 120, [120, 120, 120, 255]);
+    for (x, y, p) in rotated.enumerate_pixels() {
+        if p[3] >= 128 {
+            scan.put_pixel(x, y, Rgba([255, 255, 255, 255]));
+        }
+    }
+    scan
+}
+
+#[test]
+fn detect_skew_finds_page_rotation() {
+    let angle = detect_skew(&skewed_scan(8.0), DEFAULT_SCAN_TOLERANCE).expect("skewed page");
+    assert!((angle + 8.0).abs() < 0.5, "angle {}", angle);
+    let angle = detect_skew(&skewed_scan(-5.0), DEFAULT_SCAN_TOLERANCE).expect("skewed page");
+    assert!((angle - 5.0).abs() < 0.5, "angle {}", angle);
+    assert_eq!(detect_skew(&skewed_scan(0.0), DEFAULT_SCAN_TOLERANCE), None);
+}
+
+#[test]
+fn auto_straighten_levels_and_crops_scan() {
+    let mut state = canvas_from_image(&skewed_scan(8.0));
+    let angle = auto_straighten_and_crop(&mut state, DEFAULT_SCAN_TOLERANCE).expect("changed");
+    assert!((angle + 8.0).abs() < 0.5, "angle {}", angle);
+    assert!(state.width.abs_diff(60) <= 3, "width {}", state.width);
+    assert!(state.height.abs_diff(40) <= 3, "height {}", state.height);
+    let center = *state.layers[0]
+        .pixels
+        .get_pixel(state.width / 2, state.height / 2);
+    assert_eq!(center, Rgba([255, 255, 255, 255]));
+}
+
 // =============================================================================
 // Golden tests for displacement warp
 // =============================================================================
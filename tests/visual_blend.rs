@@ -2,9 +2,10 @@
 // Visual regression tests — Blend Modes
 // =============================================================================
 //
-// Tests all 25 blend modes by compositing a gradient foreground over a
-// checkerboard background, then comparing the flattened result against
-// a golden PNG.
+// Tests all 32 blend modes by compositing a gradient foreground over a
+// checkerboard background (saturated colour bands for Hue and Saturation,
+// which need a coloured backdrop), then comparing the flattened result
+// against a golden PNG.
 //
 // Run with GENERATE_GOLDEN=1 to create/update golden files:
 //   GENERATE_GOLDEN=1 cargo test --test visual_blend
@@ -13,16 +14,19 @@ mod common;
 
 use common::*;
 use image::{Rgba, RgbaImage};
-use paintfe::canvas::{BlendMode, CanvasState, Layer, TiledImage};
+use paintfe::canvas::{BlendMode, BlendRange, CanvasState, Layer, TiledImage};
 
 /// Create a 2-layer test canvas: checkerboard background + translucent gradient foreground.
-fn make_blend_test(mode: BlendMode) -> RgbaImage {
+/// `linear` turns on linear-light blending for the document.
+fn make_blend_test(mode: BlendMode, linear: bool) -> RgbaImage {
+    make_blend_test_over(mode, linear, create_test_checkerboard(64, 64))
+}
+
+/// Like [`make_blend_test`], over a 64×64 `bg_img`.
+fn make_blend_test_over(mode: BlendMode, linear: bool, bg_img: RgbaImage) -> RgbaImage {
     let w = 64;
     let h = 64;
 
-    // Background: checkerboard
-    let bg_img = create_test_checkerboard(w, h);
-
     // Foreground: semi-transparent gradient (alpha varies across width)
     let mut fg_img = RgbaImage::new(w, h);
     for y in 0..h {
@@ -36,6 +40,7 @@ fn make_blend_test(mode: BlendMode) -> RgbaImage {
     }
 
     let mut state = CanvasState::new(w, h);
+    state.linear_blending = linear;
     state.layers[0].pixels = TiledImage::from_rgba_image(&bg_img);
 
     let mut fg = Layer::new("Foreground".into(), w, h, Rgba([0, 0, 0, 0]));
@@ -46,13 +51,23 @@ fn make_blend_test(mode: BlendMode) -> RgbaImage {
     state.composite()
 }
 
-// -- tests for all 25 blend modes ------------------------------------------------
+// -- tests for all 32 blend modes ------------------------------------------------
 
 macro_rules! blend_test {
     ($name:ident, $mode:expr) => {
+        blend_test!($name, $mode, false);
+    };
+    ($name:ident, $mode:expr, $linear:expr) => {
+        #[test]
+        fn $name() {
+            let result = make_blend_test($mode, $linear);
+            assert_golden("blend", stringify!($name), &result);
+        }
+    };
+    ($name:ident, $mode:expr, over $backdrop:ident) => {
         #[test]
         fn $name() {
-            let result = make_blend_test($mode);
+            let result = make_blend_test_over($mode, false, $backdrop(64, 64));
             assert_golden("blend", stringify!($name), &result);
         }
     };
@@ -83,6 +98,113 @@ blend_test!(vivid_light, BlendMode::VividLight);
 blend_test!(linear_light, BlendMode::LinearLight);
 blend_test!(pin_light, BlendMode::PinLight);
 blend_test!(hard_mix, BlendMode::HardMix);
+blend_test!(hue, BlendMode::Hue, over create_color_bands);
+blend_test!(saturation, BlendMode::Saturation, over create_color_bands);
+blend_test!(color, BlendMode::Color);
+blend_test!(luminosity, BlendMode::Luminosity);
+blend_test!(darker_color, BlendMode::DarkerColor);
+blend_test!(lighter_color, BlendMode::LighterColor);
+blend_test!(dissolve, BlendMode::Dissolve);
+
+// -- Linear-light blending ---------------------------------------------------------
+
+blend_test!(normal_linear, BlendMode::Normal, true);
+blend_test!(multiply_linear, BlendMode::Multiply, true);
+blend_test!(screen_linear, BlendMode::Screen, true);
+
+#[test]
+fn linear_blending_changes_the_composite() {
+    assert_ne!(
+        make_blend_test(BlendMode::Normal, false),
+        make_blend_test(BlendMode::Normal, true)
+    );
+}
+
+#[test]
+fn linear_blending_keeps_mixes_bright() {
+    let red = Rgba([255, 0, 0, 255]);
+    let green = Rgba([0, 255, 0, 128]);
+    let srgb = CanvasState::blend_pixel_space(red, green, BlendMode::Normal, 1.0, false);
+    let linear = CanvasState::blend_pixel_space(red, green, BlendMode::Normal, 1.0, true);
+    assert_eq!(srgb, Rgba([126, 128, 0, 255]));
+    assert_eq!(linear, Rgba([187, 188, 0, 255]));
+
+    // Opaque Normal and transparent sources are untouched in both modes.
+    for linear in [false, true] {
+        let opaque = Rgba([10, 20, 30, 255]);
+        let out = CanvasState::blend_pixel_space(red, opaque, BlendMode::Normal, 1.0, linear);
+        assert_eq!(out, opaque);
+        let clear = Rgba([10, 20, 30, 0]);
+        let out = CanvasState::blend_pixel_space(red, clear, BlendMode::Multiply, 1.0, linear);
+        assert_eq!(out, red);
+    }
+}
+
+// -- HSL and Dissolve behaviour -----------------------------------------------------
+
+#[test]
+fn hsl_modes_over_colored_backdrop() {
+    let base = Rgba([200, 60, 40, 255]);
+    let top = Rgba([40, 90, 220, 255]);
+    let cases = [
+        (BlendMode::Hue, [55, 100, 215]),
+        (BlendMode::Saturation, [212, 55, 32]),
+        (BlendMode::Color, [50, 100, 230]),
+        (BlendMode::Luminosity, [189, 49, 29]),
+        (BlendMode::DarkerColor, [40, 90, 220]),
+        (BlendMode::LighterColor, [200, 60, 40]),
+    ];
+    for (mode, expected) in cases {
+        let out = CanvasState::blend_pixel_static(base, top, mode, 1.0);
+        for c in 0..3 {
+            assert!(
+                (out[c] as i32 - expected[c]).abs() <= 1,
+                "{:?}: got {:?}, expected {:?}",
+                mode,
+                out,
+                expected
+            );
+        }
+        assert_eq!(out[3], 255);
+    }
+}
+
+#[test]
+fn cpu_blend_matches_reference_table() {
+    for &(mode, x, base, top, expected) in BLEND_CASES {
+        let out = CanvasState::blend_pixel_at(Rgba(base), Rgba(top), mode, 1.0, x, 0, false);
+        assert_eq!(
+            out.0, expected,
+            "{:?} at x={}: {:?} over {:?}",
+            mode, x, top, base
+        );
+    }
+}
+
+#[test]
+fn dissolve_is_all_or_nothing_per_pixel() {
+    let (w, h) = (64, 64);
+    let mut state = CanvasState::new(w, h);
+    state.layers[0].pixels =
+        TiledImage::from_rgba_image(&RgbaImage::from_pixel(w, h, Rgba([255, 255, 255, 255])));
+    let mut fg = Layer::new("Speckle".into(), w, h, Rgba([255, 0, 0, 128]));
+    fg.blend_mode = BlendMode::Dissolve;
+    state.layers.push(fg);
+
+    let result = state.composite();
+    let red = result
+        .pixels()
+        .filter(|p| **p == Rgba([255, 0, 0, 255]))
+        .count();
+    let white = result
+        .pixels()
+        .filter(|p| **p == Rgba([255, 255, 255, 255]))
+        .count();
+    assert_eq!(red + white, (w * h) as usize);
+    // About half the pixels survive at 50% alpha.
+    let share = red as f32 / (w * h) as f32;
+    assert!((0.4..0.6).contains(&share), "dissolve share {share}");
+}
 
 // -- Opacity test -----------------------------------------------------------------
 
@@ -134,3 +256,48 @@ fn hidden_layer_invisible() {
         "hidden layer should not contribute to composite"
     );
 }
+
+// -- Blend If tests -----------------------------------------------------------------
+
+#[test]
+fn blend_if_underlying_range_hides_over_light_backdrop() {
+    let mut state = CanvasState::new(4, 1);
+    for x in 0..4 {
+        let v = [0, 100, 200, 255][x as usize];
+        state.layers[0].pixels.put_pixel(x, 0, Rgba([v, v, v, 255]));
+    }
+    let mut fg = Layer::new("Red".into(), 4, 1, Rgba([255, 0, 0, 255]));
+    fg.blend_if.underlying[0] = BlendRange {
+        black: [0, 0],
+        white: [150, 150],
+    };
+    state.layers.push(fg);
+
+    let result = state.composite();
+    assert_eq!(*result.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+    assert_eq!(*result.get_pixel(1, 0), Rgba([255, 0, 0, 255]));
+    assert_eq!(*result.get_pixel(2, 0), Rgba([200, 200, 200, 255]));
+    assert_eq!(*result.get_pixel(3, 0), Rgba([255, 255, 255, 255]));
+}
+
+#[test]
+fn blend_if_split_range_fades_this_layer() {
+    let mut state = CanvasState::new(3, 1);
+    state.layers[0].pixels =
+        TiledImage::from_rgba_image(&RgbaImage::from_pixel(3, 1, Rgba([0, 0, 0, 255])));
+    let mut fg = Layer::new("Gray".into(), 3, 1, Rgba([0, 0, 0, 0]));
+    for (x, v) in [(0, 50u8), (1, 150), (2, 250)] {
+        fg.pixels.put_pixel(x, 0, Rgba([v, v, v, 255]));
+    }
+    fg.blend_if.this_layer[0] = BlendRange {
+        black: [100, 200],
+        white: [255, 255],
+    };
+    state.layers.push(fg);
+
+    let result = state.composite();
+    // Below the split: excluded. Halfway through it: half coverage.
+    assert_eq!(result.get_pixel(0, 0)[0], 0);
+    assert!((result.get_pixel(1, 0)[0] as i32 - 75).abs() <= 2);
+    assert_eq!(result.get_pixel(2, 0)[0], 250);
+}
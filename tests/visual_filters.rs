@@ -15,7 +15,7 @@ mod common;
 use common::*;
 use image::RgbaImage;
 use paintfe::ops::effects::*;
-use paintfe::ops::filters::parallel_gaussian_blur_pub;
+use paintfe::ops::filters::{gaussian_blur_layer, parallel_gaussian_blur_pub};
 
 /// Standard 64×64 test image used by all filter/effect visual tests.
 fn test_image() -> RgbaImage {
@@ -40,6 +40,37 @@ fn gaussian_blur_s5() {
     assert_golden("filters", "gaussian_blur_s5", &result);
 }
 
+/// With linear blending on, a blurred black/white edge is brighter at the
+/// seam, and flat areas come back unchanged.
+#[test]
+fn gaussian_blur_linear_brightens_edges() {
+    let img = RgbaImage::from_fn(32, 8, |x, _| {
+        if x < 16 {
+            image::Rgba([0, 0, 0, 255])
+        } else {
+            image::Rgba([255, 255, 255, 255])
+        }
+    });
+
+    let mut srgb = canvas_from_image(&img);
+    gaussian_blur_layer(&mut srgb, 0, 2.0);
+    let mut linear = canvas_from_image(&img);
+    linear.linear_blending = true;
+    gaussian_blur_layer(&mut linear, 0, 2.0);
+
+    let s = extract_layer(&srgb, 0);
+    let l = extract_layer(&linear, 0);
+    for x in [14, 15, 16, 17] {
+        assert!(
+            l.get_pixel(x, 4)[0] > s.get_pixel(x, 4)[0],
+            "column {} should be brighter in linear light",
+            x
+        );
+    }
+    assert_eq!(l.get_pixel(0, 4).0, [0, 0, 0, 255]);
+    assert_eq!(l.get_pixel(31, 4).0, [255, 255, 255, 255]);
+}
+
 #[test]
 fn bokeh_blur_r5() {
     let img = test_image();
@@ -222,6 +253,21 @@ fn contours() {
     assert_golden("filters", "contours", &result);
 }
 
+#[test]
+fn gradient_conic_and_spiral_shapes() {
+    use paintfe::components::tools::{GradientShape, GradientToolState};
+
+    let mut gs = GradientToolState::default();
+    gs.shape = GradientShape::Conic;
+    // Start at the origin, pointing along +X; a quarter turn (canvas Y down) is t = 0.25.
+    let t = |gs: &GradientToolState, px: f32, py: f32| gs.compute_t(px, py, 0.0, 0.0, 10.0, 0.0);
+    assert!((t(&gs, 0.0, 10.0) - 0.25).abs() < 1e-5);
+    assert!((t(&gs, -10.0, 0.0) - 0.5).abs() < 1e-5);
+    gs.shape = GradientShape::Spiral;
+    // Half a handle length out adds half a turn.
+    assert!((t(&gs, 0.0, 5.0) - 0.75).abs() < 1e-5);
+}
+
 #[test]
 fn canvas_border_core_applies_edges_only() {
     let img = create_solid(8, 8, [10, 20, 30, 255]);
@@ -163,6 +163,31 @@ fn resize_half_lanczos() {
     assert_golden("transforms", "resize_half_lanczos", &result);
 }
 
+/// Black/white stripes halved with linear blending average in linear light,
+/// landing on a brighter gray than the sRGB average.
+#[test]
+fn resize_half_linear_averages_in_linear_light() {
+    let img = RgbaImage::from_fn(16, 16, |x, _| {
+        if x % 2 == 0 {
+            Rgba([0, 0, 0, 255])
+        } else {
+            Rgba([255, 255, 255, 255])
+        }
+    });
+
+    let mut srgb = canvas_from_image(&img);
+    resize_image(&mut srgb, 8, 8, Interpolation::Bilinear);
+    let mut linear = canvas_from_image(&img);
+    linear.linear_blending = true;
+    resize_image(&mut linear, 8, 8, Interpolation::Bilinear);
+
+    let s = extract_layer(&srgb, 0).get_pixel(4, 4).0;
+    let l = extract_layer(&linear, 0).get_pixel(4, 4).0;
+    assert!((120..=135).contains(&s[0]), "sRGB average was {:?}", s);
+    assert!((180..=195).contains(&l[0]), "linear average was {:?}", l);
+    assert_eq!(l[3], 255);
+}
+
 // =============================================================================
 // Resize Canvas (anchor‐based)
 // =============================================================================